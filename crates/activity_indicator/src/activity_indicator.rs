@@ -131,6 +131,11 @@ impl ActivityIndicator {
             )
             .detach();
 
+            cx.observe(&project.read(cx).buffer_store().clone(), |_, _, cx| {
+                cx.notify()
+            })
+            .detach();
+
             if let Some(auto_updater) = auto_updater.as_ref() {
                 cx.observe(auto_updater, |_, _, cx| cx.notify()).detach();
             }
@@ -245,6 +250,26 @@ impl ActivityIndicator {
         self.project.read(cx).shell_environment_errors(cx)
     }
 
+    fn large_file_load_message(&self, cx: &App) -> Option<String> {
+        let buffer_store = self.project.read(cx).buffer_store().read(cx);
+        let mut loads = buffer_store.large_file_loads();
+        let (path, progress) = loads.next()?;
+        let mut message = format!(
+            "Loading {}",
+            path.path.file_name().unwrap_or_default().to_string_lossy()
+        );
+        if progress.total_bytes > 0 {
+            let percentage =
+                progress.loaded_bytes.min(progress.total_bytes) * 100 / progress.total_bytes;
+            write!(&mut message, " ({}%)", percentage).unwrap();
+        }
+        let additional_load_count = loads.count();
+        if additional_load_count > 0 {
+            write!(&mut message, " + {} more", additional_load_count).unwrap();
+        }
+        Some(message)
+    }
+
     fn content_to_render(&mut self, cx: &mut Context<Self>) -> Option<Content> {
         // Show if any direnv calls failed
         if let Some((abs_path, error)) = self.pending_environment_errors(cx).next() {
@@ -308,6 +333,24 @@ impl ActivityIndicator {
             });
         }
 
+        // Show the progress of reading large files.
+        if let Some(message) = self.large_file_load_message(cx) {
+            return Some(Content {
+                icon: Some(
+                    Icon::new(IconName::ArrowCircle)
+                        .size(IconSize::Small)
+                        .with_animation(
+                            "arrow-circle",
+                            Animation::new(Duration::from_secs(2)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        )
+                        .into_any_element(),
+                ),
+                message,
+                on_click: None,
+            });
+        }
+
         let current_job = self
             .project
             .read(cx)
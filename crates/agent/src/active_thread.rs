@@ -5,8 +5,8 @@ use crate::context_store::ContextStore;
 use crate::context_strip::{ContextStrip, ContextStripEvent, SuggestContextKind};
 use crate::message_editor::insert_message_creases;
 use crate::thread::{
-    LastRestoreCheckpoint, MessageCrease, MessageId, MessageSegment, Thread, ThreadError,
-    ThreadEvent, ThreadFeedback, ThreadSummary,
+    LastRestoreCheckpoint, MessageCrease, MessageId, MessageSegment, QueueState, Thread,
+    ThreadError, ThreadEvent, ThreadFeedback, ThreadSummary,
 };
 use crate::thread_store::{RulesLoadingError, TextThreadStore, ThreadStore};
 use crate::tool_use::{PendingToolUseStatus, ToolUse};
@@ -43,7 +43,7 @@ use std::ffi::OsStr;
 use std::path::Path;
 use std::rc::Rc;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use text::ToPoint;
 use theme::ThemeSettings;
 use ui::{
@@ -1421,6 +1421,9 @@ impl ActiveThread {
                             &configured_model.model,
                             cx,
                         ),
+                        max_tokens: None,
+                        response_format: None,
+                        reasoning: None,
                     };
 
                     Some(configured_model.model.count_tokens(request, cx))
@@ -1603,6 +1606,29 @@ impl ActiveThread {
         self.confirm_editing_message(&menu::Confirm, window, cx);
     }
 
+    fn fork_thread_from_message(
+        &mut self,
+        message_id: MessageId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let thread = self.thread.clone();
+        let fork = self.thread_store.update(cx, |thread_store, cx| {
+            thread_store.fork_thread(&thread, message_id, cx)
+        });
+        let workspace = self.workspace.clone();
+
+        cx.spawn_in(window, async move |_this, cx| {
+            let thread = fork.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                    panel.update(cx, |panel, cx| panel.open_thread(thread, window, cx));
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn handle_feedback_click(
         &mut self,
         message_id: MessageId,
@@ -1780,6 +1806,27 @@ impl ActiveThread {
 
         let loading_dots = (is_generating_stale && is_last_message)
             .then(|| AnimatedLabel::new("").size(LabelSize::Small));
+        let retry_label = match thread.queue_state() {
+            Some(QueueState::Retrying {
+                attempt,
+                max_attempts,
+                retry_at,
+            }) if is_last_message => {
+                let seconds = retry_at
+                    .saturating_duration_since(Instant::now())
+                    .as_secs_f32()
+                    .ceil();
+                Some(
+                    Label::new(format!(
+                        "Retrying in {seconds}s (attempt {} of {max_attempts})…",
+                        attempt + 1
+                    ))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+            }
+            _ => None,
+        };
 
         let editing_message_state = self
             .editing_message
@@ -1805,6 +1852,16 @@ impl ActiveThread {
                 }
             });
 
+        let fork_thread = |cx: &Context<Self>| {
+            IconButton::new(("fork-thread", ix), IconName::GitBranchSmall)
+                .icon_size(IconSize::XSmall)
+                .icon_color(Color::Ignored)
+                .tooltip(Tooltip::text("Fork Thread from Here"))
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.fork_thread_from_message(message_id, window, cx);
+                }))
+        };
+
         // For all items that should be aligned with the LLM's response.
         const RESPONSE_PADDING_X: Pixels = px(19.);
 
@@ -1871,6 +1928,7 @@ impl ActiveThread {
                                     );
                                 })),
                         )
+                        .child(fork_thread(cx))
                         .child(open_as_markdown),
                 )
                 .into_any_element(),
@@ -1915,14 +1973,25 @@ impl ActiveThread {
                                     );
                                 })),
                         )
+                        .child(fork_thread(cx))
                         .child(open_as_markdown),
                 )
                 .into_any_element(),
             None => feedback_container
-                .child(h_flex().child(open_as_markdown))
+                .child(h_flex().child(fork_thread(cx)).child(open_as_markdown))
                 .into_any_element(),
         };
 
+        // At the end of a turn, the thread can be forked with the feedback buttons,
+        // and from any other message with a button shown while hovering it.
+        let fork_controls = (!show_feedback && message.role != Role::System).then(|| {
+            h_flex()
+                .px(RESPONSE_PADDING_X)
+                .justify_end()
+                .visible_on_hover("message")
+                .child(fork_thread(cx))
+        });
+
         let message_is_empty = message.should_display_content();
         let has_content = !message_is_empty || !added_context.is_empty();
 
@@ -2095,6 +2164,7 @@ impl ActiveThread {
             .on_click(cx.listener(Self::handle_cancel_click));
 
         v_flex()
+            .group("message")
             .w_full()
             .map(|parent| {
                 if let Some(checkpoint) = checkpoint.filter(|_| !is_generating) {
@@ -2173,6 +2243,7 @@ impl ActiveThread {
                 parent.child(self.render_rules_item(cx))
             })
             .child(styled_message)
+            .children(fork_controls)
             .when(is_generating && is_last_message, |this| {
                 this.child(
                     h_flex()
@@ -2181,7 +2252,9 @@ impl ActiveThread {
                         .mb_4()
                         .ml_4()
                         .py_1p5()
-                        .when_some(loading_dots, |this, loading_dots| this.child(loading_dots)),
+                        .gap_2()
+                        .when_some(loading_dots, |this, loading_dots| this.child(loading_dots))
+                        .when_some(retry_label, |this, retry_label| this.child(retry_label)),
                 )
             })
             .when(show_feedback, move |parent| {
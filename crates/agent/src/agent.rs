@@ -7,6 +7,7 @@ mod buffer_codegen;
 mod context;
 mod context_picker;
 mod context_server_configuration;
+mod context_server_elicitation;
 mod context_server_tool;
 mod context_store;
 mod context_strip;
@@ -129,6 +130,7 @@ pub fn init(
     thread_store::init(cx);
     agent_panel::init(cx);
     context_server_configuration::init(language_registry, cx);
+    context_server_elicitation::init(cx);
 
     register_slash_commands(cx);
     inline_assistant::init(
@@ -304,6 +304,7 @@ fn wait_for_context_server(
                 _ => {}
             }
         }
+        project::context_server_store::Event::ElicitationRequested(_) => {}
     });
 
     cx.spawn(async move |_cx| {
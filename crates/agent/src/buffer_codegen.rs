@@ -470,6 +470,9 @@ impl CodegenAlternative {
                 stop: Vec::new(),
                 temperature,
                 messages: vec![request_message],
+                max_tokens: None,
+                response_format: None,
+                reasoning: None,
             }
         }))
     }
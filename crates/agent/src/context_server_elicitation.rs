@@ -0,0 +1,359 @@
+use std::collections::VecDeque;
+
+use context_server::types::{ElicitAction, ElicitResponse};
+use editor::{Editor, EditorElement, EditorStyle};
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, TextStyle};
+use project::context_server_store::{self, ContextServerElicitation};
+use serde_json::Value;
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::{KeyBinding, Modal, ModalFooter, ModalHeader, Section, prelude::*};
+use workspace::{ModalView, Workspace};
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        let context_server_store = workspace.project().read(cx).context_server_store();
+        cx.subscribe_in(
+            &context_server_store,
+            window,
+            |workspace, _, event, window, cx| {
+                if let context_server_store::Event::ElicitationRequested(elicitation) = event {
+                    show_elicitation(elicitation.clone(), workspace, window, cx);
+                }
+            },
+        )
+        .detach();
+    })
+    .detach();
+}
+
+fn show_elicitation(
+    elicitation: ContextServerElicitation,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if let Some(modal) = workspace.active_modal::<ContextServerElicitationModal>(cx) {
+        modal.update(cx, |modal, _| modal.pending.push_back(elicitation));
+        return;
+    }
+    workspace.toggle_modal(window, cx, |window, cx| {
+        ContextServerElicitationModal::new(elicitation, window, cx)
+    });
+}
+
+/// Asks the user for the information a context server requested, as a JSON object matching the requested schema.
+pub(crate) struct ContextServerElicitationModal {
+    focus_handle: FocusHandle,
+    elicitation: ContextServerElicitation,
+    properties: Vec<ElicitedProperty>,
+    validator: Option<jsonschema::Validator>,
+    content_editor: Entity<Editor>,
+    last_error: Option<SharedString>,
+    /// Requests made while this one is shown, asked for after it.
+    pending: VecDeque<ContextServerElicitation>,
+}
+
+struct ElicitedProperty {
+    name: SharedString,
+    description: Option<SharedString>,
+    required: bool,
+}
+
+impl ContextServerElicitationModal {
+    fn new(
+        elicitation: ContextServerElicitation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let content_editor = cx.new(|cx| Editor::auto_height(16, window, cx));
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            properties: Vec::new(),
+            validator: None,
+            content_editor,
+            last_error: None,
+            pending: VecDeque::new(),
+            elicitation: elicitation.clone(),
+        };
+        this.show(elicitation, window, cx);
+        this
+    }
+
+    fn show(
+        &mut self,
+        elicitation: ContextServerElicitation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let schema = &elicitation.requested_schema;
+        let required = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        let properties = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+
+        self.properties = properties
+            .iter()
+            .map(|(name, property)| ElicitedProperty {
+                name: property
+                    .get("title")
+                    .and_then(Value::as_str)
+                    .unwrap_or(name.as_str())
+                    .to_string()
+                    .into(),
+                description: property
+                    .get("description")
+                    .and_then(Value::as_str)
+                    .map(|description| description.to_string().into()),
+                required: required.iter().any(|required| required == name),
+            })
+            .collect();
+        self.validator = jsonschema::validator_for(schema).ok();
+        self.last_error = None;
+
+        let initial_content = properties
+            .into_iter()
+            .map(|(name, property)| {
+                let value = property.get("default").cloned().unwrap_or_else(|| {
+                    match property.get("type").and_then(Value::as_str) {
+                        Some("boolean") => Value::Bool(false),
+                        Some("number" | "integer") => Value::from(0),
+                        _ => property
+                            .get("enum")
+                            .and_then(Value::as_array)
+                            .and_then(|options| options.first().cloned())
+                            .unwrap_or_else(|| Value::String(String::new())),
+                    }
+                });
+                (name, value)
+            })
+            .collect::<serde_json::Map<_, _>>();
+        let initial_content =
+            serde_json::to_string_pretty(&Value::Object(initial_content)).unwrap_or_default();
+        self.content_editor.update(cx, |editor, cx| {
+            editor.set_text(initial_content, window, cx);
+            editor.set_show_gutter(false, cx);
+        });
+
+        self.elicitation = elicitation;
+        cx.notify();
+    }
+
+    fn accept(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let content =
+            match serde_json_lenient::from_str::<Value>(&self.content_editor.read(cx).text(cx)) {
+                Ok(content) => content,
+                Err(error) => {
+                    self.last_error = Some(error.to_string().into());
+                    cx.notify();
+                    return;
+                }
+            };
+        if let Some(validator) = self.validator.as_ref() {
+            if let Err(error) = validator.validate(&content) {
+                self.last_error = Some(error.to_string().into());
+                cx.notify();
+                return;
+            }
+        }
+        let Value::Object(content) = content else {
+            self.last_error = Some("Expected a JSON object".into());
+            cx.notify();
+            return;
+        };
+
+        self.respond(
+            ElicitResponse {
+                action: ElicitAction::Accept,
+                content: Some(content.into_iter().collect()),
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn decline(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.respond(
+            ElicitResponse {
+                action: ElicitAction::Decline,
+                content: None,
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn respond(&mut self, response: ElicitResponse, window: &mut Window, cx: &mut Context<Self>) {
+        self.elicitation.respond(response);
+        if let Some(elicitation) = self.pending.pop_front() {
+            self.show(elicitation, window, cx);
+        } else {
+            cx.emit(DismissEvent);
+        }
+    }
+}
+
+impl ModalView for ContextServerElicitationModal {
+    fn on_before_dismiss(
+        &mut self,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) -> workspace::DismissDecision {
+        // Requests that were answered already ignore this.
+        let cancel = || ElicitResponse {
+            action: ElicitAction::Cancel,
+            content: None,
+        };
+        self.elicitation.respond(cancel());
+        for elicitation in self.pending.drain(..) {
+            elicitation.respond(cancel());
+        }
+        workspace::DismissDecision::Dismiss(true)
+    }
+}
+
+impl EventEmitter<DismissEvent> for ContextServerElicitationModal {}
+
+impl Focusable for ContextServerElicitationModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.content_editor.read(cx).focus_handle(cx)
+    }
+}
+
+impl Render for ContextServerElicitationModal {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle(cx);
+
+        div()
+            .elevation_3(cx)
+            .w(rems(42.))
+            .key_context("ContextServerElicitationModal")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(|this, _: &menu::Confirm, window, cx| this.accept(window, cx)))
+            .on_action(cx.listener(Self::cancel))
+            .child(
+                Modal::new("context-server-elicitation", None)
+                    .header(ModalHeader::new().headline(format!(
+                        "{} Requests Information",
+                        self.elicitation.server_id
+                    )))
+                    .section(
+                        Section::new()
+                            .child(
+                                div()
+                                    .pb_2()
+                                    .child(Label::new(self.elicitation.message.clone())),
+                            )
+                            .children(self.properties.iter().map(|property| {
+                                h_flex()
+                                    .gap_1()
+                                    .child(Label::new(property.name.clone()).size(LabelSize::Small))
+                                    .when(property.required, |this| {
+                                        this.child(
+                                            Label::new("(required)")
+                                                .size(LabelSize::Small)
+                                                .color(Color::Muted),
+                                        )
+                                    })
+                                    .when_some(property.description.clone(), |this, description| {
+                                        this.child(
+                                            Label::new(description)
+                                                .size(LabelSize::Small)
+                                                .color(Color::Muted),
+                                        )
+                                    })
+                            }))
+                            .child(
+                                div()
+                                    .mt_2()
+                                    .p_2()
+                                    .rounded_md()
+                                    .border_1()
+                                    .border_color(cx.theme().colors().border_variant)
+                                    .bg(cx.theme().colors().editor_background)
+                                    .child({
+                                        let settings = ThemeSettings::get_global(cx);
+                                        let text_style = TextStyle {
+                                            color: cx.theme().colors().text,
+                                            font_family: settings.buffer_font.family.clone(),
+                                            font_fallbacks: settings.buffer_font.fallbacks.clone(),
+                                            font_size: settings.buffer_font_size(cx).into(),
+                                            font_weight: settings.buffer_font.weight,
+                                            line_height: relative(
+                                                settings.buffer_line_height.value(),
+                                            ),
+                                            ..Default::default()
+                                        };
+                                        EditorElement::new(
+                                            &self.content_editor,
+                                            EditorStyle {
+                                                background: cx.theme().colors().editor_background,
+                                                local_player: cx.theme().players().local(),
+                                                text: text_style,
+                                                syntax: cx.theme().syntax().clone(),
+                                                ..Default::default()
+                                            },
+                                        )
+                                    })
+                                    .when_some(self.last_error.clone(), |this, error| {
+                                        this.child(
+                                            h_flex()
+                                                .gap_2()
+                                                .px_2()
+                                                .py_1()
+                                                .child(
+                                                    Icon::new(IconName::Warning)
+                                                        .size(IconSize::XSmall)
+                                                        .color(Color::Warning),
+                                                )
+                                                .child(
+                                                    div().w_full().child(
+                                                        Label::new(error)
+                                                            .size(LabelSize::Small)
+                                                            .color(Color::Muted),
+                                                    ),
+                                                ),
+                                        )
+                                    }),
+                            ),
+                    )
+                    .footer(
+                        ModalFooter::new().end_slot(
+                            h_flex()
+                                .gap_2()
+                                .child(Button::new("decline", "Decline").on_click(
+                                    cx.listener(|this, _, window, cx| this.decline(window, cx)),
+                                ))
+                                .child(
+                                    Button::new("accept", "Submit")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Confirm,
+                                                &focus_handle,
+                                                window,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.accept(window, cx)
+                                        })),
+                                ),
+                        ),
+                    ),
+            )
+    }
+}
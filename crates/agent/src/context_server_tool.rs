@@ -48,6 +48,8 @@ impl Tool for ContextServerTool {
     }
 
     fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        // The tool annotations, such as `readOnlyHint` or `destructiveHint`, come from the server and
+        // can't be trusted, so they don't waive the confirmation.
         true
     }
 
@@ -66,7 +68,7 @@ impl Tool for ContextServerTool {
     }
 
     fn ui_text(&self, _input: &serde_json::Value) -> String {
-        format!("Run MCP tool `{}`", self.tool.name)
+        format!("Run MCP tool `{}`", self.tool.display_name())
     }
 
     fn run(
@@ -108,14 +110,38 @@ impl Tool for ContextServerTool {
                         types::ToolResponseContent::Text { text } => {
                             result.push_str(&text);
                         }
+                        types::ToolResponseContent::ResourceLink(link) => {
+                            if !result.is_empty() {
+                                result.push('\n');
+                            }
+                            result.push_str(&format!("[{}]({})", link.name, link.uri));
+                            if let Some(description) = link.description {
+                                result.push_str(&format!(": {description}"));
+                            }
+                        }
                         types::ToolResponseContent::Image { .. } => {
                             log::warn!("Ignoring image content from tool response");
                         }
+                        types::ToolResponseContent::Audio { .. } => {
+                            log::warn!("Ignoring audio content from tool response");
+                        }
                         types::ToolResponseContent::Resource { .. } => {
                             log::warn!("Ignoring resource content from tool response");
                         }
                     }
                 }
+
+                // Servers are expected to mirror structured output in a text block,
+                // but not all of them do.
+                if result.is_empty() {
+                    if let Some(structured_content) = response.structured_content {
+                        result = serde_json::to_string_pretty(&structured_content)?;
+                    }
+                }
+
+                if response.is_error == Some(true) {
+                    bail!(result);
+                }
                 Ok(result.into())
             })
             .into()
@@ -1254,6 +1254,9 @@ impl MessageEditor {
                         tool_choice: None,
                         stop: vec![],
                         temperature: AssistantSettings::temperature_for_model(&model.model, cx),
+                        max_tokens: None,
+                        response_format: None,
+                        reasoning: None,
                     };
 
                     Some(model.model.count_tokens(request, cx))
@@ -296,6 +296,9 @@ impl TerminalInlineAssistant {
                 tool_choice: None,
                 stop: Vec::new(),
                 temperature,
+                max_tokens: None,
+                response_format: None,
+                reasoning: None,
             }
         }))
     }
@@ -2,7 +2,7 @@ use std::fmt::Write as _;
 use std::io::Write;
 use std::ops::Range;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::{Result, anyhow};
 use assistant_settings::{AssistantSettings, CompletionMode};
@@ -23,8 +23,8 @@ use language_model::{
     LanguageModelId, LanguageModelKnownError, LanguageModelRegistry, LanguageModelRequest,
     LanguageModelRequestMessage, LanguageModelRequestTool, LanguageModelToolResult,
     LanguageModelToolResultContent, LanguageModelToolUseId, MessageContent,
-    ModelRequestLimitReachedError, PaymentRequiredError, RequestUsage, Role, SelectedModel,
-    StopReason, TokenUsage, WrappedTextContent,
+    ModelRequestLimitReachedError, PaymentRequiredError, RequestUsage, RetryPolicy, Role,
+    SelectedModel, StopReason, TokenUsage, WrappedTextContent,
 };
 use postage::stream::Stream as _;
 use project::Project;
@@ -98,6 +98,13 @@ impl MessageId {
     }
 }
 
+/// The thread and message that a thread was branched from.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct ThreadFork {
+    pub thread_id: ThreadId,
+    pub message_id: MessageId,
+}
+
 /// Stored information that can be used to resurrect a context crease when creating an editor for a past message.
 #[derive(Clone, Debug)]
 pub struct MessageCrease {
@@ -316,8 +323,16 @@ pub enum TokenUsageRatio {
 #[derive(Debug, Clone, Copy)]
 pub enum QueueState {
     Sending,
-    Queued { position: usize },
+    Queued {
+        position: usize,
+    },
     Started,
+    /// The request failed with a transient error and is sent again at `retry_at`.
+    Retrying {
+        attempt: u32,
+        max_attempts: u32,
+        retry_at: Instant,
+    },
 }
 
 /// A thread of conversation with the LLM.
@@ -359,6 +374,7 @@ pub struct Thread {
     >,
     remaining_turns: u32,
     configured_model: Option<ConfiguredModel>,
+    forked_from: Option<ThreadFork>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -448,6 +464,7 @@ impl Thread {
             request_callback: None,
             remaining_turns: u32::MAX,
             configured_model,
+            forked_from: None,
         }
     }
 
@@ -568,6 +585,7 @@ impl Thread {
             request_callback: None,
             remaining_turns: u32::MAX,
             configured_model,
+            forked_from: serialized.forked_from,
         }
     }
 
@@ -583,6 +601,10 @@ impl Thread {
         &self.id
     }
 
+    pub fn forked_from(&self) -> Option<&ThreadFork> {
+        self.forked_from.as_ref()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.messages.is_empty()
     }
@@ -794,6 +816,30 @@ impl Thread {
         cx.notify();
     }
 
+    /// Returns the checkpoints of the messages up to and including `message_id`, so that they can
+    /// be carried over to a thread forked from it.
+    pub(crate) fn checkpoints_through(&self, message_id: MessageId) -> Vec<ThreadCheckpoint> {
+        self.checkpoints_by_message
+            .values()
+            .filter(|checkpoint| checkpoint.message_id <= message_id)
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn insert_checkpoints(
+        &mut self,
+        checkpoints: impl IntoIterator<Item = ThreadCheckpoint>,
+        cx: &mut Context<Self>,
+    ) {
+        self.checkpoints_by_message.extend(
+            checkpoints
+                .into_iter()
+                .map(|checkpoint| (checkpoint.message_id, checkpoint)),
+        );
+        cx.emit(ThreadEvent::CheckpointChanged);
+        cx.notify();
+    }
+
     pub fn last_restore_checkpoint(&self) -> Option<&LastRestoreCheckpoint> {
         self.last_restore_checkpoint.as_ref()
     }
@@ -1142,6 +1188,7 @@ impl Thread {
                         model: model.model.id().0.to_string(),
                     }),
                 completion_mode: Some(this.completion_mode),
+                forked_from: this.forked_from.clone(),
             })
         })
     }
@@ -1197,6 +1244,9 @@ impl Thread {
             tool_choice: None,
             stop: Vec::new(),
             temperature: AssistantSettings::temperature_for_model(&model, cx),
+            max_tokens: None,
+            response_format: None,
+            reasoning: None,
         };
 
         let available_tools = self.available_tools(cx, model.clone());
@@ -1356,6 +1406,9 @@ impl Thread {
             tool_choice: None,
             stop: Vec::new(),
             temperature: AssistantSettings::temperature_for_model(model, cx),
+            max_tokens: None,
+            response_format: None,
+            reasoning: None,
         };
 
         for message in &self.messages {
@@ -1456,225 +1509,296 @@ impl Thread {
         self.last_received_chunk_at = Some(Instant::now());
 
         let task = cx.spawn(async move |thread, cx| {
-            let stream_completion_future = model.stream_completion(request, &cx);
             let initial_token_usage =
                 thread.read_with(cx, |thread, _cx| thread.cumulative_token_usage);
+            let retry_policy = RetryPolicy::default();
             let stream_completion = async {
-                let mut events = stream_completion_future.await?;
-
-                let mut stop_reason = StopReason::EndTurn;
-                let mut current_token_usage = TokenUsage::default();
+                let mut attempt = 0;
+                loop {
+                    attempt += 1;
+                    let mut received_content = false;
+                    let mut request_assistant_message_id = None;
 
-                thread
-                    .update(cx, |_thread, cx| {
-                        cx.emit(ThreadEvent::NewRequest);
-                    })
-                    .ok();
+                    let result = async {
+                        let mut events = model.stream_completion(request.clone(), &cx).await?;
 
-                let mut request_assistant_message_id = None;
+                        let mut stop_reason = StopReason::EndTurn;
+                        let mut current_token_usage = TokenUsage::default();
 
-                while let Some(event) = events.next().await {
-                    if let Some((_, response_events)) = request_callback_parameters.as_mut() {
-                        response_events
-                            .push(event.as_ref().map_err(|error| error.to_string()).cloned());
-                    }
+                        thread
+                            .update(cx, |_thread, cx| {
+                                cx.emit(ThreadEvent::NewRequest);
+                            })
+                            .ok();
 
-                    thread.update(cx, |thread, cx| {
-                        let event = match event {
-                            Ok(event) => event,
-                            Err(LanguageModelCompletionError::BadInputJson {
-                                id,
-                                tool_name,
-                                raw_input: invalid_input_json,
-                                json_parse_error,
-                            }) => {
-                                thread.receive_invalid_tool_json(
-                                    id,
-                                    tool_name,
-                                    invalid_input_json,
-                                    json_parse_error,
-                                    window,
-                                    cx,
-                                );
-                                return Ok(());
-                            }
-                            Err(LanguageModelCompletionError::Other(error)) => {
-                                return Err(error);
-                            }
-                        };
-
-                        match event {
-                            LanguageModelCompletionEvent::StartMessage { .. } => {
-                                request_assistant_message_id =
-                                    Some(thread.insert_assistant_message(
-                                        vec![MessageSegment::Text(String::new())],
-                                        cx,
-                                    ));
-                            }
-                            LanguageModelCompletionEvent::Stop(reason) => {
-                                stop_reason = reason;
-                            }
-                            LanguageModelCompletionEvent::UsageUpdate(token_usage) => {
-                                thread.update_token_usage_at_last_message(token_usage);
-                                thread.cumulative_token_usage = thread.cumulative_token_usage
-                                    + token_usage
-                                    - current_token_usage;
-                                current_token_usage = token_usage;
-                            }
-                            LanguageModelCompletionEvent::Text(chunk) => {
-                                thread.received_chunk();
-
-                                cx.emit(ThreadEvent::ReceivedTextChunk);
-                                if let Some(last_message) = thread.messages.last_mut() {
-                                    if last_message.role == Role::Assistant
-                                        && !thread.tool_use.has_tool_results(last_message.id)
-                                    {
-                                        last_message.push_text(&chunk);
-                                        cx.emit(ThreadEvent::StreamedAssistantText(
-                                            last_message.id,
-                                            chunk,
-                                        ));
-                                    } else {
-                                        // If we won't have an Assistant message yet, assume this chunk marks the beginning
-                                        // of a new Assistant response.
-                                        //
-                                        // Importantly: We do *not* want to emit a `StreamedAssistantText` event here, as it
-                                        // will result in duplicating the text of the chunk in the rendered Markdown.
-                                        request_assistant_message_id =
-                                            Some(thread.insert_assistant_message(
-                                                vec![MessageSegment::Text(chunk.to_string())],
-                                                cx,
-                                            ));
-                                    };
-                                }
+                        while let Some(event) = events.next().await {
+                            if let Some((_, response_events)) = request_callback_parameters.as_mut() {
+                                response_events
+                                    .push(event.as_ref().map_err(|error| error.to_string()).cloned());
                             }
-                            LanguageModelCompletionEvent::Thinking {
-                                text: chunk,
-                                signature,
-                            } => {
-                                thread.received_chunk();
-
-                                if let Some(last_message) = thread.messages.last_mut() {
-                                    if last_message.role == Role::Assistant
-                                        && !thread.tool_use.has_tool_results(last_message.id)
-                                    {
-                                        last_message.push_thinking(&chunk, signature);
-                                        cx.emit(ThreadEvent::StreamedAssistantThinking(
-                                            last_message.id,
-                                            chunk,
-                                        ));
-                                    } else {
-                                        // If we won't have an Assistant message yet, assume this chunk marks the beginning
-                                        // of a new Assistant response.
-                                        //
-                                        // Importantly: We do *not* want to emit a `StreamedAssistantText` event here, as it
-                                        // will result in duplicating the text of the chunk in the rendered Markdown.
+
+                            thread.update(cx, |thread, cx| {
+                                let event = match event {
+                                    Ok(event) => event,
+                                    Err(LanguageModelCompletionError::BadInputJson {
+                                        id,
+                                        tool_name,
+                                        raw_input: invalid_input_json,
+                                        json_parse_error,
+                                    }) => {
+                                        thread.receive_invalid_tool_json(
+                                            id,
+                                            tool_name,
+                                            invalid_input_json,
+                                            json_parse_error,
+                                            window,
+                                            cx,
+                                        );
+                                        return Ok(());
+                                    }
+                                    Err(error) => return Err(error),
+                                };
+
+                                match event {
+                                    LanguageModelCompletionEvent::StartMessage { .. } => {
                                         request_assistant_message_id =
                                             Some(thread.insert_assistant_message(
-                                                vec![MessageSegment::Thinking {
-                                                    text: chunk.to_string(),
-                                                    signature,
-                                                }],
+                                                vec![MessageSegment::Text(String::new())],
                                                 cx,
                                             ));
-                                    };
-                                }
-                            }
-                            LanguageModelCompletionEvent::ToolUse(tool_use) => {
-                                let last_assistant_message_id = request_assistant_message_id
-                                    .unwrap_or_else(|| {
-                                        let new_assistant_message_id =
-                                            thread.insert_assistant_message(vec![], cx);
-                                        request_assistant_message_id =
-                                            Some(new_assistant_message_id);
-                                        new_assistant_message_id
-                                    });
-
-                                let tool_use_id = tool_use.id.clone();
-                                let streamed_input = if tool_use.is_input_complete {
-                                    None
-                                } else {
-                                    Some((&tool_use.input).clone())
-                                };
-
-                                let ui_text = thread.tool_use.request_tool_use(
-                                    last_assistant_message_id,
-                                    tool_use,
-                                    tool_use_metadata.clone(),
-                                    cx,
-                                );
-
-                                if let Some(input) = streamed_input {
-                                    cx.emit(ThreadEvent::StreamedToolUse {
-                                        tool_use_id,
-                                        ui_text,
-                                        input,
-                                    });
-                                }
-                            }
-                            LanguageModelCompletionEvent::StatusUpdate(status_update) => {
-                                if let Some(completion) = thread
-                                    .pending_completions
-                                    .iter_mut()
-                                    .find(|completion| completion.id == pending_completion_id)
-                                {
-                                    match status_update {
-                                        CompletionRequestStatus::Queued {
-                                            position,
-                                        } => {
-                                            completion.queue_state = QueueState::Queued { position };
-                                        }
-                                        CompletionRequestStatus::Started => {
-                                            completion.queue_state =  QueueState::Started;
+                                    }
+                                    LanguageModelCompletionEvent::Stop(reason) => {
+                                        stop_reason = reason;
+                                    }
+                                    LanguageModelCompletionEvent::UsageUpdate(token_usage) => {
+                                        thread.update_token_usage_at_last_message(token_usage);
+                                        thread.cumulative_token_usage = thread.cumulative_token_usage
+                                            + token_usage
+                                            - current_token_usage;
+                                        current_token_usage = token_usage;
+                                    }
+                                    LanguageModelCompletionEvent::Text(chunk) => {
+                                        thread.received_chunk();
+                                        received_content = true;
+
+                                        cx.emit(ThreadEvent::ReceivedTextChunk);
+                                        if let Some(last_message) = thread.messages.last_mut() {
+                                            if last_message.role == Role::Assistant
+                                                && !thread.tool_use.has_tool_results(last_message.id)
+                                            {
+                                                last_message.push_text(&chunk);
+                                                cx.emit(ThreadEvent::StreamedAssistantText(
+                                                    last_message.id,
+                                                    chunk,
+                                                ));
+                                            } else {
+                                                // If we won't have an Assistant message yet, assume this chunk marks the beginning
+                                                // of a new Assistant response.
+                                                //
+                                                // Importantly: We do *not* want to emit a `StreamedAssistantText` event here, as it
+                                                // will result in duplicating the text of the chunk in the rendered Markdown.
+                                                request_assistant_message_id =
+                                                    Some(thread.insert_assistant_message(
+                                                        vec![MessageSegment::Text(chunk.to_string())],
+                                                        cx,
+                                                    ));
+                                            };
                                         }
-                                        CompletionRequestStatus::Failed {
-                                            code, message, request_id
-                                        } => {
-                                            anyhow::bail!("completion request failed. request_id: {request_id}, code: {code}, message: {message}");
+                                    }
+                                    LanguageModelCompletionEvent::Thinking {
+                                        text: chunk,
+                                        signature,
+                                    } => {
+                                        thread.received_chunk();
+                                        received_content = true;
+
+                                        if let Some(last_message) = thread.messages.last_mut() {
+                                            if last_message.role == Role::Assistant
+                                                && !thread.tool_use.has_tool_results(last_message.id)
+                                            {
+                                                last_message.push_thinking(&chunk, signature);
+                                                cx.emit(ThreadEvent::StreamedAssistantThinking(
+                                                    last_message.id,
+                                                    chunk,
+                                                ));
+                                            } else {
+                                                // If we won't have an Assistant message yet, assume this chunk marks the beginning
+                                                // of a new Assistant response.
+                                                //
+                                                // Importantly: We do *not* want to emit a `StreamedAssistantText` event here, as it
+                                                // will result in duplicating the text of the chunk in the rendered Markdown.
+                                                request_assistant_message_id =
+                                                    Some(thread.insert_assistant_message(
+                                                        vec![MessageSegment::Thinking {
+                                                            text: chunk.to_string(),
+                                                            signature,
+                                                        }],
+                                                        cx,
+                                                    ));
+                                            };
                                         }
-                                        CompletionRequestStatus::UsageUpdated {
-                                            amount, limit
-                                        } => {
-                                            let usage = RequestUsage { limit, amount: amount as i32 };
-
-                                            thread.last_usage = Some(usage);
+                                    }
+                                    LanguageModelCompletionEvent::ToolUse(tool_use) => {
+                                        received_content = true;
+                                        let last_assistant_message_id = request_assistant_message_id
+                                            .unwrap_or_else(|| {
+                                                let new_assistant_message_id =
+                                                    thread.insert_assistant_message(vec![], cx);
+                                                request_assistant_message_id =
+                                                    Some(new_assistant_message_id);
+                                                new_assistant_message_id
+                                            });
+
+                                        let tool_use_id = tool_use.id.clone();
+                                        let streamed_input = if tool_use.is_input_complete {
+                                            None
+                                        } else {
+                                            Some((&tool_use.input).clone())
+                                        };
+
+                                        let ui_text = thread.tool_use.request_tool_use(
+                                            last_assistant_message_id,
+                                            tool_use,
+                                            tool_use_metadata.clone(),
+                                            cx,
+                                        );
+
+                                        if let Some(input) = streamed_input {
+                                            cx.emit(ThreadEvent::StreamedToolUse {
+                                                tool_use_id,
+                                                ui_text,
+                                                input,
+                                            });
                                         }
-                                        CompletionRequestStatus::ToolUseLimitReached => {
-                                            thread.tool_use_limit_reached = true;
+                                    }
+                                    LanguageModelCompletionEvent::StatusUpdate(status_update) => {
+                                        if let Some(completion) = thread
+                                            .pending_completions
+                                            .iter_mut()
+                                            .find(|completion| completion.id == pending_completion_id)
+                                        {
+                                            match status_update {
+                                                CompletionRequestStatus::Queued {
+                                                    position,
+                                                } => {
+                                                    completion.queue_state = QueueState::Queued { position };
+                                                }
+                                                CompletionRequestStatus::Started => {
+                                                    completion.queue_state =  QueueState::Started;
+                                                }
+                                                CompletionRequestStatus::Failed {
+                                                    code, message, request_id
+                                                } => {
+                                                    return Err(anyhow!("completion request failed. request_id: {request_id}, code: {code}, message: {message}").into());
+                                                }
+                                                CompletionRequestStatus::UsageUpdated {
+                                                    amount, limit
+                                                } => {
+                                                    let usage = RequestUsage { limit, amount: amount as i32 };
+
+                                                    thread.last_usage = Some(usage);
+                                                }
+                                                CompletionRequestStatus::ToolUseLimitReached => {
+                                                    thread.tool_use_limit_reached = true;
+                                                }
+                                            }
                                         }
                                     }
                                 }
-                            }
+
+                                thread.touch_updated_at();
+                                cx.emit(ThreadEvent::StreamedCompletion);
+                                cx.notify();
+
+                                thread.auto_capture_telemetry(cx);
+                                Ok(())
+                            })??;
+
+                            smol::future::yield_now().await;
                         }
 
-                        thread.touch_updated_at();
-                        cx.emit(ThreadEvent::StreamedCompletion);
-                        cx.notify();
+                        thread.update(cx, |thread, cx| {
+                            thread.last_received_chunk_at = None;
+                            thread
+                                .pending_completions
+                                .retain(|completion| completion.id != pending_completion_id);
+
+                            // If there is a response without tool use, summarize the message. Otherwise,
+                            // allow two tool uses before summarizing.
+                            if matches!(thread.summary, ThreadSummary::Pending)
+                                && thread.messages.len() >= 2
+                                && (!thread.has_pending_tool_uses() || thread.messages.len() >= 6)
+                            {
+                                thread.summarize(cx);
+                            }
+                        })?;
 
-                        thread.auto_capture_telemetry(cx);
-                        Ok(())
-                    })??;
+                        Ok::<_, LanguageModelCompletionError>(stop_reason)
+                    }
+                    .await;
 
-                    smol::future::yield_now().await;
-                }
+                    let error = match result {
+                        Ok(stop_reason) => break anyhow::Ok(stop_reason),
+                        Err(error) => error,
+                    };
 
-                thread.update(cx, |thread, cx| {
-                    thread.last_received_chunk_at = None;
-                    thread
-                        .pending_completions
-                        .retain(|completion| completion.id != pending_completion_id);
-
-                    // If there is a response without tool use, summarize the message. Otherwise,
-                    // allow two tool uses before summarizing.
-                    if matches!(thread.summary, ThreadSummary::Pending)
-                        && thread.messages.len() >= 2
-                        && (!thread.has_pending_tool_uses() || thread.messages.len() >= 6)
-                    {
-                        thread.summarize(cx);
+                    // Only retry while nothing was streamed, so that a partial response isn't repeated.
+                    let retry_delay = if received_content {
+                        None
+                    } else {
+                        retry_policy.retry_delay(&error, attempt)
+                    };
+                    let Some(retry_delay) = retry_delay else {
+                        break Err(error.into_anyhow());
+                    };
+
+                    log::warn!(
+                        "completion attempt {attempt} of {} failed, retrying in {retry_delay:?}: {error}",
+                        retry_policy.max_attempts
+                    );
+                    let retry_at = Instant::now() + retry_delay;
+                    thread.update(cx, |thread, cx| {
+                        if let Some(message_id) = request_assistant_message_id {
+                            thread.delete_message(message_id, cx);
+                        }
+                        if let Some(completion) = thread
+                            .pending_completions
+                            .iter_mut()
+                            .find(|completion| completion.id == pending_completion_id)
+                        {
+                            completion.queue_state = QueueState::Retrying {
+                                attempt,
+                                max_attempts: retry_policy.max_attempts,
+                                retry_at,
+                            };
+                        }
+                        cx.notify();
+                    })?;
+
+                    // Wake up every second so that the countdown stays current.
+                    loop {
+                        let remaining = retry_at.saturating_duration_since(Instant::now());
+                        if remaining.is_zero() {
+                            break;
+                        }
+                        cx.background_executor()
+                            .timer(remaining.min(Duration::from_secs(1)))
+                            .await;
+                        thread.update(cx, |_, cx| cx.notify())?;
                     }
-                })?;
 
-                anyhow::Ok(stop_reason)
+                    thread.update(cx, |thread, cx| {
+                        if let Some(completion) = thread
+                            .pending_completions
+                            .iter_mut()
+                            .find(|completion| completion.id == pending_completion_id)
+                        {
+                            completion.queue_state = QueueState::Sending;
+                        }
+                        thread.last_received_chunk_at = Some(Instant::now());
+                        cx.notify();
+                    })?;
+                }
             };
 
             let result = stream_completion.await;
@@ -3478,6 +3602,120 @@ fn main() {{
         cx.run_until_parked();
     }
 
+    #[gpui::test]
+    async fn test_fork_thread(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({"code.rs": "fn main() {}"})).await;
+        let (_workspace, thread_store, thread, _context_store, _model) =
+            setup_test_environment(cx, project.clone()).await;
+
+        let git_store = project.read_with(cx, |project, _| project.git_store().clone());
+        let git_checkpoint = git_store
+            .update(cx, |git_store, cx| git_store.checkpoint(cx))
+            .await
+            .unwrap();
+        let message_ids = thread.update(cx, |thread, cx| {
+            let message_ids = [
+                thread.insert_user_message(
+                    "First question",
+                    ContextLoadResult::default(),
+                    None,
+                    Vec::new(),
+                    cx,
+                ),
+                thread.insert_assistant_message(
+                    vec![MessageSegment::Text("First answer".into())],
+                    cx,
+                ),
+                thread.insert_user_message(
+                    "Second question",
+                    ContextLoadResult::default(),
+                    None,
+                    Vec::new(),
+                    cx,
+                ),
+                thread.insert_assistant_message(
+                    vec![MessageSegment::Text("Second answer".into())],
+                    cx,
+                ),
+            ];
+            for message_id in [message_ids[0], message_ids[2]] {
+                thread.insert_checkpoint(
+                    ThreadCheckpoint {
+                        message_id,
+                        git_checkpoint: git_checkpoint.clone(),
+                    },
+                    cx,
+                );
+            }
+            message_ids
+        });
+
+        let forked_thread = thread_store
+            .update(cx, |thread_store, cx| {
+                thread_store.fork_thread(&thread, message_ids[1], cx)
+            })
+            .await
+            .unwrap();
+
+        let original_id = thread.read_with(cx, |thread, _| thread.id().clone());
+        forked_thread.read_with(cx, |forked_thread, _| {
+            assert_ne!(forked_thread.id(), &original_id);
+            assert_eq!(
+                forked_thread
+                    .messages()
+                    .map(|message| message.id)
+                    .collect::<Vec<_>>(),
+                message_ids[..2]
+            );
+            assert_eq!(
+                forked_thread.message(message_ids[1]).unwrap().segments,
+                vec![MessageSegment::Text("First answer".into())]
+            );
+            assert!(
+                forked_thread
+                    .checkpoint_for_message(message_ids[0])
+                    .is_some()
+            );
+            assert!(
+                forked_thread
+                    .checkpoint_for_message(message_ids[2])
+                    .is_none()
+            );
+            assert_eq!(
+                forked_thread.forked_from(),
+                Some(&ThreadFork {
+                    thread_id: original_id.clone(),
+                    message_id: message_ids[1],
+                })
+            );
+        });
+
+        // The original thread keeps all of its messages and checkpoints.
+        thread.read_with(cx, |thread, _| {
+            assert_eq!(
+                thread
+                    .messages()
+                    .map(|message| message.id)
+                    .collect::<Vec<_>>(),
+                message_ids
+            );
+            assert!(thread.checkpoint_for_message(message_ids[0]).is_some());
+            assert!(thread.checkpoint_for_message(message_ids[2]).is_some());
+            assert_eq!(thread.forked_from(), None);
+        });
+
+        // The fork was saved to the threads database, don't leave it there.
+        let forked_id = forked_thread.read_with(cx, |forked_thread, _| forked_thread.id().clone());
+        thread_store
+            .update(cx, |thread_store, cx| {
+                thread_store.delete_thread(&forked_id, cx)
+            })
+            .await
+            .unwrap();
+    }
+
     fn init_test_settings(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings_store = SettingsStore::test(cx);
@@ -3,6 +3,7 @@ use std::ops::Range;
 use std::sync::Arc;
 
 use chrono::{Datelike as _, Local, NaiveDate, TimeDelta};
+use collections::{HashMap, HashSet};
 use editor::{Editor, EditorEvent};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
@@ -17,6 +18,7 @@ use ui::{
 use util::ResultExt;
 
 use crate::history_store::{HistoryEntry, HistoryStore};
+use crate::thread::ThreadId;
 use crate::{AgentPanel, RemoveSelectedThread};
 
 pub struct ThreadHistory {
@@ -56,6 +58,8 @@ enum ListItemType {
     Entry {
         index: usize,
         format: EntryTimeFormat,
+        /// How many forks away from the thread it was originally branched from.
+        depth: usize,
     },
 }
 
@@ -117,10 +121,7 @@ impl ThreadHistory {
     }
 
     fn update_all_entries(&mut self, cx: &mut Context<Self>) {
-        let new_entries: Arc<Vec<HistoryEntry>> = self
-            .history_store
-            .update(cx, |store, cx| store.entries(cx))
-            .into();
+        let new_entries = self.history_store.update(cx, |store, cx| store.entries(cx));
 
         self._separated_items_task.take();
 
@@ -128,16 +129,24 @@ impl ThreadHistory {
         let mut indexes = Vec::with_capacity(new_entries.len() + 1);
 
         let bg_task = cx.background_spawn(async move {
+            let (new_entries, depths): (Vec<_>, Vec<_>) =
+                entries_in_fork_order(new_entries).into_iter().unzip();
             let mut bucket = None;
             let today = Local::now().naive_local().date();
 
-            for (index, entry) in new_entries.iter().enumerate() {
-                let entry_date = entry
-                    .updated_at()
-                    .with_timezone(&Local)
-                    .naive_local()
-                    .date();
-                let entry_bucket = TimeBucket::from_dates(today, entry_date);
+            for (index, (entry, depth)) in new_entries.iter().zip(depths).enumerate() {
+                // Forks are listed under the thread they were branched from, whatever their date.
+                let entry_bucket = match bucket {
+                    Some(bucket) if depth > 0 => bucket,
+                    _ => {
+                        let entry_date = entry
+                            .updated_at()
+                            .with_timezone(&Local)
+                            .naive_local()
+                            .date();
+                        TimeBucket::from_dates(today, entry_date)
+                    }
+                };
 
                 if Some(entry_bucket) != bucket {
                     bucket = Some(entry_bucket);
@@ -148,9 +157,10 @@ impl ThreadHistory {
                 items.push(ListItemType::Entry {
                     index,
                     format: entry_bucket.into(),
+                    depth,
                 });
             }
-            (new_entries, items, indexes)
+            (Arc::new(new_entries), items, indexes)
         });
 
         let task = cx.spawn(async move |this, cx| {
@@ -475,6 +485,7 @@ impl ThreadHistory {
                         &ListItemType::Entry {
                             index: m.candidate_id,
                             format: EntryTimeFormat::DateAndTime,
+                            depth: 0,
                         },
                         m.positions.clone(),
                         cx,
@@ -495,7 +506,11 @@ impl ThreadHistory {
         cx: &Context<Self>,
     ) -> AnyElement {
         match item {
-            ListItemType::Entry { index, format } => match self.all_entries.get(*index) {
+            ListItemType::Entry {
+                index,
+                format,
+                depth,
+            } => match self.all_entries.get(*index) {
                 Some(entry) => h_flex()
                     .w_full()
                     .pb_1()
@@ -503,6 +518,7 @@ impl ThreadHistory {
                         HistoryEntryElement::new(entry.clone(), self.agent_panel.clone())
                             .highlight_positions(highlight_positions)
                             .timestamp_format(*format)
+                            .depth(*depth)
                             .selected(list_entry_ix == Some(self.selected_index))
                             .hovered(list_entry_ix == self.hovered_index)
                             .on_hover(cx.listener(move |this, is_hovered, _window, cx| {
@@ -619,6 +635,7 @@ pub struct HistoryEntryElement {
     hovered: bool,
     highlight_positions: Vec<usize>,
     timestamp_format: EntryTimeFormat,
+    depth: usize,
     on_hover: Box<dyn Fn(&bool, &mut Window, &mut App) + 'static>,
 }
 
@@ -631,6 +648,7 @@ impl HistoryEntryElement {
             hovered: false,
             highlight_positions: vec![],
             timestamp_format: EntryTimeFormat::DateAndTime,
+            depth: 0,
             on_hover: Box::new(|_, _, _| {}),
         }
     }
@@ -659,20 +677,27 @@ impl HistoryEntryElement {
         self.timestamp_format = format;
         self
     }
+
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
 }
 
 impl RenderOnce for HistoryEntryElement {
     fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
-        let (id, summary, timestamp) = match &self.entry {
+        let (id, summary, timestamp, is_fork) = match &self.entry {
             HistoryEntry::Thread(thread) => (
                 thread.id.to_string(),
                 thread.summary.clone(),
                 thread.updated_at.timestamp(),
+                thread.forked_from.is_some(),
             ),
             HistoryEntry::Context(context) => (
                 context.path.to_string_lossy().to_string(),
                 context.title.clone().into(),
                 context.mtime.timestamp(),
+                false,
             ),
         };
 
@@ -684,15 +709,29 @@ impl RenderOnce for HistoryEntryElement {
             .rounded()
             .toggle_state(self.selected)
             .spacing(ListItemSpacing::Sparse)
+            .indent_level(self.depth)
+            .indent_step_size(px(12.))
             .start_slot(
                 h_flex()
                     .w_full()
                     .gap_2()
                     .justify_between()
                     .child(
-                        HighlightedLabel::new(summary, self.highlight_positions)
-                            .size(LabelSize::Small)
-                            .truncate(),
+                        h_flex()
+                            .min_w_0()
+                            .gap_1()
+                            .when(is_fork, |this| {
+                                this.child(
+                                    Icon::new(IconName::GitBranchSmall)
+                                        .size(IconSize::XSmall)
+                                        .color(Color::Muted),
+                                )
+                            })
+                            .child(
+                                HighlightedLabel::new(summary, self.highlight_positions)
+                                    .size(LabelSize::Small)
+                                    .truncate(),
+                            ),
                     )
                     .child(
                         Label::new(thread_timestamp)
@@ -779,6 +818,71 @@ impl RenderOnce for HistoryEntryElement {
     }
 }
 
+/// Orders the entries so that every forked thread comes right after the thread it was branched
+/// from, returning each entry along with how deep it is in the tree of forks.
+///
+/// Threads forked from a thread that is no longer in the history are listed at the top level.
+fn entries_in_fork_order(entries: Vec<HistoryEntry>) -> Vec<(HistoryEntry, usize)> {
+    let thread_ids = entries
+        .iter()
+        .filter_map(|entry| match entry {
+            HistoryEntry::Thread(thread) => Some(thread.id.clone()),
+            HistoryEntry::Context(_) => None,
+        })
+        .collect::<HashSet<_>>();
+
+    let mut roots = Vec::new();
+    let mut forks_by_thread = HashMap::<ThreadId, Vec<usize>>::default();
+    for (ix, entry) in entries.iter().enumerate() {
+        let parent_id = match entry {
+            HistoryEntry::Thread(thread) => thread
+                .forked_from
+                .as_ref()
+                .map(|fork| &fork.thread_id)
+                .filter(|parent_id| thread_ids.contains(*parent_id)),
+            HistoryEntry::Context(_) => None,
+        };
+        match parent_id {
+            Some(parent_id) => forks_by_thread
+                .entry(parent_id.clone())
+                .or_default()
+                .push(ix),
+            None => roots.push(ix),
+        }
+    }
+
+    let mut order = Vec::with_capacity(entries.len());
+    let mut visited = vec![false; entries.len()];
+    let mut stack = roots
+        .into_iter()
+        .rev()
+        .map(|ix| (ix, 0))
+        .collect::<Vec<_>>();
+    while let Some((ix, depth)) = stack.pop() {
+        if std::mem::replace(&mut visited[ix], true) {
+            continue;
+        }
+        order.push((ix, depth));
+        if let HistoryEntry::Thread(thread) = &entries[ix] {
+            if let Some(forks) = forks_by_thread.get(&thread.id) {
+                stack.extend(forks.iter().rev().map(|fork_ix| (*fork_ix, depth + 1)));
+            }
+        }
+    }
+    // Entries that only lead back to each other can't be reached from a root.
+    order.extend(
+        (0..entries.len())
+            .filter(|ix| !visited[*ix])
+            .map(|ix| (ix, 0)),
+    );
+
+    let mut entries = entries.into_iter().map(Some).collect::<Vec<_>>();
+    order
+        .into_iter()
+        .filter_map(|(ix, depth)| Some((entries[ix].take()?, depth)))
+        .collect()
+}
+
 #[derive(Clone, Copy)]
 pub enum EntryTimeFormat {
     DateAndTime,
@@ -871,7 +975,51 @@ impl Display for TimeBucket {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::NaiveDate;
+    use crate::thread::{MessageId, ThreadFork};
+    use crate::thread_store::SerializedThreadMetadata;
+    use chrono::{NaiveDate, TimeZone as _, Utc};
+
+    #[test]
+    fn test_entries_in_fork_order() {
+        fn thread(id: &str, hour: u32, forked_from: Option<&str>) -> HistoryEntry {
+            HistoryEntry::Thread(SerializedThreadMetadata {
+                id: ThreadId::from(id),
+                summary: id.to_string().into(),
+                updated_at: Utc.with_ymd_and_hms(2023, 1, 15, hour, 0, 0).unwrap(),
+                forked_from: forked_from.map(|thread_id| ThreadFork {
+                    thread_id: ThreadId::from(thread_id),
+                    message_id: MessageId(0),
+                }),
+            })
+        }
+
+        let entries = vec![
+            thread("fork-of-fork", 12, Some("fork")),
+            thread("other", 11, None),
+            thread("fork", 10, Some("root")),
+            thread("orphan", 9, Some("deleted")),
+            thread("second-fork", 8, Some("root")),
+            thread("root", 7, None),
+        ];
+        let ordered = entries_in_fork_order(entries)
+            .into_iter()
+            .map(|(entry, depth)| match entry {
+                HistoryEntry::Thread(thread) => (thread.id.to_string(), depth),
+                HistoryEntry::Context(_) => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ordered,
+            [
+                ("other".to_string(), 0),
+                ("orphan".to_string(), 0),
+                ("root".to_string(), 0),
+                ("fork".to_string(), 1),
+                ("fork-of-fork".to_string(), 2),
+                ("second-fork".to_string(), 1),
+            ]
+        );
+    }
 
     #[test]
     fn test_time_bucket_from_dates() {
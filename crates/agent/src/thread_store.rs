@@ -33,7 +33,8 @@ use util::ResultExt as _;
 
 use crate::context_server_tool::ContextServerTool;
 use crate::thread::{
-    DetailedSummaryState, ExceededWindowError, MessageId, ProjectSnapshot, Thread, ThreadId,
+    DetailedSummaryState, ExceededWindowError, MessageId, ProjectSnapshot, Thread, ThreadFork,
+    ThreadId,
 };
 
 const RULES_FILE_NAMES: [&'static str; 6] = [
@@ -405,6 +406,54 @@ impl ThreadStore {
         })
     }
 
+    /// Creates a thread that branches off `thread` at `message_id`, keeping the messages, context,
+    /// tool results and checkpoints up to and including that message. The original thread is
+    /// left untouched.
+    pub fn fork_thread(
+        &mut self,
+        thread: &Entity<Thread>,
+        message_id: MessageId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Thread>>> {
+        let (thread_id, serialized_thread, checkpoints) = thread.update(cx, |thread, cx| {
+            (
+                thread.id().clone(),
+                thread.serialize(cx),
+                thread.checkpoints_through(message_id),
+            )
+        });
+
+        cx.spawn(async move |this, cx| {
+            let mut serialized_thread = serialized_thread.await?;
+            let message_ix = serialized_thread
+                .messages
+                .iter()
+                .position(|message| message.id == message_id)
+                .with_context(|| format!("no message found with ID: {message_id:?}"))?;
+            serialized_thread.messages.truncate(message_ix + 1);
+            serialized_thread
+                .request_token_usage
+                .truncate(message_ix + 1);
+            serialized_thread.updated_at = Utc::now();
+            serialized_thread.detailed_summary_state = DetailedSummaryState::default();
+            serialized_thread.exceeded_window_error = None;
+            serialized_thread.forked_from = Some(ThreadFork {
+                thread_id,
+                message_id,
+            });
+
+            let thread = this.update(cx, |this, cx| {
+                let thread = this.create_thread_from_serialized(serialized_thread, cx);
+                thread.update(cx, |thread, cx| thread.insert_checkpoints(checkpoints, cx));
+                thread
+            })?;
+            this.update(cx, |this, cx| this.save_thread(&thread, cx))?
+                .await?;
+
+            Ok(thread)
+        })
+    }
+
     pub fn open_thread(
         &self,
         id: &ThreadId,
@@ -645,6 +694,7 @@ impl ThreadStore {
                     _ => {}
                 }
             }
+            project::context_server_store::Event::ElicitationRequested(_) => {}
         }
     }
 }
@@ -654,6 +704,7 @@ pub struct SerializedThreadMetadata {
     pub id: ThreadId,
     pub summary: SharedString,
     pub updated_at: DateTime<Utc>,
+    pub forked_from: Option<ThreadFork>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -676,6 +727,8 @@ pub struct SerializedThread {
     pub model: Option<SerializedLanguageModel>,
     #[serde(default)]
     pub completion_mode: Option<CompletionMode>,
+    #[serde(default)]
+    pub forked_from: Option<ThreadFork>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -815,6 +868,7 @@ impl LegacySerializedThread {
             exceeded_window_error: None,
             model: None,
             completion_mode: None,
+            forked_from: None,
         }
     }
 }
@@ -939,6 +993,7 @@ impl ThreadsDatabase {
                     id: key,
                     summary: value.summary,
                     updated_at: value.updated_at,
+                    forked_from: value.forked_from,
                 });
             }
 
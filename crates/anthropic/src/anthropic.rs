@@ -4,7 +4,7 @@ use anyhow::{Context as _, Result, anyhow};
 use chrono::{DateTime, Utc};
 use futures::{AsyncBufReadExt, AsyncReadExt, StreamExt, io::BufReader, stream::BoxStream};
 use http_client::http::{HeaderMap, HeaderValue};
-use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest};
+use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest, StatusCode, StatusError};
 use serde::{Deserialize, Serialize};
 use strum::{EnumIter, EnumString};
 use thiserror::Error;
@@ -459,6 +459,16 @@ pub async fn stream_completion_with_rate_limit_info(
         let body_str =
             std::str::from_utf8(&body).context("failed to parse response body as UTF-8")?;
 
+        // Rate limits and overloads are transient, so they keep the delay to wait before retrying.
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            return Err(AnthropicError::Other(anyhow!(StatusError::new(
+                status,
+                response.headers(),
+                format!("Failed to connect to API: {status} {body_str}"),
+            ))));
+        }
+
         match serde_json::from_str::<Event>(body_str) {
             Ok(Event::Error { error }) => Err(AnthropicError::ApiError(error)),
             Ok(_) => Err(AnthropicError::Other(anyhow!(
@@ -2268,6 +2268,9 @@ impl AssistantContext {
             stop: Vec::new(),
             temperature: model
                 .and_then(|model| AssistantSettings::temperature_for_model(model, cx)),
+            max_tokens: None,
+            response_format: None,
+            reasoning: None,
         };
         for message in self.messages(cx) {
             if message.status != MessageStatus::Done {
@@ -882,6 +882,7 @@ impl ContextStore {
                     _ => {}
                 }
             }
+            project::context_server_store::Event::ElicitationRequested(_) => {}
         }
     }
 }
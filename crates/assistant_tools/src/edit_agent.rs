@@ -580,6 +580,9 @@ impl EditAgent {
             tools,
             stop: Vec::new(),
             temperature: None,
+            max_tokens: None,
+            response_format: None,
+            reasoning: None,
         };
 
         Ok(self.model.stream_completion_text(request, cx).await?.stream)
@@ -141,7 +141,7 @@ impl Tool for TerminalTool {
         } else {
             format!("({}) </dev/null", input.command)
         };
-        let args = vec!["-c".into(), command];
+        let args = vec!["-c".into(), command.clone()];
 
         let cwd = working_dir.clone();
         let env = match &working_dir {
@@ -207,6 +207,10 @@ impl Tool for TerminalTool {
             let project = project.downgrade();
             async move |cx| {
                 let program = program.await;
+                let args = match command_with_output_marks(&program, &command) {
+                    Some(command) => vec!["-c".into(), command],
+                    None => args,
+                };
                 let env = env.await;
                 let terminal = project
                     .update(cx, |project, cx| {
@@ -276,7 +280,12 @@ impl Tool for TerminalTool {
                     .update(cx, |terminal, cx| terminal.wait_for_completed_task(cx))?
                     .await;
                 let (content, content_line_count) = terminal.update(cx, |terminal, _| {
-                    (terminal.get_content(), terminal.total_lines())
+                    (
+                        terminal
+                            .last_command_output()
+                            .unwrap_or_else(|| terminal.get_content()),
+                        terminal.total_lines(),
+                    )
                 })?;
 
                 let previous_len = content.len();
@@ -307,6 +316,20 @@ impl Tool for TerminalTool {
     }
 }
 
+/// Wraps the command to mark where its output starts and ends, so that it can be read apart from the rest of the terminal content.
+///
+/// The wrapper uses the POSIX shell syntax, so the command is left as it is for other shells.
+fn command_with_output_marks(shell: &str, command: &str) -> Option<String> {
+    let shell_name = Path::new(shell).file_name()?.to_str()?;
+    if !matches!(shell_name, "sh" | "bash" | "zsh" | "dash" | "ksh") {
+        return None;
+    }
+    // `status` is read-only in zsh, so the exit status is kept in a variable of our own.
+    Some(format!(
+        "printf '\\033]133;C\\007'; {command}; __zed_status=$?; printf '\\033]133;D;%d\\007' \"$__zed_status\"; exit \"$__zed_status\""
+    ))
+}
+
 fn process_content(
     content: &str,
     command: &str,
@@ -846,4 +869,15 @@ mod tests {
         })
         .await;
     }
+
+    #[test]
+    fn test_command_with_output_marks() {
+        for shell in ["bash", "/bin/sh", "/usr/bin/zsh"] {
+            let command = command_with_output_marks(shell, "(ls) </dev/null").unwrap();
+            assert!(command.contains("(ls) </dev/null; __zed_status=$?;"));
+            assert!(!command.contains("\"$status\""));
+        }
+        assert_eq!(command_with_output_marks("/usr/bin/fish", "ls"), None);
+        assert_eq!(command_with_output_marks("powershell.exe", "ls"), None);
+    }
 }
@@ -18,7 +18,7 @@ pub use bedrock::operation::converse_stream::ConverseStreamInput as BedrockStrea
 pub use bedrock::types::{
     ContentBlock as BedrockRequestContent, ConversationRole as BedrockRole,
     ConverseOutput as BedrockResponse, ConverseStreamOutput as BedrockStreamingResponse,
-    ImageBlock as BedrockImageBlock, Message as BedrockMessage,
+    ImageBlock as BedrockImageBlock, InferenceConfiguration, Message as BedrockMessage,
     ReasoningContentBlock as BedrockThinkingBlock, ReasoningTextBlock as BedrockThinkingTextBlock,
     ResponseStream as BedrockResponseStream, ToolResultBlock as BedrockToolResultBlock,
     ToolResultContentBlock as BedrockToolResultContentBlock,
@@ -40,7 +40,12 @@ pub async fn stream_completion(
         .spawn(async move {
             let mut response = bedrock::Client::converse_stream(&client)
                 .model_id(request.model.clone())
-                .set_messages(request.messages.into());
+                .set_messages(request.messages.into())
+                .inference_config(
+                    InferenceConfiguration::builder()
+                        .max_tokens(request.max_tokens.min(i32::MAX as u32) as i32)
+                        .build(),
+                );
 
             if let Some(Thinking::Enabled {
                 budget_tokens: Some(budget_tokens),
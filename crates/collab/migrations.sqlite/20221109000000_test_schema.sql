@@ -118,6 +118,7 @@ CREATE TABLE "project_repositories" (
     "current_merge_conflicts" VARCHAR,
     "branch_summary" VARCHAR,
     "head_commit_details" VARCHAR,
+    "is_jj_colocated" BOOL NOT NULL DEFAULT FALSE,
     PRIMARY KEY (project_id, id)
 );
 
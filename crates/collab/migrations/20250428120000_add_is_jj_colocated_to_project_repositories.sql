@@ -0,0 +1,2 @@
+alter table project_repositories
+    add column is_jj_colocated bool not null default false;
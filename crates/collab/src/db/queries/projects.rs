@@ -352,6 +352,7 @@ impl Database {
                                 abs_path: ActiveValue::set(String::new()),
                                 entry_ids: ActiveValue::set("[]".into()),
                                 head_commit_details: ActiveValue::set(None),
+                                is_jj_colocated: ActiveValue::set(false),
                             }
                         }),
                     )
@@ -500,6 +501,7 @@ impl Database {
                 current_merge_conflicts: ActiveValue::Set(Some(
                     serde_json::to_string(&update.current_merge_conflicts).unwrap(),
                 )),
+                is_jj_colocated: ActiveValue::Set(update.is_jj_colocated),
             })
             .on_conflict(
                 OnConflict::columns([
@@ -513,6 +515,7 @@ impl Database {
                     project_repository::Column::AbsPath,
                     project_repository::Column::CurrentMergeConflicts,
                     project_repository::Column::HeadCommitDetails,
+                    project_repository::Column::IsJjColocated,
                 ])
                 .to_owned(),
             )
@@ -966,6 +969,7 @@ impl Database {
                         head_commit_details,
                         scan_id: db_repository_entry.scan_id as u64,
                         is_last_update: true,
+                        is_jj_colocated: db_repository_entry.is_jj_colocated,
                     });
                 }
             }
@@ -791,6 +791,7 @@ impl Database {
                             abs_path: db_repository.abs_path,
                             scan_id: db_repository.scan_id as u64,
                             is_last_update: true,
+                            is_jj_colocated: db_repository.is_jj_colocated,
                         });
                     }
                 }
@@ -20,6 +20,7 @@ pub struct Model {
     pub branch_summary: Option<String>,
     // A JSON object representing the current Head commit values
     pub head_commit_details: Option<String>,
+    pub is_jj_colocated: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -285,6 +285,15 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferById>)
             .add_request_handler(forward_read_only_project_request::<proto::SynchronizeBuffers>)
             .add_request_handler(forward_read_only_project_request::<proto::InlayHints>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSemanticTokens>)
+            .add_request_handler(forward_read_only_project_request::<proto::PrepareCallHierarchy>)
+            .add_request_handler(forward_read_only_project_request::<proto::PrepareTypeHierarchy>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetIncomingCalls>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetOutgoingCalls>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSupertypes>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSubtypes>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetDocumentLinks>)
+            .add_request_handler(forward_read_only_project_request::<proto::ResolveDocumentLink>)
             .add_request_handler(forward_read_only_project_request::<proto::ResolveInlayHint>)
             .add_request_handler(forward_mutating_project_request::<proto::GetCodeLens>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferByPath>)
@@ -342,6 +351,9 @@ impl Server {
             .add_request_handler(update_buffer)
             .add_message_handler(broadcast_project_message_from_host::<proto::RefreshInlayHints>)
             .add_message_handler(broadcast_project_message_from_host::<proto::RefreshCodeLens>)
+            .add_message_handler(
+                broadcast_project_message_from_host::<proto::RefreshSemanticTokens>,
+            )
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateBufferFile>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
@@ -397,6 +409,10 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::LoadCommitDiff>)
             .add_request_handler(forward_read_only_project_request::<proto::GitReset>)
             .add_request_handler(forward_read_only_project_request::<proto::GitCheckoutFiles>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashPaths>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitGetStashEntries>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashApply>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashDrop>)
             .add_request_handler(forward_mutating_project_request::<proto::SetIndexText>)
             .add_request_handler(forward_mutating_project_request::<proto::ToggleBreakpoint>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BreakpointsForFile>)
@@ -2,6 +2,7 @@ use anyhow::{Context as _, Result, anyhow};
 use collections::HashMap;
 use futures::{FutureExt, StreamExt, channel::oneshot, select};
 use gpui::{AppContext as _, AsyncApp, BackgroundExecutor, Task};
+use http_client::HttpClient;
 use parking_lot::Mutex;
 use postage::barrier;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
@@ -18,7 +19,8 @@ use std::{
 };
 use util::TryFutureExt;
 
-use crate::transport::{StdioTransport, Transport};
+use crate::transport::{SseTransport, StdioTransport, StreamableHttpTransport, Transport};
+use crate::{ContextServerEndpoint, ContextServerHttpTransport};
 
 const JSON_RPC_VERSION: &str = "2.0";
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
@@ -32,6 +34,7 @@ pub const INTERNAL_ERROR: i32 = -32603;
 
 type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
 type NotificationHandler = Box<dyn Send + FnMut(Value, AsyncApp)>;
+type RequestHandler = Box<dyn Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>>;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -46,6 +49,7 @@ pub(crate) struct Client {
     outbound_tx: channel::Sender<String>,
     name: Arc<str>,
     notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
@@ -78,6 +82,16 @@ struct Request<'a, T> {
     params: T,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct AnyRequest<'a> {
+    #[allow(dead_code)]
+    jsonrpc: &'a str,
+    id: RequestId,
+    method: String,
+    #[serde(default)]
+    params: Option<Value>,
+}
+
 #[derive(Serialize, Deserialize)]
 struct AnyResponse<'a> {
     jsonrpc: &'a str,
@@ -161,6 +175,31 @@ impl Client {
         Self::new(server_id, server_name.into(), transport, cx)
     }
 
+    /// Creates a new Client instance for a context server running as an HTTP service.
+    pub fn http(
+        server_id: ContextServerId,
+        endpoint: ContextServerEndpoint,
+        http_client: Arc<dyn HttpClient>,
+        cx: AsyncApp,
+    ) -> Result<Self> {
+        log::info!(
+            "connecting to context server (url={}, transport={:?})",
+            endpoint.url,
+            endpoint.transport
+        );
+
+        let server_name = endpoint.url.host_str().unwrap_or_default().to_string();
+        let transport: Arc<dyn Transport> = match endpoint.transport {
+            ContextServerHttpTransport::StreamableHttp => {
+                Arc::new(StreamableHttpTransport::new(endpoint, http_client, &cx))
+            }
+            ContextServerHttpTransport::Sse => {
+                Arc::new(SseTransport::new(endpoint, http_client, &cx))
+            }
+        };
+        Self::new(server_id, server_name.into(), transport, cx)
+    }
+
     /// Creates a new Client instance for a context server.
     pub fn new(
         server_id: ContextServerId,
@@ -173,17 +212,27 @@ impl Client {
 
         let notification_handlers =
             Arc::new(Mutex::new(HashMap::<_, NotificationHandler>::default()));
+        let request_handlers = Arc::new(Mutex::new(HashMap::<_, RequestHandler>::default()));
         let response_handlers =
             Arc::new(Mutex::new(Some(HashMap::<_, ResponseHandler>::default())));
 
         let receive_input_task = cx.spawn({
             let notification_handlers = notification_handlers.clone();
+            let request_handlers = request_handlers.clone();
             let response_handlers = response_handlers.clone();
             let transport = transport.clone();
+            let outbound_tx = outbound_tx.clone();
             async move |cx| {
-                Self::handle_input(transport, notification_handlers, response_handlers, cx)
-                    .log_err()
-                    .await
+                Self::handle_input(
+                    transport,
+                    notification_handlers,
+                    request_handlers,
+                    response_handlers,
+                    outbound_tx,
+                    cx,
+                )
+                .log_err()
+                .await
             }
         });
         let receive_err_task = cx.spawn({
@@ -209,6 +258,7 @@ impl Client {
         Ok(Self {
             server_id,
             notification_handlers,
+            request_handlers,
             response_handlers,
             name: server_name,
             next_id: Default::default(),
@@ -223,19 +273,56 @@ impl Client {
     /// Handles input from the server's stdout.
     ///
     /// This function continuously reads lines from the provided stdout stream,
-    /// parses them as JSON-RPC responses or notifications, and dispatches them
-    /// to the appropriate handlers. It processes both responses (which are matched
-    /// to pending requests) and notifications (which trigger registered handlers).
+    /// parses them as JSON-RPC requests, responses or notifications, and dispatches them
+    /// to the appropriate handlers. It processes requests (which are answered by registered
+    /// handlers), responses (which are matched to pending requests) and notifications
+    /// (which trigger registered handlers).
     async fn handle_input(
         transport: Arc<dyn Transport>,
         notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+        request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
         cx: &mut AsyncApp,
     ) -> anyhow::Result<()> {
         let mut receiver = transport.receive();
 
         while let Some(message) = receiver.next().await {
-            if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
+            if let Ok(request) = serde_json::from_str::<AnyRequest>(&message) {
+                let response = request_handlers
+                    .lock()
+                    .get_mut(request.method.as_str())
+                    .map(|handler| handler(request.params.unwrap_or(Value::Null), cx.clone()));
+                let id = request.id;
+                let method = request.method;
+                let outbound_tx = outbound_tx.clone();
+                cx.background_spawn(async move {
+                    let response = match response {
+                        Some(response) => match response.await {
+                            Ok(result) => serde_json::json!({
+                                "jsonrpc": JSON_RPC_VERSION,
+                                "id": id,
+                                "result": result,
+                            }),
+                            Err(error) => serde_json::json!({
+                                "jsonrpc": JSON_RPC_VERSION,
+                                "id": id,
+                                "error": {"code": INTERNAL_ERROR, "message": error.to_string()},
+                            }),
+                        },
+                        None => serde_json::json!({
+                            "jsonrpc": JSON_RPC_VERSION,
+                            "id": id,
+                            "error": {
+                                "code": METHOD_NOT_FOUND,
+                                "message": format!("unhandled method {method}"),
+                            },
+                        }),
+                    };
+                    outbound_tx.send(response.to_string()).await.ok();
+                })
+                .detach();
+            } else if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
                 if let Some(handlers) = response_handlers.lock().as_mut() {
                     if let Some(handler) = handlers.remove(&response.id) {
                         handler(Ok(message.to_string()));
@@ -376,6 +463,15 @@ impl Client {
             .lock()
             .insert(method, Box::new(f));
     }
+
+    /// Registers a handler for requests sent by the context server. Requests without a
+    /// handler are answered with a "method not found" error.
+    pub fn on_request<F>(&self, method: &'static str, f: F)
+    where
+        F: 'static + Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>,
+    {
+        self.request_handlers.lock().insert(method, Box::new(f));
+    }
 }
 
 impl fmt::Display for ContextServerId {
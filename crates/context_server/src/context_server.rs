@@ -10,10 +10,11 @@ use std::sync::Arc;
 use anyhow::Result;
 use client::Client;
 use collections::HashMap;
-use gpui::AsyncApp;
+use gpui::{App, AsyncApp, Task};
 use parking_lot::RwLock;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use url::Url;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ContextServerId(pub Arc<str>);
@@ -31,15 +32,39 @@ pub struct ContextServerCommand {
     pub env: Option<HashMap<String, String>>,
 }
 
+/// The HTTP-based transports a remote context server can be reached with.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextServerHttpTransport {
+    /// The Streamable HTTP transport, where every message is POSTed to a single endpoint.
+    #[default]
+    StreamableHttp,
+    /// The legacy HTTP+SSE transport, where the server pushes messages over an event stream.
+    Sse,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ContextServerEndpoint {
+    pub url: Url,
+    pub headers: HashMap<String, String>,
+    pub transport: ContextServerHttpTransport,
+}
+
 enum ContextServerTransport {
     Stdio(ContextServerCommand),
+    Http(ContextServerEndpoint),
     Custom(Arc<dyn crate::transport::Transport>),
 }
 
+/// Asks the user for the information requested by a context server, see [`ContextServer::set_elicitation_handler`].
+pub type ElicitationHandler =
+    Arc<dyn Fn(types::ElicitParams, &mut App) -> Task<Result<types::ElicitResponse>> + Send + Sync>;
+
 pub struct ContextServer {
     id: ContextServerId,
     client: RwLock<Option<Arc<crate::protocol::InitializedContextServerProtocol>>>,
     configuration: ContextServerTransport,
+    elicitation_handler: RwLock<Option<ElicitationHandler>>,
 }
 
 impl ContextServer {
@@ -47,14 +72,25 @@ impl ContextServer {
         Self {
             id,
             client: RwLock::new(None),
+            elicitation_handler: RwLock::new(None),
             configuration: ContextServerTransport::Stdio(command),
         }
     }
 
+    pub fn http(id: ContextServerId, endpoint: ContextServerEndpoint) -> Self {
+        Self {
+            id,
+            client: RwLock::new(None),
+            elicitation_handler: RwLock::new(None),
+            configuration: ContextServerTransport::Http(endpoint),
+        }
+    }
+
     pub fn new(id: ContextServerId, transport: Arc<dyn crate::transport::Transport>) -> Self {
         Self {
             id,
             client: RwLock::new(None),
+            elicitation_handler: RwLock::new(None),
             configuration: ContextServerTransport::Custom(transport),
         }
     }
@@ -67,6 +103,12 @@ impl ContextServer {
         self.client.read().clone()
     }
 
+    /// Sets the handler answering the server's `elicitation/create` requests.
+    /// The capability is advertised to the server when it is started after this.
+    pub fn set_elicitation_handler(&self, handler: ElicitationHandler) {
+        *self.elicitation_handler.write() = Some(handler);
+    }
+
     pub async fn start(self: Arc<Self>, cx: &AsyncApp) -> Result<()> {
         let client = match &self.configuration {
             ContextServerTransport::Stdio(command) => Client::stdio(
@@ -78,6 +120,12 @@ impl ContextServer {
                 },
                 cx.clone(),
             )?,
+            ContextServerTransport::Http(endpoint) => Client::http(
+                client::ContextServerId(self.id.0.clone()),
+                endpoint.clone(),
+                cx.update(|cx| cx.http_client())?,
+                cx.clone(),
+            )?,
             ContextServerTransport::Custom(transport) => Client::new(
                 client::ContextServerId(self.id.0.clone()),
                 self.id().0,
@@ -90,9 +138,13 @@ impl ContextServer {
 
     async fn initialize(&self, client: Client) -> Result<()> {
         log::info!("starting context server {}", self.id);
-        let protocol = crate::protocol::ModelContextProtocol::new(client);
+        let protocol = crate::protocol::ModelContextProtocol::new(
+            client,
+            self.elicitation_handler.read().clone(),
+        );
         let client_info = types::Implementation {
             name: "Zed".to_string(),
+            title: None,
             version: env!("CARGO_PKG_VERSION").to_string(),
         };
         let initialized_protocol = protocol.initialize(client_info).await?;
@@ -7,35 +7,57 @@
 
 use anyhow::Result;
 use collections::HashMap;
+use gpui::Task;
 
+use crate::ElicitationHandler;
 use crate::client::Client;
 use crate::types;
 
 pub struct ModelContextProtocol {
     inner: Client,
+    supports_elicitation: bool,
 }
 
 impl ModelContextProtocol {
-    pub(crate) fn new(inner: Client) -> Self {
-        Self { inner }
-    }
-
-    fn supported_protocols() -> Vec<types::ProtocolVersion> {
-        vec![types::ProtocolVersion(
-            types::LATEST_PROTOCOL_VERSION.to_string(),
-        )]
+    pub(crate) fn new(inner: Client, elicitation_handler: Option<ElicitationHandler>) -> Self {
+        inner.on_request(types::RequestType::Ping.as_str(), |_, _| {
+            Task::ready(Ok(serde_json::json!({})))
+        });
+        let supports_elicitation = elicitation_handler.is_some();
+        if let Some(handler) = elicitation_handler {
+            inner.on_request(
+                types::RequestType::Elicitation.as_str(),
+                move |params, cx| {
+                    let handler = handler.clone();
+                    cx.spawn(async move |cx| {
+                        let params: types::ElicitParams = serde_json::from_value(params)?;
+                        let response = cx.update(|cx| handler(params, cx))?.await?;
+                        Ok(serde_json::to_value(response)?)
+                    })
+                },
+            );
+        }
+        Self {
+            inner,
+            supports_elicitation,
+        }
     }
 
+    /// Initializes the connection, negotiating the protocol revision to use.
+    ///
+    /// We ask for the latest revision we know; servers that don't support it answer
+    /// with a revision of their own, which is accepted if it is one we can still talk.
     pub async fn initialize(
         self,
         client_info: types::Implementation,
     ) -> Result<InitializedContextServerProtocol> {
         let params = types::InitializeParams {
-            protocol_version: types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
+            protocol_version: types::ProtocolVersion::latest(),
             capabilities: types::ClientCapabilities {
                 experimental: None,
                 sampling: None,
                 roots: None,
+                elicitation: self.supports_elicitation.then(|| serde_json::json!({})),
             },
             meta: None,
             client_info,
@@ -47,12 +69,16 @@ impl ModelContextProtocol {
             .await?;
 
         anyhow::ensure!(
-            Self::supported_protocols().contains(&response.protocol_version),
+            response.protocol_version.is_supported(),
             "Unsupported protocol version: {:?}",
             response.protocol_version
         );
 
-        log::trace!("mcp server info {:?}", response.server_info);
+        log::trace!(
+            "mcp server info {:?}, protocol version {:?}",
+            response.server_info,
+            response.protocol_version
+        );
 
         self.inner.notify(
             types::NotificationType::Initialized.as_str(),
@@ -83,6 +109,11 @@ pub enum ServerCapability {
 }
 
 impl InitializedContextServerProtocol {
+    /// The protocol revision negotiated with the server.
+    pub fn protocol_version(&self) -> &types::ProtocolVersion {
+        &self.initialize.protocol_version
+    }
+
     /// Check if the server supports a specific capability
     pub fn capable(&self, capability: ServerCapability) -> bool {
         match capability {
@@ -228,3 +259,193 @@ impl InitializedContextServerProtocol {
         self.inner.request(method, params).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ContextServerId;
+    use crate::transport::Transport;
+    use futures::{Stream, StreamExt as _};
+    use gpui::TestAppContext;
+    use serde_json::{Value, json};
+    use smol::channel;
+    use std::pin::Pin;
+    use std::sync::Arc;
+
+    struct FakeTransport {
+        server_protocol_version: &'static str,
+        tx: channel::Sender<String>,
+        rx: channel::Receiver<String>,
+    }
+
+    #[async_trait::async_trait]
+    impl Transport for FakeTransport {
+        async fn send(&self, message: String) -> Result<()> {
+            let message: Value = serde_json::from_str(&message)?;
+            if message["method"] == "initialize" {
+                let response = json!({
+                    "jsonrpc": "2.0",
+                    "id": message["id"],
+                    "result": {
+                        "protocolVersion": self.server_protocol_version,
+                        "capabilities": {},
+                        "serverInfo": {"name": "fake", "version": "1.0.0"},
+                    },
+                });
+                self.tx.send(response.to_string()).await?;
+            }
+            Ok(())
+        }
+
+        fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+            Box::pin(self.rx.clone())
+        }
+
+        fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+            Box::pin(futures::stream::empty())
+        }
+    }
+
+    async fn initialize(
+        server_protocol_version: &'static str,
+        cx: &mut TestAppContext,
+    ) -> Result<InitializedContextServerProtocol> {
+        let (tx, rx) = channel::unbounded();
+        let transport = Arc::new(FakeTransport {
+            server_protocol_version,
+            tx,
+            rx,
+        });
+        let client = Client::new(
+            ContextServerId("fake".into()),
+            "fake".into(),
+            transport,
+            cx.to_async(),
+        )?;
+        ModelContextProtocol::new(client, None)
+            .initialize(types::Implementation {
+                name: "Zed".into(),
+                title: None,
+                version: "1.0.0".into(),
+            })
+            .await
+    }
+
+    #[gpui::test]
+    async fn test_protocol_version_negotiation(cx: &mut TestAppContext) {
+        let protocol = initialize(types::LATEST_PROTOCOL_VERSION, cx)
+            .await
+            .unwrap();
+        assert_eq!(
+            protocol.protocol_version(),
+            &types::ProtocolVersion::latest()
+        );
+
+        let protocol = initialize("2024-11-05", cx).await.unwrap();
+        assert_eq!(protocol.protocol_version().0, "2024-11-05");
+
+        assert!(initialize("2023-01-01", cx).await.is_err());
+    }
+
+    /// Passes the server's messages to the client, and records the client's messages.
+    struct RecordingTransport {
+        rx: channel::Receiver<String>,
+        outbound_tx: channel::Sender<String>,
+    }
+
+    #[async_trait::async_trait]
+    impl Transport for RecordingTransport {
+        async fn send(&self, message: String) -> Result<()> {
+            Ok(self.outbound_tx.send(message).await?)
+        }
+
+        fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+            Box::pin(self.rx.clone())
+        }
+
+        fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+            Box::pin(futures::stream::empty())
+        }
+    }
+
+    fn recording_client(
+        cx: &mut TestAppContext,
+    ) -> (
+        Client,
+        channel::Sender<String>,
+        Pin<Box<channel::Receiver<String>>>,
+    ) {
+        let (tx, rx) = channel::unbounded();
+        let (outbound_tx, outbound_rx) = channel::unbounded();
+        let client = Client::new(
+            ContextServerId("fake".into()),
+            "fake".into(),
+            Arc::new(RecordingTransport { rx, outbound_tx }),
+            cx.to_async(),
+        )
+        .unwrap();
+        (client, tx, Box::pin(outbound_rx))
+    }
+
+    #[gpui::test]
+    async fn test_unhandled_server_requests(cx: &mut TestAppContext) {
+        let (client, tx, mut outbound_rx) = recording_client(cx);
+        let _protocol = ModelContextProtocol::new(client, None);
+
+        tx.send(json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}).to_string())
+            .await
+            .unwrap();
+        let response: Value = serde_json::from_str(&outbound_rx.next().await.unwrap()).unwrap();
+        assert_eq!(response, json!({"jsonrpc": "2.0", "id": 1, "result": {}}));
+
+        tx.send(
+            json!({"jsonrpc": "2.0", "id": "e1", "method": "elicitation/create", "params": {}})
+                .to_string(),
+        )
+        .await
+        .unwrap();
+        let response: Value = serde_json::from_str(&outbound_rx.next().await.unwrap()).unwrap();
+        assert_eq!(response["id"], "e1");
+        assert_eq!(response["error"]["code"], crate::client::METHOD_NOT_FOUND);
+    }
+
+    #[gpui::test]
+    async fn test_elicitation_requests(cx: &mut TestAppContext) {
+        let (client, tx, mut outbound_rx) = recording_client(cx);
+        let handler: ElicitationHandler = Arc::new(|params: types::ElicitParams, _| {
+            assert_eq!(params.message, "What is your name?");
+            Task::ready(Ok(types::ElicitResponse {
+                action: types::ElicitAction::Accept,
+                content: Some([("name".to_string(), json!("Zed"))].into_iter().collect()),
+            }))
+        });
+        let _protocol = ModelContextProtocol::new(client, Some(handler));
+
+        tx.send(
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "elicitation/create",
+                "params": {
+                    "message": "What is your name?",
+                    "requestedSchema": {
+                        "type": "object",
+                        "properties": {"name": {"type": "string"}},
+                    },
+                },
+            })
+            .to_string(),
+        )
+        .await
+        .unwrap();
+        let response: Value = serde_json::from_str(&outbound_rx.next().await.unwrap()).unwrap();
+        assert_eq!(
+            response,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {"action": "accept", "content": {"name": "Zed"}},
+            })
+        );
+    }
+}
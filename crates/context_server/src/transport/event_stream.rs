@@ -0,0 +1,121 @@
+use std::pin::Pin;
+
+use futures::io::BufReader;
+use futures::{AsyncBufReadExt as _, AsyncRead, Stream, StreamExt as _};
+
+/// A single event received over a `text/event-stream` response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SseEvent {
+    pub event: String,
+    pub data: String,
+}
+
+/// Incrementally assembles server-sent events from the lines of an event stream.
+///
+/// See https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation
+#[derive(Default)]
+pub(crate) struct SseParser {
+    event: Option<String>,
+    data: Option<String>,
+}
+
+impl SseParser {
+    /// Feeds a single line (without its line terminator) into the parser, returning
+    /// an event once a blank line terminates it.
+    pub fn push_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            let event = self.event.take();
+            let data = self.data.take()?;
+            return Some(SseEvent {
+                event: event.unwrap_or_else(|| "message".to_string()),
+                data,
+            });
+        }
+
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => match &mut self.data {
+                Some(data) => {
+                    data.push('\n');
+                    data.push_str(value);
+                }
+                None => self.data = Some(value.to_string()),
+            },
+            _ => {}
+        }
+        None
+    }
+}
+
+/// Reads server-sent events from the given body until it is exhausted.
+pub(crate) fn sse_events<R>(body: R) -> Pin<Box<dyn Stream<Item = SseEvent> + Send>>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    let mut parser = SseParser::default();
+    BufReader::new(body)
+        .lines()
+        .filter_map(move |line| {
+            let event = match line {
+                Ok(line) => parser.push_line(&line),
+                Err(error) => {
+                    log::warn!("failed to read context server event stream: {error}");
+                    None
+                }
+            };
+            futures::future::ready(event)
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_parser() {
+        let mut parser = SseParser::default();
+        let mut events = Vec::new();
+        for line in [
+            ": keep-alive",
+            "event: endpoint",
+            "data: /messages?session_id=1",
+            "",
+            "data: {\"jsonrpc\":",
+            "data:\"2.0\"}",
+            "",
+            "",
+            "id: 3",
+            "data",
+            "",
+        ] {
+            events.extend(parser.push_line(line));
+        }
+
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: "endpoint".into(),
+                    data: "/messages?session_id=1".into(),
+                },
+                SseEvent {
+                    event: "message".into(),
+                    data: "{\"jsonrpc\":\n\"2.0\"}".into(),
+                },
+                SseEvent {
+                    event: "message".into(),
+                    data: "".into(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,341 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use futures::{AsyncReadExt as _, Stream, StreamExt as _};
+use gpui::{AsyncApp, BackgroundExecutor};
+use http_client::{AsyncBody, HttpClient, Method, Request, Response, Url, http};
+use parking_lot::Mutex;
+use serde_json::Value;
+use smol::channel;
+use util::ResultExt as _;
+
+use crate::ContextServerEndpoint;
+use crate::client::INTERNAL_ERROR;
+use crate::transport::Transport;
+use crate::transport::event_stream::sse_events;
+
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+const EVENT_STREAM_CONTENT_TYPE: &str = "text/event-stream";
+
+/// A transport for context servers using the Streamable HTTP transport.
+///
+/// Every outgoing message is POSTed to the server's endpoint, which answers
+/// either with a JSON body or with an event stream carrying the responses.
+pub struct StreamableHttpTransport {
+    endpoint: ContextServerEndpoint,
+    http_client: Arc<dyn HttpClient>,
+    executor: BackgroundExecutor,
+    session_id: Arc<Mutex<Option<String>>>,
+    inbound_tx: channel::Sender<String>,
+    inbound_rx: channel::Receiver<String>,
+}
+
+impl StreamableHttpTransport {
+    pub fn new(
+        endpoint: ContextServerEndpoint,
+        http_client: Arc<dyn HttpClient>,
+        cx: &AsyncApp,
+    ) -> Self {
+        let (inbound_tx, inbound_rx) = channel::unbounded::<String>();
+        Self {
+            endpoint,
+            http_client,
+            executor: cx.background_executor().clone(),
+            session_id: Arc::new(Mutex::new(None)),
+            inbound_tx,
+            inbound_rx,
+        }
+    }
+
+    async fn post(
+        http_client: Arc<dyn HttpClient>,
+        request: Request<AsyncBody>,
+        session_id: Arc<Mutex<Option<String>>>,
+        inbound_tx: channel::Sender<String>,
+    ) -> Result<()> {
+        let mut response = http_client.send(request).await?;
+        ensure_success(&mut response).await?;
+
+        if let Some(id) = response
+            .headers()
+            .get(SESSION_ID_HEADER)
+            .and_then(|id| id.to_str().ok())
+        {
+            session_id.lock().replace(id.to_string());
+        }
+
+        if is_event_stream(&response) {
+            let mut events = sse_events(response.into_body());
+            while let Some(event) = events.next().await {
+                if event.event == "message" && inbound_tx.send(event.data).await.is_err() {
+                    break;
+                }
+            }
+        } else {
+            let mut body = String::new();
+            response.into_body().read_to_string(&mut body).await?;
+            for message in split_batch(&body)? {
+                inbound_tx.send(message).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Transport for StreamableHttpTransport {
+    async fn send(&self, message: String) -> Result<()> {
+        let mut request = build_request(Method::POST, &self.endpoint.url, &self.endpoint)
+            .header("Content-Type", "application/json")
+            .header(
+                "Accept",
+                format!("application/json, {EVENT_STREAM_CONTENT_TYPE}"),
+            );
+        if let Some(session_id) = self.session_id.lock().as_ref() {
+            request = request.header(SESSION_ID_HEADER, session_id.as_str());
+        }
+        let request = request.body(AsyncBody::from(message.clone()))?;
+
+        // Responses may stream for as long as the server needs to handle a request,
+        // so they are read in the background to keep other requests flowing.
+        let http_client = self.http_client.clone();
+        let session_id = self.session_id.clone();
+        let inbound_tx = self.inbound_tx.clone();
+        self.executor
+            .spawn(async move {
+                if let Err(error) =
+                    Self::post(http_client, request, session_id, inbound_tx.clone()).await
+                {
+                    log::error!("context server request failed: {error:#}");
+                    if let Some(response) = error_response(&message, &error) {
+                        inbound_tx.send(response).await.ok();
+                    }
+                }
+            })
+            .detach();
+
+        Ok(())
+    }
+
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.inbound_rx.clone())
+    }
+
+    fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(futures::stream::empty())
+    }
+}
+
+impl Drop for StreamableHttpTransport {
+    fn drop(&mut self) {
+        let Some(session_id) = self.session_id.lock().take() else {
+            return;
+        };
+        let request = build_request(Method::DELETE, &self.endpoint.url, &self.endpoint)
+            .header(SESSION_ID_HEADER, session_id)
+            .body(AsyncBody::empty());
+        if let Some(request) = request.log_err() {
+            let http_client = self.http_client.clone();
+            self.executor
+                .spawn(async move {
+                    http_client.send(request).await.log_err();
+                })
+                .detach();
+        }
+    }
+}
+
+/// Starts a request to the context server, including the user-configured headers.
+pub(crate) fn build_request(
+    method: Method,
+    url: &Url,
+    endpoint: &ContextServerEndpoint,
+) -> http::request::Builder {
+    let mut request = Request::builder().method(method).uri(url.as_str());
+    for (name, value) in &endpoint.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    request
+}
+
+/// Fails with the response body if the server did not accept the request.
+pub(crate) async fn ensure_success(response: &mut Response<AsyncBody>) -> Result<()> {
+    if response.status().is_success() {
+        return Ok(());
+    }
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await.ok();
+    anyhow::bail!(
+        "context server responded with status {}: {}",
+        response.status(),
+        body.trim()
+    );
+}
+
+pub(crate) fn is_event_stream(response: &Response<AsyncBody>) -> bool {
+    response
+        .headers()
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .map_or(false, |content_type| {
+            content_type.starts_with(EVENT_STREAM_CONTENT_TYPE)
+        })
+}
+
+/// Splits a JSON response body into individual JSON-RPC messages, unpacking batches.
+fn split_batch(body: &str) -> Result<Vec<String>> {
+    if body.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let value: Value =
+        serde_json::from_str(body).context("context server returned invalid JSON")?;
+    match value {
+        Value::Array(messages) => Ok(messages.iter().map(Value::to_string).collect()),
+        message => Ok(vec![message.to_string()]),
+    }
+}
+
+/// Builds a JSON-RPC error response for a request that could not be delivered,
+/// so the pending request fails immediately instead of timing out.
+pub(crate) fn error_response(message: &str, error: &anyhow::Error) -> Option<String> {
+    let message: Value = serde_json::from_str(message).ok()?;
+    message.get("method")?;
+    let id = message.get("id")?;
+    Some(
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {
+                "code": INTERNAL_ERROR,
+                "message": format!("{error:#}"),
+            }
+        })
+        .to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContextServerHttpTransport;
+    use collections::HashMap;
+    use gpui::TestAppContext;
+    use http_client::FakeHttpClient;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_streamable_http_transport(cx: &mut TestAppContext) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let requests = requests.clone();
+            move |mut request| {
+                let requests = requests.clone();
+                async move {
+                    let mut body = String::new();
+                    request.body_mut().read_to_string(&mut body).await?;
+                    let message: Value = serde_json::from_str(&body)?;
+                    requests.lock().push((
+                        request
+                            .headers()
+                            .get(SESSION_ID_HEADER)
+                            .map(|id| id.to_str().unwrap().to_string()),
+                        request
+                            .headers()
+                            .get("Authorization")
+                            .map(|value| value.to_str().unwrap().to_string()),
+                        message.clone(),
+                    ));
+
+                    let response = Response::builder();
+                    Ok(match message["method"].as_str() {
+                        Some("initialize") => response
+                            .header("Content-Type", "application/json")
+                            .header(SESSION_ID_HEADER, "session-1")
+                            .body(
+                                json!({"jsonrpc": "2.0", "id": message["id"], "result": {}})
+                                    .to_string()
+                                    .into(),
+                            )?,
+                        Some("tools/list") => response
+                            .header("Content-Type", EVENT_STREAM_CONTENT_TYPE)
+                            .body(
+                                format!(
+                                    "event: message\ndata: {}\n\n",
+                                    json!({"jsonrpc": "2.0", "id": message["id"], "result": {"tools": []}})
+                                )
+                                .into(),
+                            )?,
+                        Some("prompts/list") => response
+                            .status(500)
+                            .body("internal failure".into())?,
+                        _ => response.status(202).body(AsyncBody::empty())?,
+                    })
+                }
+            }
+        });
+
+        let endpoint = ContextServerEndpoint {
+            url: Url::parse("http://localhost:3000/mcp").unwrap(),
+            headers: HashMap::from_iter([("Authorization".into(), "Bearer token".into())]),
+            transport: ContextServerHttpTransport::StreamableHttp,
+        };
+        let transport = StreamableHttpTransport::new(endpoint, http_client, &cx.to_async());
+        let mut incoming = transport.receive();
+
+        transport
+            .send(json!({"jsonrpc": "2.0", "id": 0, "method": "initialize"}).to_string())
+            .await
+            .unwrap();
+        let response: Value = serde_json::from_str(&incoming.next().await.unwrap()).unwrap();
+        assert_eq!(response["id"], 0);
+
+        transport
+            .send(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}).to_string())
+            .await
+            .unwrap();
+        transport
+            .send(json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}).to_string())
+            .await
+            .unwrap();
+        let response: Value = serde_json::from_str(&incoming.next().await.unwrap()).unwrap();
+        assert_eq!(response["result"], json!({"tools": []}));
+
+        transport
+            .send(json!({"jsonrpc": "2.0", "id": 2, "method": "prompts/list"}).to_string())
+            .await
+            .unwrap();
+        let response: Value = serde_json::from_str(&incoming.next().await.unwrap()).unwrap();
+        assert_eq!(response["id"], 2);
+        assert_eq!(response["error"]["code"], INTERNAL_ERROR);
+
+        cx.run_until_parked();
+        let requests = requests.lock();
+        let mut requests = requests
+            .iter()
+            .map(|(session_id, authorization, message)| {
+                (
+                    message["method"].as_str().unwrap(),
+                    session_id.as_deref(),
+                    authorization.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        requests.sort();
+        assert_eq!(
+            requests,
+            vec![
+                ("initialize", None, Some("Bearer token")),
+                (
+                    "notifications/initialized",
+                    Some("session-1"),
+                    Some("Bearer token")
+                ),
+                ("prompts/list", Some("session-1"), Some("Bearer token")),
+                ("tools/list", Some("session-1"), Some("Bearer token")),
+            ]
+        );
+    }
+}
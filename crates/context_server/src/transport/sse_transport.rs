@@ -0,0 +1,215 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt as _};
+use gpui::{AsyncApp, Task};
+use http_client::{AsyncBody, HttpClient, Method, Url};
+use postage::{stream::Stream as _, watch};
+use smol::channel;
+
+use crate::ContextServerEndpoint;
+use crate::transport::Transport;
+use crate::transport::event_stream::sse_events;
+use crate::transport::http_transport::{
+    build_request, ensure_success, error_response, is_event_stream,
+};
+
+/// A transport for context servers using the legacy HTTP+SSE transport.
+///
+/// The server pushes all of its messages over a long-lived event stream, whose
+/// first `endpoint` event tells the client where to POST outgoing messages.
+pub struct SseTransport {
+    endpoint: ContextServerEndpoint,
+    http_client: Arc<dyn HttpClient>,
+    message_url: watch::Receiver<Option<Url>>,
+    inbound_tx: channel::Sender<String>,
+    inbound_rx: channel::Receiver<String>,
+    _event_stream_task: Task<()>,
+}
+
+impl SseTransport {
+    pub fn new(
+        endpoint: ContextServerEndpoint,
+        http_client: Arc<dyn HttpClient>,
+        cx: &AsyncApp,
+    ) -> Self {
+        let (inbound_tx, inbound_rx) = channel::unbounded::<String>();
+        let (message_url_tx, message_url_rx) = watch::channel();
+
+        let event_stream_task = cx.background_spawn({
+            let endpoint = endpoint.clone();
+            let http_client = http_client.clone();
+            let inbound_tx = inbound_tx.clone();
+            async move {
+                if let Err(error) =
+                    Self::handle_event_stream(endpoint, http_client, message_url_tx, inbound_tx)
+                        .await
+                {
+                    log::error!("context server event stream failed: {error:#}");
+                }
+            }
+        });
+
+        Self {
+            endpoint,
+            http_client,
+            message_url: message_url_rx,
+            inbound_tx,
+            inbound_rx,
+            _event_stream_task: event_stream_task,
+        }
+    }
+
+    async fn handle_event_stream(
+        endpoint: ContextServerEndpoint,
+        http_client: Arc<dyn HttpClient>,
+        mut message_url_tx: watch::Sender<Option<Url>>,
+        inbound_tx: channel::Sender<String>,
+    ) -> Result<()> {
+        let request = build_request(Method::GET, &endpoint.url, &endpoint)
+            .header("Accept", "text/event-stream")
+            .body(AsyncBody::empty())?;
+        let mut response = http_client.send(request).await?;
+        ensure_success(&mut response).await?;
+        anyhow::ensure!(
+            is_event_stream(&response),
+            "context server did not respond with an event stream"
+        );
+
+        let mut events = sse_events(response.into_body());
+        while let Some(event) = events.next().await {
+            match event.event.as_str() {
+                "endpoint" => {
+                    let url = endpoint
+                        .url
+                        .join(event.data.trim())
+                        .context("context server sent an invalid endpoint")?;
+                    *message_url_tx.borrow_mut() = Some(url);
+                }
+                "message" => {
+                    if inbound_tx.send(event.data).await.is_err() {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn message_url(&self) -> Result<Url> {
+        let mut message_url = self.message_url.clone();
+        loop {
+            if let Some(url) = message_url
+                .recv()
+                .await
+                .context("context server closed the event stream")?
+            {
+                return Ok(url);
+            }
+        }
+    }
+
+    async fn post(&self, message: String) -> Result<()> {
+        let url = self.message_url().await?;
+        let request = build_request(Method::POST, &url, &self.endpoint)
+            .header("Content-Type", "application/json")
+            .body(AsyncBody::from(message))?;
+        let mut response = self.http_client.send(request).await?;
+        ensure_success(&mut response).await
+    }
+}
+
+#[async_trait]
+impl Transport for SseTransport {
+    async fn send(&self, message: String) -> Result<()> {
+        if let Err(error) = self.post(message.clone()).await {
+            log::error!("context server request failed: {error:#}");
+            if let Some(response) = error_response(&message, &error) {
+                self.inbound_tx.send(response).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.inbound_rx.clone())
+    }
+
+    fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(futures::stream::empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContextServerHttpTransport;
+    use collections::HashMap;
+    use futures::{AsyncReadExt as _, TryStreamExt as _};
+    use gpui::TestAppContext;
+    use http_client::{FakeHttpClient, Response};
+    use parking_lot::Mutex;
+    use serde_json::{Value, json};
+
+    #[gpui::test]
+    async fn test_sse_transport(cx: &mut TestAppContext) {
+        let (events_tx, events_rx) = futures::channel::mpsc::unbounded::<String>();
+        let events_rx = Arc::new(Mutex::new(Some(events_rx)));
+        let posted_urls = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let posted_urls = posted_urls.clone();
+            move |mut request| {
+                let events_rx = events_rx.clone();
+                let events_tx = events_tx.clone();
+                let posted_urls = posted_urls.clone();
+                async move {
+                    if request.method() == Method::GET {
+                        let events_rx = events_rx.lock().take().unwrap();
+                        events_tx.unbounded_send(
+                            "event: endpoint\ndata: /messages?session_id=abc\n\n".into(),
+                        )?;
+                        let body = events_rx
+                            .map(|event| Ok::<_, std::io::Error>(event.into_bytes()))
+                            .into_async_read();
+                        return Ok(Response::builder()
+                            .header("Content-Type", "text/event-stream")
+                            .body(AsyncBody::from_reader(body))?);
+                    }
+
+                    posted_urls.lock().push(request.uri().to_string());
+                    let mut body = String::new();
+                    request.body_mut().read_to_string(&mut body).await?;
+                    let message: Value = serde_json::from_str(&body)?;
+                    events_tx.unbounded_send(format!(
+                        "data: {}\n\n",
+                        json!({"jsonrpc": "2.0", "id": message["id"], "result": {}})
+                    ))?;
+                    Ok(Response::builder().status(202).body(AsyncBody::empty())?)
+                }
+            }
+        });
+
+        let endpoint = ContextServerEndpoint {
+            url: Url::parse("http://localhost:3000/sse").unwrap(),
+            headers: HashMap::default(),
+            transport: ContextServerHttpTransport::Sse,
+        };
+        let transport = SseTransport::new(endpoint, http_client, &cx.to_async());
+        let mut incoming = transport.receive();
+
+        transport
+            .send(json!({"jsonrpc": "2.0", "id": 7, "method": "ping"}).to_string())
+            .await
+            .unwrap();
+        let response: Value = serde_json::from_str(&incoming.next().await.unwrap()).unwrap();
+        assert_eq!(response["id"], 7);
+        assert_eq!(
+            posted_urls.lock().as_slice(),
+            ["http://localhost:3000/messages?session_id=abc"]
+        );
+    }
+}
@@ -2,7 +2,12 @@ use collections::HashMap;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-pub const LATEST_PROTOCOL_VERSION: &str = "2024-11-05";
+pub const LATEST_PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// The protocol revisions we can talk, from newest to oldest. During initialization we
+/// request the latest one and accept any of these in the server's answer.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] =
+    &[LATEST_PROTOCOL_VERSION, "2025-03-26", "2024-11-05"];
 
 pub enum RequestType {
     Initialize,
@@ -19,6 +24,7 @@ pub enum RequestType {
     ListTools,
     ListResourceTemplates,
     ListRoots,
+    Elicitation,
 }
 
 impl RequestType {
@@ -38,6 +44,7 @@ impl RequestType {
             RequestType::ListTools => "tools/list",
             RequestType::ListResourceTemplates => "resources/templates/list",
             RequestType::ListRoots => "roots/list",
+            RequestType::Elicitation => "elicitation/create",
         }
     }
 }
@@ -61,15 +68,26 @@ impl TryFrom<&str> for RequestType {
             "tools/list" => Ok(RequestType::ListTools),
             "resources/templates/list" => Ok(RequestType::ListResourceTemplates),
             "roots/list" => Ok(RequestType::ListRoots),
+            "elicitation/create" => Ok(RequestType::Elicitation),
             _ => Err(()),
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct ProtocolVersion(pub String);
 
+impl ProtocolVersion {
+    pub fn latest() -> Self {
+        Self(LATEST_PROTOCOL_VERSION.to_string())
+    }
+
+    pub fn is_supported(&self) -> bool {
+        SUPPORTED_PROTOCOL_VERSIONS.contains(&self.0.as_str())
+    }
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InitializeParams {
@@ -365,6 +383,8 @@ pub struct ClientCapabilities {
     pub sampling: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub roots: Option<RootsCapabilities>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elicitation: Option<serde_json::Value>,
 }
 
 #[derive(Default, Debug, Serialize, Deserialize)]
@@ -416,15 +436,50 @@ pub struct RootsCapabilities {
 #[serde(rename_all = "camelCase")]
 pub struct Tool {
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub input_schema: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
+}
+
+impl Tool {
+    /// The human-readable name of the tool, falling back to its programmatic name.
+    pub fn display_name(&self) -> &str {
+        self.title
+            .as_deref()
+            .or_else(|| self.annotations.as_ref()?.title.as_deref())
+            .unwrap_or(&self.name)
+    }
+}
+
+/// Hints describing a tool's behavior. Clients must not rely on these for
+/// security decisions, as they come from the (untrusted) server.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_world_hint: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Implementation {
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
     pub version: String,
 }
 
@@ -595,6 +650,9 @@ pub struct CallToolResponse {
     pub content: Vec<ToolResponseContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_error: Option<bool>,
+    /// The result of the tool as a JSON object conforming to the tool's `output_schema`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<serde_json::Value>,
     #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
     pub meta: Option<HashMap<String, serde_json::Value>>,
 }
@@ -606,8 +664,30 @@ pub enum ToolResponseContent {
     Text { text: String },
     #[serde(rename = "image")]
     Image { data: String, mime_type: String },
+    #[serde(rename = "audio")]
+    Audio {
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
     #[serde(rename = "resource")]
     Resource { resource: ResourceContents },
+    #[serde(rename = "resource_link")]
+    ResourceLink(ResourceLink),
+}
+
+/// A reference to a resource the server can provide, returned instead of embedding its contents.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceLink {
+    pub uri: Url,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -638,6 +718,31 @@ pub struct ListRootsResponse {
     pub meta: Option<HashMap<String, serde_json::Value>>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElicitParams {
+    pub message: String,
+    /// A restricted JSON schema (flat object of primitive properties) describing the
+    /// information the server is asking the user for.
+    pub requested_schema: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElicitResponse {
+    pub action: ElicitAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<HashMap<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElicitAction {
+    Accept,
+    Decline,
+    Cancel,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Root {
@@ -645,3 +750,59 @@ pub struct Root {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize_tool_with_annotations() {
+        let tool: Tool = serde_json::from_value(json!({
+            "name": "get_weather",
+            "inputSchema": {"type": "object"},
+            "outputSchema": {
+                "type": "object",
+                "properties": {"temperature": {"type": "number"}}
+            },
+            "annotations": {"title": "Get Weather", "readOnlyHint": true}
+        }))
+        .unwrap();
+        assert_eq!(tool.display_name(), "Get Weather");
+        assert!(tool.output_schema.is_some());
+        assert_eq!(tool.annotations.unwrap().read_only_hint, Some(true));
+    }
+
+    #[test]
+    fn test_deserialize_call_tool_response() {
+        let response: CallToolResponse = serde_json::from_value(json!({
+            "content": [
+                {"type": "text", "text": "{\"temperature\": 22.5}"},
+                {
+                    "type": "resource_link",
+                    "uri": "file:///project/src/main.rs",
+                    "name": "main.rs",
+                    "mimeType": "text/x-rust"
+                }
+            ],
+            "structuredContent": {"temperature": 22.5},
+        }))
+        .unwrap();
+        assert_eq!(
+            response.structured_content,
+            Some(json!({"temperature": 22.5}))
+        );
+        assert!(matches!(
+            &response.content[1],
+            ToolResponseContent::ResourceLink(link)
+                if link.name == "main.rs" && link.mime_type.as_deref() == Some("text/x-rust")
+        ));
+    }
+
+    #[test]
+    fn test_protocol_version_support() {
+        assert!(ProtocolVersion::latest().is_supported());
+        assert!(ProtocolVersion("2024-11-05".into()).is_supported());
+        assert!(!ProtocolVersion("2023-01-01".into()).is_supported());
+    }
+}
@@ -1316,7 +1316,7 @@ mod tests {
             self.abs_path.clone()
         }
 
-        fn load(&self, _: &App) -> Task<Result<String>> {
+        fn load(&self, _: Option<fs::Encoding>, _: &App) -> Task<Result<(String, fs::Encoding)>> {
             unimplemented!()
         }
 
@@ -812,6 +812,7 @@ impl SearchableItem for DapLogView {
             // DAP log is read-only.
             replacement: false,
             selection: false,
+            syntax_filter: false,
         }
     }
     fn active_match_index(
@@ -44,6 +44,9 @@ actions!(
         FocusModules,
         FocusLoadedSources,
         FocusTerminal,
+        FocusWatchList,
+        FocusDisassembly,
+        FocusMemory,
         ShowStackTrace,
         ToggleThreadPicker,
         ToggleSessionPicker,
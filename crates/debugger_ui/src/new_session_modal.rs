@@ -1155,6 +1155,7 @@ impl PickerDelegate for DebugScenarioDelegate {
             Some(TaskSourceKind::UserInput) => Some(Icon::new(IconName::Terminal)),
             Some(TaskSourceKind::AbsPath { .. }) => Some(Icon::new(IconName::Settings)),
             Some(TaskSourceKind::Worktree { .. }) => Some(Icon::new(IconName::FileTree)),
+            Some(TaskSourceKind::Provider { .. }) => Some(Icon::new(IconName::Blocks)),
             Some(TaskSourceKind::Language { name }) => file_icons::FileIcons::get(cx)
                 .get_icon_for_type(&name.to_lowercase(), cx)
                 .map(Icon::from_path),
@@ -1,9 +1,12 @@
 pub(crate) mod breakpoint_list;
 pub(crate) mod console;
+pub(crate) mod disassembly_view;
 pub(crate) mod loaded_source_list;
+pub(crate) mod memory_view;
 pub(crate) mod module_list;
 pub mod stack_frame_list;
 pub mod variable_list;
+pub(crate) mod watch_list;
 
 use std::{any::Any, ops::ControlFlow, path::PathBuf, sync::Arc, time::Duration};
 
@@ -20,6 +23,7 @@ use dap::{
     client::SessionId,
     debugger_settings::DebuggerSettings,
 };
+use disassembly_view::DisassemblyView;
 use futures::{SinkExt, channel::mpsc};
 use gpui::{
     Action as _, AnyView, AppContext, Axis, Entity, EntityId, EventEmitter, FocusHandle, Focusable,
@@ -27,6 +31,7 @@ use gpui::{
 };
 use language::Buffer;
 use loaded_source_list::LoadedSourceList;
+use memory_view::MemoryView;
 use module_list::ModuleList;
 use project::{
     Project, WorktreeId,
@@ -49,7 +54,8 @@ use ui::{
     VisibleOnHover, VisualContext, Window, div, h_flex, v_flex,
 };
 use util::ResultExt;
-use variable_list::VariableList;
+use variable_list::{VariableList, VariableListEvent};
+use watch_list::WatchList;
 use workspace::{
     ActivePaneDecorator, DraggedTab, Item, ItemHandle, Member, Pane, PaneGroup, SplitDirection,
     Workspace, item::TabContentParams, move_item, pane::Event,
@@ -70,6 +76,9 @@ pub struct RunningState {
     module_list: Entity<module_list::ModuleList>,
     console: Entity<Console>,
     breakpoint_list: Entity<BreakpointList>,
+    watch_list: Entity<WatchList>,
+    disassembly_view: Entity<DisassemblyView>,
+    memory_view: Entity<MemoryView>,
     panes: PaneGroup,
     active_pane: Option<Entity<Pane>>,
     pane_close_subscriptions: HashMap<EntityId, Subscription>,
@@ -532,7 +541,13 @@ impl RunningState {
         let session_id = session.read(cx).session_id();
         let weak_state = cx.weak_entity();
         let stack_frame_list = cx.new(|cx| {
-            StackFrameList::new(workspace.clone(), session.clone(), weak_state, window, cx)
+            StackFrameList::new(
+                workspace.clone(),
+                session.clone(),
+                weak_state.clone(),
+                window,
+                cx,
+            )
         });
 
         let debug_terminal = cx.new(DebugTerminal::empty);
@@ -554,10 +569,33 @@ impl RunningState {
             )
         });
 
-        let breakpoint_list = BreakpointList::new(session.clone(), workspace.clone(), &project, cx);
+        let breakpoint_list =
+            BreakpointList::new(session.clone(), workspace.clone(), &project, window, cx);
+
+        let watch_list =
+            cx.new(|cx| WatchList::new(session.clone(), stack_frame_list.clone(), window, cx));
+
+        let disassembly_view = cx.new(|cx| {
+            DisassemblyView::new(session.clone(), stack_frame_list.clone(), weak_state, cx)
+        });
+
+        let memory_view = cx.new(|cx| MemoryView::new(session.clone(), window, cx));
 
         let _subscriptions = vec![
             cx.observe(&module_list, |_, _, cx| cx.notify()),
+            cx.subscribe_in(
+                &variable_list,
+                window,
+                |this, _, event, window, cx| match event {
+                    VariableListEvent::ViewMemory(memory_reference) => {
+                        this.memory_view.update(cx, |memory_view, cx| {
+                            memory_view.go_to(memory_reference.clone(), window, cx);
+                        });
+                        this.ensure_pane_item(DebuggerPaneItem::Memory, window, cx);
+                        this.activate_item(DebuggerPaneItem::Memory, window, cx);
+                    }
+                },
+            ),
             cx.subscribe_in(&session, window, |this, _, event, window, cx| {
                 match event {
                     SessionEvent::Stopped(thread_id) => {
@@ -586,6 +624,12 @@ impl RunningState {
                         {
                             this.remove_pane_item(DebuggerPaneItem::LoadedSources, window, cx);
                         }
+                        if !capabilities.supports_disassemble_request.unwrap_or(false) {
+                            this.remove_pane_item(DebuggerPaneItem::Disassembly, window, cx);
+                        }
+                        if !capabilities.supports_read_memory_request.unwrap_or(false) {
+                            this.remove_pane_item(DebuggerPaneItem::Memory, window, cx);
+                        }
                     }
                     SessionEvent::RunInTerminal { request, sender } => this
                         .handle_run_in_terminal(request, sender.clone(), window, cx)
@@ -614,6 +658,9 @@ impl RunningState {
                 &breakpoint_list,
                 &loaded_source_list,
                 &debug_terminal,
+                &watch_list,
+                &disassembly_view,
+                &memory_view,
                 &mut pane_close_subscriptions,
                 window,
                 cx,
@@ -632,6 +679,9 @@ impl RunningState {
                 &loaded_source_list,
                 &console,
                 &breakpoint_list,
+                &watch_list,
+                &disassembly_view,
+                &memory_view,
                 dock_axis,
                 &mut pane_close_subscriptions,
                 window,
@@ -656,6 +706,9 @@ impl RunningState {
             module_list,
             console,
             breakpoint_list,
+            watch_list,
+            disassembly_view,
+            memory_view,
             loaded_sources_list: loaded_source_list,
             pane_close_subscriptions,
             debug_terminal,
@@ -745,6 +798,11 @@ impl RunningState {
                 let Some(task) = task.resolve_task("debug-build-task", &task_context) else {
                     anyhow::bail!("Could not resolve task variables within a debug scenario");
                 };
+                anyhow::ensure!(
+                    !task.is_aggregator(),
+                    "Build task `{}` has no command to run",
+                    task.display_label()
+                );
 
                 let locator_name = if let Some(locator_name) = locator_name {
                     debug_assert!(request.is_none());
@@ -955,6 +1013,7 @@ impl RunningState {
                 show_summary: false,
                 show_command: false,
                 show_rerun: false,
+                problem_matchers: Vec::new(),
             })
         } else {
             TerminalKind::Shell(cwd.map(|c| c.to_path_buf()))
@@ -1065,6 +1124,27 @@ impl RunningState {
                 None,
                 cx,
             )),
+            DebuggerPaneItem::WatchList => Box::new(SubView::new(
+                self.watch_list.focus_handle(cx),
+                self.watch_list.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
+            DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                self.disassembly_view.focus_handle(cx),
+                self.disassembly_view.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
+            DebuggerPaneItem::Memory => Box::new(SubView::new(
+                self.memory_view.focus_handle(cx),
+                self.memory_view.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
         }
     }
 
@@ -1472,6 +1552,9 @@ impl RunningState {
         loaded_source_list: &Entity<LoadedSourceList>,
         console: &Entity<Console>,
         breakpoints: &Entity<BreakpointList>,
+        watch_list: &Entity<WatchList>,
+        disassembly_view: &Entity<DisassemblyView>,
+        memory_view: &Entity<MemoryView>,
         dock_axis: Axis,
         subscriptions: &mut HashMap<EntityId, Subscription>,
         window: &mut Window,
@@ -1526,6 +1609,20 @@ impl RunningState {
                 window,
                 cx,
             );
+            this.add_item(
+                Box::new(SubView::new(
+                    watch_list.focus_handle(cx),
+                    watch_list.clone().into(),
+                    DebuggerPaneItem::WatchList,
+                    None,
+                    cx,
+                )),
+                false,
+                false,
+                None,
+                window,
+                cx,
+            );
             this.add_item(
                 Box::new(SubView::new(
                     module_list.focus_handle(cx),
@@ -1555,6 +1652,34 @@ impl RunningState {
                 window,
                 cx,
             );
+            this.add_item(
+                Box::new(SubView::new(
+                    disassembly_view.focus_handle(cx),
+                    disassembly_view.clone().into(),
+                    DebuggerPaneItem::Disassembly,
+                    None,
+                    cx,
+                )),
+                false,
+                false,
+                None,
+                window,
+                cx,
+            );
+            this.add_item(
+                Box::new(SubView::new(
+                    memory_view.focus_handle(cx),
+                    memory_view.clone().into(),
+                    DebuggerPaneItem::Memory,
+                    None,
+                    cx,
+                )),
+                false,
+                false,
+                None,
+                window,
+                cx,
+            );
             this.activate_item(0, false, false, window, cx);
         });
 
@@ -6,14 +6,18 @@ use std::{
 use dap::ExceptionBreakpointsFilter;
 use editor::Editor;
 use gpui::{
-    AppContext, Entity, FocusHandle, Focusable, ListState, MouseButton, Stateful, Task, WeakEntity,
-    list,
+    AppContext, Entity, FocusHandle, Focusable, ListState, MouseButton, Stateful, Subscription,
+    Task, WeakEntity, list,
 };
 use language::Point;
+use menu::Confirm;
 use project::{
     Project,
     debugger::{
-        breakpoint_store::{BreakpointEditAction, BreakpointStore, SourceBreakpoint},
+        breakpoint_store::{
+            BreakpointEditAction, BreakpointStore, DataBreakpoint, FunctionBreakpoint,
+            SourceBreakpoint,
+        },
         session::Session,
     },
     worktree_store::WorktreeStore,
@@ -24,6 +28,7 @@ use ui::{
     Scrollbar, ScrollbarState, SharedString, StatefulInteractiveElement, Styled, Tooltip, Window,
     div, h_flex, px, v_flex,
 };
+use ui::{Divider, FluentBuilder as _};
 use util::{ResultExt, maybe};
 use workspace::Workspace;
 
@@ -37,7 +42,9 @@ pub(crate) struct BreakpointList {
     session: Entity<Session>,
     hide_scrollbar_task: Option<Task<()>>,
     show_scrollbar: bool,
+    function_breakpoint_editor: Entity<Editor>,
     focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl Focusable for BreakpointList {
@@ -51,6 +58,7 @@ impl BreakpointList {
         session: Entity<Session>,
         workspace: WeakEntity<Workspace>,
         project: &Entity<Project>,
+        window: &mut Window,
         cx: &mut App,
     ) -> Entity<Self> {
         let project = project.read(cx);
@@ -73,6 +81,16 @@ impl BreakpointList {
                     breakpoint.render(window, cx).into_any_element()
                 },
             );
+            let function_breakpoint_editor = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text("Break on function", cx);
+                editor.set_use_autoclose(false);
+                editor
+            });
+            let _subscriptions = vec![
+                cx.observe(&breakpoint_store, |_, _, cx| cx.notify()),
+                cx.observe(&session, |_, _, cx| cx.notify()),
+            ];
             Self {
                 breakpoint_store,
                 worktree_store,
@@ -83,11 +101,29 @@ impl BreakpointList {
                 show_scrollbar: false,
                 workspace,
                 session,
+                function_breakpoint_editor,
                 focus_handle: cx.focus_handle(),
+                _subscriptions,
             }
         })
     }
 
+    fn add_function_breakpoint(
+        &mut self,
+        _: &Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let name = self.function_breakpoint_editor.update(cx, |editor, cx| {
+            let name = editor.text(cx);
+            editor.clear(window, cx);
+            name
+        });
+        self.breakpoint_store.update(cx, |store, cx| {
+            store.add_function_breakpoint(name.into(), cx);
+        });
+    }
+
     fn hide_scrollbar(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         const SCROLLBAR_SHOW_INTERVAL: Duration = Duration::from_secs(1);
         self.hide_scrollbar_task = Some(cx.spawn_in(window, async move |panel, cx| {
@@ -207,8 +243,31 @@ impl Render for BreakpointList {
                     }),
                     weak: weak.clone(),
                 });
-        self.breakpoints
-            .extend(breakpoints.chain(exception_breakpoints));
+        let function_breakpoints = self
+            .breakpoint_store
+            .read(cx)
+            .function_breakpoints()
+            .iter()
+            .map(|breakpoint| BreakpointEntry {
+                kind: BreakpointEntryKind::FunctionBreakpoint(breakpoint.clone()),
+                weak: weak.clone(),
+            })
+            .collect::<Vec<_>>();
+        let data_breakpoints = self
+            .session
+            .read(cx)
+            .data_breakpoints(cx)
+            .map(|breakpoint| BreakpointEntry {
+                kind: BreakpointEntryKind::DataBreakpoint(breakpoint.clone()),
+                weak: weak.clone(),
+            })
+            .collect::<Vec<_>>();
+        self.breakpoints.extend(
+            breakpoints
+                .chain(function_breakpoints)
+                .chain(data_breakpoints)
+                .chain(exception_breakpoints),
+        );
         if self.breakpoints.len() != old_len {
             self.list_state.reset(self.breakpoints.len());
         }
@@ -224,10 +283,31 @@ impl Render for BreakpointList {
                     this.hide_scrollbar(window, cx);
                 }
             }))
+            .on_action(cx.listener(Self::add_function_breakpoint))
             .size_full()
             .m_0p5()
-            .child(list(self.list_state.clone()).flex_grow())
-            .children(self.render_vertical_scrollbar(cx))
+            .child(
+                div()
+                    .relative()
+                    .flex_grow()
+                    .child(list(self.list_state.clone()).size_full())
+                    .children(self.render_vertical_scrollbar(cx)),
+            )
+            .when(
+                self.session
+                    .read(cx)
+                    .capabilities()
+                    .supports_function_breakpoints
+                    .unwrap_or_default(),
+                |this| {
+                    this.child(Divider::horizontal()).child(
+                        div()
+                            .key_context("FunctionBreakpointEditor")
+                            .px_1()
+                            .child(self.function_breakpoint_editor.clone()),
+                    )
+                },
+            )
     }
 }
 #[derive(Clone, Debug)]
@@ -475,9 +555,171 @@ impl ExceptionBreakpoint {
         )
     }
 }
+fn render_function_breakpoint(
+    breakpoint: FunctionBreakpoint,
+    list: WeakEntity<BreakpointList>,
+) -> ListItem {
+    let is_enabled = breakpoint.state.is_enabled();
+    let name = breakpoint.name;
+    ListItem::new(SharedString::from(format!(
+        "function-breakpoint-ui-item-{name}"
+    )))
+    .rounded()
+    .on_secondary_mouse_down(|_, _, cx| {
+        cx.stop_propagation();
+    })
+    .start_slot(
+        div()
+            .id(SharedString::from(format!(
+                "function-breakpoint-ui-item-{name}-click-handler"
+            )))
+            .tooltip(Tooltip::text(if is_enabled {
+                "Disable Function Breakpoint"
+            } else {
+                "Enable Function Breakpoint"
+            }))
+            .on_click({
+                let list = list.clone();
+                let name = name.clone();
+                move |_, _, cx| {
+                    list.update(cx, |this, cx| {
+                        this.breakpoint_store.update(cx, |store, cx| {
+                            store.toggle_function_breakpoint_state(&name, cx);
+                        });
+                    })
+                    .ok();
+                }
+            })
+            .cursor_pointer()
+            .child(
+                Indicator::icon(Icon::new(if is_enabled {
+                    IconName::DebugBreakpoint
+                } else {
+                    IconName::DebugDisabledBreakpoint
+                }))
+                .color(Color::Debugger),
+            ),
+    )
+    .end_hover_slot(
+        IconButton::new(
+            SharedString::from(format!("function-breakpoint-ui-remove-{name}")),
+            IconName::Close,
+        )
+        .on_click({
+            let name = name.clone();
+            move |_, _, cx| {
+                list.update(cx, |this, cx| {
+                    this.breakpoint_store.update(cx, |store, cx| {
+                        store.remove_function_breakpoint(&name, cx);
+                    });
+                })
+                .ok();
+            }
+        })
+        .icon_size(ui::IconSize::XSmall),
+    )
+    .child(
+        h_flex()
+            .py_1()
+            .gap_1()
+            .child(
+                Label::new(SharedString::from(name.to_string()))
+                    .size(LabelSize::Small)
+                    .line_height_style(ui::LineHeightStyle::UiLabel),
+            )
+            .child(
+                Label::new("Function")
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted)
+                    .line_height_style(ui::LineHeightStyle::UiLabel),
+            ),
+    )
+}
+
+fn render_data_breakpoint(
+    breakpoint: DataBreakpoint,
+    list: WeakEntity<BreakpointList>,
+) -> ListItem {
+    let data_id = SharedString::from(breakpoint.data_id);
+    ListItem::new(SharedString::from(format!(
+        "data-breakpoint-ui-item-{data_id}"
+    )))
+    .rounded()
+    .on_secondary_mouse_down(|_, _, cx| {
+        cx.stop_propagation();
+    })
+    .start_slot(
+        div()
+            .id(SharedString::from(format!(
+                "data-breakpoint-ui-item-{data_id}-click-handler"
+            )))
+            .tooltip(Tooltip::text(if breakpoint.is_enabled {
+                "Disable Data Breakpoint"
+            } else {
+                "Enable Data Breakpoint"
+            }))
+            .on_click({
+                let list = list.clone();
+                let data_id = data_id.clone();
+                move |_, _, cx| {
+                    list.update(cx, |this, cx| {
+                        this.session.update(cx, |session, cx| {
+                            session.toggle_data_breakpoint(&data_id, cx);
+                        });
+                    })
+                    .ok();
+                }
+            })
+            .cursor_pointer()
+            .child(
+                Indicator::icon(Icon::new(IconName::Eye)).color(if breakpoint.is_enabled {
+                    Color::Debugger
+                } else {
+                    Color::Muted
+                }),
+            ),
+    )
+    .end_hover_slot(
+        IconButton::new(
+            SharedString::from(format!("data-breakpoint-ui-remove-{data_id}")),
+            IconName::Close,
+        )
+        .on_click({
+            let data_id = data_id.clone();
+            move |_, _, cx| {
+                list.update(cx, |this, cx| {
+                    this.session.update(cx, |session, cx| {
+                        session.remove_data_breakpoint(&data_id, cx);
+                    });
+                })
+                .ok();
+            }
+        })
+        .icon_size(ui::IconSize::XSmall),
+    )
+    .child(
+        h_flex()
+            .py_1()
+            .gap_1()
+            .child(
+                Label::new(breakpoint.description)
+                    .size(LabelSize::Small)
+                    .line_height_style(ui::LineHeightStyle::UiLabel),
+            )
+            .child(
+                Label::new("Data")
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted)
+                    .line_height_style(ui::LineHeightStyle::UiLabel),
+            ),
+    )
+}
+
 #[derive(Clone, Debug)]
 enum BreakpointEntryKind {
     LineBreakpoint(LineBreakpoint),
+    FunctionBreakpoint(FunctionBreakpoint),
+    DataBreakpoint(DataBreakpoint),
     ExceptionBreakpoint(ExceptionBreakpoint),
 }
 
@@ -492,6 +734,12 @@ impl RenderOnce for BreakpointEntry {
             BreakpointEntryKind::LineBreakpoint(line_breakpoint) => {
                 line_breakpoint.render(self.weak)
             }
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                render_function_breakpoint(function_breakpoint, self.weak)
+            }
+            BreakpointEntryKind::DataBreakpoint(data_breakpoint) => {
+                render_data_breakpoint(data_breakpoint, self.weak)
+            }
             BreakpointEntryKind::ExceptionBreakpoint(exception_breakpoint) => {
                 exception_breakpoint.render(self.weak)
             }
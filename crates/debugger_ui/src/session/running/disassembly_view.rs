@@ -0,0 +1,291 @@
+use dap::{DisassembledInstruction, SteppingGranularity};
+use gpui::{
+    Entity, FocusHandle, Focusable, ScrollStrategy, Subscription, Task, UniformListScrollHandle,
+    WeakEntity, uniform_list,
+};
+use project::debugger::session::{Session, SessionEvent};
+use ui::{Divider, IconButton, Tooltip, prelude::*};
+use util::ResultExt;
+
+use super::{
+    RunningState,
+    stack_frame_list::{StackFrameList, StackFrameListEvent},
+};
+
+/// How many instructions before the instruction pointer to disassemble, so that the
+/// surrounding code is visible too.
+const INSTRUCTIONS_BEFORE: i64 = 32;
+const INSTRUCTION_COUNT: i64 = 128;
+/// How close the instruction pointer may get to either end of the disassembled code
+/// before it's disassembled again.
+const REFETCH_MARGIN: usize = 8;
+
+/// Shows the machine code around the instruction pointer of the selected stack frame.
+pub(crate) struct DisassemblyView {
+    session: Entity<Session>,
+    state: WeakEntity<RunningState>,
+    stack_frame_list: Entity<StackFrameList>,
+    instructions: Vec<DisassembledInstruction>,
+    instruction_pointer: Option<String>,
+    error: Option<SharedString>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    _fetch_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DisassemblyView {
+    pub fn new(
+        session: Entity<Session>,
+        stack_frame_list: Entity<StackFrameList>,
+        state: WeakEntity<RunningState>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = vec![
+            cx.subscribe(&stack_frame_list, |this, _, event, cx| match event {
+                StackFrameListEvent::SelectedStackFrameChanged(_)
+                | StackFrameListEvent::BuiltEntries => this.refresh(cx),
+            }),
+            cx.subscribe(&session, |this, _, event, cx| match event {
+                SessionEvent::Memory => {
+                    this.instruction_pointer.take();
+                    this.instructions.clear();
+                    this.refresh(cx);
+                }
+                _ => {}
+            }),
+        ];
+
+        let mut this = Self {
+            session,
+            state,
+            stack_frame_list,
+            instructions: Vec::new(),
+            instruction_pointer: None,
+            error: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            _fetch_task: Task::ready(()),
+            _subscriptions,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let instruction_pointer = self
+            .stack_frame_list
+            .read(cx)
+            .opened_stack_frame()
+            .and_then(|stack_frame| stack_frame.instruction_pointer_reference);
+        let Some(instruction_pointer) = instruction_pointer else {
+            self.instructions.clear();
+            self.instruction_pointer = None;
+            self.error = None;
+            cx.notify();
+            return;
+        };
+        if self.instruction_pointer.as_ref() == Some(&instruction_pointer) {
+            return;
+        }
+
+        self.instruction_pointer = Some(instruction_pointer.clone());
+        // Stepping by instruction usually stays within the code we've already disassembled.
+        if let Some(ix) = self
+            .instruction_pointer_ix()
+            .filter(|ix| *ix >= REFETCH_MARGIN && ix + REFETCH_MARGIN < self.instructions.len())
+        {
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+            cx.notify();
+            return;
+        }
+
+        let task = self.session.update(cx, |session, _| {
+            session.disassemble(
+                instruction_pointer.clone(),
+                -INSTRUCTIONS_BEFORE,
+                INSTRUCTION_COUNT,
+            )
+        });
+        self._fetch_task = cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(instructions) => {
+                        this.instructions = instructions;
+                        this.error = None;
+                        if let Some(ix) = this.instruction_pointer_ix() {
+                            this.scroll_handle
+                                .scroll_to_item(ix, ScrollStrategy::Center);
+                        }
+                    }
+                    Err(error) => {
+                        this.instructions.clear();
+                        this.error = Some(format!("{error:#}").into());
+                    }
+                }
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
+
+    fn instruction_pointer_ix(&self) -> Option<usize> {
+        let instruction_pointer = parse_address(self.instruction_pointer.as_deref()?)?;
+        self.instructions.iter().position(|instruction| {
+            parse_address(&instruction.address) == Some(instruction_pointer)
+        })
+    }
+
+    fn step(&mut self, step_in: bool, cx: &mut Context<Self>) {
+        let Some(thread_id) = self
+            .state
+            .read_with(cx, |state, _| state.thread_id())
+            .ok()
+            .flatten()
+        else {
+            return;
+        };
+        self.session.update(cx, |session, cx| {
+            if step_in {
+                session.step_in(thread_id, SteppingGranularity::Instruction, cx);
+            } else {
+                session.step_over(thread_id, SteppingGranularity::Instruction, cx);
+            }
+        });
+    }
+
+    fn render_instruction(
+        &self,
+        ix: usize,
+        instruction_pointer_ix: Option<usize>,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let instruction = &self.instructions[ix];
+        let is_current = instruction_pointer_ix == Some(ix);
+
+        h_flex()
+            .id(("instruction", ix))
+            .w_full()
+            .px_1()
+            .gap_2()
+            .font_buffer(cx)
+            .text_ui_sm(cx)
+            .when(is_current, |this| {
+                this.bg(cx.theme().colors().editor_active_line_background)
+            })
+            .child(div().w_4().when(is_current, |this| {
+                this.child(
+                    Icon::new(IconName::ArrowRight)
+                        .size(IconSize::XSmall)
+                        .color(Color::Debugger),
+                )
+            }))
+            .child(
+                Label::new(instruction.address.clone())
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .when_some(instruction.instruction_bytes.clone(), |this, bytes| {
+                this.child(
+                    div().min_w_32().child(
+                        Label::new(bytes)
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+                )
+            })
+            .child(
+                Label::new(instruction.instruction.clone())
+                    .buffer_font(cx)
+                    .size(LabelSize::Small),
+            )
+            .when_some(instruction.symbol.clone(), |this, symbol| {
+                this.child(
+                    Label::new(format!("<{symbol}>"))
+                        .buffer_font(cx)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .into_any_element()
+    }
+}
+
+/// Parses addresses such as `0x00401000`, which is how adapters usually report them.
+pub(crate) fn parse_address(address: &str) -> Option<u64> {
+    let address = address.trim();
+    match address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => address.parse().ok(),
+    }
+}
+
+impl Focusable for DisassemblyView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DisassemblyView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let instruction_pointer_ix = self.instruction_pointer_ix();
+        let content = if let Some(error) = self.error.clone() {
+            div()
+                .p_2()
+                .child(Label::new(error).color(Color::Error))
+                .into_any_element()
+        } else if self.instructions.is_empty() {
+            div()
+                .p_2()
+                .child(
+                    Label::new("No instructions to show for the selected frame")
+                        .color(Color::Muted),
+                )
+                .into_any_element()
+        } else {
+            uniform_list(
+                cx.entity(),
+                "disassembly",
+                self.instructions.len(),
+                move |this, range, _, cx| {
+                    range
+                        .map(|ix| this.render_instruction(ix, instruction_pointer_ix, cx))
+                        .collect()
+                },
+            )
+            .track_scroll(self.scroll_handle.clone())
+            .size_full()
+            .into_any_element()
+        };
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(
+                h_flex()
+                    .p_1()
+                    .gap_1()
+                    .child(
+                        IconButton::new("step-over-instruction", IconName::DebugStepOver)
+                            .icon_size(IconSize::XSmall)
+                            .on_click(cx.listener(|this, _, _, cx| this.step(false, cx)))
+                            .tooltip(Tooltip::text("Step Over Instruction")),
+                    )
+                    .child(
+                        IconButton::new("step-into-instruction", IconName::DebugStepInto)
+                            .icon_size(IconSize::XSmall)
+                            .on_click(cx.listener(|this, _, _, cx| this.step(true, cx)))
+                            .tooltip(Tooltip::text("Step Into Instruction")),
+                    ),
+            )
+            .child(Divider::horizontal())
+            .child(div().flex_1().size_full().child(content))
+    }
+}
@@ -0,0 +1,333 @@
+use editor::Editor;
+use gpui::{Entity, FocusHandle, Focusable, Subscription, Task};
+use menu::Confirm;
+use project::debugger::session::{MemoryContents, Session, SessionEvent};
+use ui::{Divider, IconButton, Tooltip, prelude::*};
+use util::ResultExt;
+
+use super::disassembly_view::parse_address;
+
+const BYTES_PER_ROW: usize = 16;
+const BYTES_PER_PAGE: u64 = 256;
+
+/// A hex viewer for the debuggee's memory, which also allows overwriting bytes if the
+/// adapter supports it.
+pub(crate) struct MemoryView {
+    session: Entity<Session>,
+    address_editor: Entity<Editor>,
+    write_editor: Entity<Editor>,
+    memory_reference: Option<String>,
+    /// The offset from `memory_reference` of the first byte on the page.
+    offset: i64,
+    contents: Option<MemoryContents>,
+    selected_byte: Option<usize>,
+    error: Option<SharedString>,
+    focus_handle: FocusHandle,
+    _fetch_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MemoryView {
+    pub fn new(session: Entity<Session>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let address_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Go to address or memory reference", cx);
+            editor
+        });
+        let write_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Hex bytes to write at the selected address", cx);
+            editor
+        });
+
+        let _subscriptions = vec![cx.subscribe(&session, |this, _, event, cx| match event {
+            SessionEvent::Stopped(_) | SessionEvent::Memory => this.fetch(cx),
+            _ => {}
+        })];
+
+        Self {
+            session,
+            address_editor,
+            write_editor,
+            memory_reference: None,
+            offset: 0,
+            contents: None,
+            selected_byte: None,
+            error: None,
+            focus_handle: cx.focus_handle(),
+            _fetch_task: Task::ready(()),
+            _subscriptions,
+        }
+    }
+
+    /// Shows the memory at the given reference, e.g. one from a variable or stack frame.
+    pub(crate) fn go_to(
+        &mut self,
+        memory_reference: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.address_editor.update(cx, |editor, cx| {
+            editor.set_text(memory_reference.clone(), window, cx);
+        });
+        self.memory_reference = Some(memory_reference);
+        self.offset = 0;
+        self.selected_byte = None;
+        self.fetch(cx);
+    }
+
+    fn confirm_address(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let memory_reference = self.address_editor.read(cx).text(cx).trim().to_string();
+        if memory_reference.is_empty() {
+            return;
+        }
+        self.go_to(memory_reference, window, cx);
+    }
+
+    fn confirm_write(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(memory_reference), Some(selected_byte)) =
+            (self.memory_reference.clone(), self.selected_byte)
+        else {
+            return;
+        };
+        let text = self.write_editor.read(cx).text(cx);
+        let Some(data) = parse_hex_bytes(&text) else {
+            self.error = Some(format!("Invalid hex bytes: {text}").into());
+            cx.notify();
+            return;
+        };
+        self.write_editor
+            .update(cx, |editor, cx| editor.clear(window, cx));
+
+        let offset = self.offset + selected_byte as i64;
+        let task = self.session.update(cx, |session, cx| {
+            session.write_memory(memory_reference, offset, &data, cx)
+        });
+        cx.spawn(async move |this, cx| {
+            if let Err(error) = task.await {
+                this.update(cx, |this, cx| {
+                    this.error = Some(format!("{error:#}").into());
+                    cx.notify();
+                })
+                .log_err();
+            }
+        })
+        .detach();
+    }
+
+    fn scroll_page(&mut self, forward: bool, cx: &mut Context<Self>) {
+        if forward {
+            self.offset += BYTES_PER_PAGE as i64;
+        } else {
+            self.offset -= BYTES_PER_PAGE as i64;
+        }
+        self.selected_byte = None;
+        self.fetch(cx);
+    }
+
+    fn fetch(&mut self, cx: &mut Context<Self>) {
+        let Some(memory_reference) = self.memory_reference.clone() else {
+            return;
+        };
+        let task = self.session.update(cx, |session, cx| {
+            session.read_memory(memory_reference, self.offset, BYTES_PER_PAGE, cx)
+        });
+        self._fetch_task = cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(contents) => {
+                        this.contents = Some(contents);
+                        this.error = None;
+                    }
+                    Err(error) => {
+                        this.contents = None;
+                        this.error = Some(format!("{error:#}").into());
+                    }
+                }
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
+
+    fn render_row(
+        &self,
+        row: usize,
+        base_address: Option<u64>,
+        bytes: &[u8],
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let start = row * BYTES_PER_ROW;
+        let address = match base_address {
+            Some(base_address) => format!("{:016x}", base_address.wrapping_add(start as u64)),
+            None => format!("+{start:#06x}"),
+        };
+        let ascii = bytes
+            .iter()
+            .map(|byte| {
+                if byte.is_ascii_graphic() || *byte == b' ' {
+                    *byte as char
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>();
+
+        h_flex()
+            .gap_3()
+            .px_1()
+            .font_buffer(cx)
+            .text_ui_sm(cx)
+            .child(
+                Label::new(address)
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .children(bytes.iter().enumerate().map(|(ix, byte)| {
+                        let byte_ix = start + ix;
+                        let is_selected = self.selected_byte == Some(byte_ix);
+                        div()
+                            .id(("memory-byte", byte_ix))
+                            .cursor_pointer()
+                            .rounded_sm()
+                            .when(is_selected, |this| {
+                                this.bg(cx.theme().colors().element_selected)
+                            })
+                            .hover(|style| style.bg(cx.theme().colors().element_hover))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.selected_byte = Some(byte_ix);
+                                cx.notify();
+                            }))
+                            .child(
+                                Label::new(format!("{byte:02x}"))
+                                    .buffer_font(cx)
+                                    .size(LabelSize::Small),
+                            )
+                    })),
+            )
+            .child(
+                Label::new(ascii)
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+    }
+}
+
+/// Parses bytes written as hex, e.g. `de ad be ef` or `deadbeef`.
+fn parse_hex_bytes(text: &str) -> Option<Vec<u8>> {
+    let digits = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return None;
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let pair = pair.iter().collect::<String>();
+            u8::from_str_radix(&pair, 16).ok()
+        })
+        .collect()
+}
+
+impl Focusable for MemoryView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for MemoryView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let supports_write = self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_write_memory_request
+            .unwrap_or_default();
+        let base_address = self
+            .contents
+            .as_ref()
+            .and_then(|contents| parse_address(&contents.address));
+        let rows = self
+            .contents
+            .as_ref()
+            .map(|contents| {
+                contents
+                    .data
+                    .chunks(BYTES_PER_ROW)
+                    .enumerate()
+                    .map(|(row, bytes)| self.render_row(row, base_address, bytes, cx))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let unreadable_bytes = self
+            .contents
+            .as_ref()
+            .map_or(0, |contents| contents.unreadable_bytes);
+        let has_memory_reference = self.memory_reference.is_some();
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .key_context("MemoryView")
+            .size_full()
+            .child(
+                h_flex()
+                    .p_1()
+                    .gap_1()
+                    .child(
+                        div()
+                            .flex_1()
+                            .on_action(cx.listener(Self::confirm_address))
+                            .child(self.address_editor.clone()),
+                    )
+                    .child(
+                        IconButton::new("memory-previous-page", IconName::ChevronUp)
+                            .icon_size(IconSize::XSmall)
+                            .disabled(!has_memory_reference)
+                            .on_click(cx.listener(|this, _, _, cx| this.scroll_page(false, cx)))
+                            .tooltip(Tooltip::text("Previous Page")),
+                    )
+                    .child(
+                        IconButton::new("memory-next-page", IconName::ChevronDown)
+                            .icon_size(IconSize::XSmall)
+                            .disabled(!has_memory_reference)
+                            .on_click(cx.listener(|this, _, _, cx| this.scroll_page(true, cx)))
+                            .tooltip(Tooltip::text("Next Page")),
+                    ),
+            )
+            .child(Divider::horizontal())
+            .child(
+                v_flex()
+                    .id("memory-rows")
+                    .flex_1()
+                    .p_1()
+                    .overflow_y_scroll()
+                    .children(rows)
+                    .when(unreadable_bytes > 0, |this| {
+                        this.child(
+                            Label::new(format!("{unreadable_bytes} bytes couldn't be read"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .when_some(self.error.clone(), |this, error| {
+                        this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+                    }),
+            )
+            .when(supports_write && self.selected_byte.is_some(), |this| {
+                this.child(Divider::horizontal()).child(
+                    div()
+                        .p_1()
+                        .on_action(cx.listener(Self::confirm_write))
+                        .child(self.write_editor.clone()),
+                )
+            })
+    }
+}
@@ -2,9 +2,9 @@ use super::stack_frame_list::{StackFrameList, StackFrameListEvent};
 use dap::{ScopePresentationHint, StackFrameId, VariablePresentationHintKind, VariableReference};
 use editor::Editor;
 use gpui::{
-    AnyElement, ClickEvent, ClipboardItem, Context, DismissEvent, Entity, FocusHandle, Focusable,
-    Hsla, MouseButton, MouseDownEvent, Point, Stateful, Subscription, TextStyleRefinement,
-    UniformListScrollHandle, actions, anchored, deferred, uniform_list,
+    AnyElement, ClickEvent, ClipboardItem, Context, DismissEvent, Entity, EventEmitter,
+    FocusHandle, Focusable, Hsla, MouseButton, MouseDownEvent, Point, Stateful, Subscription,
+    TextStyleRefinement, UniformListScrollHandle, actions, anchored, deferred, uniform_list,
 };
 use menu::{SelectFirst, SelectLast, SelectNext, SelectPrevious};
 use project::debugger::session::{Session, SessionEvent};
@@ -128,6 +128,11 @@ impl ListEntry {
     }
 }
 
+pub(crate) enum VariableListEvent {
+    /// The user asked to see the memory that a variable refers to.
+    ViewMemory(String),
+}
+
 pub struct VariableList {
     entries: Vec<ListEntry>,
     entry_states: HashMap<EntryPath, EntryState>,
@@ -504,26 +509,87 @@ impl VariableList {
 
         let variable_value = dap_var.value.clone();
         let variable_name = dap_var.name.clone();
+        let watch_expression = dap_var
+            .evaluate_name
+            .clone()
+            .unwrap_or_else(|| dap_var.name.clone());
+        let parent_reference = self
+            .entry_states
+            .get(&variable.path)
+            .map(|state| state.parent_reference);
+        let supports_data_breakpoints = self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_data_breakpoints
+            .unwrap_or_default();
+        let supports_read_memory = self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_read_memory_request
+            .unwrap_or_default();
+        let memory_reference = dap_var
+            .memory_reference
+            .clone()
+            .filter(|_| supports_read_memory);
+        let session = self.session.clone();
+        let frame_id = self.selected_stack_frame_id;
+        let weak = cx.weak_entity();
         let this = cx.entity().clone();
 
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
-            menu.entry("Copy name", None, move |_, cx| {
-                cx.write_to_clipboard(ClipboardItem::new_string(variable_name.clone()))
-            })
-            .entry("Copy value", None, {
-                let variable_value = variable_value.clone();
-                move |_, cx| {
-                    cx.write_to_clipboard(ClipboardItem::new_string(variable_value.clone()))
-                }
-            })
-            .entry("Set value", None, move |window, cx| {
-                this.update(cx, |variable_list, cx| {
-                    let editor = Self::create_variable_editor(&variable_value, window, cx);
-                    variable_list.edited_path = Some((variable.path.clone(), editor));
+            let menu = menu
+                .entry("Copy name", None, {
+                    let variable_name = variable_name.clone();
+                    move |_, cx| {
+                        cx.write_to_clipboard(ClipboardItem::new_string(variable_name.clone()))
+                    }
+                })
+                .entry("Copy value", None, {
+                    let variable_value = variable_value.clone();
+                    move |_, cx| {
+                        cx.write_to_clipboard(ClipboardItem::new_string(variable_value.clone()))
+                    }
+                })
+                .entry("Set value", None, move |window, cx| {
+                    this.update(cx, |variable_list, cx| {
+                        let editor = Self::create_variable_editor(&variable_value, window, cx);
+                        variable_list.edited_path = Some((variable.path.clone(), editor));
 
-                    cx.notify();
+                        cx.notify();
+                    });
+                })
+                .entry("Add to Watch", None, {
+                    let session = session.clone();
+                    move |_, cx| {
+                        session.update(cx, |session, cx| {
+                            session.add_watcher(watch_expression.clone().into(), frame_id, cx);
+                        });
+                    }
                 });
-            })
+            let menu = match memory_reference {
+                Some(memory_reference) => menu.entry("View Memory", None, move |_, cx| {
+                    weak.update(cx, |_, cx| {
+                        cx.emit(VariableListEvent::ViewMemory(memory_reference.clone()));
+                    })
+                    .ok();
+                }),
+                None => menu,
+            };
+
+            match parent_reference {
+                Some(parent_reference) if supports_data_breakpoints => {
+                    menu.entry("Break on Value Change", None, move |_, cx| {
+                        session.update(cx, |session, cx| {
+                            session
+                                .add_data_breakpoint(parent_reference, variable_name.clone(), cx)
+                                .detach_and_log_err(cx);
+                        });
+                    })
+                }
+                _ => menu,
+            }
         });
 
         cx.focus_view(&context_menu, window);
@@ -921,6 +987,8 @@ impl VariableList {
     }
 }
 
+impl EventEmitter<VariableListEvent> for VariableList {}
+
 impl Focusable for VariableList {
     fn focus_handle(&self, _: &App) -> gpui::FocusHandle {
         self.focus_handle.clone()
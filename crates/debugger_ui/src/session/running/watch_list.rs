@@ -0,0 +1,158 @@
+use super::stack_frame_list::{StackFrameList, StackFrameListEvent};
+use editor::Editor;
+use gpui::{Entity, FocusHandle, Focusable, Subscription};
+use menu::Confirm;
+use project::debugger::session::{Session, SessionEvent, Watcher};
+use ui::{Divider, IconButton, ListItem, prelude::*};
+
+/// Shows the expressions the user wants to keep an eye on, along with their values as of
+/// the last stop.
+pub(crate) struct WatchList {
+    session: Entity<Session>,
+    stack_frame_list: Entity<StackFrameList>,
+    query_bar: Entity<Editor>,
+    focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl WatchList {
+    pub fn new(
+        session: Entity<Session>,
+        stack_frame_list: Entity<StackFrameList>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+        let query_bar = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Add a watch expression", cx);
+            editor.set_use_autoclose(false);
+            editor
+        });
+
+        let _subscriptions = vec![
+            cx.subscribe(&stack_frame_list, Self::handle_stack_frame_list_events),
+            cx.subscribe(&session, |_, _, event, cx| match event {
+                SessionEvent::Watchers => cx.notify(),
+                _ => {}
+            }),
+            cx.on_focus_in(&focus_handle, window, |this, window, cx| {
+                this.query_bar.focus_handle(cx).focus(window);
+            }),
+        ];
+
+        Self {
+            session,
+            stack_frame_list,
+            query_bar,
+            focus_handle,
+            _subscriptions,
+        }
+    }
+
+    fn handle_stack_frame_list_events(
+        &mut self,
+        _: Entity<StackFrameList>,
+        event: &StackFrameListEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            StackFrameListEvent::SelectedStackFrameChanged(stack_frame_id) => {
+                self.session.update(cx, |session, cx| {
+                    session.refresh_watchers(Some(*stack_frame_id), cx)
+                });
+            }
+            StackFrameListEvent::BuiltEntries => {}
+        }
+    }
+
+    fn add_watcher(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let expression = self.query_bar.update(cx, |editor, cx| {
+            let expression = editor.text(cx);
+            editor.clear(window, cx);
+            expression
+        });
+        let expression = expression.trim();
+        if expression.is_empty() {
+            return;
+        }
+
+        let frame_id = self.stack_frame_list.read(cx).opened_stack_frame_id();
+        self.session.update(cx, |session, cx| {
+            session.add_watcher(SharedString::from(expression.to_string()), frame_id, cx)
+        });
+    }
+
+    fn render_watcher(&self, ix: usize, watcher: &Watcher, cx: &Context<Self>) -> impl IntoElement {
+        let expression = watcher.expression.clone();
+        let value = match &watcher.value {
+            Some(value) => Label::new(format!("= {value}"))
+                .size(LabelSize::Small)
+                .color(if watcher.is_error {
+                    Color::Error
+                } else {
+                    Color::Muted
+                }),
+            None => Label::new("Not evaluated yet")
+                .size(LabelSize::Small)
+                .color(Color::Disabled),
+        };
+
+        ListItem::new(("watcher", ix))
+            .rounded()
+            .end_hover_slot(
+                IconButton::new(("remove-watcher", ix), IconName::Close)
+                    .icon_size(IconSize::XSmall)
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.session
+                            .update(cx, |session, cx| session.remove_watcher(&expression, cx));
+                    })),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .text_ui_sm(cx)
+                    .w_full()
+                    .child(Label::new(watcher.expression.clone()).size(LabelSize::Small))
+                    .child(value.single_line().truncate()),
+            )
+    }
+}
+
+impl Focusable for WatchList {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for WatchList {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let watchers = self
+            .session
+            .read(cx)
+            .watchers()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .key_context("WatchList")
+            .on_action(cx.listener(Self::add_watcher))
+            .size_full()
+            .p_1()
+            .child(
+                v_flex()
+                    .id("watch-list")
+                    .flex_grow()
+                    .overflow_y_scroll()
+                    .children(
+                        watchers
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, watcher)| self.render_watcher(ix, watcher, cx)),
+                    ),
+            )
+            .child(Divider::horizontal())
+            .child(div().px_1().child(self.query_bar.clone()))
+    }
+}
@@ -23,6 +23,8 @@ mod debugger_panel;
 #[cfg(test)]
 mod inline_values;
 #[cfg(test)]
+mod memory_view;
+#[cfg(test)]
 mod module_list;
 #[cfg(test)]
 mod persistence;
@@ -30,6 +32,8 @@ mod persistence;
 mod stack_frame_list;
 #[cfg(test)]
 mod variable_list;
+#[cfg(test)]
+mod watch_list;
 
 pub fn init_test(cx: &mut gpui::TestAppContext) {
     if std::env::var("RUST_LOG").is_ok() {
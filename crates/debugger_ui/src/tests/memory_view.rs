@@ -0,0 +1,121 @@
+use crate::{
+    debugger_panel::DebugPanel,
+    tests::{init_test, init_test_workspace, start_debug_session},
+};
+use base64::Engine as _;
+use dap::requests::{Disassemble, Initialize, ReadMemory, WriteMemory};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{FakeFs, Project};
+use std::sync::{Arc, Mutex};
+use util::path;
+
+#[gpui::test]
+async fn test_read_and_write_memory(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_read_memory_request: Some(true),
+                supports_write_memory_request: Some(true),
+                supports_disassemble_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    cx.run_until_parked();
+
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    let memory = Arc::new(Mutex::new(vec![0x10u8, 0x20, 0x30, 0x40]));
+    client.on_request::<ReadMemory, _>({
+        let memory = memory.clone();
+        move |_, args| {
+            assert_eq!(args.memory_reference, "0x1000");
+            let offset = args.offset.unwrap_or_default() as usize;
+            let memory = memory.lock().unwrap();
+            let end = (offset + args.count as usize).min(memory.len());
+            Ok(dap::ReadMemoryResponse {
+                address: format!("{:#x}", 0x1000 + offset),
+                unreadable_bytes: Some((offset + args.count as usize - end) as u64),
+                data: Some(base64::engine::general_purpose::STANDARD.encode(&memory[offset..end])),
+            })
+        }
+    });
+    client.on_request::<WriteMemory, _>({
+        let memory = memory.clone();
+        move |_, args| {
+            let offset = args.offset.unwrap_or_default() as usize;
+            let data = base64::engine::general_purpose::STANDARD
+                .decode(args.data)
+                .unwrap();
+            memory.lock().unwrap()[offset..offset + data.len()].copy_from_slice(&data);
+            Ok(dap::WriteMemoryResponse {
+                offset: None,
+                bytes_written: Some(data.len() as u64),
+            })
+        }
+    });
+    client.on_request::<Disassemble, _>(move |_, args| {
+        assert_eq!(args.memory_reference, "0x1000");
+        assert_eq!(args.instruction_offset, Some(-1));
+        Ok(dap::DisassembleResponse {
+            instructions: (0..args.instruction_count)
+                .map(|ix| dap::DisassembledInstruction {
+                    address: format!("{:#x}", 0x1000 + (ix - 1) * 4),
+                    instruction_bytes: None,
+                    instruction: format!("nop {ix}"),
+                    symbol: None,
+                    location: None,
+                    line: None,
+                    column: None,
+                    end_line: None,
+                    end_column: None,
+                    presentation_hint: None,
+                })
+                .collect(),
+        })
+    });
+
+    let contents = session
+        .update(cx, |session, cx| {
+            session.read_memory("0x1000".into(), 1, 8, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(contents.address, "0x1001");
+    assert_eq!(contents.data, vec![0x20, 0x30, 0x40]);
+    assert_eq!(contents.unreadable_bytes, 5);
+
+    session
+        .update(cx, |session, cx| {
+            session.write_memory("0x1000".into(), 2, &[0xaa, 0xbb], cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(*memory.lock().unwrap(), vec![0x10, 0x20, 0xaa, 0xbb]);
+
+    let instructions = session
+        .update(cx, |session, _| session.disassemble("0x1000".into(), -1, 3))
+        .await
+        .unwrap();
+    assert_eq!(
+        instructions
+            .iter()
+            .map(|instruction| instruction.address.as_str())
+            .collect::<Vec<_>>(),
+        vec!["0xffc", "0x1000", "0x1004"]
+    );
+}
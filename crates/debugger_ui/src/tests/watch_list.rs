@@ -0,0 +1,137 @@
+use crate::{
+    debugger_panel::DebugPanel,
+    tests::{init_test, init_test_workspace, start_debug_session},
+};
+use dap::requests::Evaluate;
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{FakeFs, Project};
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+use util::path;
+
+#[gpui::test]
+async fn test_watchers_are_reevaluated(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    let evaluations = Arc::new(AtomicUsize::new(0));
+    client.on_request::<Evaluate, _>({
+        let evaluations = evaluations.clone();
+        move |_, args| {
+            assert_eq!(Some(dap::EvaluateArgumentsContext::Watch), args.context);
+            assert_eq!(Some(1), args.frame_id);
+            if args.expression == "missing" {
+                return Err(dap::ErrorResponse {
+                    error: Some(dap::Message {
+                        id: 1,
+                        format: "not available".into(),
+                        variables: None,
+                        send_telemetry: None,
+                        show_user: None,
+                        url: None,
+                        url_label: None,
+                    }),
+                });
+            }
+
+            let count = evaluations.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(dap::EvaluateResponse {
+                result: format!("{} = {count}", args.expression),
+                type_: None,
+                presentation_hint: None,
+                variables_reference: 0,
+                named_variables: None,
+                indexed_variables: None,
+                memory_reference: None,
+                value_location_reference: None,
+            })
+        }
+    });
+
+    session.update(cx, |session, cx| {
+        session.add_watcher("counter".into(), Some(1), cx);
+        session.add_watcher("missing".into(), Some(1), cx);
+        session.add_watcher("counter".into(), Some(1), cx);
+    });
+    cx.run_until_parked();
+
+    session.update(cx, |session, _| {
+        let watchers = session
+            .watchers()
+            .map(|watcher| {
+                (
+                    watcher.expression.to_string(),
+                    watcher.value.is_some(),
+                    watcher.is_error,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            watchers,
+            vec![
+                ("counter".to_string(), true, false),
+                ("missing".to_string(), true, true),
+            ]
+        );
+        assert_eq!(
+            session.watchers().next().unwrap().value.as_deref(),
+            Some("counter = 1")
+        );
+    });
+
+    session.update(cx, |session, cx| session.refresh_watchers(Some(1), cx));
+    cx.run_until_parked();
+
+    session.update(cx, |session, cx| {
+        assert_eq!(
+            session.watchers().next().unwrap().value.as_deref(),
+            Some("counter = 2")
+        );
+        session.remove_watcher("missing", cx);
+        assert_eq!(session.watchers().count(), 1);
+    });
+    assert_eq!(evaluations.load(Ordering::SeqCst), 2);
+
+    // Watch expressions belong to the project, so that they outlive the session.
+    let breakpoint_store = project.read_with(cx, |project, _| project.breakpoint_store());
+    breakpoint_store.read_with(cx, |store, _| {
+        assert_eq!(
+            store
+                .watch_expressions()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["counter".to_string()]
+        );
+    });
+
+    breakpoint_store.update(cx, |store, cx| {
+        store.add_watch_expression("total".into(), cx);
+    });
+    cx.run_until_parked();
+    session.read_with(cx, |session, _| {
+        let watchers = session
+            .watchers()
+            .map(|watcher| (watcher.expression.to_string(), watcher.value.is_some()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            watchers,
+            vec![("counter".to_string(), true), ("total".to_string(), false)]
+        );
+    });
+}
@@ -82,6 +82,7 @@ pub trait ToDisplayPoint {
 
 type TextHighlights = TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = TreeMap<TypeId, TreeMap<InlayId, (HighlightStyle, InlayHighlight)>>;
+type SemanticTokenHighlights = Arc<[(Range<Anchor>, HighlightStyle)]>;
 
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
 /// folding, hard tabs, soft wrapping, custom blocks (like diagnostics), and highlighting.
@@ -105,6 +106,8 @@ pub struct DisplayMap {
     text_highlights: TextHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// Regions of text highlighted according to the semantic tokens of language servers.
+    semantic_token_highlights: SemanticTokenHighlights,
     /// A container for explicitly foldable ranges, which supersede indentation based fold range suggestions.
     crease_map: CreaseMap,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -151,6 +154,7 @@ impl DisplayMap {
             diagnostics_max_severity,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            semantic_token_highlights: Default::default(),
             clip_at_line_ends: false,
             masked: false,
         }
@@ -179,6 +183,7 @@ impl DisplayMap {
             crease_snapshot: self.crease_map.snapshot(),
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            semantic_token_highlights: self.semantic_token_highlights.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
             masked: self.masked,
             fold_placeholder: self.fold_placeholder.clone(),
@@ -504,6 +509,15 @@ impl DisplayMap {
         let highlights = self.text_highlights.get(&type_id)?;
         Some((highlights.0, &highlights.1))
     }
+
+    /// Replaces the semantic token highlights, which must be sorted and non-overlapping.
+    pub(crate) fn set_semantic_token_highlights(
+        &mut self,
+        highlights: Vec<(Range<Anchor>, HighlightStyle)>,
+    ) {
+        self.semantic_token_highlights = highlights.into();
+    }
+
     pub fn clear_highlights(&mut self, type_id: TypeId) -> bool {
         let mut cleared = self.text_highlights.remove(&type_id).is_some();
         cleared |= self.inlay_highlights.remove(&type_id).is_some();
@@ -614,6 +628,7 @@ impl DisplayMap {
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
+    pub semantic_token_highlights: Option<&'a [(Range<Anchor>, HighlightStyle)]>,
     pub styles: HighlightStyles,
 }
 
@@ -748,6 +763,7 @@ pub struct DisplaySnapshot {
     block_snapshot: BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_token_highlights: SemanticTokenHighlights,
     clip_at_line_ends: bool,
     masked: bool,
     diagnostics_max_severity: DiagnosticSeverity,
@@ -919,6 +935,7 @@ impl DisplaySnapshot {
             Highlights {
                 text_highlights: Some(&self.text_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
+                semantic_token_highlights: Some(&self.semantic_token_highlights),
                 styles: highlight_styles,
             },
         )
@@ -2627,6 +2644,67 @@ pub mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_chunks_with_semantic_token_highlights(cx: &mut gpui::TestAppContext) {
+        cx.update(|cx| init_test(cx, |_| {}));
+
+        let text = "let mut x = foo::bar();";
+        let buffer = cx.update(|cx| MultiBuffer::build_simple(text, cx));
+        let buffer_snapshot = buffer.read_with(cx, |buffer, cx| buffer.snapshot(cx));
+        let map = cx.new(|cx| {
+            DisplayMap::new(
+                buffer,
+                font("Courier"),
+                px(16.0),
+                None,
+                1,
+                1,
+                FoldPlaceholder::test(),
+                DiagnosticSeverity::Warning,
+                cx,
+            )
+        });
+
+        let style = |color| HighlightStyle {
+            color: Some(color),
+            ..Default::default()
+        };
+        let range = |range: Range<usize>| {
+            buffer_snapshot.anchor_after(range.start)..buffer_snapshot.anchor_before(range.end)
+        };
+        enum MyType {}
+        map.update(cx, |map, _| {
+            map.set_semantic_token_highlights(vec![
+                (range(8..9), style(Hsla::red())),
+                (range(12..15), style(Hsla::green())),
+                (range(15..17), style(Hsla::blue())),
+            ]);
+            // Text highlights take precedence over semantic tokens.
+            map.highlight_text(
+                TypeId::of::<MyType>(),
+                vec![range(8..9)],
+                style(Hsla::white()),
+            );
+        });
+
+        assert_eq!(
+            cx.update(|cx| chunks(
+                DisplayRow(0)..DisplayRow(1),
+                &map,
+                &SyntaxTheme::default(),
+                cx
+            )),
+            [
+                ("let mut ".to_string(), None, None),
+                ("x".to_string(), None, Some(Hsla::white())),
+                (" = ".to_string(), None, None),
+                ("foo".to_string(), None, Some(Hsla::green())),
+                ("::".to_string(), None, Some(Hsla::blue())),
+                ("bar();".to_string(), None, None),
+            ]
+        );
+    }
+
     #[gpui::test]
     fn test_clip_point(cx: &mut gpui::App) {
         init_test(cx, |_| {});
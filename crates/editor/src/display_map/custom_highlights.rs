@@ -19,34 +19,48 @@ pub struct CustomHighlightsChunks<'a> {
     multibuffer_snapshot: &'a MultiBufferSnapshot,
 
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
-    active_highlights: BTreeMap<TypeId, HighlightStyle>,
+    active_highlights: BTreeMap<HighlightKey, HighlightStyle>,
     text_highlights: Option<&'a TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+    semantic_token_highlights: Option<&'a [(Range<Anchor>, HighlightStyle)]>,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 struct HighlightEndpoint {
     offset: usize,
     is_start: bool,
-    tag: TypeId,
+    tag: HighlightKey,
     style: HighlightStyle,
 }
 
+/// Identifies an active highlight. Semantic tokens sort first, so that
+/// text highlights are layered on top of them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+enum HighlightKey {
+    SemanticToken(usize),
+    Text(TypeId),
+}
+
 impl<'a> CustomHighlightsChunks<'a> {
     pub fn new(
         range: Range<usize>,
         language_aware: bool,
         text_highlights: Option<&'a TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+        semantic_token_highlights: Option<&'a [(Range<Anchor>, HighlightStyle)]>,
         multibuffer_snapshot: &'a MultiBufferSnapshot,
     ) -> Self {
+        // Semantic tokens refine the syntax highlighting, so they are only shown alongside it.
+        let semantic_token_highlights = semantic_token_highlights.filter(|_| language_aware);
         Self {
             buffer_chunks: multibuffer_snapshot.chunks(range.clone(), language_aware),
             buffer_chunk: None,
             offset: range.start,
 
             text_highlights,
+            semantic_token_highlights,
             highlight_endpoints: create_highlight_endpoints(
                 &range,
                 text_highlights,
+                semantic_token_highlights,
                 multibuffer_snapshot,
             ),
             active_highlights: Default::default(),
@@ -55,8 +69,12 @@ impl<'a> CustomHighlightsChunks<'a> {
     }
 
     pub fn seek(&mut self, new_range: Range<usize>) {
-        self.highlight_endpoints =
-            create_highlight_endpoints(&new_range, self.text_highlights, self.multibuffer_snapshot);
+        self.highlight_endpoints = create_highlight_endpoints(
+            &new_range,
+            self.text_highlights,
+            self.semantic_token_highlights,
+            self.multibuffer_snapshot,
+        );
         self.offset = new_range.start;
         self.buffer_chunks.seek(new_range);
         self.buffer_chunk.take();
@@ -67,27 +85,18 @@ impl<'a> CustomHighlightsChunks<'a> {
 fn create_highlight_endpoints(
     range: &Range<usize>,
     text_highlights: Option<&TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+    semantic_token_highlights: Option<&[(Range<Anchor>, HighlightStyle)]>,
     buffer: &MultiBufferSnapshot,
 ) -> iter::Peekable<vec::IntoIter<HighlightEndpoint>> {
     let mut highlight_endpoints = Vec::new();
+    let start = buffer.anchor_after(range.start);
+    let end = buffer.anchor_after(range.end);
     if let Some(text_highlights) = text_highlights {
-        let start = buffer.anchor_after(range.start);
-        let end = buffer.anchor_after(range.end);
         for (&tag, text_highlights) in text_highlights.iter() {
             let style = text_highlights.0;
             let ranges = &text_highlights.1;
 
-            let start_ix = match ranges.binary_search_by(|probe| {
-                let cmp = probe.end.cmp(&start, &buffer);
-                if cmp.is_gt() {
-                    cmp::Ordering::Greater
-                } else {
-                    cmp::Ordering::Less
-                }
-            }) {
-                Ok(i) | Err(i) => i,
-            };
-
+            let start_ix = first_range_ending_after(ranges, |range| range, &start, buffer);
             for range in &ranges[start_ix..] {
                 if range.start.cmp(&end, &buffer).is_ge() {
                     break;
@@ -96,22 +105,67 @@ fn create_highlight_endpoints(
                 highlight_endpoints.push(HighlightEndpoint {
                     offset: range.start.to_offset(&buffer),
                     is_start: true,
-                    tag,
+                    tag: HighlightKey::Text(tag),
                     style,
                 });
                 highlight_endpoints.push(HighlightEndpoint {
                     offset: range.end.to_offset(&buffer),
                     is_start: false,
-                    tag,
+                    tag: HighlightKey::Text(tag),
                     style,
                 });
             }
         }
-        highlight_endpoints.sort();
     }
+    if let Some(semantic_token_highlights) = semantic_token_highlights {
+        let start_ix = first_range_ending_after(
+            semantic_token_highlights,
+            |(range, _)| range,
+            &start,
+            buffer,
+        );
+        for (ix, (range, style)) in semantic_token_highlights.iter().enumerate().skip(start_ix) {
+            if range.start.cmp(&end, &buffer).is_ge() {
+                break;
+            }
+
+            let tag = HighlightKey::SemanticToken(ix);
+            highlight_endpoints.push(HighlightEndpoint {
+                offset: range.start.to_offset(&buffer),
+                is_start: true,
+                tag,
+                style: *style,
+            });
+            highlight_endpoints.push(HighlightEndpoint {
+                offset: range.end.to_offset(&buffer),
+                is_start: false,
+                tag,
+                style: *style,
+            });
+        }
+    }
+    highlight_endpoints.sort();
     highlight_endpoints.into_iter().peekable()
 }
 
+fn first_range_ending_after<T>(
+    items: &[T],
+    range: impl Fn(&T) -> &Range<Anchor>,
+    start: &Anchor,
+    buffer: &MultiBufferSnapshot,
+) -> usize {
+    match items.binary_search_by(|probe| {
+        let cmp = range(probe).end.cmp(start, buffer);
+        if cmp.is_gt() {
+            cmp::Ordering::Greater
+        } else {
+            cmp::Ordering::Less
+        }
+    }) {
+        Ok(i) | Err(i) => i,
+    }
+}
+
 impl<'a> Iterator for CustomHighlightsChunks<'a> {
     type Item = Chunk<'a>;
 
@@ -0,0 +1,133 @@
+use std::{mem, time::Duration};
+
+use clock::Global;
+use collections::HashMap;
+use futures::future::join_all;
+use gpui::{App, Context, Entity, Task};
+use language::Buffer;
+use project::DocumentLink;
+use text::BufferId;
+
+use crate::Editor;
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// The document links that the language servers reported for the buffers of an editor.
+#[derive(Default)]
+pub(super) struct DocumentLinksCache {
+    links: HashMap<BufferId, CachedDocumentLinks>,
+    pending_buffers: HashMap<BufferId, Entity<Buffer>>,
+    _refresh_task: Option<Task<()>>,
+}
+
+struct CachedDocumentLinks {
+    version: Global,
+    links: Vec<DocumentLink>,
+}
+
+/// Queries the language servers for the document links of the given buffer, or of
+/// every buffer in the editor, unless the cached links are still up to date.
+pub(super) fn refresh_document_links(
+    editor: &mut Editor,
+    for_buffer: Option<BufferId>,
+    cx: &mut Context<Editor>,
+) {
+    if !editor.mode().is_full() || editor.semantics_provider.is_none() {
+        return;
+    }
+
+    for buffer in editor.buffer.read(cx).all_buffers() {
+        let buffer_id = buffer.read(cx).remote_id();
+        if for_buffer.is_some_and(|for_buffer| for_buffer != buffer_id) {
+            continue;
+        }
+        let up_to_date = editor
+            .document_links
+            .links
+            .get(&buffer_id)
+            .is_some_and(|cached| cached.version == buffer.read(cx).version());
+        if !up_to_date {
+            editor
+                .document_links
+                .pending_buffers
+                .insert(buffer_id, buffer);
+        }
+    }
+    if editor.document_links.pending_buffers.is_empty() {
+        return;
+    }
+
+    editor.document_links._refresh_task = Some(cx.spawn(async move |editor, cx| {
+        cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+
+        let Ok(requests) = editor.update(cx, |editor, cx| {
+            let provider = editor.semantics_provider.clone()?;
+            let buffers = mem::take(&mut editor.document_links.pending_buffers);
+            Some(
+                buffers
+                    .into_iter()
+                    .filter_map(|(buffer_id, buffer)| {
+                        let version = buffer.read(cx).version();
+                        let request = provider.document_links(&buffer, cx)?;
+                        Some(async move { (buffer_id, version, request.await) })
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        }) else {
+            return;
+        };
+        let Some(requests) = requests else {
+            return;
+        };
+        let responses = join_all(requests).await;
+
+        editor
+            .update(cx, |editor, _| {
+                for (buffer_id, version, response) in responses {
+                    match response {
+                        Ok(links) => {
+                            editor
+                                .document_links
+                                .links
+                                .insert(buffer_id, CachedDocumentLinks { version, links });
+                        }
+                        Err(error) => {
+                            log::error!("failed to fetch document links: {error:#}");
+                        }
+                    }
+                }
+            })
+            .ok();
+    }));
+}
+
+/// Forgets the links of buffers that are no longer part of the editor.
+pub(super) fn remove_document_links(editor: &mut Editor, buffer_ids: &[BufferId]) {
+    for buffer_id in buffer_ids {
+        editor.document_links.pending_buffers.remove(buffer_id);
+        editor.document_links.links.remove(buffer_id);
+    }
+}
+
+/// Returns the cached link that contains the given position, if the buffer hasn't
+/// changed since the links were fetched.
+pub(super) fn document_link_at(
+    editor: &Editor,
+    buffer: &Entity<Buffer>,
+    position: text::Anchor,
+    cx: &App,
+) -> Option<DocumentLink> {
+    let buffer = buffer.read(cx);
+    let cached = editor.document_links.links.get(&buffer.remote_id())?;
+    if cached.version != buffer.version() {
+        return None;
+    }
+    cached
+        .links
+        .iter()
+        .find(|link| {
+            link.range.start.cmp(&position, buffer).is_le()
+                && link.range.end.cmp(&position, buffer).is_gt()
+        })
+        .cloned()
+}
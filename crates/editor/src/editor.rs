@@ -17,6 +17,7 @@ mod blink_manager;
 mod clangd_ext;
 mod code_context_menus;
 pub mod display_map;
+mod document_links;
 mod editor_settings;
 mod editor_settings_controls;
 mod element;
@@ -28,6 +29,7 @@ mod indent_guides;
 mod inlay_hint_cache;
 pub mod items;
 mod jsx_tag_auto_close;
+pub mod large_file_indicator;
 mod linked_editing_ranges;
 mod lsp_ext;
 mod mouse_context_menu;
@@ -37,6 +39,7 @@ mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod semantic_tokens;
 pub mod tasks;
 
 #[cfg(test)]
@@ -956,6 +959,8 @@ pub struct Editor {
     document_highlights_task: Option<Task<()>>,
     linked_editing_range_task: Option<Task<Option<()>>>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
+    semantic_tokens: semantic_tokens::SemanticTokensState,
+    document_links: document_links::DocumentLinksCache,
     pending_rename: Option<RenameState>,
     searchable: bool,
     cursor_shape: CursorShape,
@@ -1628,6 +1633,11 @@ impl Editor {
                             editor
                                 .refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
                         }
+                        project::Event::RefreshSemanticTokens
+                        | project::Event::LanguageServerAdded(..) => {
+                            semantic_tokens::refresh_semantic_tokens(editor, None, cx);
+                            document_links::refresh_document_links(editor, None, cx);
+                        }
                         project::Event::SnippetEdit(id, snippet_edits) => {
                             if let Some(buffer) = editor.buffer.read(cx).buffer(*id) {
                                 let focus_handle = editor.focus_handle(cx);
@@ -1882,6 +1892,8 @@ impl Editor {
             ],
             tasks_update_task: None,
             linked_edit_ranges: Default::default(),
+            semantic_tokens: Default::default(),
+            document_links: Default::default(),
             in_project_search: false,
             previous_search_ranges: None,
             breadcrumb_header: None,
@@ -1912,6 +1924,8 @@ impl Editor {
                 }));
         }
         this.tasks_update_task = Some(this.refresh_runnables(window, cx));
+        semantic_tokens::refresh_semantic_tokens(&mut this, None, cx);
+        document_links::refresh_document_links(&mut this, None, cx);
         this._subscriptions.extend(project_subscriptions);
 
         this._subscriptions.push(cx.subscribe_in(
@@ -18182,6 +18196,8 @@ impl Editor {
                 }
                 if let Some(buffer) = buffer_edited {
                     let buffer_id = buffer.read(cx).remote_id();
+                    semantic_tokens::refresh_semantic_tokens(self, Some(buffer_id), cx);
+                    document_links::refresh_document_links(self, Some(buffer_id), cx);
                     if !self.registered_buffers.contains_key(&buffer_id) {
                         if let Some(project) = self.project.as_ref() {
                             project.update(cx, |project, cx| {
@@ -18240,6 +18256,8 @@ impl Editor {
             } => {
                 self.tasks_update_task = Some(self.refresh_runnables(window, cx));
                 let buffer_id = buffer.read(cx).remote_id();
+                semantic_tokens::refresh_semantic_tokens(self, Some(buffer_id), cx);
+                document_links::refresh_document_links(self, Some(buffer_id), cx);
                 if self.buffer.read(cx).diff_for(buffer_id).is_none() {
                     if let Some(project) = &self.project {
                         update_uncommitted_diff_for_buffer(
@@ -18264,6 +18282,8 @@ impl Editor {
                 removed_buffer_ids,
             } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                semantic_tokens::remove_semantic_tokens(self, removed_buffer_ids, cx);
+                document_links::remove_document_links(self, removed_buffer_ids);
                 let buffer = self.buffer.read(cx);
                 self.registered_buffers
                     .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
@@ -18299,6 +18319,8 @@ impl Editor {
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, window, cx);
+                semantic_tokens::refresh_semantic_tokens(self, Some(*buffer_id), cx);
+                document_links::refresh_document_links(self, Some(*buffer_id), cx);
                 jsx_tag_auto_close::refresh_enabled_in_any_buffer(self, multibuffer, cx);
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
@@ -18364,6 +18386,7 @@ impl Editor {
         };
         self.set_max_diagnostics_severity(new_severity, cx);
         self.tasks_update_task = Some(self.refresh_runnables(window, cx));
+        semantic_tokens::refresh_semantic_tokens(self, None, cx);
         self.update_edit_prediction_settings(cx);
         self.refresh_inline_completion(true, false, window, cx);
         self.refresh_inlay_hints(
@@ -19781,6 +19804,19 @@ pub trait SemanticsProvider {
 
     fn supports_inlay_hints(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool;
 
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<project::DocumentLink>>>>;
+
+    fn resolve_document_link(
+        &self,
+        link: project::DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<project::DocumentLink>>>;
+
     fn document_highlights(
         &self,
         buffer: &Entity<Buffer>,
@@ -20277,6 +20313,25 @@ impl SemanticsProvider for Entity<Project> {
         }))
     }
 
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<project::DocumentLink>>>> {
+        Some(self.update(cx, |project, cx| project.document_links(buffer, cx)))
+    }
+
+    fn resolve_document_link(
+        &self,
+        link: project::DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<project::DocumentLink>>> {
+        Some(self.update(cx, |project, cx| {
+            project.resolve_document_link(link, buffer, cx)
+        }))
+    }
+
     fn range_for_rename(
         &self,
         buffer: &Entity<Buffer>,
@@ -1,6 +1,7 @@
 use crate::{
     Anchor, Editor, EditorSettings, EditorSnapshot, FindAllReferences, GoToDefinition,
     GoToTypeDefinition, GotoDefinitionKind, InlayId, Navigated, PointForPosition, SelectPhase,
+    SemanticsProvider, document_links,
     editor_settings::{GoToDefinitionFallback, MultiCursorModifier},
     hover_popover::{self, InlayHover},
     scroll::ScrollAmount,
@@ -10,11 +11,11 @@ use language::{Bias, ToOffset};
 use linkify::{LinkFinder, LinkKind};
 use lsp::LanguageServerId;
 use project::{
-    HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip, LocationLink, Project,
-    ResolveState, ResolvedPath,
+    DocumentLink, HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip,
+    LocationLink, Project, ResolveState, ResolvedPath,
 };
 use settings::Settings;
-use std::ops::Range;
+use std::{ops::Range, rc::Rc};
 use theme::ActiveTheme as _;
 use util::{ResultExt, TryFutureExt as _, maybe};
 
@@ -532,13 +533,29 @@ pub fn show_link_definition(
     }
     let project = editor.project.clone();
     let provider = editor.semantics_provider.clone();
+    let document_link = document_links::document_link_at(editor, &buffer, buffer_position, cx);
 
     let snapshot = snapshot.buffer_snapshot.clone();
     hovered_link_state.task = Some(cx.spawn_in(window, async move |this, cx| {
         async move {
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, buffer_position, cx.clone()) {
+                    let document_link = match document_link {
+                        Some(link) => {
+                            resolve_document_link(link, &buffer, provider.as_ref(), cx).await
+                        }
+                        None => None,
+                    };
+                    if let Some((link_range, link)) = document_link {
+                        let range = maybe!({
+                            let start = snapshot.anchor_in_excerpt(excerpt_id, link_range.start)?;
+                            let end = snapshot.anchor_in_excerpt(excerpt_id, link_range.end)?;
+                            Some(RangeInEditor::Text(start..end))
+                        });
+                        Some((range, vec![link]))
+                    } else if let Some((url_range, url)) =
+                        find_url(&buffer, buffer_position, cx.clone())
+                    {
                         this.update(cx, |_, _| {
                             let range = maybe!({
                                 let start =
@@ -658,6 +675,39 @@ pub fn show_link_definition(
     editor.hovered_link_state = Some(hovered_link_state);
 }
 
+/// Turns a language server's document link into a hover link, asking the server for
+/// the link's target first if it didn't report one.
+async fn resolve_document_link(
+    link: DocumentLink,
+    buffer: &Entity<language::Buffer>,
+    provider: Option<&Rc<dyn SemanticsProvider>>,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let link = match (&link.target, provider) {
+        (None, Some(provider)) => {
+            let task = cx
+                .update(|_, cx| provider.resolve_document_link(link.clone(), buffer.clone(), cx))
+                .ok()
+                .flatten();
+            match task {
+                Some(task) => task.await.log_err().unwrap_or(link),
+                None => link,
+            }
+        }
+        _ => link,
+    };
+
+    let target = link.target?;
+    let hover_link = match target.to_file_path() {
+        Ok(path) if target.scheme() == "file" => HoverLink::File(ResolvedPath::AbsPath {
+            path,
+            is_dir: false,
+        }),
+        _ => HoverLink::Url(target.to_string()),
+    };
+    Some((link.range, hover_link))
+}
+
 pub(crate) fn find_url(
     buffer: &Entity<language::Buffer>,
     position: text::Anchor,
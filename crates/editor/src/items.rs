@@ -1379,6 +1379,79 @@ fn clip_ranges<'a>(
 
 impl EventEmitter<SearchEvent> for Editor {}
 
+/// Returns the text to replace the match of the query with, if the query has a replacement.
+fn replacement_for_match(
+    snapshot: &MultiBufferSnapshot,
+    range: &Range<Anchor>,
+    query: &SearchQuery,
+) -> Option<Arc<str>> {
+    if query.is_structural() {
+        // Structural replacements refer to the syntax nodes captured by the match, so they are
+        // computed within the buffer containing it.
+        let buffer_ranges = snapshot.range_to_buffer_ranges(range.clone());
+        let [(buffer, buffer_range, _)] = buffer_ranges.as_slice() else {
+            return None;
+        };
+        return query
+            .replacement_in_buffer(buffer, buffer_range.clone())
+            .map(Arc::from);
+    }
+
+    let text = snapshot.text_for_range(range.clone()).collect::<Vec<_>>();
+    let text: Cow<_> = if text.len() == 1 {
+        text.first().cloned().unwrap().into()
+    } else {
+        let joined_chunks = text.join("");
+        joined_chunks.into()
+    };
+    query
+        .replacement_for(&text)
+        .map(|replacement| Arc::from(&*replacement))
+}
+
+/// Returns the texts to replace the matches of the query with, if the query has a replacement.
+fn replacements_for_matches(
+    snapshot: &MultiBufferSnapshot,
+    ranges: &[Range<Anchor>],
+    query: &SearchQuery,
+) -> Vec<Option<Arc<str>>> {
+    if !query.is_structural() {
+        return ranges
+            .iter()
+            .map(|range| replacement_for_match(snapshot, range, query))
+            .collect();
+    }
+
+    // Structural replacements are computed for all the matches in a buffer at once, as the
+    // buffer is searched again to find the syntax nodes captured by the matches.
+    let mut ranges_by_buffer: HashMap<BufferId, (&_, Vec<(usize, Range<usize>)>)> =
+        HashMap::default();
+    for (ix, range) in ranges.iter().enumerate() {
+        if let [(buffer, buffer_range, _)] =
+            snapshot.range_to_buffer_ranges(range.clone()).as_slice()
+        {
+            ranges_by_buffer
+                .entry(buffer.remote_id())
+                .or_insert_with(|| (*buffer, Vec::new()))
+                .1
+                .push((ix, buffer_range.clone()));
+        }
+    }
+
+    let mut replacements = vec![None; ranges.len()];
+    for (buffer, ranges) in ranges_by_buffer.into_values() {
+        let buffer_ranges = ranges
+            .iter()
+            .map(|(_, range)| range.clone())
+            .collect::<Vec<_>>();
+        let buffer_replacements = query.replacements_in_buffer(buffer, &buffer_ranges);
+        for ((ix, _), replacement) in ranges.into_iter().zip(buffer_replacements) {
+            replacements[ix] = replacement.map(Arc::from);
+        }
+    }
+    replacements
+}
+
 impl Editor {
     pub fn update_restoration_data(
         &self,
@@ -1498,6 +1571,7 @@ impl SearchableItem for Editor {
                 replacement: false,
                 selection: false,
                 find_in_results: true,
+                syntax_filter: false,
             }
         } else {
             SearchOptions {
@@ -1507,6 +1581,7 @@ impl SearchableItem for Editor {
                 replacement: true,
                 selection: true,
                 find_in_results: false,
+                syntax_filter: true,
             }
         }
     }
@@ -1574,19 +1649,10 @@ impl SearchableItem for Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
-        let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
-        let text: Cow<_> = if text.len() == 1 {
-            text.first().cloned().unwrap().into()
-        } else {
-            let joined_chunks = text.join("");
-            joined_chunks.into()
-        };
-
-        if let Some(replacement) = query.replacement_for(&text) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        if let Some(replacement) = replacement_for_match(&snapshot, identifier, query) {
             self.transact(window, cx, |this, _, cx| {
-                this.edit([(identifier.clone(), Arc::from(&*replacement))], cx);
+                this.edit([(identifier.clone(), replacement)], cx);
             });
         }
     }
@@ -1599,12 +1665,11 @@ impl SearchableItem for Editor {
     ) {
         let text = self.buffer.read(cx);
         let text = text.snapshot(cx);
-        let mut edits = vec![];
+        let mut ranges = vec![];
         let mut last_point: Option<Point> = None;
 
         for m in matches {
             let point = m.start.to_point(&text);
-            let text = text.text_for_range(m.clone()).collect::<Vec<_>>();
 
             // Check if the row for the current match is different from the last
             // match. If that's not the case and we're still replacing matches
@@ -1618,18 +1683,15 @@ impl SearchableItem for Editor {
                 continue;
             }
 
-            let text: Cow<_> = if text.len() == 1 {
-                text.first().cloned().unwrap().into()
-            } else {
-                let joined_chunks = text.join("");
-                joined_chunks.into()
-            };
-
-            if let Some(replacement) = query.replacement_for(&text) {
-                edits.push((m.clone(), Arc::from(&*replacement)));
-            }
+            ranges.push(m.clone());
         }
 
+        let replacements = replacements_for_matches(&text, &ranges, query);
+        let edits = ranges
+            .into_iter()
+            .zip(replacements)
+            .filter_map(|(range, replacement)| Some((range, replacement?)))
+            .collect::<Vec<_>>();
         if !edits.is_empty() {
             self.transact(window, cx, |this, _, cx| {
                 this.edit(edits, cx);
@@ -0,0 +1,83 @@
+use crate::Editor;
+use gpui::{
+    Context, Entity, IntoElement, ParentElement, Render, SharedString, Subscription, Window, div,
+};
+use ui::{Button, ButtonCommon, FluentBuilder, LabelSize, Tooltip};
+use workspace::{StatusItemView, item::ItemHandle};
+
+/// Tells when the active buffer holds a large file, and which features are turned off for it.
+pub struct LargeFileIndicator {
+    disabled_features: Option<SharedString>,
+    _observe_active_editor: Option<Subscription>,
+}
+
+impl LargeFileIndicator {
+    pub fn new() -> Self {
+        Self {
+            disabled_features: None,
+            _observe_active_editor: None,
+        }
+    }
+
+    fn update_large_file(
+        &mut self,
+        editor: Entity<Editor>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.disabled_features = editor
+            .read(cx)
+            .active_excerpt(cx)
+            .map(|(_, buffer, _)| buffer.read(cx))
+            .filter(|buffer| buffer.is_large_file())
+            .map(|buffer| {
+                let mut disabled_features =
+                    "Syntax highlighting, language servers and undo history are turned off"
+                        .to_string();
+                if buffer.read_only() {
+                    disabled_features.push_str(", and the file is read-only");
+                }
+                disabled_features.into()
+            });
+
+        cx.notify();
+    }
+}
+
+impl Default for LargeFileIndicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Render for LargeFileIndicator {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        div().when_some(self.disabled_features.clone(), |el, disabled_features| {
+            el.child(
+                Button::new("large-file", "Large File")
+                    .label_size(LabelSize::Small)
+                    .tooltip(Tooltip::text(disabled_features)),
+            )
+        })
+    }
+}
+
+impl StatusItemView for LargeFileIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.downcast::<Editor>()) {
+            self._observe_active_editor =
+                Some(cx.observe_in(&editor, window, Self::update_large_file));
+            self.update_large_file(editor, window, cx);
+        } else {
+            self.disabled_features = None;
+            self._observe_active_editor = None;
+        }
+
+        cx.notify();
+    }
+}
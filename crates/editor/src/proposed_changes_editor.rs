@@ -483,6 +483,23 @@ impl SemanticsProvider for BranchBufferSemanticsProvider {
         }
     }
 
+    fn document_links(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<project::DocumentLink>>>> {
+        None
+    }
+
+    fn resolve_document_link(
+        &self,
+        _: project::DocumentLink,
+        _: Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<project::DocumentLink>>> {
+        None
+    }
+
     fn document_highlights(
         &self,
         buffer: &Entity<Buffer>,
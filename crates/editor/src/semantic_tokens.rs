@@ -0,0 +1,163 @@
+use std::{mem, time::Duration};
+
+use collections::HashMap;
+use futures::future::join_all;
+use gpui::{App, Context, Entity, Task};
+use language::{Buffer, language_settings::language_settings};
+use multi_buffer::Anchor;
+use project::BufferSemanticTokens;
+use text::BufferId;
+use theme::ActiveTheme as _;
+
+use crate::Editor;
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(150);
+
+#[derive(Default)]
+pub(super) struct SemanticTokensState {
+    tokens: HashMap<BufferId, Vec<BufferSemanticTokens>>,
+    pending_buffers: HashMap<BufferId, Entity<Buffer>>,
+    _refresh_task: Option<Task<()>>,
+}
+
+fn semantic_tokens_enabled(buffer: &Buffer, cx: &App) -> bool {
+    language_settings(
+        buffer.language().map(|language| language.name()),
+        buffer.file(),
+        cx,
+    )
+    .semantic_tokens
+}
+
+/// Queries the language servers for the semantic tokens of the given buffer, or of
+/// every buffer in the editor, and highlights them once they arrive.
+pub(super) fn refresh_semantic_tokens(
+    editor: &mut Editor,
+    for_buffer: Option<BufferId>,
+    cx: &mut Context<Editor>,
+) {
+    if !editor.mode().is_full() {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let mut tokens_removed = false;
+    for buffer in editor.buffer.read(cx).all_buffers() {
+        let buffer_id = buffer.read(cx).remote_id();
+        if for_buffer.is_some_and(|for_buffer| for_buffer != buffer_id) {
+            continue;
+        }
+        if semantic_tokens_enabled(buffer.read(cx), cx) {
+            editor
+                .semantic_tokens
+                .pending_buffers
+                .insert(buffer_id, buffer);
+        } else {
+            tokens_removed |= editor.semantic_tokens.tokens.remove(&buffer_id).is_some();
+        }
+    }
+    if tokens_removed {
+        update_semantic_token_highlights(editor, cx);
+    }
+    if editor.semantic_tokens.pending_buffers.is_empty() {
+        return;
+    }
+
+    editor.semantic_tokens._refresh_task = Some(cx.spawn(async move |editor, cx| {
+        cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+
+        let Ok(requests) = editor.update(cx, |editor, cx| {
+            let buffers = mem::take(&mut editor.semantic_tokens.pending_buffers);
+            project.update(cx, |project, cx| {
+                buffers
+                    .into_iter()
+                    .map(|(buffer_id, buffer)| {
+                        let request = project.semantic_tokens(&buffer, cx);
+                        async move { (buffer_id, request.await) }
+                    })
+                    .collect::<Vec<_>>()
+            })
+        }) else {
+            return;
+        };
+        let responses = join_all(requests).await;
+
+        editor
+            .update(cx, |editor, cx| {
+                for (buffer_id, response) in responses {
+                    match response {
+                        Ok(tokens) => {
+                            editor.semantic_tokens.tokens.insert(buffer_id, tokens);
+                        }
+                        Err(error) => {
+                            log::error!("failed to fetch semantic tokens: {error:#}");
+                        }
+                    }
+                }
+                update_semantic_token_highlights(editor, cx);
+            })
+            .ok();
+    }));
+}
+
+/// Forgets the tokens of buffers that are no longer part of the editor.
+pub(super) fn remove_semantic_tokens(
+    editor: &mut Editor,
+    buffer_ids: &[BufferId],
+    cx: &mut Context<Editor>,
+) {
+    let mut tokens_removed = false;
+    for buffer_id in buffer_ids {
+        editor.semantic_tokens.pending_buffers.remove(buffer_id);
+        tokens_removed |= editor.semantic_tokens.tokens.remove(buffer_id).is_some();
+    }
+    // Highlights of the removed excerpts need to go even if their buffer remains.
+    if tokens_removed || !editor.semantic_tokens.tokens.is_empty() {
+        update_semantic_token_highlights(editor, cx);
+    }
+}
+
+/// Resolves the theme styles of the known tokens and hands them to the display map.
+pub(super) fn update_semantic_token_highlights(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let syntax_theme = cx.theme().syntax().clone();
+    let multibuffer = editor.buffer.read(cx).snapshot(cx);
+    let mut highlights = Vec::new();
+    for (excerpt_id, buffer, excerpt_range) in multibuffer.excerpts() {
+        // Tokens of different servers may overlap, so only those of the first server
+        // that reported any are shown; tokens of a single server are sorted and disjoint.
+        let Some(server_tokens) = editor
+            .semantic_tokens
+            .tokens
+            .get(&buffer.remote_id())
+            .and_then(|tokens| tokens.iter().find(|tokens| !tokens.tokens.is_empty()))
+        else {
+            continue;
+        };
+
+        let context = &excerpt_range.context;
+        let start_ix = server_tokens
+            .tokens
+            .partition_point(|token| token.range.end.cmp(&context.start, buffer).is_le());
+        for token in &server_tokens.tokens[start_ix..] {
+            if token.range.start.cmp(&context.end, buffer).is_ge() {
+                break;
+            }
+            let Some(style) = server_tokens.token_type(token).and_then(|token_type| {
+                syntax_theme.semantic_token_style(token_type, server_tokens.token_modifiers(token))
+            }) else {
+                continue;
+            };
+            highlights.push((
+                Anchor::range_in_buffer(excerpt_id, buffer.remote_id(), token.range.clone()),
+                style,
+            ));
+        }
+    }
+
+    editor.display_map.update(cx, |display_map, _| {
+        display_map.set_semantic_token_highlights(highlights)
+    });
+    cx.notify();
+}
@@ -0,0 +1,82 @@
+use editor::Editor;
+use fs::Encoding;
+use gpui::{
+    Context, Entity, IntoElement, ParentElement, Render, Subscription, WeakEntity, Window, div,
+};
+use ui::{Button, ButtonCommon, Clickable, FluentBuilder, LabelSize, Tooltip};
+use workspace::{StatusItemView, Workspace, item::ItemHandle};
+
+use crate::{EncodingAction, EncodingSelector, ReopenWithEncoding};
+
+pub struct ActiveBufferEncoding {
+    active_encoding: Option<Encoding>,
+    workspace: WeakEntity<Workspace>,
+    _observe_active_editor: Option<Subscription>,
+}
+
+impl ActiveBufferEncoding {
+    pub fn new(workspace: &Workspace) -> Self {
+        Self {
+            active_encoding: None,
+            workspace: workspace.weak_handle(),
+            _observe_active_editor: None,
+        }
+    }
+
+    fn update_encoding(&mut self, editor: Entity<Editor>, _: &mut Window, cx: &mut Context<Self>) {
+        self.active_encoding = editor
+            .read(cx)
+            .active_excerpt(cx)
+            .map(|(_, buffer, _)| buffer.read(cx))
+            .filter(|buffer| buffer.file().is_some())
+            .map(|buffer| buffer.encoding());
+
+        cx.notify();
+    }
+}
+
+impl Render for ActiveBufferEncoding {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div().when_some(self.active_encoding, |el, active_encoding| {
+            el.child(
+                Button::new("change-encoding", active_encoding.to_string())
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        if let Some(workspace) = this.workspace.upgrade() {
+                            workspace.update(cx, |workspace, cx| {
+                                EncodingSelector::toggle(
+                                    workspace,
+                                    EncodingAction::Reopen,
+                                    window,
+                                    cx,
+                                )
+                            });
+                        }
+                    }))
+                    .tooltip(|window, cx| {
+                        Tooltip::for_action("Reopen with Encoding", &ReopenWithEncoding, window, cx)
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ActiveBufferEncoding {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.downcast::<Editor>()) {
+            self._observe_active_editor =
+                Some(cx.observe_in(&editor, window, Self::update_encoding));
+            self.update_encoding(editor, window, cx);
+        } else {
+            self.active_encoding = None;
+            self._observe_active_editor = None;
+        }
+
+        cx.notify();
+    }
+}
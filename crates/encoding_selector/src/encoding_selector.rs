@@ -0,0 +1,300 @@
+mod active_buffer_encoding;
+
+pub use active_buffer_encoding::ActiveBufferEncoding;
+use anyhow::anyhow;
+use editor::Editor;
+use fs::Encoding;
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ParentElement,
+    Render, Styled, WeakEntity, Window, actions,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace, notifications::DetachAndPromptErr};
+
+actions!(encoding_selector, [ReopenWithEncoding, SaveWithEncoding]);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(EncodingSelector::register).detach();
+}
+
+/// What happens to the buffer once an encoding is picked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EncodingAction {
+    /// Reloads the file, decoding it with the encoding.
+    Reopen,
+    /// Saves the buffer, encoding it with the encoding.
+    Save,
+}
+
+pub struct EncodingSelector {
+    picker: Entity<Picker<EncodingSelectorDelegate>>,
+}
+
+impl EncodingSelector {
+    fn register(
+        workspace: &mut Workspace,
+        _window: Option<&mut Window>,
+        _: &mut Context<Workspace>,
+    ) {
+        workspace.register_action(move |workspace, _: &ReopenWithEncoding, window, cx| {
+            Self::toggle(workspace, EncodingAction::Reopen, window, cx);
+        });
+        workspace.register_action(move |workspace, _: &SaveWithEncoding, window, cx| {
+            Self::toggle(workspace, EncodingAction::Save, window, cx);
+        });
+    }
+
+    fn toggle(
+        workspace: &mut Workspace,
+        action: EncodingAction,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Option<()> {
+        let (_, buffer, _) = workspace
+            .active_item(cx)?
+            .act_as::<Editor>(cx)?
+            .read(cx)
+            .active_excerpt(cx)?;
+        buffer.read(cx).file()?;
+        if action == EncodingAction::Reopen && buffer.read(cx).is_dirty() {
+            workspace.show_error(
+                &anyhow!(
+                    "Save or discard the changes before reopening the file with another encoding"
+                ),
+                cx,
+            );
+            return None;
+        }
+        let project = workspace.project().clone();
+
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            EncodingSelector::new(buffer, project, action, window, cx)
+        });
+        Some(())
+    }
+
+    fn new(
+        buffer: Entity<Buffer>,
+        project: Entity<Project>,
+        action: EncodingAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate =
+            EncodingSelectorDelegate::new(cx.entity().downgrade(), buffer, project, action, cx);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+impl Render for EncodingSelector {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl Focusable for EncodingSelector {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for EncodingSelector {}
+impl ModalView for EncodingSelector {}
+
+pub struct EncodingSelectorDelegate {
+    encoding_selector: WeakEntity<EncodingSelector>,
+    buffer: Entity<Buffer>,
+    project: Entity<Project>,
+    action: EncodingAction,
+    current_encoding: Encoding,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl EncodingSelectorDelegate {
+    fn new(
+        encoding_selector: WeakEntity<EncodingSelector>,
+        buffer: Entity<Buffer>,
+        project: Entity<Project>,
+        action: EncodingAction,
+        cx: &App,
+    ) -> Self {
+        let current_encoding = buffer.read(cx).encoding();
+        let candidates = Encoding::all()
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, encoding)| {
+                StringMatchCandidate::new(candidate_id, &encoding.to_string())
+            })
+            .collect::<Vec<_>>();
+        let selected_index = Encoding::all()
+            .iter()
+            .position(|encoding| *encoding == current_encoding)
+            .unwrap_or(0);
+
+        Self {
+            encoding_selector,
+            buffer,
+            project,
+            action,
+            current_encoding,
+            candidates,
+            matches: Vec::new(),
+            selected_index,
+        }
+    }
+}
+
+impl PickerDelegate for EncodingSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        match self.action {
+            EncodingAction::Reopen => "Reopen with encoding…".into(),
+            EncodingAction::Save => "Save with encoding…".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let encoding = Encoding::all()[mat.candidate_id];
+            let buffer = self.buffer.clone();
+            match self.action {
+                EncodingAction::Reopen => {
+                    let reload = self.project.update(cx, |project, cx| {
+                        project.reload_buffer_with_encoding(buffer, encoding, cx)
+                    });
+                    cx.background_spawn(async move {
+                        reload.await?;
+                        anyhow::Ok(())
+                    })
+                    .detach_and_prompt_err(
+                        "Failed to reopen the file",
+                        window,
+                        cx,
+                        |_, _, _| None,
+                    );
+                }
+                EncodingAction::Save => {
+                    let previous_encoding = buffer.read(cx).encoding();
+                    buffer.update(cx, |buffer, cx| buffer.set_encoding(encoding, cx));
+                    let save = self
+                        .project
+                        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx));
+                    cx.spawn(async move |_, cx| {
+                        if let Err(error) = save.await {
+                            buffer.update(cx, |buffer, cx| {
+                                buffer.set_encoding(previous_encoding, cx)
+                            })?;
+                            return Err(error);
+                        }
+                        anyhow::Ok(())
+                    })
+                    .detach_and_prompt_err(
+                        "Failed to save the file",
+                        window,
+                        cx,
+                        |_, _, _| None,
+                    );
+                }
+            }
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.encoding_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let mut label = mat.string.clone();
+        if Encoding::all()[mat.candidate_id] == self.current_encoding {
+            label.push_str(" (current)");
+        }
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(label, mat.positions.clone())),
+        )
+    }
+}
@@ -585,6 +585,9 @@ impl ExampleInstance {
                 tools: Vec::new(),
                 tool_choice: None,
                 stop: Vec::new(),
+                max_tokens: None,
+                response_format: None,
+                reasoning: None,
             };
 
             let model = model.clone();
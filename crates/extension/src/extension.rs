@@ -11,6 +11,7 @@ use ::lsp::LanguageServerName;
 use anyhow::{Context as _, Result, bail};
 use async_trait::async_trait;
 use fs::normalize_path;
+use futures::stream::BoxStream;
 use gpui::{App, Task};
 use language::LanguageName;
 use semantic_version::SemanticVersion;
@@ -143,6 +144,34 @@ pub trait Extension: Send + Sync + 'static {
         user_installed_path: Option<PathBuf>,
         worktree: Arc<dyn WorktreeDelegate>,
     ) -> Result<DebugAdapterBinary>;
+
+    async fn llm_provider_authenticate(
+        &self,
+        provider_id: Arc<str>,
+        api_key: Option<String>,
+    ) -> Result<()>;
+
+    async fn llm_provider_models(&self, provider_id: Arc<str>) -> Result<Vec<LlmModelInfo>>;
+
+    async fn llm_count_tokens(
+        &self,
+        provider_id: Arc<str>,
+        model_id: Arc<str>,
+        request: LlmCompletionRequest,
+    ) -> Result<u64>;
+
+    async fn llm_stream_completion(
+        &self,
+        provider_id: Arc<str>,
+        model_id: Arc<str>,
+        request: LlmCompletionRequest,
+    ) -> Result<BoxStream<'static, Result<LlmCompletionEvent>>>;
+
+    async fn task_templates(
+        &self,
+        provider_id: Arc<str>,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Vec<task::TaskTemplate>>;
 }
 
 pub fn parse_wasm_extension_version(
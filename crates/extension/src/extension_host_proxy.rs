@@ -30,6 +30,8 @@ pub struct ExtensionHostProxy {
     context_server_proxy: RwLock<Option<Arc<dyn ExtensionContextServerProxy>>>,
     indexed_docs_provider_proxy: RwLock<Option<Arc<dyn ExtensionIndexedDocsProviderProxy>>>,
     debug_adapter_provider_proxy: RwLock<Option<Arc<dyn ExtensionDebugAdapterProviderProxy>>>,
+    language_model_provider_proxy: RwLock<Option<Arc<dyn ExtensionLanguageModelProviderProxy>>>,
+    task_provider_proxy: RwLock<Option<Arc<dyn ExtensionTaskProviderProxy>>>,
 }
 
 impl ExtensionHostProxy {
@@ -56,6 +58,8 @@ impl ExtensionHostProxy {
             context_server_proxy: RwLock::default(),
             indexed_docs_provider_proxy: RwLock::default(),
             debug_adapter_provider_proxy: RwLock::default(),
+            language_model_provider_proxy: RwLock::default(),
+            task_provider_proxy: RwLock::default(),
         }
     }
 
@@ -100,6 +104,19 @@ impl ExtensionHostProxy {
             .write()
             .replace(Arc::new(proxy));
     }
+
+    pub fn register_language_model_provider_proxy(
+        &self,
+        proxy: impl ExtensionLanguageModelProviderProxy,
+    ) {
+        self.language_model_provider_proxy
+            .write()
+            .replace(Arc::new(proxy));
+    }
+
+    pub fn register_task_provider_proxy(&self, proxy: impl ExtensionTaskProviderProxy) {
+        self.task_provider_proxy.write().replace(Arc::new(proxy));
+    }
 }
 
 pub trait ExtensionThemeProxy: Send + Sync + 'static {
@@ -423,3 +440,88 @@ impl ExtensionDebugAdapterProviderProxy for ExtensionHostProxy {
         proxy.register_debug_adapter(extension, debug_adapter_name)
     }
 }
+
+pub trait ExtensionLanguageModelProviderProxy: Send + Sync + 'static {
+    fn register_language_model_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    );
+
+    fn unregister_language_model_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    );
+}
+
+impl ExtensionLanguageModelProviderProxy for ExtensionHostProxy {
+    fn register_language_model_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.language_model_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_language_model_provider(extension, provider_id, cx)
+    }
+
+    fn unregister_language_model_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.language_model_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_language_model_provider(extension_id, provider_id, cx)
+    }
+}
+
+pub trait ExtensionTaskProviderProxy: Send + Sync + 'static {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        languages: Vec<LanguageName>,
+        cx: &mut App,
+    );
+
+    fn unregister_task_provider(&self, extension_id: Arc<str>, provider_id: Arc<str>, cx: &mut App);
+}
+
+impl ExtensionTaskProviderProxy for ExtensionHostProxy {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        languages: Vec<LanguageName>,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.task_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_task_provider(extension, provider_id, languages, cx)
+    }
+
+    fn unregister_task_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.task_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_task_provider(extension_id, provider_id, cx)
+    }
+}
@@ -3,6 +3,7 @@ use collections::{BTreeMap, HashMap};
 use fs::Fs;
 use language::LanguageName;
 use lsp::LanguageServerName;
+use schemars::JsonSchema;
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -89,6 +90,10 @@ pub struct ExtensionManifest {
     pub capabilities: Vec<ExtensionCapability>,
     #[serde(default)]
     pub debug_adapters: Vec<Arc<str>>,
+    #[serde(default)]
+    pub language_model_providers: BTreeMap<Arc<str>, LanguageModelProviderManifestEntry>,
+    #[serde(default)]
+    pub task_providers: BTreeMap<Arc<str>, TaskProviderManifestEntry>,
 }
 
 impl ExtensionManifest {
@@ -97,33 +102,24 @@ impl ExtensionManifest {
         desired_command: &str,
         desired_args: &[impl AsRef<str> + std::fmt::Debug],
     ) -> Result<()> {
-        let is_allowed = self.capabilities.iter().any(|capability| match capability {
-            ExtensionCapability::ProcessExec { command, args } if command == desired_command => {
-                for (ix, arg) in args.iter().enumerate() {
-                    if arg == "**" {
-                        return true;
-                    }
-
-                    if ix >= desired_args.len() {
-                        return false;
-                    }
-
-                    if arg != "*" && arg != desired_args[ix].as_ref() {
-                        return false;
-                    }
-                }
-                if args.len() < desired_args.len() {
-                    return false;
-                }
-                true
-            }
-            _ => false,
-        });
+        let desired_args = desired_args
+            .iter()
+            .map(|arg| arg.as_ref())
+            .collect::<Vec<_>>();
+        self.allow(&CapabilityUse::ProcessExec {
+            command: desired_command,
+            args: &desired_args,
+        })
+    }
 
-        if !is_allowed {
-            bail!(
-                "capability for process:exec {desired_command} {desired_args:?} was not listed in the extension manifest",
-            );
+    /// Returns an error if none of the capabilities listed in the manifest allow the given use.
+    pub fn allow(&self, capability_use: &CapabilityUse) -> Result<()> {
+        if !self
+            .capabilities
+            .iter()
+            .any(|capability| capability.allows(capability_use))
+        {
+            bail!("capability for {capability_use} was not listed in the extension manifest");
         }
 
         Ok(())
@@ -131,17 +127,164 @@ impl ExtensionManifest {
 }
 
 /// A capability for an extension.
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "kind")]
 pub enum ExtensionCapability {
     #[serde(rename = "process:exec")]
     ProcessExec {
-        /// The command to execute.
+        /// The command to execute. Use `*` to allow any command.
         command: String,
         /// The arguments to pass to the command. Use `*` for a single wildcard argument.
         /// If the last element is `**`, then any trailing arguments are allowed.
         args: Vec<String>,
     },
+    #[serde(rename = "network:request")]
+    NetworkRequest {
+        /// The host to make HTTP requests to. Use `*` to allow any host, or `*.example.com`
+        /// to allow any subdomain of `example.com`.
+        host: String,
+    },
+    #[serde(rename = "download:file")]
+    DownloadFile {
+        /// The host to download files from, with the same wildcards as for `network:request`.
+        host: String,
+        /// The components of the destination path, relative to the extension's working
+        /// directory. Use `*` for a single wildcard component. If the last element is `**`,
+        /// then any trailing components are allowed.
+        path: Vec<String>,
+    },
+    #[serde(rename = "worktree:read")]
+    WorktreeRead {
+        /// The components of the path to read, relative to the root of the worktree, with the
+        /// same wildcards as for `download:file`.
+        path: Vec<String>,
+    },
+}
+
+impl ExtensionCapability {
+    /// Returns whether this capability allows the given use.
+    pub fn allows(&self, capability_use: &CapabilityUse) -> bool {
+        match (self, capability_use) {
+            (
+                ExtensionCapability::ProcessExec { command, args },
+                CapabilityUse::ProcessExec {
+                    command: desired_command,
+                    args: desired_args,
+                },
+            ) => {
+                (command == "*" || command == desired_command)
+                    && matches_components(args, desired_args)
+            }
+            (
+                ExtensionCapability::NetworkRequest { host },
+                CapabilityUse::NetworkRequest { host: desired_host },
+            ) => matches_host(host, desired_host),
+            (
+                ExtensionCapability::DownloadFile { host, path },
+                CapabilityUse::DownloadFile {
+                    host: desired_host,
+                    path: desired_path,
+                },
+            ) => matches_host(host, desired_host) && matches_components(path, desired_path),
+            (
+                ExtensionCapability::WorktreeRead { path },
+                CapabilityUse::WorktreeRead { path: desired_path },
+            ) => matches_components(path, desired_path),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ExtensionCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtensionCapability::ProcessExec { command, args } => {
+                write!(f, "Run `{command}")?;
+                for arg in args {
+                    write!(f, " {arg}")?;
+                }
+                write!(f, "`")
+            }
+            ExtensionCapability::NetworkRequest { host } => {
+                write!(f, "Make network requests to `{host}`")
+            }
+            ExtensionCapability::DownloadFile { host, path } => {
+                write!(f, "Download files from `{host}` to `{}`", path.join("/"))
+            }
+            ExtensionCapability::WorktreeRead { path } => {
+                write!(f, "Read `{}` in your worktrees", path.join("/"))
+            }
+        }
+    }
+}
+
+/// A use of a capability by an extension, which is checked against the [`ExtensionCapability`]s
+/// the extension was granted.
+#[derive(Debug, Clone, Copy)]
+pub enum CapabilityUse<'a> {
+    ProcessExec {
+        command: &'a str,
+        args: &'a [&'a str],
+    },
+    NetworkRequest {
+        host: &'a str,
+    },
+    DownloadFile {
+        host: &'a str,
+        path: &'a [&'a str],
+    },
+    WorktreeRead {
+        path: &'a [&'a str],
+    },
+}
+
+impl fmt::Display for CapabilityUse<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapabilityUse::ProcessExec { command, args } => {
+                write!(f, "process:exec {command} {args:?}")
+            }
+            CapabilityUse::NetworkRequest { host } => write!(f, "network:request {host}"),
+            CapabilityUse::DownloadFile { host, path } => {
+                write!(f, "download:file {host} {}", path.join("/"))
+            }
+            CapabilityUse::WorktreeRead { path } => write!(f, "worktree:read {}", path.join("/")),
+        }
+    }
+}
+
+fn matches_components(patterns: &[String], components: &[&str]) -> bool {
+    for (ix, pattern) in patterns.iter().enumerate() {
+        if pattern == "**" {
+            return true;
+        }
+
+        if ix >= components.len() {
+            return false;
+        }
+
+        if pattern != "*" && pattern != components[ix] {
+            return false;
+        }
+    }
+
+    patterns.len() >= components.len()
+}
+
+fn matches_host(pattern: &str, host: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+
+    let host = host.to_ascii_lowercase();
+    let pattern = pattern.to_ascii_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .and_then(|subdomain| subdomain.strip_suffix('.'))
+            .is_some_and(|subdomain| !subdomain.is_empty()),
+        None => pattern == host,
+    }
 }
 
 #[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
@@ -208,6 +351,23 @@ pub struct SlashCommandManifestEntry {
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct IndexedDocsProviderEntry {}
 
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct LanguageModelProviderManifestEntry {
+    /// The name of the provider to display in the UI.
+    pub name: String,
+    /// The URL of the page where users can get an API key for the provider.
+    #[serde(default)]
+    pub api_key_url: Option<String>,
+}
+
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct TaskProviderManifestEntry {
+    /// The languages whose files the provided tasks are shown for. If empty, the tasks are
+    /// shown for every file in the worktree.
+    #[serde(default)]
+    pub languages: Vec<LanguageName>,
+}
+
 impl ExtensionManifest {
     pub async fn load(fs: Arc<dyn Fs>, extension_dir: &Path) -> Result<Self> {
         let extension_name = extension_dir
@@ -277,6 +437,8 @@ fn manifest_from_old_manifest(
         snippets: None,
         capabilities: Vec::new(),
         debug_adapters: vec![],
+        language_model_providers: BTreeMap::default(),
+        task_providers: BTreeMap::default(),
     }
 }
 
@@ -305,6 +467,8 @@ mod tests {
             snippets: None,
             capabilities: vec![],
             debug_adapters: Default::default(),
+            language_model_providers: BTreeMap::default(),
+            task_providers: BTreeMap::default(),
         }
     }
 
@@ -383,4 +547,79 @@ mod tests {
         );
         assert!(manifest.allow_exec("docker", &["ps"]).is_err()); // wrong first arg
     }
+
+    #[test]
+    fn test_allow_network_request() {
+        let manifest = ExtensionManifest {
+            capabilities: vec![
+                ExtensionCapability::NetworkRequest {
+                    host: "api.github.com".to_string(),
+                },
+                ExtensionCapability::NetworkRequest {
+                    host: "*.example.com".to_string(),
+                },
+            ],
+            ..extension_manifest()
+        };
+
+        let allow = |host: &str| manifest.allow(&CapabilityUse::NetworkRequest { host });
+        assert!(allow("api.github.com").is_ok());
+        assert!(allow("API.GitHub.com").is_ok());
+        assert!(allow("github.com").is_err());
+        assert!(allow("docs.example.com").is_ok());
+        assert!(allow("a.b.example.com").is_ok());
+        assert!(allow("example.com").is_err()); // only subdomains
+        assert!(allow("notexample.com").is_err());
+    }
+
+    #[test]
+    fn test_allow_download_file() {
+        let manifest = ExtensionManifest {
+            capabilities: vec![ExtensionCapability::DownloadFile {
+                host: "github.com".to_string(),
+                path: vec!["bin".to_string(), "**".to_string()],
+            }],
+            ..extension_manifest()
+        };
+
+        let allow =
+            |host: &str, path: &[&str]| manifest.allow(&CapabilityUse::DownloadFile { host, path });
+        assert!(allow("github.com", &["bin", "server.zip"]).is_ok());
+        assert!(allow("github.com", &["bin", "v1", "server"]).is_ok());
+        assert!(allow("github.com", &["server.zip"]).is_err()); // wrong directory
+        assert!(allow("example.com", &["bin", "server.zip"]).is_err()); // wrong host
+        assert!(
+            manifest
+                .allow(&CapabilityUse::NetworkRequest { host: "github.com" })
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_allow_worktree_read() {
+        let manifest = ExtensionManifest {
+            capabilities: vec![ExtensionCapability::WorktreeRead {
+                path: vec!["*".to_string()],
+            }],
+            ..extension_manifest()
+        };
+
+        let allow = |path: &[&str]| manifest.allow(&CapabilityUse::WorktreeRead { path });
+        assert!(allow(&["Cargo.toml"]).is_ok());
+        assert!(allow(&["src", "main.rs"]).is_err()); // too many components
+    }
+
+    #[test]
+    fn test_allow_any_command() {
+        let manifest = ExtensionManifest {
+            capabilities: vec![ExtensionCapability::ProcessExec {
+                command: "*".to_string(),
+                args: vec!["**".to_string()],
+            }],
+            ..extension_manifest()
+        };
+
+        assert!(manifest.allow_exec("ls", &["-la"]).is_ok());
+        assert!(manifest.allow_exec("pwd", &[] as &[&str]).is_ok());
+    }
 }
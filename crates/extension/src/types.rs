@@ -1,5 +1,6 @@
 mod context_server;
 mod dap;
+mod llm_provider;
 mod lsp;
 mod slash_command;
 
@@ -7,6 +8,7 @@ use std::ops::Range;
 
 pub use context_server::*;
 pub use dap::*;
+pub use llm_provider::*;
 pub use lsp::*;
 pub use slash_command::*;
 
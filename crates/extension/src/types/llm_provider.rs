@@ -0,0 +1,156 @@
+/// A language model offered by a provider.
+#[derive(Debug, Clone)]
+pub struct LlmModelInfo {
+    /// The ID of the model, unique within its provider.
+    pub id: String,
+    /// The name of the model to display in the UI.
+    pub name: String,
+    /// The maximum number of tokens in the model's context window.
+    pub max_token_count: u64,
+    /// The maximum number of tokens the model can generate, if limited.
+    pub max_output_tokens: Option<u32>,
+    /// Whether the model accepts images.
+    pub supports_images: bool,
+    /// Whether the model can use tools.
+    pub supports_tools: bool,
+    /// Whether this is the model to use by default.
+    pub is_default: bool,
+    /// Whether this is the model to use by default for quick tasks.
+    pub is_default_fast: bool,
+}
+
+/// The role of a message in a conversation.
+#[derive(Debug, Clone, Copy)]
+pub enum LlmMessageRole {
+    User,
+    Assistant,
+    System,
+}
+
+/// The reasoning of a model.
+#[derive(Debug, Clone)]
+pub struct LlmThinking {
+    /// The text of the reasoning.
+    pub text: String,
+    /// The signature of the reasoning, for the providers that require it to be sent back.
+    pub signature: Option<String>,
+}
+
+/// A request from the model to use a tool.
+#[derive(Debug, Clone)]
+pub struct LlmToolUse {
+    /// The ID of the tool use, used to pair it with its result.
+    pub id: String,
+    /// The name of the tool.
+    pub name: String,
+    /// The input to the tool, as JSON.
+    pub input: String,
+}
+
+/// The content of a tool result.
+#[derive(Debug, Clone)]
+pub enum LlmToolResultContent {
+    Text(String),
+    /// A base64-encoded PNG image.
+    Image(String),
+}
+
+/// The result of using a tool.
+#[derive(Debug, Clone)]
+pub struct LlmToolResult {
+    /// The ID of the tool use this is the result of.
+    pub tool_use_id: String,
+    /// The name of the tool.
+    pub tool_name: String,
+    /// Whether using the tool failed.
+    pub is_error: bool,
+    /// The content of the result.
+    pub content: LlmToolResultContent,
+}
+
+/// A piece of a message.
+#[derive(Debug, Clone)]
+pub enum LlmMessageContent {
+    Text(String),
+    Thinking(LlmThinking),
+    /// A base64-encoded PNG image.
+    Image(String),
+    ToolUse(LlmToolUse),
+    ToolResult(LlmToolResult),
+}
+
+/// A message in a conversation.
+#[derive(Debug, Clone)]
+pub struct LlmRequestMessage {
+    /// The role of the message.
+    pub role: LlmMessageRole,
+    /// The content of the message.
+    pub content: Vec<LlmMessageContent>,
+    /// Whether the conversation up to and including this message should be cached.
+    pub cache: bool,
+}
+
+/// A tool the model can use.
+#[derive(Debug, Clone)]
+pub struct LlmToolDefinition {
+    /// The name of the tool.
+    pub name: String,
+    /// The description of the tool.
+    pub description: String,
+    /// The JSON schema of the tool's input.
+    pub input_schema: String,
+}
+
+/// How the model should choose which tool to use.
+#[derive(Debug, Clone, Copy)]
+pub enum LlmToolChoice {
+    Auto,
+    Any,
+    None,
+}
+
+/// A request for a completion.
+#[derive(Debug, Clone)]
+pub struct LlmCompletionRequest {
+    /// The messages of the conversation.
+    pub messages: Vec<LlmRequestMessage>,
+    /// The tools the model can use.
+    pub tools: Vec<LlmToolDefinition>,
+    /// How the model should choose which tool to use.
+    pub tool_choice: Option<LlmToolChoice>,
+    /// The sequences that stop the completion.
+    pub stop_sequences: Vec<String>,
+    /// The sampling temperature.
+    pub temperature: Option<f32>,
+    /// The maximum number of tokens to generate.
+    pub max_tokens: Option<u32>,
+}
+
+/// The reason a completion stopped.
+#[derive(Debug, Clone, Copy)]
+pub enum LlmStopReason {
+    EndTurn,
+    MaxTokens,
+    ToolUse,
+}
+
+/// The number of tokens used by a completion.
+#[derive(Debug, Clone, Copy)]
+pub struct LlmTokenUsage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub cache_creation_input_tokens: u32,
+    pub cache_read_input_tokens: u32,
+}
+
+/// An event in a streamed completion.
+#[derive(Debug, Clone)]
+pub enum LlmCompletionEvent {
+    Text(String),
+    Thinking(LlmThinking),
+    /// A complete request to use a tool.
+    ToolUse(LlmToolUse),
+    Stop(LlmStopReason),
+    /// The tokens used so far.
+    Usage(LlmTokenUsage),
+}
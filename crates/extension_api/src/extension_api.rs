@@ -53,6 +53,20 @@ pub mod lsp {
     };
 }
 
+/// Constructs for providing language models.
+pub mod llm_provider {
+    pub use crate::wit::zed::extension::llm_provider::{
+        CompletionEvent, CompletionRequest, MessageContent, MessageRole, ModelInfo, RequestMessage,
+        StopReason, ThinkingContent, TokenUsage, ToolChoice, ToolDefinition, ToolResult,
+        ToolResultContent, ToolUse,
+    };
+}
+
+/// Constructs for providing tasks.
+pub mod task_provider {
+    pub use crate::wit::zed::extension::task_provider::TaskTemplate;
+}
+
 /// A result returned from a Zed extension.
 pub type Result<T, E = String> = core::result::Result<T, E>;
 
@@ -203,6 +217,63 @@ pub trait Extension: Send + Sync {
     ) -> Result<DebugAdapterBinary, String> {
         Err("`get_dap_binary` not implemented".to_string())
     }
+
+    /// Authenticates the specified language model provider with the API key the user stored for it, if any.
+    ///
+    /// Providers that require an API key should return an error when none is given. This is also
+    /// called with `None` when the user resets the provider's credentials.
+    fn llm_provider_authenticate(
+        &mut self,
+        _provider_id: &str,
+        _api_key: Option<String>,
+    ) -> Result<()> {
+        Err("`llm_provider_authenticate` not implemented".to_string())
+    }
+
+    /// Returns the models offered by the specified language model provider.
+    fn llm_provider_models(&mut self, _provider_id: &str) -> Result<Vec<llm_provider::ModelInfo>> {
+        Err("`llm_provider_models` not implemented".to_string())
+    }
+
+    /// Returns the number of tokens in the given request.
+    fn llm_count_tokens(
+        &mut self,
+        _provider_id: &str,
+        _model_id: &str,
+        _request: llm_provider::CompletionRequest,
+    ) -> Result<u64> {
+        Err("`llm_count_tokens` not implemented".to_string())
+    }
+
+    /// Starts streaming a completion for the given request, returning the ID of the stream.
+    fn llm_stream_completion_start(
+        &mut self,
+        _provider_id: &str,
+        _model_id: &str,
+        _request: llm_provider::CompletionRequest,
+    ) -> Result<u64> {
+        Err("`llm_stream_completion_start` not implemented".to_string())
+    }
+
+    /// Returns the next event of the specified completion stream, or `None` once it has finished.
+    fn llm_stream_completion_next(
+        &mut self,
+        _stream_id: u64,
+    ) -> Result<Option<llm_provider::CompletionEvent>> {
+        Err("`llm_stream_completion_next` not implemented".to_string())
+    }
+
+    /// Releases the specified completion stream.
+    fn llm_stream_completion_close(&mut self, _stream_id: u64) {}
+
+    /// Returns the task templates the specified task provider offers for the given worktree.
+    fn task_templates(
+        &mut self,
+        _provider_id: &str,
+        _worktree: &Worktree,
+    ) -> Result<Vec<task_provider::TaskTemplate>> {
+        Err("`task_templates` not implemented".to_string())
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -396,6 +467,45 @@ impl wit::Guest for Component {
     ) -> Result<wit::DebugAdapterBinary, String> {
         extension().get_dap_binary(adapter_name, config, user_installed_path, worktree)
     }
+
+    fn llm_provider_authenticate(provider_id: String, api_key: Option<String>) -> Result<()> {
+        extension().llm_provider_authenticate(&provider_id, api_key)
+    }
+
+    fn llm_provider_models(provider_id: String) -> Result<Vec<llm_provider::ModelInfo>> {
+        extension().llm_provider_models(&provider_id)
+    }
+
+    fn llm_count_tokens(
+        provider_id: String,
+        model_id: String,
+        request: llm_provider::CompletionRequest,
+    ) -> Result<u64> {
+        extension().llm_count_tokens(&provider_id, &model_id, request)
+    }
+
+    fn llm_stream_completion_start(
+        provider_id: String,
+        model_id: String,
+        request: llm_provider::CompletionRequest,
+    ) -> Result<u64> {
+        extension().llm_stream_completion_start(&provider_id, &model_id, request)
+    }
+
+    fn llm_stream_completion_next(stream_id: u64) -> Result<Option<llm_provider::CompletionEvent>> {
+        extension().llm_stream_completion_next(stream_id)
+    }
+
+    fn llm_stream_completion_close(stream_id: u64) {
+        extension().llm_stream_completion_close(stream_id)
+    }
+
+    fn task_templates(
+        provider_id: String,
+        worktree: &Worktree,
+    ) -> Result<Vec<task_provider::TaskTemplate>> {
+        extension().task_templates(&provider_id, worktree)
+    }
 }
 
 /// The ID of a language server.
@@ -139,6 +139,8 @@ fn manifest() -> ExtensionManifest {
             args: vec!["hello!".into()],
         }],
         debug_adapters: Default::default(),
+        language_model_providers: Default::default(),
+        task_providers: Default::default(),
     }
 }
 
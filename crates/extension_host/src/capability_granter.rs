@@ -0,0 +1,265 @@
+use anyhow::{Result, bail};
+use collections::HashMap;
+use extension::{CapabilityUse, ExtensionCapability, ExtensionManifest};
+use semantic_version::SemanticVersion;
+use std::sync::Arc;
+
+/// The first extension API version whose extensions have to declare the network, download and
+/// worktree capabilities they use.
+///
+/// Extensions built against older versions of the API could not declare these capabilities, so
+/// only the capability settings apply to them.
+const MIN_VERSION_WITH_DECLARED_CAPABILITIES: SemanticVersion = SemanticVersion::new(0, 6, 0);
+
+/// Decides whether an extension may use a capability, based on the capabilities declared in its
+/// manifest, the capabilities allowed and denied in the settings, and the capabilities the user
+/// consented to.
+#[derive(Debug, Default)]
+pub struct CapabilityGranter {
+    /// The capabilities extensions may be granted, or `None` if they may be granted any.
+    allowed: Option<Vec<ExtensionCapability>>,
+    /// The capabilities extensions are never granted.
+    denied: Vec<ExtensionCapability>,
+    /// The capabilities the user consented to, by extension ID, or `None` if extensions are
+    /// granted the capabilities they declare without asking the user.
+    consented: Option<HashMap<Arc<str>, Vec<ExtensionCapability>>>,
+}
+
+impl CapabilityGranter {
+    pub fn new(consented: Option<HashMap<Arc<str>, Vec<ExtensionCapability>>>) -> Self {
+        Self {
+            allowed: None,
+            denied: Vec::new(),
+            consented,
+        }
+    }
+
+    /// Sets the capabilities extensions may be granted, and the ones they are never granted.
+    pub fn set_policy(
+        &mut self,
+        allowed: Option<Vec<ExtensionCapability>>,
+        denied: Vec<ExtensionCapability>,
+    ) {
+        self.allowed = allowed;
+        self.denied = denied;
+    }
+
+    /// Returns the capabilities the user consented to, by extension ID.
+    pub fn consented_capabilities(&self) -> Option<&HashMap<Arc<str>, Vec<ExtensionCapability>>> {
+        self.consented.as_ref()
+    }
+
+    /// Records that the user consented to the given capabilities of the extension.
+    pub fn consent(&mut self, extension_id: Arc<str>, capabilities: Vec<ExtensionCapability>) {
+        if let Some(consented) = self.consented.as_mut() {
+            let extension_capabilities = consented.entry(extension_id).or_default();
+            for capability in capabilities {
+                if !extension_capabilities.contains(&capability) {
+                    extension_capabilities.push(capability);
+                }
+            }
+        }
+    }
+
+    /// Forgets the capabilities the user consented to for the extension.
+    pub fn revoke(&mut self, extension_id: &str) {
+        if let Some(consented) = self.consented.as_mut() {
+            consented.remove(extension_id);
+        }
+    }
+
+    /// Returns the capabilities declared in the manifest that the user has not consented to.
+    pub fn capabilities_pending_consent(
+        &self,
+        manifest: &ExtensionManifest,
+    ) -> Vec<ExtensionCapability> {
+        let Some(consented) = self.consented.as_ref() else {
+            return Vec::new();
+        };
+        let consented = consented
+            .get(&manifest.id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        manifest
+            .capabilities
+            .iter()
+            .filter(|capability| !consented.contains(capability))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns an error if the extension may not use the capability.
+    pub fn grant(
+        &self,
+        manifest: &ExtensionManifest,
+        zed_api_version: SemanticVersion,
+        capability_use: &CapabilityUse,
+    ) -> Result<()> {
+        if let Some(allowed) = self.allowed.as_ref() {
+            if !allowed
+                .iter()
+                .any(|capability| capability.allows(capability_use))
+            {
+                bail!(
+                    "capability for {capability_use} is not allowed by the `allowed_extension_capabilities` setting"
+                );
+            }
+        }
+
+        if self
+            .denied
+            .iter()
+            .any(|capability| capability.allows(capability_use))
+        {
+            bail!(
+                "capability for {capability_use} is denied by the `denied_extension_capabilities` setting"
+            );
+        }
+
+        let must_be_declared = matches!(capability_use, CapabilityUse::ProcessExec { .. })
+            || zed_api_version >= MIN_VERSION_WITH_DECLARED_CAPABILITIES;
+        if !must_be_declared {
+            return Ok(());
+        }
+
+        manifest.allow(capability_use)?;
+
+        if let Some(consented) = self.consented.as_ref() {
+            let is_consented = consented.get(&manifest.id).is_some_and(|capabilities| {
+                capabilities
+                    .iter()
+                    .filter(|capability| manifest.capabilities.contains(capability))
+                    .any(|capability| capability.allows(capability_use))
+            });
+            if !is_consented {
+                bail!(
+                    "capability for {capability_use} has not been granted to the extension; review its capabilities in the extensions page"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(capabilities: Vec<ExtensionCapability>) -> ExtensionManifest {
+        ExtensionManifest {
+            id: "test".into(),
+            name: "Test".to_string(),
+            version: "1.0.0".into(),
+            schema_version: extension::SchemaVersion::ZERO,
+            description: None,
+            repository: None,
+            authors: vec![],
+            lib: Default::default(),
+            themes: vec![],
+            icon_themes: vec![],
+            languages: vec![],
+            grammars: Default::default(),
+            language_servers: Default::default(),
+            context_servers: Default::default(),
+            slash_commands: Default::default(),
+            indexed_docs_providers: Default::default(),
+            snippets: None,
+            capabilities,
+            debug_adapters: Default::default(),
+            language_model_providers: Default::default(),
+            task_providers: Default::default(),
+        }
+    }
+
+    fn network_request(host: &str) -> ExtensionCapability {
+        ExtensionCapability::NetworkRequest {
+            host: host.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_grant_requires_declaration_and_consent() {
+        let manifest = manifest(vec![network_request("*.github.com")]);
+        let mut granter = CapabilityGranter::new(Some(HashMap::default()));
+        let version = SemanticVersion::new(0, 6, 0);
+        let api_github = CapabilityUse::NetworkRequest {
+            host: "api.github.com",
+        };
+
+        assert!(granter.grant(&manifest, version, &api_github).is_err());
+        assert_eq!(
+            granter.capabilities_pending_consent(&manifest),
+            vec![network_request("*.github.com")]
+        );
+
+        granter.consent(manifest.id.clone(), manifest.capabilities.clone());
+        assert!(granter.capabilities_pending_consent(&manifest).is_empty());
+        assert!(granter.grant(&manifest, version, &api_github).is_ok());
+        assert!(
+            granter
+                .grant(
+                    &manifest,
+                    version,
+                    &CapabilityUse::NetworkRequest {
+                        host: "example.com"
+                    }
+                )
+                .is_err()
+        );
+
+        granter.revoke(&manifest.id);
+        assert!(granter.grant(&manifest, version, &api_github).is_err());
+    }
+
+    #[test]
+    fn test_grant_applies_settings_policy() {
+        let manifest = manifest(vec![network_request("*")]);
+        let mut granter = CapabilityGranter::new(None);
+        let version = SemanticVersion::new(0, 6, 0);
+        let api_github = CapabilityUse::NetworkRequest {
+            host: "api.github.com",
+        };
+        let example = CapabilityUse::NetworkRequest {
+            host: "example.com",
+        };
+
+        assert!(granter.grant(&manifest, version, &api_github).is_ok());
+        assert!(granter.grant(&manifest, version, &example).is_ok());
+
+        granter.set_policy(Some(vec![network_request("*.github.com")]), vec![]);
+        assert!(granter.grant(&manifest, version, &api_github).is_ok());
+        assert!(granter.grant(&manifest, version, &example).is_err());
+
+        granter.set_policy(None, vec![network_request("api.github.com")]);
+        assert!(granter.grant(&manifest, version, &api_github).is_err());
+        assert!(granter.grant(&manifest, version, &example).is_ok());
+    }
+
+    #[test]
+    fn test_grant_to_extensions_on_older_api_versions() {
+        let manifest = manifest(vec![]);
+        let mut granter = CapabilityGranter::new(Some(HashMap::default()));
+        let version = SemanticVersion::new(0, 5, 0);
+        let example = CapabilityUse::NetworkRequest {
+            host: "example.com",
+        };
+
+        assert!(granter.grant(&manifest, version, &example).is_ok());
+        assert!(
+            granter
+                .grant(
+                    &manifest,
+                    version,
+                    &CapabilityUse::ProcessExec {
+                        command: "ls",
+                        args: &[]
+                    }
+                )
+                .is_err()
+        );
+
+        granter.set_policy(None, vec![network_request("example.com")]);
+        assert!(granter.grant(&manifest, version, &example).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod capability_granter;
 pub mod extension_settings;
 pub mod headless_host;
 pub mod wasm_host;
@@ -8,16 +9,17 @@ mod extension_store_test;
 use anyhow::{Context as _, Result, anyhow, bail};
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
+use capability_granter::CapabilityGranter;
 use client::ExtensionProvides;
 use client::{Client, ExtensionMetadata, GetExtensionsResponse, proto, telemetry::Telemetry};
 use collections::{BTreeMap, BTreeSet, HashMap, HashSet, btree_map};
 pub use extension::ExtensionManifest;
 use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use extension::{
-    ExtensionContextServerProxy, ExtensionDebugAdapterProviderProxy, ExtensionEvents,
-    ExtensionGrammarProxy, ExtensionHostProxy, ExtensionIndexedDocsProviderProxy,
-    ExtensionLanguageProxy, ExtensionLanguageServerProxy, ExtensionSlashCommandProxy,
-    ExtensionSnippetProxy, ExtensionThemeProxy,
+    ExtensionCapability, ExtensionContextServerProxy, ExtensionDebugAdapterProviderProxy,
+    ExtensionEvents, ExtensionGrammarProxy, ExtensionHostProxy, ExtensionIndexedDocsProviderProxy,
+    ExtensionLanguageModelProviderProxy, ExtensionLanguageProxy, ExtensionLanguageServerProxy,
+    ExtensionSlashCommandProxy, ExtensionSnippetProxy, ExtensionThemeProxy,
 };
 use fs::{Fs, RemoveOptions};
 use futures::{
@@ -44,7 +46,7 @@ use release_channel::ReleaseChannel;
 use remote::SshRemoteClient;
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
-use settings::Settings;
+use settings::{Settings, SettingsStore};
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 use std::{
@@ -112,6 +114,7 @@ pub struct ExtensionStore {
     pub installed_dir: PathBuf,
     pub outstanding_operations: BTreeMap<Arc<str>, ExtensionOperation>,
     pub index_path: PathBuf,
+    pub consented_capabilities_path: PathBuf,
     pub modified_extensions: HashSet<Arc<str>>,
     pub wasm_host: Arc<WasmHost>,
     pub wasm_extensions: Vec<(Arc<ExtensionManifest>, WasmExtension)>,
@@ -133,6 +136,8 @@ pub enum Event {
     StartedReloading,
     ExtensionInstalled(Arc<str>),
     ExtensionFailedToLoad(Arc<str>),
+    /// An installed or upgraded extension declares capabilities the user has not consented to.
+    ExtensionCapabilitiesNeedConsent(Arc<str>),
 }
 
 impl EventEmitter<Event> for ExtensionStore {}
@@ -235,6 +240,7 @@ impl ExtensionStore {
         let build_dir = build_dir.unwrap_or_else(|| extensions_dir.join("build"));
         let installed_dir = extensions_dir.join("installed");
         let index_path = extensions_dir.join("index.json");
+        let consented_capabilities_path = extensions_dir.join("consented_capabilities.json");
 
         let (reload_tx, mut reload_rx) = unbounded();
         let (connection_registered_tx, mut connection_registered_rx) = unbounded();
@@ -243,6 +249,7 @@ impl ExtensionStore {
             extension_index: Default::default(),
             installed_dir,
             index_path,
+            consented_capabilities_path,
             builder: Arc::new(ExtensionBuilder::new(builder_client, build_dir)),
             outstanding_operations: Default::default(),
             modified_extensions: Default::default(),
@@ -269,12 +276,13 @@ impl ExtensionStore {
         // The extensions store maintains an index file, which contains a complete
         // list of the installed extensions and the resources that they provide.
         // This index is loaded synchronously on startup.
-        let (index_content, index_metadata, extensions_metadata) =
+        let (index_content, index_metadata, extensions_metadata, consented_capabilities_content) =
             cx.background_executor().block(async {
                 futures::join!(
                     this.fs.load(&this.index_path),
                     this.fs.metadata(&this.index_path),
                     this.fs.metadata(&this.installed_dir),
+                    this.fs.load(&this.consented_capabilities_path),
                 )
             });
 
@@ -299,6 +307,29 @@ impl ExtensionStore {
             }
         }
 
+        // The capabilities the user consented to are also loaded synchronously, so
+        // that the initial extensions can use them. Extensions that were installed
+        // before capabilities required consent keep the capabilities they declare.
+        let needs_consented_capabilities_save = consented_capabilities_content.is_err();
+        let consented_capabilities = match consented_capabilities_content {
+            Ok(content) => serde_json::from_str(&content).log_err().unwrap_or_default(),
+            Err(_) => extension_index
+                .extensions
+                .iter()
+                .map(|(extension_id, entry)| {
+                    (extension_id.clone(), entry.manifest.capabilities.clone())
+                })
+                .collect(),
+        };
+        *this.wasm_host.capability_granter.write() =
+            CapabilityGranter::new(Some(consented_capabilities));
+        if needs_consented_capabilities_save {
+            this.save_consented_capabilities(cx);
+        }
+        this.update_capability_policy(cx);
+        cx.observe_global::<SettingsStore>(|this, cx| this.update_capability_policy(cx))
+            .detach();
+
         // Immediately load all of the extensions in the initial manifest. If the
         // index needs to be rebuild, then enqueue
         let load_initial_extensions = this.extensions_updated(extension_index, cx);
@@ -751,6 +782,12 @@ impl ExtensionStore {
             })?
             .await;
 
+            this.update(cx, |this, cx| {
+                if !this.capabilities_pending_consent(&extension_id).is_empty() {
+                    cx.emit(Event::ExtensionCapabilitiesNeedConsent(extension_id.clone()));
+                }
+            })?;
+
             if let ExtensionOperation::Install = operation {
                 this.update( cx, |this, cx| {
                     cx.emit(Event::ExtensionInstalled(extension_id.clone()));
@@ -868,6 +905,13 @@ impl ExtensionStore {
             // todo(windows)
             // Stop the server here.
             this.update(cx, |this, cx| this.reload(None, cx))?.await;
+            this.update(cx, |this, cx| {
+                this.wasm_host
+                    .capability_granter
+                    .write()
+                    .revoke(&extension_id);
+                this.save_consented_capabilities(cx);
+            })?;
 
             fs.remove_dir(
                 &work_dir,
@@ -883,6 +927,60 @@ impl ExtensionStore {
         .detach_and_log_err(cx)
     }
 
+    /// Returns the capabilities declared by the extension that the user has not consented to.
+    pub fn capabilities_pending_consent(&self, extension_id: &str) -> Vec<ExtensionCapability> {
+        let Some(manifest) = self.extension_manifest_for_id(extension_id) else {
+            return Vec::new();
+        };
+        self.wasm_host
+            .capability_granter
+            .read()
+            .capabilities_pending_consent(manifest)
+    }
+
+    /// Records that the user consented to the given capabilities of the extension.
+    pub fn consent_to_capabilities(
+        &mut self,
+        extension_id: Arc<str>,
+        capabilities: Vec<ExtensionCapability>,
+        cx: &mut Context<Self>,
+    ) {
+        self.wasm_host
+            .capability_granter
+            .write()
+            .consent(extension_id, capabilities);
+        self.save_consented_capabilities(cx);
+        cx.notify();
+    }
+
+    fn save_consented_capabilities(&self, cx: &mut Context<Self>) {
+        let Some(content) = self
+            .wasm_host
+            .capability_granter
+            .read()
+            .consented_capabilities()
+            .and_then(|consented| serde_json::to_string_pretty(consented).log_err())
+        else {
+            return;
+        };
+
+        let fs = self.fs.clone();
+        let path = self.consented_capabilities_path.clone();
+        cx.background_spawn(async move {
+            fs.save(&path, &content.as_str().into(), Default::default())
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn update_capability_policy(&self, cx: &App) {
+        let extension_settings = ExtensionSettings::get_global(cx);
+        self.wasm_host.capability_granter.write().set_policy(
+            extension_settings.allowed_extension_capabilities.clone(),
+            extension_settings.denied_extension_capabilities.clone(),
+        );
+    }
+
     pub fn install_dev_extension(
         &mut self,
         extension_source_path: PathBuf,
@@ -1019,6 +1117,18 @@ impl ExtensionStore {
         mut new_index: ExtensionIndex,
         cx: &mut Context<Self>,
     ) -> Task<()> {
+        // Dev extensions are built by the user from source, so they are granted
+        // the capabilities they declare without asking.
+        {
+            let mut capability_granter = self.wasm_host.capability_granter.write();
+            for (extension_id, entry) in &new_index.extensions {
+                if entry.dev {
+                    capability_granter
+                        .consent(extension_id.clone(), entry.manifest.capabilities.clone());
+                }
+            }
+        }
+
         let old_index = &self.extension_index;
 
         // Determine which extensions need to be loaded and unloaded, based
@@ -1135,6 +1245,19 @@ impl ExtensionStore {
             for (server_id, _) in extension.manifest.context_servers.iter() {
                 self.proxy.unregister_context_server(server_id.clone(), cx);
             }
+
+            for (provider_id, _) in extension.manifest.language_model_providers.iter() {
+                self.proxy.unregister_language_model_provider(
+                    extension_id.clone(),
+                    provider_id.clone(),
+                    cx,
+                );
+            }
+
+            for (provider_id, _) in extension.manifest.task_providers.iter() {
+                self.proxy
+                    .unregister_task_provider(extension_id.clone(), provider_id.clone(), cx);
+            }
         }
 
         self.wasm_extensions
@@ -1334,6 +1457,23 @@ impl ExtensionStore {
                         this.proxy
                             .register_debug_adapter(extension.clone(), debug_adapter.clone());
                     }
+
+                    for (provider_id, _provider) in &manifest.language_model_providers {
+                        this.proxy.register_language_model_provider(
+                            extension.clone(),
+                            provider_id.clone(),
+                            cx,
+                        );
+                    }
+
+                    for (provider_id, provider) in &manifest.task_providers {
+                        this.proxy.register_task_provider(
+                            extension.clone(),
+                            provider_id.clone(),
+                            provider.languages.clone(),
+                            cx,
+                        );
+                    }
                 }
 
                 this.wasm_extensions.extend(wasm_extensions);
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use collections::HashMap;
+use extension::ExtensionCapability;
 use gpui::App;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -18,6 +19,19 @@ pub struct ExtensionSettings {
     pub auto_install_extensions: HashMap<Arc<str>, bool>,
     #[serde(default)]
     pub auto_update_extensions: HashMap<Arc<str>, bool>,
+    /// The capabilities that extensions may be granted.
+    ///
+    /// When set, extensions are only granted the capabilities declared in their
+    /// manifests that match one of these.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub allowed_extension_capabilities: Option<Vec<ExtensionCapability>>,
+    /// The capabilities that extensions are never granted, even when allowed.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub denied_extension_capabilities: Vec<ExtensionCapability>,
 }
 
 impl ExtensionSettings {
@@ -165,6 +165,8 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         snippets: None,
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
+                        language_model_providers: Default::default(),
+                        task_providers: Default::default(),
                     }),
                     dev: false,
                 },
@@ -195,6 +197,8 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         snippets: None,
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
+                        language_model_providers: Default::default(),
+                        task_providers: Default::default(),
                     }),
                     dev: false,
                 },
@@ -370,6 +374,8 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 snippets: None,
                 capabilities: Vec::new(),
                 debug_adapters: Default::default(),
+                language_model_providers: Default::default(),
+                task_providers: Default::default(),
             }),
             dev: false,
         },
@@ -1,11 +1,13 @@
 pub mod wit;
 
 use crate::ExtensionManifest;
+use crate::capability_granter::CapabilityGranter;
 use anyhow::{Context as _, Result, anyhow, bail};
 use async_trait::async_trait;
 use extension::{
-    CodeLabel, Command, Completion, ContextServerConfiguration, DebugAdapterBinary,
-    DebugTaskDefinition, ExtensionHostProxy, KeyValueStoreDelegate, ProjectDelegate, SlashCommand,
+    CapabilityUse, CodeLabel, Command, Completion, ContextServerConfiguration, DebugAdapterBinary,
+    DebugTaskDefinition, ExtensionHostProxy, KeyValueStoreDelegate, LlmCompletionEvent,
+    LlmCompletionRequest, LlmModelInfo, ProjectDelegate, SlashCommand,
     SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, WorktreeDelegate,
 };
 use fs::{Fs, normalize_path};
@@ -17,6 +19,7 @@ use futures::{
         oneshot,
     },
     future::BoxFuture,
+    stream::BoxStream,
 };
 use gpui::{App, AsyncApp, BackgroundExecutor, Task};
 use http_client::HttpClient;
@@ -24,6 +27,7 @@ use language::LanguageName;
 use lsp::LanguageServerName;
 use moka::sync::Cache;
 use node_runtime::NodeRuntime;
+use parking_lot::RwLock;
 use release_channel::ReleaseChannel;
 use semantic_version::SemanticVersion;
 use std::borrow::Cow;
@@ -32,6 +36,9 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
+use task::TaskTemplate;
+use url::Url;
+use util::ResultExt as _;
 use wasmtime::{
     CacheStore, Engine, Store,
     component::{Component, ResourceTable},
@@ -47,6 +54,7 @@ pub struct WasmHost {
     pub(crate) proxy: Arc<ExtensionHostProxy>,
     fs: Arc<dyn Fs>,
     pub work_dir: PathBuf,
+    pub(crate) capability_granter: RwLock<CapabilityGranter>,
     _main_thread_message_task: Task<()>,
     main_thread_message_tx: mpsc::UnboundedSender<MainThreadCall>,
 }
@@ -398,10 +406,147 @@ impl extension::Extension for WasmExtension {
         })
         .await
     }
+
+    async fn llm_provider_authenticate(
+        &self,
+        provider_id: Arc<str>,
+        api_key: Option<String>,
+    ) -> Result<()> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_llm_provider_authenticate(store, &provider_id, api_key.as_deref())
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn llm_provider_models(&self, provider_id: Arc<str>) -> Result<Vec<LlmModelInfo>> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_llm_provider_models(store, &provider_id)
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn llm_count_tokens(
+        &self,
+        provider_id: Arc<str>,
+        model_id: Arc<str>,
+        request: LlmCompletionRequest,
+    ) -> Result<u64> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_llm_count_tokens(store, &provider_id, &model_id, request)
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn llm_stream_completion(
+        &self,
+        provider_id: Arc<str>,
+        model_id: Arc<str>,
+        request: LlmCompletionRequest,
+    ) -> Result<BoxStream<'static, Result<LlmCompletionEvent>>> {
+        let stream_id = self
+            .call(|extension, store| {
+                async move {
+                    extension
+                        .call_llm_stream_completion_start(store, &provider_id, &model_id, request)
+                        .await?
+                        .map_err(|err| anyhow!("{err:?}"))
+                }
+                .boxed()
+            })
+            .await?;
+
+        let stream = LlmCompletionStream {
+            extension: self.clone(),
+            stream_id,
+        };
+        Ok(futures::stream::unfold(Some(stream), |stream| async move {
+            let stream = stream?;
+            let stream_id = stream.stream_id;
+            let event = stream
+                .extension
+                .call(move |extension, store| {
+                    async move {
+                        extension
+                            .call_llm_stream_completion_next(store, stream_id)
+                            .await?
+                            .map_err(|err| anyhow!("{err:?}"))
+                    }
+                    .boxed()
+                })
+                .await;
+            match event {
+                Ok(Some(event)) => Some((Ok(event), Some(stream))),
+                Ok(None) => None,
+                Err(error) => Some((Err(error), None)),
+            }
+        })
+        .boxed())
+    }
+
+    async fn task_templates(
+        &self,
+        provider_id: Arc<str>,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Vec<TaskTemplate>> {
+        self.call(|extension, store| {
+            async move {
+                let resource = store.data_mut().table().push(worktree)?;
+                extension
+                    .call_task_templates(store, &provider_id, resource)
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))
+            }
+            .boxed()
+        })
+        .await
+    }
+}
+
+/// A completion stream in a Wasm extension, closed when dropped.
+struct LlmCompletionStream {
+    extension: WasmExtension,
+    stream_id: u64,
+}
+
+impl Drop for LlmCompletionStream {
+    fn drop(&mut self) {
+        let stream_id = self.stream_id;
+        self.extension
+            .tx
+            .unbounded_send(Box::new(move |extension, store| {
+                async move {
+                    extension
+                        .call_llm_stream_completion_close(store, stream_id)
+                        .await
+                        .log_err();
+                }
+                .boxed()
+            }))
+            .ok();
+    }
 }
 
 pub struct WasmState {
     manifest: Arc<ExtensionManifest>,
+    zed_api_version: SemanticVersion,
     pub table: ResourceTable,
     ctx: wasi::WasiCtx,
     pub host: Arc<WasmHost>,
@@ -455,6 +600,7 @@ impl WasmHost {
             http_client,
             node_runtime,
             proxy,
+            capability_granter: RwLock::default(),
             release_channel: ReleaseChannel::global(cx),
             _main_thread_message_task: task,
             main_thread_message_tx: tx,
@@ -480,6 +626,7 @@ impl WasmHost {
                 WasmState {
                     ctx: this.build_wasi_ctx(&manifest).await?,
                     manifest: manifest.clone(),
+                    zed_api_version,
                     table: ResourceTable::new(),
                     host: this.clone(),
                 },
@@ -666,6 +813,52 @@ impl WasmState {
     fn work_dir(&self) -> PathBuf {
         self.host.work_dir.join(self.manifest.id.as_ref())
     }
+
+    /// Returns an error if the extension may not use the given capability.
+    fn grant_capability(&self, capability_use: &CapabilityUse) -> Result<()> {
+        self.host.capability_granter.read().grant(
+            &self.manifest,
+            self.zed_api_version,
+            capability_use,
+        )
+    }
+
+    /// Returns an error if the extension may not make network requests to the given URL.
+    fn grant_network_request(&self, url: &str) -> Result<()> {
+        let url = Url::parse(url)?;
+        let host = url.host_str().context("URL has no host")?;
+        self.grant_capability(&CapabilityUse::NetworkRequest { host })
+    }
+
+    /// Returns an error if the extension may not download the given URL to the given path,
+    /// relative to its working directory.
+    fn grant_download_file(&self, url: &str, path: &Path) -> Result<()> {
+        let url = Url::parse(url)?;
+        let host = url.host_str().context("URL has no host")?;
+        self.grant_capability(&CapabilityUse::DownloadFile {
+            host,
+            path: &path_components(path)?,
+        })
+    }
+
+    /// Returns an error if the extension may not read the given path, relative to the root of
+    /// a worktree.
+    fn grant_worktree_read(&self, path: &Path) -> Result<()> {
+        self.grant_capability(&CapabilityUse::WorktreeRead {
+            path: &path_components(path)?,
+        })
+    }
+}
+
+fn path_components(path: &Path) -> Result<Vec<&str>> {
+    path.components()
+        .map(|component| {
+            component
+                .as_os_str()
+                .to_str()
+                .with_context(|| format!("invalid path {path:?}"))
+        })
+        .collect()
 }
 
 impl wasi::WasiView for WasmState {
@@ -804,6 +997,8 @@ mod tests {
                 args: vec!["hello!".into()],
             }],
             debug_adapters: Vec::new(),
+            language_model_providers: BTreeMap::default(),
+            task_providers: BTreeMap::default(),
         }
     }
 }
@@ -7,7 +7,10 @@ mod since_v0_3_0;
 mod since_v0_4_0;
 mod since_v0_5_0;
 mod since_v0_6_0;
-use extension::{DebugTaskDefinition, KeyValueStoreDelegate, WorktreeDelegate};
+use extension::{
+    DebugTaskDefinition, KeyValueStoreDelegate, LlmCompletionEvent, LlmCompletionRequest,
+    LlmModelInfo, WorktreeDelegate,
+};
 use language::LanguageName;
 use lsp::LanguageServerName;
 use release_channel::ReleaseChannel;
@@ -17,6 +20,7 @@ use anyhow::{Context as _, Result, anyhow};
 use semantic_version::SemanticVersion;
 use since_v0_6_0 as latest;
 use std::{ops::RangeInclusive, path::PathBuf, sync::Arc};
+use task::TaskTemplate;
 use wasmtime::{
     Store,
     component::{Component, Linker, Resource},
@@ -922,6 +926,107 @@ impl Extension {
             _ => anyhow::bail!("`get_dap_binary` not available prior to v0.6.0"),
         }
     }
+
+    pub async fn call_llm_provider_authenticate(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        api_key: Option<&str>,
+    ) -> Result<Result<(), String>> {
+        match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_llm_provider_authenticate(store, provider_id, api_key)
+                    .await
+            }
+            _ => anyhow::bail!("`llm_provider_authenticate` not available prior to v0.6.0"),
+        }
+    }
+
+    pub async fn call_llm_provider_models(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+    ) -> Result<Result<Vec<LlmModelInfo>, String>> {
+        match self {
+            Extension::V0_6_0(ext) => Ok(ext
+                .call_llm_provider_models(store, provider_id)
+                .await?
+                .map(|models| models.into_iter().map(Into::into).collect())),
+            _ => anyhow::bail!("`llm_provider_models` not available prior to v0.6.0"),
+        }
+    }
+
+    pub async fn call_llm_count_tokens(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        model_id: &str,
+        request: LlmCompletionRequest,
+    ) -> Result<Result<u64, String>> {
+        match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_llm_count_tokens(store, provider_id, model_id, &request.into())
+                    .await
+            }
+            _ => anyhow::bail!("`llm_count_tokens` not available prior to v0.6.0"),
+        }
+    }
+
+    pub async fn call_llm_stream_completion_start(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        model_id: &str,
+        request: LlmCompletionRequest,
+    ) -> Result<Result<u64, String>> {
+        match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_llm_stream_completion_start(store, provider_id, model_id, &request.into())
+                    .await
+            }
+            _ => anyhow::bail!("`llm_stream_completion_start` not available prior to v0.6.0"),
+        }
+    }
+
+    pub async fn call_llm_stream_completion_next(
+        &self,
+        store: &mut Store<WasmState>,
+        stream_id: u64,
+    ) -> Result<Result<Option<LlmCompletionEvent>, String>> {
+        match self {
+            Extension::V0_6_0(ext) => Ok(ext
+                .call_llm_stream_completion_next(store, stream_id)
+                .await?
+                .map(|event| event.map(Into::into))),
+            _ => anyhow::bail!("`llm_stream_completion_next` not available prior to v0.6.0"),
+        }
+    }
+
+    pub async fn call_llm_stream_completion_close(
+        &self,
+        store: &mut Store<WasmState>,
+        stream_id: u64,
+    ) -> Result<()> {
+        match self {
+            Extension::V0_6_0(ext) => ext.call_llm_stream_completion_close(store, stream_id).await,
+            _ => anyhow::bail!("`llm_stream_completion_close` not available prior to v0.6.0"),
+        }
+    }
+
+    pub async fn call_task_templates(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        resource: Resource<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<Result<Vec<TaskTemplate>, String>> {
+        match self {
+            Extension::V0_6_0(ext) => Ok(ext
+                .call_task_templates(store, provider_id, resource)
+                .await?
+                .map(|templates| templates.into_iter().map(Into::into).collect())),
+            _ => anyhow::bail!("`task_templates` not available prior to v0.6.0"),
+        }
+    }
 }
 
 trait ToWasmtimeResult<T> {
@@ -295,6 +295,7 @@ impl http_client::Host for WasmState {
     ) -> wasmtime::Result<Result<http_client::HttpResponse, String>> {
         maybe!(async {
             let url = &request.url;
+            self.grant_network_request(url)?;
             let request = convert_request(&request)?;
             let mut response = self.host.http_client.send(request).await?;
 
@@ -311,6 +312,9 @@ impl http_client::Host for WasmState {
         &mut self,
         request: http_client::HttpRequest,
     ) -> wasmtime::Result<Result<Resource<ExtensionHttpResponseStream>, String>> {
+        if let Err(error) = self.grant_network_request(&request.url) {
+            return Ok(Err(format!("{error:?}")));
+        }
         let request = convert_request(&request)?;
         let response = self.host.http_client.send(request);
         maybe!(async {
@@ -503,6 +507,7 @@ impl ExtensionImports for WasmState {
             let destination_path = self
                 .host
                 .writeable_path_from_extension(&self.manifest.id, &path)?;
+            self.grant_download_file(&url, destination_path.strip_prefix(&extension_work_dir)?)?;
 
             let mut response = self
                 .host
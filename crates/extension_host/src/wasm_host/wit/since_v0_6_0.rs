@@ -14,7 +14,8 @@ use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use async_trait::async_trait;
 use extension::{
-    ExtensionLanguageServerProxy, KeyValueStoreDelegate, ProjectDelegate, WorktreeDelegate,
+    CapabilityUse, ExtensionLanguageServerProxy, KeyValueStoreDelegate, ProjectDelegate,
+    WorktreeDelegate,
 };
 use futures::{AsyncReadExt, lock::Mutex};
 use futures::{FutureExt as _, io::BufReader};
@@ -363,6 +364,179 @@ impl TryFrom<ContextServerConfiguration> for extension::ContextServerConfigurati
     }
 }
 
+impl From<extension::LlmCompletionRequest> for llm_provider::CompletionRequest {
+    fn from(value: extension::LlmCompletionRequest) -> Self {
+        Self {
+            messages: value.messages.into_iter().map(Into::into).collect(),
+            tools: value.tools.into_iter().map(Into::into).collect(),
+            tool_choice: value.tool_choice.map(Into::into),
+            stop_sequences: value.stop_sequences,
+            temperature: value.temperature,
+            max_tokens: value.max_tokens,
+        }
+    }
+}
+
+impl From<extension::LlmRequestMessage> for llm_provider::RequestMessage {
+    fn from(value: extension::LlmRequestMessage) -> Self {
+        Self {
+            role: value.role.into(),
+            content: value.content.into_iter().map(Into::into).collect(),
+            cache: value.cache,
+        }
+    }
+}
+
+impl From<extension::LlmMessageRole> for llm_provider::MessageRole {
+    fn from(value: extension::LlmMessageRole) -> Self {
+        match value {
+            extension::LlmMessageRole::User => Self::User,
+            extension::LlmMessageRole::Assistant => Self::Assistant,
+            extension::LlmMessageRole::System => Self::System,
+        }
+    }
+}
+
+impl From<extension::LlmMessageContent> for llm_provider::MessageContent {
+    fn from(value: extension::LlmMessageContent) -> Self {
+        match value {
+            extension::LlmMessageContent::Text(text) => Self::Text(text),
+            extension::LlmMessageContent::Thinking(thinking) => Self::Thinking(thinking.into()),
+            extension::LlmMessageContent::Image(image) => Self::Image(image),
+            extension::LlmMessageContent::ToolUse(tool_use) => Self::ToolUse(tool_use.into()),
+            extension::LlmMessageContent::ToolResult(tool_result) => {
+                Self::ToolResult(tool_result.into())
+            }
+        }
+    }
+}
+
+impl From<extension::LlmThinking> for llm_provider::ThinkingContent {
+    fn from(value: extension::LlmThinking) -> Self {
+        Self {
+            text: value.text,
+            signature: value.signature,
+        }
+    }
+}
+
+impl From<llm_provider::ThinkingContent> for extension::LlmThinking {
+    fn from(value: llm_provider::ThinkingContent) -> Self {
+        Self {
+            text: value.text,
+            signature: value.signature,
+        }
+    }
+}
+
+impl From<extension::LlmToolUse> for llm_provider::ToolUse {
+    fn from(value: extension::LlmToolUse) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            input: value.input,
+        }
+    }
+}
+
+impl From<llm_provider::ToolUse> for extension::LlmToolUse {
+    fn from(value: llm_provider::ToolUse) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            input: value.input,
+        }
+    }
+}
+
+impl From<extension::LlmToolResult> for llm_provider::ToolResult {
+    fn from(value: extension::LlmToolResult) -> Self {
+        Self {
+            tool_use_id: value.tool_use_id,
+            tool_name: value.tool_name,
+            is_error: value.is_error,
+            content: match value.content {
+                extension::LlmToolResultContent::Text(text) => {
+                    llm_provider::ToolResultContent::Text(text)
+                }
+                extension::LlmToolResultContent::Image(image) => {
+                    llm_provider::ToolResultContent::Image(image)
+                }
+            },
+        }
+    }
+}
+
+impl From<extension::LlmToolDefinition> for llm_provider::ToolDefinition {
+    fn from(value: extension::LlmToolDefinition) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+            input_schema: value.input_schema,
+        }
+    }
+}
+
+impl From<extension::LlmToolChoice> for llm_provider::ToolChoice {
+    fn from(value: extension::LlmToolChoice) -> Self {
+        match value {
+            extension::LlmToolChoice::Auto => Self::Auto,
+            extension::LlmToolChoice::Any => Self::Any,
+            extension::LlmToolChoice::None => Self::None,
+        }
+    }
+}
+
+impl From<llm_provider::ModelInfo> for extension::LlmModelInfo {
+    fn from(value: llm_provider::ModelInfo) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            max_token_count: value.max_token_count,
+            max_output_tokens: value.max_output_tokens,
+            supports_images: value.supports_images,
+            supports_tools: value.supports_tools,
+            is_default: value.is_default,
+            is_default_fast: value.is_default_fast,
+        }
+    }
+}
+
+impl From<llm_provider::CompletionEvent> for extension::LlmCompletionEvent {
+    fn from(value: llm_provider::CompletionEvent) -> Self {
+        match value {
+            llm_provider::CompletionEvent::Text(text) => Self::Text(text),
+            llm_provider::CompletionEvent::Thinking(thinking) => Self::Thinking(thinking.into()),
+            llm_provider::CompletionEvent::ToolUse(tool_use) => Self::ToolUse(tool_use.into()),
+            llm_provider::CompletionEvent::Stop(stop_reason) => Self::Stop(match stop_reason {
+                llm_provider::StopReason::EndTurn => extension::LlmStopReason::EndTurn,
+                llm_provider::StopReason::MaxTokens => extension::LlmStopReason::MaxTokens,
+                llm_provider::StopReason::ToolUse => extension::LlmStopReason::ToolUse,
+            }),
+            llm_provider::CompletionEvent::Usage(usage) => Self::Usage(extension::LlmTokenUsage {
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                cache_creation_input_tokens: usage.cache_creation_input_tokens,
+                cache_read_input_tokens: usage.cache_read_input_tokens,
+            }),
+        }
+    }
+}
+
+impl From<task_provider::TaskTemplate> for task::TaskTemplate {
+    fn from(value: task_provider::TaskTemplate) -> Self {
+        Self {
+            label: value.label,
+            command: value.command,
+            args: value.args,
+            env: value.env.into_iter().collect(),
+            cwd: value.cwd,
+            tags: value.tags,
+            ..Default::default()
+        }
+    }
+}
+
 impl HostKeyValueStore for WasmState {
     async fn insert(
         &mut self,
@@ -415,8 +589,12 @@ impl HostWorktree for WasmState {
         path: String,
     ) -> wasmtime::Result<Result<String, String>> {
         let delegate = self.table.get(&delegate)?;
+        let path = PathBuf::from(path);
+        if let Err(error) = self.grant_worktree_read(&path) {
+            return Ok(Err(error.to_string()));
+        }
         Ok(delegate
-            .read_text_file(path.into())
+            .read_text_file(path)
             .await
             .map_err(|error| error.to_string()))
     }
@@ -453,8 +631,9 @@ impl http_client::Host for WasmState {
     ) -> wasmtime::Result<Result<http_client::HttpResponse, String>> {
         maybe!(async {
             let url = &request.url;
-            let request = convert_request(&request)?;
-            let mut response = self.host.http_client.send(request).await?;
+            let mut response = self
+                .send_request(&request, |url| self.grant_network_request(url))
+                .await?;
 
             if response.status().is_client_error() || response.status().is_server_error() {
                 bail!("failed to fetch '{url}': status code {}", response.status())
@@ -469,10 +648,10 @@ impl http_client::Host for WasmState {
         &mut self,
         request: http_client::HttpRequest,
     ) -> wasmtime::Result<Result<Resource<ExtensionHttpResponseStream>, String>> {
-        let request = convert_request(&request)?;
-        let response = self.host.http_client.send(request);
         maybe!(async {
-            let response = response.await?;
+            let response = self
+                .send_request(&request, |url| self.grant_network_request(url))
+                .await?;
             let stream = Arc::new(Mutex::new(response));
             let resource = self.table.push(stream)?;
             Ok(resource)
@@ -482,6 +661,68 @@ impl http_client::Host for WasmState {
     }
 }
 
+impl WasmState {
+    /// Sends the extension's request, following its redirects one at a time, so that `grant`
+    /// can check every URL the request reaches and not only the first one.
+    async fn send_request(
+        &self,
+        extension_request: &http_client::HttpRequest,
+        grant: impl Fn(&str) -> Result<()>,
+    ) -> Result<::http_client::Response<AsyncBody>> {
+        let max_redirects = match extension_request.redirect_policy {
+            http_client::RedirectPolicy::NoFollow => 0,
+            http_client::RedirectPolicy::FollowLimit(limit) => limit,
+            http_client::RedirectPolicy::FollowAll => MAX_REDIRECTS,
+        };
+        let mut method = ::http_client::Method::from(extension_request.method);
+        let mut url = ::http_client::Url::parse(&extension_request.url)?;
+        let mut headers = extension_request.headers.clone();
+        let mut body = extension_request.body.clone();
+        let mut redirects = 0;
+        loop {
+            grant(url.as_str())?;
+            let request = convert_request(&method, &url, &headers, body.clone())?;
+            let response = self.host.http_client.send(request).await?;
+
+            let status = response.status();
+            let location = response
+                .headers()
+                .get(::http_client::http::header::LOCATION);
+            let Some(location) = location.filter(|_| status.is_redirection() && max_redirects > 0)
+            else {
+                return Ok(response);
+            };
+            anyhow::ensure!(
+                redirects < max_redirects,
+                "too many redirects fetching '{}'",
+                extension_request.url
+            );
+            redirects += 1;
+
+            let next_url = url.join(location.to_str()?)?;
+            if next_url.host_str() != url.host_str() {
+                headers.retain(|(key, _)| {
+                    !SENSITIVE_HEADERS
+                        .iter()
+                        .any(|header| key.eq_ignore_ascii_case(header))
+                });
+            }
+            if status == ::http_client::StatusCode::SEE_OTHER
+                || (method == ::http_client::Method::POST
+                    && matches!(
+                        status,
+                        ::http_client::StatusCode::MOVED_PERMANENTLY
+                            | ::http_client::StatusCode::FOUND
+                    ))
+            {
+                method = ::http_client::Method::GET;
+                body = None;
+            }
+            url = next_url;
+        }
+    }
+}
+
 impl http_client::HostHttpResponseStream for WasmState {
     async fn next_chunk(
         &mut self,
@@ -522,27 +763,26 @@ impl From<http_client::HttpMethod> for ::http_client::Method {
     }
 }
 
+/// The most redirects followed for requests that follow all of them.
+const MAX_REDIRECTS: u32 = 20;
+
+/// The headers that are not sent along when a request is redirected to another host.
+const SENSITIVE_HEADERS: &[&str] = &["authorization", "cookie", "proxy-authorization"];
+
 fn convert_request(
-    extension_request: &http_client::HttpRequest,
+    method: &::http_client::Method,
+    url: &::http_client::Url,
+    headers: &[(String, String)],
+    body: Option<Vec<u8>>,
 ) -> anyhow::Result<::http_client::Request<AsyncBody>> {
     let mut request = ::http_client::Request::builder()
-        .method(::http_client::Method::from(extension_request.method))
-        .uri(&extension_request.url)
-        .follow_redirects(match extension_request.redirect_policy {
-            http_client::RedirectPolicy::NoFollow => ::http_client::RedirectPolicy::NoFollow,
-            http_client::RedirectPolicy::FollowLimit(limit) => {
-                ::http_client::RedirectPolicy::FollowLimit(limit)
-            }
-            http_client::RedirectPolicy::FollowAll => ::http_client::RedirectPolicy::FollowAll,
-        });
-    for (key, value) in &extension_request.headers {
+        .method(method.clone())
+        .uri(url.as_str())
+        .follow_redirects(::http_client::RedirectPolicy::NoFollow);
+    for (key, value) in headers {
         request = request.header(key, value);
     }
-    let body = extension_request
-        .body
-        .clone()
-        .map(AsyncBody::from)
-        .unwrap_or_default();
+    let body = body.map(AsyncBody::from).unwrap_or_default();
     request.body(body).map_err(anyhow::Error::from)
 }
 
@@ -708,7 +948,11 @@ impl process::Host for WasmState {
         command: process::Command,
     ) -> wasmtime::Result<Result<process::Output, String>> {
         maybe!(async {
-            self.manifest.allow_exec(&command.command, &command.args)?;
+            let args = command.args.iter().map(String::as_str).collect::<Vec<_>>();
+            self.grant_capability(&CapabilityUse::ProcessExec {
+                command: &command.command,
+                args: &args,
+            })?;
 
             let output = util::command::new_smol_command(command.command.as_str())
                 .args(&command.args)
@@ -729,6 +973,12 @@ impl slash_command::Host for WasmState {}
 #[async_trait]
 impl context_server::Host for WasmState {}
 
+#[async_trait]
+impl llm_provider::Host for WasmState {}
+
+#[async_trait]
+impl task_provider::Host for WasmState {}
+
 impl dap::Host for WasmState {
     async fn resolve_tcp_template(
         &mut self,
@@ -865,11 +1115,17 @@ impl ExtensionImports for WasmState {
             let destination_path = self
                 .host
                 .writeable_path_from_extension(&self.manifest.id, &path)?;
-
+            let relative_path = destination_path.strip_prefix(&extension_work_dir)?;
+
+            let request = http_client::HttpRequest {
+                method: http_client::HttpMethod::Get,
+                url,
+                headers: Vec::new(),
+                body: None,
+                redirect_policy: http_client::RedirectPolicy::FollowAll,
+            };
             let mut response = self
-                .host
-                .http_client
-                .get(&url, Default::default(), true)
+                .send_request(&request, |url| self.grant_download_file(url, relative_path))
                 .await
                 .context("downloading release")?;
 
@@ -9,11 +9,12 @@ use std::{ops::Range, sync::Arc};
 use client::{ExtensionMetadata, ExtensionProvides};
 use collections::{BTreeMap, BTreeSet};
 use editor::{Editor, EditorElement, EditorStyle};
+use extension::ExtensionCapability;
 use extension_host::{ExtensionManifest, ExtensionOperation, ExtensionStore};
 use fuzzy::{StringMatchCandidate, match_strings};
 use gpui::{
-    Action, App, ClipboardItem, Context, Entity, EventEmitter, Flatten, Focusable,
-    InteractiveElement, KeyContext, ParentElement, Render, Styled, Task, TextStyle,
+    Action, App, ClipboardItem, Context, DismissEvent, Entity, EventEmitter, Flatten, Focusable,
+    InteractiveElement, KeyContext, ParentElement, PromptLevel, Render, Styled, Task, TextStyle,
     UniformListScrollHandle, WeakEntity, Window, actions, point, uniform_list,
 };
 use num_format::{Locale, ToFormattedString};
@@ -30,6 +31,10 @@ use vim_mode_setting::VimModeSetting;
 use workspace::{
     Workspace, WorkspaceId,
     item::{Item, ItemEvent},
+    notifications::{
+        NotificationId, dismiss_app_notification, show_app_notification,
+        simple_message_notification::MessageNotification,
+    },
 };
 use zed_actions::ExtensionCategoryFilter;
 
@@ -159,6 +164,17 @@ pub fn init(cx: &mut App) {
         .detach();
     })
     .detach();
+
+    // Extensions get installed and upgraded without the extensions page, e.g. when they are
+    // auto-installed or auto-updated, so the consent is asked for in all workspaces.
+    if let Some(store) = ExtensionStore::try_global(cx) {
+        cx.subscribe(&store, |_, event, cx| {
+            if let extension_host::Event::ExtensionCapabilitiesNeedConsent(extension_id) = event {
+                show_capability_consent_notification(extension_id.clone(), cx);
+            }
+        })
+        .detach();
+    }
 }
 
 fn extension_provides_label(provides: ExtensionProvides) -> &'static str {
@@ -249,10 +265,118 @@ fn keywords_by_feature() -> &'static BTreeMap<Feature, Vec<&'static str>> {
     })
 }
 
+struct CapabilityConsentNotification;
+
+fn capability_consent_notification_id(extension_id: &str) -> NotificationId {
+    NotificationId::composite::<CapabilityConsentNotification>(SharedString::from(
+        extension_id.to_string(),
+    ))
+}
+
+/// The capabilities of the extension that the user has not consented to yet, along with the
+/// question to ask the user and the list of capabilities to show them.
+fn pending_capability_consent(
+    extension_id: &str,
+    cx: &App,
+) -> Option<(Vec<ExtensionCapability>, String, String)> {
+    let store = ExtensionStore::global(cx);
+    let store = store.read(cx);
+    let capabilities = store.capabilities_pending_consent(extension_id);
+    if capabilities.is_empty() {
+        return None;
+    }
+
+    let extension_name = store.extension_manifest_for_id(extension_id).map_or_else(
+        || extension_id.to_string(),
+        |manifest| manifest.name.clone(),
+    );
+    let detail = capabilities
+        .iter()
+        .map(|capability| format!("• {capability}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some((
+        capabilities,
+        format!("Allow the {extension_name} extension to do the following?"),
+        detail,
+    ))
+}
+
+/// Shows a notification in every workspace asking the user to allow or deny the capabilities
+/// of the extension that they have not consented to yet.
+fn show_capability_consent_notification(extension_id: Arc<str>, cx: &mut App) {
+    let Some((capabilities, question, detail)) = pending_capability_consent(&extension_id, cx)
+    else {
+        return;
+    };
+
+    let message = SharedString::from(format!("{question}\n{detail}"));
+    show_app_notification(
+        capability_consent_notification_id(&extension_id),
+        cx,
+        move |cx| {
+            let extension_id = extension_id.clone();
+            let capabilities = capabilities.clone();
+            cx.new(|cx| {
+                MessageNotification::new(message.clone(), cx)
+                    .primary_message("Allow")
+                    .primary_icon(IconName::Check)
+                    .primary_icon_color(Color::Success)
+                    .primary_on_click(move |_, cx| {
+                        ExtensionStore::global(cx).update(cx, |store, cx| {
+                            store.consent_to_capabilities(
+                                extension_id.clone(),
+                                capabilities.clone(),
+                                cx,
+                            )
+                        });
+                        cx.emit(DismissEvent);
+                    })
+                    .secondary_message("Deny")
+                    .secondary_icon(IconName::Close)
+                    .secondary_icon_color(Color::Error)
+                    .secondary_on_click(|_, cx| cx.emit(DismissEvent))
+                    .show_suppress_button(false)
+            })
+        },
+    );
+}
+
+/// Asks the user to consent to the capabilities of the extension that they have not consented to yet.
+fn prompt_for_capability_consent(extension_id: Arc<str>, window: &mut Window, cx: &mut App) {
+    let Some((capabilities, question, detail)) = pending_capability_consent(&extension_id, cx)
+    else {
+        return;
+    };
+
+    let answer = window.prompt(
+        PromptLevel::Warning,
+        &question,
+        Some(&detail),
+        &["Allow", "Deny"],
+        cx,
+    );
+    cx.spawn(async move |cx| {
+        if answer.await.ok() != Some(0) {
+            return;
+        }
+
+        cx.update(|cx| {
+            dismiss_app_notification(&capability_consent_notification_id(&extension_id), cx);
+            ExtensionStore::global(cx).update(cx, |store, cx| {
+                store.consent_to_capabilities(extension_id, capabilities, cx)
+            });
+        })
+        .ok();
+    })
+    .detach();
+}
+
 struct ExtensionCardButtons {
     install_or_uninstall: Button,
     upgrade: Option<Button>,
     configure: Option<Button>,
+    review_capabilities: Option<Button>,
 }
 
 pub struct ExtensionsPage {
@@ -734,6 +858,7 @@ impl ExtensionsPage {
                         h_flex()
                             .gap_2()
                             .justify_between()
+                            .children(buttons.review_capabilities)
                             .children(buttons.upgrade)
                             .children(buttons.configure)
                             .child(buttons.install_or_uninstall),
@@ -915,6 +1040,7 @@ impl ExtensionsPage {
                 ),
                 configure: None,
                 upgrade: None,
+                review_capabilities: None,
             };
         }
 
@@ -922,6 +1048,10 @@ impl ExtensionsPage {
             .manifest
             .provides
             .contains(&ExtensionProvides::ContextServers);
+        let has_capabilities_pending_consent = !ExtensionStore::global(cx)
+            .read(cx)
+            .capabilities_pending_consent(&extension.id)
+            .is_empty();
 
         match status.clone() {
             ExtensionStatus::NotInstalled => ExtensionCardButtons {
@@ -940,6 +1070,7 @@ impl ExtensionsPage {
                 }),
                 configure: None,
                 upgrade: None,
+                review_capabilities: None,
             },
             ExtensionStatus::Installing => ExtensionCardButtons {
                 install_or_uninstall: Button::new(
@@ -949,6 +1080,7 @@ impl ExtensionsPage {
                 .disabled(true),
                 configure: None,
                 upgrade: None,
+                review_capabilities: None,
             },
             ExtensionStatus::Upgrading => ExtensionCardButtons {
                 install_or_uninstall: Button::new(
@@ -966,6 +1098,7 @@ impl ExtensionsPage {
                 upgrade: Some(
                     Button::new(SharedString::from(extension.id.clone()), "Upgrade").disabled(true),
                 ),
+                review_capabilities: None,
             },
             ExtensionStatus::Installed(installed_version) => ExtensionCardButtons {
                 install_or_uninstall: Button::new(
@@ -1043,6 +1176,18 @@ impl ExtensionsPage {
                             }),
                     )
                 },
+                review_capabilities: has_capabilities_pending_consent.then(|| {
+                    Button::new(
+                        SharedString::from(format!("review-capabilities-{}", extension.id)),
+                        "Review Capabilities",
+                    )
+                    .on_click({
+                        let extension_id = extension.id.clone();
+                        move |_, window, cx| {
+                            prompt_for_capability_consent(extension_id.clone(), window, cx)
+                        }
+                    })
+                }),
             },
             ExtensionStatus::Removing => ExtensionCardButtons {
                 install_or_uninstall: Button::new(
@@ -1058,6 +1203,7 @@ impl ExtensionsPage {
                     .disabled(true)
                 }),
                 upgrade: None,
+                review_capabilities: None,
             },
         }
     }
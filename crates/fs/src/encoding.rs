@@ -0,0 +1,333 @@
+//! The text encodings of the files, detected when they are loaded and preserved when they are saved.
+
+use std::fmt;
+
+use anyhow::{Result, anyhow};
+
+/// How much of the file the encoding detector looks at.
+const DETECTION_LIMIT: usize = 1 << 20;
+/// How much of the file is checked for the zero bytes of UTF-16 without a byte order mark.
+const UTF_16_DETECTION_LIMIT: usize = 4096;
+
+/// The encoding of a text file, and whether the file starts with a byte order mark.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Encoding {
+    encoding: &'static encoding_rs::Encoding,
+    bom: bool,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self::UTF_8
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.bom {
+            write!(f, "{} with BOM", self.name())
+        } else {
+            f.write_str(self.name())
+        }
+    }
+}
+
+impl Encoding {
+    pub const UTF_8: Self = Self {
+        encoding: encoding_rs::UTF_8,
+        bom: false,
+    };
+
+    const fn new(encoding: &'static encoding_rs::Encoding, bom: bool) -> Self {
+        Self { encoding, bom }
+    }
+
+    /// The encodings that files can be reopened or saved with.
+    pub fn all() -> &'static [Self] {
+        const ALL: &[Encoding] = &[
+            Encoding::UTF_8,
+            Encoding::new(encoding_rs::UTF_8, true),
+            Encoding::new(encoding_rs::UTF_16LE, true),
+            Encoding::new(encoding_rs::UTF_16BE, true),
+            Encoding::new(encoding_rs::WINDOWS_1252, false),
+            Encoding::new(encoding_rs::ISO_8859_2, false),
+            Encoding::new(encoding_rs::ISO_8859_15, false),
+            Encoding::new(encoding_rs::WINDOWS_1250, false),
+            Encoding::new(encoding_rs::WINDOWS_1251, false),
+            Encoding::new(encoding_rs::WINDOWS_1253, false),
+            Encoding::new(encoding_rs::WINDOWS_1254, false),
+            Encoding::new(encoding_rs::WINDOWS_1255, false),
+            Encoding::new(encoding_rs::WINDOWS_1256, false),
+            Encoding::new(encoding_rs::WINDOWS_1257, false),
+            Encoding::new(encoding_rs::WINDOWS_1258, false),
+            Encoding::new(encoding_rs::WINDOWS_874, false),
+            Encoding::new(encoding_rs::ISO_8859_5, false),
+            Encoding::new(encoding_rs::ISO_8859_7, false),
+            Encoding::new(encoding_rs::KOI8_R, false),
+            Encoding::new(encoding_rs::KOI8_U, false),
+            Encoding::new(encoding_rs::IBM866, false),
+            Encoding::new(encoding_rs::MACINTOSH, false),
+            Encoding::new(encoding_rs::SHIFT_JIS, false),
+            Encoding::new(encoding_rs::EUC_JP, false),
+            Encoding::new(encoding_rs::ISO_2022_JP, false),
+            Encoding::new(encoding_rs::GBK, false),
+            Encoding::new(encoding_rs::GB18030, false),
+            Encoding::new(encoding_rs::BIG5, false),
+            Encoding::new(encoding_rs::EUC_KR, false),
+        ];
+        ALL
+    }
+
+    /// Looks up the encoding by its name or by any of the labels that the WHATWG Encoding Standard defines.
+    pub fn from_name(name: &str, bom: bool) -> Option<Self> {
+        let encoding = encoding_rs::Encoding::for_label(name.as_bytes())?;
+        Some(Self::new(encoding, bom && has_bom(encoding)))
+    }
+
+    /// The canonical name of the encoding, e.g. `Shift_JIS`.
+    pub fn name(&self) -> &'static str {
+        self.encoding.name()
+    }
+
+    pub fn has_bom(&self) -> bool {
+        self.bom
+    }
+
+    pub fn is_utf_8(&self) -> bool {
+        self.encoding == encoding_rs::UTF_8
+    }
+
+    /// Decodes the file contents. Without an encoding, it is detected from the contents,
+    /// and the files that look binary fail to decode.
+    pub fn decode(bytes: Vec<u8>, encoding: Option<Self>) -> Result<(String, Self)> {
+        match encoding {
+            Some(encoding) => Ok(encoding.decode_lossy(bytes)),
+            None => Self::detect(bytes),
+        }
+    }
+
+    fn detect(bytes: Vec<u8>) -> Result<(String, Self)> {
+        let encoding = Self::guess(&bytes, true)?;
+        Ok(encoding.decode_lossy(bytes))
+    }
+
+    /// Detects the encoding from the start of a file that is decoded in chunks.
+    pub fn detect_from_prefix(prefix: &[u8]) -> Result<Self> {
+        Self::guess(prefix, false)
+    }
+
+    fn guess(bytes: &[u8], complete: bool) -> Result<Self> {
+        if let Some((encoding, _)) = encoding_rs::Encoding::for_bom(bytes) {
+            return Ok(Self::new(encoding, true));
+        }
+        // ASCII text in UTF-16 is valid UTF-8 too, so UTF-16 is detected first.
+        if let Some(encoding) = detect_utf_16(bytes) {
+            return Ok(Self::new(encoding, false));
+        }
+        match std::str::from_utf8(bytes) {
+            Ok(_) => return Ok(Self::UTF_8),
+            // A prefix can end in the middle of a character.
+            Err(error) if !complete && error.error_len().is_none() => return Ok(Self::UTF_8),
+            Err(_) => {}
+        }
+        if bytes.contains(&0) {
+            return Err(anyhow!("file is not valid text"));
+        }
+
+        let mut detector = chardetng::EncodingDetector::new();
+        detector.feed(&bytes[..bytes.len().min(DETECTION_LIMIT)], complete);
+        Ok(Self::new(detector.guess(None, false), false))
+    }
+
+    /// Creates a decoder for the contents that are read in chunks, which skips the byte order mark.
+    pub fn decoder(&self) -> Decoder {
+        Decoder {
+            decoder: self.encoding.new_decoder_with_bom_removal(),
+        }
+    }
+
+    /// Decodes the bytes, skipping the byte order mark and replacing the malformed sequences.
+    fn decode_lossy(self, bytes: Vec<u8>) -> (String, Self) {
+        let bom_len = match encoding_rs::Encoding::for_bom(&bytes) {
+            Some((encoding, bom_len)) if encoding == self.encoding => bom_len,
+            _ => 0,
+        };
+        let encoding = Self::new(self.encoding, bom_len > 0);
+        if encoding.is_utf_8() {
+            let text = match String::from_utf8(bytes) {
+                Ok(mut text) => {
+                    text.drain(..bom_len);
+                    text
+                }
+                Err(error) => String::from_utf8_lossy(&error.into_bytes()[bom_len..]).into_owned(),
+            };
+            return (text, encoding);
+        }
+        let (text, _) = self.encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        (text.into_owned(), encoding)
+    }
+
+    /// Encodes the text, failing if the encoding cannot represent some of its characters.
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(text.len() + 3);
+        if self.bom {
+            bytes.extend_from_slice(bom(self.encoding));
+        }
+        // encoding_rs only decodes UTF-16, so the text is encoded by hand.
+        if self.encoding == encoding_rs::UTF_16LE {
+            bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        } else if self.encoding == encoding_rs::UTF_16BE {
+            bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+        } else {
+            let (encoded, _, unmappable) = self.encoding.encode(text);
+            if unmappable {
+                return Err(anyhow!(
+                    "{self} cannot represent all characters of the text"
+                ));
+            }
+            bytes.extend_from_slice(&encoded);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Decodes the contents of a file that is read in chunks.
+pub struct Decoder {
+    decoder: encoding_rs::Decoder,
+}
+
+impl Decoder {
+    /// Appends the decoded chunk to the text, replacing the malformed sequences.
+    /// The characters split between the chunks are decoded with the next one.
+    pub fn decode(&mut self, mut bytes: &[u8], last: bool, text: &mut String) {
+        loop {
+            if let Some(len) = self.decoder.max_utf8_buffer_length(bytes.len()) {
+                text.reserve(len);
+            }
+            let (result, read, _) = self.decoder.decode_to_string(bytes, text, last);
+            bytes = &bytes[read..];
+            if matches!(result, encoding_rs::CoderResult::InputEmpty) {
+                break;
+            }
+        }
+    }
+}
+
+fn has_bom(encoding: &'static encoding_rs::Encoding) -> bool {
+    !bom(encoding).is_empty()
+}
+
+fn bom(encoding: &'static encoding_rs::Encoding) -> &'static [u8] {
+    if encoding == encoding_rs::UTF_8 {
+        b"\xEF\xBB\xBF"
+    } else if encoding == encoding_rs::UTF_16LE {
+        b"\xFF\xFE"
+    } else if encoding == encoding_rs::UTF_16BE {
+        b"\xFE\xFF"
+    } else {
+        b""
+    }
+}
+
+/// Detects UTF-16 without a byte order mark, from the zero high bytes of ASCII characters.
+fn detect_utf_16(bytes: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    if bytes.len() < 2 || bytes.len() % 2 != 0 {
+        return None;
+    }
+    let sample = &bytes[..bytes.len().min(UTF_16_DETECTION_LIMIT)];
+    let units = sample.len() / 2;
+    let (mut even_zeros, mut odd_zeros) = (0, 0);
+    for pair in sample.chunks_exact(2) {
+        even_zeros += (pair[0] == 0) as usize;
+        odd_zeros += (pair[1] == 0) as usize;
+    }
+    if odd_zeros * 2 > units && even_zeros * 10 < units {
+        Some(encoding_rs::UTF_16LE)
+    } else if even_zeros * 2 > units && odd_zeros * 10 < units {
+        Some(encoding_rs::UTF_16BE)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detecting_encodings() {
+        let (text, encoding) = Encoding::decode(b"plain text".to_vec(), None).unwrap();
+        assert_eq!((text.as_str(), encoding), ("plain text", Encoding::UTF_8));
+
+        let (text, encoding) = Encoding::decode(b"\xEF\xBB\xBFbom".to_vec(), None).unwrap();
+        assert_eq!(text, "bom");
+        assert_eq!(encoding.to_string(), "UTF-8 with BOM");
+
+        let (text, encoding) = Encoding::decode(b"\xFF\xFEh\0i\0".to_vec(), None).unwrap();
+        assert_eq!(text, "hi");
+        assert_eq!(encoding.to_string(), "UTF-16LE with BOM");
+
+        let (text, encoding) = Encoding::decode(b"\0h\0i\0!".to_vec(), None).unwrap();
+        assert_eq!(text, "hi!");
+        assert_eq!(encoding.to_string(), "UTF-16BE");
+
+        let shift_jis = "日本語のテキストです。これはテストです。";
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode(shift_jis);
+        let (text, encoding) = Encoding::decode(bytes.into_owned(), None).unwrap();
+        assert_eq!(text, shift_jis);
+        assert_eq!(encoding.name(), "Shift_JIS");
+
+        let (text, encoding) = Encoding::decode(b"caf\xE9 cr\xE8me".to_vec(), None).unwrap();
+        assert_eq!(text, "café crème");
+        assert_eq!(encoding.name(), "windows-1252");
+
+        assert!(Encoding::decode(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec(), None).is_err());
+    }
+
+    #[test]
+    fn test_decoding_in_chunks() {
+        let text = "a → b\nc";
+        let mut bytes = b"\xEF\xBB\xBF".to_vec();
+        bytes.extend_from_slice(text.as_bytes());
+        let encoding = Encoding::detect_from_prefix(&bytes[..5]).unwrap();
+        assert_eq!(encoding.to_string(), "UTF-8 with BOM");
+
+        // The chunks split the BOM and the arrow.
+        let mut decoder = encoding.decoder();
+        let mut decoded = String::new();
+        for (ix, chunk) in bytes.chunks(2).enumerate() {
+            decoder.decode(chunk, (ix + 1) * 2 >= bytes.len(), &mut decoded);
+        }
+        assert_eq!(decoded, text);
+
+        assert_eq!(
+            Encoding::detect_from_prefix(&"→".as_bytes()[..2]).unwrap(),
+            Encoding::UTF_8
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = "ÀÉÎõü\n";
+        for &encoding in Encoding::all() {
+            let Ok(bytes) = encoding.encode(text) else {
+                continue;
+            };
+            let (decoded, decoded_encoding) = Encoding::decode(bytes, Some(encoding)).unwrap();
+            assert_eq!(decoded, text, "{encoding}");
+            assert_eq!(decoded_encoding, encoding);
+        }
+
+        let latin_1 = Encoding::from_name("latin1", false).unwrap();
+        assert_eq!(latin_1.name(), "windows-1252");
+        assert!(!Encoding::from_name("windows-1252", true).unwrap().has_bom());
+        assert!(latin_1.encode("日本").is_err());
+        assert_eq!(
+            Encoding::from_name("utf-16be", true)
+                .unwrap()
+                .encode("a")
+                .unwrap(),
+            b"\xFE\xFF\0a"
+        );
+    }
+}
@@ -6,7 +6,7 @@ use git::{
     blame::Blame,
     repository::{
         AskPassDelegate, Branch, CommitDetails, CommitOptions, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode,
+        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode, StashEntry,
     },
     status::{FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus},
 };
@@ -171,6 +171,35 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn stash_paths(
+        &self,
+        _paths: Vec<RepoPath>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn stash_entries(&self) -> BoxFuture<Result<Vec<StashEntry>>> {
+        future::ready(Ok(Vec::new())).boxed()
+    }
+
+    fn stash_apply(
+        &self,
+        _index: usize,
+        _pop: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn stash_drop(
+        &self,
+        _index: usize,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
     fn path(&self) -> PathBuf {
         self.repository_dir_path.clone()
     }
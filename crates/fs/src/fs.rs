@@ -1,3 +1,4 @@
+mod encoding;
 #[cfg(target_os = "macos")]
 mod mac_watcher;
 
@@ -36,6 +37,8 @@ use std::{
 use tempfile::TempDir;
 use text::LineEnding;
 
+pub use encoding::{Decoder, Encoding};
+
 #[cfg(any(test, feature = "test-support"))]
 mod fake_git_repo;
 #[cfg(any(test, feature = "test-support"))]
@@ -108,9 +111,30 @@ pub trait Fs: Send + Sync {
     async fn load(&self, path: &Path) -> Result<String> {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
+    /// Loads the text in the given encoding, or detects the encoding if there is none.
+    async fn load_with_encoding(
+        &self,
+        path: &Path,
+        encoding: Option<Encoding>,
+    ) -> Result<(String, Encoding)> {
+        Encoding::decode(self.load_bytes(path).await?, encoding)
+    }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
+        if encoding == Encoding::UTF_8 {
+            return self.save(path, text, line_ending).await;
+        }
+        let content = encoding.encode(&chunks(text, line_ending).collect::<String>())?;
+        self.write(path, &content).await
+    }
     async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
@@ -39,12 +39,16 @@ actions!(
         // per-file
         StageFile,
         UnstageFile,
+        StashFile,
         // repo-wide
         StageAll,
         UnstageAll,
         RestoreTrackedFiles,
         TrashUntrackedFiles,
         Uncommit,
+        StashAll,
+        StashPop,
+        ViewStash,
         Push,
         ForcePush,
         Pull,
@@ -161,6 +161,27 @@ pub struct CommitDetails {
     pub author_name: SharedString,
 }
 
+/// An entry in the stash, where `index` is `N` in `stash@{N}`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct StashEntry {
+    pub index: usize,
+    pub sha: SharedString,
+    pub message: SharedString,
+    /// This is a unix timestamp
+    pub timestamp: i64,
+}
+
+impl StashEntry {
+    pub fn to_commit_summary(&self) -> CommitSummary {
+        CommitSummary {
+            sha: self.sha.clone(),
+            subject: self.message.clone(),
+            commit_timestamp: self.timestamp,
+            has_parent: true,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct CommitDiff {
     pub files: Vec<CommitFile>,
@@ -256,6 +277,27 @@ pub trait GitRepository: Send + Sync {
 
     fn show(&self, commit: String) -> BoxFuture<Result<CommitDetails>>;
 
+    /// Stashes the changes to the given paths, or to all files if `paths` is empty,
+    /// including untracked files.
+    fn stash_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Returns the stash entries, most recent first.
+    fn stash_entries(&self) -> BoxFuture<Result<Vec<StashEntry>>>;
+
+    /// Applies the changes in a stash entry, removing it from the stash if `pop` is set.
+    fn stash_apply(
+        &self,
+        index: usize,
+        pop: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    fn stash_drop(&self, index: usize, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>>;
+
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>>;
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<Result<crate::blame::Blame>>;
 
@@ -471,6 +513,9 @@ impl GitRepository for RealGitRepository {
                     "-z",
                     "--no-renames",
                     "--name-status",
+                    // Diff merge commits, such as stash entries, against their first parent.
+                    "-m",
+                    "--first-parent",
                 ])
                 .arg(&commit)
                 .stdin(Stdio::null())
@@ -481,9 +526,51 @@ impl GitRepository for RealGitRepository {
 
             let show_stdout = String::from_utf8_lossy(&show_output.stdout);
             let mut lines = show_stdout.split('\n');
-            let parent_sha = lines.next().unwrap().trim().trim_end_matches('\0');
+            let mut parent_shas = lines
+                .next()
+                .unwrap()
+                .trim()
+                .trim_end_matches('\0')
+                .split(' ');
+            let parent_sha = parent_shas.next().unwrap_or_default();
             let changes = parse_git_diff_name_status(lines.next().unwrap_or(""));
 
+            // Stash entries created with `--include-untracked` record the untracked files in a
+            // third parent, which has no parents of its own.
+            let mut untracked_sha = None;
+            let mut untracked_files = String::new();
+            if let Some(third_parent_sha) = parent_shas.nth(1) {
+                let output = util::command::new_std_command("git")
+                    .current_dir(&working_directory)
+                    .args(["--no-optional-locks", "show", "-s", "--format=%P%x00%s"])
+                    .arg(third_parent_sha)
+                    .stdin(Stdio::null())
+                    .output()
+                    .context("starting git show process")?;
+                let output = String::from_utf8_lossy(&output.stdout);
+                if let Some(("", subject)) = output.trim_end().split_once('\0') {
+                    if subject.starts_with("untracked files on ") {
+                        let output = util::command::new_std_command("git")
+                            .current_dir(&working_directory)
+                            .args(["--no-optional-locks", "ls-tree", "-r", "-z", "--name-only"])
+                            .arg(third_parent_sha)
+                            .stdin(Stdio::null())
+                            .output()
+                            .context("starting git ls-tree process")?;
+                        untracked_files = String::from_utf8_lossy(&output.stdout).into_owned();
+                        untracked_sha = Some(third_parent_sha);
+                    }
+                }
+            }
+            let changes = changes
+                .map(|(path, status_code)| (path, status_code, commit.as_str()))
+                .chain(untracked_sha.into_iter().flat_map(|untracked_sha| {
+                    untracked_files
+                        .split('\0')
+                        .filter(|path| !path.is_empty())
+                        .map(move |path| (Path::new(path), StatusCode::Added, untracked_sha))
+                }));
+
             let mut cat_file_process = util::command::new_std_command("git")
                 .current_dir(&working_directory)
                 .args(["--no-optional-locks", "cat-file", "--batch=%(objectsize)"])
@@ -499,14 +586,14 @@ impl GitRepository for RealGitRepository {
             let mut stdout = BufReader::new(cat_file_process.stdout.take().unwrap());
             let mut info_line = String::new();
             let mut newline = [b'\0'];
-            for (path, status_code) in changes {
+            for (path, status_code, new_sha) in changes {
                 match status_code {
                     StatusCode::Modified => {
-                        writeln!(&mut stdin, "{commit}:{}", path.display())?;
+                        writeln!(&mut stdin, "{new_sha}:{}", path.display())?;
                         writeln!(&mut stdin, "{parent_sha}:{}", path.display())?;
                     }
                     StatusCode::Added => {
-                        writeln!(&mut stdin, "{commit}:{}", path.display())?;
+                        writeln!(&mut stdin, "{new_sha}:{}", path.display())?;
                     }
                     StatusCode::Deleted => {
                         writeln!(&mut stdin, "{parent_sha}:{}", path.display())?;
@@ -618,6 +705,106 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn stash_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(["stash", "push", "--quiet", "--include-untracked", "--"])
+                    .args(paths.iter().map(|path| path.as_ref()))
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to stash:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn stash_entries(&self) -> BoxFuture<Result<Vec<StashEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args([
+                        "--no-optional-locks",
+                        "stash",
+                        "list",
+                        "--format=%gd%x00%H%x00%ct%x00%gs",
+                    ])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to list stash entries:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(parse_stash_list(&String::from_utf8_lossy(&output.stdout)))
+            })
+            .boxed()
+    }
+
+    fn stash_apply(
+        &self,
+        index: usize,
+        pop: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(["stash", if pop { "pop" } else { "apply" }, "--quiet"])
+                    .arg(format!("stash@{{{index}}}"))
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to apply stash:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn stash_drop(&self, index: usize, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(["stash", "drop", "--quiet"])
+                    .arg(format!("stash@{{{index}}}"))
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to drop stash:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
     fn load_index_text(&self, path: RepoPath) -> BoxFuture<Option<String>> {
         // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
         const GIT_MODE_SYMLINK: u32 = 0o120000;
@@ -1758,6 +1945,31 @@ fn parse_upstream_track(upstream_track: &str) -> Result<UpstreamTracking> {
     }))
 }
 
+/// Parses the output of `git stash list --format=%gd%x00%H%x00%ct%x00%gs`.
+fn parse_stash_list(output: &str) -> Vec<StashEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\0');
+            let index = fields
+                .next()?
+                .strip_prefix("stash@{")?
+                .strip_suffix('}')?
+                .parse()
+                .ok()?;
+            let sha = fields.next()?.to_string().into();
+            let timestamp = fields.next()?.parse().ok()?;
+            let message = fields.next()?.to_string().into();
+            Some(StashEntry {
+                index,
+                sha,
+                message,
+                timestamp,
+            })
+        })
+        .collect()
+}
+
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
     match relative_file_path.components().next() {
         None => anyhow::bail!("repo path should not be empty"),
@@ -1985,6 +2197,102 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_stash_list_parsing() {
+        let input = "stash@{0}\0060964da10574cd9bf06463a53bf6e0769c5c45e\01733187470\0On main: wip\nstash@{1}\0a1b2c3d4e5f60718293a4b5c6d7e8f9012345678\01733180000\0WIP on main: 060964d generated protobuf\nnot a stash line\n";
+        assert_eq!(
+            parse_stash_list(input),
+            vec![
+                StashEntry {
+                    index: 0,
+                    sha: "060964da10574cd9bf06463a53bf6e0769c5c45e".into(),
+                    message: "On main: wip".into(),
+                    timestamp: 1733187470,
+                },
+                StashEntry {
+                    index: 1,
+                    sha: "a1b2c3d4e5f60718293a4b5c6d7e8f9012345678".into(),
+                    message: "WIP on main: 060964d generated protobuf".into(),
+                    timestamp: 1733180000,
+                },
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_stash(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let file_path = repo_dir.path().join("file");
+        smol::fs::write(&file_path, "initial").await.unwrap();
+
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        repo.stage_paths(vec![RepoPath::from_str("file")], env.clone())
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::write(&file_path, "modified").await.unwrap();
+        let untracked_path = repo_dir.path().join("untracked");
+        smol::fs::write(&untracked_path, "untracked").await.unwrap();
+        repo.stash_paths(vec![RepoPath::from_str("file")], env.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(&file_path).await.unwrap(),
+            "initial"
+        );
+        assert!(untracked_path.exists());
+
+        repo.stash_paths(Vec::new(), env.clone()).await.unwrap();
+        assert!(!untracked_path.exists());
+
+        let entries = repo.stash_entries().await.unwrap();
+        assert_eq!(
+            entries.iter().map(|entry| entry.index).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+
+        let diff = repo
+            .load_commit(entries[1].sha.to_string(), cx.to_async())
+            .await
+            .unwrap();
+        assert_eq!(diff.files.len(), 1);
+        assert_eq!(diff.files[0].old_text.as_deref(), Some("initial"));
+        assert_eq!(diff.files[0].new_text.as_deref(), Some("modified"));
+
+        let diff = repo
+            .load_commit(entries[0].sha.to_string(), cx.to_async())
+            .await
+            .unwrap();
+        assert_eq!(diff.files.len(), 1);
+        assert_eq!(diff.files[0].path, RepoPath::from_str("untracked"));
+        assert_eq!(diff.files[0].old_text, None);
+        assert_eq!(diff.files[0].new_text.as_deref(), Some("untracked"));
+
+        repo.stash_apply(1, true, env.clone()).await.unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(&file_path).await.unwrap(),
+            "modified"
+        );
+        assert_eq!(repo.stash_entries().await.unwrap().len(), 1);
+
+        repo.stash_drop(0, env).await.unwrap();
+        assert!(repo.stash_entries().await.unwrap().is_empty());
+        assert!(!untracked_path.exists());
+    }
+
     impl RealGitRepository {
         /// Force a Git garbage collection on the repository.
         fn gc(&self) -> BoxFuture<Result<()>> {
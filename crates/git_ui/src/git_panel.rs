@@ -26,7 +26,10 @@ use git::repository::{
 };
 use git::status::StageStatus;
 use git::{Amend, ToggleStaged, repository::RepoPath, status::FileStatus};
-use git::{ExpandCommitEditor, RestoreTrackedFiles, StageAll, TrashUntrackedFiles, UnstageAll};
+use git::{
+    ExpandCommitEditor, RestoreTrackedFiles, StageAll, StashAll, StashFile, StashPop,
+    TrashUntrackedFiles, UnstageAll, ViewStash,
+};
 use gpui::{
     Action, Animation, AnimationExt as _, Axis, ClickEvent, Corner, DismissEvent, Entity,
     EventEmitter, FocusHandle, Focusable, KeyContext, ListHorizontalSizingBehavior,
@@ -110,6 +113,7 @@ struct GitMenuState {
     has_staged_changes: bool,
     has_unstaged_changes: bool,
     has_new_changes: bool,
+    has_stash_entries: bool,
 }
 
 fn git_panel_context_menu(
@@ -138,6 +142,22 @@ fn git_panel_context_menu(
             .separator()
             .action("Open Diff", project_diff::Diff.boxed_clone())
             .separator()
+            .map(|menu| {
+                if state.has_tracked_changes || state.has_new_changes {
+                    menu.action("Stash All", StashAll.boxed_clone())
+                } else {
+                    menu.disabled_action("Stash All", StashAll.boxed_clone())
+                }
+            })
+            .map(|menu| {
+                if state.has_stash_entries {
+                    menu.action("Pop Latest Stash", StashPop.boxed_clone())
+                } else {
+                    menu.disabled_action("Pop Latest Stash", StashPop.boxed_clone())
+                }
+            })
+            .action("View Stash…", ViewStash.boxed_clone())
+            .separator()
             .map(|menu| {
                 if state.has_tracked_changes {
                     menu.action("Discard Tracked Changes", RestoreTrackedFiles.boxed_clone())
@@ -336,6 +356,7 @@ pub struct GitPanel {
     horizontal_scrollbar: ScrollbarProperties,
     vertical_scrollbar: ScrollbarProperties,
     new_count: usize,
+    has_stash_entries: bool,
     entry_count: usize,
     new_staged_count: usize,
     pending: Vec<PendingOperation>,
@@ -458,7 +479,9 @@ impl GitPanel {
                         .ok();
                 }
                 GitStoreEvent::RepositoryUpdated(_, _, _) => {}
-                GitStoreEvent::JobsUpdated | GitStoreEvent::ConflictsUpdated => {}
+                GitStoreEvent::JobsUpdated
+                | GitStoreEvent::ConflictsUpdated
+                | GitStoreEvent::JujutsuStoresChanged => {}
             },
         )
         .detach();
@@ -501,6 +524,7 @@ impl GitPanel {
             focus_handle: cx.focus_handle(),
             fs,
             new_count: 0,
+            has_stash_entries: false,
             new_staged_count: 0,
             pending: Vec::new(),
             pending_commit: None,
@@ -1235,6 +1259,59 @@ impl GitPanel {
         self.change_file_stage(true, entries, cx);
     }
 
+    fn stash_all(&mut self, _: &StashAll, window: &mut Window, cx: &mut Context<Self>) {
+        self.stash_paths(Vec::new(), window, cx);
+    }
+
+    fn stash_selected(&mut self, _: &StashFile, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self
+            .selected_entry
+            .and_then(|ix| self.entries.get(ix))
+            .and_then(|entry| entry.status_entry())
+        else {
+            return;
+        };
+        let repo_path = entry.repo_path.clone();
+        self.stash_paths(vec![repo_path], window, cx);
+    }
+
+    fn stash_paths(&mut self, paths: Vec<RepoPath>, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        telemetry::event!("Git Stashed");
+
+        let stash = repo.update(cx, |repo, cx| repo.stash_paths(paths, cx));
+        cx.spawn_in(window, async move |this, cx| {
+            let result = maybe!(async { stash.await? }).await;
+            this.update(cx, |this, cx| {
+                if let Err(e) = result {
+                    this.show_error_toast("stash", e, cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn stash_pop(&mut self, _: &StashPop, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+
+        let pop = repo.update(cx, |repo, _| repo.stash_apply(0, true));
+        cx.spawn_in(window, async move |this, cx| {
+            let result = maybe!(async { pop.await? }).await;
+            this.update(cx, |this, cx| {
+                if let Err(e) = result {
+                    this.show_error_toast("stash pop", e, cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
     pub fn unstage_all(&mut self, _: &UnstageAll, _window: &mut Window, cx: &mut Context<Self>) {
         let entries = self
             .entries
@@ -1775,6 +1852,9 @@ impl GitPanel {
                     tool_choice: None,
                     stop: Vec::new(),
                     temperature,
+                    max_tokens: None,
+                    response_format: None,
+                    reasoning: None,
                 };
 
                 let stream = model.stream_completion_text(request, &cx);
@@ -2242,12 +2322,30 @@ impl GitPanel {
         self.reopen_commit_buffer(window, cx);
         self.update_visible_entries_task = cx.spawn_in(window, async move |_, cx| {
             cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            let stash_entries = handle
+                .update(cx, |git_panel, cx| {
+                    git_panel
+                        .active_repository
+                        .as_ref()
+                        .map(|repo| repo.update(cx, |repo, _| repo.stash_entries()))
+                })
+                .ok()
+                .flatten();
+            let has_stash_entries = match stash_entries {
+                Some(stash_entries) => stash_entries
+                    .await
+                    .ok()
+                    .and_then(|stash_entries| stash_entries.log_err())
+                    .map_or(false, |stash_entries| !stash_entries.is_empty()),
+                None => false,
+            };
             if let Some(git_panel) = handle.upgrade() {
                 git_panel
                     .update_in(cx, |git_panel, window, cx| {
                         if clear_pending {
                             git_panel.clear_pending();
                         }
+                        git_panel.has_stash_entries = has_stash_entries;
                         git_panel.update_visible_entries(cx);
                         git_panel.update_scrollbar_properties(window, cx);
                     })
@@ -2675,6 +2773,7 @@ impl GitPanel {
         let has_staged_changes = self.has_staged_changes();
         let has_unstaged_changes = self.has_unstaged_changes();
         let has_new_changes = self.new_count > 0;
+        let has_stash_entries = self.has_stash_entries;
 
         PopoverMenu::new(id.into())
             .trigger(
@@ -2690,6 +2789,7 @@ impl GitPanel {
                         has_staged_changes,
                         has_unstaged_changes,
                         has_new_changes,
+                        has_stash_entries,
                     },
                     window,
                     cx,
@@ -2994,6 +3094,18 @@ impl GitPanel {
         });
         let has_previous_commit = head_commit.is_some();
 
+        if active_repository.read(cx).is_jj_colocated {
+            let footer = v_flex()
+                .child(PanelRepoFooter::new(
+                    display_name,
+                    branch,
+                    head_commit,
+                    Some(git_panel.clone()),
+                ))
+                .child(self.render_jj_footer(cx));
+            return Some(footer.into_any_element());
+        }
+
         let footer = v_flex()
             .child(PanelRepoFooter::new(
                 display_name,
@@ -3082,7 +3194,7 @@ impl GitPanel {
                     ),
             );
 
-        Some(footer)
+        Some(footer.into_any_element())
     }
 
     fn render_commit_button(
@@ -3247,6 +3359,58 @@ impl GitPanel {
             })
     }
 
+    /// Replaces the commit editor in repositories colocated with Jujutsu, where
+    /// committing with git would fight with jj over the working-copy commit.
+    fn render_jj_footer(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .py_2()
+            .px(px(8.))
+            .gap_2()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .child(
+                        Icon::new(IconName::Info)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(
+                            "This repository is managed by Jujutsu. Changes are recorded in the working-copy change as you save.",
+                        )
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .justify_end()
+                    .child(
+                        panel_button("New Change")
+                            .tooltip(Tooltip::for_action_title(
+                                "Start a new change on top of this one",
+                                &zed_actions::jj::NewChange,
+                            ))
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(zed_actions::jj::NewChange.boxed_clone(), cx)
+                            }),
+                    )
+                    .child(
+                        panel_filled_button("Describe")
+                            .tooltip(Tooltip::for_action_title(
+                                "Describe the working-copy change",
+                                &zed_actions::jj::Describe,
+                            ))
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(zed_actions::jj::Describe.boxed_clone(), cx)
+                            }),
+                    ),
+            )
+    }
+
     fn render_pending_amend(&self, cx: &mut Context<Self>) -> impl IntoElement {
         div()
             .py_2()
@@ -3264,6 +3428,7 @@ impl GitPanel {
         let active_repository = self.active_repository.as_ref()?;
         let branch = active_repository.read(cx).branch.as_ref()?;
         let commit = branch.most_recent_commit.as_ref()?.clone();
+        let is_jj_colocated = active_repository.read(cx).is_jj_colocated;
         let workspace = self.workspace.clone();
 
         let this = cx.entity();
@@ -3315,7 +3480,8 @@ impl GitPanel {
                         }),
                 )
                 .child(div().flex_1())
-                .when(commit.has_parent, |this| {
+                // jj rewrites `HEAD` itself, so resetting it behind jj's back would be undone.
+                .when(commit.has_parent && !is_jj_colocated, |this| {
                     let has_unstaged = self.has_unstaged_changes();
                     this.child(
                         panel_icon_button("undo", IconName::Undo)
@@ -3762,6 +3928,7 @@ impl GitPanel {
                 .context(self.focus_handle.clone())
                 .action(stage_title, ToggleStaged.boxed_clone())
                 .action(restore_title, git::RestoreFile::default().boxed_clone())
+                .action("Stash File", StashFile.boxed_clone())
                 .separator()
                 .action("Open Diff", Confirm.boxed_clone())
                 .action("Open File", SecondaryConfirm.boxed_clone())
@@ -3783,6 +3950,7 @@ impl GitPanel {
                 has_staged_changes: self.has_staged_changes(),
                 has_unstaged_changes: self.has_unstaged_changes(),
                 has_new_changes: self.new_count > 0,
+                has_stash_entries: self.has_stash_entries,
             },
             window,
             cx,
@@ -4091,6 +4259,9 @@ impl Render for GitPanel {
                     .on_action(cx.listener(Self::stage_selected))
                     .on_action(cx.listener(Self::unstage_selected))
                     .on_action(cx.listener(Self::restore_tracked_files))
+                    .on_action(cx.listener(Self::stash_all))
+                    .on_action(cx.listener(Self::stash_selected))
+                    .on_action(cx.listener(Self::stash_pop))
                     .on_action(cx.listener(Self::revert_selected))
                     .on_action(cx.listener(Self::clean_all))
                     .on_action(cx.listener(Self::generate_commit_message_action))
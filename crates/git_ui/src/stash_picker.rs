@@ -0,0 +1,347 @@
+use anyhow::Context as _;
+use fuzzy::StringMatchCandidate;
+
+use git::ViewStash;
+use git::repository::StashEntry;
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, IntoElement,
+    ParentElement, Render, SharedString, Styled, Subscription, Task, WeakEntity, Window, rems,
+};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use time_format::format_local_timestamp;
+use ui::{HighlightedLabel, IconButtonShape, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+use crate::commit_view::CommitView;
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &ViewStash,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx).clone();
+    let workspace_handle = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        StashList::new(repository, workspace_handle, window, cx)
+    })
+}
+
+/// Lists the stash entries of the active repository.
+///
+/// Confirming an entry applies it, and secondary-confirming pops it.
+pub struct StashList {
+    pub picker: Entity<Picker<StashListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl StashList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = StashListDelegate::new(repository, workspace);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        picker.update(cx, |picker, cx| {
+            StashListDelegate::reload(picker, window, cx)
+        });
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for StashList {}
+impl EventEmitter<DismissEvent> for StashList {}
+
+impl Focusable for StashList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for StashList {
+    fn render(&mut self, _: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StashMatch {
+    entry: StashEntry,
+    positions: Vec<usize>,
+}
+
+pub struct StashListDelegate {
+    matches: Vec<StashMatch>,
+    all_entries: Option<Vec<StashEntry>>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+}
+
+impl StashListDelegate {
+    fn new(repo: Option<Entity<Repository>>, workspace: WeakEntity<Workspace>) -> Self {
+        Self {
+            matches: Vec::new(),
+            all_entries: None,
+            repo,
+            workspace,
+            selected_index: 0,
+        }
+    }
+
+    fn reload(picker: &mut Picker<Self>, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let entries_request =
+            picker.delegate.repo.clone().map(|repository| {
+                repository.update(cx, |repository, _| repository.stash_entries())
+            });
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let entries = entries_request.context("No active repository")?.await??;
+
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.all_entries = Some(entries);
+                picker.refresh(window, cx);
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn drop_entry(&self, index: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            repo.update(cx, |repo, _| repo.stash_drop(index))?.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                Self::reload(picker, window, cx);
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to drop stash", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn view_entry(&self, entry: &StashEntry, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.as_ref() else {
+            return;
+        };
+
+        CommitView::open(
+            entry.to_commit_summary(),
+            repo.downgrade(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+        cx.emit(DismissEvent);
+    }
+}
+
+impl PickerDelegate for StashListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select a stash to apply…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_entries) = self.all_entries.clone() else {
+            return Task::ready(());
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches: Vec<StashMatch> = if query.is_empty() {
+                all_entries
+                    .into_iter()
+                    .map(|entry| StashMatch {
+                        entry,
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let candidates = all_entries
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, entry)| StringMatchCandidate::new(ix, &entry.message))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| StashMatch {
+                    entry: all_entries[candidate.candidate_id].clone(),
+                    positions: candidate.positions,
+                })
+                .collect()
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index()) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let index = entry.entry.index;
+
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| repo.stash_apply(index, secondary))?
+                .await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to apply stash", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let stash_match = &self.matches[ix];
+        let entry = stash_match.entry.clone();
+
+        let stash_time = OffsetDateTime::from_unix_timestamp(entry.timestamp)
+            .unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let formatted_time = format_local_timestamp(
+            stash_time,
+            OffsetDateTime::now_utc(),
+            time_format::TimestampFormat::Relative,
+        );
+
+        Some(
+            ListItem::new(SharedString::from(format!("stash-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_2()
+                        .child(
+                            Label::new(format!("stash@{{{}}}", entry.index))
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            div().flex_1().overflow_x_hidden().child(
+                                HighlightedLabel::new(
+                                    entry.message.clone(),
+                                    stash_match.positions.clone(),
+                                )
+                                .truncate(),
+                            ),
+                        )
+                        .child(
+                            Label::new(formatted_time)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+                .end_slot(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            IconButton::new(("view-stash", ix), IconName::Eye)
+                                .shape(IconButtonShape::Square)
+                                .icon_size(IconSize::Small)
+                                .tooltip(Tooltip::text("View Stash"))
+                                .on_click({
+                                    let entry = entry.clone();
+                                    cx.listener(move |picker, _, window, cx| {
+                                        picker.delegate.view_entry(&entry, window, cx);
+                                    })
+                                }),
+                        )
+                        .child(
+                            IconButton::new(("drop-stash", ix), IconName::Trash)
+                                .shape(IconButtonShape::Square)
+                                .icon_size(IconSize::Small)
+                                .tooltip(Tooltip::text("Drop Stash"))
+                                .on_click(cx.listener(move |picker, _, window, cx| {
+                                    picker.delegate.drop_entry(entry.index, window, cx);
+                                })),
+                        ),
+                ),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No stashes".into())
+    }
+}
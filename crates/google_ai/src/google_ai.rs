@@ -2,7 +2,7 @@ use std::mem;
 
 use anyhow::{Result, anyhow, bail};
 use futures::{AsyncBufReadExt, AsyncReadExt, StreamExt, io::BufReader, stream::BoxStream};
-use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest};
+use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest, StatusError};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 pub const API_URL: &str = "https://generativelanguage.googleapis.com";
@@ -53,11 +53,15 @@ pub async fn stream_generate_content(
     } else {
         let mut text = String::new();
         response.body_mut().read_to_string(&mut text).await?;
-        Err(anyhow!(
-            "error during streamGenerateContent, status code: {:?}, body: {}",
+        Err(anyhow!(StatusError::new(
             response.status(),
-            text
-        ))
+            response.headers(),
+            format!(
+                "error during streamGenerateContent, status code: {:?}, body: {}",
+                response.status(),
+                text
+            )
+        )))
     }
 }
 
@@ -296,6 +300,18 @@ pub struct GenerationConfig {
     pub top_p: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_k: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking_config: Option<ThinkingConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThinkingConfig {
+    pub thinking_budget: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -546,6 +562,11 @@ impl Model {
     }
 }
 
+/// Returns whether the model with the given ID takes a thinking budget.
+pub fn supports_thinking_budget(model_id: &str) -> bool {
+    model_id.starts_with("gemini-2.5-")
+}
+
 impl std::fmt::Display for Model {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.id())
@@ -0,0 +1,839 @@
+mod hierarchy_panel_settings;
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, scroll::Autoscroll};
+use gpui::{
+    Action, App, AsyncWindowContext, ClickEvent, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, InteractiveElement, IntoElement, KeyContext, ListSizingBehavior, ParentElement,
+    Pixels, Render, ScrollStrategy, Styled, Subscription, Task, UniformListScrollHandle,
+    WeakEntity, Window, actions, px, uniform_list,
+};
+use hierarchy_panel_settings::{HierarchyPanelDockPosition, HierarchyPanelSettings};
+use language::{Buffer, ToPoint as _};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::{Fs, HierarchyItem, HierarchyKind, Location, Project};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use ui::{
+    ButtonCommon, Clickable, Color, FluentBuilder, Icon, IconButton, IconName, IconSize, Label,
+    LabelCommon, LabelSize, ListItem, StyledTypography, Toggleable, Tooltip, h_flex, v_flex,
+};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+actions!(
+    hierarchy_panel,
+    [
+        CollapseSelectedEntry,
+        ExpandSelectedEntry,
+        OpenSelectedEntry,
+        ShowCallHierarchy,
+        ShowTypeHierarchy,
+        ToggleDirection,
+        ToggleFocus,
+    ]
+);
+
+const HIERARCHY_PANEL_KEY: &str = "HierarchyPanel";
+
+/// Which neighbours of an item are listed as its children.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HierarchyDirection {
+    /// Callers of functions, supertypes of types.
+    Incoming,
+    /// Callees of functions, subtypes of types.
+    Outgoing,
+}
+
+pub struct HierarchyPanel {
+    fs: Arc<dyn Fs>,
+    width: Option<Pixels>,
+    active: bool,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    pending_serialization: Task<Option<()>>,
+    kind: HierarchyKind,
+    direction: HierarchyDirection,
+    /// The items at the position the hierarchy was requested for, kept to rebuild
+    /// the tree when the direction changes.
+    roots: Vec<HierarchyItem>,
+    /// All nodes loaded so far, starting with one node per root.
+    nodes: Vec<HierarchyNode>,
+    /// Indices of the nodes in display order, skipping the children of collapsed nodes.
+    visible_entries: Vec<usize>,
+    selected_node: Option<usize>,
+    preparing: bool,
+    prepare_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct HierarchyNode {
+    item: HierarchyItem,
+    /// For call hierarchies, the calls connecting the item with its parent.
+    call_sites: Vec<Location>,
+    depth: usize,
+    expanded: bool,
+    children: NodeChildren,
+}
+
+/// Children are only requested from the language server once their parent is expanded.
+enum NodeChildren {
+    NotLoaded,
+    Loading(Task<()>),
+    Loaded(Vec<usize>),
+}
+
+impl HierarchyNode {
+    fn new(item: HierarchyItem, call_sites: Vec<Location>, depth: usize) -> Self {
+        Self {
+            item,
+            call_sites,
+            depth,
+            expanded: false,
+            children: NodeChildren::NotLoaded,
+        }
+    }
+
+    fn has_children(&self) -> bool {
+        match &self.children {
+            NodeChildren::Loaded(children) => !children.is_empty(),
+            NodeChildren::NotLoaded | NodeChildren::Loading(_) => true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedHierarchyPanel {
+    width: Option<Pixels>,
+    active: Option<bool>,
+}
+
+pub fn init_settings(cx: &mut App) {
+    HierarchyPanelSettings::register(cx);
+}
+
+pub fn init(cx: &mut App) {
+    init_settings(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<HierarchyPanel>(window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowCallHierarchy, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::Call, window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowTypeHierarchy, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::Type, window, cx);
+        });
+    })
+    .detach();
+}
+
+/// Shows the hierarchy of the symbol at the newest cursor of the active editor.
+fn show_hierarchy(
+    workspace: &mut Workspace,
+    kind: HierarchyKind,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    if let Some(panel) = workspace.focus_panel::<HierarchyPanel>(window, cx) {
+        panel.update(cx, |panel, cx| {
+            panel.show_hierarchy(kind, buffer, position, window, cx)
+        });
+    }
+}
+
+impl HierarchyPanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<Entity<Self>> {
+        let serialized_panel = match workspace
+            .read_with(&cx, |workspace, _| {
+                HierarchyPanel::serialization_key(workspace)
+            })
+            .ok()
+            .flatten()
+        {
+            Some(serialization_key) => cx
+                .background_spawn(async move { KEY_VALUE_STORE.read_kvp(&serialization_key) })
+                .await
+                .context("loading hierarchy panel")
+                .log_err()
+                .flatten()
+                .map(|panel| serde_json::from_str::<SerializedHierarchyPanel>(&panel))
+                .transpose()
+                .log_err()
+                .flatten(),
+            None => None,
+        };
+
+        workspace.update_in(&mut cx, |workspace, window, cx| {
+            let panel = Self::new(workspace, window, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    panel.active = serialized_panel.active.unwrap_or(false);
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let workspace_handle = cx.entity().downgrade();
+        let fs = workspace.app_state().fs.clone();
+        cx.new(|cx| {
+            let mut hierarchy_panel_settings = *HierarchyPanelSettings::get_global(cx);
+            let settings_subscription =
+                cx.observe_global_in::<SettingsStore>(window, move |_, _, cx| {
+                    let new_settings = HierarchyPanelSettings::get_global(cx);
+                    if &hierarchy_panel_settings != new_settings {
+                        hierarchy_panel_settings = *new_settings;
+                        cx.notify();
+                    }
+                });
+
+            Self {
+                fs,
+                width: None,
+                active: false,
+                project,
+                workspace: workspace_handle,
+                focus_handle: cx.focus_handle(),
+                scroll_handle: UniformListScrollHandle::new(),
+                pending_serialization: Task::ready(None),
+                kind: HierarchyKind::Call,
+                direction: HierarchyDirection::Incoming,
+                roots: Vec::new(),
+                nodes: Vec::new(),
+                visible_entries: Vec::new(),
+                selected_node: None,
+                preparing: false,
+                prepare_task: Task::ready(()),
+                _subscriptions: vec![settings_subscription],
+            }
+        })
+    }
+
+    fn serialization_key(workspace: &Workspace) -> Option<String> {
+        workspace
+            .database_id()
+            .map(|id| i64::from(id).to_string())
+            .or(workspace.session_id())
+            .map(|id| format!("{}-{:?}", HIERARCHY_PANEL_KEY, id))
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let Some(serialization_key) = self
+            .workspace
+            .update(cx, |workspace, _| {
+                HierarchyPanel::serialization_key(workspace)
+            })
+            .ok()
+            .flatten()
+        else {
+            return;
+        };
+        let width = self.width;
+        let active = Some(self.active);
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        serialization_key,
+                        serde_json::to_string(&SerializedHierarchyPanel { width, active })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("HierarchyPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    /// Replaces the displayed hierarchy with the one of the symbol at the given position.
+    pub fn show_hierarchy(
+        &mut self,
+        kind: HierarchyKind,
+        buffer: Entity<Buffer>,
+        position: language::Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.kind = kind;
+        self.direction = HierarchyDirection::Incoming;
+        self.roots.clear();
+        self.nodes.clear();
+        self.visible_entries.clear();
+        self.selected_node = None;
+        self.preparing = true;
+
+        let prepare = self.project.update(cx, |project, cx| {
+            project.prepare_hierarchy(&buffer, position, kind, cx)
+        });
+        self.prepare_task = cx.spawn_in(window, async move |panel, cx| {
+            let roots = prepare.await.log_err().unwrap_or_default();
+            panel
+                .update_in(cx, |panel, window, cx| {
+                    panel.preparing = false;
+                    panel.roots = roots;
+                    panel.rebuild_tree(window, cx);
+                })
+                .ok();
+        });
+        cx.notify();
+    }
+
+    fn rebuild_tree(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.nodes = self
+            .roots
+            .iter()
+            .map(|item| HierarchyNode::new(item.clone(), Vec::new(), 0))
+            .collect();
+        self.selected_node = (!self.nodes.is_empty()).then_some(0);
+        for ix in 0..self.nodes.len() {
+            self.expand_node(ix, window, cx);
+        }
+        self.update_visible_entries(cx);
+    }
+
+    fn update_visible_entries(&mut self, cx: &mut Context<Self>) {
+        self.visible_entries.clear();
+        let mut stack = (0..self.roots.len().min(self.nodes.len()))
+            .rev()
+            .collect::<Vec<_>>();
+        while let Some(ix) = stack.pop() {
+            self.visible_entries.push(ix);
+            let node = &self.nodes[ix];
+            if let (true, NodeChildren::Loaded(children)) = (node.expanded, &node.children) {
+                stack.extend(children.iter().rev());
+            }
+        }
+        cx.notify();
+    }
+
+    fn request_children(
+        &self,
+        item: HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Vec<(HierarchyItem, Vec<Location>)>>> {
+        let direction = self.direction;
+        self.project
+            .update(cx, |project, cx| match (item.hierarchy_kind(), direction) {
+                (HierarchyKind::Call, HierarchyDirection::Incoming) => {
+                    let calls = project.incoming_calls(item, cx);
+                    cx.spawn(async move |_, _| {
+                        Ok(calls
+                            .await?
+                            .into_iter()
+                            .map(|call| (call.item, call.call_sites))
+                            .collect())
+                    })
+                }
+                (HierarchyKind::Call, HierarchyDirection::Outgoing) => {
+                    let calls = project.outgoing_calls(item, cx);
+                    cx.spawn(async move |_, _| {
+                        Ok(calls
+                            .await?
+                            .into_iter()
+                            .map(|call| (call.item, call.call_sites))
+                            .collect())
+                    })
+                }
+                (HierarchyKind::Type, HierarchyDirection::Incoming) => {
+                    let supertypes = project.supertypes(item, cx);
+                    cx.spawn(async move |_, _| {
+                        Ok(supertypes
+                            .await?
+                            .into_iter()
+                            .map(|item| (item, Vec::new()))
+                            .collect())
+                    })
+                }
+                (HierarchyKind::Type, HierarchyDirection::Outgoing) => {
+                    let subtypes = project.subtypes(item, cx);
+                    cx.spawn(async move |_, _| {
+                        Ok(subtypes
+                            .await?
+                            .into_iter()
+                            .map(|item| (item, Vec::new()))
+                            .collect())
+                    })
+                }
+            })
+    }
+
+    fn expand_node(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(node) = self.nodes.get_mut(ix) else {
+            return;
+        };
+        node.expanded = true;
+        if !matches!(node.children, NodeChildren::NotLoaded) {
+            self.update_visible_entries(cx);
+            return;
+        }
+
+        let request = self.request_children(self.nodes[ix].item.clone(), cx);
+        let task = cx.spawn_in(window, async move |panel, cx| {
+            let children = request.await.log_err().unwrap_or_default();
+            panel
+                .update(cx, |panel, cx| {
+                    let depth = panel.nodes[ix].depth + 1;
+                    let mut child_ixs = Vec::with_capacity(children.len());
+                    for (item, call_sites) in children {
+                        child_ixs.push(panel.nodes.len());
+                        panel
+                            .nodes
+                            .push(HierarchyNode::new(item, call_sites, depth));
+                    }
+                    panel.nodes[ix].children = NodeChildren::Loaded(child_ixs);
+                    panel.update_visible_entries(cx);
+                })
+                .ok();
+        });
+        self.nodes[ix].children = NodeChildren::Loading(task);
+        self.update_visible_entries(cx);
+    }
+
+    fn collapse_node(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if let Some(node) = self.nodes.get_mut(ix) {
+            node.expanded = false;
+            self.update_visible_entries(cx);
+        }
+    }
+
+    fn toggle_node(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if self.nodes.get(ix).is_some_and(|node| node.expanded) {
+            self.collapse_node(ix, cx);
+        } else {
+            self.expand_node(ix, window, cx);
+        }
+    }
+
+    fn toggle_direction(
+        &mut self,
+        _: &ToggleDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.direction = match self.direction {
+            HierarchyDirection::Incoming => HierarchyDirection::Outgoing,
+            HierarchyDirection::Outgoing => HierarchyDirection::Incoming,
+        };
+        self.rebuild_tree(window, cx);
+    }
+
+    fn expand_selected_entry(
+        &mut self,
+        _: &ExpandSelectedEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ix) = self.selected_node else {
+            return;
+        };
+        match self.nodes[ix].children {
+            NodeChildren::Loaded(ref children) if self.nodes[ix].expanded => {
+                if let Some(&first_child) = children.first() {
+                    self.select_node(first_child, cx);
+                }
+            }
+            _ => self.expand_node(ix, window, cx),
+        }
+    }
+
+    fn collapse_selected_entry(
+        &mut self,
+        _: &CollapseSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ix) = self.selected_node else {
+            return;
+        };
+        if self.nodes[ix].expanded && self.nodes[ix].has_children() {
+            self.collapse_node(ix, cx);
+        } else if let Some(parent) = self.parent_of(ix) {
+            self.select_node(parent, cx);
+        }
+    }
+
+    fn parent_of(&self, ix: usize) -> Option<usize> {
+        self.nodes.iter().position(|node| {
+            matches!(&node.children, NodeChildren::Loaded(children) if children.contains(&ix))
+        })
+    }
+
+    fn select_node(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_node = Some(ix);
+        if let Some(visible_ix) = self.visible_entries.iter().position(|&entry| entry == ix) {
+            self.scroll_handle
+                .scroll_to_item(visible_ix, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        let next = self
+            .selected_node
+            .and_then(|selected| {
+                self.visible_entries
+                    .iter()
+                    .skip_while(|&&entry| entry != selected)
+                    .nth(1)
+                    .copied()
+            })
+            .or_else(|| self.visible_entries.first().copied());
+        if let Some(next) = next {
+            self.select_node(next, cx);
+            self.open_node(next, false, window, cx);
+        }
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, window: &mut Window, cx: &mut Context<Self>) {
+        let previous = self
+            .selected_node
+            .and_then(|selected| {
+                self.visible_entries
+                    .iter()
+                    .rev()
+                    .skip_while(|&&entry| entry != selected)
+                    .nth(1)
+                    .copied()
+            })
+            .or_else(|| self.visible_entries.last().copied());
+        if let Some(previous) = previous {
+            self.select_node(previous, cx);
+            self.open_node(previous, false, window, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(&first) = self.visible_entries.first() {
+            self.select_node(first, cx);
+        }
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(&last) = self.visible_entries.last() {
+            self.select_node(last, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_node {
+            self.open_node(ix, true, window, cx);
+        }
+    }
+
+    fn open_selected_entry(
+        &mut self,
+        _: &OpenSelectedEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.selected_node {
+            self.open_node(ix, false, window, cx);
+        }
+    }
+
+    /// Reveals the item of the node in an editor, selecting the calls connecting it
+    /// with its parent if there are any, and its name otherwise.
+    fn open_node(
+        &mut self,
+        ix: usize,
+        focus_editor: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(node) = self.nodes.get(ix) else {
+            return;
+        };
+        let locations = if node.call_sites.is_empty() {
+            vec![Location {
+                buffer: node.item.location.buffer.clone(),
+                range: node.item.selection_range.clone(),
+            }]
+        } else {
+            node.call_sites.clone()
+        };
+        let buffer = locations[0].buffer.clone();
+        let ranges = {
+            let snapshot = buffer.read(cx);
+            locations
+                .iter()
+                .filter(|location| location.buffer == buffer)
+                .map(|location| {
+                    location.range.start.to_point(snapshot)..location.range.end.to_point(snapshot)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().clone();
+                let editor = workspace.open_project_item::<Editor>(
+                    pane,
+                    buffer,
+                    true,
+                    focus_editor,
+                    window,
+                    cx,
+                );
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), window, cx, |s| {
+                        s.select_ranges(ranges)
+                    });
+                });
+            })
+            .ok();
+        if !focus_editor {
+            self.focus_handle.focus(window);
+        }
+    }
+
+    fn direction_label(&self) -> &'static str {
+        match (self.kind, self.direction) {
+            (HierarchyKind::Call, HierarchyDirection::Incoming) => "Callers",
+            (HierarchyKind::Call, HierarchyDirection::Outgoing) => "Callees",
+            (HierarchyKind::Type, HierarchyDirection::Incoming) => "Supertypes",
+            (HierarchyKind::Type, HierarchyDirection::Outgoing) => "Subtypes",
+        }
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> ListItem {
+        let node = &self.nodes[ix];
+        let settings = HierarchyPanelSettings::get_global(cx);
+        ListItem::new(ix)
+            .indent_level(node.depth)
+            .indent_step_size(px(settings.indent_size))
+            .toggle_state(self.selected_node == Some(ix))
+            .toggle(node.has_children().then_some(node.expanded))
+            .on_toggle(cx.listener(move |panel, _, window, cx| {
+                panel.toggle_node(ix, window, cx);
+            }))
+            .on_click(cx.listener(move |panel, event: &ClickEvent, window, cx| {
+                panel.select_node(ix, cx);
+                panel.open_node(ix, event.down.click_count > 1, window, cx);
+            }))
+            .start_slot(
+                Icon::new(symbol_icon(node.item.kind))
+                    .size(IconSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Label::new(node.item.name.clone()))
+                    .when_some(node.item.detail.clone(), |label, detail| {
+                        label.child(
+                            Label::new(detail)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    }),
+            )
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let (icon, tooltip) = match self.direction {
+            HierarchyDirection::Incoming => (IconName::ArrowUp, "Show Outgoing"),
+            HierarchyDirection::Outgoing => (IconName::ArrowDown, "Show Incoming"),
+        };
+        h_flex()
+            .px_2()
+            .py_1()
+            .justify_between()
+            .child(
+                Label::new(self.direction_label())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                IconButton::new("toggle-hierarchy-direction", icon)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::for_action_title(tooltip, &ToggleDirection))
+                    .on_click(cx.listener(|panel, _, window, cx| {
+                        panel.toggle_direction(&ToggleDirection, window, cx);
+                    })),
+            )
+    }
+}
+
+fn symbol_icon(kind: lsp::SymbolKind) -> IconName {
+    match kind {
+        lsp::SymbolKind::FUNCTION | lsp::SymbolKind::METHOD | lsp::SymbolKind::CONSTRUCTOR => {
+            IconName::Function
+        }
+        _ => IconName::Code,
+    }
+}
+
+impl Panel for HierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "Hierarchy Panel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        match HierarchyPanelSettings::get_global(cx).dock {
+            HierarchyPanelDockPosition::Left => DockPosition::Left,
+            HierarchyPanelDockPosition::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file::<HierarchyPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| {
+                let dock = match position {
+                    DockPosition::Left | DockPosition::Bottom => HierarchyPanelDockPosition::Left,
+                    DockPosition::Right => HierarchyPanelDockPosition::Right,
+                };
+                settings.dock = Some(dock);
+            },
+        );
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| HierarchyPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, window: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+        cx.defer_in(window, |this, _, cx| {
+            this.serialize(cx);
+        });
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        HierarchyPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::Route)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _: &App) -> Option<&'static str> {
+        Some("Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn starts_open(&self, _window: &Window, _: &App) -> bool {
+        self.active
+    }
+
+    fn set_active(&mut self, active: bool, _: &mut Window, cx: &mut Context<Self>) {
+        if self.active != active {
+            self.active = active;
+            self.serialize(cx);
+        }
+    }
+
+    fn activation_priority(&self) -> u32 {
+        6
+    }
+}
+
+impl Focusable for HierarchyPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for HierarchyPanel {}
+
+impl Render for HierarchyPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let contents = if self.visible_entries.is_empty() {
+            let message = if self.preparing {
+                "Loading…"
+            } else if self.roots.is_empty() {
+                "Run \"Show Call Hierarchy\" or \"Show Type Hierarchy\" in an editor to explore the symbol under the cursor."
+            } else {
+                "No items found."
+            };
+            v_flex()
+                .size_full()
+                .p_4()
+                .child(Label::new(message).color(Color::Muted))
+                .into_any_element()
+        } else {
+            uniform_list(
+                cx.entity().clone(),
+                "hierarchy-entries",
+                self.visible_entries.len(),
+                |panel, range, _, cx| {
+                    panel.visible_entries[range]
+                        .to_vec()
+                        .into_iter()
+                        .map(|ix| panel.render_entry(ix, cx))
+                        .collect()
+                },
+            )
+            .with_sizing_behavior(ListSizingBehavior::Infer)
+            .track_scroll(self.scroll_handle.clone())
+            .size_full()
+            .into_any_element()
+        };
+
+        v_flex()
+            .id("hierarchy-panel")
+            .size_full()
+            .overflow_hidden()
+            .text_ui(cx)
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::open_selected_entry))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::toggle_direction))
+            .when(!self.roots.is_empty(), |panel| {
+                panel.child(self.render_header(cx))
+            })
+            .child(contents)
+    }
+}
@@ -0,0 +1,54 @@
+use gpui::Pixels;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HierarchyPanelDockPosition {
+    Left,
+    Right,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct HierarchyPanelSettings {
+    pub button: bool,
+    pub default_width: Pixels,
+    pub dock: HierarchyPanelDockPosition,
+    pub indent_size: f32,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct HierarchyPanelSettingsContent {
+    /// Whether to show the hierarchy panel button in the status bar.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Customize default width (in pixels) taken by hierarchy panel
+    ///
+    /// Default: 300
+    pub default_width: Option<f32>,
+    /// The position of hierarchy panel
+    ///
+    /// Default: left
+    pub dock: Option<HierarchyPanelDockPosition>,
+    /// Amount of indentation (in pixels) for nested items.
+    ///
+    /// Default: 20
+    pub indent_size: Option<f32>,
+}
+
+impl Settings for HierarchyPanelSettings {
+    const KEY: Option<&'static str> = Some("hierarchy_panel");
+
+    type FileContent = HierarchyPanelSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::App,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+
+    fn import_from_vscode(_vscode: &settings::VsCodeSettings, _current: &mut Self::FileContent) {}
+}
@@ -1,10 +1,12 @@
 mod async_body;
 pub mod github;
+mod status_error;
 
 pub use anyhow::{Result, anyhow};
 pub use async_body::{AsyncBody, Inner};
 use derive_more::Deref;
 pub use http::{self, Method, Request, Response, StatusCode, Uri};
+pub use status_error::StatusError;
 
 use futures::future::BoxFuture;
 use http::request::Builder;
@@ -0,0 +1,74 @@
+use std::{fmt, time::Duration};
+
+use http::{HeaderMap, StatusCode};
+
+/// An unsuccessful response from an API, keeping the status and the delay that the server
+/// asked to wait before retrying, so that callers can tell transient failures apart.
+#[derive(Debug)]
+pub struct StatusError {
+    pub status: StatusCode,
+    pub retry_after: Option<Duration>,
+    pub message: String,
+}
+
+impl StatusError {
+    pub fn new(status: StatusCode, headers: &HeaderMap, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            retry_after: retry_after(headers),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+/// Reads the `Retry-After` header in seconds, or the `retry-after-ms` header some APIs send instead.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .filter(|value| value.is_finite() && *value >= 0.)
+    };
+    header("retry-after-ms")
+        .map(|millis| Duration::from_secs_f64(millis / 1000.))
+        .or_else(|| header("retry-after").map(Duration::from_secs_f64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        let error = StatusError::new(StatusCode::TOO_MANY_REQUESTS, &headers, "rate limited");
+        assert_eq!(error.retry_after, None);
+        assert_eq!(error.to_string(), "rate limited");
+
+        headers.insert("retry-after", "12".parse().unwrap());
+        let error = StatusError::new(StatusCode::TOO_MANY_REQUESTS, &headers, "rate limited");
+        assert_eq!(error.retry_after, Some(Duration::from_secs(12)));
+
+        headers.insert("retry-after-ms", "1500".parse().unwrap());
+        let error = StatusError::new(StatusCode::TOO_MANY_REQUESTS, &headers, "rate limited");
+        assert_eq!(error.retry_after, Some(Duration::from_millis(1500)));
+
+        // Dates aren't supported, and are ignored rather than misread.
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "retry-after",
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        let error = StatusError::new(StatusCode::SERVICE_UNAVAILABLE, &headers, "unavailable");
+        assert_eq!(error.retry_after, None);
+    }
+}
@@ -1,30 +1,102 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::Result;
-use gpui::SharedString;
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use futures::FutureExt as _;
+use futures::future::BoxFuture;
+use gpui::{BackgroundExecutor, SharedString};
 use jj_lib::config::StackedConfig;
-use jj_lib::repo::StoreFactories;
+use jj_lib::repo::{RepoLoader, StoreFactories};
 use jj_lib::settings::UserSettings;
 use jj_lib::workspace::{self, DefaultWorkspaceLoaderFactory, WorkspaceLoaderFactory};
+use parking_lot::Mutex;
+use util::command::new_smol_command;
 
 #[derive(Debug, Clone)]
 pub struct Bookmark {
     pub ref_name: SharedString,
 }
 
+/// A jj change, i.e. a commit along with the ID that stays stable when it's rewritten.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub change_id: SharedString,
+    pub commit_id: SharedString,
+    pub description: SharedString,
+    pub is_empty: bool,
+    pub has_conflict: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChangeKind {
+    Added,
+    Modified,
+    Removed,
+    Copied,
+    Renamed,
+}
+
+/// A file that the working-copy change touches, relative to the workspace root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: Arc<Path>,
+    pub kind: FileChangeKind,
+}
+
+/// An entry in the operation log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    pub id: SharedString,
+    pub description: SharedString,
+    pub user: SharedString,
+    pub time: SharedString,
+}
+
 pub trait JujutsuRepository: Send + Sync {
     fn list_bookmarks(&self) -> Vec<Bookmark>;
+
+    /// The root of the jj workspace, which all paths are relative to.
+    fn workspace_root(&self) -> &Path;
+
+    /// Returns the working-copy change (`@`), snapshotting the working copy first.
+    fn working_copy(&self) -> BoxFuture<'static, Result<Change>>;
+
+    /// Returns the files that the working-copy change modifies.
+    fn status(&self) -> BoxFuture<'static, Result<Vec<FileChange>>>;
+
+    /// Returns the contents of a file in the parent of the working-copy change, or `None`
+    /// if it doesn't exist there.
+    fn load_parent_text(&self, path: &Path) -> BoxFuture<'static, Result<Option<String>>>;
+
+    /// Sets the description of the working-copy change.
+    fn describe(&self, message: String) -> BoxFuture<'static, Result<()>>;
+
+    /// Starts a new, empty change on top of the working-copy change.
+    fn new_change(&self) -> BoxFuture<'static, Result<()>>;
+
+    /// Moves the changes in the working-copy change into its parent.
+    fn squash(&self) -> BoxFuture<'static, Result<()>>;
+
+    /// Returns the most recent operations, newest first.
+    fn operation_log(&self, limit: usize) -> BoxFuture<'static, Result<Vec<Operation>>>;
+
+    /// Undoes the given operation.
+    fn undo_operation(&self, id: SharedString) -> BoxFuture<'static, Result<()>>;
 }
 
 pub struct RealJujutsuRepository {
-    repository: Arc<jj_lib::repo::ReadonlyRepo>,
+    repo_loader: RepoLoader,
+    workspace_root: PathBuf,
+    jj_binary_path: PathBuf,
+    executor: BackgroundExecutor,
 }
 
 impl RealJujutsuRepository {
-    pub fn new(cwd: &Path) -> Result<Self> {
+    pub fn new(cwd: &Path, executor: BackgroundExecutor) -> Result<Self> {
+        let workspace_dir = Self::find_workspace_dir(cwd);
         let workspace_loader_factory = DefaultWorkspaceLoaderFactory;
-        let workspace_loader = workspace_loader_factory.create(Self::find_workspace_dir(cwd))?;
+        let workspace_loader = workspace_loader_factory.create(workspace_dir)?;
 
         let config = StackedConfig::with_defaults();
         let settings = UserSettings::from_config(config)?;
@@ -35,10 +107,12 @@ impl RealJujutsuRepository {
             &workspace::default_working_copy_factories(),
         )?;
 
-        let repo_loader = workspace.repo_loader();
-        let repository = repo_loader.load_at_head()?;
-
-        Ok(Self { repository })
+        Ok(Self {
+            repo_loader: workspace.repo_loader().clone(),
+            workspace_root: workspace.workspace_root().to_path_buf(),
+            jj_binary_path: "jj".into(),
+            executor,
+        })
     }
 
     fn find_workspace_dir(cwd: &Path) -> &Path {
@@ -46,27 +120,379 @@ impl RealJujutsuRepository {
             .find(|path| path.join(".jj").is_dir())
             .unwrap_or(cwd)
     }
+
+    fn run(&self, args: Vec<String>) -> BoxFuture<'static, Result<String>> {
+        let jj_binary_path = self.jj_binary_path.clone();
+        let workspace_root = self.workspace_root.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&jj_binary_path)
+                    .current_dir(&workspace_root)
+                    .args(["--no-pager", "--color=never"])
+                    .args(&args)
+                    .output()
+                    .await
+                    .with_context(|| format!("running jj {}", args.join(" ")))?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "jj {} failed: {}",
+                    args.join(" "),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+            })
+            .boxed()
+    }
 }
 
 impl JujutsuRepository for RealJujutsuRepository {
     fn list_bookmarks(&self) -> Vec<Bookmark> {
-        let bookmarks = self
-            .repository
+        let Ok(repository) = self.repo_loader.load_at_head() else {
+            return Vec::new();
+        };
+
+        repository
             .view()
             .bookmarks()
             .map(|(ref_name, _target)| Bookmark {
                 ref_name: ref_name.as_str().to_string().into(),
             })
-            .collect();
+            .collect()
+    }
+
+    fn workspace_root(&self) -> &Path {
+        &self.workspace_root
+    }
+
+    fn working_copy(&self) -> BoxFuture<'static, Result<Change>> {
+        const TEMPLATE: &str = r#"change_id.short() ++ "\t" ++ commit_id.short() ++ "\t" ++ if(empty, "1", "0") ++ "\t" ++ if(conflict, "1", "0") ++ "\t" ++ description"#;
+        let output = self.run(args(["log", "--no-graph", "-r", "@", "-T", TEMPLATE]));
+        async move { parse_change(&output.await?) }.boxed()
+    }
+
+    fn status(&self) -> BoxFuture<'static, Result<Vec<FileChange>>> {
+        let output = self.run(args(["diff", "--summary", "-r", "@"]));
+        async move { Ok(parse_diff_summary(&output.await?)) }.boxed()
+    }
+
+    fn load_parent_text(&self, path: &Path) -> BoxFuture<'static, Result<Option<String>>> {
+        let path = path
+            .to_string_lossy()
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"");
+        let fileset = format!("root-file:\"{path}\"");
+        let files = self.run(vec![
+            "file".into(),
+            "list".into(),
+            "-r".into(),
+            "@-".into(),
+            "--".into(),
+            fileset.clone(),
+        ]);
+        let text = self.run(vec![
+            "file".into(),
+            "show".into(),
+            "-r".into(),
+            "@-".into(),
+            "--".into(),
+            fileset,
+        ]);
+        // `jj file show` prints nothing (and succeeds) both for empty files and for paths
+        // that don't exist in the revision, so check whether the file exists separately.
+        async move {
+            if files.await?.trim().is_empty() {
+                return Ok(None);
+            }
+            text.await.map(Some)
+        }
+        .boxed()
+    }
 
-        bookmarks
+    fn describe(&self, message: String) -> BoxFuture<'static, Result<()>> {
+        let output = self.run(vec!["describe".into(), "-m".into(), message]);
+        async move { output.await.map(|_| ()) }.boxed()
+    }
+
+    fn new_change(&self) -> BoxFuture<'static, Result<()>> {
+        let output = self.run(args(["new"]));
+        async move { output.await.map(|_| ()) }.boxed()
+    }
+
+    fn squash(&self) -> BoxFuture<'static, Result<()>> {
+        let output = self.run(args(["squash"]));
+        async move { output.await.map(|_| ()) }.boxed()
+    }
+
+    fn operation_log(&self, limit: usize) -> BoxFuture<'static, Result<Vec<Operation>>> {
+        const TEMPLATE: &str = r#"id.short() ++ "\t" ++ description.first_line() ++ "\t" ++ user ++ "\t" ++ time.start().ago() ++ "\n""#;
+        let output = self.run(vec![
+            "op".into(),
+            "log".into(),
+            "--no-graph".into(),
+            "-n".into(),
+            limit.to_string(),
+            "-T".into(),
+            TEMPLATE.into(),
+        ]);
+        async move { Ok(parse_operation_log(&output.await?)) }.boxed()
+    }
+
+    fn undo_operation(&self, id: SharedString) -> BoxFuture<'static, Result<()>> {
+        let output = self.run(vec!["op".into(), "undo".into(), id.to_string()]);
+        async move { output.await.map(|_| ()) }.boxed()
     }
 }
 
-pub struct FakeJujutsuRepository {}
+fn args<const N: usize>(args: [&str; N]) -> Vec<String> {
+    args.into_iter().map(ToOwned::to_owned).collect()
+}
+
+fn parse_change(output: &str) -> Result<Change> {
+    let mut fields = output.splitn(5, '\t');
+    let mut next_field = || fields.next().context("unexpected jj log output");
+    Ok(Change {
+        change_id: next_field()?.to_string().into(),
+        commit_id: next_field()?.to_string().into(),
+        is_empty: next_field()? == "1",
+        has_conflict: next_field()? == "1",
+        description: next_field()?.trim_end().to_string().into(),
+    })
+}
+
+/// Parses the output of `jj diff --summary`, e.g. `M src/main.rs` or `R src/{a.rs => b.rs}`.
+fn parse_diff_summary(output: &str) -> Vec<FileChange> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (kind, path) = line.split_once(' ')?;
+            let kind = match kind {
+                "A" => FileChangeKind::Added,
+                "M" => FileChangeKind::Modified,
+                "D" => FileChangeKind::Removed,
+                "C" => FileChangeKind::Copied,
+                "R" => FileChangeKind::Renamed,
+                _ => return None,
+            };
+            let path = match kind {
+                FileChangeKind::Copied | FileChangeKind::Renamed => target_path(path),
+                _ => path.to_string(),
+            };
+            Some(FileChange {
+                path: Path::new(&path).into(),
+                kind,
+            })
+        })
+        .collect()
+}
+
+/// Returns the new path of a copy or rename, which jj prints as `{old => new}`, possibly
+/// with a common prefix and suffix.
+fn target_path(path: &str) -> String {
+    let Some((prefix, rest)) = path.split_once('{') else {
+        return path
+            .split_once(" => ")
+            .map_or(path, |(_, new)| new)
+            .to_string();
+    };
+    let Some((renamed, suffix)) = rest.split_once('}') else {
+        return path.to_string();
+    };
+    let new = renamed.split_once(" => ").map_or(renamed, |(_, new)| new);
+    let path = format!("{prefix}{new}{suffix}");
+    // An empty side of the rename leaves a doubled separator behind, e.g. `a/{b => }/c`.
+    path.replace("//", "/")
+}
+
+fn parse_operation_log(output: &str) -> Vec<Operation> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\t');
+            Some(Operation {
+                id: fields.next()?.to_string().into(),
+                description: fields.next()?.to_string().into(),
+                user: fields.next()?.to_string().into(),
+                time: fields.next()?.to_string().into(),
+            })
+        })
+        .collect()
+}
+
+/// An in-memory repository for tests.
+pub struct FakeJujutsuRepository {
+    workspace_root: PathBuf,
+    state: Arc<Mutex<FakeJujutsuState>>,
+}
+
+#[derive(Default)]
+pub struct FakeJujutsuState {
+    pub bookmarks: Vec<Bookmark>,
+    pub working_copy: Option<Change>,
+    pub file_changes: Vec<FileChange>,
+    pub parent_texts: HashMap<PathBuf, String>,
+    pub operations: Vec<Operation>,
+}
+
+impl FakeJujutsuRepository {
+    pub fn new(workspace_root: impl Into<PathBuf>) -> Self {
+        Self {
+            workspace_root: workspace_root.into(),
+            state: Default::default(),
+        }
+    }
+
+    pub fn state(&self) -> Arc<Mutex<FakeJujutsuState>> {
+        self.state.clone()
+    }
+
+    fn record_operation(state: &mut FakeJujutsuState, description: &str) {
+        state.operations.insert(
+            0,
+            Operation {
+                id: format!("{:012x}", state.operations.len()).into(),
+                description: description.to_string().into(),
+                user: "test@localhost".into(),
+                time: "now".into(),
+            },
+        );
+    }
+
+    fn update(
+        &self,
+        description: &'static str,
+        f: impl FnOnce(&mut FakeJujutsuState),
+    ) -> BoxFuture<'static, Result<()>> {
+        let mut state = self.state.lock();
+        f(&mut state);
+        Self::record_operation(&mut state, description);
+        futures::future::ready(Ok(())).boxed()
+    }
+}
 
 impl JujutsuRepository for FakeJujutsuRepository {
     fn list_bookmarks(&self) -> Vec<Bookmark> {
-        Vec::new()
+        self.state.lock().bookmarks.clone()
+    }
+
+    fn workspace_root(&self) -> &Path {
+        &self.workspace_root
+    }
+
+    fn working_copy(&self) -> BoxFuture<'static, Result<Change>> {
+        let working_copy = self
+            .state
+            .lock()
+            .working_copy
+            .clone()
+            .context("no working-copy change");
+        futures::future::ready(working_copy).boxed()
+    }
+
+    fn status(&self) -> BoxFuture<'static, Result<Vec<FileChange>>> {
+        futures::future::ready(Ok(self.state.lock().file_changes.clone())).boxed()
+    }
+
+    fn load_parent_text(&self, path: &Path) -> BoxFuture<'static, Result<Option<String>>> {
+        let text = self.state.lock().parent_texts.get(path).cloned();
+        futures::future::ready(Ok(text)).boxed()
+    }
+
+    fn describe(&self, message: String) -> BoxFuture<'static, Result<()>> {
+        self.update("describe commit", move |state| {
+            if let Some(working_copy) = state.working_copy.as_mut() {
+                working_copy.description = message.into();
+            }
+        })
+    }
+
+    fn new_change(&self) -> BoxFuture<'static, Result<()>> {
+        self.update("new empty commit", |state| {
+            state.file_changes.clear();
+            if let Some(working_copy) = state.working_copy.as_mut() {
+                working_copy.description = SharedString::default();
+                working_copy.is_empty = true;
+            }
+        })
+    }
+
+    fn squash(&self) -> BoxFuture<'static, Result<()>> {
+        self.update("squash commits", |state| {
+            state.file_changes.clear();
+            if let Some(working_copy) = state.working_copy.as_mut() {
+                working_copy.is_empty = true;
+            }
+        })
+    }
+
+    fn operation_log(&self, limit: usize) -> BoxFuture<'static, Result<Vec<Operation>>> {
+        let operations = self
+            .state
+            .lock()
+            .operations
+            .iter()
+            .take(limit)
+            .cloned()
+            .collect();
+        futures::future::ready(Ok(operations)).boxed()
+    }
+
+    fn undo_operation(&self, id: SharedString) -> BoxFuture<'static, Result<()>> {
+        self.update("undo operation", move |state| {
+            state.operations.retain(|operation| operation.id != id);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_change() {
+        let change =
+            parse_change("kxqpmzvu\t3f2a1b9c\t0\t1\tAdd the thing\n\nWith a body\n").unwrap();
+        assert_eq!(
+            change,
+            Change {
+                change_id: "kxqpmzvu".into(),
+                commit_id: "3f2a1b9c".into(),
+                description: "Add the thing\n\nWith a body".into(),
+                is_empty: false,
+                has_conflict: true,
+            }
+        );
+        assert!(parse_change("kxqpmzvu\t3f2a1b9c").is_err());
+    }
+
+    #[test]
+    fn test_parse_diff_summary() {
+        let changes = parse_diff_summary(
+            "M src/main.rs\nA docs/new file.md\nD old.txt\nR src/{lib.rs => core.rs}\nC {a => b}/mod.rs\nR dir/{sub => }/x.rs\n",
+        );
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| (change.kind, change.path.to_string_lossy().into_owned()))
+                .collect::<Vec<_>>(),
+            vec![
+                (FileChangeKind::Modified, "src/main.rs".to_string()),
+                (FileChangeKind::Added, "docs/new file.md".to_string()),
+                (FileChangeKind::Removed, "old.txt".to_string()),
+                (FileChangeKind::Renamed, "src/core.rs".to_string()),
+                (FileChangeKind::Copied, "b/mod.rs".to_string()),
+                (FileChangeKind::Renamed, "dir/x.rs".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_operation_log() {
+        let operations = parse_operation_log(
+            "a1b2c3d4e5f6\tdescribe commit 123\tme@host\t2 minutes ago\nffeeddccbbaa\tsnapshot working copy\tme@host\t1 hour ago\n",
+        );
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations[0].id, "a1b2c3d4e5f6");
+        assert_eq!(operations[0].description, "describe commit 123");
+        assert_eq!(operations[1].time, "1 hour ago");
     }
 }
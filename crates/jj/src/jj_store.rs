@@ -1,41 +1,209 @@
 use std::path::Path;
 use std::sync::Arc;
 
-use gpui::{App, Entity, Global, prelude::*};
+use anyhow::Result;
+use futures::future::BoxFuture;
+use gpui::{App, Context, EventEmitter, SharedString, Task};
+use util::ResultExt as _;
 
-use crate::{JujutsuRepository, RealJujutsuRepository};
-
-/// Note: We won't ultimately be storing the jj store in a global, we're just doing this for exploration purposes.
-struct GlobalJujutsuStore(Entity<JujutsuStore>);
-
-impl Global for GlobalJujutsuStore {}
+use crate::{Change, FileChange, JujutsuRepository, Operation};
 
+/// The state of a single Jujutsu workspace.
 pub struct JujutsuStore {
     repository: Arc<dyn JujutsuRepository>,
+    working_copy: Option<Change>,
+    file_changes: Vec<FileChange>,
+    _refresh_task: Task<()>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JujutsuStoreEvent {
+    /// The working-copy change or the files it touches changed.
+    WorkingCopyChanged,
+}
+
+impl EventEmitter<JujutsuStoreEvent> for JujutsuStore {}
+
 impl JujutsuStore {
-    pub fn init_global(cx: &mut App) {
-        let Some(repository) = RealJujutsuRepository::new(&Path::new(".")).ok() else {
-            return;
+    pub fn new(repository: Arc<dyn JujutsuRepository>, cx: &mut Context<Self>) -> Self {
+        let mut this = Self {
+            repository,
+            working_copy: None,
+            file_changes: Vec::new(),
+            _refresh_task: Task::ready(()),
         };
+        this.refresh(cx);
+        this
+    }
+
+    pub fn repository(&self) -> &Arc<dyn JujutsuRepository> {
+        &self.repository
+    }
 
-        let repository = Arc::new(repository);
-        let jj_store = cx.new(|cx| JujutsuStore::new(repository, cx));
+    /// The working-copy change as of the last refresh.
+    pub fn working_copy(&self) -> Option<&Change> {
+        self.working_copy.as_ref()
+    }
 
-        cx.set_global(GlobalJujutsuStore(jj_store));
+    /// The files changed in the working-copy change as of the last refresh.
+    pub fn file_changes(&self) -> &[FileChange] {
+        &self.file_changes
     }
 
-    pub fn try_global(cx: &App) -> Option<Entity<Self>> {
-        cx.try_global::<GlobalJujutsuStore>()
-            .map(|global| global.0.clone())
+    /// Whether the given absolute path is inside the jj workspace.
+    pub fn contains_path(&self, abs_path: &Path) -> bool {
+        abs_path.starts_with(self.repository.workspace_root())
     }
 
-    pub fn new(repository: Arc<dyn JujutsuRepository>, _cx: &mut Context<Self>) -> Self {
-        Self { repository }
+    /// Re-reads the working-copy change, e.g. after files were saved.
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        let working_copy = self.repository.working_copy();
+        let status = self.repository.status();
+        self._refresh_task = cx.spawn(async move |this, cx| {
+            let working_copy = working_copy.await.log_err();
+            let file_changes = status.await.log_err().unwrap_or_default();
+            this.update(cx, |this, cx| {
+                if this.working_copy != working_copy || this.file_changes != file_changes {
+                    this.working_copy = working_copy;
+                    this.file_changes = file_changes;
+                    cx.emit(JujutsuStoreEvent::WorkingCopyChanged);
+                    cx.notify();
+                }
+            })
+            .ok();
+        });
     }
 
-    pub fn repository(&self) -> &Arc<dyn JujutsuRepository> {
-        &self.repository
+    /// Returns the contents of the file at the given absolute path in the parent of the
+    /// working-copy change, which is what the working copy is diffed against.
+    pub fn load_parent_text(&self, abs_path: &Path, cx: &App) -> Task<Result<Option<String>>> {
+        let Ok(path) = abs_path.strip_prefix(self.repository.workspace_root()) else {
+            return Task::ready(Ok(None));
+        };
+        let load = self.repository.load_parent_text(path);
+        cx.background_spawn(load)
+    }
+
+    pub fn describe(&mut self, message: String, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let describe = self.repository.describe(message);
+        self.run_and_refresh(describe, cx)
+    }
+
+    pub fn new_change(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let new_change = self.repository.new_change();
+        self.run_and_refresh(new_change, cx)
+    }
+
+    pub fn squash(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let squash = self.repository.squash();
+        self.run_and_refresh(squash, cx)
+    }
+
+    pub fn operation_log(&self, limit: usize, cx: &App) -> Task<Result<Vec<Operation>>> {
+        let operation_log = self.repository.operation_log(limit);
+        cx.background_spawn(operation_log)
+    }
+
+    pub fn undo_operation(&mut self, id: SharedString, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let undo = self.repository.undo_operation(id);
+        self.run_and_refresh(undo, cx)
+    }
+
+    fn run_and_refresh(
+        &mut self,
+        operation: BoxFuture<'static, Result<()>>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        cx.spawn(async move |this, cx| {
+            let result = operation.await;
+            this.update(cx, |this, cx| this.refresh(cx))?;
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FakeJujutsuRepository, FileChangeKind};
+    use gpui::{AppContext as _, TestAppContext};
+
+    #[gpui::test]
+    async fn test_operations_refresh_working_copy(cx: &mut TestAppContext) {
+        let repository = FakeJujutsuRepository::new("/workspace");
+        let state = repository.state();
+        state.lock().working_copy = Some(Change {
+            change_id: "kxqpmzvu".into(),
+            commit_id: "3f2a1b9c".into(),
+            description: SharedString::default(),
+            is_empty: false,
+            has_conflict: false,
+        });
+        state.lock().file_changes = vec![FileChange {
+            path: Path::new("src/main.rs").into(),
+            kind: FileChangeKind::Modified,
+        }];
+        state
+            .lock()
+            .parent_texts
+            .insert("src/main.rs".into(), "fn main() {}\n".into());
+
+        let jj_store = cx.new(|cx| JujutsuStore::new(Arc::new(repository), cx));
+        cx.run_until_parked();
+        jj_store.read_with(cx, |jj_store, _| {
+            assert_eq!(jj_store.file_changes().len(), 1);
+            assert_eq!(jj_store.working_copy().unwrap().description, "");
+        });
+
+        let parent_text = jj_store
+            .read_with(cx, |jj_store, cx| {
+                jj_store.load_parent_text(Path::new("/workspace/src/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(parent_text.as_deref(), Some("fn main() {}\n"));
+        let outside_workspace = jj_store
+            .read_with(cx, |jj_store, cx| {
+                jj_store.load_parent_text(Path::new("/elsewhere/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(outside_workspace, None);
+
+        jj_store
+            .update(cx, |jj_store, cx| {
+                jj_store.describe("Fix the thing".into(), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        jj_store.read_with(cx, |jj_store, _| {
+            assert_eq!(
+                jj_store.working_copy().unwrap().description,
+                "Fix the thing"
+            );
+        });
+
+        jj_store
+            .update(cx, |jj_store, cx| jj_store.new_change(cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        jj_store.read_with(cx, |jj_store, _| {
+            assert!(jj_store.file_changes().is_empty());
+            assert!(jj_store.working_copy().unwrap().is_empty);
+        });
+
+        let operations = jj_store
+            .read_with(cx, |jj_store, cx| jj_store.operation_log(10, cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            operations
+                .iter()
+                .map(|operation| operation.description.as_ref())
+                .collect::<Vec<_>>(),
+            vec!["new empty commit", "describe commit"]
+        );
     }
 }
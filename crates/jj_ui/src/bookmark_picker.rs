@@ -21,7 +21,7 @@ fn open(
     window: &mut Window,
     cx: &mut Context<Workspace>,
 ) {
-    let Some(jj_store) = JujutsuStore::try_global(cx) else {
+    let Some(jj_store) = crate::jj_store_for_workspace(workspace, cx) else {
         return;
     };
 
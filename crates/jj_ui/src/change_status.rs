@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+use fuzzy::{StringMatchCandidate, match_strings};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, Task,
+    WeakEntity, Window, prelude::*,
+};
+use jj::{FileChange, FileChangeKind, JujutsuStore};
+use picker::{Picker, PickerDelegate};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, OpenOptions, Workspace};
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+}
+
+fn open(
+    workspace: &mut Workspace,
+    _: &zed_actions::jj::ChangeStatus,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(jj_store) = crate::jj_store_for_workspace(workspace, cx) else {
+        return;
+    };
+    jj_store.update(cx, |jj_store, cx| jj_store.refresh(cx));
+
+    let workspace_handle = cx.entity().downgrade();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        let delegate =
+            ChangeStatusDelegate::new(cx.entity().downgrade(), workspace_handle, jj_store);
+        ChangeStatus::new(delegate, window, cx)
+    });
+}
+
+/// Lists the files that the working-copy change touches, and opens the confirmed one.
+pub struct ChangeStatus {
+    picker: Entity<Picker<ChangeStatusDelegate>>,
+    _subscription: Subscription,
+}
+
+impl ChangeStatus {
+    pub fn new(
+        delegate: ChangeStatusDelegate,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let jj_store = delegate.jj_store.clone();
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        // Pick up the refreshed status once it has been read.
+        let _subscription = cx.observe_in(&jj_store, window, |this, _, window, cx| {
+            this.picker
+                .update(cx, |picker, cx| picker.refresh(window, cx));
+        });
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for ChangeStatus {}
+
+impl EventEmitter<DismissEvent> for ChangeStatus {}
+
+impl Focusable for ChangeStatus {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for ChangeStatus {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FileChangeEntry {
+    file_change: FileChange,
+    positions: Vec<usize>,
+}
+
+pub struct ChangeStatusDelegate {
+    change_status: WeakEntity<ChangeStatus>,
+    workspace: WeakEntity<Workspace>,
+    jj_store: Entity<JujutsuStore>,
+    matches: Vec<FileChangeEntry>,
+    selected_index: usize,
+}
+
+impl ChangeStatusDelegate {
+    fn new(
+        change_status: WeakEntity<ChangeStatus>,
+        workspace: WeakEntity<Workspace>,
+        jj_store: Entity<JujutsuStore>,
+    ) -> Self {
+        Self {
+            change_status,
+            workspace,
+            jj_store,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for ChangeStatusDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, cx: &mut App) -> Arc<str> {
+        match self.jj_store.read(cx).working_copy() {
+            Some(change) if !change.description.is_empty() => {
+                let summary = change.description.lines().next().unwrap_or_default();
+                format!("{}: {summary}", change.change_id).into()
+            }
+            Some(change) => format!("{}: (no description set)", change.change_id).into(),
+            None => "Search changed files…".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let file_changes = self.jj_store.read(cx).file_changes().to_vec();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                file_changes
+                    .into_iter()
+                    .map(|file_change| FileChangeEntry {
+                        file_change,
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let candidates = file_changes
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, file_change)| {
+                        StringMatchCandidate::new(ix, &file_change.path.to_string_lossy())
+                    })
+                    .collect::<Vec<_>>();
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+                .into_iter()
+                .map(|mat| FileChangeEntry {
+                    file_change: file_changes[mat.candidate_id].clone(),
+                    positions: mat.positions,
+                })
+                .collect()
+            };
+
+            this.update(cx, |this, _cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = 0;
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        // Removed files can't be opened.
+        if entry.file_change.kind == FileChangeKind::Removed {
+            return;
+        }
+        let abs_path = self
+            .jj_store
+            .read(cx)
+            .repository()
+            .workspace_root()
+            .join(&entry.file_change.path);
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_abs_path(abs_path, OpenOptions::default(), window, cx)
+                    .detach_and_log_err(cx);
+            })
+            .log_err();
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.change_status
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.matches[ix];
+        let (status, color) = match entry.file_change.kind {
+            FileChangeKind::Added => ("A", Color::Created),
+            FileChangeKind::Modified => ("M", Color::Modified),
+            FileChangeKind::Removed => ("D", Color::Deleted),
+            FileChangeKind::Copied => ("C", Color::Created),
+            FileChangeKind::Renamed => ("R", Color::Modified),
+        };
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Label::new(status).size(LabelSize::Small).color(color))
+                .child(HighlightedLabel::new(
+                    entry.file_change.path.to_string_lossy().into_owned(),
+                    entry.positions.clone(),
+                )),
+        )
+    }
+}
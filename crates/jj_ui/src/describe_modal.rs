@@ -0,0 +1,150 @@
+use editor::Editor;
+use gpui::{App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Window, prelude::*};
+use jj::JujutsuStore;
+use ui::prelude::*;
+use workspace::notifications::NotifyTaskExt as _;
+use workspace::{ModalView, Workspace};
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(describe);
+    workspace.register_action(new_change);
+    workspace.register_action(squash);
+}
+
+fn describe(
+    workspace: &mut Workspace,
+    _: &zed_actions::jj::Describe,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(jj_store) = crate::jj_store_for_workspace(workspace, cx) else {
+        return;
+    };
+
+    workspace.toggle_modal(window, cx, |window, cx| {
+        DescribeModal::new(jj_store, window, cx)
+    });
+}
+
+fn new_change(
+    workspace: &mut Workspace,
+    _: &zed_actions::jj::NewChange,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(jj_store) = crate::jj_store_for_workspace(workspace, cx) else {
+        return;
+    };
+
+    jj_store
+        .update(cx, |jj_store, cx| jj_store.new_change(cx))
+        .detach_and_notify_err(window, cx);
+}
+
+fn squash(
+    workspace: &mut Workspace,
+    _: &zed_actions::jj::Squash,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(jj_store) = crate::jj_store_for_workspace(workspace, cx) else {
+        return;
+    };
+
+    jj_store
+        .update(cx, |jj_store, cx| jj_store.squash(cx))
+        .detach_and_notify_err(window, cx);
+}
+
+/// Edits the description of the working-copy change.
+pub struct DescribeModal {
+    jj_store: Entity<JujutsuStore>,
+    editor: Entity<Editor>,
+}
+
+impl DescribeModal {
+    pub fn new(
+        jj_store: Entity<JujutsuStore>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let description = jj_store
+            .read(cx)
+            .working_copy()
+            .map(|change| change.description.clone())
+            .unwrap_or_default();
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(8, window, cx);
+            editor.set_placeholder_text("Describe the working-copy change", cx);
+            editor.set_text(description, window, cx);
+            editor.select_all(&Default::default(), window, cx);
+            editor
+        });
+
+        Self { jj_store, editor }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let message = self.editor.read(cx).text(cx);
+        self.jj_store
+            .update(cx, |jj_store, cx| jj_store.describe(message, cx))
+            .detach_and_notify_err(window, cx);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for DescribeModal {}
+
+impl EventEmitter<DismissEvent> for DescribeModal {}
+
+impl Focusable for DescribeModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for DescribeModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let change_id = self
+            .jj_store
+            .read(cx)
+            .working_copy()
+            .map(|change| change.change_id.clone());
+
+        v_flex()
+            .key_context("JjDescribe")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .gap_1p5()
+                    .child(Headline::new("Describe Change").size(HeadlineSize::XSmall))
+                    .when_some(change_id, |this, change_id| {
+                        this.child(
+                            Label::new(change_id)
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .py_2()
+                    .px_3()
+                    .bg(cx.theme().colors().editor_background)
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.editor.clone()),
+            )
+    }
+}
@@ -1,22 +1,46 @@
 mod bookmark_picker;
+mod change_status;
+mod describe_modal;
+mod operation_log;
+mod working_copy_diff;
 
 use command_palette_hooks::CommandPaletteFilter;
+use editor::Editor;
 use feature_flags::FeatureFlagAppExt as _;
-use gpui::App;
+use gpui::{App, Entity};
 use jj::JujutsuStore;
 use workspace::Workspace;
 
 pub fn init(cx: &mut App) {
-    JujutsuStore::init_global(cx);
-
     cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
         bookmark_picker::register(workspace);
+        change_status::register(workspace);
+        describe_modal::register(workspace);
+        operation_log::register(workspace);
+    })
+    .detach();
+
+    cx.observe_new(|editor: &mut Editor, _window, cx| {
+        working_copy_diff::register_editor(editor, cx);
     })
     .detach();
 
     feature_gate_jj_ui_actions(cx);
 }
 
+/// Returns the jj workspace that actions apply to: the one containing the active item's file,
+/// or otherwise the first one in the project.
+fn jj_store_for_workspace(workspace: &Workspace, cx: &App) -> Option<Entity<JujutsuStore>> {
+    let project = workspace.project().read(cx);
+    let git_store = project.git_store().read(cx);
+    workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx))
+        .and_then(|project_path| project.absolute_path(&project_path, cx))
+        .and_then(|abs_path| git_store.jj_store_for_path(&abs_path, cx))
+        .or_else(|| git_store.jj_stores().first().cloned())
+}
+
 fn feature_gate_jj_ui_actions(cx: &mut App) {
     const JJ_ACTION_NAMESPACE: &str = "jj";
 
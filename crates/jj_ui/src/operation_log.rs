@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+use fuzzy::{StringMatchCandidate, match_strings};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task, WeakEntity, Window,
+    prelude::*,
+};
+use jj::{JujutsuStore, Operation};
+use picker::{Picker, PickerDelegate};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::notifications::NotifyTaskExt as _;
+use workspace::{ModalView, Workspace};
+
+const OPERATION_LIMIT: usize = 200;
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+}
+
+fn open(
+    workspace: &mut Workspace,
+    _: &zed_actions::jj::OperationLog,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(jj_store) = crate::jj_store_for_workspace(workspace, cx) else {
+        return;
+    };
+
+    workspace.toggle_modal(window, cx, |window, cx| {
+        let delegate = OperationLogDelegate::new(cx.entity().downgrade(), jj_store);
+        OperationLog::new(delegate, window, cx)
+    });
+}
+
+/// Lists the operations in the operation log, and undoes the confirmed one.
+pub struct OperationLog {
+    picker: Entity<Picker<OperationLogDelegate>>,
+    _load_task: Task<()>,
+}
+
+impl OperationLog {
+    pub fn new(
+        delegate: OperationLogDelegate,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let load = delegate
+            .jj_store
+            .read(cx)
+            .operation_log(OPERATION_LIMIT, cx);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let _load_task = cx.spawn_in(window, {
+            let picker = picker.downgrade();
+            async move |_, cx| {
+                let Some(operations) = load.await.log_err() else {
+                    return;
+                };
+                picker
+                    .update_in(cx, |picker, window, cx| {
+                        picker.delegate.all_operations = operations;
+                        picker.refresh(window, cx);
+                    })
+                    .log_err();
+            }
+        });
+
+        Self { picker, _load_task }
+    }
+}
+
+impl ModalView for OperationLog {}
+
+impl EventEmitter<DismissEvent> for OperationLog {}
+
+impl Focusable for OperationLog {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for OperationLog {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct OperationEntry {
+    operation: Operation,
+    positions: Vec<usize>,
+}
+
+pub struct OperationLogDelegate {
+    operation_log: WeakEntity<OperationLog>,
+    jj_store: Entity<JujutsuStore>,
+    matches: Vec<OperationEntry>,
+    all_operations: Vec<Operation>,
+    selected_index: usize,
+}
+
+impl OperationLogDelegate {
+    fn new(operation_log: WeakEntity<OperationLog>, jj_store: Entity<JujutsuStore>) -> Self {
+        Self {
+            operation_log,
+            jj_store,
+            matches: Vec::new(),
+            all_operations: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for OperationLogDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select an operation to undo…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let all_operations = self.all_operations.clone();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                all_operations
+                    .into_iter()
+                    .map(|operation| OperationEntry {
+                        operation,
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let candidates = all_operations
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, operation)| StringMatchCandidate::new(ix, &operation.description))
+                    .collect::<Vec<_>>();
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+                .into_iter()
+                .map(|mat| OperationEntry {
+                    operation: all_operations[mat.candidate_id].clone(),
+                    positions: mat.positions,
+                })
+                .collect()
+            };
+
+            this.update(cx, |this, _cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = 0;
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        let id = entry.operation.id.clone();
+        self.jj_store
+            .update(cx, |jj_store, cx| jj_store.undo_operation(id, cx))
+            .detach_and_notify_err(window, cx);
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.operation_log
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.matches[ix];
+        let operation = &entry.operation;
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_2()
+                        .child(
+                            Label::new(operation.id.clone())
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            div().flex_1().overflow_hidden().child(
+                                HighlightedLabel::new(
+                                    operation.description.clone(),
+                                    entry.positions.clone(),
+                                )
+                                .single_line()
+                                .truncate(),
+                            ),
+                        )
+                        .child(
+                            Label::new(operation.time.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+                .tooltip(Tooltip::text(operation.user.clone())),
+        )
+    }
+}
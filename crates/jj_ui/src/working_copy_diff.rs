@@ -0,0 +1,215 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use buffer_diff::BufferDiff;
+use editor::Editor;
+use gpui::{AsyncApp, Context, Entity, Subscription, Task, WeakEntity};
+use jj::{JujutsuStore, JujutsuStoreEvent};
+use language::{Buffer, BufferEvent};
+use project::git_store::GitStoreEvent;
+use util::ResultExt as _;
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// Keeps the diff of a buffer against the parent of the working-copy change alive for
+/// as long as the editor is open.
+struct WorkingCopyDiffAddon {
+    working_copy_diff: Option<Entity<WorkingCopyDiff>>,
+    _git_store_subscription: Subscription,
+}
+
+impl editor::Addon for WorkingCopyDiffAddon {
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn to_any_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+        Some(self)
+    }
+}
+
+/// Shows the lines that the working-copy change touches in the gutter of editors for
+/// files in a jj workspace that isn't colocated with git.
+///
+/// In colocated workspaces jj keeps git's `HEAD` at the parent of the working-copy
+/// change, so the git store already produces the same diff.
+pub(crate) fn register_editor(editor: &mut Editor, cx: &mut Context<Editor>) {
+    if !editor.mode().is_full() {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    // jj workspaces are loaded in the background, possibly after the editor was opened.
+    let git_store = project.read(cx).git_store().clone();
+    let subscription = cx.subscribe(&git_store, |editor, _, event, cx| {
+        if let GitStoreEvent::JujutsuStoresChanged = event {
+            add_working_copy_diff(editor, cx);
+        }
+    });
+    editor.register_addon(WorkingCopyDiffAddon {
+        working_copy_diff: None,
+        _git_store_subscription: subscription,
+    });
+    add_working_copy_diff(editor, cx);
+}
+
+fn add_working_copy_diff(editor: &mut Editor, cx: &mut Context<Editor>) {
+    if editor
+        .addon::<WorkingCopyDiffAddon>()
+        .map_or(true, |addon| addon.working_copy_diff.is_some())
+    {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let Some(abs_path) = buffer
+        .read(cx)
+        .file()
+        .and_then(|file| file.as_local())
+        .map(|file| file.abs_path(cx))
+    else {
+        return;
+    };
+    let git_store = project.read(cx).git_store().read(cx);
+    let Some(jj_store) = git_store.jj_store_for_path(&abs_path, cx) else {
+        return;
+    };
+    let buffer_id = buffer.read(cx).remote_id();
+    if git_store
+        .repository_and_path_for_buffer_id(buffer_id, cx)
+        .is_some()
+    {
+        return;
+    }
+
+    let diff = cx.new(|cx| BufferDiff::new(&buffer.read(cx).text_snapshot(), cx));
+    let working_copy_diff =
+        cx.new(|cx| WorkingCopyDiff::new(abs_path, buffer, diff.clone(), jj_store, cx));
+    editor
+        .buffer()
+        .update(cx, |multibuffer, cx| multibuffer.add_diff(diff, cx));
+    if let Some(addon) = editor.addon_mut::<WorkingCopyDiffAddon>() {
+        addon.working_copy_diff = Some(working_copy_diff);
+    }
+}
+
+struct WorkingCopyDiff {
+    abs_path: PathBuf,
+    buffer: Entity<Buffer>,
+    diff: Entity<BufferDiff>,
+    jj_store: Entity<JujutsuStore>,
+    base_text: Option<Arc<String>>,
+    /// Bumped whenever the base text or the buffer's language changes, both of which
+    /// require re-parsing the base text.
+    base_version: usize,
+    diffed_base_version: Option<usize>,
+    _reload_task: Task<()>,
+    _update_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl WorkingCopyDiff {
+    fn new(
+        abs_path: PathBuf,
+        buffer: Entity<Buffer>,
+        diff: Entity<BufferDiff>,
+        jj_store: Entity<JujutsuStore>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = vec![
+            cx.subscribe(&buffer, |this, _, event, cx| match event {
+                BufferEvent::Edited | BufferEvent::Reloaded => this.schedule_update(cx),
+                BufferEvent::LanguageChanged => {
+                    this.base_version += 1;
+                    this.schedule_update(cx);
+                }
+                // jj only notices changes to the working copy when it snapshots it.
+                BufferEvent::Saved => this
+                    .jj_store
+                    .update(cx, |jj_store, cx| jj_store.refresh(cx)),
+                _ => {}
+            }),
+            cx.subscribe(&jj_store, |this, _, event, cx| match event {
+                JujutsuStoreEvent::WorkingCopyChanged => this.reload_base_text(cx),
+            }),
+        ];
+
+        let mut this = Self {
+            abs_path,
+            buffer,
+            diff,
+            jj_store,
+            base_text: None,
+            base_version: 0,
+            diffed_base_version: None,
+            _reload_task: Task::ready(()),
+            _update_task: Task::ready(()),
+            _subscriptions,
+        };
+        this.reload_base_text(cx);
+        this
+    }
+
+    fn reload_base_text(&mut self, cx: &mut Context<Self>) {
+        let load = self.jj_store.read(cx).load_parent_text(&self.abs_path, cx);
+        self._reload_task = cx.spawn(async move |this, cx| {
+            let Some(base_text) = load.await.log_err() else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                let base_text = base_text.map(Arc::new);
+                if this.diffed_base_version.is_none() || this.base_text != base_text {
+                    this.base_text = base_text;
+                    this.base_version += 1;
+                    this.schedule_update(cx);
+                }
+            })
+            .ok();
+        });
+    }
+
+    fn schedule_update(&mut self, cx: &mut Context<Self>) {
+        self._update_task = cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            Self::update_diff(this, cx).await.log_err();
+        });
+    }
+
+    async fn update_diff(this: WeakEntity<Self>, cx: &mut AsyncApp) -> Result<()> {
+        let (diff, buffer, base_text, base_version, base_changed, language, language_registry) =
+            this.read_with(cx, |this, cx| {
+                let buffer = this.buffer.read(cx);
+                (
+                    this.diff.clone(),
+                    buffer.text_snapshot(),
+                    this.base_text.clone(),
+                    this.base_version,
+                    this.diffed_base_version != Some(this.base_version),
+                    buffer.language().cloned(),
+                    buffer.language_registry(),
+                )
+            })?;
+
+        let snapshot = BufferDiff::update_diff(
+            diff.clone(),
+            buffer.clone(),
+            base_text,
+            base_changed,
+            base_changed,
+            language,
+            language_registry,
+            cx,
+        )
+        .await?;
+
+        diff.update(cx, |diff, cx| diff.set_snapshot(snapshot, &buffer, cx))?;
+        this.update(cx, |this, _| this.diffed_base_version = Some(base_version))
+    }
+}
@@ -22,7 +22,7 @@ use async_watch as watch;
 pub use clock::ReplicaId;
 use clock::{AGENT_REPLICA_ID, Lamport};
 use collections::HashMap;
-use fs::MTime;
+use fs::{Encoding, MTime};
 use futures::channel::oneshot;
 use gpui::{
     App, AppContext as _, Context, Entity, EventEmitter, HighlightStyle, SharedString, StyledText,
@@ -101,6 +101,10 @@ pub struct Buffer {
     /// The version vector when this buffer was last loaded from
     /// or saved to disk.
     saved_version: clock::Global,
+    /// The encoding that the buffer's file is reloaded and saved with.
+    encoding: Encoding,
+    /// Whether the buffer holds a large file, which is neither parsed nor kept in the undo history.
+    large_file: bool,
     preview_version: clock::Global,
     transaction_depth: usize,
     was_dirty_before_starting_transaction: Option<bool>,
@@ -231,6 +235,22 @@ pub struct Diagnostic {
     pub is_unnecessary: bool,
     /// Data from language server that produced this diagnostic. Passed back to the LS when we request code actions for this diagnostic.
     pub data: Option<Value>,
+    /// How the language server delivered this diagnostic.
+    pub source_kind: DiagnosticSourceKind,
+}
+
+/// The way a diagnostic was obtained from a language server.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DiagnosticSourceKind {
+    /// Returned in response to a `textDocument/diagnostic` or `workspace/diagnostic` request.
+    Pulled,
+    /// Sent by the server in a `textDocument/publishDiagnostics` notification.
+    Pushed,
+    /// Produced in some other way, e.g. by a server-specific extension.
+    #[default]
+    Other,
+    /// Found in the output of a task by its problem matchers.
+    Task,
 }
 
 /// An operation used to synchronize this buffer with its other replicas.
@@ -384,8 +404,9 @@ pub trait LocalFile: File {
     /// Returns the absolute path of this file
     fn abs_path(&self, cx: &App) -> PathBuf;
 
-    /// Loads the file contents from disk and returns them as a UTF-8 encoded string.
-    fn load(&self, cx: &App) -> Task<Result<String>>;
+    /// Loads the file contents from disk and decodes them with the given encoding,
+    /// or with the one that is detected if there is none.
+    fn load(&self, encoding: Option<Encoding>, cx: &App) -> Task<Result<(String, Encoding)>>;
 
     /// Loads the file's contents from disk.
     fn load_bytes(&self, cx: &App) -> Task<Result<Vec<u8>>>;
@@ -511,6 +532,17 @@ pub enum CharKind {
     Word,
 }
 
+/// The kind of syntax surrounding a position in a buffer.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SyntaxScope {
+    /// A comment.
+    Comment,
+    /// A string literal.
+    String,
+    /// Any other code.
+    Code,
+}
+
 /// A runnable is a set of data about a region that could be resolved into a task
 pub struct Runnable {
     pub tags: SmallVec<[RunnableTag; 1]>,
@@ -824,6 +856,11 @@ impl Buffer {
         ));
         this.saved_version = proto::deserialize_version(&message.saved_version);
         this.saved_mtime = message.saved_mtime.map(|time| time.into());
+        this.encoding = message
+            .encoding
+            .and_then(proto::deserialize_encoding)
+            .unwrap_or_default();
+        this.large_file = message.large_file;
         Ok(this)
     }
 
@@ -836,6 +873,8 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            encoding: Some(proto::serialize_encoding(self.encoding)),
+            large_file: self.large_file,
         }
     }
 
@@ -913,6 +952,8 @@ impl Buffer {
         Self {
             saved_mtime,
             saved_version: buffer.version(),
+            encoding: Encoding::default(),
+            large_file: false,
             preview_version: buffer.version(),
             reload_task: None,
             transaction_depth: 0,
@@ -1047,6 +1088,8 @@ impl Buffer {
                 }),
                 language: self.language.clone(),
                 has_conflict: self.has_conflict,
+                encoding: self.encoding,
+                large_file: self.large_file,
                 has_unsaved_edits: Cell::new(self.has_unsaved_edits.get_mut().clone()),
                 _subscriptions: vec![cx.subscribe(&this, Self::on_base_buffer_event)],
                 ..Self::build(self.text.branch(), self.file.clone(), self.capability())
@@ -1208,6 +1251,38 @@ impl Buffer {
         self.saved_mtime
     }
 
+    /// The encoding of the buffer's file.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Assign the encoding that the buffer's file is reloaded and saved with.
+    pub fn set_encoding(&mut self, encoding: Encoding, cx: &mut Context<Self>) {
+        if self.encoding != encoding {
+            self.encoding = encoding;
+            cx.notify();
+        }
+    }
+
+    /// Whether the buffer holds a large file, for which syntax parsing and the undo history are disabled.
+    pub fn is_large_file(&self) -> bool {
+        self.large_file
+    }
+
+    /// Marks the buffer as holding a large file. Large files are not parsed,
+    /// and their edits are not recorded in the undo history.
+    pub fn set_large_file(&mut self, large_file: bool, cx: &mut Context<Self>) {
+        if self.large_file != large_file {
+            self.large_file = large_file;
+            if large_file {
+                self.reparse = None;
+            } else {
+                self.reparse(cx);
+            }
+            cx.notify();
+        }
+    }
+
     /// Assign a language to the buffer.
     pub fn set_language(&mut self, language: Option<Arc<Language>>, cx: &mut Context<Self>) {
         self.non_text_state_update_count += 1;
@@ -1261,19 +1336,29 @@ impl Buffer {
 
     /// Reloads the contents of the buffer from disk.
     pub fn reload(&mut self, cx: &Context<Self>) -> oneshot::Receiver<Option<Transaction>> {
+        self.reload_with_encoding(self.encoding, cx)
+    }
+
+    /// Reloads the contents of the buffer from disk, decoding them with the given encoding.
+    /// The buffer switches to the encoding once its contents are replaced.
+    pub fn reload_with_encoding(
+        &mut self,
+        encoding: Encoding,
+        cx: &Context<Self>,
+    ) -> oneshot::Receiver<Option<Transaction>> {
         let (tx, rx) = futures::channel::oneshot::channel();
         let prev_version = self.text.version();
         self.reload_task = Some(cx.spawn(async move |this, cx| {
             let Some((new_mtime, new_text)) = this.update(cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
 
-                Some((file.disk_state().mtime(), file.load(cx)))
+                Some((file.disk_state().mtime(), file.load(Some(encoding), cx)))
             })?
             else {
                 return Ok(());
             };
 
-            let new_text = new_text.await?;
+            let (new_text, new_encoding) = new_text.await?;
             let diff = this
                 .update(cx, |this, cx| this.diff(new_text.clone(), cx))?
                 .await;
@@ -1283,6 +1368,7 @@ impl Buffer {
                     this.apply_diff(diff, cx);
                     tx.send(this.finalize_last_transaction().cloned()).ok();
                     this.has_conflict = false;
+                    this.encoding = new_encoding;
                     this.did_reload(this.version(), this.line_ending(), new_mtime, cx);
                 } else {
                     if !diff.edits.is_empty()
@@ -1441,7 +1527,7 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut Context<Self>) {
-        if self.reparse.is_some() {
+        if self.reparse.is_some() || self.large_file {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
@@ -2012,6 +2098,9 @@ impl Buffer {
             false
         };
         if let Some((transaction_id, start_version)) = self.text.end_transaction_at(now) {
+            if self.large_file {
+                self.text.forget_transaction(transaction_id);
+            }
             self.did_edit(&start_version, was_dirty, cx);
             Some(transaction_id)
         } else {
@@ -3272,6 +3361,45 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns whether the given position is in a comment, a string literal or other code.
+    ///
+    /// This uses the `comment` and `string` override scopes of the language at that position,
+    /// falling back to its `comment` and `string` highlights.
+    pub fn syntax_scope_at<D: ToOffset>(&self, position: D) -> SyntaxScope {
+        let offset = position.to_offset(self);
+        if let Some(scope) = self.language_scope_at(offset) {
+            match scope.override_name() {
+                Some("comment") => return SyntaxScope::Comment,
+                Some("string") => return SyntaxScope::String,
+                _ => {}
+            }
+        }
+
+        let mut captures = self.syntax.captures(offset..offset, &self.text, |grammar| {
+            grammar.highlights_query.as_ref()
+        });
+        let mut innermost_scope: Option<(usize, SyntaxScope)> = None;
+        while let Some(capture) = captures.peek() {
+            let range = capture.node.byte_range();
+            if range.contains(&offset)
+                && innermost_scope.is_none_or(|(innermost_len, _)| range.len() < innermost_len)
+            {
+                let grammar = captures.grammars()[capture.grammar_index];
+                let name = grammar
+                    .highlights_query
+                    .as_ref()
+                    .map_or("", |query| query.capture_names()[capture.index as usize]);
+                if name == "comment" || name.starts_with("comment.") {
+                    innermost_scope = Some((range.len(), SyntaxScope::Comment));
+                } else if name == "string" || name.starts_with("string.") {
+                    innermost_scope = Some((range.len(), SyntaxScope::String));
+                }
+            }
+            captures.advance();
+        }
+        innermost_scope.map_or(SyntaxScope::Code, |(_, scope)| scope)
+    }
+
     /// Returns a tuple of the range and character kind of the word
     /// surrounding the given position.
     pub fn surrounding_word<T: ToOffset>(&self, start: T) -> (Range<usize>, Option<CharKind>) {
@@ -4633,6 +4761,7 @@ impl Default for Diagnostic {
             is_disk_based: false,
             is_unnecessary: false,
             data: None,
+            source_kind: DiagnosticSourceKind::Other,
         }
     }
 }
@@ -4750,7 +4879,7 @@ impl LocalFile for TestFile {
             .join(self.path.as_ref())
     }
 
-    fn load(&self, _cx: &App) -> Task<Result<String>> {
+    fn load(&self, _: Option<Encoding>, _cx: &App) -> Task<Result<(String, Encoding)>> {
         unimplemented!()
     }
 
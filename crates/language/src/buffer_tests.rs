@@ -2420,6 +2420,60 @@ fn test_language_scope_at_with_rust(cx: &mut App) {
     });
 }
 
+#[gpui::test]
+fn test_syntax_scope_at(cx: &mut App) {
+    init_settings(cx, |_| {});
+
+    cx.new(|cx| {
+        let language = Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_override_query(
+            r#"
+                (string_literal) @string
+            "#,
+        )
+        .unwrap()
+        .with_highlights_query(
+            r#"
+                (line_comment) @comment
+                (identifier) @variable
+            "#,
+        )
+        .unwrap();
+
+        let text = r#"
+            // say hello
+            let s = "hello";
+        "#
+        .unindent();
+
+        let buffer = Buffer::local(text.clone(), cx).with_language(Arc::new(language), cx);
+        let snapshot = buffer.snapshot();
+
+        // Strings are found with the override scopes.
+        assert_eq!(
+            snapshot.syntax_scope_at(text.find("ello\"").unwrap()),
+            SyntaxScope::String
+        );
+        // Comments are found with the highlights, as the language has no comment override scope.
+        assert_eq!(
+            snapshot.syntax_scope_at(text.find("hello").unwrap()),
+            SyntaxScope::Comment
+        );
+        assert_eq!(
+            snapshot.syntax_scope_at(text.find("s =").unwrap()),
+            SyntaxScope::Code
+        );
+
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_language_scope_at_with_combined_injections(cx: &mut App) {
     init_settings(cx, |_| {});
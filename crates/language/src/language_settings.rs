@@ -143,6 +143,8 @@ pub struct LanguageSettings {
     pub code_actions_on_format: HashMap<String, bool>,
     /// Whether to perform linked edits
     pub linked_edits: bool,
+    /// Whether to highlight semantic tokens reported by language servers.
+    pub semantic_tokens: bool,
     /// Task configuration for this language.
     pub tasks: LanguageTaskConfig,
     /// Whether to pop the completions menu while typing in an editor without
@@ -534,6 +536,11 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub linked_edits: Option<bool>,
+    /// Whether to highlight the semantic tokens reported by language servers on top of
+    /// the tree-sitter syntax highlighting, if the language server supports it.
+    ///
+    /// Default: false
+    pub semantic_tokens: Option<bool>,
     /// Whether indentation of pasted content should be adjusted based on the context.
     ///
     /// Default: true
@@ -1458,6 +1465,10 @@ impl settings::Settings for AllLanguageSettings {
         );
         vscode.bool_setting("editor.formatOnType", &mut d.use_on_type_format);
         vscode.bool_setting("editor.linkedEditing", &mut d.linked_edits);
+        vscode.bool_setting(
+            "editor.semanticHighlighting.enabled",
+            &mut d.semantic_tokens,
+        );
         vscode.bool_setting("editor.formatOnPaste", &mut d.auto_indent_on_paste);
         vscode.bool_setting(
             "editor.suggestOnTriggerCharacters",
@@ -1528,6 +1539,7 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.code_actions_on_format.clone(),
     );
     merge(&mut settings.linked_edits, src.linked_edits);
+    merge(&mut settings.semantic_tokens, src.semantic_tokens);
     merge(&mut settings.tasks, src.tasks.clone());
 
     merge(
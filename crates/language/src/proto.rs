@@ -1,6 +1,6 @@
 //! Handles conversions of `language` items to and from the [`rpc`] protocol.
 
-use crate::{CursorShape, Diagnostic, diagnostic_set::DiagnosticEntry};
+use crate::{CursorShape, Diagnostic, DiagnosticSourceKind, diagnostic_set::DiagnosticEntry};
 use anyhow::{Context as _, Result};
 use clock::ReplicaId;
 use lsp::{DiagnosticSeverity, LanguageServerId};
@@ -27,6 +27,19 @@ pub fn serialize_line_ending(message: text::LineEnding) -> proto::LineEnding {
     }
 }
 
+/// Deserializes an [`fs::Encoding`] from the RPC representation.
+pub fn deserialize_encoding(message: proto::Encoding) -> Option<fs::Encoding> {
+    fs::Encoding::from_name(&message.name, message.bom)
+}
+
+/// Serializes an [`fs::Encoding`] to be sent over RPC.
+pub fn serialize_encoding(encoding: fs::Encoding) -> proto::Encoding {
+    proto::Encoding {
+        name: encoding.name().to_string(),
+        bom: encoding.has_bom(),
+    }
+}
+
 /// Serializes a [`crate::Operation`] to be sent over RPC.
 pub fn serialize_operation(operation: &crate::Operation) -> proto::Operation {
     proto::Operation {
@@ -222,6 +235,12 @@ pub fn serialize_diagnostics<'a>(
             is_disk_based: entry.diagnostic.is_disk_based,
             is_unnecessary: entry.diagnostic.is_unnecessary,
             data: entry.diagnostic.data.as_ref().map(|data| data.to_string()),
+            source_kind: match entry.diagnostic.source_kind {
+                DiagnosticSourceKind::Pulled => proto::diagnostic::SourceKind::Pulled,
+                DiagnosticSourceKind::Pushed => proto::diagnostic::SourceKind::Pushed,
+                DiagnosticSourceKind::Other => proto::diagnostic::SourceKind::Other,
+                DiagnosticSourceKind::Task => proto::diagnostic::SourceKind::Task,
+            } as i32,
         })
         .collect()
 }
@@ -430,6 +449,14 @@ pub fn deserialize_diagnostics(
                     is_disk_based: diagnostic.is_disk_based,
                     is_unnecessary: diagnostic.is_unnecessary,
                     data,
+                    source_kind: match proto::diagnostic::SourceKind::from_i32(
+                        diagnostic.source_kind,
+                    )? {
+                        proto::diagnostic::SourceKind::Pulled => DiagnosticSourceKind::Pulled,
+                        proto::diagnostic::SourceKind::Pushed => DiagnosticSourceKind::Pushed,
+                        proto::diagnostic::SourceKind::Other => DiagnosticSourceKind::Other,
+                        proto::diagnostic::SourceKind::Task => DiagnosticSourceKind::Task,
+                    },
                 },
             })
         })
@@ -0,0 +1,768 @@
+use crate::{BufferSnapshot, Language, LanguageId, syntax_map::QueryCursorHandle, with_parser};
+use anyhow::{Context as _, Result, bail};
+use collections::HashMap;
+use parking_lot::Mutex;
+use std::{fmt, ops::Range, sync::Arc};
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Query};
+
+/// The prefix of the identifiers that stand for metavariables when parsing a pattern.
+const METAVARIABLE_PREFIX: &str = "zed_meta_";
+/// The prefix of the identifiers that stand for metavariables matching any number of nodes.
+const MULTIPLE_METAVARIABLE_PREFIX: &str = "zed_metas_";
+
+/// A syntax-aware search query, matched against the syntax trees of buffers.
+///
+/// The query is either a Tree-sitter query, such as `(call_expression function: (identifier) @name)`,
+/// or a code pattern with metavariables, such as `foo($A, $$$REST)`. In patterns, `$NAME` matches
+/// any single syntax node, `$$$NAME` matches any number of sibling nodes, and `$_` matches any
+/// node without capturing it. A metavariable used more than once only matches the same text.
+///
+/// Queries are compiled for each language they are matched against, and never match in languages
+/// in which they do not parse.
+pub struct StructuralQuery {
+    source: Arc<str>,
+    /// Strings that occur in every text the query matches.
+    literals: Vec<String>,
+    compiled: Mutex<HashMap<LanguageId, Option<Arc<CompiledQuery>>>>,
+}
+
+/// A match of a [`StructuralQuery`] in a buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructuralMatch {
+    /// The byte range of the matched syntax nodes.
+    pub range: Range<usize>,
+    /// The byte ranges captured by metavariables or query captures, by name.
+    pub captures: Vec<(Arc<str>, Range<usize>)>,
+}
+
+enum CompiledQuery {
+    TreeSitter(Query),
+    Pattern(PatternNode),
+}
+
+#[derive(Debug)]
+enum PatternNode {
+    MetaVariable {
+        name: Option<Arc<str>>,
+        multiple: bool,
+    },
+    Node {
+        kind_id: u16,
+        /// The text of the node, for leaf nodes.
+        text: Option<String>,
+        children: Vec<PatternNode>,
+    },
+}
+
+impl StructuralQuery {
+    pub fn new(source: &str) -> Result<Self> {
+        let source = source.trim();
+        if source.is_empty() {
+            bail!("structural query is empty");
+        }
+        let mut literals = Vec::new();
+        if is_tree_sitter_query(source) {
+            literals = tree_sitter_query_literals(source);
+        } else {
+            let code = replace_metavariables(source, |code, _, _| code.push(' '));
+            if code.trim().is_empty() {
+                bail!("structural pattern must contain code besides metavariables");
+            }
+            for word in code.split(|c: char| !is_name_char(c)) {
+                if !word.is_empty() && !literals.iter().any(|literal| literal == word) {
+                    literals.push(word.to_string());
+                }
+            }
+        }
+
+        Ok(Self {
+            source: source.into(),
+            literals,
+            compiled: Mutex::default(),
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns whether the query may match in the given text, without parsing it.
+    pub fn may_match(&self, text: &str) -> bool {
+        self.literals.iter().all(|literal| text.contains(literal))
+    }
+
+    /// Returns whether the query may match in buffers of the given language, i.e. whether it compiles for it.
+    pub fn may_match_language(&self, language: &Arc<Language>) -> bool {
+        self.compile(language).is_some()
+    }
+
+    /// Returns the non-overlapping matches of the query that lie within the given range of the buffer.
+    pub fn find_matches(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Vec<StructuralMatch> {
+        let text = buffer.text();
+        if !self.may_match(&text[range.clone()]) {
+            return Vec::new();
+        }
+
+        let mut matches = Vec::new();
+        for layer in buffer.syntax_layers() {
+            let root = layer.node();
+            if root.end_byte() < range.start || root.start_byte() > range.end {
+                continue;
+            }
+            let Some(compiled) = self.compile(layer.language) else {
+                continue;
+            };
+            compiled.find_matches(root, &text, &range, &mut matches);
+        }
+
+        matches.sort_by_key(|mat| (mat.range.start, std::cmp::Reverse(mat.range.end)));
+        let mut last_end = None;
+        matches.retain(|mat| {
+            if last_end.is_some_and(|last_end| mat.range.start < last_end) {
+                false
+            } else {
+                last_end = Some(mat.range.end);
+                true
+            }
+        });
+        matches
+    }
+
+    /// Returns the match of the query that spans exactly the given range of the buffer, if any.
+    pub fn match_at(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<StructuralMatch> {
+        self.matches_at(buffer, &[range]).pop().flatten()
+    }
+
+    /// Returns the matches of the query that span exactly the given ranges of the buffer, if any.
+    ///
+    /// The buffer is only searched once, so this is cheaper than calling [`Self::match_at`] for each range.
+    pub fn matches_at(
+        &self,
+        buffer: &BufferSnapshot,
+        ranges: &[Range<usize>],
+    ) -> Vec<Option<StructuralMatch>> {
+        let start = ranges.iter().map(|range| range.start).min();
+        let end = ranges.iter().map(|range| range.end).max();
+        let (Some(start), Some(end)) = (start, end) else {
+            return Vec::new();
+        };
+
+        let matches_by_range = self
+            .find_matches(buffer, start..end)
+            .into_iter()
+            .map(|mat| (mat.range.clone(), mat))
+            .collect::<HashMap<_, _>>();
+        ranges
+            .iter()
+            .map(|range| matches_by_range.get(range).cloned())
+            .collect()
+    }
+
+    fn compile(&self, language: &Arc<Language>) -> Option<Arc<CompiledQuery>> {
+        self.compiled
+            .lock()
+            .entry(language.id())
+            .or_insert_with(|| {
+                CompiledQuery::new(&self.source, language)
+                    .ok()
+                    .map(Arc::new)
+            })
+            .clone()
+    }
+}
+
+impl fmt::Debug for StructuralQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructuralQuery")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StructuralMatch {
+    /// Expands the `$NAME` references to captures in the given template with the captured text.
+    ///
+    /// References to unknown captures are kept as they are.
+    pub fn expand_template(&self, template: &str, buffer: &BufferSnapshot) -> String {
+        let mut expanded = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(dollar_ix) = rest.find('$') {
+            expanded.push_str(&rest[..dollar_ix]);
+            let reference = &rest[dollar_ix..];
+            let name_start = reference
+                .char_indices()
+                .take(3)
+                .take_while(|(_, c)| *c == '$')
+                .count();
+            let name_len = reference[name_start..]
+                .find(|c: char| !is_name_char(c))
+                .unwrap_or(reference.len() - name_start);
+            let name = &reference[name_start..name_start + name_len];
+            let capture = self
+                .captures
+                .iter()
+                .find(|(capture_name, _)| !name.is_empty() && **capture_name == *name);
+            match capture {
+                Some((_, range)) => expanded.extend(buffer.text_for_range(range.clone())),
+                None => expanded.push_str(&reference[..name_start + name_len]),
+            }
+            rest = &reference[name_start + name_len..];
+        }
+        expanded.push_str(rest);
+        expanded
+    }
+}
+
+impl CompiledQuery {
+    fn new(source: &str, language: &Language) -> Result<Self> {
+        let grammar = language.grammar().context("language has no grammar")?;
+        if is_tree_sitter_query(source) {
+            if let Ok(query) = Query::new(&grammar.ts_language, source) {
+                return Ok(Self::TreeSitter(query));
+            }
+        }
+
+        let pattern = replace_metavariables(source, |pattern, multiple, name| {
+            pattern.push_str(if multiple {
+                MULTIPLE_METAVARIABLE_PREFIX
+            } else {
+                METAVARIABLE_PREFIX
+            });
+            pattern.push_str(name);
+        });
+        let tree = with_parser(|parser| {
+            parser.set_language(&grammar.ts_language)?;
+            parser
+                .parse(&pattern, None)
+                .context("failed to parse pattern")
+        })?;
+        let mut root = tree.root_node();
+        if has_error_node(root) {
+            bail!("pattern does not parse in {}", language.name());
+        }
+        // Descend through the nodes that wrap the pattern, such as the source file or an expression statement.
+        while let [child] = significant_children(root).as_slice() {
+            if child.byte_range() != root.byte_range() {
+                break;
+            }
+            root = *child;
+        }
+        Ok(Self::Pattern(PatternNode::new(root, &pattern)))
+    }
+
+    fn find_matches(
+        &self,
+        root: Node,
+        text: &str,
+        range: &Range<usize>,
+        matches: &mut Vec<StructuralMatch>,
+    ) {
+        match self {
+            Self::TreeSitter(query) => {
+                let mut cursor = QueryCursorHandle::new();
+                cursor.set_byte_range(range.clone());
+                let mut query_matches = cursor.matches(query, root, text.as_bytes());
+                while let Some(query_match) = query_matches.next() {
+                    let mut match_range: Option<Range<usize>> = None;
+                    let mut captures = Vec::new();
+                    for capture in query_match.captures {
+                        let name = query.capture_names()[capture.index as usize];
+                        let capture_range = capture.node.byte_range();
+                        if name == "match" {
+                            match_range = Some(capture_range.clone());
+                        }
+                        captures.push((Arc::from(name), capture_range));
+                    }
+                    let match_range = match_range.or_else(|| {
+                        let start = captures.iter().map(|(_, range)| range.start).min()?;
+                        let end = captures.iter().map(|(_, range)| range.end).max()?;
+                        Some(start..end)
+                    });
+                    if let Some(match_range) = match_range {
+                        if range.start <= match_range.start && match_range.end <= range.end {
+                            captures.sort_by_key(|(_, capture_range)| {
+                                (capture_range.start, std::cmp::Reverse(capture_range.end))
+                            });
+                            matches.push(StructuralMatch {
+                                range: match_range,
+                                captures,
+                            });
+                        }
+                    }
+                }
+            }
+            Self::Pattern(pattern) => {
+                let mut cursor = root.walk();
+                loop {
+                    let node = cursor.node();
+                    let node_range = node.byte_range();
+                    let mut descend =
+                        node_range.start <= range.end && range.start <= node_range.end;
+                    if descend && range.start <= node_range.start && node_range.end <= range.end {
+                        let mut captures = Vec::new();
+                        if pattern.matches(node, text, &mut captures) {
+                            matches.push(StructuralMatch {
+                                range: node_range,
+                                captures,
+                            });
+                            descend = false;
+                        }
+                    }
+
+                    if descend && cursor.goto_first_child() {
+                        continue;
+                    }
+                    while !cursor.goto_next_sibling() {
+                        if !cursor.goto_parent() {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl PatternNode {
+    fn new(node: Node, pattern: &str) -> Self {
+        let text = &pattern[node.byte_range()];
+        for (prefix, multiple) in [
+            (MULTIPLE_METAVARIABLE_PREFIX, true),
+            (METAVARIABLE_PREFIX, false),
+        ] {
+            if let Some(name) = text.strip_prefix(prefix) {
+                if name.chars().all(is_name_char) {
+                    return Self::MetaVariable {
+                        name: metavariable_name(name),
+                        multiple,
+                    };
+                }
+            }
+        }
+
+        Self::Node {
+            kind_id: node.kind_id(),
+            text: (node.child_count() == 0).then(|| text.to_string()),
+            children: significant_children(node)
+                .into_iter()
+                .map(|child| Self::new(child, pattern))
+                .collect(),
+        }
+    }
+
+    fn matches(
+        &self,
+        node: Node,
+        text: &str,
+        captures: &mut Vec<(Arc<str>, Range<usize>)>,
+    ) -> bool {
+        match self {
+            Self::MetaVariable { name, .. } => bind(name, node.byte_range(), text, captures),
+            Self::Node {
+                kind_id,
+                text: pattern_text,
+                children,
+            } => {
+                if node.kind_id() != *kind_id {
+                    return false;
+                }
+                if let Some(pattern_text) = pattern_text {
+                    return text[node.byte_range()] == **pattern_text;
+                }
+                match_sequence(children, &significant_children(node), text, captures)
+            }
+        }
+    }
+}
+
+fn match_sequence(
+    patterns: &[PatternNode],
+    nodes: &[Node],
+    text: &str,
+    captures: &mut Vec<(Arc<str>, Range<usize>)>,
+) -> bool {
+    let Some((pattern, rest)) = patterns.split_first() else {
+        return nodes.is_empty();
+    };
+
+    if let PatternNode::MetaVariable {
+        name,
+        multiple: true,
+    } = pattern
+    {
+        for count in 0..=nodes.len() {
+            let captures_len = captures.len();
+            let range = match (nodes[..count].first(), nodes[..count].last()) {
+                (Some(first), Some(last)) => first.start_byte()..last.end_byte(),
+                _ => {
+                    let offset = nodes.first().map_or(0, |node| node.start_byte());
+                    offset..offset
+                }
+            };
+            if bind(name, range, text, captures)
+                && match_sequence(rest, &nodes[count..], text, captures)
+            {
+                return true;
+            }
+            captures.truncate(captures_len);
+        }
+        return false;
+    }
+
+    let Some((node, nodes)) = nodes.split_first() else {
+        return false;
+    };
+    let captures_len = captures.len();
+    if pattern.matches(*node, text, captures) && match_sequence(rest, nodes, text, captures) {
+        return true;
+    }
+    captures.truncate(captures_len);
+    false
+}
+
+/// Captures the range for the metavariable, unless it already captured different text.
+fn bind(
+    name: &Option<Arc<str>>,
+    range: Range<usize>,
+    text: &str,
+    captures: &mut Vec<(Arc<str>, Range<usize>)>,
+) -> bool {
+    let Some(name) = name else {
+        return true;
+    };
+    if let Some((_, captured_range)) = captures
+        .iter()
+        .find(|(captured_name, _)| captured_name == name)
+    {
+        return text[captured_range.clone()] == text[range];
+    }
+    captures.push((name.clone(), range));
+    true
+}
+
+/// Returns the children of the node, without comments and missing nodes inserted by error recovery.
+fn significant_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| !child.is_extra() && !child.is_missing())
+        .collect()
+}
+
+fn has_error_node(node: Node) -> bool {
+    if node.is_error() {
+        return true;
+    }
+    if !node.has_error() {
+        return false;
+    }
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(has_error_node)
+}
+
+fn is_tree_sitter_query(source: &str) -> bool {
+    source.starts_with('(') || source.starts_with('[')
+}
+
+/// Returns the strings that `#eq?` predicates compare captures to, which every match contains
+/// when the query consists of a single pattern and the predicates are outside of alternations.
+fn tree_sitter_query_literals(source: &str) -> Vec<String> {
+    enum Token<'a> {
+        Open(char),
+        Close,
+        String(String),
+        Word(&'a str),
+    }
+
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            '(' | '[' => tokens.push(Token::Open(c)),
+            ')' | ']' => tokens.push(Token::Close),
+            ';' => while chars.next_if(|(_, c)| *c != '\n').is_some() {},
+            '"' => {
+                let mut string = String::new();
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some((_, 'n')) => string.push('\n'),
+                            Some((_, 't')) => string.push('\t'),
+                            Some((_, c)) => string.push(c),
+                            None => {}
+                        },
+                        c => string.push(c),
+                    }
+                }
+                tokens.push(Token::String(string));
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                let mut end = ix + c.len_utf8();
+                while let Some((ix, c)) = chars.next_if(|(_, c)| {
+                    !c.is_whitespace() && !matches!(c, '(' | ')' | '[' | ']' | '"' | ';')
+                }) {
+                    end = ix + c.len_utf8();
+                }
+                tokens.push(Token::Word(&source[ix..end]));
+            }
+        }
+    }
+
+    let mut literals = Vec::new();
+    let mut patterns = 0;
+    let mut open = Vec::new();
+    for (ix, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Open(bracket) => {
+                if open.is_empty() {
+                    patterns += 1;
+                }
+                open.push(*bracket);
+                if let [
+                    Token::Word("#eq?"),
+                    Token::Word(capture),
+                    Token::String(literal),
+                    Token::Close,
+                ] = &tokens[ix + 1..(ix + 5).min(tokens.len())]
+                {
+                    if capture.starts_with('@') && !literal.is_empty() && !open.contains(&'[') {
+                        literals.push(literal.clone());
+                    }
+                }
+            }
+            Token::Close => {
+                open.pop();
+            }
+            Token::String(_) | Token::Word("_") if open.is_empty() => patterns += 1,
+            Token::String(_) | Token::Word(_) => {}
+        }
+    }
+    if patterns == 1 { literals } else { Vec::new() }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn is_metavariable_name_char(c: char) -> bool {
+    c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'
+}
+
+fn metavariable_name(name: &str) -> Option<Arc<str>> {
+    (!name.is_empty() && name != "_").then(|| name.into())
+}
+
+/// Replaces the `$NAME` and `$$$NAME` metavariables in the source using the given function,
+/// which is called with whether the metavariable matches multiple nodes, and its name.
+fn replace_metavariables(source: &str, mut replace: impl FnMut(&mut String, bool, &str)) -> String {
+    let mut replaced = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(dollar_ix) = rest.find('$') {
+        replaced.push_str(&rest[..dollar_ix]);
+        let reference = &rest[dollar_ix..];
+        let multiple = reference.starts_with("$$$");
+        let name_start = if multiple { 3 } else { 1 };
+        let name_len = reference[name_start..]
+            .find(|c: char| !is_metavariable_name_char(c))
+            .unwrap_or(reference.len() - name_start);
+        if name_len == 0 && !multiple {
+            replaced.push('$');
+            rest = &reference[1..];
+            continue;
+        }
+        replace(
+            &mut replaced,
+            multiple,
+            &reference[name_start..name_start + name_len],
+        );
+        rest = &reference[name_start + name_len..];
+    }
+    replaced.push_str(rest);
+    replaced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Buffer, LanguageConfig};
+    use gpui::{App, AppContext as _};
+
+    fn rust_lang() -> Arc<Language> {
+        Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        ))
+    }
+
+    fn find_matches(text: &str, query: &str, cx: &mut App) -> Vec<(String, Vec<(String, String)>)> {
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(rust_lang(), cx));
+        let snapshot = buffer.read(cx).snapshot();
+        let query = StructuralQuery::new(query).unwrap();
+        query
+            .find_matches(&snapshot, 0..snapshot.len())
+            .into_iter()
+            .map(|mat| {
+                (
+                    text[mat.range.clone()].to_string(),
+                    mat.captures
+                        .iter()
+                        .map(|(name, range)| (name.to_string(), text[range.clone()].to_string()))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[gpui::test]
+    fn test_pattern_matches(cx: &mut App) {
+        let text = "
+            fn main() {
+                foo(1, bar(2));
+                foo(x);
+                baz(3);
+                assert_eq!(a, a);
+            }
+        ";
+
+        assert_eq!(
+            find_matches(text, "foo($A)", cx),
+            vec![(
+                "foo(x)".to_string(),
+                vec![("A".to_string(), "x".to_string())]
+            )]
+        );
+        assert_eq!(
+            find_matches(text, "foo($$$ARGS)", cx),
+            vec![
+                (
+                    "foo(1, bar(2))".to_string(),
+                    vec![("ARGS".to_string(), "1, bar(2)".to_string())]
+                ),
+                (
+                    "foo(x)".to_string(),
+                    vec![("ARGS".to_string(), "x".to_string())]
+                ),
+            ]
+        );
+        assert_eq!(
+            find_matches(text, "$F($_, bar($B))", cx),
+            vec![(
+                "foo(1, bar(2))".to_string(),
+                vec![
+                    ("F".to_string(), "foo".to_string()),
+                    ("B".to_string(), "2".to_string())
+                ]
+            )]
+        );
+        assert!(find_matches(text, "qux($A)", cx).is_empty());
+    }
+
+    #[gpui::test]
+    fn test_tree_sitter_query_matches(cx: &mut App) {
+        let text = "fn one() {} fn two() {}";
+
+        assert_eq!(
+            find_matches(text, "(function_item name: (identifier) @name) @match", cx),
+            vec![
+                (
+                    "fn one() {}".to_string(),
+                    vec![
+                        ("match".to_string(), "fn one() {}".to_string()),
+                        ("name".to_string(), "one".to_string())
+                    ]
+                ),
+                (
+                    "fn two() {}".to_string(),
+                    vec![
+                        ("match".to_string(), "fn two() {}".to_string()),
+                        ("name".to_string(), "two".to_string())
+                    ]
+                ),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    fn test_expand_template(cx: &mut App) {
+        let text = "fn main() { foo(1, 2); }";
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(rust_lang(), cx));
+        let snapshot = buffer.read(cx).snapshot();
+        let query = StructuralQuery::new("foo($A, $B)").unwrap();
+        let mat = query
+            .match_at(
+                &snapshot,
+                text.find("foo").unwrap()..text.find(';').unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            mat.expand_template("bar($B, $A, $C, $$)", &snapshot),
+            "bar(2, 1, $C, $$)"
+        );
+    }
+
+    #[gpui::test]
+    fn test_matches_at(cx: &mut App) {
+        let text = "fn main() { foo(1); foo(2); bar(3); }";
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(rust_lang(), cx));
+        let snapshot = buffer.read(cx).snapshot();
+        let query = StructuralQuery::new("$F($A)").unwrap();
+        let range_of = |call: &str| {
+            let start = text.find(call).unwrap();
+            start..start + call.len()
+        };
+
+        let matches = query.matches_at(
+            &snapshot,
+            &[range_of("foo(2)"), range_of("1); foo"), range_of("bar(3)")],
+        );
+        assert_eq!(
+            matches
+                .iter()
+                .map(|mat| Some(mat.as_ref()?.expand_template("$A", &snapshot)))
+                .collect::<Vec<_>>(),
+            vec![Some("2".to_string()), None, Some("3".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_query_validation() {
+        assert!(StructuralQuery::new("  ").is_err());
+        assert!(StructuralQuery::new("$A").is_err());
+        assert!(StructuralQuery::new("$$$ARGS").is_err());
+        assert!(StructuralQuery::new("foo($A)").is_ok());
+
+        let query = StructuralQuery::new("foo($A, bar)").unwrap();
+        assert!(query.may_match("foo(1, bar)"));
+        assert!(!query.may_match("foo(1, baz)"));
+
+        let query =
+            StructuralQuery::new(r#"((identifier) @name (#eq? @name "foo")) ; "bar""#).unwrap();
+        assert!(query.may_match("let foo = 1;"));
+        assert!(!query.may_match("let bar = 1;"));
+
+        // Literals of alternatives, or of one of several patterns, don't have to occur.
+        let query = StructuralQuery::new(
+            r#"[((identifier) @name (#eq? @name "foo")) ((identifier) @name (#eq? @name "bar"))]"#,
+        )
+        .unwrap();
+        assert!(query.may_match("let bar = 1;"));
+        let query =
+            StructuralQuery::new(r#"((identifier) @name (#eq? @name "foo")) (string_literal)"#)
+                .unwrap();
+        assert!(query.may_match("let bar = 1;"));
+    }
+}
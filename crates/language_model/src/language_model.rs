@@ -8,11 +8,13 @@ mod telemetry;
 #[cfg(any(test, feature = "test-support"))]
 pub mod fake_provider;
 
+use anthropic::{AnthropicError, ApiErrorCode};
 use anyhow::{Context as _, Result};
 use client::Client;
 use futures::FutureExt;
 use futures::{StreamExt, future::BoxFuture, stream::BoxStream};
 use gpui::{AnyElement, AnyView, App, AsyncApp, SharedString, Task, Window};
+use http_client::StatusError;
 use http_client::http::{HeaderMap, HeaderValue};
 use icons::IconName;
 use parking_lot::Mutex;
@@ -20,9 +22,11 @@ use proto::Plan;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::fmt;
+use std::io;
 use std::ops::{Add, Sub};
 use std::str::FromStr as _;
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use util::serde::is_default;
 use zed_llm_client::{
@@ -91,8 +95,87 @@ pub enum LanguageModelCompletionError {
         raw_input: Arc<str>,
         json_parse_error: String,
     },
+    #[error("rate limit exceeded")]
+    RateLimitExceeded { retry_after: Option<Duration> },
+    #[error("the provider is overloaded")]
+    Overloaded { retry_after: Option<Duration> },
+    #[error("the connection to the provider was lost: {0}")]
+    ConnectionLost(anyhow::Error),
     #[error(transparent)]
-    Other(#[from] anyhow::Error),
+    Other(anyhow::Error),
+}
+
+impl LanguageModelCompletionError {
+    /// The delay the provider asked to wait before sending the request again.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimitExceeded { retry_after } | Self::Overloaded { retry_after } => {
+                *retry_after
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the error is transient, so that sending the same request again may succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::RateLimitExceeded { .. } | Self::Overloaded { .. } | Self::ConnectionLost(_)
+        )
+    }
+
+    pub fn into_anyhow(self) -> anyhow::Error {
+        match self {
+            Self::Other(error) => error,
+            error => anyhow::Error::new(error),
+        }
+    }
+}
+
+impl From<anyhow::Error> for LanguageModelCompletionError {
+    /// Recognizes the transient failures reported by providers, so that they can be retried.
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<Self>() {
+            Ok(error) => return error,
+            Err(error) => error,
+        };
+
+        for cause in error.chain() {
+            if let Some(status_error) = cause.downcast_ref::<StatusError>() {
+                let retry_after = status_error.retry_after;
+                match status_error.status.as_u16() {
+                    429 => return Self::RateLimitExceeded { retry_after },
+                    500 | 502 | 503 | 504 | 529 => return Self::Overloaded { retry_after },
+                    _ => {}
+                }
+            } else if let Some(AnthropicError::ApiError(api_error)) =
+                cause.downcast_ref::<AnthropicError>()
+            {
+                match api_error.code() {
+                    Some(ApiErrorCode::RateLimitError) => {
+                        return Self::RateLimitExceeded { retry_after: None };
+                    }
+                    Some(ApiErrorCode::OverloadedError | ApiErrorCode::ApiError) => {
+                        return Self::Overloaded { retry_after: None };
+                    }
+                    _ => {}
+                }
+            } else if let Some(io_error) = cause.downcast_ref::<io::Error>() {
+                if matches!(
+                    io_error.kind(),
+                    io::ErrorKind::ConnectionReset
+                        | io::ErrorKind::ConnectionAborted
+                        | io::ErrorKind::TimedOut
+                        | io::ErrorKind::UnexpectedEof
+                        | io::ErrorKind::BrokenPipe
+                ) {
+                    return Self::ConnectionLost(error);
+                }
+            }
+        }
+
+        Self::Other(error)
+    }
 }
 
 /// Indicates the format used to define the input schema for a language model tool.
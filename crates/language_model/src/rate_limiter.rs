@@ -1,3 +1,4 @@
+use crate::LanguageModelCompletionError;
 use anyhow::Result;
 use futures::Stream;
 use smol::lock::{Semaphore, SemaphoreGuardArc};
@@ -6,6 +7,7 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 
 #[derive(Clone)]
@@ -68,3 +70,130 @@ impl RateLimiter {
         }
     }
 }
+
+/// How often, and after how long, a completion that failed with a transient error is sent again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The number of attempts, including the first one.
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_delay: Duration::from_secs(2),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns how long to wait before retrying after `attempt` (starting at 1) failed with
+    /// `error`, or `None` when the error isn't transient or the attempts are used up.
+    ///
+    /// The delay the provider asked for is honored when there is one; otherwise it doubles
+    /// with every attempt.
+    pub fn retry_delay(
+        &self,
+        error: &LanguageModelCompletionError,
+        attempt: u32,
+    ) -> Option<Duration> {
+        if !error.is_retryable() || attempt >= self.max_attempts {
+            return None;
+        }
+
+        let delay = error.retry_after().unwrap_or_else(|| {
+            self.initial_delay
+                .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        });
+        Some(delay.min(self.max_delay))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy::default();
+        let overloaded = LanguageModelCompletionError::Overloaded { retry_after: None };
+        assert_eq!(
+            policy.retry_delay(&overloaded, 1),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            policy.retry_delay(&overloaded, 2),
+            Some(Duration::from_secs(4))
+        );
+        assert_eq!(
+            policy.retry_delay(&overloaded, 3),
+            Some(Duration::from_secs(8))
+        );
+        assert_eq!(policy.retry_delay(&overloaded, 4), None);
+
+        let rate_limited = LanguageModelCompletionError::RateLimitExceeded {
+            retry_after: Some(Duration::from_secs(30)),
+        };
+        assert_eq!(
+            policy.retry_delay(&rate_limited, 1),
+            Some(Duration::from_secs(30))
+        );
+        let rate_limited = LanguageModelCompletionError::RateLimitExceeded {
+            retry_after: Some(Duration::from_secs(600)),
+        };
+        assert_eq!(policy.retry_delay(&rate_limited, 1), Some(policy.max_delay));
+
+        let other = LanguageModelCompletionError::Other(anyhow!("invalid request"));
+        assert_eq!(policy.retry_delay(&other, 1), None);
+    }
+
+    #[test]
+    fn test_classify_completion_errors() {
+        let error = LanguageModelCompletionError::from(anyhow!(http_client::StatusError::new(
+            http_client::StatusCode::TOO_MANY_REQUESTS,
+            &http_client::http::HeaderMap::new(),
+            "rate limited",
+        )));
+        assert!(matches!(
+            error,
+            LanguageModelCompletionError::RateLimitExceeded { .. }
+        ));
+
+        let error = LanguageModelCompletionError::from(
+            anyhow!(http_client::StatusError::new(
+                http_client::StatusCode::BAD_GATEWAY,
+                &http_client::http::HeaderMap::new(),
+                "bad gateway",
+            ))
+            .context("failed to stream completion"),
+        );
+        assert!(matches!(
+            error,
+            LanguageModelCompletionError::Overloaded { .. }
+        ));
+
+        let error = LanguageModelCompletionError::from(anyhow!(
+            anthropic::AnthropicError::ApiError(anthropic::ApiError {
+                error_type: "overloaded_error".into(),
+                message: "Overloaded".into(),
+            })
+        ));
+        assert!(error.is_retryable());
+
+        let error = LanguageModelCompletionError::from(anyhow!(std::io::Error::from(
+            std::io::ErrorKind::ConnectionReset
+        )));
+        assert!(matches!(
+            error,
+            LanguageModelCompletionError::ConnectionLost(_)
+        ));
+
+        let error = LanguageModelCompletionError::from(anyhow!("prompt is too long"));
+        assert!(!error.is_retryable());
+    }
+}
@@ -287,6 +287,108 @@ pub struct LanguageModelRequest {
     pub tool_choice: Option<LanguageModelToolChoice>,
     pub stop: Vec<String>,
     pub temperature: Option<f32>,
+    /// The maximum number of tokens to generate, capped by the model's own limit.
+    pub max_tokens: Option<u32>,
+    /// Constrains the response to JSON. Providers that cannot enforce it are instructed to follow it.
+    pub response_format: Option<LanguageModelResponseFormat>,
+    /// How much the model reasons before answering. Ignored by the models that cannot reason.
+    pub reasoning: Option<LanguageModelReasoning>,
+}
+
+impl LanguageModelRequest {
+    /// The maximum number of tokens to generate, given the model's own limit.
+    pub fn max_tokens_within(&self, model_limit: Option<u32>) -> Option<u32> {
+        match (self.max_tokens, model_limit) {
+            (Some(max_tokens), Some(model_limit)) => Some(max_tokens.min(model_limit)),
+            (max_tokens, model_limit) => max_tokens.or(model_limit),
+        }
+    }
+
+    /// Replaces the response format with instructions to follow it, for the providers that cannot constrain their output.
+    pub fn inline_response_format(&mut self) {
+        if let Some(response_format) = self.response_format.take() {
+            self.messages.push(LanguageModelRequestMessage {
+                role: Role::System,
+                content: vec![MessageContent::Text(response_format.instructions())],
+                cache: false,
+            });
+        }
+    }
+}
+
+/// The format of the model's response.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LanguageModelResponseFormat {
+    /// Any JSON object.
+    JsonObject,
+    /// A JSON value matching the schema. Like the tools' input schemas, the schema
+    /// should be in the model's [`crate::LanguageModelToolSchemaFormat`].
+    JsonSchema {
+        name: String,
+        schema: serde_json::Value,
+    },
+}
+
+impl LanguageModelResponseFormat {
+    /// Instructions describing the format, for the providers that cannot constrain their output.
+    pub fn instructions(&self) -> String {
+        match self {
+            Self::JsonObject => {
+                "Respond with a single JSON object, without any surrounding text or code fences."
+                    .to_string()
+            }
+            Self::JsonSchema { schema, .. } => format!(
+                "Respond with a single JSON value matching the following JSON schema, \
+                without any surrounding text or code fences:\n\n{}",
+                serde_json::to_string_pretty(schema).unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// How much a model reasons before answering.
+///
+/// Providers take either an effort or a budget of tokens, so each is converted to the other when needed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LanguageModelReasoning {
+    Effort(ReasoningEffort),
+    BudgetTokens(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasoningEffort {
+    Low,
+    Medium,
+    High,
+}
+
+impl LanguageModelReasoning {
+    const LOW_BUDGET_TOKENS: u32 = 2_048;
+    const MEDIUM_BUDGET_TOKENS: u32 = 8_192;
+    const HIGH_BUDGET_TOKENS: u32 = 24_576;
+
+    pub fn effort(&self) -> ReasoningEffort {
+        match *self {
+            Self::Effort(effort) => effort,
+            Self::BudgetTokens(tokens) if tokens <= Self::LOW_BUDGET_TOKENS => ReasoningEffort::Low,
+            Self::BudgetTokens(tokens) if tokens <= Self::MEDIUM_BUDGET_TOKENS => {
+                ReasoningEffort::Medium
+            }
+            Self::BudgetTokens(_) => ReasoningEffort::High,
+        }
+    }
+
+    pub fn budget_tokens(&self) -> u32 {
+        match *self {
+            Self::Effort(ReasoningEffort::Low) => Self::LOW_BUDGET_TOKENS,
+            Self::Effort(ReasoningEffort::Medium) => Self::MEDIUM_BUDGET_TOKENS,
+            Self::Effort(ReasoningEffort::High) => Self::HIGH_BUDGET_TOKENS,
+            Self::BudgetTokens(tokens) => tokens,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
@@ -0,0 +1,648 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use credentials_provider::CredentialsProvider;
+use editor::{Editor, EditorElement, EditorStyle};
+use extension::{
+    Extension, LanguageModelProviderManifestEntry, LlmCompletionEvent, LlmCompletionRequest,
+    LlmMessageContent, LlmMessageRole, LlmModelInfo, LlmRequestMessage, LlmStopReason, LlmThinking,
+    LlmToolChoice, LlmToolDefinition, LlmToolResult, LlmToolResultContent, LlmToolUse,
+};
+use futures::{FutureExt, StreamExt, future::BoxFuture, stream::BoxStream};
+use gpui::{
+    AnyView, App, AppContext as _, AsyncApp, Entity, FontStyle, Task, TextStyle, WhiteSpace,
+};
+use language_model::{
+    AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
+    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
+    LanguageModelToolChoice, LanguageModelToolResultContent, LanguageModelToolUse, MessageContent,
+    RateLimiter, Role, StopReason, TokenUsage,
+};
+use settings::Settings;
+use theme::ThemeSettings;
+use ui::{Icon, IconName, prelude::*};
+use util::ResultExt;
+
+/// Returns the ID of the provider in the [`LanguageModelRegistry`](language_model::LanguageModelRegistry).
+///
+/// Extensions only need their providers' IDs to be unique among their own, so
+/// they are qualified with the ID of the extension.
+pub(crate) fn registry_provider_id(
+    extension_id: &str,
+    provider_id: &str,
+) -> LanguageModelProviderId {
+    LanguageModelProviderId(format!("{extension_id}.{provider_id}").into())
+}
+
+/// A language model provider implemented by an extension.
+pub(crate) struct ExtensionLanguageModelProvider {
+    id: LanguageModelProviderId,
+    name: LanguageModelProviderName,
+    state: Entity<State>,
+}
+
+struct State {
+    extension: Arc<dyn Extension>,
+    provider_id: Arc<str>,
+    api_key_url: Option<String>,
+    credentials_url: String,
+    authenticated: bool,
+    models: Vec<LlmModelInfo>,
+}
+
+impl State {
+    fn is_authenticated(&self) -> bool {
+        self.authenticated
+    }
+
+    fn authenticate(&self, cx: &mut Context<Self>) -> Task<Result<(), AuthenticateError>> {
+        if self.is_authenticated() {
+            return Task::ready(Ok(()));
+        }
+
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let credentials_url = self.credentials_url.clone();
+        let extension = self.extension.clone();
+        let provider_id = self.provider_id.clone();
+        cx.spawn(async move |this, cx| {
+            let api_key = match credentials_provider
+                .read_credentials(&credentials_url, &cx)
+                .await?
+            {
+                Some((_, api_key)) => Some(String::from_utf8(api_key).context("invalid API key")?),
+                None => None,
+            };
+
+            let has_api_key = api_key.is_some();
+            if let Err(error) = extension
+                .llm_provider_authenticate(provider_id.clone(), api_key)
+                .await
+            {
+                return Err(if has_api_key {
+                    AuthenticateError::Other(error)
+                } else {
+                    AuthenticateError::CredentialsNotFound
+                });
+            }
+            let models = extension.llm_provider_models(provider_id).await?;
+
+            this.update(cx, |this, cx| {
+                this.authenticated = true;
+                this.models = models;
+                cx.notify();
+            })?;
+
+            Ok(())
+        })
+    }
+
+    fn set_api_key(&mut self, api_key: String, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let credentials_url = self.credentials_url.clone();
+        let extension = self.extension.clone();
+        let provider_id = self.provider_id.clone();
+        cx.spawn(async move |this, cx| {
+            extension
+                .llm_provider_authenticate(provider_id.clone(), Some(api_key.clone()))
+                .await?;
+            credentials_provider
+                .write_credentials(&credentials_url, "Bearer", api_key.as_bytes(), &cx)
+                .await?;
+            let models = extension.llm_provider_models(provider_id).await?;
+
+            this.update(cx, |this, cx| {
+                this.authenticated = true;
+                this.models = models;
+                cx.notify();
+            })
+        })
+    }
+
+    fn reset_api_key(&self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let credentials_url = self.credentials_url.clone();
+        let extension = self.extension.clone();
+        let provider_id = self.provider_id.clone();
+        cx.spawn(async move |this, cx| {
+            credentials_provider
+                .delete_credentials(&credentials_url, &cx)
+                .await
+                .log_err();
+            extension
+                .llm_provider_authenticate(provider_id, None)
+                .await
+                .ok();
+
+            this.update(cx, |this, cx| {
+                this.authenticated = false;
+                this.models.clear();
+                cx.notify();
+            })
+        })
+    }
+}
+
+impl ExtensionLanguageModelProvider {
+    pub(crate) fn new(
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        entry: LanguageModelProviderManifestEntry,
+        cx: &mut App,
+    ) -> Self {
+        let extension_id = extension.manifest().id.clone();
+        let state = cx.new(|_| State {
+            extension,
+            provider_id: provider_id.clone(),
+            api_key_url: entry.api_key_url,
+            credentials_url: format!("extension://{extension_id}/{provider_id}"),
+            authenticated: false,
+            models: Vec::new(),
+        });
+
+        Self {
+            id: registry_provider_id(&extension_id, &provider_id),
+            name: LanguageModelProviderName(entry.name.into()),
+            state,
+        }
+    }
+
+    fn create_language_model(&self, model: LlmModelInfo, cx: &App) -> Arc<dyn LanguageModel> {
+        let state = self.state.read(cx);
+        Arc::new(ExtensionLanguageModel {
+            id: LanguageModelId::from(model.id.clone()),
+            model,
+            extension: state.extension.clone(),
+            provider_id: self.id.clone(),
+            provider_name: self.name.clone(),
+            extension_provider_id: state.provider_id.clone(),
+            request_limiter: RateLimiter::new(4),
+        })
+    }
+}
+
+impl LanguageModelProviderState for ExtensionLanguageModelProvider {
+    type ObservableEntity = State;
+
+    fn observable_entity(&self) -> Option<Entity<Self::ObservableEntity>> {
+        Some(self.state.clone())
+    }
+}
+
+impl LanguageModelProvider for ExtensionLanguageModelProvider {
+    fn id(&self) -> LanguageModelProviderId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelProviderName {
+        self.name.clone()
+    }
+
+    fn default_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        let models = &self.state.read(cx).models;
+        let model = models
+            .iter()
+            .find(|model| model.is_default)
+            .or_else(|| models.first())?;
+        Some(self.create_language_model(model.clone(), cx))
+    }
+
+    fn default_fast_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        let model = self
+            .state
+            .read(cx)
+            .models
+            .iter()
+            .find(|model| model.is_default_fast)?;
+        Some(self.create_language_model(model.clone(), cx))
+    }
+
+    fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
+        self.state
+            .read(cx)
+            .models
+            .iter()
+            .map(|model| self.create_language_model(model.clone(), cx))
+            .collect()
+    }
+
+    fn is_authenticated(&self, cx: &App) -> bool {
+        self.state.read(cx).is_authenticated()
+    }
+
+    fn authenticate(&self, cx: &mut App) -> Task<Result<(), AuthenticateError>> {
+        self.state.update(cx, |state, cx| state.authenticate(cx))
+    }
+
+    fn configuration_view(&self, window: &mut Window, cx: &mut App) -> AnyView {
+        let provider_name = self.name.0.clone();
+        cx.new(|cx| ConfigurationView::new(self.state.clone(), provider_name, window, cx))
+            .into()
+    }
+
+    fn reset_credentials(&self, cx: &mut App) -> Task<Result<()>> {
+        self.state.update(cx, |state, cx| state.reset_api_key(cx))
+    }
+}
+
+/// A language model offered by a provider implemented by an extension.
+struct ExtensionLanguageModel {
+    id: LanguageModelId,
+    model: LlmModelInfo,
+    extension: Arc<dyn Extension>,
+    provider_id: LanguageModelProviderId,
+    provider_name: LanguageModelProviderName,
+    extension_provider_id: Arc<str>,
+    request_limiter: RateLimiter,
+}
+
+impl LanguageModel for ExtensionLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(self.model.name.clone())
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        self.provider_id.clone()
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        self.provider_name.clone()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.model.supports_tools
+    }
+
+    fn supports_tool_choice(&self, _choice: LanguageModelToolChoice) -> bool {
+        self.model.supports_tools
+    }
+
+    fn supports_images(&self) -> bool {
+        self.model.supports_images
+    }
+
+    fn telemetry_id(&self) -> String {
+        format!("{}/{}", self.provider_id.0, self.model.id)
+    }
+
+    fn max_token_count(&self) -> usize {
+        self.model.max_token_count as usize
+    }
+
+    fn max_output_tokens(&self) -> Option<u32> {
+        self.model.max_output_tokens
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        _cx: &App,
+    ) -> BoxFuture<'static, Result<usize>> {
+        let extension = self.extension.clone();
+        let provider_id = self.extension_provider_id.clone();
+        let model_id: Arc<str> = self.model.id.as_str().into();
+        let request = into_llm_completion_request(request, self.max_output_tokens());
+        async move {
+            let token_count = extension
+                .llm_count_tokens(provider_id, model_id, request)
+                .await?;
+            Ok(token_count as usize)
+        }
+        .boxed()
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        _cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+        >,
+    > {
+        let extension = self.extension.clone();
+        let provider_id = self.extension_provider_id.clone();
+        let model_id: Arc<str> = self.model.id.as_str().into();
+        let request = into_llm_completion_request(request, self.max_output_tokens());
+        let future = self.request_limiter.stream(async move {
+            extension
+                .llm_stream_completion(provider_id, model_id, request)
+                .await
+        });
+
+        async move {
+            Ok(future
+                .await?
+                .map(|event| {
+                    event
+                        .map_err(LanguageModelCompletionError::from)
+                        .and_then(into_language_model_completion_event)
+                })
+                .boxed())
+        }
+        .boxed()
+    }
+}
+
+fn into_llm_completion_request(
+    mut request: LanguageModelRequest,
+    max_output_tokens: Option<u32>,
+) -> LlmCompletionRequest {
+    request.inline_response_format();
+    let max_tokens = request.max_tokens_within(max_output_tokens);
+
+    LlmCompletionRequest {
+        messages: request
+            .messages
+            .into_iter()
+            .map(|message| LlmRequestMessage {
+                role: match message.role {
+                    Role::User => LlmMessageRole::User,
+                    Role::Assistant => LlmMessageRole::Assistant,
+                    Role::System => LlmMessageRole::System,
+                },
+                content: message
+                    .content
+                    .into_iter()
+                    .filter_map(|content| match content {
+                        MessageContent::Text(text) => Some(LlmMessageContent::Text(text)),
+                        MessageContent::Thinking { text, signature } => {
+                            Some(LlmMessageContent::Thinking(LlmThinking { text, signature }))
+                        }
+                        MessageContent::RedactedThinking(_) => None,
+                        MessageContent::Image(image) => {
+                            Some(LlmMessageContent::Image(image.source.to_string()))
+                        }
+                        MessageContent::ToolUse(tool_use) => {
+                            Some(LlmMessageContent::ToolUse(LlmToolUse {
+                                id: tool_use.id.to_string(),
+                                name: tool_use.name.to_string(),
+                                input: tool_use.input.to_string(),
+                            }))
+                        }
+                        MessageContent::ToolResult(tool_result) => {
+                            Some(LlmMessageContent::ToolResult(LlmToolResult {
+                                tool_use_id: tool_result.tool_use_id.to_string(),
+                                tool_name: tool_result.tool_name.to_string(),
+                                is_error: tool_result.is_error,
+                                content: match tool_result.content {
+                                    LanguageModelToolResultContent::Image(image) => {
+                                        LlmToolResultContent::Image(image.source.to_string())
+                                    }
+                                    content => LlmToolResultContent::Text(
+                                        content.to_str().unwrap_or_default().to_string(),
+                                    ),
+                                },
+                            }))
+                        }
+                    })
+                    .collect(),
+                cache: message.cache,
+            })
+            .collect(),
+        tools: request
+            .tools
+            .into_iter()
+            .map(|tool| LlmToolDefinition {
+                name: tool.name,
+                description: tool.description,
+                input_schema: tool.input_schema.to_string(),
+            })
+            .collect(),
+        tool_choice: request.tool_choice.map(|choice| match choice {
+            LanguageModelToolChoice::Auto => LlmToolChoice::Auto,
+            LanguageModelToolChoice::Any => LlmToolChoice::Any,
+            LanguageModelToolChoice::None => LlmToolChoice::None,
+        }),
+        stop_sequences: request.stop,
+        temperature: request.temperature,
+        max_tokens,
+    }
+}
+
+fn into_language_model_completion_event(
+    event: LlmCompletionEvent,
+) -> Result<LanguageModelCompletionEvent, LanguageModelCompletionError> {
+    Ok(match event {
+        LlmCompletionEvent::Text(text) => LanguageModelCompletionEvent::Text(text),
+        LlmCompletionEvent::Thinking(LlmThinking { text, signature }) => {
+            LanguageModelCompletionEvent::Thinking { text, signature }
+        }
+        LlmCompletionEvent::ToolUse(tool_use) => match serde_json::from_str(&tool_use.input) {
+            Ok(input) => LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+                id: tool_use.id.into(),
+                name: tool_use.name.into(),
+                raw_input: tool_use.input,
+                input,
+                is_input_complete: true,
+            }),
+            Err(error) => {
+                return Err(LanguageModelCompletionError::BadInputJson {
+                    id: tool_use.id.into(),
+                    tool_name: tool_use.name.into(),
+                    raw_input: tool_use.input.into(),
+                    json_parse_error: error.to_string(),
+                });
+            }
+        },
+        LlmCompletionEvent::Stop(stop_reason) => {
+            LanguageModelCompletionEvent::Stop(match stop_reason {
+                LlmStopReason::EndTurn => StopReason::EndTurn,
+                LlmStopReason::MaxTokens => StopReason::MaxTokens,
+                LlmStopReason::ToolUse => StopReason::ToolUse,
+            })
+        }
+        LlmCompletionEvent::Usage(usage) => LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_creation_input_tokens: usage.cache_creation_input_tokens,
+            cache_read_input_tokens: usage.cache_read_input_tokens,
+        }),
+    })
+}
+
+struct ConfigurationView {
+    api_key_editor: Entity<Editor>,
+    state: Entity<State>,
+    provider_name: SharedString,
+    load_credentials_task: Option<Task<()>>,
+}
+
+impl ConfigurationView {
+    fn new(
+        state: Entity<State>,
+        provider_name: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let api_key_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("API key", cx);
+            editor
+        });
+
+        cx.observe(&state, |_, _, cx| {
+            cx.notify();
+        })
+        .detach();
+
+        let load_credentials_task = Some(cx.spawn({
+            let state = state.clone();
+            async move |this, cx| {
+                if let Some(task) = state
+                    .update(cx, |state, cx| state.authenticate(cx))
+                    .log_err()
+                {
+                    let _ = task.await;
+                }
+
+                this.update(cx, |this, cx| {
+                    this.load_credentials_task = None;
+                    cx.notify();
+                })
+                .log_err();
+            }
+        }));
+
+        Self {
+            api_key_editor,
+            state,
+            provider_name,
+            load_credentials_task,
+        }
+    }
+
+    fn save_api_key(&mut self, _: &menu::Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        let api_key = self.api_key_editor.read(cx).text(cx);
+        if api_key.is_empty() {
+            return;
+        }
+
+        let state = self.state.clone();
+        cx.spawn(async move |_, cx| {
+            state
+                .update(cx, |state, cx| state.set_api_key(api_key, cx))?
+                .await
+        })
+        .detach_and_log_err(cx);
+
+        cx.notify();
+    }
+
+    fn reset_api_key(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.api_key_editor
+            .update(cx, |editor, cx| editor.set_text("", window, cx));
+
+        let state = self.state.clone();
+        cx.spawn(async move |_, cx| state.update(cx, |state, cx| state.reset_api_key(cx))?.await)
+            .detach_and_log_err(cx);
+
+        cx.notify();
+    }
+
+    fn render_api_key_editor(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.ui_font.family.clone(),
+            font_features: settings.ui_font.features.clone(),
+            font_fallbacks: settings.ui_font.fallbacks.clone(),
+            font_size: rems(0.875).into(),
+            font_weight: settings.ui_font.weight,
+            font_style: FontStyle::Normal,
+            line_height: relative(1.3),
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+            white_space: WhiteSpace::Normal,
+            ..Default::default()
+        };
+        EditorElement::new(
+            &self.api_key_editor,
+            EditorStyle {
+                background: cx.theme().colors().editor_background,
+                local_player: cx.theme().players().local(),
+                text: text_style,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+impl Render for ConfigurationView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.load_credentials_task.is_some() {
+            return div().child(Label::new("Loading credentials...")).into_any();
+        }
+
+        if self.state.read(cx).is_authenticated() {
+            return h_flex()
+                .mt_1()
+                .p_1()
+                .justify_between()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .bg(cx.theme().colors().background)
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(Icon::new(IconName::Check).color(Color::Success))
+                        .child(Label::new("API key configured")),
+                )
+                .child(
+                    Button::new("reset-key", "Reset Key")
+                        .label_size(LabelSize::Small)
+                        .icon(Some(IconName::Trash))
+                        .icon_size(IconSize::Small)
+                        .icon_position(IconPosition::Start)
+                        .on_click(
+                            cx.listener(|this, _, window, cx| this.reset_api_key(window, cx)),
+                        ),
+                )
+                .into_any();
+        }
+
+        let api_key_url = self.state.read(cx).api_key_url.clone();
+        v_flex()
+            .size_full()
+            .on_action(cx.listener(Self::save_api_key))
+            .child(Label::new(format!(
+                "To use {} in Zed, you need an API key:",
+                self.provider_name
+            )))
+            .when_some(api_key_url, |this, api_key_url| {
+                this.child(
+                    Button::new("get-api-key", "Get your API key")
+                        .label_size(LabelSize::Small)
+                        .icon(IconName::ArrowUpRight)
+                        .icon_size(IconSize::XSmall)
+                        .icon_position(IconPosition::End)
+                        .on_click(move |_, _, cx| cx.open_url(&api_key_url)),
+                )
+            })
+            .child(
+                h_flex()
+                    .w_full()
+                    .my_2()
+                    .px_2()
+                    .py_1()
+                    .bg(cx.theme().colors().editor_background)
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .rounded_sm()
+                    .child(self.render_api_key_editor(cx)),
+            )
+            .child(
+                Label::new("Paste your API key above and hit enter to start using the models.")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .into_any()
+    }
+}
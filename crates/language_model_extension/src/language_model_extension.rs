@@ -0,0 +1,44 @@
+mod extension_language_model_provider;
+
+use std::sync::Arc;
+
+use extension::{Extension, ExtensionHostProxy, ExtensionLanguageModelProviderProxy};
+use extension_language_model_provider::{ExtensionLanguageModelProvider, registry_provider_id};
+use gpui::App;
+use language_model::LanguageModelRegistry;
+
+pub fn init(extension_host_proxy: Arc<ExtensionHostProxy>) {
+    extension_host_proxy.register_language_model_provider_proxy(LanguageModelRegistryProxy);
+}
+
+struct LanguageModelRegistryProxy;
+
+impl ExtensionLanguageModelProviderProxy for LanguageModelRegistryProxy {
+    fn register_language_model_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    ) {
+        let manifest = extension.manifest();
+        let Some(entry) = manifest.language_model_providers.get(&provider_id).cloned() else {
+            return;
+        };
+
+        let provider = ExtensionLanguageModelProvider::new(extension, provider_id, entry, cx);
+        LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+            registry.register_provider(provider, cx);
+        });
+    }
+
+    fn unregister_language_model_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    ) {
+        LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+            registry.unregister_provider(registry_provider_id(&extension_id, &provider_id), cx);
+        });
+    }
+}
@@ -510,13 +510,40 @@ impl LanguageModel for AnthropicModel {
     }
 }
 
+/// Anthropic requires thinking budgets of at least this many tokens.
+const MIN_THINKING_BUDGET_TOKENS: u32 = 1024;
+
 pub fn into_anthropic(
-    request: LanguageModelRequest,
+    mut request: LanguageModelRequest,
     model: String,
     default_temperature: f32,
     max_output_tokens: u32,
     mode: AnthropicModelMode,
 ) -> anthropic::Request {
+    // Anthropic can't constrain the response to a schema, so the model is asked to follow it.
+    request.inline_response_format();
+    let max_tokens = request
+        .max_tokens_within(Some(max_output_tokens))
+        .unwrap_or(max_output_tokens);
+    let thinking = match mode {
+        AnthropicModelMode::Thinking { budget_tokens } => {
+            let budget_tokens = request
+                .reasoning
+                .map(|reasoning| reasoning.budget_tokens())
+                .or(budget_tokens);
+            match budget_tokens {
+                // The budget must be below the maximum number of tokens, leaving no room for thinking.
+                Some(_) if max_tokens <= MIN_THINKING_BUDGET_TOKENS => None,
+                budget_tokens => Some(anthropic::Thinking::Enabled {
+                    budget_tokens: budget_tokens.map(|budget_tokens| {
+                        budget_tokens.clamp(MIN_THINKING_BUDGET_TOKENS, max_tokens - 1)
+                    }),
+                }),
+            }
+        }
+        AnthropicModelMode::Default => None,
+    };
+
     let mut new_messages: Vec<anthropic::Message> = Vec::new();
     let mut system_message = String::new();
 
@@ -639,17 +666,13 @@ pub fn into_anthropic(
     anthropic::Request {
         model,
         messages: new_messages,
-        max_tokens: max_output_tokens,
+        max_tokens,
         system: if system_message.is_empty() {
             None
         } else {
             Some(anthropic::StringOrContents::String(system_message))
         },
-        thinking: if let AnthropicModelMode::Thinking { budget_tokens } = mode {
-            Some(anthropic::Thinking::Enabled { budget_tokens })
-        } else {
-            None
-        },
+        thinking,
         tools: request
             .tools
             .into_iter()
@@ -695,7 +718,7 @@ impl AnthropicEventMapper {
         events.flat_map(move |event| {
             futures::stream::iter(match event {
                 Ok(event) => self.map_event(event),
-                Err(error) => vec![Err(LanguageModelCompletionError::Other(anyhow!(error)))],
+                Err(error) => vec![Err(LanguageModelCompletionError::from(anyhow!(error)))],
             })
         })
     }
@@ -839,7 +862,7 @@ impl AnthropicEventMapper {
                 vec![Ok(LanguageModelCompletionEvent::Stop(self.stop_reason))]
             }
             Event::Error { error } => {
-                vec![Err(LanguageModelCompletionError::Other(anyhow!(
+                vec![Err(LanguageModelCompletionError::from(anyhow!(
                     AnthropicError::ApiError(error)
                 )))]
             }
@@ -35,9 +35,9 @@ use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCacheConfiguration,
     LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelId, LanguageModelName,
     LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
-    LanguageModelProviderState, LanguageModelRequest, LanguageModelToolChoice,
-    LanguageModelToolResultContent, LanguageModelToolUse, MessageContent, RateLimiter, Role,
-    TokenUsage, WrappedTextContent,
+    LanguageModelProviderState, LanguageModelRequest, LanguageModelRequestMessage,
+    LanguageModelToolChoice, LanguageModelToolResultContent, LanguageModelToolUse, MessageContent,
+    RateLimiter, Role, TokenUsage, WrappedTextContent,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -581,13 +581,47 @@ impl LanguageModel for BedrockModel {
     }
 }
 
+/// Bedrock requires thinking budgets of at least this many tokens.
+const MIN_THINKING_BUDGET_TOKENS: u64 = 1024;
+
 pub fn into_bedrock(
-    request: LanguageModelRequest,
+    mut request: LanguageModelRequest,
     model: String,
     default_temperature: f32,
     max_output_tokens: u32,
     mode: BedrockModelMode,
 ) -> Result<bedrock::Request> {
+    // Bedrock can't constrain the response to a schema, so the model is asked to follow it.
+    // The system prompt isn't sent to Bedrock, so the instructions go in a user message.
+    if let Some(response_format) = request.response_format.take() {
+        request.messages.push(LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![MessageContent::Text(response_format.instructions())],
+            cache: false,
+        });
+    }
+    let max_tokens = request
+        .max_tokens_within(Some(max_output_tokens))
+        .unwrap_or(max_output_tokens);
+    let thinking = match mode {
+        BedrockModelMode::Thinking { budget_tokens } => {
+            let budget_tokens = request
+                .reasoning
+                .map(|reasoning| reasoning.budget_tokens() as u64)
+                .or(budget_tokens);
+            match budget_tokens {
+                // The budget must be below the maximum number of tokens, leaving no room for thinking.
+                Some(_) if max_tokens as u64 <= MIN_THINKING_BUDGET_TOKENS => None,
+                budget_tokens => Some(bedrock::Thinking::Enabled {
+                    budget_tokens: budget_tokens.map(|budget_tokens| {
+                        budget_tokens.clamp(MIN_THINKING_BUDGET_TOKENS, max_tokens as u64 - 1)
+                    }),
+                }),
+            }
+        }
+        BedrockModelMode::Default => None,
+    };
+
     let mut new_messages: Vec<BedrockMessage> = Vec::new();
     let mut system_message = String::new();
 
@@ -729,14 +763,10 @@ pub fn into_bedrock(
     Ok(bedrock::Request {
         model,
         messages: new_messages,
-        max_tokens: max_output_tokens,
+        max_tokens,
         system: Some(system_message),
         tools: Some(tool_config),
-        thinking: if let BedrockModelMode::Thinking { budget_tokens } = mode {
-            Some(bedrock::Thinking::Enabled { budget_tokens })
-        } else {
-            None
-        },
+        thinking,
         metadata: None,
         stop_sequences: Vec::new(),
         temperature: request.temperature.or(Some(default_temperature)),
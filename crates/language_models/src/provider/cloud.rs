@@ -9,14 +9,14 @@ use futures::{
 use gpui::{
     AnyElement, AnyView, App, AsyncApp, Context, Entity, SemanticVersion, Subscription, Task,
 };
-use http_client::{AsyncBody, HttpClient, Method, Response, StatusCode};
+use http_client::{AsyncBody, HttpClient, Method, Response, StatusCode, StatusError};
 use language_model::{
     AuthenticateError, CloudModel, LanguageModel, LanguageModelCacheConfiguration,
     LanguageModelCompletionError, LanguageModelId, LanguageModelKnownError, LanguageModelName,
     LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
     LanguageModelProviderTosView, LanguageModelRequest, LanguageModelToolChoice,
     LanguageModelToolSchemaFormat, ModelRequestLimitReachedError, RateLimiter, RequestUsage,
-    ZED_CLOUD_PROVIDER_ID,
+    RetryPolicy, ZED_CLOUD_PROVIDER_ID,
 };
 use language_model::{
     LanguageModelAvailability, LanguageModelCompletionEvent, LanguageModelProvider, LlmApiToken,
@@ -31,10 +31,7 @@ use smol::Timer;
 use smol::io::{AsyncReadExt, BufReader};
 use std::pin::Pin;
 use std::str::FromStr as _;
-use std::{
-    sync::{Arc, LazyLock},
-    time::Duration,
-};
+use std::sync::{Arc, LazyLock};
 use strum::IntoEnumIterator;
 use thiserror::Error;
 use ui::{TintColor, prelude::*};
@@ -528,8 +525,6 @@ struct PerformLlmCompletionResponse {
 }
 
 impl CloudLanguageModel {
-    const MAX_RETRIES: usize = 3;
-
     async fn perform_llm_completion(
         client: Arc<Client>,
         llm_api_token: LlmApiToken,
@@ -539,10 +534,12 @@ impl CloudLanguageModel {
         let http_client = &client.http_client();
 
         let mut token = llm_api_token.acquire(&client).await?;
-        let mut retries_remaining = Self::MAX_RETRIES;
-        let mut retry_delay = Duration::from_secs(1);
+        let mut token_refreshed = false;
+        let retry_policy = RetryPolicy::default();
+        let mut attempt = 0;
 
         loop {
+            attempt += 1;
             let request_builder = http_client::Request::builder()
                 .method(Method::POST)
                 .uri(http_client.build_zed_llm_url("/completions", &[])?.as_ref());
@@ -587,7 +584,12 @@ impl CloudLanguageModel {
                 .get(EXPIRED_LLM_TOKEN_HEADER_NAME)
                 .is_some()
             {
-                retries_remaining -= 1;
+                // A token that expires right after being refreshed won't get any better.
+                anyhow::ensure!(
+                    !token_refreshed,
+                    "cloud language model completion failed: the refreshed LLM token has expired"
+                );
+                token_refreshed = true;
                 token = llm_api_token.refresh(&client).await?;
             } else if status == StatusCode::FORBIDDEN
                 && response
@@ -616,26 +618,29 @@ impl CloudLanguageModel {
                 }
 
                 anyhow::bail!("Forbidden");
-            } else if status.as_u16() >= 500 && status.as_u16() < 600 {
-                // If we encounter an error in the 500 range, retry after a delay.
-                // We've seen at least these in the wild from API providers:
-                // * 500 Internal Server Error
-                // * 502 Bad Gateway
-                // * 529 Service Overloaded
-
-                if retries_remaining == 0 {
-                    let mut body = String::new();
-                    response.body_mut().read_to_string(&mut body).await?;
-                    anyhow::bail!(
-                        "cloud language model completion failed after {} retries with status {status}: {body}",
-                        Self::MAX_RETRIES
+            } else if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+                // Rate limits and errors in the 500 range are transient, so the request is sent again here,
+                // after the delay the server asked for, rather than by each caller. We've seen at least
+                // 500 Internal Server Error, 502 Bad Gateway and 529 Service Overloaded in the wild.
+                let mut body = String::new();
+                response.body_mut().read_to_string(&mut body).await?;
+                let error = LanguageModelCompletionError::from(anyhow!(StatusError::new(
+                    status,
+                    response.headers(),
+                    format!("cloud language model completion failed with status {status}: {body}"),
+                )));
+                let Some(retry_delay) = retry_policy.retry_delay(&error, attempt) else {
+                    // The retries are used up, so the callers shouldn't retry on top of them.
+                    anyhow::ensure!(
+                        !error.is_retryable(),
+                        "cloud language model completion failed after {attempt} attempts with status {status}: {body}"
                     );
-                }
-
+                    return Err(error.into_anyhow());
+                };
+                log::warn!(
+                    "cloud language model completion attempt {attempt} failed with status {status}, retrying in {retry_delay:?}"
+                );
                 Timer::after(retry_delay).await;
-
-                retries_remaining -= 1;
-                retry_delay *= 2; // If it fails again, wait longer.
             } else if status == StatusCode::PAYMENT_REQUIRED {
                 return Err(anyhow!(PaymentRequiredError));
             } else {
@@ -957,7 +962,7 @@ where
         .flat_map(move |event| {
             futures::stream::iter(match event {
                 Err(error) => {
-                    vec![Err(LanguageModelCompletionError::Other(error))]
+                    vec![Err(LanguageModelCompletionError::from(error))]
                 }
                 Ok(CloudCompletionEvent::Status(event)) => {
                     vec![Ok(LanguageModelCompletionEvent::StatusUpdate(event))]
@@ -365,7 +365,7 @@ impl LanguageModel for DeepSeekLanguageModel {
                                         .map(LanguageModelCompletionEvent::Text)
                                 })
                         })
-                        .map_err(LanguageModelCompletionError::Other)
+                        .map_err(LanguageModelCompletionError::from)
                 })
                 .boxed())
         }
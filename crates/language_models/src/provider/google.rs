@@ -12,8 +12,8 @@ use gpui::{
 use http_client::HttpClient;
 use language_model::{
     AuthenticateError, LanguageModelCompletionError, LanguageModelCompletionEvent,
-    LanguageModelToolChoice, LanguageModelToolSchemaFormat, LanguageModelToolUse,
-    LanguageModelToolUseId, MessageContent, StopReason,
+    LanguageModelResponseFormat, LanguageModelToolChoice, LanguageModelToolSchemaFormat,
+    LanguageModelToolUse, LanguageModelToolUseId, MessageContent, StopReason,
 };
 use language_model::{
     LanguageModel, LanguageModelId, LanguageModelName, LanguageModelProvider,
@@ -384,7 +384,7 @@ impl LanguageModel for GoogleLanguageModel {
         let future = self.request_limiter.stream(async move {
             let response = request
                 .await
-                .map_err(|err| LanguageModelCompletionError::Other(anyhow!(err)))?;
+                .map_err(|err| LanguageModelCompletionError::from(anyhow!(err)))?;
             Ok(GoogleEventMapper::new().map_stream(response))
         });
         async move { Ok(future.await?.boxed()) }.boxed()
@@ -480,6 +480,20 @@ pub fn into_google(
         None
     };
 
+    let (response_mime_type, response_schema) = match request.response_format.take() {
+        Some(LanguageModelResponseFormat::JsonObject) => (Some("application/json".into()), None),
+        Some(LanguageModelResponseFormat::JsonSchema { schema, .. }) => {
+            (Some("application/json".into()), Some(schema))
+        }
+        None => (None, None),
+    };
+    let thinking_config = request
+        .reasoning
+        .filter(|_| google_ai::supports_thinking_budget(&model_id))
+        .map(|reasoning| google_ai::ThinkingConfig {
+            thinking_budget: reasoning.budget_tokens(),
+        });
+
     google_ai::GenerateContentRequest {
         model: google_ai::ModelName { model_id },
         system_instruction: system_instructions,
@@ -505,10 +519,13 @@ pub fn into_google(
         generation_config: Some(google_ai::GenerationConfig {
             candidate_count: Some(1),
             stop_sequences: Some(request.stop),
-            max_output_tokens: None,
+            max_output_tokens: request.max_tokens.map(|max_tokens| max_tokens as usize),
             temperature: request.temperature.map(|t| t as f64).or(Some(1.0)),
             top_p: None,
             top_k: None,
+            response_mime_type,
+            response_schema,
+            thinking_config,
         }),
         safety_settings: None,
         tools: (request.tools.len() > 0).then(|| {
@@ -562,7 +579,7 @@ impl GoogleEventMapper {
                 futures::stream::iter(match event {
                     Some(Ok(event)) => self.map_event(event),
                     Some(Err(error)) => {
-                        vec![Err(LanguageModelCompletionError::Other(anyhow!(error)))]
+                        vec![Err(LanguageModelCompletionError::from(anyhow!(error)))]
                     }
                     None => vec![Ok(LanguageModelCompletionEvent::Stop(self.stop_reason))],
                 })
@@ -12,8 +12,8 @@ use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
     LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
     LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
-    LanguageModelToolChoice, LanguageModelToolResultContent, LanguageModelToolUse, MessageContent,
-    RateLimiter, Role, StopReason, WrappedTextContent,
+    LanguageModelResponseFormat, LanguageModelToolChoice, LanguageModelToolResultContent,
+    LanguageModelToolUse, MessageContent, RateLimiter, Role, StopReason, WrappedTextContent,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -387,6 +387,7 @@ pub fn into_mistral(
     max_output_tokens: Option<u32>,
 ) -> mistral::Request {
     let stream = true;
+    let max_tokens = request.max_tokens_within(max_output_tokens);
 
     let mut messages = Vec::new();
     for message in request.messages {
@@ -452,9 +453,22 @@ pub fn into_mistral(
         model,
         messages,
         stream,
-        max_tokens: max_output_tokens,
+        max_tokens,
         temperature: request.temperature,
-        response_format: None,
+        response_format: request
+            .response_format
+            .map(|response_format| match response_format {
+                LanguageModelResponseFormat::JsonObject => mistral::ResponseFormat::JsonObject,
+                LanguageModelResponseFormat::JsonSchema { name, schema } => {
+                    mistral::ResponseFormat::JsonSchema {
+                        json_schema: mistral::JsonSchemaFormat {
+                            name,
+                            schema,
+                            strict: None,
+                        },
+                    }
+                }
+            }),
         tool_choice: match request.tool_choice {
             Some(LanguageModelToolChoice::Auto) if !request.tools.is_empty() => {
                 Some(mistral::ToolChoice::Auto)
@@ -504,7 +518,7 @@ impl MistralEventMapper {
         events.flat_map(move |event| {
             futures::stream::iter(match event {
                 Ok(event) => self.map_event(event),
-                Err(error) => vec![Err(LanguageModelCompletionError::Other(anyhow!(error)))],
+                Err(error) => vec![Err(LanguageModelCompletionError::from(anyhow!(error)))],
             })
         })
     }
@@ -822,6 +836,9 @@ mod tests {
             prompt_id: None,
             mode: None,
             stop: Vec::new(),
+            max_tokens: None,
+            response_format: None,
+            reasoning: None,
         };
 
         let model_name = "mistral-medium-latest".to_string();
@@ -5,8 +5,8 @@ use gpui::{AnyView, App, AsyncApp, Context, Subscription, Task};
 use http_client::HttpClient;
 use language_model::{
     AuthenticateError, LanguageModelCompletionError, LanguageModelCompletionEvent,
-    LanguageModelRequestTool, LanguageModelToolChoice, LanguageModelToolUse,
-    LanguageModelToolUseId, StopReason,
+    LanguageModelRequestTool, LanguageModelResponseFormat, LanguageModelToolChoice,
+    LanguageModelToolUse, LanguageModelToolUseId, StopReason,
 };
 use language_model::{
     LanguageModel, LanguageModelId, LanguageModelName, LanguageModelProvider,
@@ -19,6 +19,7 @@ use ollama::{
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use settings::{Settings, SettingsStore};
 use std::pin::Pin;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -273,6 +274,15 @@ pub struct OllamaLanguageModel {
 
 impl OllamaLanguageModel {
     fn to_ollama_request(&self, request: LanguageModelRequest) -> ChatRequest {
+        let num_predict = request
+            .max_tokens
+            .map(|max_tokens| max_tokens.min(isize::MAX as u32) as isize);
+        let format = request
+            .response_format
+            .map(|response_format| match response_format {
+                LanguageModelResponseFormat::JsonObject => Value::String("json".into()),
+                LanguageModelResponseFormat::JsonSchema { schema, .. } => schema,
+            });
         ChatRequest {
             model: self.model.name.clone(),
             messages: request
@@ -295,11 +305,13 @@ impl OllamaLanguageModel {
             stream: true,
             options: Some(ChatOptions {
                 num_ctx: Some(self.model.max_tokens),
+                num_predict,
                 stop: Some(request.stop),
                 temperature: request.temperature.or(Some(1.0)),
                 ..Default::default()
             }),
             tools: request.tools.into_iter().map(tool_into_ollama).collect(),
+            format,
         }
     }
 }
@@ -416,7 +428,7 @@ fn map_to_language_model_completion_events(
             let delta = match response {
                 Ok(delta) => delta,
                 Err(e) => {
-                    let event = Err(LanguageModelCompletionError::Other(anyhow!(e)));
+                    let event = Err(LanguageModelCompletionError::from(anyhow!(e)));
                     return Some((vec![event], state));
                 }
             };
@@ -12,8 +12,9 @@ use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
     LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
     LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
-    LanguageModelToolChoice, LanguageModelToolResultContent, LanguageModelToolUse, MessageContent,
-    RateLimiter, Role, StopReason, WrappedTextContent,
+    LanguageModelResponseFormat, LanguageModelToolChoice, LanguageModelToolResultContent,
+    LanguageModelToolUse, MessageContent, RateLimiter, ReasoningEffort, Role, StopReason,
+    WrappedTextContent,
 };
 use open_ai::{ImageUrl, Model, ResponseStreamEvent, stream_completion};
 use schemars::JsonSchema;
@@ -357,6 +358,7 @@ pub fn into_open_ai(
     max_output_tokens: Option<u32>,
 ) -> open_ai::Request {
     let stream = !model.id().starts_with("o1-");
+    let max_tokens = request.max_tokens_within(max_output_tokens);
 
     let mut messages = Vec::new();
     for message in request.messages {
@@ -441,7 +443,7 @@ pub fn into_open_ai(
         stream,
         stop: request.stop,
         temperature: request.temperature.unwrap_or(1.0),
-        max_tokens: max_output_tokens,
+        max_tokens,
         parallel_tool_calls: if model.supports_parallel_tool_calls() && !request.tools.is_empty() {
             // Disable parallel tool calls, as the Agent currently expects a maximum of one per turn.
             Some(false)
@@ -464,6 +466,28 @@ pub fn into_open_ai(
             LanguageModelToolChoice::Any => open_ai::ToolChoice::Required,
             LanguageModelToolChoice::None => open_ai::ToolChoice::None,
         }),
+        response_format: request
+            .response_format
+            .map(|response_format| match response_format {
+                LanguageModelResponseFormat::JsonObject => open_ai::ResponseFormat::JsonObject,
+                LanguageModelResponseFormat::JsonSchema { name, schema } => {
+                    open_ai::ResponseFormat::JsonSchema {
+                        json_schema: open_ai::JsonSchemaFormat {
+                            name,
+                            schema,
+                            strict: None,
+                        },
+                    }
+                }
+            }),
+        reasoning_effort: request
+            .reasoning
+            .filter(|_| model.supports_reasoning_effort())
+            .map(|reasoning| match reasoning.effort() {
+                ReasoningEffort::Low => open_ai::ReasoningEffort::Low,
+                ReasoningEffort::Medium => open_ai::ReasoningEffort::Medium,
+                ReasoningEffort::High => open_ai::ReasoningEffort::High,
+            }),
     }
 }
 
@@ -514,7 +538,7 @@ impl OpenAiEventMapper {
         events.flat_map(move |event| {
             futures::stream::iter(match event {
                 Ok(event) => self.map_event(event),
-                Err(error) => vec![Err(LanguageModelCompletionError::Other(anyhow!(error)))],
+                Err(error) => vec![Err(LanguageModelCompletionError::from(anyhow!(error)))],
             })
         })
     }
@@ -849,7 +873,7 @@ impl Render for ConfigurationView {
 #[cfg(test)]
 mod tests {
     use gpui::TestAppContext;
-    use language_model::LanguageModelRequestMessage;
+    use language_model::{LanguageModelReasoning, LanguageModelRequestMessage};
 
     use super::*;
 
@@ -868,6 +892,9 @@ mod tests {
             tool_choice: None,
             stop: vec![],
             temperature: None,
+            max_tokens: None,
+            response_format: None,
+            reasoning: None,
         };
 
         // Validate that all models are supported by tiktoken-rs
@@ -883,4 +910,41 @@ mod tests {
             assert!(count > 0);
         }
     }
+
+    #[test]
+    fn test_structured_output_and_reasoning() {
+        let request = LanguageModelRequest {
+            messages: vec![LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec![MessageContent::Text("message".into())],
+                cache: false,
+            }],
+            max_tokens: Some(1_000),
+            response_format: Some(LanguageModelResponseFormat::JsonSchema {
+                name: "answer".into(),
+                schema: serde_json::json!({"type": "object"}),
+            }),
+            reasoning: Some(LanguageModelReasoning::BudgetTokens(30_000)),
+            ..Default::default()
+        };
+
+        let open_ai_request = into_open_ai(request.clone(), &Model::O3, Some(500));
+        assert_eq!(open_ai_request.max_tokens, Some(500));
+        assert_eq!(
+            serde_json::to_value(&open_ai_request.response_format).unwrap(),
+            serde_json::json!({
+                "type": "json_schema",
+                "json_schema": {"name": "answer", "schema": {"type": "object"}},
+            })
+        );
+        assert!(matches!(
+            open_ai_request.reasoning_effort,
+            Some(open_ai::ReasoningEffort::High)
+        ));
+
+        // Models that don't reason don't get a reasoning effort.
+        let open_ai_request = into_open_ai(request, &Model::FourOmni, None);
+        assert_eq!(open_ai_request.max_tokens, Some(1_000));
+        assert!(open_ai_request.reasoning_effort.is_none());
+    }
 }
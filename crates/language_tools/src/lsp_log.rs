@@ -1180,6 +1180,7 @@ impl SearchableItem for LspLogView {
             // LSP log is read-only.
             replacement: false,
             selection: false,
+            syntax_filter: false,
         }
     }
     fn active_match_index(
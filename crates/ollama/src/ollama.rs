@@ -1,6 +1,6 @@
 use anyhow::{Context as _, Result};
 use futures::{AsyncBufReadExt, AsyncReadExt, StreamExt, io::BufReader, stream::BoxStream};
-use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest, http};
+use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest, StatusError, http};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::{sync::Arc, time::Duration};
@@ -141,6 +141,9 @@ pub struct ChatRequest {
     pub keep_alive: KeepAlive,
     pub options: Option<ChatOptions>,
     pub tools: Vec<OllamaTool>,
+    /// Either `"json"`, or a JSON schema that the response must match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<Value>,
 }
 
 impl ChatRequest {
@@ -276,11 +279,15 @@ pub async fn stream_chat_completion(
     } else {
         let mut body = String::new();
         response.body_mut().read_to_string(&mut body).await?;
-        anyhow::bail!(
-            "Failed to connect to Ollama API: {} {}",
+        Err(anyhow::anyhow!(StatusError::new(
             response.status(),
-            body,
-        );
+            response.headers(),
+            format!(
+                "Failed to connect to Ollama API: {} {}",
+                response.status(),
+                body,
+            )
+        )))
     }
 }
 
@@ -4,7 +4,7 @@ use futures::{
     io::BufReader,
     stream::{self, BoxStream},
 };
-use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest};
+use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest, StatusError};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::{
@@ -212,6 +212,16 @@ impl Model {
             _ => false,
         }
     }
+
+    /// Returns whether the given model supports the `reasoning_effort` parameter.
+    ///
+    /// If the model does not support the parameter, do not pass it up, or the API will return an error.
+    pub fn supports_reasoning_effort(&self) -> bool {
+        match self {
+            Self::O1 | Self::O3Mini | Self::O3 | Self::O4Mini => true,
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -231,6 +241,34 @@ pub struct Request {
     pub parallel_tool_calls: Option<bool>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tools: Vec<ToolDefinition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<ReasoningEffort>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    Text,
+    JsonObject,
+    JsonSchema { json_schema: JsonSchemaFormat },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonSchemaFormat {
+    pub name: String,
+    pub schema: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Low,
+    Medium,
+    High,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -664,18 +702,22 @@ pub async fn stream_completion(
             message: String,
         }
 
-        match serde_json::from_str::<OpenAiResponse>(&body) {
-            Ok(response) if !response.error.message.is_empty() => Err(anyhow!(
+        let message = match serde_json::from_str::<OpenAiResponse>(&body) {
+            Ok(response) if !response.error.message.is_empty() => format!(
                 "Failed to connect to OpenAI API: {}",
                 response.error.message,
-            )),
-
-            _ => anyhow::bail!(
+            ),
+            _ => format!(
                 "Failed to connect to OpenAI API: {} {}",
                 response.status(),
                 body,
             ),
-        }
+        };
+        Err(anyhow!(StatusError::new(
+            response.status(),
+            response.headers(),
+            message
+        )))
     }
 }
 
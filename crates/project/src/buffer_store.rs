@@ -7,16 +7,16 @@ use crate::{
 use anyhow::{Context as _, Result, anyhow};
 use client::Client;
 use collections::{HashMap, HashSet, hash_map};
-use fs::Fs;
+use fs::{Encoding, Fs};
 use futures::{Future, FutureExt as _, StreamExt, channel::oneshot, future::Shared};
 use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity,
 };
 use language::{
-    Buffer, BufferEvent, Capability, DiskState, File as _, Language, Operation,
+    Buffer, BufferEvent, Capability, DiskState, File as _, Language, LanguageRegistry, Operation,
     proto::{
-        deserialize_line_ending, deserialize_version, serialize_line_ending, serialize_version,
-        split_operations,
+        deserialize_encoding, deserialize_line_ending, deserialize_version, serialize_encoding,
+        serialize_line_ending, serialize_version, split_operations,
     },
 };
 use rpc::{
@@ -27,7 +27,7 @@ use smol::channel::Receiver;
 use std::{io, path::Path, pin::pin, sync::Arc, time::Instant};
 use text::BufferId;
 use util::{ResultExt as _, TryFutureExt, debug_panic, maybe};
-use worktree::{File, PathChange, ProjectEntryId, Worktree, WorktreeId};
+use worktree::{File, LoadProgress, PathChange, ProjectEntryId, Worktree, WorktreeId};
 
 /// A set of open buffers.
 pub struct BufferStore {
@@ -39,6 +39,7 @@ pub struct BufferStore {
     path_to_buffer_id: HashMap<ProjectPath, BufferId>,
     downstream_client: Option<(AnyProtoClient, u64)>,
     shared_buffers: HashMap<proto::PeerId, HashMap<BufferId, SharedBuffer>>,
+    large_file_loads: HashMap<ProjectPath, LoadProgress>,
 }
 
 #[derive(Hash, Eq, PartialEq, Clone)]
@@ -123,6 +124,7 @@ impl RemoteBufferStore {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id().into();
         let version = buffer.version();
+        let encoding = buffer.encoding();
         let rpc = self.upstream_client.clone();
         let project_id = self.project_id;
         cx.spawn(async move |_, cx| {
@@ -132,12 +134,17 @@ impl RemoteBufferStore {
                     buffer_id,
                     new_path,
                     version: serialize_version(&version),
+                    encoding: Some(serialize_encoding(encoding)),
                 })
                 .await?;
             let version = deserialize_version(&response.version);
             let mtime = response.mtime.map(|mtime| mtime.into());
+            let encoding = response.encoding.and_then(deserialize_encoding);
 
             buffer_handle.update(cx, |buffer, cx| {
+                if let Some(encoding) = encoding {
+                    buffer.set_encoding(encoding, cx);
+                }
                 buffer.did_save(version.clone(), mtime, cx);
             })?;
 
@@ -325,6 +332,7 @@ impl RemoteBufferStore {
     fn reload_buffers(
         &self,
         buffers: HashSet<Entity<Buffer>>,
+        encoding: Option<Encoding>,
         push_to_history: bool,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<ProjectTransaction>> {
@@ -334,6 +342,7 @@ impl RemoteBufferStore {
                 .iter()
                 .map(|buffer| buffer.read(cx).remote_id().to_proto())
                 .collect(),
+            encoding: encoding.map(serialize_encoding),
         });
 
         cx.spawn(async move |this, cx| {
@@ -359,6 +368,7 @@ impl LocalBufferStore {
 
         let text = buffer.as_rope().clone();
         let line_ending = buffer.line_ending();
+        let encoding = buffer.encoding();
         let version = buffer.version();
         let buffer_id = buffer.remote_id();
         let file = buffer.file().cloned();
@@ -370,7 +380,7 @@ impl LocalBufferStore {
         }
 
         let save = worktree.update(cx, |worktree, cx| {
-            worktree.write_file(path.as_ref(), text, line_ending, cx)
+            worktree.write_file(path.as_ref(), text, line_ending, encoding, cx)
         });
 
         cx.spawn(async move |this, cx| {
@@ -393,6 +403,7 @@ impl LocalBufferStore {
                             buffer_id: buffer_id.to_proto(),
                             version: serialize_version(&version),
                             mtime: mtime.map(|time| time.into()),
+                            encoding: Some(serialize_encoding(encoding)),
                         })
                         .log_err();
                 }
@@ -417,6 +428,21 @@ impl LocalBufferStore {
                     worktree::Event::UpdatedEntries(changes) => {
                         Self::local_worktree_entries_changed(this, &worktree, changes, cx);
                     }
+                    worktree::Event::LargeFileLoadProgress { path, progress } => {
+                        let project_path = ProjectPath {
+                            worktree_id: worktree.read(cx).id(),
+                            path: path.clone(),
+                        };
+                        match progress {
+                            Some(progress) => {
+                                this.large_file_loads.insert(project_path, *progress);
+                            }
+                            None => {
+                                this.large_file_loads.remove(&project_path);
+                            }
+                        }
+                        cx.notify();
+                    }
                     _ => {}
                 }
             }
@@ -604,15 +630,31 @@ impl LocalBufferStore {
     ) -> Task<Result<Entity<Buffer>>> {
         let load_buffer = worktree.update(cx, |worktree, cx| {
             let load_file = worktree.load_file(path.as_ref(), cx);
+            let large_files_read_only = worktree
+                .as_local()
+                .map_or(false, |worktree| worktree.settings().large_files.read_only);
             let reservation = cx.reserve_entity();
             let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
             cx.spawn(async move |_, cx| {
                 let loaded = load_file.await?;
+                let encoding = loaded.encoding;
+                let is_large = loaded.is_large;
+                let line_ending = loaded.line_ending;
                 let text_buffer = cx
-                    .background_spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
+                    .background_spawn(async move {
+                        text::Buffer::new_normalized(0, buffer_id, line_ending, loaded.text)
+                    })
                     .await;
-                cx.insert_entity(reservation, |_| {
-                    Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite)
+                let capability = if is_large && large_files_read_only {
+                    Capability::ReadOnly
+                } else {
+                    Capability::ReadWrite
+                };
+                cx.insert_entity(reservation, |cx| {
+                    let mut buffer = Buffer::build(text_buffer, Some(loaded.file), capability);
+                    buffer.set_encoding(encoding, cx);
+                    buffer.set_large_file(is_large, cx);
+                    buffer
                 })
             })
         });
@@ -677,13 +719,19 @@ impl LocalBufferStore {
     fn reload_buffers(
         &self,
         buffers: HashSet<Entity<Buffer>>,
+        encoding: Option<Encoding>,
         push_to_history: bool,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<ProjectTransaction>> {
         cx.spawn(async move |_, cx| {
             let mut project_transaction = ProjectTransaction::default();
             for buffer in buffers {
-                let transaction = buffer.update(cx, |buffer, cx| buffer.reload(cx))?.await?;
+                let transaction = buffer
+                    .update(cx, |buffer, cx| match encoding {
+                        Some(encoding) => buffer.reload_with_encoding(encoding, cx),
+                        None => buffer.reload(cx),
+                    })?
+                    .await?;
                 buffer.update(cx, |buffer, cx| {
                     if let Some(transaction) = transaction {
                         if !push_to_history {
@@ -725,6 +773,7 @@ impl BufferStore {
             opened_buffers: Default::default(),
             path_to_buffer_id: Default::default(),
             shared_buffers: Default::default(),
+            large_file_loads: Default::default(),
             loading_buffers: Default::default(),
             worktree_store,
         }
@@ -750,6 +799,7 @@ impl BufferStore {
             path_to_buffer_id: Default::default(),
             loading_buffers: Default::default(),
             shared_buffers: Default::default(),
+            large_file_loads: Default::default(),
             worktree_store,
         }
     }
@@ -939,6 +989,11 @@ impl BufferStore {
         })
     }
 
+    /// The large files that are being read from disk, with how much of them has been read so far.
+    pub fn large_file_loads(&self) -> impl Iterator<Item = (&ProjectPath, &LoadProgress)> {
+        self.large_file_loads.iter()
+    }
+
     pub fn buffer_id_for_project_path(&self, project_path: &ProjectPath) -> Option<&BufferId> {
         self.path_to_buffer_id.get(project_path)
     }
@@ -1052,6 +1107,7 @@ impl BufferStore {
         query: &SearchQuery,
         mut limit: usize,
         fs: Arc<dyn Fs>,
+        languages: Arc<LanguageRegistry>,
         cx: &mut Context<Self>,
     ) -> Receiver<Entity<Buffer>> {
         let (tx, rx) = smol::channel::unbounded();
@@ -1071,7 +1127,14 @@ impl BufferStore {
         let project_paths_rx = self
             .worktree_store
             .update(cx, |worktree_store, cx| {
-                worktree_store.find_search_candidates(query.clone(), limit, open_buffers, fs, cx)
+                worktree_store.find_search_candidates(
+                    query.clone(),
+                    limit,
+                    open_buffers,
+                    fs,
+                    languages,
+                    cx,
+                )
             })
             .chunks(MAX_CONCURRENT_BUFFER_OPENS);
 
@@ -1124,6 +1187,7 @@ impl BufferStore {
                         version: serialize_version(&buffer.version()),
                         mtime: buffer.saved_mtime().map(|t| t.into()),
                         line_ending: serialize_line_ending(buffer.line_ending()) as i32,
+                        encoding: Some(serialize_encoding(buffer.encoding())),
                     })
                     .log_err();
             }
@@ -1240,6 +1304,7 @@ impl BufferStore {
                         mtime: buffer.saved_mtime().map(|time| time.into()),
                         line_ending: language::proto::serialize_line_ending(buffer.line_ending())
                             as i32,
+                        encoding: Some(serialize_encoding(buffer.encoding())),
                     })
                     .log_err();
 
@@ -1354,17 +1419,27 @@ impl BufferStore {
                 buffer.wait_for_version(deserialize_version(&envelope.payload.version))
             })?
             .await?;
-        let buffer_id = buffer.update(&mut cx, |buffer, _| buffer.remote_id())?;
+        let (buffer_id, previous_encoding) = buffer.update(&mut cx, |buffer, cx| {
+            let previous_encoding = buffer.encoding();
+            if let Some(encoding) = envelope.payload.encoding.and_then(deserialize_encoding) {
+                buffer.set_encoding(encoding, cx);
+            }
+            (buffer.remote_id(), previous_encoding)
+        })?;
 
-        if let Some(new_path) = envelope.payload.new_path {
+        let save = if let Some(new_path) = envelope.payload.new_path {
             let new_path = ProjectPath::from_proto(new_path);
             this.update(&mut cx, |this, cx| {
                 this.save_buffer_as(buffer.clone(), new_path, cx)
             })?
-            .await?;
         } else {
             this.update(&mut cx, |this, cx| this.save_buffer(buffer.clone(), cx))?
-                .await?;
+        };
+        if let Err(error) = save.await {
+            buffer.update(&mut cx, |buffer, cx| {
+                buffer.set_encoding(previous_encoding, cx)
+            })?;
+            return Err(error);
         }
 
         buffer.update(&mut cx, |buffer, _| proto::BufferSaved {
@@ -1372,6 +1447,7 @@ impl BufferStore {
             buffer_id: buffer_id.into(),
             version: serialize_version(buffer.saved_version()),
             mtime: buffer.saved_mtime().map(|time| time.into()),
+            encoding: Some(serialize_encoding(buffer.encoding())),
         })
     }
 
@@ -1408,9 +1484,17 @@ impl BufferStore {
         let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
         let version = deserialize_version(&envelope.payload.version);
         let mtime = envelope.payload.mtime.clone().map(|time| time.into());
+        let encoding = envelope
+            .payload
+            .encoding
+            .clone()
+            .and_then(deserialize_encoding);
         this.update(&mut cx, move |this, cx| {
             if let Some(buffer) = this.get_possibly_incomplete(buffer_id) {
                 buffer.update(cx, |buffer, cx| {
+                    if let Some(encoding) = encoding {
+                        buffer.set_encoding(encoding, cx);
+                    }
                     buffer.did_save(version, mtime, cx);
                 });
             }
@@ -1422,6 +1506,7 @@ impl BufferStore {
                         buffer_id: buffer_id.into(),
                         mtime: envelope.payload.mtime,
                         version: envelope.payload.version,
+                        encoding: envelope.payload.encoding,
                     })
                     .log_err();
             }
@@ -1440,9 +1525,17 @@ impl BufferStore {
             proto::LineEnding::from_i32(envelope.payload.line_ending)
                 .context("missing line ending")?,
         );
+        let encoding = envelope
+            .payload
+            .encoding
+            .clone()
+            .and_then(deserialize_encoding);
         this.update(&mut cx, |this, cx| {
             if let Some(buffer) = this.get_possibly_incomplete(buffer_id) {
                 buffer.update(cx, |buffer, cx| {
+                    if let Some(encoding) = encoding {
+                        buffer.set_encoding(encoding, cx);
+                    }
                     buffer.did_reload(version, line_ending, mtime, cx);
                 });
             }
@@ -1455,6 +1548,7 @@ impl BufferStore {
                         mtime: envelope.payload.mtime,
                         version: envelope.payload.version,
                         line_ending: envelope.payload.line_ending,
+                        encoding: envelope.payload.encoding,
                     })
                     .log_err();
             }
@@ -1466,13 +1560,28 @@ impl BufferStore {
         buffers: HashSet<Entity<Buffer>>,
         push_to_history: bool,
         cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        self.reload_buffers_with_encoding(buffers, None, push_to_history, cx)
+    }
+
+    /// Reloads the buffers, decoding their files with the given encoding instead of their own ones.
+    pub fn reload_buffers_with_encoding(
+        &self,
+        buffers: HashSet<Entity<Buffer>>,
+        encoding: Option<Encoding>,
+        push_to_history: bool,
+        cx: &mut Context<Self>,
     ) -> Task<Result<ProjectTransaction>> {
         if buffers.is_empty() {
             return Task::ready(Ok(ProjectTransaction::default()));
         }
         match &self.state {
-            BufferStoreState::Local(this) => this.reload_buffers(buffers, push_to_history, cx),
-            BufferStoreState::Remote(this) => this.reload_buffers(buffers, push_to_history, cx),
+            BufferStoreState::Local(this) => {
+                this.reload_buffers(buffers, encoding, push_to_history, cx)
+            }
+            BufferStoreState::Remote(this) => {
+                this.reload_buffers(buffers, encoding, push_to_history, cx)
+            }
         }
     }
 
@@ -1488,7 +1597,12 @@ impl BufferStore {
                 let buffer_id = BufferId::new(*buffer_id)?;
                 buffers.insert(this.get_existing(buffer_id)?);
             }
-            anyhow::Ok(this.reload_buffers(buffers, false, cx))
+            let encoding = envelope
+                .payload
+                .encoding
+                .clone()
+                .and_then(deserialize_encoding);
+            anyhow::Ok(this.reload_buffers_with_encoding(buffers, encoding, false, cx))
         })??;
 
         let project_transaction = reload.await?;
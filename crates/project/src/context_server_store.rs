@@ -5,7 +5,10 @@ use std::{path::Path, sync::Arc};
 
 use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet};
-use context_server::{ContextServer, ContextServerId};
+use context_server::{
+    ContextServer, ContextServerId,
+    types::{ElicitAction, ElicitResponse},
+};
 use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity, actions};
 use registry::ContextServerDescriptorRegistry;
 use settings::{Settings as _, SettingsStore};
@@ -99,6 +102,23 @@ pub enum Event {
         server_id: ContextServerId,
         status: ContextServerStatus,
     },
+    ElicitationRequested(ContextServerElicitation),
+}
+
+/// A context server asking the user for information, with a JSON schema describing it.
+#[derive(Clone, Debug)]
+pub struct ContextServerElicitation {
+    pub server_id: ContextServerId,
+    pub message: String,
+    pub requested_schema: serde_json::Value,
+    response_channel: smol::channel::Sender<ElicitResponse>,
+}
+
+impl ContextServerElicitation {
+    /// Answers the request. Dropping every copy of the request without answering cancels it.
+    pub fn respond(&self, response: ElicitResponse) {
+        self.response_channel.try_send(response).ok();
+    }
 }
 
 impl EventEmitter<Event> for ContextServerStore {}
@@ -284,6 +304,30 @@ impl ContextServerStore {
             self.stop_server(&id, cx).log_err();
         }
 
+        server.set_elicitation_handler(Arc::new({
+            let this = cx.weak_entity();
+            let id = id.clone();
+            move |params, cx| {
+                let (response_channel, response_rx) = smol::channel::bounded(1);
+                let elicitation = ContextServerElicitation {
+                    server_id: id.clone(),
+                    message: params.message,
+                    requested_schema: params.requested_schema,
+                    response_channel,
+                };
+                this.update(cx, |_, cx| {
+                    cx.emit(Event::ElicitationRequested(elicitation));
+                })
+                .ok();
+                cx.background_spawn(async move {
+                    Ok(response_rx.recv().await.unwrap_or(ElicitResponse {
+                        action: ElicitAction::Cancel,
+                        content: None,
+                    }))
+                })
+            }
+        }));
+
         let task = cx.spawn({
             let id = server.id();
             let server = server.clone();
@@ -350,8 +394,10 @@ impl ContextServerStore {
     }
 
     fn is_configuration_valid(&self, configuration: &ContextServerConfiguration) -> bool {
-        // Command must be some when we are running in stdio mode.
-        self.context_server_factory.as_ref().is_some() || configuration.command.is_some()
+        // Either a command or a URL must be set, unless a custom factory creates the servers.
+        self.context_server_factory.as_ref().is_some()
+            || configuration.command.is_some()
+            || configuration.url.is_some()
     }
 
     fn create_context_server(
@@ -361,6 +407,8 @@ impl ContextServerStore {
     ) -> Result<Arc<ContextServer>> {
         if let Some(factory) = self.context_server_factory.as_ref() {
             Ok(factory(id, configuration))
+        } else if let Some(endpoint) = configuration.endpoint()? {
+            Ok(Arc::new(ContextServer::http(id, endpoint)))
         } else {
             let command = configuration
                 .command
@@ -429,7 +477,7 @@ impl ContextServerStore {
             registry.read_with(cx, |registry, _| registry.context_server_descriptors())?
         {
             let config = desired_servers.entry(id.clone()).or_default();
-            if config.command.is_none() {
+            if config.command.is_none() && config.url.is_none() {
                 if let Some(extension_command) = descriptor
                     .command(worktree_store.clone(), &cx)
                     .await
@@ -784,6 +832,7 @@ mod tests {
                     settings: Some(json!({
                         "somevalue": true
                     })),
+                    ..Default::default()
                 },
             )],
         )
@@ -843,6 +892,7 @@ mod tests {
                         settings: Some(json!({
                             "somevalue": false
                         })),
+                        ..Default::default()
                     },
                 )],
                 cx,
@@ -862,6 +912,7 @@ mod tests {
                         settings: Some(json!({
                             "somevalue": false
                         })),
+                        ..Default::default()
                     },
                 )],
                 cx,
@@ -889,6 +940,7 @@ mod tests {
                             settings: Some(json!({
                                 "somevalue": false
                             })),
+                            ..Default::default()
                         },
                     ),
                     (
@@ -898,6 +950,7 @@ mod tests {
                             settings: Some(json!({
                                 "somevalue": true
                             })),
+                            ..Default::default()
                         },
                     ),
                 ],
@@ -922,6 +975,7 @@ mod tests {
                         settings: Some(json!({
                             "somevalue": false
                         })),
+                        ..Default::default()
                     },
                 )],
                 cx,
@@ -1001,6 +1055,7 @@ mod tests {
                         ix += 1;
                         *received_event_count.borrow_mut() += 1;
                     }
+                    Event::ElicitationRequested(_) => {}
                 }
             });
             ServerEvents {
@@ -1039,6 +1094,7 @@ mod tests {
             protocol_version: ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
             server_info: Implementation {
                 name: server_name,
+                title: None,
                 version: "1.0.0".to_string(),
             },
             capabilities: ServerCapabilities::default(),
@@ -6,7 +6,9 @@ pub use breakpoints_in_file::{BreakpointSessionState, BreakpointWithPosition};
 use breakpoints_in_file::{BreakpointsInFile, StatefulBreakpoint};
 use collections::{BTreeMap, HashMap};
 use dap::{StackFrameId, client::SessionId};
-use gpui::{App, AppContext, AsyncApp, Context, Entity, EventEmitter, Subscription, Task};
+use gpui::{
+    App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Subscription, Task,
+};
 use itertools::Itertools;
 use language::{Buffer, BufferSnapshot, proto::serialize_anchor as serialize_text_anchor};
 use rpc::{
@@ -156,6 +158,9 @@ pub struct ActiveStackFrame {
 
 pub struct BreakpointStore {
     breakpoints: BTreeMap<Arc<Path>, BreakpointsInFile>,
+    function_breakpoints: Vec<FunctionBreakpoint>,
+    data_breakpoints: Vec<DataBreakpoint>,
+    watch_expressions: Vec<SharedString>,
     downstream_client: Option<(AnyProtoClient, u64)>,
     active_stack_frame: Option<ActiveStackFrame>,
     // E.g ssh
@@ -170,6 +175,9 @@ impl BreakpointStore {
     pub fn local(worktree_store: Entity<WorktreeStore>, buffer_store: Entity<BufferStore>) -> Self {
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            function_breakpoints: Vec::new(),
+            data_breakpoints: Vec::new(),
+            watch_expressions: Vec::new(),
             mode: BreakpointStoreMode::Local(LocalBreakpointStore {
                 worktree_store,
                 buffer_store,
@@ -182,6 +190,9 @@ impl BreakpointStore {
     pub(crate) fn remote(upstream_project_id: u64, upstream_client: AnyProtoClient) -> Self {
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            function_breakpoints: Vec::new(),
+            data_breakpoints: Vec::new(),
+            watch_expressions: Vec::new(),
             mode: BreakpointStoreMode::Remote(RemoteBreakpointStore {
                 upstream_client,
                 _upstream_project_id: upstream_project_id,
@@ -601,6 +612,131 @@ impl BreakpointStore {
         let breakpoint_paths = self.breakpoints.keys().cloned().collect();
         self.breakpoints.clear();
         cx.emit(BreakpointStoreEvent::BreakpointsCleared(breakpoint_paths));
+        if !self.function_breakpoints.is_empty() {
+            self.function_breakpoints.clear();
+            cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+        }
+        if !self.data_breakpoints.is_empty() {
+            self.data_breakpoints.clear();
+            cx.emit(BreakpointStoreEvent::DataBreakpointsUpdated);
+        }
+    }
+
+    pub fn function_breakpoints(&self) -> &[FunctionBreakpoint] {
+        &self.function_breakpoints
+    }
+
+    /// Adds a breakpoint on the function with the given name, unless there's one already.
+    pub fn add_function_breakpoint(&mut self, name: Arc<str>, cx: &mut Context<Self>) {
+        let name: Arc<str> = name.trim().into();
+        if name.is_empty()
+            || self
+                .function_breakpoints
+                .iter()
+                .any(|breakpoint| breakpoint.name == name)
+        {
+            return;
+        }
+
+        self.function_breakpoints.push(FunctionBreakpoint {
+            name,
+            condition: None,
+            hit_condition: None,
+            state: BreakpointState::Enabled,
+        });
+        cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+        cx.notify();
+    }
+
+    pub fn remove_function_breakpoint(&mut self, name: &str, cx: &mut Context<Self>) {
+        let len = self.function_breakpoints.len();
+        self.function_breakpoints
+            .retain(|breakpoint| breakpoint.name.as_ref() != name);
+        if self.function_breakpoints.len() != len {
+            cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+            cx.notify();
+        }
+    }
+
+    pub fn toggle_function_breakpoint_state(&mut self, name: &str, cx: &mut Context<Self>) {
+        if let Some(breakpoint) = self
+            .function_breakpoints
+            .iter_mut()
+            .find(|breakpoint| breakpoint.name.as_ref() == name)
+        {
+            breakpoint.state = match breakpoint.state {
+                BreakpointState::Enabled => BreakpointState::Disabled,
+                BreakpointState::Disabled => BreakpointState::Enabled,
+            };
+            cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+            cx.notify();
+        }
+    }
+
+    /// Data breakpoints that the debug adapter allows to persist across sessions.
+    ///
+    /// Session-only data breakpoints are kept by their [`Session`](super::session::Session).
+    pub fn data_breakpoints(&self) -> &[DataBreakpoint] {
+        &self.data_breakpoints
+    }
+
+    pub fn add_data_breakpoint(&mut self, breakpoint: DataBreakpoint, cx: &mut Context<Self>) {
+        if self
+            .data_breakpoints
+            .iter()
+            .any(|existing| existing.data_id == breakpoint.data_id)
+        {
+            return;
+        }
+        self.data_breakpoints.push(breakpoint);
+        cx.emit(BreakpointStoreEvent::DataBreakpointsUpdated);
+        cx.notify();
+    }
+
+    pub fn remove_data_breakpoint(&mut self, data_id: &str, cx: &mut Context<Self>) {
+        let len = self.data_breakpoints.len();
+        self.data_breakpoints
+            .retain(|breakpoint| breakpoint.data_id != data_id);
+        if self.data_breakpoints.len() != len {
+            cx.emit(BreakpointStoreEvent::DataBreakpointsUpdated);
+            cx.notify();
+        }
+    }
+
+    pub fn toggle_data_breakpoint(&mut self, data_id: &str, cx: &mut Context<Self>) {
+        if let Some(breakpoint) = self
+            .data_breakpoints
+            .iter_mut()
+            .find(|breakpoint| breakpoint.data_id == data_id)
+        {
+            breakpoint.is_enabled = !breakpoint.is_enabled;
+            cx.emit(BreakpointStoreEvent::DataBreakpointsUpdated);
+            cx.notify();
+        }
+    }
+
+    /// Expressions that every debug session evaluates whenever the debuggee stops.
+    pub fn watch_expressions(&self) -> &[SharedString] {
+        &self.watch_expressions
+    }
+
+    pub fn add_watch_expression(&mut self, expression: SharedString, cx: &mut Context<Self>) {
+        if self.watch_expressions.contains(&expression) {
+            return;
+        }
+        self.watch_expressions.push(expression);
+        cx.emit(BreakpointStoreEvent::WatchExpressionsUpdated);
+        cx.notify();
+    }
+
+    pub fn remove_watch_expression(&mut self, expression: &str, cx: &mut Context<Self>) {
+        let len = self.watch_expressions.len();
+        self.watch_expressions
+            .retain(|existing| existing.as_ref() != expression);
+        if self.watch_expressions.len() != len {
+            cx.emit(BreakpointStoreEvent::WatchExpressionsUpdated);
+            cx.notify();
+        }
     }
 
     pub fn breakpoints<'a>(
@@ -770,6 +906,28 @@ impl BreakpointStore {
             .collect()
     }
 
+    pub fn serialized_debug_state(&self) -> SerializedDebugState {
+        SerializedDebugState {
+            function_breakpoints: self.function_breakpoints.clone(),
+            data_breakpoints: self.data_breakpoints.clone(),
+            watch_expressions: self.watch_expressions.clone(),
+        }
+    }
+
+    pub fn with_serialized_debug_state(
+        &mut self,
+        state: SerializedDebugState,
+        cx: &mut Context<BreakpointStore>,
+    ) {
+        self.function_breakpoints = state.function_breakpoints;
+        self.data_breakpoints = state.data_breakpoints;
+        self.watch_expressions = state.watch_expressions;
+        cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+        cx.emit(BreakpointStoreEvent::DataBreakpointsUpdated);
+        cx.emit(BreakpointStoreEvent::WatchExpressionsUpdated);
+        cx.notify();
+    }
+
     pub fn with_serialized_breakpoints(
         &self,
         breakpoints: BTreeMap<Arc<Path>, Vec<SourceBreakpoint>>,
@@ -872,6 +1030,9 @@ pub enum BreakpointStoreEvent {
     ClearDebugLines,
     BreakpointsUpdated(Arc<Path>, BreakpointUpdatedReason),
     BreakpointsCleared(Vec<Arc<Path>>),
+    FunctionBreakpointsUpdated,
+    DataBreakpointsUpdated,
+    WatchExpressionsUpdated,
 }
 
 impl EventEmitter<BreakpointStoreEvent> for BreakpointStore {}
@@ -1033,3 +1194,54 @@ impl From<SourceBreakpoint> for dap::SourceBreakpoint {
         }
     }
 }
+
+/// Breakpoint that stops the debuggee whenever a function with the given name is entered.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct FunctionBreakpoint {
+    pub name: Arc<str>,
+    pub condition: Option<Arc<str>>,
+    pub hit_condition: Option<Arc<str>>,
+    pub state: BreakpointState,
+}
+
+impl From<FunctionBreakpoint> for dap::FunctionBreakpoint {
+    fn from(bp: FunctionBreakpoint) -> Self {
+        Self {
+            name: String::from(bp.name.as_ref()),
+            condition: bp
+                .condition
+                .map(|condition| String::from(condition.as_ref())),
+            hit_condition: bp
+                .hit_condition
+                .map(|hit_condition| String::from(hit_condition.as_ref())),
+        }
+    }
+}
+
+/// A watchpoint on a piece of data, as identified by the debug adapter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataBreakpoint {
+    pub data_id: String,
+    pub description: SharedString,
+    pub access_type: Option<dap::DataBreakpointAccessType>,
+    pub is_enabled: bool,
+}
+
+impl From<DataBreakpoint> for dap::DataBreakpoint {
+    fn from(bp: DataBreakpoint) -> Self {
+        Self {
+            data_id: bp.data_id,
+            access_type: bp.access_type,
+            condition: None,
+            hit_condition: None,
+        }
+    }
+}
+
+/// The breakpoints and watch expressions that aren't tied to a position in a file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SerializedDebugState {
+    pub function_breakpoints: Vec<FunctionBreakpoint>,
+    pub data_breakpoints: Vec<DataBreakpoint>,
+    pub watch_expressions: Vec<SharedString>,
+}
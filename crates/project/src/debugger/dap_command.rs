@@ -1704,6 +1704,199 @@ impl LocalDapCommand for SetExceptionBreakpoints {
     }
 }
 
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct SetFunctionBreakpoints {
+    pub(super) breakpoints: Vec<dap::FunctionBreakpoint>,
+}
+
+impl LocalDapCommand for SetFunctionBreakpoints {
+    type Response = Vec<dap::Breakpoint>;
+    type DapRequest = dap::requests::SetFunctionBreakpoints;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_function_breakpoints
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetFunctionBreakpointsArguments {
+            breakpoints: self.breakpoints.clone(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.breakpoints)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct SetDataBreakpoints {
+    pub(super) breakpoints: Vec<dap::DataBreakpoint>,
+}
+
+impl LocalDapCommand for SetDataBreakpoints {
+    type Response = Vec<dap::Breakpoint>;
+    type DapRequest = dap::requests::SetDataBreakpoints;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities.supports_data_breakpoints.unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetDataBreakpointsArguments {
+            breakpoints: self.breakpoints.clone(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.breakpoints)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(super) struct DataBreakpointInfoCommand {
+    pub(super) variables_reference: Option<u64>,
+    pub(super) name: String,
+    pub(super) frame_id: Option<u64>,
+}
+
+impl LocalDapCommand for DataBreakpointInfoCommand {
+    type Response = dap::DataBreakpointInfoResponse;
+    type DapRequest = dap::requests::DataBreakpointInfo;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities.supports_data_breakpoints.unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::DataBreakpointInfoArguments {
+            variables_reference: self.variables_reference,
+            name: self.name.clone(),
+            frame_id: self.frame_id,
+            bytes: None,
+            as_address: None,
+            mode: None,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(super) struct DisassembleCommand {
+    pub(super) memory_reference: String,
+    pub(super) instruction_offset: i64,
+    pub(super) instruction_count: i64,
+}
+
+impl LocalDapCommand for DisassembleCommand {
+    type Response = Vec<dap::DisassembledInstruction>;
+    type DapRequest = dap::requests::Disassemble;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_disassemble_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::DisassembleArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: None,
+            instruction_offset: Some(self.instruction_offset),
+            instruction_count: self.instruction_count,
+            resolve_symbols: Some(true),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.instructions)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(super) struct ReadMemoryCommand {
+    pub(super) memory_reference: String,
+    pub(super) offset: i64,
+    pub(super) count: u64,
+}
+
+impl LocalDapCommand for ReadMemoryCommand {
+    type Response = dap::ReadMemoryResponse;
+    type DapRequest = dap::requests::ReadMemory;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_read_memory_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::ReadMemoryArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: Some(self.offset),
+            count: self.count,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(super) struct WriteMemoryCommand {
+    pub(super) memory_reference: String,
+    pub(super) offset: i64,
+    /// The bytes to write, base64-encoded.
+    pub(super) data: String,
+}
+
+impl LocalDapCommand for WriteMemoryCommand {
+    type Response = dap::WriteMemoryResponse;
+    type DapRequest = dap::requests::WriteMemory;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_write_memory_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::WriteMemoryArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: Some(self.offset),
+            allow_partial: Some(false),
+            data: self.data.clone(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message)
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub(super) struct LocationsCommand {
     pub(super) reference: u64,
@@ -1,18 +1,22 @@
 use crate::debugger::breakpoint_store::BreakpointSessionState;
 
 use super::breakpoint_store::{
-    BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason, SourceBreakpoint,
+    BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason, DataBreakpoint,
+    SourceBreakpoint,
 };
 use super::dap_command::{
-    self, Attach, ConfigurationDone, ContinueCommand, DapCommand, DisconnectCommand,
-    EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
-    ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetExceptionBreakpoints, SetVariableValueCommand, StackTraceCommand,
-    StepBackCommand, StepCommand, StepInCommand, StepOutCommand, TerminateCommand,
-    TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    self, Attach, ConfigurationDone, ContinueCommand, DapCommand, DataBreakpointInfoCommand,
+    DisassembleCommand, DisconnectCommand, EvaluateCommand, Initialize, Launch,
+    LoadedSourcesCommand, LocalDapCommand, LocationsCommand, ModulesCommand, NextCommand,
+    PauseCommand, ReadMemoryCommand, RestartCommand, RestartStackFrameCommand, ScopesCommand,
+    SetDataBreakpoints, SetExceptionBreakpoints, SetFunctionBreakpoints, SetVariableValueCommand,
+    StackTraceCommand, StepBackCommand, StepCommand, StepInCommand, StepOutCommand,
+    TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    WriteMemoryCommand,
 };
 use super::dap_store::DapStore;
 use anyhow::{Context as _, Result, anyhow};
+use base64::Engine as _;
 use collections::{HashMap, HashSet, IndexMap, IndexSet};
 use dap::adapters::{DebugAdapterBinary, DebugAdapterName};
 use dap::messages::Response;
@@ -296,6 +300,34 @@ impl LocalMode {
         self.request(arg)
     }
 
+    fn send_function_breakpoints(
+        &self,
+        ignore_breakpoints: bool,
+        breakpoint_store: &Entity<BreakpointStore>,
+        cx: &App,
+    ) -> Task<Result<Vec<dap::Breakpoint>>> {
+        let breakpoints = if ignore_breakpoints {
+            vec![]
+        } else {
+            breakpoint_store
+                .read(cx)
+                .function_breakpoints()
+                .iter()
+                .filter(|bp| bp.state.is_enabled())
+                .cloned()
+                .map(Into::into)
+                .collect()
+        };
+        self.request(SetFunctionBreakpoints { breakpoints })
+    }
+
+    fn send_data_breakpoints(
+        &self,
+        breakpoints: Vec<dap::DataBreakpoint>,
+    ) -> Task<Result<Vec<dap::Breakpoint>>> {
+        self.request(SetDataBreakpoints { breakpoints })
+    }
+
     fn send_source_breakpoints(
         &self,
         ignore_breakpoints: bool,
@@ -404,6 +436,8 @@ impl LocalMode {
         let supports_exception_filters = capabilities
             .supports_exception_filter_options
             .unwrap_or_default();
+        let supports_function_breakpoints = SetFunctionBreakpoints::is_supported(capabilities);
+        let supports_data_breakpoints = SetDataBreakpoints::is_supported(capabilities);
         let this = self.clone();
         let worktree = self.worktree().clone();
         let configuration_sequence = cx.spawn({
@@ -442,6 +476,28 @@ impl LocalMode {
                     }
                 })?;
 
+                if supports_function_breakpoints {
+                    cx.update(|cx| this.send_function_breakpoints(false, &breakpoint_store, cx))?
+                        .await
+                        .log_err();
+                }
+
+                if supports_data_breakpoints {
+                    let breakpoints = cx.update(|cx| {
+                        breakpoint_store
+                            .read(cx)
+                            .data_breakpoints()
+                            .iter()
+                            .filter(|breakpoint| breakpoint.is_enabled)
+                            .cloned()
+                            .map(Into::into)
+                            .collect::<Vec<_>>()
+                    })?;
+                    if !breakpoints.is_empty() {
+                        this.send_data_breakpoints(breakpoints).await.log_err();
+                    }
+                }
+
                 this.send_exception_breakpoints(exception_filters, supports_exception_filters)
                     .await
                     .ok();
@@ -585,9 +641,42 @@ pub struct Session {
     pub(crate) breakpoint_store: Entity<BreakpointStore>,
     ignore_breakpoints: bool,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
+    data_breakpoints: IndexMap<String, DataBreakpoint>,
+    watchers: IndexMap<SharedString, Watcher>,
     background_tasks: Vec<Task<()>>,
 }
 
+/// A chunk of the debuggee's memory, as returned by a `readMemory` request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryContents {
+    /// The address of the first byte of `data`.
+    pub address: String,
+    pub data: Vec<u8>,
+    /// The number of bytes after `data` that couldn't be read.
+    pub unreadable_bytes: u64,
+}
+
+/// An expression that's re-evaluated whenever the debuggee stops.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Watcher {
+    pub expression: SharedString,
+    /// The last result of evaluating the expression, or `None` if it hasn't been evaluated yet.
+    pub value: Option<SharedString>,
+    pub variables_reference: VariableReference,
+    pub is_error: bool,
+}
+
+impl Watcher {
+    fn new(expression: SharedString) -> Self {
+        Self {
+            expression,
+            value: None,
+            variables_reference: 0,
+            is_error: false,
+        }
+    }
+}
+
 trait CacheableCommand: Any + Send + Sync {
     fn dyn_eq(&self, rhs: &dyn CacheableCommand) -> bool;
     fn dyn_hash(&self, hasher: &mut dyn Hasher);
@@ -667,6 +756,9 @@ pub enum SessionEvent {
     Stopped(Option<ThreadId>),
     StackTrace,
     Variables,
+    Watchers,
+    /// The contents of the debuggee's memory changed, so memory views need to be re-read.
+    Memory,
     Threads,
     InvalidateInlineValue,
     CapabilitiesLoaded,
@@ -721,11 +813,41 @@ impl Session {
                         local.unset_breakpoints_from_paths(paths, cx).detach();
                     }
                 }
+                BreakpointStoreEvent::FunctionBreakpointsUpdated => {
+                    if !SetFunctionBreakpoints::is_supported(&this.capabilities) {
+                        return;
+                    }
+                    if let Some(local) = (!this.ignore_breakpoints)
+                        .then(|| this.as_local_mut())
+                        .flatten()
+                    {
+                        local
+                            .send_function_breakpoints(false, &store, cx)
+                            .detach_and_log_err(cx);
+                    }
+                }
+                BreakpointStoreEvent::DataBreakpointsUpdated => {
+                    if SetDataBreakpoints::is_supported(&this.capabilities)
+                        && this.as_local().is_some()
+                    {
+                        this.send_data_breakpoints(cx);
+                    }
+                }
+                BreakpointStoreEvent::WatchExpressionsUpdated => {
+                    this.sync_watchers(cx);
+                }
                 BreakpointStoreEvent::SetDebugLine | BreakpointStoreEvent::ClearDebugLines => {}
             })
             .detach();
             cx.on_app_quit(Self::on_app_quit).detach();
 
+            let watchers = breakpoint_store
+                .read(cx)
+                .watch_expressions()
+                .iter()
+                .map(|expression| (expression.clone(), Watcher::new(expression.clone())))
+                .collect();
+
             let this = Self {
                 mode: Mode::Building,
                 id: session_id,
@@ -747,6 +869,8 @@ impl Session {
                 ignore_breakpoints: false,
                 breakpoint_store,
                 exception_breakpoints: Default::default(),
+                data_breakpoints: Default::default(),
+                watchers,
                 label,
                 adapter,
             };
@@ -1302,7 +1426,9 @@ impl Session {
                 self.capabilities = self.capabilities.merge(event.capabilities);
                 cx.notify();
             }
-            Events::Memory(_) => {}
+            Events::Memory(_) => {
+                cx.emit(SessionEvent::Memory);
+            }
             Events::Process(_) => {}
             Events::ProgressEnd(_) => {}
             Events::ProgressStart(_) => {}
@@ -1525,6 +1651,11 @@ impl Session {
         self.ignore_breakpoints = ignore;
 
         if let Some(local) = self.as_local() {
+            if SetFunctionBreakpoints::is_supported(&self.capabilities) {
+                local
+                    .send_function_breakpoints(ignore, &self.breakpoint_store, cx)
+                    .detach_and_log_err(cx);
+            }
             local.send_source_breakpoints(ignore, &self.breakpoint_store, cx)
         } else {
             // todo(debugger): We need to propagate this change to downstream sessions and send a message to upstream sessions
@@ -1565,6 +1696,108 @@ impl Session {
         }
     }
 
+    /// The data breakpoints persisted in the [`BreakpointStore`], followed by the ones that are
+    /// only valid within this session.
+    pub fn data_breakpoints<'a>(&'a self, cx: &'a App) -> impl Iterator<Item = &'a DataBreakpoint> {
+        self.breakpoint_store
+            .read(cx)
+            .data_breakpoints()
+            .iter()
+            .chain(self.data_breakpoints.values())
+    }
+
+    /// Asks the adapter whether the given variable can be watched, and if so, sets a data
+    /// breakpoint that stops whenever the variable is written to.
+    pub fn add_data_breakpoint(
+        &mut self,
+        variables_reference: VariableReference,
+        name: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if !DataBreakpointInfoCommand::is_supported(&self.capabilities) {
+            return Task::ready(Err(anyhow!(
+                "Debug adapter doesn't support data breakpoints"
+            )));
+        }
+        let Some(local) = self.as_local() else {
+            return Task::ready(Err(anyhow!("Data breakpoints require a local session")));
+        };
+        let info = local.request(DataBreakpointInfoCommand {
+            variables_reference: Some(variables_reference),
+            name,
+            frame_id: None,
+        });
+        cx.spawn(async move |this, cx| {
+            let info = info.await?;
+            let data_id = info
+                .data_id
+                .with_context(|| format!("Can't set a data breakpoint: {}", info.description))?;
+            let access_type = info.access_types.and_then(|access_types| {
+                access_types
+                    .contains(&dap::DataBreakpointAccessType::Write)
+                    .then_some(dap::DataBreakpointAccessType::Write)
+            });
+            let breakpoint = DataBreakpoint {
+                data_id,
+                description: info.description.into(),
+                access_type,
+                is_enabled: true,
+            };
+            this.update(cx, |this, cx| {
+                // Data IDs the adapter can't persist are only valid within this session.
+                if info.can_persist == Some(true) {
+                    this.breakpoint_store.update(cx, |store, cx| {
+                        store.add_data_breakpoint(breakpoint, cx);
+                    });
+                } else {
+                    this.data_breakpoints
+                        .insert(breakpoint.data_id.clone(), breakpoint);
+                    this.send_data_breakpoints(cx);
+                }
+                cx.notify();
+            })
+        })
+    }
+
+    pub fn toggle_data_breakpoint(&mut self, data_id: &str, cx: &mut Context<Self>) {
+        if let Some(breakpoint) = self.data_breakpoints.get_mut(data_id) {
+            breakpoint.is_enabled = !breakpoint.is_enabled;
+            self.send_data_breakpoints(cx);
+            cx.notify();
+        } else {
+            self.breakpoint_store.update(cx, |store, cx| {
+                store.toggle_data_breakpoint(data_id, cx);
+            });
+        }
+    }
+
+    pub fn remove_data_breakpoint(&mut self, data_id: &str, cx: &mut Context<Self>) {
+        if self.data_breakpoints.shift_remove(data_id).is_some() {
+            self.send_data_breakpoints(cx);
+            cx.notify();
+        } else {
+            self.breakpoint_store.update(cx, |store, cx| {
+                store.remove_data_breakpoint(data_id, cx);
+            });
+        }
+    }
+
+    fn send_data_breakpoints(&mut self, cx: &App) {
+        if let Some(local) = self.as_local() {
+            let breakpoints = self
+                .data_breakpoints(cx)
+                .filter(|breakpoint| breakpoint.is_enabled)
+                .cloned()
+                .map(Into::into)
+                .collect();
+            local
+                .send_data_breakpoints(breakpoints)
+                .detach_and_log_err(cx);
+        } else {
+            debug_assert!(false, "Not implemented");
+        }
+    }
+
     pub fn breakpoints_enabled(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -2121,6 +2354,197 @@ impl Session {
         })
     }
 
+    pub fn watchers(&self) -> impl Iterator<Item = &Watcher> {
+        self.watchers.values()
+    }
+
+    /// Starts watching the given expression, evaluating it in the context of the given frame.
+    ///
+    /// The expression is kept in the [`BreakpointStore`], so that other sessions watch it too.
+    pub fn add_watcher(
+        &mut self,
+        expression: SharedString,
+        frame_id: Option<u64>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.watchers.contains_key(&expression) {
+            return;
+        }
+        self.watchers
+            .insert(expression.clone(), Watcher::new(expression.clone()));
+        self.breakpoint_store.update(cx, |store, cx| {
+            store.add_watch_expression(expression.clone(), cx);
+        });
+        self.evaluate_watcher(expression, frame_id, cx);
+        cx.emit(SessionEvent::Watchers);
+        cx.notify();
+    }
+
+    pub fn remove_watcher(&mut self, expression: &str, cx: &mut Context<Self>) {
+        self.breakpoint_store.update(cx, |store, cx| {
+            store.remove_watch_expression(expression, cx);
+        });
+        if self.watchers.shift_remove(expression).is_some() {
+            cx.emit(SessionEvent::Watchers);
+            cx.notify();
+        }
+    }
+
+    /// Brings the watchers in line with the watch expressions in the [`BreakpointStore`].
+    ///
+    /// Expressions added elsewhere are evaluated the next time the debuggee stops.
+    fn sync_watchers(&mut self, cx: &mut Context<Self>) {
+        let store = self.breakpoint_store.read(cx);
+        if self.watchers.keys().eq(store.watch_expressions()) {
+            return;
+        }
+        self.watchers = store
+            .watch_expressions()
+            .iter()
+            .map(|expression| {
+                let watcher = self
+                    .watchers
+                    .get(expression)
+                    .cloned()
+                    .unwrap_or_else(|| Watcher::new(expression.clone()));
+                (expression.clone(), watcher)
+            })
+            .collect();
+        cx.emit(SessionEvent::Watchers);
+        cx.notify();
+    }
+
+    /// Re-evaluates every watched expression, e.g. after the debuggee stopped or another
+    /// frame got selected.
+    pub fn refresh_watchers(&mut self, frame_id: Option<u64>, cx: &mut Context<Self>) {
+        let expressions = self.watchers.keys().cloned().collect::<Vec<_>>();
+        for expression in expressions {
+            self.evaluate_watcher(expression, frame_id, cx);
+        }
+    }
+
+    fn evaluate_watcher(
+        &mut self,
+        expression: SharedString,
+        frame_id: Option<u64>,
+        cx: &mut Context<Self>,
+    ) {
+        let request = self.mode.request_dap(EvaluateCommand {
+            expression: expression.to_string(),
+            context: Some(EvaluateArgumentsContext::Watch),
+            frame_id,
+            source: None,
+        });
+        cx.spawn(async move |this, cx| {
+            let response = request.await;
+            this.update(cx, |this, cx| {
+                let Some(watcher) = this.watchers.get_mut(&expression) else {
+                    return;
+                };
+                match response {
+                    Ok(response) => {
+                        watcher.value = Some(response.result.into());
+                        watcher.variables_reference = response.variables_reference;
+                        watcher.is_error = false;
+                    }
+                    Err(error) => {
+                        watcher.value = Some(error.to_string().into());
+                        watcher.variables_reference = 0;
+                        watcher.is_error = true;
+                    }
+                }
+                cx.emit(SessionEvent::Watchers);
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Disassembles `instruction_count` instructions, starting `instruction_offset`
+    /// instructions away from the given memory reference.
+    pub fn disassemble(
+        &mut self,
+        memory_reference: String,
+        instruction_offset: i64,
+        instruction_count: i64,
+    ) -> Task<Result<Vec<dap::DisassembledInstruction>>> {
+        if !DisassembleCommand::is_supported(&self.capabilities) {
+            return Task::ready(Err(anyhow!("Debug adapter doesn't support disassembly")));
+        }
+        let Some(local) = self.as_local() else {
+            return Task::ready(Err(anyhow!("Disassembly requires a local session")));
+        };
+        local.request(DisassembleCommand {
+            memory_reference,
+            instruction_offset,
+            instruction_count,
+        })
+    }
+
+    pub fn read_memory(
+        &mut self,
+        memory_reference: String,
+        offset: i64,
+        count: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<MemoryContents>> {
+        if !ReadMemoryCommand::is_supported(&self.capabilities) {
+            return Task::ready(Err(anyhow!("Debug adapter doesn't support reading memory")));
+        }
+        let Some(local) = self.as_local() else {
+            return Task::ready(Err(anyhow!("Reading memory requires a local session")));
+        };
+        let request = local.request(ReadMemoryCommand {
+            memory_reference,
+            offset,
+            count,
+        });
+        cx.background_spawn(async move {
+            let response = request.await?;
+            let data = match response.data {
+                Some(data) => base64::engine::general_purpose::STANDARD
+                    .decode(data)
+                    .context("decoding memory contents")?,
+                None => Vec::new(),
+            };
+            Ok(MemoryContents {
+                address: response.address,
+                data,
+                unreadable_bytes: response.unreadable_bytes.unwrap_or_default(),
+            })
+        })
+    }
+
+    pub fn write_memory(
+        &mut self,
+        memory_reference: String,
+        offset: i64,
+        data: &[u8],
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if !WriteMemoryCommand::is_supported(&self.capabilities) {
+            return Task::ready(Err(anyhow!("Debug adapter doesn't support writing memory")));
+        }
+        let Some(local) = self.as_local() else {
+            return Task::ready(Err(anyhow!("Writing memory requires a local session")));
+        };
+        let request = local.request(WriteMemoryCommand {
+            memory_reference,
+            offset,
+            data: base64::engine::general_purpose::STANDARD.encode(data),
+        });
+        cx.spawn(async move |this, cx| {
+            request.await?;
+            this.update(cx, |this, cx| {
+                this.invalidate_command_type::<VariablesCommand>();
+                cx.emit(SessionEvent::Memory);
+                cx.emit(SessionEvent::Variables);
+                cx.notify();
+            })
+        })
+    }
+
     pub fn location(
         &mut self,
         reference: u64,
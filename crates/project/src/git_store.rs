@@ -25,7 +25,7 @@ use git::{
     repository::{
         Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, GitRepository,
         GitRepositoryCheckpoint, PushOptions, Remote, RemoteCommandOutput, RepoPath, ResetMode,
-        UpstreamTrackingStatus,
+        StashEntry, UpstreamTrackingStatus,
     },
     status::{
         FileStatus, GitSummary, StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode,
@@ -35,6 +35,7 @@ use gpui::{
     App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Subscription, Task,
     WeakEntity,
 };
+use jj::{JujutsuRepository as _, JujutsuStore, RealJujutsuRepository};
 use language::{
     Buffer, BufferEvent, Language, LanguageRegistry,
     proto::{deserialize_version, serialize_version},
@@ -78,6 +79,8 @@ pub struct GitStore {
         HashMap<(BufferId, DiffKind), Shared<Task<Result<Entity<BufferDiff>, Arc<anyhow::Error>>>>>,
     diffs: HashMap<BufferId, Entity<BufferGitState>>,
     shared_diffs: HashMap<proto::PeerId, HashMap<BufferId, SharedDiffs>>,
+    /// The Jujutsu workspaces that local worktrees belong to.
+    jj_stores: Vec<Entity<JujutsuStore>>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -245,6 +248,9 @@ pub struct RepositorySnapshot {
     pub head_commit: Option<CommitDetails>,
     pub scan_id: u64,
     pub merge: MergeDetails,
+    /// Whether the repository is colocated with a Jujutsu workspace, in which case jj
+    /// owns `HEAD` and the working-copy commit rather than the user.
+    pub is_jj_colocated: bool,
 }
 
 type JobId = u64;
@@ -283,6 +289,7 @@ pub enum RepositoryState {
     Local {
         backend: Arc<dyn GitRepository>,
         environment: Arc<HashMap<String, String>>,
+        fs: Arc<dyn Fs>,
     },
     Remote {
         project_id: ProjectId,
@@ -308,6 +315,7 @@ pub enum GitStoreEvent {
     IndexWriteError(anyhow::Error),
     JobsUpdated,
     ConflictsUpdated,
+    JujutsuStoresChanged,
 }
 
 impl EventEmitter<RepositoryEvent> for Repository {}
@@ -405,6 +413,7 @@ impl GitStore {
             loading_diffs: HashMap::default(),
             shared_diffs: HashMap::default(),
             diffs: HashMap::default(),
+            jj_stores: Vec::new(),
         }
     }
 
@@ -424,6 +433,10 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_show);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
         client.add_entity_request_handler(Self::handle_checkout_files);
+        client.add_entity_request_handler(Self::handle_stash_paths);
+        client.add_entity_request_handler(Self::handle_get_stash_entries);
+        client.add_entity_request_handler(Self::handle_stash_apply);
+        client.add_entity_request_handler(Self::handle_stash_drop);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
         client.add_entity_request_handler(Self::handle_set_index_text);
         client.add_entity_request_handler(Self::handle_askpass);
@@ -1134,10 +1147,76 @@ impl GitStore {
                 );
                 self.local_worktree_git_repos_changed(worktree, changed_repos, cx);
             }
+            WorktreeStoreEvent::WorktreeAdded(worktree) => {
+                if worktree.read(cx).is_visible() {
+                    self.discover_jj_workspace(worktree.read(cx).abs_path(), fs.clone(), cx);
+                }
+            }
+            WorktreeStoreEvent::WorktreeRemoved(..) => {
+                let worktree_paths = worktree_store
+                    .read(cx)
+                    .visible_worktrees(cx)
+                    .map(|worktree| worktree.read(cx).abs_path())
+                    .collect::<Vec<_>>();
+                let jj_store_count = self.jj_stores.len();
+                self.jj_stores.retain(|jj_store| {
+                    let workspace_root = jj_store.read(cx).repository().workspace_root();
+                    worktree_paths
+                        .iter()
+                        .any(|path| path.starts_with(workspace_root))
+                });
+                if self.jj_stores.len() != jj_store_count {
+                    cx.emit(GitStoreEvent::JujutsuStoresChanged);
+                }
+            }
             _ => {}
         }
     }
 
+    /// Loads the Jujutsu workspace that contains the given worktree, if any.
+    fn discover_jj_workspace(
+        &mut self,
+        worktree_abs_path: Arc<Path>,
+        fs: Arc<dyn Fs>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.jj_store_for_path(&worktree_abs_path, cx).is_some() {
+            return;
+        }
+        let executor = cx.background_executor().clone();
+        cx.spawn(async move |this, cx| {
+            let mut workspace_root = None;
+            for ancestor in worktree_abs_path.ancestors() {
+                let metadata = fs.metadata(&ancestor.join(".jj")).await.ok().flatten();
+                if metadata.map_or(false, |metadata| metadata.is_dir) {
+                    workspace_root = Some(ancestor.to_path_buf());
+                    break;
+                }
+            }
+            let Some(workspace_root) = workspace_root else {
+                return Ok(());
+            };
+            let repository = cx
+                .background_spawn(async move {
+                    RealJujutsuRepository::new(&workspace_root, executor)
+                        .with_context(|| format!("loading jj workspace at {workspace_root:?}"))
+                })
+                .await?;
+            this.update(cx, |this, cx| {
+                let workspace_root = repository.workspace_root();
+                if this.jj_stores.iter().any(|jj_store| {
+                    jj_store.read(cx).repository().workspace_root() == workspace_root
+                }) {
+                    return;
+                }
+                let jj_store = cx.new(|cx| JujutsuStore::new(Arc::new(repository), cx));
+                this.jj_stores.push(jj_store);
+                cx.emit(GitStoreEvent::JujutsuStoresChanged);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn on_repository_event(
         &mut self,
         repo: Entity<Repository>,
@@ -1395,6 +1474,27 @@ impl GitStore {
         &self.repositories
     }
 
+    /// The Jujutsu workspaces that the project's local worktrees belong to.
+    pub fn jj_stores(&self) -> &[Entity<JujutsuStore>] {
+        &self.jj_stores
+    }
+
+    /// Returns the Jujutsu workspace containing the given absolute path, if any.
+    pub fn jj_store_for_path(&self, abs_path: &Path, cx: &App) -> Option<Entity<JujutsuStore>> {
+        self.jj_stores
+            .iter()
+            .filter(|jj_store| jj_store.read(cx).contains_path(abs_path))
+            .max_by_key(|jj_store| {
+                jj_store
+                    .read(cx)
+                    .repository()
+                    .workspace_root()
+                    .components()
+                    .count()
+            })
+            .cloned()
+    }
+
     pub fn status_for_buffer_id(&self, buffer_id: BufferId, cx: &App) -> Option<FileStatus> {
         let (repo, path) = self.repository_and_path_for_buffer_id(buffer_id, cx)?;
         let status = repo.read(cx).snapshot.status_for_path(&path)?;
@@ -1921,6 +2021,86 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_stash_paths(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitStashPaths>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|s| RepoPath::from_str(s))
+            .collect();
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.stash_paths(paths, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_get_stash_entries(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetStashEntries>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitStashEntriesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_entries()
+            })?
+            .await??;
+        Ok(proto::GitStashEntriesResponse {
+            entries: entries
+                .into_iter()
+                .map(|entry| proto::GitStashEntry {
+                    index: entry.index as u64,
+                    sha: entry.sha.to_string(),
+                    message: entry.message.to_string(),
+                    timestamp: entry.timestamp,
+                })
+                .collect(),
+        })
+    }
+
+    async fn handle_stash_apply(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitStashApply>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_apply(envelope.payload.index as usize, envelope.payload.pop)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_stash_drop(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitStashDrop>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_drop(envelope.payload.index as usize)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_open_commit_message_buffer(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::OpenCommitMessageBuffer>,
@@ -2557,6 +2737,7 @@ impl RepositorySnapshot {
             head_commit: None,
             scan_id: 0,
             merge: Default::default(),
+            is_jj_colocated: false,
         }
     }
 
@@ -2582,6 +2763,7 @@ impl RepositorySnapshot {
             entry_ids: vec![self.id.to_proto()],
             scan_id: self.scan_id,
             is_last_update: true,
+            is_jj_colocated: self.is_jj_colocated,
         }
     }
 
@@ -2644,6 +2826,7 @@ impl RepositorySnapshot {
             entry_ids: vec![],
             scan_id: self.scan_id,
             is_last_update: true,
+            is_jj_colocated: self.is_jj_colocated,
         }
     }
 
@@ -3203,6 +3386,130 @@ impl Repository {
         )
     }
 
+    /// Stashes the changes to the given paths, or all changes (including untracked files)
+    /// if `paths` is empty.
+    pub fn stash_paths(
+        &mut self,
+        paths: Vec<RepoPath>,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+
+        self.send_job(Some("git stash".into()), move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.stash_paths(paths, environment.clone()).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitStashPaths {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            paths: paths
+                                .into_iter()
+                                .map(|p| p.to_string_lossy().to_string())
+                                .collect(),
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn stash_entries(&mut self) -> oneshot::Receiver<Result<Vec<StashEntry>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.stash_entries().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitGetStashEntries {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(response
+                        .entries
+                        .into_iter()
+                        .map(|entry| StashEntry {
+                            index: entry.index as usize,
+                            sha: entry.sha.into(),
+                            message: entry.message.into(),
+                            timestamp: entry.timestamp,
+                        })
+                        .collect())
+                }
+            }
+        })
+    }
+
+    pub fn stash_apply(&mut self, index: usize, pop: bool) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let job_description = if pop {
+            "git stash pop"
+        } else {
+            "git stash apply"
+        };
+
+        self.send_job(
+            Some(job_description.into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.stash_apply(index, pop, environment.clone()).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitStashApply {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                index: index as u64,
+                                pop,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn stash_drop(&mut self, index: usize) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+
+        self.send_job(
+            Some("git stash drop".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.stash_drop(index, environment.clone()).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitStashDrop {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                index: index as u64,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
     pub fn reset(
         &mut self,
         commit: String,
@@ -3949,6 +4256,7 @@ impl Repository {
             .map(proto_to_commit_details);
 
         self.snapshot.merge.conflicted_paths = conflicted_paths;
+        self.snapshot.is_jj_colocated = update.is_jj_colocated;
 
         let edits = update
             .removed_statuses
@@ -4018,7 +4326,7 @@ impl Repository {
                 let Some(this) = this.upgrade() else {
                     return Ok(());
                 };
-                let RepositoryState::Local { backend, .. } = state else {
+                let RepositoryState::Local { backend, fs, .. } = state else {
                     bail!("not a local repository")
                 };
                 let (snapshot, events) = this
@@ -4028,6 +4336,7 @@ impl Repository {
                             this.work_directory_abs_path.clone(),
                             this.snapshot.clone(),
                             backend.clone(),
+                            fs.clone(),
                         )
                     })?
                     .await?;
@@ -4071,9 +4380,12 @@ impl Repository {
                     HashMap::default()
                 });
             let backend = cx
-                .background_spawn(async move {
-                    fs.open_repo(&dot_git_abs_path)
-                        .with_context(|| format!("opening repository at {dot_git_abs_path:?}"))
+                .background_spawn({
+                    let fs = fs.clone();
+                    async move {
+                        fs.open_repo(&dot_git_abs_path)
+                            .with_context(|| format!("opening repository at {dot_git_abs_path:?}"))
+                    }
                 })
                 .await?;
 
@@ -4089,6 +4401,7 @@ impl Repository {
             let state = RepositoryState::Local {
                 backend,
                 environment: Arc::new(environment),
+                fs,
             };
             let mut jobs = VecDeque::new();
             loop {
@@ -4534,6 +4847,7 @@ async fn compute_snapshot(
     work_directory_abs_path: Arc<Path>,
     prev_snapshot: RepositorySnapshot,
     backend: Arc<dyn GitRepository>,
+    fs: Arc<dyn Fs>,
 ) -> Result<(RepositorySnapshot, Vec<RepositoryEvent>)> {
     let mut events = Vec::new();
     let branches = backend.branches().await?;
@@ -4553,9 +4867,17 @@ async fn compute_snapshot(
         MergeDetails::load(&backend, &statuses_by_path, &prev_snapshot).await?;
     log::debug!("new merge details (changed={merge_heads_changed:?}): {merge_details:?}");
 
+    let is_jj_colocated = fs
+        .metadata(&work_directory_abs_path.join(".jj"))
+        .await
+        .ok()
+        .flatten()
+        .map_or(false, |metadata| metadata.is_dir);
+
     if merge_heads_changed
         || branch != prev_snapshot.branch
         || statuses_by_path != prev_snapshot.statuses_by_path
+        || is_jj_colocated != prev_snapshot.is_jj_colocated
     {
         events.push(RepositoryEvent::Updated { full_scan: true });
     }
@@ -4580,6 +4902,7 @@ async fn compute_snapshot(
         branch,
         head_commit,
         merge: merge_details,
+        is_jj_colocated,
     };
 
     Ok((snapshot, events))
@@ -1,8 +1,9 @@
+mod hierarchy;
 mod signature_help;
 
 use crate::{
-    CodeAction, CompletionSource, CoreCompletion, DocumentHighlight, DocumentSymbol, Hover,
-    HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel, InlayHintLabelPart,
+    CodeAction, CompletionSource, CoreCompletion, DocumentHighlight, DocumentLink, DocumentSymbol,
+    Hover, HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel, InlayHintLabelPart,
     InlayHintLabelPartTooltip, InlayHintTooltip, Location, LocationLink, LspAction, MarkupContent,
     PrepareRenameResponse, ProjectTransaction, ResolveState,
     lsp_store::{LocalLspStore, LspStore},
@@ -32,6 +33,11 @@ use signature_help::{lsp_to_proto_signature, proto_to_lsp_signature};
 use std::{cmp::Reverse, mem, ops::Range, path::Path, sync::Arc};
 use text::{BufferId, LineEnding};
 
+pub(crate) use hierarchy::{
+    GetIncomingCalls, GetOutgoingCalls, GetSubtypes, GetSupertypes, PrepareCallHierarchy,
+    PrepareTypeHierarchy,
+};
+pub use hierarchy::{HierarchyCall, HierarchyItem, HierarchyKind};
 pub use signature_help::SignatureHelp;
 
 pub fn lsp_formatting_options(settings: &LanguageSettings) -> lsp::FormattingOptions {
@@ -249,6 +255,59 @@ impl GetCodeLens {
     }
 }
 
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetDocumentLinks;
+
+impl GetDocumentLinks {
+    pub(crate) fn can_resolve_links(capabilities: &ServerCapabilities) -> bool {
+        capabilities
+            .document_link_provider
+            .as_ref()
+            .and_then(|document_link_options| document_link_options.resolve_provider)
+            .unwrap_or(false)
+    }
+
+    pub(crate) fn lsp_to_project_link(
+        lsp_link: lsp::DocumentLink,
+        range: Range<Anchor>,
+        server_id: LanguageServerId,
+    ) -> DocumentLink {
+        DocumentLink {
+            range,
+            target: lsp_link.target.clone(),
+            tooltip: lsp_link.tooltip.clone(),
+            server_id,
+            lsp_link,
+        }
+    }
+
+    pub(crate) fn project_to_proto_link(link: &DocumentLink) -> proto::DocumentLink {
+        proto::DocumentLink {
+            start: Some(serialize_anchor(&link.range.start)),
+            end: Some(serialize_anchor(&link.range.end)),
+            language_server_id: link.server_id.0 as u64,
+            lsp_link: serde_json::to_vec(&link.lsp_link).unwrap(),
+        }
+    }
+
+    pub(crate) fn proto_to_project_link(link: proto::DocumentLink) -> Result<DocumentLink> {
+        let start = link
+            .start
+            .and_then(deserialize_anchor)
+            .context("invalid document link start")?;
+        let end = link
+            .end
+            .and_then(deserialize_anchor)
+            .context("invalid document link end")?;
+        let lsp_link = serde_json::from_slice(&link.lsp_link)?;
+        Ok(Self::lsp_to_project_link(
+            lsp_link,
+            start..end,
+            LanguageServerId(link.language_server_id as usize),
+        ))
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct LinkedEditingRange {
     pub position: Anchor,
@@ -3489,6 +3548,121 @@ impl LspCommand for GetCodeLens {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for GetDocumentLinks {
+    type Response = Vec<DocumentLink>;
+    type LspRequest = lsp::request::DocumentLinkRequest;
+    type ProtoRequest = proto::GetDocumentLinks;
+
+    fn display_name(&self) -> &str {
+        "Document links"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        capabilities
+            .server_capabilities
+            .document_link_provider
+            .is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::DocumentLinkParams> {
+        Ok(lsp::DocumentLinkParams {
+            text_document: lsp::TextDocumentIdentifier {
+                uri: file_path_to_lsp_url(path)?,
+            },
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::DocumentLink>>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<DocumentLink>> {
+        let snapshot = buffer.update(&mut cx, |buffer, _| buffer.snapshot())?;
+        Ok(message
+            .unwrap_or_default()
+            .into_iter()
+            .map(|lsp_link| {
+                let link_range = range_from_lsp(lsp_link.range);
+                let start = snapshot.clip_point_utf16(link_range.start, Bias::Left);
+                let end = snapshot.clip_point_utf16(link_range.end, Bias::Right);
+                let range = snapshot.anchor_before(start)..snapshot.anchor_after(end);
+                Self::lsp_to_project_link(lsp_link, range, server_id)
+            })
+            .collect())
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentLinks {
+        proto::GetDocumentLinks {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetDocumentLinks,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Vec<DocumentLink>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetDocumentLinksResponse {
+        proto::GetDocumentLinksResponse {
+            links: response.iter().map(Self::project_to_proto_link).collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentLinksResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<DocumentLink>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .links
+            .into_iter()
+            .map(Self::proto_to_project_link)
+            .collect::<Result<Vec<_>>>()
+            .context("deserializing proto document links response")
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetDocumentLinks) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
 #[async_trait(?Send)]
 impl LspCommand for LinkedEditingRange {
     type Response = Vec<Range<Anchor>>;
@@ -0,0 +1,999 @@
+use std::{mem, ops::Range, path::Path, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use client::proto::{self, PeerId};
+use gpui::{App, AsyncApp, Entity};
+use language::{
+    Anchor, Bias, Buffer, CachedLspAdapter, Location, PointUtf16, ToPointUtf16, point_from_lsp,
+    proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
+};
+use lsp::{AdapterServerCapabilities, LanguageServer, LanguageServerId};
+use text::BufferId;
+
+use super::{LspCommand, language_server_for_buffer, make_lsp_text_document_position};
+use crate::lsp_store::LspStore;
+
+/// Whether a hierarchy follows calls between functions or inheritance between types.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HierarchyKind {
+    Call,
+    Type,
+}
+
+/// An entry of a call or type hierarchy.
+#[derive(Clone, Debug)]
+pub struct HierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The whole extent of the symbol, e.g. a function including its body.
+    pub location: Location,
+    /// The part of the symbol to reveal when navigating to it, e.g. the function name.
+    pub selection_range: Range<Anchor>,
+    pub language_server_id: LanguageServerId,
+    /// The item as the language server reported it, which it expects back when
+    /// asked for the neighbours of the item.
+    lsp_item: LspHierarchyItem,
+}
+
+/// A caller or callee of a call hierarchy item.
+#[derive(Clone, Debug)]
+pub struct HierarchyCall {
+    pub item: HierarchyItem,
+    /// The ranges of the calls, which always lie within the calling item.
+    pub call_sites: Vec<Location>,
+}
+
+#[derive(Clone, Debug)]
+enum LspHierarchyItem {
+    Call(lsp::CallHierarchyItem),
+    Type(lsp::TypeHierarchyItem),
+}
+
+impl HierarchyItem {
+    pub fn hierarchy_kind(&self) -> HierarchyKind {
+        match self.lsp_item {
+            LspHierarchyItem::Call(_) => HierarchyKind::Call,
+            LspHierarchyItem::Type(_) => HierarchyKind::Type,
+        }
+    }
+
+    fn lsp_call_item(&self) -> Result<lsp::CallHierarchyItem> {
+        match &self.lsp_item {
+            LspHierarchyItem::Call(item) => Ok(item.clone()),
+            LspHierarchyItem::Type(_) => {
+                anyhow::bail!("{:?} is not a call hierarchy item", self.name)
+            }
+        }
+    }
+
+    fn lsp_type_item(&self) -> Result<lsp::TypeHierarchyItem> {
+        match &self.lsp_item {
+            LspHierarchyItem::Type(item) => Ok(item.clone()),
+            LspHierarchyItem::Call(_) => {
+                anyhow::bail!("{:?} is not a type hierarchy item", self.name)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct PrepareCallHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug)]
+pub(crate) struct PrepareTypeHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetIncomingCalls {
+    pub item: HierarchyItem,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetOutgoingCalls {
+    pub item: HierarchyItem,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetSupertypes {
+    pub item: HierarchyItem,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetSubtypes {
+    pub item: HierarchyItem,
+}
+
+fn supports_call_hierarchy(capabilities: &AdapterServerCapabilities) -> bool {
+    match &capabilities.server_capabilities.call_hierarchy_provider {
+        Some(lsp::CallHierarchyServerCapability::Simple(supported)) => *supported,
+        Some(_) => true,
+        None => false,
+    }
+}
+
+fn range_from_lsp_in(buffer: &Buffer, range: lsp::Range) -> Range<Anchor> {
+    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+    buffer.anchor_after(start)..buffer.anchor_before(end)
+}
+
+async fn hierarchy_item_from_lsp(
+    lsp_item: LspHierarchyItem,
+    lsp_store: &Entity<LspStore>,
+    lsp_adapter: &Arc<CachedLspAdapter>,
+    language_server: &Arc<LanguageServer>,
+    cx: &mut AsyncApp,
+) -> Result<HierarchyItem> {
+    let (name, kind, detail, uri, range, selection_range) = match &lsp_item {
+        LspHierarchyItem::Call(item) => (
+            item.name.clone(),
+            item.kind,
+            item.detail.clone(),
+            item.uri.clone(),
+            item.range,
+            item.selection_range,
+        ),
+        LspHierarchyItem::Type(item) => (
+            item.name.clone(),
+            item.kind,
+            item.detail.clone(),
+            item.uri.clone(),
+            item.range,
+            item.selection_range,
+        ),
+    };
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.open_local_buffer_via_lsp(
+                uri,
+                language_server.server_id(),
+                lsp_adapter.name.clone(),
+                cx,
+            )
+        })?
+        .await?;
+    let (range, selection_range) = buffer.read_with(cx, |buffer, _| {
+        (
+            range_from_lsp_in(buffer, range),
+            range_from_lsp_in(buffer, selection_range),
+        )
+    })?;
+    Ok(HierarchyItem {
+        name,
+        kind,
+        detail,
+        location: Location { buffer, range },
+        selection_range,
+        language_server_id: language_server.server_id(),
+        lsp_item,
+    })
+}
+
+async fn hierarchy_items_from_lsp(
+    lsp_items: Vec<LspHierarchyItem>,
+    lsp_store: Entity<LspStore>,
+    buffer: Entity<Buffer>,
+    server_id: LanguageServerId,
+    mut cx: AsyncApp,
+) -> Result<Vec<HierarchyItem>> {
+    if lsp_items.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (lsp_adapter, language_server) =
+        language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
+    let mut items = Vec::with_capacity(lsp_items.len());
+    for lsp_item in lsp_items {
+        items.push(
+            hierarchy_item_from_lsp(
+                lsp_item,
+                &lsp_store,
+                &lsp_adapter,
+                &language_server,
+                &mut cx,
+            )
+            .await?,
+        );
+    }
+    Ok(items)
+}
+
+/// Resolves the call ranges of a call hierarchy response, given the item they lie within.
+async fn hierarchy_calls_from_lsp(
+    calls: Vec<(lsp::CallHierarchyItem, Vec<lsp::Range>)>,
+    calling_item: Option<&HierarchyItem>,
+    lsp_store: Entity<LspStore>,
+    buffer: Entity<Buffer>,
+    server_id: LanguageServerId,
+    mut cx: AsyncApp,
+) -> Result<Vec<HierarchyCall>> {
+    if calls.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (lsp_adapter, language_server) =
+        language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
+    let mut hierarchy_calls = Vec::with_capacity(calls.len());
+    for (lsp_item, ranges) in calls {
+        let item = hierarchy_item_from_lsp(
+            LspHierarchyItem::Call(lsp_item),
+            &lsp_store,
+            &lsp_adapter,
+            &language_server,
+            &mut cx,
+        )
+        .await?;
+        let calling_buffer = calling_item.unwrap_or(&item).location.buffer.clone();
+        let call_sites = calling_buffer.read_with(&cx, |snapshot, _| {
+            ranges
+                .into_iter()
+                .map(|range| Location {
+                    buffer: calling_buffer.clone(),
+                    range: range_from_lsp_in(snapshot, range),
+                })
+                .collect()
+        })?;
+        hierarchy_calls.push(HierarchyCall { item, call_sites });
+    }
+    Ok(hierarchy_calls)
+}
+
+fn location_to_proto(location: &Location, buffer_id: BufferId) -> proto::Location {
+    proto::Location {
+        start: Some(serialize_anchor(&location.range.start)),
+        end: Some(serialize_anchor(&location.range.end)),
+        buffer_id: buffer_id.into(),
+    }
+}
+
+fn hierarchy_item_to_proto(item: &HierarchyItem, buffer_id: BufferId) -> proto::HierarchyItem {
+    proto::HierarchyItem {
+        language_server_id: item.language_server_id.to_proto(),
+        name: item.name.clone(),
+        kind: unsafe { mem::transmute::<lsp::SymbolKind, i32>(item.kind) },
+        detail: item.detail.clone(),
+        location: Some(location_to_proto(&item.location, buffer_id)),
+        selection_start: Some(serialize_anchor(&item.selection_range.start)),
+        selection_end: Some(serialize_anchor(&item.selection_range.end)),
+        lsp_item: match &item.lsp_item {
+            LspHierarchyItem::Call(lsp_item) => serde_json::to_vec(lsp_item).unwrap(),
+            LspHierarchyItem::Type(lsp_item) => serde_json::to_vec(lsp_item).unwrap(),
+        },
+    }
+}
+
+/// Serializes a location of a response, sharing its buffer with the requesting peer.
+fn location_to_proto_for_peer(
+    location: &Location,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::Location {
+    lsp_store
+        .buffer_store()
+        .update(cx, |buffer_store, cx| {
+            buffer_store.create_buffer_for_peer(&location.buffer, peer_id, cx)
+        })
+        .detach_and_log_err(cx);
+    location_to_proto(location, location.buffer.read(cx).remote_id())
+}
+
+fn hierarchy_item_to_proto_for_peer(
+    item: &HierarchyItem,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::HierarchyItem {
+    let location = location_to_proto_for_peer(&item.location, lsp_store, peer_id, cx);
+    proto::HierarchyItem {
+        location: Some(location),
+        ..hierarchy_item_to_proto(item, item.location.buffer.read(cx).remote_id())
+    }
+}
+
+async fn hierarchy_item_from_proto(
+    item: proto::HierarchyItem,
+    kind: HierarchyKind,
+    buffer: Entity<Buffer>,
+    cx: &mut AsyncApp,
+) -> Result<HierarchyItem> {
+    let location = item.location.context("missing hierarchy item location")?;
+    let start = location
+        .start
+        .and_then(deserialize_anchor)
+        .context("missing hierarchy item start")?;
+    let end = location
+        .end
+        .and_then(deserialize_anchor)
+        .context("missing hierarchy item end")?;
+    let selection_start = item
+        .selection_start
+        .and_then(deserialize_anchor)
+        .context("missing hierarchy item selection start")?;
+    let selection_end = item
+        .selection_end
+        .and_then(deserialize_anchor)
+        .context("missing hierarchy item selection end")?;
+    buffer
+        .update(cx, |buffer, _| {
+            buffer.wait_for_anchors([start, end, selection_start, selection_end])
+        })?
+        .await?;
+    let lsp_item = match kind {
+        HierarchyKind::Call => LspHierarchyItem::Call(serde_json::from_slice(&item.lsp_item)?),
+        HierarchyKind::Type => LspHierarchyItem::Type(serde_json::from_slice(&item.lsp_item)?),
+    };
+    Ok(HierarchyItem {
+        name: item.name,
+        kind: unsafe { mem::transmute::<i32, lsp::SymbolKind>(item.kind) },
+        detail: item.detail,
+        location: Location {
+            buffer,
+            range: start..end,
+        },
+        selection_range: selection_start..selection_end,
+        language_server_id: LanguageServerId::from_proto(item.language_server_id),
+        lsp_item,
+    })
+}
+
+async fn remote_hierarchy_item_from_proto(
+    item: proto::HierarchyItem,
+    kind: HierarchyKind,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<HierarchyItem> {
+    let buffer_id = BufferId::new(
+        item.location
+            .as_ref()
+            .context("missing hierarchy item location")?
+            .buffer_id,
+    )?;
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.wait_for_remote_buffer(buffer_id, cx)
+        })?
+        .await?;
+    hierarchy_item_from_proto(item, kind, buffer, cx).await
+}
+
+async fn remote_location_from_proto(
+    location: proto::Location,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Location> {
+    let buffer_id = BufferId::new(location.buffer_id)?;
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.wait_for_remote_buffer(buffer_id, cx)
+        })?
+        .await?;
+    let start = location
+        .start
+        .and_then(deserialize_anchor)
+        .context("missing call site start")?;
+    let end = location
+        .end
+        .and_then(deserialize_anchor)
+        .context("missing call site end")?;
+    buffer
+        .update(cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+        .await?;
+    Ok(Location {
+        buffer,
+        range: start..end,
+    })
+}
+
+async fn position_from_proto(
+    position: Option<proto::Anchor>,
+    version: &[proto::VectorClockEntry],
+    buffer: &Entity<Buffer>,
+    cx: &mut AsyncApp,
+) -> Result<PointUtf16> {
+    let position = position
+        .and_then(deserialize_anchor)
+        .context("invalid position")?;
+    buffer
+        .update(cx, |buffer, _| {
+            buffer.wait_for_version(deserialize_version(version))
+        })?
+        .await?;
+    buffer.read_with(cx, |buffer, _| position.to_point_utf16(buffer))
+}
+
+fn hierarchy_items_to_proto(
+    items: Vec<HierarchyItem>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::HierarchyItemsResponse {
+    proto::HierarchyItemsResponse {
+        items: items
+            .iter()
+            .map(|item| hierarchy_item_to_proto_for_peer(item, lsp_store, peer_id, cx))
+            .collect(),
+    }
+}
+
+async fn hierarchy_items_from_proto(
+    message: proto::HierarchyItemsResponse,
+    kind: HierarchyKind,
+    lsp_store: Entity<LspStore>,
+    mut cx: AsyncApp,
+) -> Result<Vec<HierarchyItem>> {
+    let mut items = Vec::with_capacity(message.items.len());
+    for item in message.items {
+        items.push(remote_hierarchy_item_from_proto(item, kind, &lsp_store, &mut cx).await?);
+    }
+    Ok(items)
+}
+
+fn hierarchy_calls_to_proto(
+    calls: Vec<HierarchyCall>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::HierarchyCallsResponse {
+    proto::HierarchyCallsResponse {
+        calls: calls
+            .iter()
+            .map(|call| proto::HierarchyCall {
+                item: Some(hierarchy_item_to_proto_for_peer(
+                    &call.item, lsp_store, peer_id, cx,
+                )),
+                call_sites: call
+                    .call_sites
+                    .iter()
+                    .map(|call_site| location_to_proto_for_peer(call_site, lsp_store, peer_id, cx))
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+async fn hierarchy_calls_from_proto(
+    message: proto::HierarchyCallsResponse,
+    lsp_store: Entity<LspStore>,
+    mut cx: AsyncApp,
+) -> Result<Vec<HierarchyCall>> {
+    let mut calls = Vec::with_capacity(message.calls.len());
+    for call in message.calls {
+        let item = call.item.context("missing hierarchy call item")?;
+        let item = remote_hierarchy_item_from_proto(item, HierarchyKind::Call, &lsp_store, &mut cx)
+            .await?;
+        let mut call_sites = Vec::with_capacity(call.call_sites.len());
+        for call_site in call.call_sites {
+            call_sites.push(remote_location_from_proto(call_site, &lsp_store, &mut cx).await?);
+        }
+        calls.push(HierarchyCall { item, call_sites });
+    }
+    Ok(calls)
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareCallHierarchy {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::CallHierarchyPrepare;
+    type ProtoRequest = proto::PrepareCallHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare call hierarchy"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        supports_call_hierarchy(&capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyPrepareParams> {
+        Ok(lsp::CallHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::CallHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        let items = items
+            .unwrap_or_default()
+            .into_iter()
+            .map(LspHierarchyItem::Call)
+            .collect();
+        hierarchy_items_from_lsp(items, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareCallHierarchy {
+        proto::PrepareCallHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareCallHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            position: position_from_proto(message.position, &message.version, &buffer, &mut cx)
+                .await?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::HierarchyItemsResponse {
+        hierarchy_items_to_proto(response, lsp_store, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyItemsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message, HierarchyKind::Call, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareCallHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+// The type hierarchy capability is not part of `lsp::ServerCapabilities`, so the
+// type hierarchy requests are sent to any server and unsupporting ones answer with errors.
+#[async_trait(?Send)]
+impl LspCommand for PrepareTypeHierarchy {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchyPrepare;
+    type ProtoRequest = proto::PrepareTypeHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare type hierarchy"
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchyPrepareParams> {
+        Ok(lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        let items = items
+            .unwrap_or_default()
+            .into_iter()
+            .map(LspHierarchyItem::Type)
+            .collect();
+        hierarchy_items_from_lsp(items, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareTypeHierarchy {
+        proto::PrepareTypeHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareTypeHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            position: position_from_proto(message.position, &message.version, &buffer, &mut cx)
+                .await?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::HierarchyItemsResponse {
+        hierarchy_items_to_proto(response, lsp_store, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyItemsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message, HierarchyKind::Type, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareTypeHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetIncomingCalls {
+    type Response = Vec<HierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyIncomingCalls;
+    type ProtoRequest = proto::GetIncomingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get incoming calls"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        supports_call_hierarchy(&capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyIncomingCallsParams> {
+        Ok(lsp::CallHierarchyIncomingCallsParams {
+            item: self.item.lsp_call_item()?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        calls: Option<Vec<lsp::CallHierarchyIncomingCall>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        let calls = calls
+            .unwrap_or_default()
+            .into_iter()
+            .map(|call| (call.from, call.from_ranges))
+            .collect();
+        hierarchy_calls_from_lsp(calls, None, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetIncomingCalls {
+        proto::GetIncomingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(hierarchy_item_to_proto(&self.item, buffer.remote_id())),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetIncomingCalls,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("missing hierarchy item")?;
+        Ok(Self {
+            item: hierarchy_item_from_proto(item, HierarchyKind::Call, buffer, &mut cx).await?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::HierarchyCallsResponse {
+        hierarchy_calls_to_proto(response, lsp_store, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        hierarchy_calls_from_proto(message, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetIncomingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetOutgoingCalls {
+    type Response = Vec<HierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyOutgoingCalls;
+    type ProtoRequest = proto::GetOutgoingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get outgoing calls"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        supports_call_hierarchy(&capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyOutgoingCallsParams> {
+        Ok(lsp::CallHierarchyOutgoingCallsParams {
+            item: self.item.lsp_call_item()?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        calls: Option<Vec<lsp::CallHierarchyOutgoingCall>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        let calls = calls
+            .unwrap_or_default()
+            .into_iter()
+            .map(|call| (call.to, call.from_ranges))
+            .collect();
+        hierarchy_calls_from_lsp(calls, Some(&self.item), lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetOutgoingCalls {
+        proto::GetOutgoingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(hierarchy_item_to_proto(&self.item, buffer.remote_id())),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetOutgoingCalls,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("missing hierarchy item")?;
+        Ok(Self {
+            item: hierarchy_item_from_proto(item, HierarchyKind::Call, buffer, &mut cx).await?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::HierarchyCallsResponse {
+        hierarchy_calls_to_proto(response, lsp_store, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        hierarchy_calls_from_proto(message, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetOutgoingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSupertypes {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySupertypes;
+    type ProtoRequest = proto::GetSupertypes;
+
+    fn display_name(&self) -> &str {
+        "Get supertypes"
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySupertypesParams> {
+        Ok(lsp::TypeHierarchySupertypesParams {
+            item: self.item.lsp_type_item()?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        let items = items
+            .unwrap_or_default()
+            .into_iter()
+            .map(LspHierarchyItem::Type)
+            .collect();
+        hierarchy_items_from_lsp(items, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSupertypes {
+        proto::GetSupertypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(hierarchy_item_to_proto(&self.item, buffer.remote_id())),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSupertypes,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("missing hierarchy item")?;
+        Ok(Self {
+            item: hierarchy_item_from_proto(item, HierarchyKind::Type, buffer, &mut cx).await?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::HierarchyItemsResponse {
+        hierarchy_items_to_proto(response, lsp_store, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyItemsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message, HierarchyKind::Type, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSupertypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSubtypes {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySubtypes;
+    type ProtoRequest = proto::GetSubtypes;
+
+    fn display_name(&self) -> &str {
+        "Get subtypes"
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySubtypesParams> {
+        Ok(lsp::TypeHierarchySubtypesParams {
+            item: self.item.lsp_type_item()?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        let items = items
+            .unwrap_or_default()
+            .into_iter()
+            .map(LspHierarchyItem::Type)
+            .collect();
+        hierarchy_items_from_lsp(items, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSubtypes {
+        proto::GetSubtypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(hierarchy_item_to_proto(&self.item, buffer.remote_id())),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSubtypes,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("missing hierarchy item")?;
+        Ok(Self {
+            item: hierarchy_item_from_proto(item, HierarchyKind::Type, buffer, &mut cx).await?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::HierarchyItemsResponse {
+        hierarchy_items_to_proto(response, lsp_store, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyItemsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message, HierarchyKind::Type, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSubtypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
@@ -1,14 +1,24 @@
 pub mod clangd_ext;
 pub mod lsp_ext_command;
+mod pull_diagnostics;
 pub mod rust_analyzer_ext;
+pub mod semantic_tokens;
 
 use crate::{
-    CodeAction, Completion, CompletionSource, CoreCompletion, Hover, InlayHint, LspAction,
-    ProjectItem, ProjectPath, ProjectTransaction, ResolveState, Symbol, ToolchainStore,
+    CodeAction, Completion, CompletionSource, CoreCompletion, DocumentLink, Hover, InlayHint,
+    LspAction, ProjectItem, ProjectPath, ProjectTransaction, ResolveState, Symbol, ToolchainStore,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
     lsp_command::{self, *},
     lsp_store,
+    lsp_store::pull_diagnostics::{
+        PulledDiagnostics, fetch_document_diagnostics, fetch_workspace_diagnostics,
+        pull_diagnostics_options,
+    },
+    lsp_store::semantic_tokens::{
+        BufferSemanticTokens, RawSemanticTokens, decode_semantic_tokens, fetch_semantic_tokens,
+        semantic_tokens_capabilities, semantic_tokens_from_proto, semantic_tokens_to_proto,
+    },
     manifest_tree::{
         AdapterQuery, LanguageServerTree, LanguageServerTreeNode, LaunchDisposition, ManifestTree,
     },
@@ -38,9 +48,9 @@ use http_client::HttpClient;
 use itertools::Itertools as _;
 use language::{
     Bias, BinaryStatus, Buffer, BufferSnapshot, CachedLspAdapter, CodeLabel, Diagnostic,
-    DiagnosticEntry, DiagnosticSet, Diff, File as _, Language, LanguageName, LanguageRegistry,
-    LanguageToolchainStore, LocalFile, LspAdapter, LspAdapterDelegate, Patch, PointUtf16,
-    TextBufferSnapshot, ToOffset, ToPointUtf16, Transaction, Unclipped,
+    DiagnosticEntry, DiagnosticSet, DiagnosticSourceKind, Diff, File as _, Language, LanguageName,
+    LanguageRegistry, LanguageToolchainStore, LocalFile, LspAdapter, LspAdapterDelegate, Patch,
+    PointUtf16, TextBufferSnapshot, ToOffset, ToPointUtf16, Transaction, Unclipped,
     language_settings::{
         FormatOnSave, Formatter, LanguageSettings, SelectedFormatter, language_settings,
     },
@@ -104,6 +114,7 @@ pub use worktree::{
 
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 pub const SERVER_PROGRESS_THROTTLE_TIMEOUT: Duration = Duration::from_millis(100);
+const DIAGNOSTICS_PULL_DEBOUNCE: Duration = Duration::from_millis(125);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FormatTrigger {
@@ -161,6 +172,10 @@ pub struct LocalLspStore {
         >,
     >,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
+    semantic_tokens: HashMap<BufferId, HashMap<LanguageServerId, RawSemanticTokens>>,
+    diagnostic_result_ids: HashMap<LanguageServerId, HashMap<lsp::Url, String>>,
+    document_diagnostics_pulls: HashMap<BufferId, Task<()>>,
+    workspace_diagnostics_pulls: HashMap<LanguageServerId, Task<()>>,
     _subscription: gpui::Subscription,
     lsp_tree: Entity<LanguageServerTree>,
     registered_buffers: HashMap<BufferId, usize>,
@@ -472,9 +487,13 @@ impl LocalLspStore {
 
                             this.merge_diagnostics(
                                 server_id,
+                                DiagnosticSourceKind::Pushed,
                                 params,
                                 &adapter.disk_based_diagnostic_sources,
-                                |diagnostic, cx| adapter.retain_old_diagnostic(diagnostic, cx),
+                                |diagnostic, cx| {
+                                    diagnostic.source_kind == DiagnosticSourceKind::Pulled
+                                        || adapter.retain_old_diagnostic(diagnostic, cx)
+                                },
                                 cx,
                             )
                             .log_err();
@@ -703,6 +722,38 @@ impl LocalLspStore {
                                         anyhow::Ok(())
                                     })??;
                                 }
+                                "textDocument/diagnostic" => {
+                                    this.update(&mut cx, |this, cx| {
+                                        if let Some(server) = this.language_server_for_id(server_id)
+                                        {
+                                            let options = reg
+                                                .register_options
+                                                .map(|options| {
+                                                    serde_json::from_value::<
+                                                        lsp::DiagnosticRegistrationOptions,
+                                                    >(
+                                                        options
+                                                    )
+                                                })
+                                                .transpose()?;
+                                            let provider = match options {
+                                                None => lsp::DiagnosticServerCapabilities::Options(
+                                                    Default::default(),
+                                                ),
+                                                Some(options) => {
+                                                    lsp::DiagnosticServerCapabilities::RegistrationOptions(
+                                                        options,
+                                                    )
+                                                }
+                                            };
+                                            server.update_capabilities(|capabilities| {
+                                                capabilities.diagnostic_provider = Some(provider);
+                                            });
+                                            this.pull_diagnostics_for_server(server_id, cx);
+                                        }
+                                        anyhow::Ok(())
+                                    })??;
+                                }
                                 _ => log::warn!("unhandled capability registration: {reg:?}"),
                             }
                         }
@@ -775,6 +826,16 @@ impl LocalLspStore {
                                         }
                                     })?;
                                 }
+                                "textDocument/diagnostic" => {
+                                    this.update(&mut cx, |this, _| {
+                                        if let Some(server) = this.language_server_for_id(server_id)
+                                        {
+                                            server.update_capabilities(|capabilities| {
+                                                capabilities.diagnostic_provider = None;
+                                            })
+                                        }
+                                    })?;
+                                }
                                 _ => log::warn!("unhandled capability unregistration: {unreg:?}"),
                             }
                         }
@@ -850,6 +911,44 @@ impl LocalLspStore {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), cx| {
+                    let this = this.clone();
+                    let mut cx = cx.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+                            this.downstream_client.as_ref().map(|(client, project_id)| {
+                                client.send(proto::RefreshSemanticTokens {
+                                    project_id: *project_id,
+                                })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = this.clone();
+                move |(), cx| {
+                    let this = this.clone();
+                    let mut cx = cx.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            this.pull_diagnostics_for_server(server_id, cx);
+                        })?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::ShowMessageRequest, _, _>({
                 let this = this.clone();
@@ -2252,6 +2351,9 @@ impl LocalLspStore {
     ) {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id();
+        if buffer.is_large_file() {
+            return;
+        }
 
         let Some(file) = File::from_dyn(buffer.file()) else {
             return;
@@ -2537,6 +2639,8 @@ impl LocalLspStore {
     ) {
         buffer.update(cx, |buffer, cx| {
             let _ = self.buffer_snapshots.remove(&buffer.remote_id());
+            let _ = self.semantic_tokens.remove(&buffer.remote_id());
+            let _ = self.document_diagnostics_pulls.remove(&buffer.remote_id());
 
             for (_, language_server) in self.language_servers_for_buffer(buffer, cx) {
                 language_server.unregister_buffer(file_url.clone());
@@ -3451,6 +3555,7 @@ pub enum LspStoreEvent {
     Notification(String),
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     DiagnosticsUpdated {
         language_server_id: LanguageServerId,
         path: ProjectPath,
@@ -3505,9 +3610,12 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_inlay_hints);
         client.add_entity_request_handler(Self::handle_get_project_symbols);
         client.add_entity_request_handler(Self::handle_resolve_inlay_hint);
+        client.add_entity_request_handler(Self::handle_resolve_document_link);
         client.add_entity_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_entity_request_handler(Self::handle_refresh_inlay_hints);
         client.add_entity_request_handler(Self::handle_refresh_code_lens);
+        client.add_entity_request_handler(Self::handle_refresh_semantic_tokens);
+        client.add_entity_request_handler(Self::handle_get_semantic_tokens);
         client.add_entity_request_handler(Self::handle_on_type_formatting);
         client.add_entity_request_handler(Self::handle_apply_additional_edits_for_completion);
         client.add_entity_request_handler(Self::handle_register_buffer_with_language_servers);
@@ -3525,6 +3633,12 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSubtypes>);
 
         client.add_entity_request_handler(Self::handle_lsp_ext_cancel_flycheck);
         client.add_entity_request_handler(Self::handle_lsp_ext_run_flycheck);
@@ -3563,6 +3677,16 @@ impl LspStore {
         }
     }
 
+    /// Returns a delegate for the given worktree, if this store manages local language servers.
+    pub(crate) fn local_adapter_delegate(
+        &self,
+        worktree: &Entity<Worktree>,
+        cx: &mut App,
+    ) -> Option<Arc<LocalLspAdapterDelegate>> {
+        let local = self.as_local()?;
+        Some(LocalLspAdapterDelegate::from_local_lsp(local, worktree, cx))
+    }
+
     pub fn upstream_client(&self) -> Option<(AnyProtoClient, u64)> {
         match &self.mode {
             LspStoreMode::Remote(RemoteLspStore {
@@ -3634,6 +3758,10 @@ impl LspStore {
                 language_server_watcher_registrations: Default::default(),
                 buffers_being_formatted: Default::default(),
                 buffer_snapshots: Default::default(),
+                semantic_tokens: Default::default(),
+                diagnostic_result_ids: Default::default(),
+                document_diagnostics_pulls: Default::default(),
+                workspace_diagnostics_pulls: Default::default(),
                 prettier_store,
                 environment,
                 http_client,
@@ -3769,7 +3897,8 @@ impl LspStore {
                         this.update_local_worktree_language_servers(&worktree, changes, cx);
                     }
                     worktree::Event::UpdatedGitRepositories(_)
-                    | worktree::Event::DeletedEntry(_) => {}
+                    | worktree::Event::DeletedEntry(_)
+                    | worktree::Event::LargeFileLoadProgress { .. } => {}
                 })
                 .detach()
             }
@@ -3839,7 +3968,8 @@ impl LspStore {
     ) {
         match event {
             language::BufferEvent::Edited { .. } => {
-                self.on_buffer_edited(buffer, cx);
+                self.on_buffer_edited(buffer.clone(), cx);
+                self.schedule_diagnostics_pull(&buffer, cx);
             }
 
             language::BufferEvent::Saved => {
@@ -3992,6 +4122,7 @@ impl LspStore {
 
             if ignore_refcounts || *refcount == 1 {
                 local.register_buffer_with_language_servers(buffer, cx);
+                self.schedule_diagnostics_pull(buffer, cx);
             }
             if !ignore_refcounts {
                 cx.observe_release(&handle, move |this, buffer, cx| {
@@ -4774,6 +4905,120 @@ impl LspStore {
         }
     }
 
+    /// Fetches the semantic tokens of every language server of the buffer that provides them.
+    ///
+    /// Servers that support it are asked for a delta against their previous response.
+    pub fn semantic_tokens(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<BufferSemanticTokens>>> {
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = proto::GetSemanticTokens {
+                project_id,
+                buffer_id: buffer.read(cx).remote_id().into(),
+                version: serialize_version(&buffer.read(cx).version()),
+            };
+            let buffer = buffer.clone();
+            return cx.spawn(async move |_, cx| {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("semantic tokens proto request")?;
+                buffer
+                    .update(cx, |buffer, _| {
+                        buffer.wait_for_version(deserialize_version(&response.version))
+                    })?
+                    .await?;
+                response
+                    .servers
+                    .into_iter()
+                    .map(semantic_tokens_from_proto)
+                    .collect()
+            });
+        }
+
+        let Some(local) = self.as_local() else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let Some(abs_path) = File::from_dyn(buffer.read(cx).file()).map(|file| file.abs_path(cx))
+        else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let text_document = match make_text_document_identifier(&abs_path) {
+            Ok(text_document) => text_document,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let snapshot = buffer.read(cx).text_snapshot();
+        let servers = buffer.update(cx, |buffer, cx| {
+            local
+                .language_servers_for_buffer(buffer, cx)
+                .filter_map(|(_, server)| {
+                    let (legend, supports_delta) =
+                        semantic_tokens_capabilities(&server.capabilities())?;
+                    Some((server.clone(), legend, supports_delta))
+                })
+                .collect::<Vec<_>>()
+        });
+        let previous_tokens = local.semantic_tokens.get(&buffer_id);
+        let requests = servers
+            .into_iter()
+            .map(|(server, legend, supports_delta)| {
+                let previous = previous_tokens
+                    .and_then(|previous_tokens| previous_tokens.get(&server.server_id()))
+                    .filter(|_| supports_delta)
+                    .and_then(|previous| {
+                        Some((previous.result_id.clone()?, previous.data.clone()))
+                    });
+                let text_document = text_document.clone();
+                async move {
+                    let response = fetch_semantic_tokens(&server, text_document, previous).await;
+                    (server.server_id(), legend, response)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(async move |lsp_store, cx| {
+            let responses = join_all(requests).await;
+            let mut raw_tokens = Vec::new();
+            lsp_store.update(cx, |lsp_store, _| {
+                let Some(local) = lsp_store.as_local_mut() else {
+                    return;
+                };
+                let cache = local.semantic_tokens.entry(buffer_id).or_default();
+                for (server_id, legend, response) in responses {
+                    match response.log_err().flatten() {
+                        Some(raw) => {
+                            if raw.result_id.is_some() {
+                                cache.insert(server_id, raw.clone());
+                            } else {
+                                cache.remove(&server_id);
+                            }
+                            raw_tokens.push((server_id, legend, raw.data));
+                        }
+                        None => {
+                            cache.remove(&server_id);
+                        }
+                    }
+                }
+            })?;
+
+            Ok(cx
+                .background_spawn(async move {
+                    raw_tokens
+                        .into_iter()
+                        .map(|(server_id, legend, data)| BufferSemanticTokens {
+                            server_id,
+                            legend: Arc::new(legend),
+                            tokens: decode_semantic_tokens(&data, &snapshot),
+                        })
+                        .collect()
+                })
+                .await)
+        })
+    }
+
     pub(crate) fn linked_edit(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -5066,6 +5311,123 @@ impl LspStore {
         }
     }
 
+    pub fn document_links(
+        &mut self,
+        buffer_handle: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request_task = upstream_client.request(proto::MultiLspQuery {
+                buffer_id: buffer_handle.read(cx).remote_id().into(),
+                version: serialize_version(&buffer_handle.read(cx).version()),
+                project_id,
+                strategy: Some(proto::multi_lsp_query::Strategy::All(
+                    proto::AllLanguageServers {},
+                )),
+                request: Some(proto::multi_lsp_query::Request::GetDocumentLinks(
+                    GetDocumentLinks.to_proto(project_id, buffer_handle.read(cx)),
+                )),
+            });
+            let buffer = buffer_handle.clone();
+            cx.spawn(async move |weak_project, cx| {
+                let Some(project) = weak_project.upgrade() else {
+                    return Ok(Vec::new());
+                };
+                let responses = request_task.await?.responses;
+                let document_links = join_all(
+                    responses
+                        .into_iter()
+                        .filter_map(|lsp_response| match lsp_response.response? {
+                            proto::lsp_response::Response::GetDocumentLinksResponse(response) => {
+                                Some(response)
+                            }
+                            unexpected => {
+                                debug_panic!("Unexpected response: {unexpected:?}");
+                                None
+                            }
+                        })
+                        .map(|document_links_response| {
+                            GetDocumentLinks.response_from_proto(
+                                document_links_response,
+                                project.clone(),
+                                buffer.clone(),
+                                cx.clone(),
+                            )
+                        }),
+                )
+                .await;
+
+                Ok(document_links
+                    .into_iter()
+                    .collect::<Result<Vec<Vec<_>>>>()?
+                    .into_iter()
+                    .flatten()
+                    .collect())
+            })
+        } else {
+            let document_links_task = self.request_multiple_lsp_locally(
+                buffer_handle,
+                None::<usize>,
+                GetDocumentLinks,
+                cx,
+            );
+            cx.spawn(async move |_, _| {
+                Ok(document_links_task.await.into_iter().flatten().collect())
+            })
+        }
+    }
+
+    /// Asks the language server that reported the link for its target, if it hasn't got one yet.
+    pub fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer_handle: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DocumentLink>> {
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = proto::ResolveDocumentLink {
+                project_id,
+                buffer_id: buffer_handle.read(cx).remote_id().into(),
+                link: Some(GetDocumentLinks::project_to_proto_link(&link)),
+            };
+            cx.spawn(async move |_, _| {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("document link proto request")?;
+                match response.link {
+                    Some(resolved_link) => GetDocumentLinks::proto_to_project_link(resolved_link)
+                        .context("document link proto resolve response conversion"),
+                    None => Ok(link),
+                }
+            })
+        } else {
+            let Some(lang_server) = buffer_handle.update(cx, |buffer, cx| {
+                self.language_server_for_local_buffer(buffer, link.server_id, cx)
+                    .map(|(_, server)| server.clone())
+            }) else {
+                return Task::ready(Ok(link));
+            };
+            if link.target.is_some()
+                || !GetDocumentLinks::can_resolve_links(&lang_server.capabilities())
+            {
+                return Task::ready(Ok(link));
+            }
+            cx.spawn(async move |_, _| {
+                let resolved_link = lang_server
+                    .request::<lsp::request::DocumentLinkResolve>(link.lsp_link)
+                    .await
+                    .into_response()
+                    .context("document link resolve LSP request")?;
+                Ok(GetDocumentLinks::lsp_to_project_link(
+                    resolved_link,
+                    link.range,
+                    link.server_id,
+                ))
+            })
+        }
+    }
+
     #[inline(never)]
     pub fn completions(
         &self,
@@ -6250,7 +6612,9 @@ impl LspStore {
         });
         for language_server_id in language_servers {
             self.simulate_disk_based_diagnostics_events_if_needed(language_server_id, cx);
+            self.pull_workspace_diagnostics(language_server_id, cx);
         }
+        self.schedule_diagnostics_pull(&buffer, cx);
 
         None
     }
@@ -7076,6 +7440,45 @@ impl LspStore {
                         .collect(),
                 })
             }
+            Some(proto::multi_lsp_query::Request::GetDocumentLinks(get_document_links)) => {
+                let get_document_links = GetDocumentLinks::from_proto(
+                    get_document_links,
+                    this.clone(),
+                    buffer.clone(),
+                    cx.clone(),
+                )
+                .await?;
+
+                let document_links = this
+                    .update(&mut cx, |project, cx| {
+                        project.request_multiple_lsp_locally(
+                            &buffer,
+                            None::<usize>,
+                            get_document_links,
+                            cx,
+                        )
+                    })?
+                    .await
+                    .into_iter();
+
+                this.update(&mut cx, |project, cx| proto::MultiLspQueryResponse {
+                    responses: document_links
+                        .map(|links| proto::LspResponse {
+                            response: Some(
+                                proto::lsp_response::Response::GetDocumentLinksResponse(
+                                    GetDocumentLinks::response_to_proto(
+                                        links,
+                                        project,
+                                        sender_id,
+                                        &buffer_version,
+                                        cx,
+                                    ),
+                                ),
+                            ),
+                        })
+                        .collect(),
+                })
+            }
             None => anyhow::bail!("empty multi lsp query request"),
         }
     }
@@ -8047,6 +8450,32 @@ impl LspStore {
         })
     }
 
+    async fn handle_get_semantic_tokens(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetSemanticTokens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetSemanticTokensResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&envelope.payload.version))
+            })?
+            .await
+            .with_context(|| format!("waiting for version for buffer {}", buffer.entity_id()))?;
+        let version = buffer.read_with(&cx, |buffer, _| buffer.version())?;
+        let tokens = this
+            .update(&mut cx, |this, cx| this.semantic_tokens(&buffer, cx))?
+            .await
+            .context("semantic tokens fetch")?;
+        Ok(proto::GetSemanticTokensResponse {
+            servers: tokens.iter().map(semantic_tokens_to_proto).collect(),
+            version: serialize_version(&version),
+        })
+    }
+
     async fn handle_resolve_inlay_hint(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::ResolveInlayHint>,
@@ -8078,6 +8507,33 @@ impl LspStore {
         })
     }
 
+    async fn handle_resolve_document_link(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::ResolveDocumentLink>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::ResolveDocumentLinkResponse> {
+        let link = GetDocumentLinks::proto_to_project_link(
+            envelope
+                .payload
+                .link
+                .context("invalid resolve document link request: missing the link")?,
+        )
+        .context("resolved proto document link conversion")?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let resolved_link = this
+            .update(&mut cx, |this, cx| {
+                this.resolve_document_link(link, buffer, cx)
+            })?
+            .await
+            .context("document link resolve")?;
+        Ok(proto::ResolveDocumentLinkResponse {
+            link: Some(GetDocumentLinks::project_to_proto_link(&resolved_link)),
+        })
+    }
+
     async fn handle_refresh_code_lens(
         this: Entity<Self>,
         _: TypedEnvelope<proto::RefreshCodeLens>,
@@ -8089,6 +8545,17 @@ impl LspStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_refresh_semantic_tokens(
+        this: Entity<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_open_buffer_for_symbol(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::OpenBufferForSymbol>,
@@ -8611,6 +9078,8 @@ impl LspStore {
             });
         }
         local.language_server_watched_paths.remove(&server_id);
+        local.diagnostic_result_ids.remove(&server_id);
+        local.workspace_diagnostics_pulls.remove(&server_id);
         let server_state = local.language_servers.remove(&server_id);
         cx.notify();
         cx.emit(LspStoreEvent::LanguageServerRemoved(server_id));
@@ -8729,6 +9198,7 @@ impl LspStore {
     ) -> Result<()> {
         self.merge_diagnostics(
             language_server_id,
+            DiagnosticSourceKind::Pushed,
             params,
             disk_based_sources,
             |_, _| false,
@@ -8739,6 +9209,7 @@ impl LspStore {
     pub fn merge_diagnostics<F: Fn(&Diagnostic, &App) -> bool + Clone>(
         &mut self,
         language_server_id: LanguageServerId,
+        source_kind: DiagnosticSourceKind,
         mut params: lsp::PublishDiagnosticsParams,
         disk_based_sources: &[String],
         filter: F,
@@ -8815,6 +9286,7 @@ impl LspStore {
                         is_disk_based,
                         is_unnecessary,
                         data: diagnostic.data.clone(),
+                        source_kind,
                     },
                 });
                 if let Some(infos) = &diagnostic.related_information {
@@ -8840,6 +9312,7 @@ impl LspStore {
                                     is_disk_based,
                                     is_unnecessary: false,
                                     data: diagnostic.data.clone(),
+                                    source_kind,
                                 },
                             });
                         }
@@ -8876,6 +9349,206 @@ impl LspStore {
         Ok(())
     }
 
+    fn schedule_diagnostics_pull(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let buffer = buffer.downgrade();
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let pull = cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(DIAGNOSTICS_PULL_DEBOUNCE)
+                .await;
+            let Some(buffer) = buffer.upgrade() else {
+                return;
+            };
+            let Ok(pull) =
+                this.update(cx, |this, cx| this.pull_diagnostics_for_buffer(&buffer, cx))
+            else {
+                return;
+            };
+            pull.await;
+        });
+        local.document_diagnostics_pulls.insert(buffer_id, pull);
+    }
+
+    /// Requests the diagnostics of the buffer from each of its language servers that
+    /// support pulling them, merging the results into the buffer's diagnostics.
+    pub fn pull_diagnostics_for_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        let Some(local) = self.as_local() else {
+            return Task::ready(());
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some(file) = File::from_dyn(buffer.read(cx).file()).and_then(|file| file.as_local())
+        else {
+            return Task::ready(());
+        };
+        let Some(uri) = lsp::Url::from_file_path(file.abs_path(cx)).log_err() else {
+            return Task::ready(());
+        };
+        let requests = buffer.update(cx, |buffer, cx| {
+            local
+                .language_servers_for_buffer(buffer, cx)
+                .filter_map(|(_, server)| {
+                    let server_id = server.server_id();
+                    let options = pull_diagnostics_options(&server.capabilities())?;
+                    let version = local
+                        .buffer_snapshots
+                        .get(&buffer_id)?
+                        .get(&server_id)?
+                        .last()?
+                        .version;
+                    let previous_result_id = local
+                        .diagnostic_result_ids
+                        .get(&server_id)
+                        .and_then(|result_ids| result_ids.get(&uri))
+                        .cloned();
+                    let server = server.clone();
+                    let uri = uri.clone();
+                    Some(async move {
+                        let pulled = fetch_document_diagnostics(
+                            &server,
+                            uri,
+                            version,
+                            options.identifier,
+                            previous_result_id,
+                        )
+                        .await;
+                        (server_id, pulled)
+                    })
+                })
+                .collect::<Vec<_>>()
+        });
+
+        cx.spawn(async move |this, cx| {
+            for (server_id, pulled) in join_all(requests).await {
+                let Some(pulled) = pulled.log_err() else {
+                    continue;
+                };
+                this.update(cx, |this, cx| {
+                    this.apply_pulled_diagnostics(server_id, pulled, cx)
+                })
+                .ok();
+            }
+        })
+    }
+
+    fn pull_workspace_diagnostics(&mut self, server_id: LanguageServerId, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let Some(server) = local.running_language_server_for_id(server_id).cloned() else {
+            return;
+        };
+        let Some(options) = pull_diagnostics_options(&server.capabilities())
+            .filter(|options| options.workspace_diagnostics)
+        else {
+            return;
+        };
+        let previous_result_ids = local
+            .diagnostic_result_ids
+            .get(&server_id)
+            .into_iter()
+            .flatten()
+            .map(|(uri, result_id)| lsp::PreviousResultId {
+                uri: uri.clone(),
+                value: result_id.clone(),
+            })
+            .collect();
+
+        let pull = cx.spawn(async move |this, cx| {
+            let pulled =
+                fetch_workspace_diagnostics(&server, options.identifier, previous_result_ids).await;
+            if let Some(pulled) = pulled.log_err() {
+                this.update(cx, |this, cx| {
+                    this.apply_pulled_diagnostics(server_id, pulled, cx)
+                })
+                .ok();
+            }
+        });
+        local.workspace_diagnostics_pulls.insert(server_id, pull);
+    }
+
+    /// Pulls the diagnostics of every buffer open in the given language server, and of the
+    /// whole workspace if the server supports it.
+    fn pull_diagnostics_for_server(&mut self, server_id: LanguageServerId, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local() else {
+            return;
+        };
+        let buffers = local
+            .buffer_snapshots
+            .iter()
+            .filter(|(_, snapshots)| snapshots.contains_key(&server_id))
+            .filter_map(|(buffer_id, _)| self.buffer_store.read(cx).get(*buffer_id))
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.schedule_diagnostics_pull(&buffer, cx);
+        }
+        self.pull_workspace_diagnostics(server_id, cx);
+    }
+
+    fn apply_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        pulled: Vec<PulledDiagnostics>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(adapter) = self.language_server_adapter_for_id(server_id) else {
+            return;
+        };
+        for PulledDiagnostics {
+            uri,
+            version,
+            result_id,
+            diagnostics,
+        } in pulled
+        {
+            let Some(local) = self.as_local_mut() else {
+                return;
+            };
+            let result_ids = local.diagnostic_result_ids.entry(server_id).or_default();
+            match result_id {
+                Some(result_id) => {
+                    result_ids.insert(uri.clone(), result_id);
+                }
+                None => {
+                    result_ids.remove(&uri);
+                }
+            }
+
+            let Some(diagnostics) = diagnostics else {
+                continue;
+            };
+            let mut params = lsp::PublishDiagnosticsParams {
+                uri,
+                diagnostics,
+                version,
+            };
+            {
+                let buffer = params
+                    .uri
+                    .to_file_path()
+                    .map(|file_path| self.get_buffer(&file_path, cx))
+                    .ok()
+                    .flatten();
+                adapter.process_diagnostics(&mut params, server_id, buffer);
+            }
+            self.merge_diagnostics(
+                server_id,
+                DiagnosticSourceKind::Pulled,
+                params,
+                &adapter.disk_based_diagnostic_sources,
+                |diagnostic, _| diagnostic.source_kind != DiagnosticSourceKind::Pulled,
+                cx,
+            )
+            .log_err();
+        }
+    }
+
     fn insert_newly_running_language_server(
         &mut self,
         adapter: Arc<CachedLspAdapter>,
@@ -9038,6 +9711,7 @@ impl LspStore {
                 });
             }
         });
+        self.pull_diagnostics_for_server(server_id, cx);
 
         cx.notify();
     }
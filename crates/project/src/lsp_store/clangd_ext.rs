@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use ::serde::{Deserialize, Serialize};
 use gpui::WeakEntity;
-use language::{CachedLspAdapter, Diagnostic};
+use language::{CachedLspAdapter, Diagnostic, DiagnosticSourceKind};
 use lsp::LanguageServer;
 use util::ResultExt as _;
 
@@ -73,6 +73,7 @@ pub fn register_notifications(
                     };
                     this.merge_diagnostics(
                         server_id,
+                        DiagnosticSourceKind::Other,
                         mapped_diagnostics,
                         &adapter.disk_based_diagnostic_sources,
                         |diag, _| !is_inactive_region(diag),
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use anyhow::{Context as _, Result};
+use lsp::LanguageServer;
+
+/// Diagnostics that a language server reported for a single document in response to a pull.
+#[derive(Debug)]
+pub(crate) struct PulledDiagnostics {
+    pub uri: lsp::Url,
+    /// The LSP version of the document that the diagnostics were computed for, if known.
+    pub version: Option<i32>,
+    /// The ID to send back to the server on the next pull, so that it can skip unchanged documents.
+    pub result_id: Option<String>,
+    /// The document's diagnostics, or `None` if they are the same as in the previous result.
+    pub diagnostics: Option<Vec<lsp::Diagnostic>>,
+}
+
+/// Returns the server's pull diagnostics options, if it supports `textDocument/diagnostic`.
+pub(crate) fn pull_diagnostics_options(
+    capabilities: &lsp::ServerCapabilities,
+) -> Option<lsp::DiagnosticOptions> {
+    match capabilities.diagnostic_provider.as_ref()? {
+        lsp::DiagnosticServerCapabilities::Options(options) => Some(options.clone()),
+        lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+            Some(options.diagnostic_options.clone())
+        }
+    }
+}
+
+/// Pulls the diagnostics of an open document, along with those of any related documents
+/// the server chooses to include.
+pub(crate) async fn fetch_document_diagnostics(
+    server: &LanguageServer,
+    uri: lsp::Url,
+    version: i32,
+    identifier: Option<String>,
+    previous_result_id: Option<String>,
+) -> Result<Vec<PulledDiagnostics>> {
+    let response = server
+        .request::<lsp::request::DocumentDiagnosticRequest>(lsp::DocumentDiagnosticParams {
+            text_document: lsp::TextDocumentIdentifier { uri: uri.clone() },
+            identifier,
+            previous_result_id,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .into_response()
+        .context("document diagnostics LSP request")?;
+
+    let mut pulled = Vec::new();
+    let related_documents = match response {
+        lsp::DocumentDiagnosticReportResult::Report(lsp::DocumentDiagnosticReport::Full(
+            report,
+        )) => {
+            pulled.push(PulledDiagnostics {
+                uri,
+                version: Some(version),
+                result_id: report.full_document_diagnostic_report.result_id,
+                diagnostics: Some(report.full_document_diagnostic_report.items),
+            });
+            report.related_documents
+        }
+        lsp::DocumentDiagnosticReportResult::Report(lsp::DocumentDiagnosticReport::Unchanged(
+            report,
+        )) => {
+            pulled.push(PulledDiagnostics {
+                uri,
+                version: Some(version),
+                result_id: Some(report.unchanged_document_diagnostic_report.result_id),
+                diagnostics: None,
+            });
+            report.related_documents
+        }
+        lsp::DocumentDiagnosticReportResult::Partial(partial) => partial.related_documents,
+    };
+    pulled.extend(related_documents.into_iter().flat_map(related_diagnostics));
+    Ok(pulled)
+}
+
+/// Pulls the diagnostics of every document in the workspace that the server knows about.
+pub(crate) async fn fetch_workspace_diagnostics(
+    server: &LanguageServer,
+    identifier: Option<String>,
+    previous_result_ids: Vec<lsp::PreviousResultId>,
+) -> Result<Vec<PulledDiagnostics>> {
+    let response = server
+        .request::<lsp::request::WorkspaceDiagnosticRequest>(lsp::WorkspaceDiagnosticParams {
+            identifier,
+            previous_result_ids,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .into_response()
+        .context("workspace diagnostics LSP request")?;
+
+    let items = match response {
+        lsp::WorkspaceDiagnosticReportResult::Report(report) => report.items,
+        lsp::WorkspaceDiagnosticReportResult::Partial(partial) => partial.items,
+    };
+    Ok(items
+        .into_iter()
+        .map(|item| match item {
+            lsp::WorkspaceDocumentDiagnosticReport::Full(report) => PulledDiagnostics {
+                uri: report.uri,
+                version: report
+                    .version
+                    .and_then(|version| i32::try_from(version).ok()),
+                result_id: report.full_document_diagnostic_report.result_id,
+                diagnostics: Some(report.full_document_diagnostic_report.items),
+            },
+            lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => PulledDiagnostics {
+                uri: report.uri,
+                version: report
+                    .version
+                    .and_then(|version| i32::try_from(version).ok()),
+                result_id: Some(report.unchanged_document_diagnostic_report.result_id),
+                diagnostics: None,
+            },
+        })
+        .collect())
+}
+
+fn related_diagnostics(
+    related_documents: HashMap<lsp::Url, lsp::DocumentDiagnosticReportKind>,
+) -> impl Iterator<Item = PulledDiagnostics> {
+    related_documents
+        .into_iter()
+        .map(|(uri, report)| match report {
+            lsp::DocumentDiagnosticReportKind::Full(report) => PulledDiagnostics {
+                uri,
+                version: None,
+                result_id: report.result_id,
+                diagnostics: Some(report.items),
+            },
+            lsp::DocumentDiagnosticReportKind::Unchanged(report) => PulledDiagnostics {
+                uri,
+                version: None,
+                result_id: Some(report.result_id),
+                diagnostics: None,
+            },
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pull_diagnostics_options() {
+        let mut capabilities = lsp::ServerCapabilities::default();
+        assert_eq!(pull_diagnostics_options(&capabilities), None);
+
+        let options = lsp::DiagnosticOptions {
+            identifier: Some("linter".to_string()),
+            inter_file_dependencies: true,
+            workspace_diagnostics: true,
+            work_done_progress_options: Default::default(),
+        };
+        capabilities.diagnostic_provider =
+            Some(lsp::DiagnosticServerCapabilities::Options(options.clone()));
+        assert_eq!(
+            pull_diagnostics_options(&capabilities),
+            Some(options.clone())
+        );
+
+        capabilities.diagnostic_provider =
+            Some(lsp::DiagnosticServerCapabilities::RegistrationOptions(
+                lsp::DiagnosticRegistrationOptions {
+                    text_document_registration_options: lsp::TextDocumentRegistrationOptions {
+                        document_selector: None,
+                    },
+                    diagnostic_options: options.clone(),
+                    static_registration_options: lsp::StaticRegistrationOptions { id: None },
+                },
+            ));
+        assert_eq!(pull_diagnostics_options(&capabilities), Some(options));
+    }
+}
@@ -0,0 +1,381 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use language::{
+    Bias, PointUtf16, Unclipped,
+    proto::{deserialize_anchor, serialize_anchor},
+};
+use lsp::{LanguageServer, LanguageServerId};
+use rpc::proto;
+use text::{Anchor, BufferSnapshot as TextBufferSnapshot};
+
+/// The number of integers used to encode a single token in the LSP wire format.
+const TOKEN_LEN: usize = 5;
+
+/// Semantic tokens that a single language server reported for a buffer.
+#[derive(Clone, Debug)]
+pub struct BufferSemanticTokens {
+    pub server_id: LanguageServerId,
+    pub legend: Arc<lsp::SemanticTokensLegend>,
+    pub tokens: Vec<SemanticToken>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub range: Range<Anchor>,
+    /// Index into the legend's token types.
+    pub token_type: u32,
+    /// Bitset of indices into the legend's token modifiers.
+    pub token_modifiers: u32,
+}
+
+impl BufferSemanticTokens {
+    pub fn token_type(&self, token: &SemanticToken) -> Option<&str> {
+        self.legend
+            .token_types
+            .get(token.token_type as usize)
+            .map(|token_type| token_type.as_str())
+    }
+
+    pub fn token_modifiers<'a>(&'a self, token: &SemanticToken) -> impl Iterator<Item = &'a str> {
+        let bitset = token.token_modifiers;
+        self.legend
+            .token_modifiers
+            .iter()
+            .take(u32::BITS as usize)
+            .enumerate()
+            .filter(move |(ix, _)| bitset & (1 << *ix) != 0)
+            .map(|(_, modifier)| modifier.as_str())
+    }
+}
+
+/// Tokens in the encoded form a language server sent them, kept around so that
+/// subsequent responses can be requested as deltas against them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct RawSemanticTokens {
+    pub result_id: Option<String>,
+    pub data: Vec<u32>,
+}
+
+/// Returns the legend of the server's semantic tokens, and whether the server can
+/// answer with deltas, if it supports requesting tokens for a whole document.
+pub(crate) fn semantic_tokens_capabilities(
+    capabilities: &lsp::ServerCapabilities,
+) -> Option<(lsp::SemanticTokensLegend, bool)> {
+    let options = match capabilities.semantic_tokens_provider.as_ref()? {
+        lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => options,
+        lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+            &options.semantic_tokens_options
+        }
+    };
+    let supports_delta = match options.full.as_ref()? {
+        lsp::SemanticTokensFullOptions::Bool(false) => return None,
+        lsp::SemanticTokensFullOptions::Bool(true) => false,
+        lsp::SemanticTokensFullOptions::Delta { delta } => delta.unwrap_or(false),
+    };
+    Some((options.legend.clone(), supports_delta))
+}
+
+/// Requests the semantic tokens of a document, as a delta if the previous response is given.
+pub(crate) async fn fetch_semantic_tokens(
+    server: &LanguageServer,
+    text_document: lsp::TextDocumentIdentifier,
+    previous: Option<(String, Vec<u32>)>,
+) -> Result<Option<RawSemanticTokens>> {
+    if let Some((previous_result_id, mut data)) = previous {
+        let response = server
+            .request::<lsp::request::SemanticTokensFullDeltaRequest>(
+                lsp::SemanticTokensDeltaParams {
+                    text_document,
+                    previous_result_id,
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                },
+            )
+            .await
+            .into_response()
+            .context("semantic tokens delta LSP request")?;
+        return Ok(match response {
+            None => None,
+            Some(lsp::SemanticTokensFullDeltaResult::Tokens(tokens)) => Some(RawSemanticTokens {
+                result_id: tokens.result_id,
+                data: encode(tokens.data),
+            }),
+            Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta)) => {
+                apply_edits(&mut data, delta.edits)?;
+                Some(RawSemanticTokens {
+                    result_id: delta.result_id,
+                    data,
+                })
+            }
+            Some(lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { edits }) => {
+                apply_edits(&mut data, edits)?;
+                Some(RawSemanticTokens {
+                    result_id: None,
+                    data,
+                })
+            }
+        });
+    }
+
+    let response = server
+        .request::<lsp::request::SemanticTokensFullRequest>(lsp::SemanticTokensParams {
+            text_document,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .into_response()
+        .context("semantic tokens LSP request")?;
+    Ok(response.map(|response| match response {
+        lsp::SemanticTokensResult::Tokens(tokens) => RawSemanticTokens {
+            result_id: tokens.result_id,
+            data: encode(tokens.data),
+        },
+        lsp::SemanticTokensResult::Partial(partial) => RawSemanticTokens {
+            result_id: None,
+            data: encode(partial.data),
+        },
+    }))
+}
+
+fn encode(tokens: Vec<lsp::SemanticToken>) -> Vec<u32> {
+    tokens
+        .into_iter()
+        .flat_map(|token| {
+            [
+                token.delta_line,
+                token.delta_start,
+                token.length,
+                token.token_type,
+                token.token_modifiers_bitset,
+            ]
+        })
+        .collect()
+}
+
+/// Applies the edits of a delta response, which all refer to offsets in the previous data.
+fn apply_edits(data: &mut Vec<u32>, mut edits: Vec<lsp::SemanticTokensEdit>) -> Result<()> {
+    edits.sort_by_key(|edit| edit.start);
+    let mut end_of_previous_edit = 0;
+    for edit in &edits {
+        let start = edit.start as usize;
+        let end = start + edit.delete_count as usize;
+        anyhow::ensure!(
+            start >= end_of_previous_edit && end <= data.len(),
+            "invalid semantic tokens edit {start}..{end} for {} integers",
+            data.len()
+        );
+        end_of_previous_edit = end;
+    }
+    for edit in edits.into_iter().rev() {
+        let start = edit.start as usize;
+        let end = start + edit.delete_count as usize;
+        data.splice(start..end, encode(edit.data.unwrap_or_default()));
+    }
+    Ok(())
+}
+
+/// Resolves the relative positions of encoded tokens against the snapshot the server saw.
+pub(crate) fn decode_semantic_tokens(
+    data: &[u32],
+    snapshot: &TextBufferSnapshot,
+) -> Vec<SemanticToken> {
+    let mut line = 0_u32;
+    let mut start = 0_u32;
+    data.chunks_exact(TOKEN_LEN)
+        .filter_map(|token| {
+            let &[delta_line, delta_start, length, token_type, token_modifiers] = token else {
+                return None;
+            };
+            if delta_line == 0 {
+                start = start.saturating_add(delta_start);
+            } else {
+                line = line.saturating_add(delta_line);
+                start = delta_start;
+            }
+            let start_point =
+                snapshot.clip_point_utf16(Unclipped(PointUtf16::new(line, start)), Bias::Left);
+            let end_point = snapshot.clip_point_utf16(
+                Unclipped(PointUtf16::new(line, start.saturating_add(length))),
+                Bias::Left,
+            );
+            (start_point < end_point).then(|| SemanticToken {
+                range: snapshot.anchor_after(start_point)..snapshot.anchor_before(end_point),
+                token_type,
+                token_modifiers,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn semantic_tokens_to_proto(
+    tokens: &BufferSemanticTokens,
+) -> proto::LanguageServerSemanticTokens {
+    proto::LanguageServerSemanticTokens {
+        server_id: tokens.server_id.to_proto(),
+        token_types: tokens
+            .legend
+            .token_types
+            .iter()
+            .map(|token_type| token_type.as_str().to_string())
+            .collect(),
+        token_modifiers: tokens
+            .legend
+            .token_modifiers
+            .iter()
+            .map(|modifier| modifier.as_str().to_string())
+            .collect(),
+        tokens: tokens
+            .tokens
+            .iter()
+            .map(|token| proto::SemanticToken {
+                start: Some(serialize_anchor(&token.range.start)),
+                end: Some(serialize_anchor(&token.range.end)),
+                token_type: token.token_type,
+                token_modifiers: token.token_modifiers,
+            })
+            .collect(),
+    }
+}
+
+pub(crate) fn semantic_tokens_from_proto(
+    tokens: proto::LanguageServerSemanticTokens,
+) -> Result<BufferSemanticTokens> {
+    Ok(BufferSemanticTokens {
+        server_id: LanguageServerId::from_proto(tokens.server_id),
+        legend: Arc::new(lsp::SemanticTokensLegend {
+            token_types: tokens
+                .token_types
+                .into_iter()
+                .map(lsp::SemanticTokenType::from)
+                .collect(),
+            token_modifiers: tokens
+                .token_modifiers
+                .into_iter()
+                .map(lsp::SemanticTokenModifier::from)
+                .collect(),
+        }),
+        tokens: tokens
+            .tokens
+            .into_iter()
+            .map(|token| {
+                let start = token
+                    .start
+                    .and_then(deserialize_anchor)
+                    .context("missing semantic token start")?;
+                let end = token
+                    .end
+                    .and_then(deserialize_anchor)
+                    .context("missing semantic token end")?;
+                Ok(SemanticToken {
+                    range: start..end,
+                    token_type: token.token_type,
+                    token_modifiers: token.token_modifiers,
+                })
+            })
+            .collect::<Result<_>>()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text::{Buffer as TextBuffer, BufferId, ToPoint as _};
+
+    fn token(delta_line: u32, delta_start: u32, length: u32) -> lsp::SemanticToken {
+        lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type: 0,
+            token_modifiers_bitset: 0,
+        }
+    }
+
+    #[test]
+    fn test_decode_semantic_tokens() {
+        let buffer = TextBuffer::new(
+            0,
+            BufferId::new(1).unwrap(),
+            "fn main() {\n    let mut x = 1;\n}".into(),
+        );
+        let snapshot = buffer.snapshot();
+        let data = encode(vec![
+            token(0, 3, 4),
+            token(1, 8, 3),
+            token(0, 4, 1),
+            // Tokens past the end of a line are clipped away.
+            token(1, 5, 2),
+        ]);
+        let ranges = decode_semantic_tokens(&data, &snapshot)
+            .into_iter()
+            .map(|token| token.range.start.to_point(&snapshot)..token.range.end.to_point(&snapshot))
+            .map(|range| snapshot.text_for_range(range).collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, ["main", "mut", "x"]);
+    }
+
+    #[test]
+    fn test_apply_semantic_tokens_edits() {
+        let mut data = encode(vec![token(0, 0, 1), token(0, 2, 1), token(1, 0, 1)]);
+        apply_edits(
+            &mut data,
+            vec![
+                lsp::SemanticTokensEdit {
+                    start: 10,
+                    delete_count: 5,
+                    data: None,
+                },
+                lsp::SemanticTokensEdit {
+                    start: 0,
+                    delete_count: 0,
+                    data: Some(vec![token(0, 0, 7)]),
+                },
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            data,
+            encode(vec![token(0, 0, 7), token(0, 0, 1), token(0, 2, 1)])
+        );
+
+        assert!(
+            apply_edits(
+                &mut data,
+                vec![lsp::SemanticTokensEdit {
+                    start: 10,
+                    delete_count: 10,
+                    data: None,
+                }],
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_semantic_token_modifiers() {
+        let tokens = BufferSemanticTokens {
+            server_id: LanguageServerId(0),
+            legend: Arc::new(lsp::SemanticTokensLegend {
+                token_types: vec![lsp::SemanticTokenType::VARIABLE],
+                token_modifiers: vec![
+                    lsp::SemanticTokenModifier::DECLARATION,
+                    lsp::SemanticTokenModifier::new("mutable"),
+                    lsp::SemanticTokenModifier::DEPRECATED,
+                ],
+            }),
+            tokens: Vec::new(),
+        };
+        let token = SemanticToken {
+            range: Anchor::MIN..Anchor::MAX,
+            token_type: 0,
+            token_modifiers: 0b110,
+        };
+        assert_eq!(tokens.token_type(&token), Some("variable"));
+        assert_eq!(
+            tokens.token_modifiers(&token).collect::<Vec<_>>(),
+            ["mutable", "deprecated"]
+        );
+    }
+}
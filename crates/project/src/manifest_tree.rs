@@ -59,7 +59,8 @@ impl WorktreeRoots {
                             }
                         }
                     }
-                    WorktreeEvent::UpdatedGitRepositories(_) => {}
+                    WorktreeEvent::UpdatedGitRepositories(_)
+                    | WorktreeEvent::LargeFileLoadProgress { .. } => {}
                     WorktreeEvent::DeletedEntry(entry_id) => {
                         let Some(entry) = this.worktree_store.read(cx).entry_for_id(*entry_id, cx)
                         else {
@@ -12,6 +12,7 @@ mod manifest_tree;
 pub mod prettier_store;
 pub mod project_settings;
 pub mod search;
+mod task_diagnostics;
 mod task_inventory;
 pub mod task_store;
 pub mod terminals;
@@ -72,8 +73,8 @@ use gpui::{
 use itertools::Itertools;
 use language::{
     Buffer, BufferEvent, Capability, CodeLabel, CursorShape, Language, LanguageName,
-    LanguageRegistry, PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainList, Transaction,
-    Unclipped, language_settings::InlayHintKind, proto::split_operations,
+    LanguageRegistry, ParseStatus, PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainList,
+    Transaction, Unclipped, language_settings::InlayHintKind, proto::split_operations,
 };
 use lsp::{
     CodeActionKind, CompletionContext, CompletionItemKind, DocumentHighlightKind, InsertTextMode,
@@ -124,10 +125,12 @@ use worktree_store::{WorktreeStore, WorktreeStoreEvent};
 
 pub use fs::*;
 pub use language::Location;
+pub use lsp_command::{HierarchyCall, HierarchyItem, HierarchyKind};
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
-    BasicContextProvider, ContextProviderWithTasks, Inventory, TaskContexts, TaskSourceKind,
+    BasicContextProvider, ChainedTask, ContextProviderWithTasks, Inventory, TaskChain,
+    TaskContexts, TaskSourceKind,
 };
 
 pub use buffer_store::ProjectTransaction;
@@ -135,6 +138,7 @@ pub use lsp_store::{
     DiagnosticSummary, LanguageServerLogType, LanguageServerProgress, LanguageServerPromptRequest,
     LanguageServerStatus, LanguageServerToQuery, LspStore, LspStoreEvent,
     SERVER_PROGRESS_THROTTLE_TIMEOUT,
+    semantic_tokens::{BufferSemanticTokens, SemanticToken},
 };
 pub use toolchain_store::ToolchainStore;
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
@@ -312,6 +316,7 @@ pub enum Event {
     Rejoined,
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
     ExpandedAllForEntry(WorktreeId, ProjectEntryId),
@@ -733,6 +738,17 @@ impl Hover {
     }
 }
 
+/// A range of a buffer that a language server reported as a link to another document.
+#[derive(Clone, Debug)]
+pub struct DocumentLink {
+    pub range: Range<language::Anchor>,
+    /// The linked document, or `None` if the link has to be resolved first.
+    pub target: Option<lsp::Url>,
+    pub tooltip: Option<String>,
+    pub server_id: LanguageServerId,
+    pub lsp_link: lsp::DocumentLink,
+}
+
 enum EntitySubscription {
     Project(PendingEntitySubscription<Project>),
     BufferStore(PendingEntitySubscription<BufferStore>),
@@ -850,6 +866,7 @@ impl Project {
         DapStore::init(&client, cx);
         BreakpointStore::init(&client);
         context_server_store::init(cx);
+        task_store::init(cx);
     }
 
     pub fn local(
@@ -918,12 +935,27 @@ impl Project {
                 )
             });
 
+            let lsp_store = cx.new(|cx| {
+                LspStore::new_local(
+                    buffer_store.clone(),
+                    worktree_store.clone(),
+                    prettier_store.clone(),
+                    toolchain_store.clone(),
+                    environment.clone(),
+                    languages.clone(),
+                    client.http_client(),
+                    fs.clone(),
+                    cx,
+                )
+            });
+
             let task_store = cx.new(|cx| {
                 TaskStore::local(
                     buffer_store.downgrade(),
                     worktree_store.clone(),
                     toolchain_store.read(cx).as_language_toolchain_store(),
                     environment.clone(),
+                    lsp_store.downgrade(),
                     cx,
                 )
             });
@@ -939,20 +971,6 @@ impl Project {
             cx.subscribe(&settings_observer, Self::on_settings_observer_event)
                 .detach();
 
-            let lsp_store = cx.new(|cx| {
-                LspStore::new_local(
-                    buffer_store.clone(),
-                    worktree_store.clone(),
-                    prettier_store.clone(),
-                    toolchain_store.clone(),
-                    environment.clone(),
-                    languages.clone(),
-                    client.http_client(),
-                    fs.clone(),
-                    cx,
-                )
-            });
-
             let git_store = cx.new(|cx| {
                 GitStore::local(
                     &worktree_store,
@@ -994,6 +1012,7 @@ impl Project {
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 node: Some(node),
                 search_history: Self::new_search_history(),
@@ -1157,6 +1176,7 @@ impl Project {
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 node: Some(node),
                 search_history: Self::new_search_history(),
@@ -1405,6 +1425,7 @@ impl Project {
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 node: None,
                 search_history: Self::new_search_history(),
@@ -2736,6 +2757,7 @@ impl Project {
             }
             LspStoreEvent::RefreshInlayHints => cx.emit(Event::RefreshInlayHints),
             LspStoreEvent::RefreshCodeLens => cx.emit(Event::RefreshCodeLens),
+            LspStoreEvent::RefreshSemanticTokens => cx.emit(Event::RefreshSemanticTokens),
             LspStoreEvent::LanguageServerPrompt(prompt) => {
                 cx.emit(Event::LanguageServerPrompt(prompt.clone()))
             }
@@ -3167,6 +3189,23 @@ impl Project {
         })
     }
 
+    /// Reloads the buffer from disk, decoding its file with the given encoding.
+    pub fn reload_buffer_with_encoding(
+        &self,
+        buffer: Entity<Buffer>,
+        encoding: Encoding,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        self.buffer_store.update(cx, |buffer_store, cx| {
+            buffer_store.reload_buffers_with_encoding(
+                HashSet::from_iter([buffer]),
+                Some(encoding),
+                true,
+                cx,
+            )
+        })
+    }
+
     pub fn reload_images(
         &self,
         images: HashSet<Entity<ImageItem>>,
@@ -3291,6 +3330,84 @@ impl Project {
         )
     }
 
+    /// Returns the call or type hierarchy items at the given position, which are the
+    /// roots to query callers and callees or supertypes and subtypes of.
+    pub fn prepare_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        kind: HierarchyKind,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        match kind {
+            HierarchyKind::Call => self.request_lsp(
+                buffer.clone(),
+                LanguageServerToQuery::FirstCapable,
+                PrepareCallHierarchy { position },
+                cx,
+            ),
+            HierarchyKind::Type => self.request_lsp(
+                buffer.clone(),
+                LanguageServerToQuery::FirstCapable,
+                PrepareTypeHierarchy { position },
+                cx,
+            ),
+        }
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        item: HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetIncomingCalls { item },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        item: HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetOutgoingCalls { item },
+            cx,
+        )
+    }
+
+    pub fn supertypes(
+        &mut self,
+        item: HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetSupertypes { item },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &mut self,
+        item: HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetSubtypes { item },
+            cx,
+        )
+    }
+
     fn document_highlights_impl(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -3628,6 +3745,16 @@ impl Project {
         })
     }
 
+    pub fn semantic_tokens(
+        &mut self,
+        buffer_handle: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Vec<BufferSemanticTokens>>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.semantic_tokens(buffer_handle, cx)
+        })
+    }
+
     pub fn resolve_inlay_hint(
         &self,
         hint: InlayHint,
@@ -3640,6 +3767,27 @@ impl Project {
         })
     }
 
+    pub fn document_links(
+        &mut self,
+        buffer_handle: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.document_links(buffer_handle, cx)
+        })
+    }
+
+    pub fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer_handle: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DocumentLink>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.resolve_document_link(link, buffer_handle, cx)
+        })
+    }
+
     pub fn search(&mut self, query: SearchQuery, cx: &mut Context<Self>) -> Receiver<SearchResult> {
         let (result_tx, result_rx) = smol::channel::unbounded();
 
@@ -3649,6 +3797,7 @@ impl Project {
             self.find_search_candidate_buffers(&query, MAX_SEARCH_RESULT_FILES + 1, cx)
         };
 
+        let languages = self.languages.clone();
         cx.spawn(async move |_, cx| {
             let mut range_count = 0;
             let mut buffer_count = 0;
@@ -3665,6 +3814,11 @@ impl Project {
                 let mut chunk_results = Vec::with_capacity(matching_buffer_chunk.len());
                 for buffer in matching_buffer_chunk {
                     let query = query.clone();
+                    if query.is_structural() || !query.syntax_filter().is_empty() {
+                        // Structural queries and syntax filters are matched against the syntax
+                        // tree, so wait for newly opened buffers to be parsed.
+                        wait_for_syntax_tree(&buffer, &languages, cx).await?;
+                    }
                     let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
                     chunk_results.push(cx.background_spawn(async move {
                         let ranges = query
@@ -3717,8 +3871,9 @@ impl Project {
     ) -> Receiver<Entity<Buffer>> {
         if self.is_local() {
             let fs = self.fs.clone();
+            let languages = self.languages.clone();
             self.buffer_store.update(cx, |buffer_store, cx| {
-                buffer_store.find_search_candidates(query, limit, fs, cx)
+                buffer_store.find_search_candidates(query, limit, fs, languages, cx)
             })
         } else {
             self.find_search_candidates_remote(query, limit, cx)
@@ -5245,6 +5400,44 @@ pub fn sort_worktree_entries(entries: &mut [impl AsRef<Entry>]) {
     });
 }
 
+/// Waits until the buffer's language is detected and its syntax tree is parsed.
+async fn wait_for_syntax_tree(
+    buffer: &Entity<Buffer>,
+    languages: &Arc<LanguageRegistry>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    let is_plain_text = |buffer: &Buffer| {
+        buffer
+            .language()
+            .is_none_or(|language| language == &*language::PLAIN_TEXT)
+    };
+    let mut parse_status = buffer.read_with(cx, |buffer, _| buffer.parse_status())?;
+    // Buffers are only assigned their language once it is loaded, and may be parsed as plain
+    // text before that.
+    let language_load = buffer.read_with(cx, |buffer, cx| {
+        if !is_plain_text(buffer) {
+            return None;
+        }
+        let available_language =
+            languages.language_for_file(buffer.file()?, Some(buffer.as_rope()), cx)?;
+        Some(languages.load_language(&available_language))
+    })?;
+    if let Some(language_load) = language_load {
+        if let Ok(Ok(language)) = language_load.await {
+            if language != *language::PLAIN_TEXT {
+                while buffer.read_with(cx, |buffer, _| is_plain_text(buffer))? {
+                    parse_status.changed().await?;
+                }
+            }
+        }
+    }
+
+    while *parse_status.borrow() != ParseStatus::Idle {
+        parse_status.changed().await?;
+    }
+    Ok(())
+}
+
 fn proto_to_prompt(level: proto::language_server_prompt_request::Level) -> gpui::PromptLevel {
     match level {
         proto::language_server_prompt_request::Level::Info(_) => gpui::PromptLevel::Info,
@@ -1,6 +1,6 @@
 use anyhow::Context as _;
 use collections::HashMap;
-use context_server::ContextServerCommand;
+use context_server::{ContextServerCommand, ContextServerEndpoint, ContextServerHttpTransport};
 use dap::adapters::DebugAdapterName;
 use fs::Fs;
 use futures::StreamExt as _;
@@ -95,6 +95,36 @@ pub struct ContextServerConfiguration {
     /// Consult the documentation for the context server to see what settings
     /// are supported.
     pub settings: Option<serde_json::Value>,
+    /// The URL of a context server running as an HTTP service.
+    ///
+    /// When set, Zed connects to this URL instead of running a command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Additional headers to send with every request to the context server at `url`,
+    /// e.g. for authentication.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// The transport to use when connecting to the context server at `url`.
+    ///
+    /// Default: streamable_http
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transport: Option<ContextServerHttpTransport>,
+}
+
+impl ContextServerConfiguration {
+    /// Returns the HTTP endpoint of this context server, if it is not run locally.
+    pub fn endpoint(&self) -> anyhow::Result<Option<ContextServerEndpoint>> {
+        let Some(url) = self.url.as_ref() else {
+            return Ok(None);
+        };
+        let url =
+            url::Url::parse(url).with_context(|| format!("invalid context server url {url:?}"))?;
+        Ok(Some(ContextServerEndpoint {
+            url,
+            headers: self.headers.clone(),
+            transport: self.transport.unwrap_or_default(),
+        }))
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -423,21 +453,43 @@ impl Settings for ProjectSettings {
                 }
             }
         }
+        #[derive(Deserialize)]
+        struct VsCodeRemoteContextServer {
+            #[serde(rename = "type")]
+            kind: Option<String>,
+            url: String,
+            headers: Option<HashMap<String, String>>,
+        }
+        impl From<VsCodeRemoteContextServer> for ContextServerConfiguration {
+            fn from(server: VsCodeRemoteContextServer) -> Self {
+                Self {
+                    url: Some(server.url),
+                    headers: server.headers.unwrap_or_default(),
+                    transport: match server.kind.as_deref() {
+                        Some("sse") => Some(ContextServerHttpTransport::Sse),
+                        _ => None,
+                    },
+                    ..Default::default()
+                }
+            }
+        }
         if let Some(mcp) = vscode.read_value("mcp").and_then(|v| v.as_object()) {
             current
                 .context_servers
                 .extend(mcp.iter().filter_map(|(k, v)| {
-                    Some((
-                        k.clone().into(),
+                    let configuration = if let Ok(command) =
+                        serde_json::from_value::<VsCodeContextServerCommand>(v.clone())
+                    {
                         ContextServerConfiguration {
-                            command: Some(
-                                serde_json::from_value::<VsCodeContextServerCommand>(v.clone())
-                                    .ok()?
-                                    .into(),
-                            ),
-                            settings: None,
-                        },
-                    ))
+                            command: Some(command.into()),
+                            ..Default::default()
+                        }
+                    } else {
+                        serde_json::from_value::<VsCodeRemoteContextServer>(v.clone())
+                            .ok()?
+                            .into()
+                    };
+                    Some((k.clone().into(), configuration))
                 }));
         }
 
@@ -1,8 +1,8 @@
 #![allow(clippy::format_collect)]
 
 use crate::{
-    Event, git_store::StatusEntry, task_inventory::TaskContexts, task_store::TaskSettingsLocation,
-    *,
+    Event, git_store::StatusEntry, search::SyntaxFilter, task_inventory::TaskContexts,
+    task_store::TaskSettingsLocation, *,
 };
 use buffer_diff::{
     BufferDiffEvent, CALCULATE_DIFF_TASK, DiffHunkSecondaryStatus, DiffHunkStatus,
@@ -18,8 +18,9 @@ use git2::RepositoryInitOptions;
 use gpui::{App, BackgroundExecutor, SemanticVersion, UpdateGlobal};
 use http_client::Url;
 use language::{
-    Diagnostic, DiagnosticEntry, DiagnosticSet, DiskState, FakeLspAdapter, LanguageConfig,
-    LanguageMatcher, LanguageName, LineEnding, OffsetRangeExt, Point, ToPoint,
+    Diagnostic, DiagnosticEntry, DiagnosticSet, DiagnosticSourceKind, DiskState, FakeLspAdapter,
+    LanguageConfig, LanguageMatcher, LanguageName, LineEnding, LoadedLanguage, OffsetRangeExt,
+    Point, ToPoint,
     language_settings::{AllLanguageSettings, LanguageSettingsContent, language_settings},
     tree_sitter_rust, tree_sitter_typescript,
 };
@@ -1567,6 +1568,7 @@ async fn test_disk_based_diagnostics_progress(cx: &mut gpui::TestAppContext) {
                     message: "undefined variable 'A'".to_string(),
                     group_id: 0,
                     is_primary: true,
+                    source_kind: DiagnosticSourceKind::Pushed,
                     ..Default::default()
                 }
             }]
@@ -2083,6 +2085,7 @@ async fn test_transforming_diagnostics(cx: &mut gpui::TestAppContext) {
                         is_disk_based: true,
                         group_id: 1,
                         is_primary: true,
+                        source_kind: DiagnosticSourceKind::Pushed,
                         ..Default::default()
                     },
                 },
@@ -2095,6 +2098,7 @@ async fn test_transforming_diagnostics(cx: &mut gpui::TestAppContext) {
                         is_disk_based: true,
                         group_id: 2,
                         is_primary: true,
+                        source_kind: DiagnosticSourceKind::Pushed,
                         ..Default::default()
                     }
                 }
@@ -2161,6 +2165,7 @@ async fn test_transforming_diagnostics(cx: &mut gpui::TestAppContext) {
                         is_disk_based: true,
                         group_id: 4,
                         is_primary: true,
+                        source_kind: DiagnosticSourceKind::Pushed,
                         ..Default::default()
                     }
                 },
@@ -2173,6 +2178,7 @@ async fn test_transforming_diagnostics(cx: &mut gpui::TestAppContext) {
                         is_disk_based: true,
                         group_id: 3,
                         is_primary: true,
+                        source_kind: DiagnosticSourceKind::Pushed,
                         ..Default::default()
                     },
                 }
@@ -2253,6 +2259,7 @@ async fn test_transforming_diagnostics(cx: &mut gpui::TestAppContext) {
                         is_disk_based: true,
                         group_id: 6,
                         is_primary: true,
+                        source_kind: DiagnosticSourceKind::Pushed,
                         ..Default::default()
                     }
                 },
@@ -2265,6 +2272,7 @@ async fn test_transforming_diagnostics(cx: &mut gpui::TestAppContext) {
                         is_disk_based: true,
                         group_id: 5,
                         is_primary: true,
+                        source_kind: DiagnosticSourceKind::Pushed,
                         ..Default::default()
                     },
                 }
@@ -2273,6 +2281,148 @@ async fn test_transforming_diagnostics(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_pull_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({ "a.rs": "fn a() { A }" }))
+        .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions {
+                        identifier: None,
+                        inter_file_dependencies: false,
+                        workspace_diagnostics: false,
+                        work_done_progress_options: Default::default(),
+                    },
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let (previous_result_ids_tx, mut previous_result_ids_rx) = futures::channel::mpsc::unbounded();
+    fake_server.set_request_handler::<lsp::request::DocumentDiagnosticRequest, _, _>(
+        move |params, _| {
+            previous_result_ids_tx
+                .unbounded_send(params.previous_result_id.clone())
+                .unwrap();
+            async move {
+                let report = if params.previous_result_id.is_some() {
+                    lsp::DocumentDiagnosticReport::Unchanged(
+                        lsp::RelatedUnchangedDocumentDiagnosticReport {
+                            related_documents: None,
+                            unchanged_document_diagnostic_report:
+                                lsp::UnchangedDocumentDiagnosticReport {
+                                    result_id: "1".to_string(),
+                                },
+                        },
+                    )
+                } else {
+                    lsp::DocumentDiagnosticReport::Full(lsp::RelatedFullDocumentDiagnosticReport {
+                        related_documents: None,
+                        full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                            result_id: Some("1".to_string()),
+                            items: vec![lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 9),
+                                    lsp::Position::new(0, 10),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                message: "undefined variable 'A'".to_string(),
+                                ..Default::default()
+                            }],
+                        },
+                    })
+                };
+                Ok(lsp::DocumentDiagnosticReportResult::Report(report))
+            }
+        },
+    );
+
+    let expected_diagnostic = Diagnostic {
+        severity: lsp::DiagnosticSeverity::ERROR,
+        message: "undefined variable 'A'".to_string(),
+        group_id: 0,
+        is_primary: true,
+        source_kind: DiagnosticSourceKind::Pulled,
+        ..Default::default()
+    };
+
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(previous_result_ids_rx.next().await.unwrap(), None);
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                .collect::<Vec<_>>(),
+            &[DiagnosticEntry {
+                range: Point::new(0, 9)..Point::new(0, 10),
+                diagnostic: expected_diagnostic.clone(),
+            }]
+        );
+    });
+
+    // Pushed diagnostics don't replace the pulled ones.
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(&lsp::PublishDiagnosticsParams {
+        uri: Url::from_file_path(path!("/dir/a.rs")).unwrap(),
+        version: None,
+        diagnostics: Vec::new(),
+    });
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                .count(),
+            1
+        );
+    });
+
+    // Edits pull again, sending the previous result ID, and unchanged results keep the
+    // existing diagnostics.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "\n")], None, cx));
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(
+        previous_result_ids_rx.next().await.unwrap(),
+        Some("1".to_string())
+    );
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                .collect::<Vec<_>>(),
+            &[DiagnosticEntry {
+                range: Point::new(1, 9)..Point::new(1, 10),
+                diagnostic: expected_diagnostic,
+            }]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_empty_diagnostic_ranges(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2947,6 +3097,113 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() { b() }\nfn b() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let uri = lsp::Url::from_file_path(path!("/dir/a.rs")).unwrap();
+    let lsp_item = |name: &str, line: u32, end: u32| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: uri.clone(),
+        range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, end)),
+        selection_range: lsp::Range::new(lsp::Position::new(line, 3), lsp::Position::new(line, 4)),
+        data: None,
+    };
+    fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>({
+        let item = lsp_item("b", 1, 9);
+        move |params, _| {
+            let item = item.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(1, 3)
+                );
+                Ok(Some(vec![item]))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>({
+        let caller = lsp_item("a", 0, 14);
+        move |params, _| {
+            let caller = caller.clone();
+            async move {
+                assert_eq!(params.item.name, "b");
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: caller,
+                    from_ranges: vec![lsp::Range::new(
+                        lsp::Position::new(0, 9),
+                        lsp::Position::new(0, 10),
+                    )],
+                }]))
+            }
+        }
+    });
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_hierarchy(&buffer, 18, HierarchyKind::Call, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    let item = items[0].clone();
+    assert_eq!(item.name, "b");
+    assert_eq!(item.hierarchy_kind(), HierarchyKind::Call);
+    cx.update(|cx| {
+        assert_eq!(item.selection_range.to_offset(buffer.read(cx)), 18..19);
+    });
+
+    let calls = project
+        .update(cx, |project, cx| project.incoming_calls(item, cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].item.name, "a");
+    cx.update(|cx| {
+        let call_sites = calls[0]
+            .call_sites
+            .iter()
+            .map(|call_site| call_site.range.to_offset(call_site.buffer.read(cx)))
+            .collect::<Vec<_>>();
+        assert_eq!(call_sites, [9..10]);
+    });
+}
+
 #[gpui::test]
 async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4419,6 +4676,7 @@ async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
                     message: "error 1".to_string(),
                     group_id: 1,
                     is_primary: true,
+                    source_kind: DiagnosticSourceKind::Pushed,
                     ..Default::default()
                 }
             },
@@ -4429,6 +4687,7 @@ async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
                     message: "error 1 hint 1".to_string(),
                     group_id: 1,
                     is_primary: false,
+                    source_kind: DiagnosticSourceKind::Pushed,
                     ..Default::default()
                 }
             },
@@ -4439,6 +4698,7 @@ async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
                     message: "error 2 hint 1".to_string(),
                     group_id: 0,
                     is_primary: false,
+                    source_kind: DiagnosticSourceKind::Pushed,
                     ..Default::default()
                 }
             },
@@ -4449,6 +4709,7 @@ async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
                     message: "error 2 hint 2".to_string(),
                     group_id: 0,
                     is_primary: false,
+                    source_kind: DiagnosticSourceKind::Pushed,
                     ..Default::default()
                 }
             },
@@ -4459,6 +4720,7 @@ async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
                     message: "error 2".to_string(),
                     group_id: 0,
                     is_primary: true,
+                    source_kind: DiagnosticSourceKind::Pushed,
                     ..Default::default()
                 }
             }
@@ -4475,6 +4737,7 @@ async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
                     message: "error 2 hint 1".to_string(),
                     group_id: 0,
                     is_primary: false,
+                    source_kind: DiagnosticSourceKind::Pushed,
                     ..Default::default()
                 }
             },
@@ -4485,6 +4748,7 @@ async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
                     message: "error 2 hint 2".to_string(),
                     group_id: 0,
                     is_primary: false,
+                    source_kind: DiagnosticSourceKind::Pushed,
                     ..Default::default()
                 }
             },
@@ -4495,6 +4759,7 @@ async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
                     message: "error 2".to_string(),
                     group_id: 0,
                     is_primary: true,
+                    source_kind: DiagnosticSourceKind::Pushed,
                     ..Default::default()
                 }
             }
@@ -4511,6 +4776,7 @@ async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
                     message: "error 1".to_string(),
                     group_id: 1,
                     is_primary: true,
+                    source_kind: DiagnosticSourceKind::Pushed,
                     ..Default::default()
                 }
             },
@@ -4521,6 +4787,7 @@ async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
                     message: "error 1 hint 1".to_string(),
                     group_id: 1,
                     is_primary: false,
+                    source_kind: DiagnosticSourceKind::Pushed,
                     ..Default::default()
                 }
             },
@@ -4874,6 +5141,156 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_search_with_syntax_filter(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "// call foo\nfn foo() { bar(\"foo\") }",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::LANGUAGE.into()),
+    )
+    .with_override_query("(line_comment) @comment.inclusive (string_literal) @string")
+    .unwrap();
+    project.read_with(cx, |project, _| {
+        project.languages().add(Arc::new(language));
+    });
+
+    let query = |syntax_filter| {
+        SearchQuery::text(
+            "foo",
+            false,
+            true,
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+        )
+        .unwrap()
+        .with_syntax_filter(syntax_filter)
+    };
+
+    assert_eq!(
+        search(&project, query(SyntaxFilter::default()), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([(
+            separator!("dir/one.rs").to_string(),
+            vec![8..11, 15..18, 28..31]
+        )])
+    );
+    assert_eq!(
+        search(
+            &project,
+            query(SyntaxFilter {
+                exclude_comments: true,
+                exclude_strings: true,
+                exclude_code: false,
+            }),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([(separator!("dir/one.rs").to_string(), vec![15..18])])
+    );
+    assert_eq!(
+        search(
+            &project,
+            query(SyntaxFilter {
+                exclude_comments: false,
+                exclude_strings: true,
+                exclude_code: true,
+            }),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([(separator!("dir/one.rs").to_string(), vec![8..11])])
+    );
+}
+
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "fn main() { foo(1); bar(2); }",
+            "two.rs": "fn two() { bar(2); }",
+            "three.txt": "foo(3);",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    // The buffer is parsed as plain text until the language registered afterwards is loaded
+    // and assigned to it.
+    let _buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/one.rs"), cx)
+        })
+        .await
+        .unwrap();
+    project.read_with(cx, |project, _| {
+        let languages = project.languages();
+        languages.register_native_grammars([("rust", tree_sitter_rust::LANGUAGE)]);
+        let matcher = LanguageMatcher {
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        };
+        languages.register_language(
+            "Rust".into(),
+            Some("rust".into()),
+            matcher.clone(),
+            false,
+            Arc::new(move || {
+                Ok(LoadedLanguage {
+                    config: LanguageConfig {
+                        name: "Rust".into(),
+                        grammar: Some("rust".into()),
+                        matcher: matcher.clone(),
+                        ..Default::default()
+                    },
+                    queries: Default::default(),
+                    context_provider: None,
+                    toolchain_provider: None,
+                })
+            }),
+        );
+    });
+
+    let query = SearchQuery::structural(
+        "foo($A)",
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        search(&project, query, cx).await.unwrap(),
+        HashMap::from_iter([(separator!("dir/one.rs").to_string(), vec![12..18])])
+    );
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -5827,6 +6244,122 @@ async fn test_hovers_with_empty_parts(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_document_links(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "import './b';\n// https://zed.dev",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let fake_server = fake_language_servers
+        .next()
+        .await
+        .expect("failed to get the language server");
+
+    let b_url = lsp::Url::from_file_path(path!("/dir/b.ts")).unwrap();
+    let web_url = lsp::Url::parse("https://zed.dev").unwrap();
+    let unresolved_target = web_url.clone();
+    fake_server.set_request_handler::<lsp::request::DocumentLinkRequest, _, _>({
+        let b_url = b_url.clone();
+        move |_, _| {
+            let b_url = b_url.clone();
+            async move {
+                Ok(Some(vec![
+                    lsp::DocumentLink {
+                        range: lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 12)),
+                        target: Some(b_url),
+                        tooltip: None,
+                        data: None,
+                    },
+                    lsp::DocumentLink {
+                        range: lsp::Range::new(lsp::Position::new(1, 3), lsp::Position::new(1, 18)),
+                        target: None,
+                        tooltip: Some("Open the website".to_string()),
+                        data: Some(json!({ "id": 1 })),
+                    },
+                ]))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::DocumentLinkResolve, _, _>(
+        move |mut link, _| {
+            let target = unresolved_target.clone();
+            async move {
+                assert_eq!(link.data, Some(json!({ "id": 1 })));
+                link.target = Some(target);
+                Ok(link)
+            }
+        },
+    );
+
+    let links = project
+        .update(cx, |project, cx| project.document_links(&buffer, cx))
+        .await
+        .unwrap();
+    let link_ranges = buffer.read_with(cx, |buffer, _| {
+        links
+            .iter()
+            .map(|link| link.range.to_point(buffer))
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(
+        link_ranges,
+        vec![
+            Point::new(0, 7)..Point::new(0, 12),
+            Point::new(1, 3)..Point::new(1, 18)
+        ]
+    );
+    assert_eq!(links[0].target, Some(b_url));
+    assert_eq!(links[1].target, None);
+
+    let resolved_link = project
+        .update(cx, |project, cx| {
+            project.resolve_document_link(links[1].clone(), buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(resolved_link.target, Some(web_url));
+    assert_eq!(resolved_link.tooltip.as_deref(), Some("Open the website"));
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(
+            resolved_link.range.to_point(buffer),
+            Point::new(1, 3)..Point::new(1, 18)
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_code_actions_only_kinds(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -7409,6 +7942,60 @@ async fn test_home_dir_as_git_repository(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_jj_colocated_repository(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/root"),
+        json!({
+            "colocated": {
+                ".git": {},
+                ".jj": {},
+                "a.txt": "A",
+            },
+            "plain": {
+                ".git": {},
+                "b.txt": "B",
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(
+        fs.clone(),
+        [
+            path!("/root/colocated").as_ref(),
+            path!("/root/plain").as_ref(),
+        ],
+        cx,
+    )
+    .await;
+    cx.run_until_parked();
+
+    project.read_with(cx, |project, cx| {
+        let mut repositories = project
+            .repositories(cx)
+            .values()
+            .map(|repository| {
+                let repository = repository.read(cx);
+                (
+                    repository.work_directory_abs_path.clone(),
+                    repository.is_jj_colocated,
+                )
+            })
+            .collect::<Vec<_>>();
+        repositories.sort();
+        assert_eq!(
+            repositories,
+            [
+                (Arc::from(Path::new(path!("/root/colocated"))), true),
+                (Arc::from(Path::new(path!("/root/plain"))), false),
+            ]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_git_repository_status(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3,7 +3,10 @@ use anyhow::Result;
 use client::proto;
 use fancy_regex::{Captures, Regex, RegexBuilder};
 use gpui::Entity;
-use language::{Buffer, BufferSnapshot, CharKind};
+use language::{
+    Buffer, BufferSnapshot, CharKind, LanguageRegistry, StructuralMatch, StructuralQuery,
+    SyntaxScope,
+};
 use smol::future::yield_now;
 use std::{
     borrow::Cow,
@@ -31,6 +34,28 @@ pub enum SearchInputKind {
     Exclude,
 }
 
+/// The kinds of syntax that search matches are excluded from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SyntaxFilter {
+    pub exclude_comments: bool,
+    pub exclude_strings: bool,
+    pub exclude_code: bool,
+}
+
+impl SyntaxFilter {
+    pub fn is_empty(&self) -> bool {
+        !(self.exclude_comments || self.exclude_strings || self.exclude_code)
+    }
+
+    pub fn allows(&self, scope: SyntaxScope) -> bool {
+        match scope {
+            SyntaxScope::Comment => !self.exclude_comments,
+            SyntaxScope::String => !self.exclude_strings,
+            SyntaxScope::Code => !self.exclude_code,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SearchInputs {
     query: Arc<str>,
@@ -38,6 +63,7 @@ pub struct SearchInputs {
     files_to_exclude: PathMatcher,
     match_full_paths: bool,
     buffers: Option<Vec<Entity<Buffer>>>,
+    syntax_filter: SyntaxFilter,
 }
 
 impl SearchInputs {
@@ -53,6 +79,9 @@ impl SearchInputs {
     pub fn buffers(&self) -> &Option<Vec<Entity<Buffer>>> {
         &self.buffers
     }
+    pub fn syntax_filter(&self) -> SyntaxFilter {
+        self.syntax_filter
+    }
 }
 #[derive(Clone, Debug)]
 pub enum SearchQuery {
@@ -75,6 +104,13 @@ pub enum SearchQuery {
         one_match_per_line: bool,
         inner: SearchInputs,
     },
+
+    Structural {
+        query: Arc<StructuralQuery>,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 static WORD_MATCH_TEST: LazyLock<Regex> = LazyLock::new(|| {
@@ -124,6 +160,7 @@ impl SearchQuery {
             files_to_include,
             match_full_paths,
             buffers,
+            syntax_filter: SyntaxFilter::default(),
         };
         Ok(Self::Text {
             search,
@@ -179,6 +216,7 @@ impl SearchQuery {
             files_to_include,
             match_full_paths,
             buffers,
+            syntax_filter: SyntaxFilter::default(),
         };
         Ok(Self::Regex {
             regex,
@@ -192,8 +230,52 @@ impl SearchQuery {
         })
     }
 
+    /// Create a structural query, matched against the syntax trees of buffers
+    ///
+    /// The query is either a Tree-sitter query or a code pattern with `$NAME` and `$$$NAME`
+    /// metavariables. Replacements can refer to the metavariables or query captures by name.
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+        match_full_paths: bool,
+        buffers: Option<Vec<Entity<Buffer>>>,
+    ) -> Result<Self> {
+        let query = query.to_string();
+        let structural_query = StructuralQuery::new(&query)?;
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+            match_full_paths,
+            buffers,
+            syntax_filter: SyntaxFilter::default(),
+        };
+        Ok(Self::Structural {
+            query: Arc::new(structural_query),
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
     pub fn from_proto(message: proto::SearchQuery) -> Result<Self> {
-        if message.regex {
+        let syntax_filter = SyntaxFilter {
+            exclude_comments: message.exclude_comments,
+            exclude_strings: message.exclude_strings,
+            exclude_code: message.exclude_code,
+        };
+        let query = if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                deserialize_path_matches(&message.files_to_include)?,
+                deserialize_path_matches(&message.files_to_exclude)?,
+                message.match_full_paths,
+                None, // search opened only don't need search remote
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -216,7 +298,8 @@ impl SearchQuery {
                 false,
                 None, // search opened only don't need search remote
             )
-        }
+        }?;
+        Ok(query.with_syntax_filter(syntax_filter))
     }
 
     pub fn with_replacement(mut self, new_replacement: String) -> Self {
@@ -228,6 +311,10 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
@@ -235,6 +322,18 @@ impl SearchQuery {
         }
     }
 
+    /// Excludes the matches within the given kinds of syntax.
+    pub fn with_syntax_filter(mut self, syntax_filter: SyntaxFilter) -> Self {
+        match self {
+            Self::Text { ref mut inner, .. }
+            | Self::Regex { ref mut inner, .. }
+            | Self::Structural { ref mut inner, .. } => {
+                inner.syntax_filter = syntax_filter;
+                self
+            }
+        }
+    }
+
     pub fn to_proto(&self) -> proto::SearchQuery {
         proto::SearchQuery {
             query: self.as_str().to_string(),
@@ -245,6 +344,10 @@ impl SearchQuery {
             files_to_include: self.files_to_include().sources().join(","),
             files_to_exclude: self.files_to_exclude().sources().join(","),
             match_full_paths: self.match_full_paths(),
+            structural: self.is_structural(),
+            exclude_comments: self.syntax_filter().exclude_comments,
+            exclude_strings: self.syntax_filter().exclude_strings,
+            exclude_code: self.syntax_filter().exclude_code,
         }
     }
 
@@ -285,20 +388,50 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            Self::Structural { query, .. } => {
+                // Files can only be matched against the syntax tree once they're loaded into
+                // buffers, so only skip the ones that can't contain the pattern.
+                let mut text = String::new();
+                reader.read_to_string(&mut text)?;
+                Ok(query.may_match(&text))
+            }
+        }
+    }
+    /// Returns whether the file at the path may contain matches, judging by its language.
+    ///
+    /// Only structural queries depend on the language, as they never match in languages
+    /// they do not compile for.
+    pub(crate) async fn may_match_language(
+        &self,
+        path: &Path,
+        languages: &Arc<LanguageRegistry>,
+    ) -> bool {
+        let Self::Structural { query, .. } = self else {
+            return true;
+        };
+        match languages.language_for_file_path(path).await {
+            Ok(language) => query.may_match_language(&language),
+            // The language of the file may still be detected from its content once it's opened.
+            Err(_) => true,
         }
     }
+
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
+    ///
+    /// Structural queries need the syntax tree around the match; use [`Self::replacement_in_buffer`] for them.
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
         match self {
-            SearchQuery::Text { replacement, .. } => replacement.clone().map(Cow::from),
+            SearchQuery::Text { replacement, .. } | SearchQuery::Structural { replacement, .. } => {
+                replacement.clone().map(Cow::from)
+            }
             SearchQuery::Regex {
                 regex, replacement, ..
             } => {
@@ -322,6 +455,59 @@ impl SearchQuery {
         }
     }
 
+    /// Replaces the search hit at the given range of the buffer if replacement is set.
+    ///
+    /// Unlike [`Self::replacement_for`], this expands the metavariables and captures that
+    /// replacements of structural queries refer to.
+    pub fn replacement_in_buffer(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        match self {
+            SearchQuery::Structural {
+                query, replacement, ..
+            } => {
+                let replacement = replacement.as_ref()?;
+                let mat = query.match_at(buffer, range)?;
+                Some(mat.expand_template(replacement, buffer))
+            }
+            _ => {
+                let text = buffer.text_for_range(range).collect::<String>();
+                self.replacement_for(&text).map(Cow::into_owned)
+            }
+        }
+    }
+
+    /// Replaces the search hits at the given ranges of the buffer if replacement is set.
+    ///
+    /// Structural queries search the buffer once for all the ranges, unlike when calling
+    /// [`Self::replacement_in_buffer`] for each of them.
+    pub fn replacements_in_buffer(
+        &self,
+        buffer: &BufferSnapshot,
+        ranges: &[Range<usize>],
+    ) -> Vec<Option<String>> {
+        match self {
+            SearchQuery::Structural {
+                query, replacement, ..
+            } => {
+                let Some(replacement) = replacement else {
+                    return vec![None; ranges.len()];
+                };
+                query
+                    .matches_at(buffer, ranges)
+                    .into_iter()
+                    .map(|mat| Some(mat?.expand_template(replacement, buffer)))
+                    .collect()
+            }
+            _ => ranges
+                .iter()
+                .map(|range| self.replacement_in_buffer(buffer, range.clone()))
+                .collect(),
+        }
+    }
+
     pub async fn search(
         &self,
         buffer: &BufferSnapshot,
@@ -414,6 +600,22 @@ impl SearchQuery {
                     }
                 }
             }
+
+            Self::Structural { query, .. } => {
+                let range = range_offset..range_offset + rope.len();
+                matches.extend(query.find_matches(buffer, range).into_iter().map(
+                    |StructuralMatch { range, .. }| {
+                        range.start - range_offset..range.end - range_offset
+                    },
+                ));
+            }
+        }
+
+        let syntax_filter = self.syntax_filter();
+        if !syntax_filter.is_empty() {
+            matches.retain(|range| {
+                syntax_filter.allows(buffer.syntax_scope_at(range_offset + range.start))
+            });
         }
 
         matches
@@ -431,6 +633,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -438,6 +641,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => true,
         }
     }
 
@@ -449,6 +653,9 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Structural {
+                include_ignored, ..
+            } => *include_ignored,
         }
     }
 
@@ -456,6 +663,10 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -481,6 +692,10 @@ impl SearchQuery {
         self.as_inner().match_full_paths
     }
 
+    pub fn syntax_filter(&self) -> SyntaxFilter {
+        self.as_inner().syntax_filter
+    }
+
     /// Check match full paths to determine whether you're required to pass a fully qualified
     /// project path (starts with a project root).
     pub fn match_path(&self, file_path: &Path) -> bool {
@@ -499,20 +714,22 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 
     /// Whether this search should replace only one match per line, instead of
     /// all matches.
-    /// Returns `None` for text searches, as only regex searches support this
-    /// option.
+    /// Returns `None` for text and structural searches, as only regex searches
+    /// support this option.
     pub fn one_match_per_line(&self) -> Option<bool> {
         match self {
             Self::Regex {
                 one_match_per_line, ..
             } => Some(*one_match_per_line),
-            Self::Text { .. } => None,
+            Self::Text { .. } | Self::Structural { .. } => None,
         }
     }
 }
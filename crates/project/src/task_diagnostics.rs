@@ -0,0 +1,245 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use collections::HashMap;
+use fs::normalize_path;
+use gpui::{App, Context, Entity, EventEmitter, Subscription, Task, WeakEntity};
+use language::{
+    Diagnostic, DiagnosticEntry, DiagnosticSeverity, DiagnosticSourceKind, LanguageServerId,
+    PointUtf16, Unclipped,
+};
+use lsp::NumberOrString;
+use task::{CompiledProblemMatcher, Problem, ProblemMatchState, ProblemMatcher, ProblemSeverity};
+use terminal::Terminal;
+use util::ResultExt as _;
+
+use crate::lsp_store::LspStore;
+
+/// How often the task output is scanned for problems while the task keeps printing.
+const SCAN_INTERVAL: Duration = Duration::from_millis(300);
+
+/// The diagnostics of all task terminals are stored together, under an id that no language server gets.
+const TASK_DIAGNOSTICS_SERVER_ID: LanguageServerId = LanguageServerId(usize::MAX);
+
+/// Finds the problems that the problem matchers of a task report in its terminal output.
+///
+/// The output is matched as it gets printed, a chunk of new lines at a time.
+pub(crate) struct TaskDiagnostics {
+    matchers: Arc<[CompiledProblemMatcher]>,
+    states: Vec<ProblemMatchState>,
+    cwd: Option<PathBuf>,
+    terminal: WeakEntity<Terminal>,
+    problems: HashMap<PathBuf, Vec<Problem>>,
+    task_finished: bool,
+    scan_task: Option<Task<()>>,
+    rescan_needed: bool,
+    _completion_task: Task<()>,
+    _subscription: Subscription,
+}
+
+/// The problems found in the files changed.
+pub(crate) struct ProblemsUpdated(pub Vec<PathBuf>);
+
+impl EventEmitter<ProblemsUpdated> for TaskDiagnostics {}
+
+impl TaskDiagnostics {
+    pub(crate) fn new(
+        matchers: Vec<ProblemMatcher>,
+        cwd: Option<PathBuf>,
+        terminal: &Entity<Terminal>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let matchers: Arc<[CompiledProblemMatcher]> = matchers
+            .into_iter()
+            .filter_map(|matcher| CompiledProblemMatcher::new(matcher).log_err())
+            .collect();
+        let states = matchers
+            .iter()
+            .map(|_| ProblemMatchState::default())
+            .collect();
+        let _subscription = cx.subscribe(terminal, |this, _, event, cx| {
+            if let terminal::Event::Wakeup = event {
+                this.schedule_scan(cx);
+            }
+        });
+        // The task summary is printed after the last wakeup, and its last line is only read once the task finishes.
+        let completion = terminal.read(cx).wait_for_completed_task(cx);
+        let _completion_task = cx.spawn(async move |this, cx| {
+            completion.await;
+            this.update(cx, |this, cx| {
+                this.task_finished = true;
+                this.schedule_scan(cx);
+            })
+            .ok();
+        });
+
+        Self {
+            matchers,
+            states,
+            cwd,
+            terminal: terminal.downgrade(),
+            problems: HashMap::default(),
+            task_finished: false,
+            scan_task: None,
+            rescan_needed: false,
+            _completion_task,
+            _subscription,
+        }
+    }
+
+    /// The problems found in the file, in the order the task printed them.
+    pub(crate) fn problems(&self, path: &Path) -> &[Problem] {
+        self.problems.get(path).map_or(&[], Vec::as_slice)
+    }
+
+    pub(crate) fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.problems.keys()
+    }
+
+    fn schedule_scan(&mut self, cx: &mut Context<Self>) {
+        if self.scan_task.is_some() {
+            self.rescan_needed = true;
+            return;
+        }
+
+        self.scan_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(SCAN_INTERVAL).await;
+            let Some((new_lines, matchers, mut states)) = this
+                .update(cx, |this, cx| {
+                    let task_finished = this.task_finished;
+                    let new_lines = this
+                        .terminal
+                        .update(cx, |terminal, _| terminal.read_new_lines(task_finished))
+                        .ok()?;
+                    Some((
+                        new_lines,
+                        this.matchers.clone(),
+                        std::mem::take(&mut this.states),
+                    ))
+                })
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            let states = cx
+                .background_spawn(async move {
+                    if new_lines.reread {
+                        states = matchers
+                            .iter()
+                            .map(|_| ProblemMatchState::default())
+                            .collect();
+                    }
+                    for (matcher, state) in matchers.iter().zip(&mut states) {
+                        matcher.match_more_lines(state, new_lines.lines.iter().map(String::as_str));
+                    }
+                    states
+                })
+                .await;
+
+            this.update(cx, |this, cx| {
+                this.scan_task = None;
+                this.states = states;
+                this.update_problems(cx);
+                if this.rescan_needed {
+                    this.rescan_needed = false;
+                    this.schedule_scan(cx);
+                }
+            })
+            .ok();
+        }));
+    }
+
+    fn update_problems(&mut self, cx: &mut Context<Self>) {
+        let mut problems_by_path = HashMap::<PathBuf, Vec<Problem>>::default();
+        for problem in self.states.iter().flat_map(ProblemMatchState::problems) {
+            let path = match &self.cwd {
+                Some(cwd) => normalize_path(&cwd.join(&problem.path)),
+                None => normalize_path(Path::new(&problem.path)),
+            };
+            if path.is_absolute() {
+                problems_by_path
+                    .entry(path)
+                    .or_default()
+                    .push(problem.clone());
+            }
+        }
+
+        let mut updated_paths = Vec::new();
+        for path in self.problems.keys() {
+            if !problems_by_path.contains_key(path) {
+                updated_paths.push(path.clone());
+            }
+        }
+        for (path, problems) in &problems_by_path {
+            if self.problems.get(path) != Some(problems) {
+                updated_paths.push(path.clone());
+            }
+        }
+        self.problems = problems_by_path;
+        if !updated_paths.is_empty() {
+            cx.emit(ProblemsUpdated(updated_paths));
+        }
+    }
+}
+
+/// Publishes the problems that all task terminals found in the given files as their task diagnostics.
+pub(crate) fn publish_task_diagnostics<'a>(
+    task_diagnostics: impl Iterator<Item = &'a Entity<TaskDiagnostics>>,
+    paths: Vec<PathBuf>,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut App,
+) {
+    let task_diagnostics = task_diagnostics.collect::<Vec<_>>();
+    let updates = paths
+        .into_iter()
+        .map(|path| {
+            let diagnostics = task_diagnostics
+                .iter()
+                .flat_map(|task_diagnostics| task_diagnostics.read(cx).problems(&path))
+                .cloned()
+                .enumerate()
+                .map(|(group_id, problem)| to_diagnostic_entry(group_id, problem))
+                .collect::<Vec<_>>();
+            (path, diagnostics)
+        })
+        .collect::<Vec<_>>();
+
+    lsp_store.update(cx, |lsp_store, cx| {
+        for (path, diagnostics) in updates {
+            lsp_store
+                .update_diagnostic_entries(TASK_DIAGNOSTICS_SERVER_ID, path, None, diagnostics, cx)
+                .log_err();
+        }
+    });
+}
+
+fn to_diagnostic_entry(
+    group_id: usize,
+    problem: Problem,
+) -> DiagnosticEntry<Unclipped<PointUtf16>> {
+    let start = PointUtf16::new(problem.start.row, problem.start.column);
+    let end = PointUtf16::new(problem.end.row, problem.end.column);
+    DiagnosticEntry {
+        range: Unclipped(start)..Unclipped(end),
+        diagnostic: Diagnostic {
+            source: problem.source,
+            code: problem.code.map(NumberOrString::String),
+            severity: match problem.severity {
+                ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+            },
+            message: problem.message,
+            group_id,
+            is_primary: true,
+            is_disk_based: true,
+            source_kind: DiagnosticSourceKind::Task,
+            ..Diagnostic::default()
+        },
+    }
+}
@@ -8,13 +8,13 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result, bail};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
 use gpui::{App, AppContext as _, Entity, SharedString, Task};
 use itertools::Itertools;
 use language::{
-    Buffer, ContextProvider, File, Language, LanguageToolchainStore, Location,
+    Buffer, ContextProvider, File, Language, LanguageName, LanguageToolchainStore, Location,
     language_settings::language_settings,
 };
 use lsp::{LanguageServerId, LanguageServerName};
@@ -37,6 +37,15 @@ pub struct Inventory {
     last_scheduled_scenarios: VecDeque<DebugScenario>,
     templates_from_settings: InventoryFor<TaskTemplate>,
     scenarios_from_settings: InventoryFor<DebugScenario>,
+    templates_from_providers: HashMap<WorktreeId, HashMap<Arc<str>, ProviderTemplates>>,
+}
+
+/// Task templates a task provider returned for a worktree.
+#[derive(Debug)]
+struct ProviderTemplates {
+    /// The languages whose files the templates apply to, or all files if empty.
+    languages: Vec<LanguageName>,
+    templates: Vec<TaskTemplate>,
 }
 
 // Helper trait for better error messages in [InventoryFor]
@@ -131,10 +140,36 @@ pub enum TaskSourceKind {
     },
     /// Languages-specific tasks coming from extensions.
     Language { name: SharedString },
+    /// Tasks coming from the task providers of extensions, for a given worktree.
+    Provider { id: Arc<str>, worktree: WorktreeId },
     /// Language-specific tasks coming from LSP servers.
     Lsp(LanguageServerId),
 }
 
+/// A task together with the tasks that have to finish before it starts, see [`TaskTemplate::depends_on`].
+#[derive(Debug, Clone)]
+pub struct TaskChain {
+    /// All tasks in the chain, each task after the tasks it depends on, and the chain's task last.
+    /// A task that several tasks depend on is only listed, and run, once.
+    pub tasks: Vec<ChainedTask>,
+}
+
+impl TaskChain {
+    /// The task the chain was resolved for.
+    pub fn task(&self) -> &ChainedTask {
+        self.tasks.last().expect("task chain contains its task")
+    }
+}
+
+/// A task in a [`TaskChain`].
+#[derive(Debug, Clone)]
+pub struct ChainedTask {
+    pub task_source_kind: TaskSourceKind,
+    pub task: ResolvedTask,
+    /// Indices of the tasks this task depends on in [`TaskChain::tasks`], in the order they are listed in the template.
+    pub dependencies: Vec<usize>,
+}
+
 /// A collection of task contexts, derived from the current state of the workspace.
 /// Only contains worktrees that are visible and with their root being a directory.
 #[derive(Debug, Default)]
@@ -211,6 +246,7 @@ impl TaskSourceKind {
                 format!("{id_base}_{id}_{}", directory_in_worktree.display())
             }
             Self::Language { name } => format!("language_{name}"),
+            Self::Provider { id, worktree } => format!("provider_{id}_{worktree}"),
             Self::Lsp(server_id) => format!("lsp_{server_id}"),
         }
     }
@@ -312,8 +348,8 @@ impl Inventory {
     }
 
     /// Pulls its task sources relevant to the worktree and the language given,
-    /// returns all task templates with their source kinds, worktree tasks first, language tasks second
-    /// and global tasks last. No specific order inside source kinds groups.
+    /// returns all task templates with their source kinds, worktree tasks first, language tasks second,
+    /// task provider tasks third and global tasks last. No specific order inside source kinds groups.
     pub fn list_tasks(
         &self,
         file: Option<Arc<dyn File>>,
@@ -328,6 +364,10 @@ impl Inventory {
         let task_source_kind = language.as_ref().map(|language| TaskSourceKind::Language {
             name: language.name().into(),
         });
+        let language_name = language.as_ref().map(|language| language.name());
+        let provider_tasks = worktree.into_iter().flat_map(|worktree| {
+            self.worktree_templates_from_providers(worktree, language_name.clone())
+        });
         let language_tasks = language
             .filter(|language| {
                 language_settings(Some(language.name()), file.as_ref(), cx)
@@ -341,6 +381,7 @@ impl Inventory {
 
         worktree_tasks
             .chain(language_tasks)
+            .chain(provider_tasks)
             .chain(global_tasks)
             .collect()
     }
@@ -364,6 +405,7 @@ impl Inventory {
         let task_source_kind = language.as_ref().map(|language| TaskSourceKind::Language {
             name: language.name().into(),
         });
+        let language_name = language.as_ref().map(|language| language.name());
         let file = location.and_then(|location| location.buffer.read(cx).file().cloned());
 
         let mut task_labels_to_ids = HashMap::<String, HashSet<TaskId>>::default();
@@ -416,16 +458,22 @@ impl Inventory {
             .into_iter()
             .flat_map(|tasks| tasks.0.into_iter())
             .flat_map(|task| Some((task_source_kind.clone()?, task)));
+        let provider_tasks = worktree.into_iter().flat_map(|worktree| {
+            self.worktree_templates_from_providers(worktree, language_name.clone())
+        });
         let worktree_tasks = worktree
             .into_iter()
             .flat_map(|worktree| self.worktree_templates_from_settings(worktree))
             .chain(language_tasks)
+            .chain(provider_tasks)
             .chain(global_tasks);
 
         let new_resolved_tasks = worktree_tasks
             .flat_map(|(kind, task)| {
                 let id_base = kind.to_id_base();
-                if let TaskSourceKind::Worktree { id, .. } = &kind {
+                if let TaskSourceKind::Worktree { id, .. }
+                | TaskSourceKind::Provider { worktree: id, .. } = &kind
+                {
                     None.or_else(|| {
                         let (_, _, item_context) = task_contexts
                             .active_item_context
@@ -441,16 +489,12 @@ impl Inventory {
                         task.resolve_task(&id_base, worktree_context)
                     })
                     .or_else(|| {
-                        if let TaskSourceKind::Worktree { id, .. } = &kind {
-                            let worktree_context = task_contexts
-                                .other_worktree_contexts
-                                .iter()
-                                .find(|(worktree_id, _)| worktree_id == id)
-                                .map(|(_, context)| context)?;
-                            task.resolve_task(&id_base, worktree_context)
-                        } else {
-                            None
-                        }
+                        let worktree_context = task_contexts
+                            .other_worktree_contexts
+                            .iter()
+                            .find(|(worktree_id, _)| worktree_id == id)
+                            .map(|(_, context)| context)?;
+                        task.resolve_task(&id_base, worktree_context)
                     })
                 } else {
                     None.or_else(|| {
@@ -485,6 +529,54 @@ impl Inventory {
         (previously_spawned_tasks, new_resolved_tasks)
     }
 
+    /// Resolves the tasks the given task depends on, recursively, with the same context the task was resolved with.
+    /// Dependencies are looked up by their labels among the tasks of the task's worktree and the global tasks.
+    ///
+    /// Fails if a dependency cannot be found or resolved, or if the dependencies form a cycle.
+    pub fn resolve_task_chain(
+        &self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+    ) -> Result<TaskChain> {
+        let worktree = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } | TaskSourceKind::Provider { worktree: id, .. } => {
+                Some(*id)
+            }
+            _ => None,
+        };
+        let provider_templates = match &task_source_kind {
+            TaskSourceKind::Provider { id, worktree } => self
+                .templates_from_providers
+                .get(worktree)
+                .and_then(|providers| providers.get(id))
+                .map(|provider| {
+                    provider
+                        .templates
+                        .iter()
+                        .map(|template| (task_source_kind.clone(), template.clone()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        let templates = worktree
+            .into_iter()
+            .flat_map(|worktree| self.worktree_templates_from_settings(worktree))
+            .chain(provider_templates)
+            .chain(self.global_templates_from_settings())
+            .collect::<Vec<_>>();
+        let mut labels_path = vec![resolved_task.original_task().label.clone()];
+        let mut tasks = Vec::new();
+        resolve_task_dependencies(
+            task_source_kind,
+            resolved_task,
+            &templates,
+            &mut labels_path,
+            &mut tasks,
+        )?;
+        Ok(TaskChain { tasks })
+    }
+
     /// Returns the last scheduled task by task_id if provided.
     /// Otherwise, returns the last scheduled task.
     pub fn last_scheduled_task(
@@ -546,6 +638,66 @@ impl Inventory {
         self.templates_from_settings.worktree_scenarios(worktree)
     }
 
+    /// Returns the templates task providers returned for the worktree that apply to files of the language given.
+    fn worktree_templates_from_providers(
+        &self,
+        worktree: WorktreeId,
+        language_name: Option<LanguageName>,
+    ) -> impl '_ + Iterator<Item = (TaskSourceKind, TaskTemplate)> {
+        self.templates_from_providers
+            .get(&worktree)
+            .into_iter()
+            .flatten()
+            .filter(move |(_, provider)| {
+                provider.languages.is_empty()
+                    || language_name
+                        .as_ref()
+                        .is_some_and(|language_name| provider.languages.contains(language_name))
+            })
+            .flat_map(move |(id, provider)| {
+                let kind = TaskSourceKind::Provider {
+                    id: id.clone(),
+                    worktree,
+                };
+                provider
+                    .templates
+                    .iter()
+                    .map(move |template| (kind.clone(), template.clone()))
+            })
+    }
+
+    /// Replaces the templates the task provider returned for the worktree.
+    pub(crate) fn update_provider_tasks(
+        &mut self,
+        worktree: WorktreeId,
+        provider_id: Arc<str>,
+        languages: Vec<LanguageName>,
+        templates: Vec<TaskTemplate>,
+    ) {
+        self.templates_from_providers
+            .entry(worktree)
+            .or_default()
+            .insert(
+                provider_id,
+                ProviderTemplates {
+                    languages,
+                    templates,
+                },
+            );
+    }
+
+    /// Removes the templates of the task providers and worktrees that do not pass the predicate.
+    pub(crate) fn retain_provider_tasks(
+        &mut self,
+        mut predicate: impl FnMut(WorktreeId, &str) -> bool,
+    ) {
+        for (worktree, providers) in &mut self.templates_from_providers {
+            providers.retain(|provider_id, _| predicate(*worktree, provider_id));
+        }
+        self.templates_from_providers
+            .retain(|_, providers| !providers.is_empty());
+    }
+
     /// Updates in-memory task metadata from the JSON string given.
     /// Will fail if the JSON is not a valid array of objects, but will continue if any object will not parse into a [`TaskTemplate`].
     ///
@@ -664,6 +816,71 @@ impl Inventory {
     }
 }
 
+/// Adds the task to the chain after the tasks it depends on, returning its index in the chain.
+/// The dependencies that are in the chain already are not added again.
+fn resolve_task_dependencies(
+    task_source_kind: TaskSourceKind,
+    resolved_task: ResolvedTask,
+    templates: &[(TaskSourceKind, TaskTemplate)],
+    labels_path: &mut Vec<String>,
+    tasks: &mut Vec<ChainedTask>,
+) -> Result<usize> {
+    let template = resolved_task.original_task();
+    let mut dependencies = Vec::with_capacity(template.depends_on.len());
+    for label in &template.depends_on {
+        if let Some(cycle_start) = labels_path.iter().position(|ancestor| ancestor == label) {
+            bail!(
+                "Task dependency cycle: {}",
+                labels_path[cycle_start..]
+                    .iter()
+                    .chain(Some(label))
+                    .join(" → ")
+            );
+        }
+        let (kind, dependency_template) = templates
+            .iter()
+            .find(|(_, template)| &template.label == label)
+            .with_context(|| {
+                format!(
+                    "Task `{}` depends on unknown task `{label}`",
+                    template.label
+                )
+            })?;
+        let dependency = dependency_template
+            .resolve_task(&kind.to_id_base(), resolved_task.task_context())
+            .with_context(|| format!("Failed to resolve task `{label}`"))?;
+
+        let dependency_ix = match tasks
+            .iter()
+            .position(|chained_task| chained_task.task.id == dependency.id)
+        {
+            Some(dependency_ix) => dependency_ix,
+            None => {
+                labels_path.push(label.clone());
+                let dependency_ix = resolve_task_dependencies(
+                    kind.clone(),
+                    dependency,
+                    templates,
+                    labels_path,
+                    tasks,
+                );
+                labels_path.pop();
+                dependency_ix?
+            }
+        };
+        if !dependencies.contains(&dependency_ix) {
+            dependencies.push(dependency_ix);
+        }
+    }
+
+    tasks.push(ChainedTask {
+        task_source_kind,
+        task: resolved_task,
+        dependencies,
+    });
+    Ok(tasks.len() - 1)
+}
+
 fn task_lru_comparator(
     (kind_a, task_a, lru_score_a): &(TaskSourceKind, ResolvedTask, u32),
     (kind_b, task_b, lru_score_b): &(TaskSourceKind, ResolvedTask, u32),
@@ -691,9 +908,10 @@ fn task_source_kind_preference(kind: &TaskSourceKind) -> u32 {
     match kind {
         TaskSourceKind::Lsp(..) => 0,
         TaskSourceKind::Language { .. } => 1,
-        TaskSourceKind::UserInput => 2,
-        TaskSourceKind::Worktree { .. } => 3,
-        TaskSourceKind::AbsPath { .. } => 4,
+        TaskSourceKind::Provider { .. } => 2,
+        TaskSourceKind::UserInput => 3,
+        TaskSourceKind::Worktree { .. } => 4,
+        TaskSourceKind::AbsPath { .. } => 5,
     }
 }
 
@@ -1039,6 +1257,85 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_task_chain_resolution(cx: &mut TestAppContext) {
+        init_test(cx);
+        let inventory = cx.update(Inventory::new);
+        inventory.update(cx, |inventory, _| {
+            inventory
+                .update_file_based_tasks(
+                    TaskSettingsLocation::Global(tasks_file()),
+                    Some(
+                        &json!([
+                            {"label": "build", "command": "cargo build"},
+                            {"label": "lint", "command": "cargo clippy", "depends_on": ["build"]},
+                            {"label": "test", "command": "cargo test", "depends_on": ["build"]},
+                            {
+                                "label": "check",
+                                "depends_on": ["lint", "test"],
+                                "depends_order": "parallel",
+                            },
+                            {"label": "broken", "depends_on": ["missing"]},
+                            {"label": "ping", "command": "echo", "depends_on": ["pong"]},
+                            {"label": "pong", "command": "echo", "depends_on": ["ping"]},
+                        ])
+                        .to_string(),
+                    ),
+                )
+                .unwrap();
+        });
+
+        let resolve_chain = |label: &str, cx: &mut TestAppContext| {
+            inventory.update(cx, |inventory, cx| {
+                let (kind, task) = inventory
+                    .used_and_current_resolved_tasks(&TaskContexts::default(), cx)
+                    .1
+                    .into_iter()
+                    .find(|(_, task)| task.original_task().label == label)
+                    .unwrap();
+                inventory.resolve_task_chain(kind, task)
+            })
+        };
+
+        let chain = resolve_chain("check", cx).unwrap();
+        assert_eq!(
+            chain
+                .tasks
+                .iter()
+                .map(|chained_task| chained_task.task.original_task().label.as_str())
+                .collect::<Vec<_>>(),
+            vec!["build", "lint", "test", "check"],
+        );
+        // Both `lint` and `test` wait for the same `build` task, which only runs once.
+        assert_eq!(
+            chain
+                .tasks
+                .iter()
+                .map(|chained_task| chained_task.dependencies.clone())
+                .collect::<Vec<_>>(),
+            vec![vec![], vec![0], vec![0], vec![1, 2]],
+        );
+        assert_eq!(
+            chain.task().task.original_task().depends_order,
+            task::DependsOrder::Parallel
+        );
+
+        let chain = resolve_chain("build", cx).unwrap();
+        assert_eq!(chain.tasks.len(), 1);
+
+        let error = resolve_chain("broken", cx).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task `broken` depends on unknown task `missing`"
+        );
+
+        let error = resolve_chain("ping", cx).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task dependency cycle: ping → pong → ping"
+        );
+    }
+
     #[gpui::test]
     async fn test_inventory_static_task_filters(cx: &mut TestAppContext) {
         init_test(cx);
@@ -1190,6 +1487,66 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_provider_tasks(cx: &mut TestAppContext) {
+        init_test(cx);
+        let inventory = cx.update(Inventory::new);
+        let worktree_1 = WorktreeId::from_usize(1);
+        let worktree_2 = WorktreeId::from_usize(2);
+        let template = |label: &str| TaskTemplate {
+            label: label.to_string(),
+            command: "echo".to_string(),
+            ..TaskTemplate::default()
+        };
+
+        inventory.update(cx, |inventory, _| {
+            inventory.update_provider_tasks(
+                worktree_1,
+                "build".into(),
+                Vec::new(),
+                vec![template("build"), template("test")],
+            );
+            inventory.update_provider_tasks(
+                worktree_1,
+                "zig".into(),
+                vec![LanguageName::new("Zig")],
+                vec![template("zig run")],
+            );
+        });
+
+        assert_eq!(
+            task_template_names(&inventory, Some(worktree_1), cx),
+            vec!["build".to_string(), "test".to_string()],
+            "Tasks of providers for other languages should not be listed"
+        );
+        assert!(task_template_names(&inventory, Some(worktree_2), cx).is_empty());
+        assert!(task_template_names(&inventory, None, cx).is_empty());
+        assert_eq!(
+            list_tasks_sorted_by_last_used(&inventory, Some(worktree_1), cx).await,
+            vec![
+                (
+                    TaskSourceKind::Provider {
+                        id: "build".into(),
+                        worktree: worktree_1,
+                    },
+                    "build".to_string()
+                ),
+                (
+                    TaskSourceKind::Provider {
+                        id: "build".into(),
+                        worktree: worktree_1,
+                    },
+                    "test".to_string()
+                ),
+            ]
+        );
+
+        inventory.update(cx, |inventory, _| {
+            inventory.retain_provider_tasks(|_, provider_id| provider_id != "build");
+        });
+        assert!(task_template_names(&inventory, Some(worktree_1), cx).is_empty());
+    }
+
     fn init_test(_cx: &mut TestAppContext) {
         if std::env::var("RUST_LOG").is_ok() {
             env_logger::try_init().ok();
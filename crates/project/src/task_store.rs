@@ -1,11 +1,15 @@
+pub mod extension;
+pub mod registry;
+
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::Context as _;
-use collections::HashMap;
-use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Task, WeakEntity};
+use collections::{HashMap, HashSet};
+use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity};
 use language::{
     ContextProvider as _, LanguageToolchainStore, Location,
     proto::{deserialize_anchor, serialize_anchor},
@@ -17,9 +21,19 @@ use text::{BufferId, OffsetRangeExt};
 use util::ResultExt;
 
 use crate::{
-    BasicContextProvider, Inventory, ProjectEnvironment, buffer_store::BufferStore,
-    worktree_store::WorktreeStore,
+    BasicContextProvider, Inventory, ProjectEnvironment,
+    buffer_store::BufferStore,
+    lsp_store::LspStore,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
+use registry::TaskProviderRegistry;
+
+/// How long the worktree files have to stay unchanged before the provider tasks are reloaded.
+const PROVIDER_TASKS_REFRESH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+pub fn init(cx: &mut App) {
+    extension::init(cx);
+}
 
 // platform-dependent warning
 pub enum TaskStore {
@@ -33,12 +47,15 @@ pub struct StoreState {
     buffer_store: WeakEntity<BufferStore>,
     worktree_store: Entity<WorktreeStore>,
     toolchain_store: Arc<dyn LanguageToolchainStore>,
+    refresh_provider_tasks: Task<()>,
+    _subscriptions: Vec<Subscription>,
 }
 
 enum StoreMode {
     Local {
         downstream_client: Option<(AnyProtoClient, u64)>,
         environment: Entity<ProjectEnvironment>,
+        lsp_store: WeakEntity<LspStore>,
     },
     Remote {
         upstream_client: AnyProtoClient,
@@ -162,18 +179,41 @@ impl TaskStore {
         worktree_store: Entity<WorktreeStore>,
         toolchain_store: Arc<dyn LanguageToolchainStore>,
         environment: Entity<ProjectEnvironment>,
+        lsp_store: WeakEntity<LspStore>,
         cx: &mut Context<Self>,
     ) -> Self {
-        Self::Functional(StoreState {
+        let task_provider_registry = TaskProviderRegistry::default_global(cx);
+        let subscriptions = vec![
+            cx.observe(&task_provider_registry, |this, _, cx| {
+                this.refresh_provider_tasks(cx);
+            }),
+            cx.subscribe(&worktree_store, |this, _, event, cx| match event {
+                WorktreeStoreEvent::WorktreeAdded(_) | WorktreeStoreEvent::WorktreeRemoved(..) => {
+                    this.refresh_provider_tasks(cx);
+                }
+                // Providers offer tasks based on the files of the worktree, e.g. a `build.zig`.
+                WorktreeStoreEvent::WorktreeUpdatedEntries(_, changes) if !changes.is_empty() => {
+                    this.refresh_provider_tasks_debounced(cx);
+                }
+                _ => {}
+            }),
+        ];
+
+        let mut this = Self::Functional(StoreState {
             mode: StoreMode::Local {
                 downstream_client: None,
                 environment,
+                lsp_store,
             },
             task_inventory: Inventory::new(cx),
             buffer_store,
             toolchain_store,
             worktree_store,
-        })
+            refresh_provider_tasks: Task::ready(()),
+            _subscriptions: subscriptions,
+        });
+        this.refresh_provider_tasks(cx);
+        this
     }
 
     pub fn remote(
@@ -193,6 +233,8 @@ impl TaskStore {
             buffer_store,
             toolchain_store,
             worktree_store,
+            refresh_provider_tasks: Task::ready(()),
+            _subscriptions: Vec::new(),
         })
     }
 
@@ -279,6 +321,96 @@ impl TaskStore {
         })
     }
 
+    fn refresh_provider_tasks_debounced(&mut self, cx: &mut Context<Self>) {
+        let TaskStore::Functional(state) = self else {
+            return;
+        };
+        state.refresh_provider_tasks = cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(PROVIDER_TASKS_REFRESH_DEBOUNCE)
+                .await;
+            this.update(cx, |this, cx| this.refresh_provider_tasks(cx))
+                .ok();
+        });
+    }
+
+    /// Reloads the templates of the registered task providers for all local worktrees.
+    fn refresh_provider_tasks(&mut self, cx: &mut Context<Self>) {
+        let TaskStore::Functional(state) = self else {
+            return;
+        };
+        let StoreMode::Local { lsp_store, .. } = &state.mode else {
+            return;
+        };
+        let Some(lsp_store) = lsp_store.upgrade() else {
+            return;
+        };
+
+        let task_providers = TaskProviderRegistry::default_global(cx)
+            .read(cx)
+            .task_providers();
+        let worktrees = state
+            .worktree_store
+            .read(cx)
+            .visible_worktrees(cx)
+            .filter(|worktree| worktree.read(cx).is_local())
+            .collect::<Vec<_>>();
+        let worktree_ids = worktrees
+            .iter()
+            .map(|worktree| worktree.read(cx).id())
+            .collect::<HashSet<_>>();
+        state.task_inventory.update(cx, |inventory, _| {
+            inventory.retain_provider_tasks(|worktree_id, provider_id| {
+                worktree_ids.contains(&worktree_id)
+                    && task_providers
+                        .iter()
+                        .any(|(id, _)| id.as_ref() == provider_id)
+            })
+        });
+
+        let mut templates_tasks = Vec::new();
+        for worktree in worktrees {
+            let worktree_id = worktree.read(cx).id();
+            let Some(delegate) = lsp_store.update(cx, |lsp_store, cx| {
+                lsp_store.local_adapter_delegate(&worktree, cx)
+            }) else {
+                continue;
+            };
+            for (provider_id, provider) in &task_providers {
+                let templates_task = provider.task_templates(delegate.clone(), cx);
+                templates_tasks.push((
+                    worktree_id,
+                    provider_id.clone(),
+                    provider.languages().to_vec(),
+                    templates_task,
+                ));
+            }
+        }
+
+        let task_inventory = state.task_inventory.downgrade();
+        state.refresh_provider_tasks = cx.spawn(async move |_, cx| {
+            for (worktree_id, provider_id, languages, templates_task) in templates_tasks {
+                let Some(templates) = templates_task
+                    .await
+                    .with_context(|| format!("loading tasks of task provider {provider_id}"))
+                    .log_err()
+                else {
+                    continue;
+                };
+                task_inventory
+                    .update(cx, |inventory, _| {
+                        inventory.update_provider_tasks(
+                            worktree_id,
+                            provider_id,
+                            languages,
+                            templates,
+                        )
+                    })
+                    .ok();
+            }
+        });
+    }
+
     pub(super) fn update_user_debug_scenarios(
         &self,
         location: TaskSettingsLocation<'_>,
@@ -0,0 +1,118 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use extension::{Extension, ExtensionHostProxy, ExtensionTaskProviderProxy, WorktreeDelegate};
+use gpui::{App, Entity, Task};
+use language::{LanguageName, LspAdapterDelegate};
+use task::TaskTemplate;
+
+use super::registry::{self, TaskProviderRegistry};
+
+pub fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_task_provider_proxy(TaskProviderRegistryProxy {
+        task_provider_registry: TaskProviderRegistry::default_global(cx),
+    });
+}
+
+/// An adapter that allows an [`LspAdapterDelegate`] to be used as a [`WorktreeDelegate`].
+struct WorktreeDelegateAdapter(Arc<dyn LspAdapterDelegate>);
+
+#[async_trait]
+impl WorktreeDelegate for WorktreeDelegateAdapter {
+    fn id(&self) -> u64 {
+        self.0.worktree_id().to_proto()
+    }
+
+    fn root_path(&self) -> String {
+        self.0.worktree_root_path().to_string_lossy().to_string()
+    }
+
+    async fn read_text_file(&self, path: PathBuf) -> Result<String> {
+        self.0.read_text_file(path).await
+    }
+
+    async fn which(&self, binary_name: String) -> Option<String> {
+        self.0
+            .which(binary_name.as_ref())
+            .await
+            .map(|path| path.to_string_lossy().to_string())
+    }
+
+    async fn shell_env(&self) -> Vec<(String, String)> {
+        self.0.shell_env().await.into_iter().collect()
+    }
+}
+
+struct ExtensionTaskProvider {
+    id: Arc<str>,
+    extension: Arc<dyn Extension>,
+    languages: Vec<LanguageName>,
+}
+
+impl registry::TaskProvider for ExtensionTaskProvider {
+    fn languages(&self) -> &[LanguageName] {
+        &self.languages
+    }
+
+    fn task_templates(
+        &self,
+        delegate: Arc<dyn LspAdapterDelegate>,
+        cx: &mut App,
+    ) -> Task<Result<Vec<TaskTemplate>>> {
+        let extension_worktree = Arc::new(WorktreeDelegateAdapter(delegate));
+
+        let id = self.id.clone();
+        let extension = self.extension.clone();
+        cx.background_spawn(async move {
+            let templates = extension
+                .task_templates(id.clone(), extension_worktree)
+                .await?;
+
+            log::debug!(
+                "loaded {} task templates from provider {id}",
+                templates.len()
+            );
+
+            Ok(templates)
+        })
+    }
+}
+
+struct TaskProviderRegistryProxy {
+    task_provider_registry: Entity<TaskProviderRegistry>,
+}
+
+impl ExtensionTaskProviderProxy for TaskProviderRegistryProxy {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        languages: Vec<LanguageName>,
+        cx: &mut App,
+    ) {
+        self.task_provider_registry.update(cx, |registry, cx| {
+            registry.register_task_provider(
+                provider_id.clone(),
+                Arc::new(ExtensionTaskProvider {
+                    id: provider_id,
+                    extension,
+                    languages,
+                }),
+                cx,
+            )
+        });
+    }
+
+    fn unregister_task_provider(
+        &self,
+        _extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    ) {
+        self.task_provider_registry.update(cx, |registry, cx| {
+            registry.unregister_task_provider(&provider_id, cx)
+        });
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use collections::HashMap;
+use gpui::{App, AppContext as _, Context, Entity, Global, Task};
+use language::{LanguageName, LspAdapterDelegate};
+use task::TaskTemplate;
+
+pub trait TaskProvider {
+    /// The languages whose files the provided tasks apply to, or all files if empty.
+    fn languages(&self) -> &[LanguageName];
+    /// Loads the task templates for the worktree of the given delegate.
+    fn task_templates(
+        &self,
+        delegate: Arc<dyn LspAdapterDelegate>,
+        cx: &mut App,
+    ) -> Task<Result<Vec<TaskTemplate>>>;
+}
+
+struct GlobalTaskProviderRegistry(Entity<TaskProviderRegistry>);
+
+impl Global for GlobalTaskProviderRegistry {}
+
+#[derive(Default)]
+pub struct TaskProviderRegistry {
+    task_providers: HashMap<Arc<str>, Arc<dyn TaskProvider>>,
+}
+
+impl TaskProviderRegistry {
+    /// Returns the global [`TaskProviderRegistry`].
+    ///
+    /// Inserts a default [`TaskProviderRegistry`] if one does not yet exist.
+    pub fn default_global(cx: &mut App) -> Entity<Self> {
+        if !cx.has_global::<GlobalTaskProviderRegistry>() {
+            let registry = cx.new(|_| Self::new());
+            cx.set_global(GlobalTaskProviderRegistry(registry));
+        }
+        cx.global::<GlobalTaskProviderRegistry>().0.clone()
+    }
+
+    pub fn new() -> Self {
+        Self {
+            task_providers: HashMap::default(),
+        }
+    }
+
+    pub fn task_providers(&self) -> Vec<(Arc<str>, Arc<dyn TaskProvider>)> {
+        self.task_providers
+            .iter()
+            .map(|(id, provider)| (id.clone(), provider.clone()))
+            .collect()
+    }
+
+    /// Registers the provided [`TaskProvider`].
+    pub fn register_task_provider(
+        &mut self,
+        id: Arc<str>,
+        provider: Arc<dyn TaskProvider>,
+        cx: &mut Context<Self>,
+    ) {
+        self.task_providers.insert(id, provider);
+        cx.notify();
+    }
+
+    /// Unregisters the [`TaskProvider`] with the given ID.
+    pub fn unregister_task_provider(&mut self, id: &str, cx: &mut Context<Self>) {
+        if self.task_providers.remove(id).is_some() {
+            cx.notify();
+        }
+    }
+}
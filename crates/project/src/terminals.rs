@@ -1,7 +1,10 @@
-use crate::{Project, ProjectPath};
+use crate::{
+    Project, ProjectPath,
+    task_diagnostics::{self, ProblemsUpdated, TaskDiagnostics},
+};
 use anyhow::{Context as _, Result};
 use collections::HashMap;
-use gpui::{AnyWindowHandle, App, AppContext as _, Context, Entity, Task, WeakEntity};
+use gpui::{AnyWindowHandle, App, AppContext as _, Context, Entity, EntityId, Task, WeakEntity};
 use itertools::Itertools;
 use language::LanguageName;
 use settings::{Settings, SettingsLocation};
@@ -21,6 +24,8 @@ use util::ResultExt;
 
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakEntity<terminal::Terminal>>,
+    /// Diagnostics of the task terminals that have problem matchers, by terminal.
+    pub(crate) task_diagnostics: HashMap<EntityId, Entity<TaskDiagnostics>>,
 }
 
 /// Terminals are opened either for the users shell, or to run a task.
@@ -234,6 +239,14 @@ impl Project {
 
         let mut python_venv_activate_command = None;
 
+        // Problem matchers only run for local projects, where the task output refers to local paths.
+        let problem_matchers = match &kind {
+            TerminalKind::Task(spawn_task) if this.is_local() => {
+                spawn_task.problem_matchers.clone()
+            }
+            _ => Vec::new(),
+        };
+
         let (spawn_task, shell) = match kind {
             TerminalKind::Shell(_) => {
                 if let Some(python_venv_directory) = &python_venv_directory {
@@ -330,7 +343,7 @@ impl Project {
             }
         };
         TerminalBuilder::new(
-            local_path.map(|path| path.to_path_buf()),
+            local_path.as_ref().map(|path| path.to_path_buf()),
             python_venv_directory,
             spawn_task,
             shell,
@@ -351,7 +364,31 @@ impl Project {
                 .push(terminal_handle.downgrade());
 
             let id = terminal_handle.entity_id();
+            if !problem_matchers.is_empty() {
+                let task_diagnostics = cx.new(|cx| {
+                    TaskDiagnostics::new(
+                        problem_matchers,
+                        local_path.map(|path| path.to_path_buf()),
+                        &terminal_handle,
+                        cx,
+                    )
+                });
+                cx.subscribe(
+                    &task_diagnostics,
+                    |project, _, ProblemsUpdated(paths), cx| {
+                        project.publish_task_diagnostics(paths.clone(), cx);
+                    },
+                )
+                .detach();
+                this.terminals.task_diagnostics.insert(id, task_diagnostics);
+            }
+
             cx.observe_release(&terminal_handle, move |project, _terminal, cx| {
+                if let Some(task_diagnostics) = project.terminals.task_diagnostics.remove(&id) {
+                    let paths = task_diagnostics.read(cx).paths().cloned().collect();
+                    project.publish_task_diagnostics(paths, cx);
+                }
+
                 let handles = &mut project.terminals.local_handles;
 
                 if let Some(index) = handles
@@ -520,6 +557,15 @@ impl Project {
     pub fn local_terminal_handles(&self) -> &Vec<WeakEntity<terminal::Terminal>> {
         &self.terminals.local_handles
     }
+
+    fn publish_task_diagnostics(&self, paths: Vec<PathBuf>, cx: &mut App) {
+        task_diagnostics::publish_task_diagnostics(
+            self.terminals.task_diagnostics.values(),
+            paths,
+            &self.lsp_store,
+            cx,
+        );
+    }
 }
 
 pub fn wrap_for_ssh(
@@ -15,6 +15,7 @@ use futures::{
 use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EntityId, EventEmitter, Task, WeakEntity,
 };
+use language::LanguageRegistry;
 use postage::oneshot;
 use rpc::{
     AnyProtoClient, ErrorExt, TypedEnvelope,
@@ -384,6 +385,7 @@ impl WorktreeStore {
                 worktree::Event::DeletedEntry(id) => {
                     cx.emit(WorktreeStoreEvent::WorktreeDeletedEntry(worktree_id, *id))
                 }
+                worktree::Event::LargeFileLoadProgress { .. } => {}
             }
         })
         .detach();
@@ -650,6 +652,7 @@ impl WorktreeStore {
         limit: usize,
         open_entries: HashSet<ProjectEntryId>,
         fs: Arc<dyn Fs>,
+        languages: Arc<LanguageRegistry>,
         cx: &Context<Self>,
     ) -> Receiver<ProjectPath> {
         let snapshots = self
@@ -691,13 +694,14 @@ impl WorktreeStore {
         const MAX_CONCURRENT_FILE_SCANS: usize = 64;
         let filters = cx.background_spawn(async move {
             let fs = &fs;
+            let languages = &languages;
             let query = &query;
             executor
                 .scoped(move |scope| {
                     for _ in 0..MAX_CONCURRENT_FILE_SCANS {
                         let filter_rx = filter_rx.clone();
                         scope.spawn(async move {
-                            Self::filter_paths(fs, filter_rx, query)
+                            Self::filter_paths(fs, languages, filter_rx, query)
                                 .await
                                 .log_with_level(log::Level::Debug);
                         })
@@ -873,12 +877,16 @@ impl WorktreeStore {
 
     async fn filter_paths(
         fs: &Arc<dyn Fs>,
+        languages: &Arc<LanguageRegistry>,
         input: Receiver<MatchingEntry>,
         query: &SearchQuery,
     ) -> Result<()> {
         let mut input = pin!(input);
         while let Some(mut entry) = input.next().await {
             let abs_path = entry.worktree_path.join(&entry.path.path);
+            if !query.may_match_language(&abs_path, languages).await {
+                continue;
+            }
             let Some(file) = fs.open_sync(&abs_path).await.log_err() else {
                 continue;
             };
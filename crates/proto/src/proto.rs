@@ -223,6 +223,21 @@ messages!(
     (RefreshCodeLens, Background),
     (GetCodeLens, Background),
     (GetCodeLensResponse, Background),
+    (GetSemanticTokens, Background),
+    (GetSemanticTokensResponse, Background),
+    (RefreshSemanticTokens, Background),
+    (PrepareCallHierarchy, Background),
+    (PrepareTypeHierarchy, Background),
+    (GetIncomingCalls, Background),
+    (GetOutgoingCalls, Background),
+    (GetSupertypes, Background),
+    (GetSubtypes, Background),
+    (HierarchyItemsResponse, Background),
+    (HierarchyCallsResponse, Background),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
+    (ResolveDocumentLink, Background),
+    (ResolveDocumentLinkResponse, Background),
     (RespondToChannelInvite, Foreground),
     (RespondToContactRequest, Foreground),
     (RestartLanguageServers, Foreground),
@@ -283,6 +298,11 @@ messages!(
     (UsersResponse, Foreground),
     (GitReset, Background),
     (GitCheckoutFiles, Background),
+    (GitStashPaths, Background),
+    (GitGetStashEntries, Background),
+    (GitStashEntriesResponse, Background),
+    (GitStashApply, Background),
+    (GitStashDrop, Background),
     (GitShow, Background),
     (GitCommitDetails, Background),
     (SetIndexText, Background),
@@ -380,6 +400,16 @@ request_messages!(
     (PrepareRename, PrepareRenameResponse),
     (RefreshInlayHints, Ack),
     (RefreshCodeLens, Ack),
+    (RefreshSemanticTokens, Ack),
+    (GetSemanticTokens, GetSemanticTokensResponse),
+    (PrepareCallHierarchy, HierarchyItemsResponse),
+    (PrepareTypeHierarchy, HierarchyItemsResponse),
+    (GetIncomingCalls, HierarchyCallsResponse),
+    (GetOutgoingCalls, HierarchyCallsResponse),
+    (GetSupertypes, HierarchyItemsResponse),
+    (GetSubtypes, HierarchyItemsResponse),
+    (GetDocumentLinks, GetDocumentLinksResponse),
+    (ResolveDocumentLink, ResolveDocumentLinkResponse),
     (RejoinChannelBuffers, RejoinChannelBuffersResponse),
     (RejoinRoom, RejoinRoomResponse),
     (ReloadBuffers, ReloadBuffersResponse),
@@ -453,6 +483,10 @@ request_messages!(
     (GitShow, GitCommitDetails),
     (GitReset, Ack),
     (GitCheckoutFiles, Ack),
+    (GitStashPaths, Ack),
+    (GitGetStashEntries, GitStashEntriesResponse),
+    (GitStashApply, Ack),
+    (GitStashDrop, Ack),
     (SetIndexText, Ack),
     (Push, RemoteMessageResponse),
     (Fetch, RemoteMessageResponse),
@@ -522,6 +556,16 @@ entity_messages!(
     PrepareRename,
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
+    GetSemanticTokens,
+    PrepareCallHierarchy,
+    PrepareTypeHierarchy,
+    GetIncomingCalls,
+    GetOutgoingCalls,
+    GetSupertypes,
+    GetSubtypes,
+    GetDocumentLinks,
+    ResolveDocumentLink,
     ReloadBuffers,
     RemoveProjectCollaborator,
     RenameProjectEntry,
@@ -576,6 +620,10 @@ entity_messages!(
     GitShow,
     GitReset,
     GitCheckoutFiles,
+    GitStashPaths,
+    GitGetStashEntries,
+    GitStashApply,
+    GitStashDrop,
     SetIndexText,
 
     Push,
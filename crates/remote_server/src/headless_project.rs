@@ -143,12 +143,29 @@ impl HeadlessProject {
             )
         });
 
+        let lsp_store = cx.new(|cx| {
+            let mut lsp_store = LspStore::new_local(
+                buffer_store.clone(),
+                worktree_store.clone(),
+                prettier_store.clone(),
+                toolchain_store.clone(),
+                environment.clone(),
+                languages.clone(),
+                http_client.clone(),
+                fs.clone(),
+                cx,
+            );
+            lsp_store.shared(SSH_PROJECT_ID, session.clone().into(), cx);
+            lsp_store
+        });
+
         let task_store = cx.new(|cx| {
             let mut task_store = TaskStore::local(
                 buffer_store.downgrade(),
                 worktree_store.clone(),
                 toolchain_store.read(cx).as_language_toolchain_store(),
-                environment.clone(),
+                environment,
+                lsp_store.downgrade(),
                 cx,
             );
             task_store.shared(SSH_PROJECT_ID, session.clone().into(), cx);
@@ -165,22 +182,6 @@ impl HeadlessProject {
             observer
         });
 
-        let lsp_store = cx.new(|cx| {
-            let mut lsp_store = LspStore::new_local(
-                buffer_store.clone(),
-                worktree_store.clone(),
-                prettier_store.clone(),
-                toolchain_store.clone(),
-                environment,
-                languages.clone(),
-                http_client.clone(),
-                fs.clone(),
-                cx,
-            );
-            lsp_store.shared(SSH_PROJECT_ID, session.clone().into(), cx);
-            lsp_store
-        });
-
         cx.subscribe(&lsp_store, Self::on_lsp_store_event).detach();
 
         cx.subscribe(
@@ -561,7 +562,13 @@ impl HeadlessProject {
         let query = SearchQuery::from_proto(message.query.context("missing query field")?)?;
         let results = this.update(&mut cx, |this, cx| {
             this.buffer_store.update(cx, |buffer_store, cx| {
-                buffer_store.find_search_candidates(&query, message.limit as _, this.fs.clone(), cx)
+                buffer_store.find_search_candidates(
+                    &query,
+                    message.limit as _,
+                    this.fs.clone(),
+                    this.languages.clone(),
+                    cx,
+                )
             })
         })?;
 
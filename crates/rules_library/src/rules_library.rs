@@ -932,6 +932,9 @@ impl RulesLibrary {
                                     tool_choice: None,
                                     stop: Vec::new(),
                                     temperature: None,
+                                    max_tokens: None,
+                                    response_format: None,
+                                    reasoning: None,
                                 },
                                 cx,
                             )
@@ -2,8 +2,10 @@ mod registrar;
 
 use crate::{
     FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
-    SelectAllMatches, SelectNextMatch, SelectPreviousMatch, ToggleCaseSensitive, ToggleRegex,
-    ToggleReplace, ToggleSelection, ToggleWholeWord, search_bar::render_nav_button,
+    SelectAllMatches, SelectNextMatch, SelectPreviousMatch, ToggleCaseSensitive, ToggleExcludeCode,
+    ToggleExcludeComments, ToggleExcludeStrings, ToggleRegex, ToggleReplace, ToggleSelection,
+    ToggleWholeWord,
+    search_bar::{render_nav_button, render_syntax_filter_menu},
 };
 use any_vec::AnyVec;
 use anyhow::Context as _;
@@ -20,7 +22,7 @@ use gpui::{
 };
 use language::{Language, LanguageRegistry};
 use project::{
-    search::SearchQuery,
+    search::{SearchQuery, SyntaxFilter},
     search_history::{SearchHistory, SearchHistoryCursor},
 };
 use schemars::JsonSchema;
@@ -284,6 +286,13 @@ impl Render for BufferSearchBar {
                                             this.toggle_regex(&ToggleRegex, window, cx)
                                         }),
                                     )
+                                }))
+                                .children(supported_options.syntax_filter.then(|| {
+                                    render_syntax_filter_menu(
+                                        "buffer-search-syntax-filter",
+                                        self.search_options,
+                                        focus_handle.clone(),
+                                    )
                                 })),
                         )
                     }),
@@ -504,6 +513,11 @@ impl Render for BufferSearchBar {
             .when(self.supported_options(cx).regex, |this| {
                 this.on_action(cx.listener(Self::toggle_regex))
             })
+            .when(self.supported_options(cx).syntax_filter, |this| {
+                this.on_action(cx.listener(Self::toggle_exclude_comments))
+                    .on_action(cx.listener(Self::toggle_exclude_strings))
+                    .on_action(cx.listener(Self::toggle_exclude_code))
+            })
             .when(self.supported_options(cx).selection, |this| {
                 this.on_action(cx.listener(Self::toggle_selection))
             })
@@ -604,6 +618,27 @@ impl BufferSearchBar {
                 this.toggle_regex(action, window, cx);
             }
         }));
+        registrar.register_handler(ForDeployed(
+            |this, action: &ToggleExcludeComments, window, cx| {
+                if this.supported_options(cx).syntax_filter {
+                    this.toggle_exclude_comments(action, window, cx);
+                }
+            },
+        ));
+        registrar.register_handler(ForDeployed(
+            |this, action: &ToggleExcludeStrings, window, cx| {
+                if this.supported_options(cx).syntax_filter {
+                    this.toggle_exclude_strings(action, window, cx);
+                }
+            },
+        ));
+        registrar.register_handler(ForDeployed(
+            |this, action: &ToggleExcludeCode, window, cx| {
+                if this.supported_options(cx).syntax_filter {
+                    this.toggle_exclude_code(action, window, cx);
+                }
+            },
+        ));
         registrar.register_handler(ForDeployed(|this, action: &ToggleSelection, window, cx| {
             if this.supported_options(cx).selection {
                 this.toggle_selection(action, window, cx);
@@ -1189,6 +1224,33 @@ impl BufferSearchBar {
         self.toggle_search_option(SearchOptions::REGEX, window, cx)
     }
 
+    fn toggle_exclude_comments(
+        &mut self,
+        _: &ToggleExcludeComments,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_search_option(SearchOptions::EXCLUDE_COMMENTS, window, cx)
+    }
+
+    fn toggle_exclude_strings(
+        &mut self,
+        _: &ToggleExcludeStrings,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_search_option(SearchOptions::EXCLUDE_STRINGS, window, cx)
+    }
+
+    fn toggle_exclude_code(
+        &mut self,
+        _: &ToggleExcludeCode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_search_option(SearchOptions::EXCLUDE_CODE, window, cx)
+    }
+
     fn clear_active_searchable_item_matches(&mut self, window: &mut Window, cx: &mut App) {
         if let Some(active_searchable_item) = self.active_searchable_item.as_ref() {
             self.active_match_index = None;
@@ -1242,6 +1304,11 @@ impl BufferSearchBar {
                 {
                     search
                 } else {
+                    let syntax_filter = if self.supported_options(cx).syntax_filter {
+                        self.search_options.syntax_filter()
+                    } else {
+                        SyntaxFilter::default()
+                    };
                     if self.search_options.contains(SearchOptions::REGEX) {
                         match SearchQuery::regex(
                             query,
@@ -1255,7 +1322,9 @@ impl BufferSearchBar {
                             false,
                             None,
                         ) {
-                            Ok(query) => query.with_replacement(self.replacement(cx)),
+                            Ok(query) => query
+                                .with_replacement(self.replacement(cx))
+                                .with_syntax_filter(syntax_filter),
                             Err(_) => {
                                 self.query_contains_error = true;
                                 self.clear_active_searchable_item_matches(window, cx);
@@ -1274,7 +1343,9 @@ impl BufferSearchBar {
                             false,
                             None,
                         ) {
-                            Ok(query) => query.with_replacement(self.replacement(cx)),
+                            Ok(query) => query
+                                .with_replacement(self.replacement(cx))
+                                .with_syntax_filter(syntax_filter),
                             Err(_) => {
                                 self.query_contains_error = true;
                                 self.clear_active_searchable_item_matches(window, cx);
@@ -1,7 +1,9 @@
 use crate::{
     BufferSearchBar, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext,
-    SearchOptions, SelectNextMatch, SelectPreviousMatch, ToggleCaseSensitive, ToggleIncludeIgnored,
-    ToggleRegex, ToggleReplace, ToggleWholeWord, buffer_search::Deploy,
+    SearchOptions, SelectNextMatch, SelectPreviousMatch, ToggleCaseSensitive, ToggleExcludeCode,
+    ToggleExcludeComments, ToggleExcludeStrings, ToggleIncludeIgnored, ToggleRegex, ToggleReplace,
+    ToggleStructural, ToggleWholeWord, buffer_search::Deploy,
+    search_bar::render_syntax_filter_menu,
 };
 use anyhow::Context as _;
 use collections::{HashMap, HashSet};
@@ -85,6 +87,30 @@ pub fn init(cx: &mut App) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, window, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, window, cx);
         });
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleStructural, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::STRUCTURAL, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleExcludeComments, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::EXCLUDE_COMMENTS, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleExcludeStrings, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::EXCLUDE_STRINGS, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleExcludeCode, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::EXCLUDE_CODE, window, cx);
+            },
+        );
         register_workspace_action(
             workspace,
             move |search_bar, action: &ToggleReplace, window, cx| {
@@ -655,6 +681,12 @@ impl ProjectSearchView {
 
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut Context<Self>) {
         self.search_options.toggle(option);
+        // Structural queries are matched against syntax trees rather than as regexes.
+        if option == SearchOptions::REGEX && self.search_options.contains(option) {
+            self.search_options.remove(SearchOptions::STRUCTURAL);
+        } else if option == SearchOptions::STRUCTURAL && self.search_options.contains(option) {
+            self.search_options.remove(SearchOptions::REGEX);
+        }
         ActiveSettings::update_global(cx, |settings, cx| {
             settings.0.insert(
                 self.entity.read(cx).project.downgrade(),
@@ -1181,7 +1213,33 @@ impl ProjectSearchView {
             .count()
             > 1;
 
-        let query = if self.search_options.contains(SearchOptions::REGEX) {
+        let query = if self.search_options.contains(SearchOptions::STRUCTURAL) {
+            match SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+                match_full_paths,
+                open_buffers,
+            ) {
+                Ok(query) => {
+                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                    if should_unmark_error {
+                        cx.notify();
+                    }
+
+                    Some(query)
+                }
+                Err(_e) => {
+                    let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
+                    if should_mark_error {
+                        cx.notify();
+                    }
+
+                    None
+                }
+            }
+        } else if self.search_options.contains(SearchOptions::REGEX) {
             match SearchQuery::regex(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -1246,7 +1304,7 @@ impl ProjectSearchView {
         if query.as_ref().is_some_and(|query| query.is_empty()) {
             return None;
         }
-        query
+        query.map(|query| query.with_syntax_filter(self.search_options.syntax_filter()))
     }
 
     fn open_buffers(&self, cx: &mut Context<Self>) -> Vec<Entity<Buffer>> {
@@ -1985,6 +2043,18 @@ impl Render for ProjectSearchBar {
                         cx.listener(|this, _, window, cx| {
                             this.toggle_search_option(SearchOptions::REGEX, window, cx);
                         }),
+                    ))
+                    .child(SearchOptions::STRUCTURAL.as_button(
+                        self.is_option_enabled(SearchOptions::STRUCTURAL, cx),
+                        focus_handle.clone(),
+                        cx.listener(|this, _, window, cx| {
+                            this.toggle_search_option(SearchOptions::STRUCTURAL, window, cx);
+                        }),
+                    ))
+                    .child(render_syntax_filter_menu(
+                        "project-search-syntax-filter",
+                        search.search_options,
+                        focus_handle.clone(),
                     )),
             );
 
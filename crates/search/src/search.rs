@@ -2,7 +2,7 @@ use bitflags::bitflags;
 pub use buffer_search::BufferSearchBar;
 use editor::SearchSettings;
 use gpui::{Action, App, FocusHandle, IntoElement, actions};
-use project::search::SearchQuery;
+use project::search::{SearchQuery, SyntaxFilter};
 pub use project_search::ProjectSearchView;
 use ui::{ButtonStyle, IconButton, IconButtonShape};
 use ui::{Tooltip, prelude::*};
@@ -28,6 +28,10 @@ actions!(
         ToggleCaseSensitive,
         ToggleIncludeIgnored,
         ToggleRegex,
+        ToggleStructural,
+        ToggleExcludeComments,
+        ToggleExcludeStrings,
+        ToggleExcludeCode,
         ToggleReplace,
         ToggleSelection,
         SelectNextMatch,
@@ -42,7 +46,7 @@ actions!(
 
 bitflags! {
     #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
-    pub struct SearchOptions: u8 {
+    pub struct SearchOptions: u16 {
         const NONE = 0b000;
         const WHOLE_WORD = 0b001;
         const CASE_SENSITIVE = 0b010;
@@ -51,6 +55,10 @@ bitflags! {
         const ONE_MATCH_PER_LINE = 0b100000;
         /// If set, reverse direction when finding the active match
         const BACKWARDS = 0b10000;
+        const STRUCTURAL = 0b1000000;
+        const EXCLUDE_COMMENTS = 0b10000000;
+        const EXCLUDE_STRINGS = 0b100000000;
+        const EXCLUDE_CODE = 0b1000000000;
     }
 }
 
@@ -61,6 +69,10 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => "Match Case Sensitively",
             SearchOptions::INCLUDE_IGNORED => "Also search files ignored by configuration",
             SearchOptions::REGEX => "Use Regular Expressions",
+            SearchOptions::STRUCTURAL => "Match Syntax Structure",
+            SearchOptions::EXCLUDE_COMMENTS => "Exclude Comments",
+            SearchOptions::EXCLUDE_STRINGS => "Exclude Strings",
+            SearchOptions::EXCLUDE_CODE => "Exclude Code",
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -71,6 +83,10 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => ui::IconName::CaseSensitive,
             SearchOptions::INCLUDE_IGNORED => ui::IconName::Sliders,
             SearchOptions::REGEX => ui::IconName::Regex,
+            SearchOptions::STRUCTURAL => ui::IconName::Code,
+            SearchOptions::EXCLUDE_COMMENTS => ui::IconName::MessageBubbles,
+            SearchOptions::EXCLUDE_STRINGS => ui::IconName::Quote,
+            SearchOptions::EXCLUDE_CODE => ui::IconName::FileCode,
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -81,6 +97,10 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => Box::new(ToggleCaseSensitive),
             SearchOptions::INCLUDE_IGNORED => Box::new(ToggleIncludeIgnored),
             SearchOptions::REGEX => Box::new(ToggleRegex),
+            SearchOptions::STRUCTURAL => Box::new(ToggleStructural),
+            SearchOptions::EXCLUDE_COMMENTS => Box::new(ToggleExcludeComments),
+            SearchOptions::EXCLUDE_STRINGS => Box::new(ToggleExcludeStrings),
+            SearchOptions::EXCLUDE_CODE => Box::new(ToggleExcludeCode),
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -95,9 +115,29 @@ impl SearchOptions {
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
         options.set(SearchOptions::REGEX, query.is_regex());
+        options.set(SearchOptions::STRUCTURAL, query.is_structural());
+        let syntax_filter = query.syntax_filter();
+        options.set(
+            SearchOptions::EXCLUDE_COMMENTS,
+            syntax_filter.exclude_comments,
+        );
+        options.set(
+            SearchOptions::EXCLUDE_STRINGS,
+            syntax_filter.exclude_strings,
+        );
+        options.set(SearchOptions::EXCLUDE_CODE, syntax_filter.exclude_code);
         options
     }
 
+    /// Returns the kinds of syntax these options exclude matches from.
+    pub fn syntax_filter(&self) -> SyntaxFilter {
+        SyntaxFilter {
+            exclude_comments: self.contains(SearchOptions::EXCLUDE_COMMENTS),
+            exclude_strings: self.contains(SearchOptions::EXCLUDE_STRINGS),
+            exclude_code: self.contains(SearchOptions::EXCLUDE_CODE),
+        }
+    }
+
     pub fn from_settings(settings: &SearchSettings) -> SearchOptions {
         let mut options = SearchOptions::NONE;
         options.set(SearchOptions::WHOLE_WORD, settings.whole_word);
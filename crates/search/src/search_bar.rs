@@ -1,5 +1,6 @@
-use gpui::{Action, FocusHandle, IntoElement};
-use ui::{IconButton, IconButtonShape};
+use crate::SearchOptions;
+use gpui::{Action, Corner, FocusHandle, IntoElement};
+use ui::{ButtonStyle, ContextMenu, IconButton, IconButtonShape, PopoverMenu};
 use ui::{Tooltip, prelude::*};
 
 pub(super) fn render_nav_button(
@@ -26,3 +27,49 @@ pub(super) fn render_nav_button(
     .tooltip(move |window, cx| Tooltip::for_action_in(tooltip, action, &focus_handle, window, cx))
     .disabled(!active)
 }
+
+/// Renders a menu toggling whether matches in comments, strings or code are excluded.
+pub(super) fn render_syntax_filter_menu(
+    id: &'static str,
+    search_options: SearchOptions,
+    focus_handle: FocusHandle,
+) -> impl IntoElement {
+    PopoverMenu::new(id)
+        .trigger_with_tooltip(
+            IconButton::new(
+                SharedString::from(format!("{id}-button")),
+                IconName::SearchCode,
+            )
+            .style(ButtonStyle::Subtle)
+            .shape(IconButtonShape::Square)
+            .toggle_state(!search_options.syntax_filter().is_empty()),
+            Tooltip::text("Filter Matches by Syntax"),
+        )
+        .anchor(Corner::TopRight)
+        .menu(move |window, cx| {
+            let focus_handle = focus_handle.clone();
+            Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                menu = menu.context(focus_handle.clone());
+                for option in [
+                    SearchOptions::EXCLUDE_COMMENTS,
+                    SearchOptions::EXCLUDE_STRINGS,
+                    SearchOptions::EXCLUDE_CODE,
+                ] {
+                    let focus_handle = focus_handle.clone();
+                    menu = menu.toggleable_entry(
+                        option.label(),
+                        search_options.contains(option),
+                        IconPosition::Start,
+                        Some(option.to_toggle_action() as Box<dyn Action>),
+                        move |window, cx| {
+                            if !focus_handle.is_focused(window) {
+                                window.focus(&focus_handle);
+                            }
+                            window.dispatch_action(option.to_toggle_action(), cx);
+                        },
+                    );
+                }
+                menu
+            }))
+        })
+}
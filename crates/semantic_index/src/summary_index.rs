@@ -569,6 +569,9 @@ impl SummaryIndex {
             tool_choice: None,
             stop: Vec::new(),
             temperature: None,
+            max_tokens: None,
+            response_format: None,
+            reasoning: None,
         };
 
         let code_len = code.len();
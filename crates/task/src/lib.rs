@@ -1,8 +1,10 @@
 //! Baseline interface of Tasks in Zed: all tasks in Zed are intended to use those for implementing their own logic.
 
 mod debug_format;
+mod problem_matcher;
 mod serde_helpers;
 pub mod static_source;
+mod task_input;
 mod task_template;
 mod vscode_debug_format;
 mod vscode_format;
@@ -19,8 +21,13 @@ pub use debug_format::{
     AttachRequest, BuildTaskDefinition, DebugRequest, DebugScenario, DebugTaskFile, LaunchRequest,
     TcpArgumentsTemplate,
 };
+pub use problem_matcher::{
+    BackgroundPatterns, CompiledProblemMatcher, Problem, ProblemMatchState, ProblemMatcher,
+    ProblemMatcherTemplate, ProblemPattern, ProblemPosition, ProblemSeverity,
+};
+pub use task_input::{TaskInput, TaskInputKind};
 pub use task_template::{
-    DebugArgsRequest, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
+    DebugArgsRequest, DependsOrder, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
     substitute_all_template_variables_in_str, substitute_variables_in_map,
     substitute_variables_in_str,
 };
@@ -71,6 +78,8 @@ pub struct SpawnInTerminal {
     pub show_command: bool,
     /// Whether to show the rerun button in the terminal tab.
     pub show_rerun: bool,
+    /// Problem matchers to turn the task output into diagnostics with.
+    pub problem_matchers: Vec<ProblemMatcher>,
 }
 
 impl SpawnInTerminal {
@@ -114,6 +123,8 @@ pub struct ResolvedTask {
     pub id: TaskId,
     /// A template the task got resolved from.
     original_task: TaskTemplate,
+    /// A context the task got resolved with, used to resolve the tasks it depends on.
+    task_context: TaskContext,
     /// Full, unshortened label of the task after all resolutions are made.
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
@@ -129,6 +140,11 @@ impl ResolvedTask {
         &self.original_task
     }
 
+    /// A context the task got resolved with.
+    pub fn task_context(&self) -> &TaskContext {
+        &self.task_context
+    }
+
     /// Variables that were substituted during the task template resolution.
     pub fn substituted_variables(&self) -> &HashSet<VariableName> {
         &self.substituted_variables
@@ -138,6 +154,12 @@ impl ResolvedTask {
     pub fn display_label(&self) -> &str {
         self.resolved.label.as_str()
     }
+
+    /// Whether the task has no command of its own, and only groups the tasks it depends on.
+    /// Such a task is never spawned.
+    pub fn is_aggregator(&self) -> bool {
+        self.original_task.command.trim().is_empty()
+    }
 }
 
 /// Variables, available for use in [`TaskContext`] when a Zed's [`TaskTemplate`] gets resolved into a [`ResolvedTask`].
@@ -0,0 +1,665 @@
+use anyhow::Context as _;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A problem matcher to use for a task: either a name of the built-in one (e.g. `$rustc`), or a custom definition.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcherTemplate {
+    /// One of the built-in matchers: `$rustc`, `$tsc`, `$tsc-watch` or `$gcc`.
+    Named(String),
+    /// A custom matcher definition.
+    Custom(ProblemMatcher),
+}
+
+impl ProblemMatcherTemplate {
+    /// Returns the matcher definition, looking up the built-in matchers by name.
+    pub fn resolve(&self) -> Option<ProblemMatcher> {
+        match self {
+            Self::Named(name) => {
+                let matcher = ProblemMatcher::builtin(name);
+                if matcher.is_none() {
+                    log::warn!("Unknown problem matcher `{name}`");
+                }
+                matcher
+            }
+            Self::Custom(matcher) => Some(matcher.clone()),
+        }
+    }
+}
+
+/// Describes how to extract problems (errors, warnings, etc.) from the task output.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemMatcher {
+    /// Name to show as the source of the produced diagnostics, e.g. `rustc`.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Severity of the problems whose pattern does not capture it.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+    /// Patterns to match consecutive output lines against, one per line.
+    /// A problem is reported after the last pattern matches; when the last pattern has `loop` set,
+    /// it is reported for every consecutive line the last pattern matches.
+    pub patterns: Vec<ProblemPattern>,
+    /// For tasks that keep running and recompile on changes, patterns that mark the start and
+    /// the end of every compilation.
+    #[serde(default)]
+    pub background: Option<BackgroundPatterns>,
+}
+
+/// A regular expression to match a task output line with, and its capture groups to extract the problem data from.
+///
+/// Relative file paths are resolved against the working directory of the task.
+/// Line and column numbers are 1-based.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regular expression to match the line with.
+    pub regexp: String,
+    /// The capture group with the file path.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// The capture group with the line number.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// The capture group with the column number.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// The capture group with the end line number.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// The capture group with the end column number.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// The capture group with the severity: `error`, `warning`, `info` or `hint`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// The capture group with the problem code.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// The capture group with the problem message.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether the pattern, being the last one, may match multiple consecutive lines.
+    #[serde(default, rename = "loop")]
+    pub r#loop: bool,
+}
+
+/// Patterns that mark the start and the end of a compilation for tasks that keep running.
+/// All problems found previously are discarded when a new compilation starts.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BackgroundPatterns {
+    /// The regular expression of the line that starts a compilation.
+    pub begins_pattern: String,
+    /// The regular expression of the line that ends a compilation.
+    pub ends_pattern: String,
+}
+
+/// The severity of a problem found in the task output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    /// Reported as an error (default).
+    #[default]
+    Error,
+    /// Reported as a warning.
+    Warning,
+    /// Reported as an information.
+    Info,
+    /// Reported as a hint.
+    Hint,
+}
+
+impl ProblemSeverity {
+    fn parse(severity: &str) -> Option<Self> {
+        match severity.trim().to_lowercase().as_str() {
+            "error" | "fatal" => Some(Self::Error),
+            "warning" | "warn" => Some(Self::Warning),
+            "info" | "information" | "note" => Some(Self::Info),
+            "hint" | "help" => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// A position in a file, zero-based.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProblemPosition {
+    pub row: u32,
+    pub column: u32,
+}
+
+/// A problem found in the task output.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Problem {
+    /// The file path, as printed by the task: may be relative to the task's working directory.
+    pub path: String,
+    pub start: ProblemPosition,
+    pub end: ProblemPosition,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+    pub source: Option<String>,
+}
+
+impl ProblemMatcher {
+    /// Returns the built-in matcher with the given name, which may omit the leading `$`.
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name.strip_prefix('$').unwrap_or(name) {
+            "rustc" => Some(Self {
+                source: Some("rustc".to_string()),
+                severity: ProblemSeverity::Error,
+                patterns: vec![
+                    ProblemPattern {
+                        regexp: r"^(warning|warn|error)(?:\[(.*?)\])?: (.*)$".to_string(),
+                        severity: Some(1),
+                        code: Some(2),
+                        message: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: r"^[\s\->=]*(.*?):(\d*):(\d*)\s*$".to_string(),
+                        file: Some(1),
+                        line: Some(2),
+                        column: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                ],
+                background: None,
+            }),
+            "tsc" => Some(Self {
+                source: Some("ts".to_string()),
+                severity: ProblemSeverity::Error,
+                patterns: vec![tsc_pattern()],
+                background: None,
+            }),
+            "tsc-watch" => Some(Self {
+                source: Some("ts".to_string()),
+                severity: ProblemSeverity::Error,
+                patterns: vec![tsc_pattern()],
+                background: Some(BackgroundPatterns {
+                    begins_pattern: r"^\s*(?:message TS6032:|\[?\D*\d{1,2}[:.]\d{1,2}[:.]\d{1,2}\D*(?:├\D*\d{1,2}\D+┤)?(?:\]| -)) File change detected\. Starting incremental compilation\.\.\.".to_string(),
+                    ends_pattern: r"^\s*(?:message TS6042:|\[?\D*\d{1,2}[:.]\d{1,2}[:.]\d{1,2}\D*(?:├\D*\d{1,2}\D+┤)?(?:\]| -)) (?:Compilation complete\.|Found \d+ errors?\.) Watching for file changes\.".to_string(),
+                }),
+            }),
+            "gcc" => Some(Self {
+                source: Some("gcc".to_string()),
+                severity: ProblemSeverity::Error,
+                patterns: vec![ProblemPattern {
+                    regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error):\s+(.*)$"
+                        .to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    message: Some(5),
+                    ..ProblemPattern::default()
+                }],
+                background: None,
+            }),
+            _ => None,
+        }
+    }
+}
+
+fn tsc_pattern() -> ProblemPattern {
+    ProblemPattern {
+        regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+TS(\d+)\s*:\s*(.*)$"
+            .to_string(),
+        file: Some(1),
+        line: Some(2),
+        column: Some(3),
+        severity: Some(4),
+        code: Some(5),
+        message: Some(6),
+        ..ProblemPattern::default()
+    }
+}
+
+/// A [`ProblemMatcher`] with all its regular expressions compiled, ready to match the task output.
+#[derive(Clone, Debug)]
+pub struct CompiledProblemMatcher {
+    matcher: ProblemMatcher,
+    patterns: Vec<Regex>,
+    background: Option<(Regex, Regex)>,
+}
+
+/// Problem data, collected from the lines matched so far.
+#[derive(Default)]
+struct PartialProblem {
+    path: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<ProblemSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+/// The progress of matching the task output, for matching it as it gets printed.
+#[derive(Default)]
+pub struct ProblemMatchState {
+    problems: Vec<Problem>,
+    data: PartialProblem,
+    pattern_ix: usize,
+}
+
+impl ProblemMatchState {
+    /// The problems found so far.
+    pub fn problems(&self) -> &[Problem] {
+        &self.problems
+    }
+}
+
+impl CompiledProblemMatcher {
+    pub fn new(matcher: ProblemMatcher) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            !matcher.patterns.is_empty(),
+            "Problem matcher has no patterns"
+        );
+        let patterns = matcher
+            .patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(&pattern.regexp)
+                    .with_context(|| format!("Invalid problem pattern `{}`", pattern.regexp))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let background = match &matcher.background {
+            Some(background) => Some((
+                Regex::new(&background.begins_pattern).with_context(|| {
+                    format!("Invalid begins pattern `{}`", background.begins_pattern)
+                })?,
+                Regex::new(&background.ends_pattern).with_context(|| {
+                    format!("Invalid ends pattern `{}`", background.ends_pattern)
+                })?,
+            )),
+            None => None,
+        };
+        Ok(Self {
+            matcher,
+            patterns,
+            background,
+        })
+    }
+
+    /// Finds all problems in the task output, given as a sequence of its lines.
+    ///
+    /// For background matchers, only the problems found after the last compilation start are returned.
+    pub fn match_lines<'a>(&self, lines: impl IntoIterator<Item = &'a str>) -> Vec<Problem> {
+        let mut state = ProblemMatchState::default();
+        self.match_more_lines(&mut state, lines);
+        state.problems
+    }
+
+    /// Continues matching the task output with the lines printed after the ones already matched,
+    /// so that problems spanning several lines are found even when the output is matched in chunks.
+    pub fn match_more_lines<'a>(
+        &self,
+        state: &mut ProblemMatchState,
+        lines: impl IntoIterator<Item = &'a str>,
+    ) {
+        let ProblemMatchState {
+            problems,
+            data,
+            pattern_ix,
+        } = state;
+        let last_ix = self.patterns.len() - 1;
+
+        for line in lines {
+            if let Some((begins, ends)) = &self.background {
+                if begins.is_match(line) {
+                    problems.clear();
+                    *data = PartialProblem::default();
+                    *pattern_ix = 0;
+                    continue;
+                }
+                if ends.is_match(line) {
+                    *data = PartialProblem::default();
+                    *pattern_ix = 0;
+                    continue;
+                }
+            }
+
+            if let Some(captures) = self.patterns[*pattern_ix].captures(line) {
+                self.collect(data, *pattern_ix, &captures);
+                if *pattern_ix == last_ix {
+                    problems.extend(self.problem(data));
+                    if !self.matcher.patterns[last_ix].r#loop {
+                        *data = PartialProblem::default();
+                        *pattern_ix = 0;
+                    }
+                } else {
+                    *pattern_ix += 1;
+                }
+            } else if *pattern_ix > 0 {
+                // The multi-line match got interrupted, so the line may start a new one.
+                *data = PartialProblem::default();
+                *pattern_ix = 0;
+                if let Some(captures) = self.patterns[0].captures(line) {
+                    self.collect(data, 0, &captures);
+                    if last_ix == 0 {
+                        problems.extend(self.problem(data));
+                        *data = PartialProblem::default();
+                    } else {
+                        *pattern_ix = 1;
+                    }
+                }
+            }
+        }
+    }
+
+    fn collect(&self, data: &mut PartialProblem, pattern_ix: usize, captures: &Captures) {
+        let pattern = &self.matcher.patterns[pattern_ix];
+        let group = |group: Option<usize>| {
+            group
+                .and_then(|group| captures.get(group))
+                .map(|capture| capture.as_str())
+                .filter(|capture| !capture.is_empty())
+        };
+        let number = |ix: Option<usize>| group(ix).and_then(|number| number.parse().ok());
+
+        if let Some(path) = group(pattern.file) {
+            data.path = Some(path.trim().to_string());
+        }
+        if let Some(line) = number(pattern.line) {
+            data.line = Some(line);
+        }
+        if let Some(column) = number(pattern.column) {
+            data.column = Some(column);
+        }
+        if let Some(end_line) = number(pattern.end_line) {
+            data.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            data.end_column = Some(end_column);
+        }
+        if let Some(severity) = group(pattern.severity).and_then(ProblemSeverity::parse) {
+            data.severity = Some(severity);
+        }
+        if let Some(code) = group(pattern.code) {
+            data.code = Some(code.to_string());
+        }
+        if let Some(message) = group(pattern.message) {
+            data.message = Some(message.trim().to_string());
+        }
+    }
+
+    fn problem(&self, data: &PartialProblem) -> Option<Problem> {
+        let path = data.path.clone()?;
+        let message = data.message.clone()?;
+        let start = ProblemPosition {
+            row: data.line.unwrap_or(1).saturating_sub(1),
+            column: data.column.unwrap_or(1).saturating_sub(1),
+        };
+        let end = match data.end_line {
+            Some(end_line) => ProblemPosition {
+                row: end_line.saturating_sub(1),
+                column: data.end_column.unwrap_or(1).saturating_sub(1),
+            },
+            None => ProblemPosition {
+                row: start.row,
+                column: data
+                    .end_column
+                    .map_or(start.column, |end_column| end_column.saturating_sub(1)),
+            },
+        };
+        Some(Problem {
+            path,
+            start,
+            end: end.max(start),
+            severity: data.severity.unwrap_or(self.matcher.severity),
+            code: data.code.clone(),
+            message,
+            source: self.matcher.source.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn builtin(name: &str) -> CompiledProblemMatcher {
+        CompiledProblemMatcher::new(ProblemMatcher::builtin(name).unwrap()).unwrap()
+    }
+
+    fn position(row: u32, column: u32) -> ProblemPosition {
+        ProblemPosition { row, column }
+    }
+
+    #[test]
+    fn test_rustc_matcher() {
+        let output = r#"   Compiling foo v0.1.0 (/work/foo)
+warning: unused variable: `x`
+ --> src/main.rs:2:9
+  |
+2 |     let x = 5;
+  |         ^ help: if this is intentional, prefix it with an underscore: `_x`
+  |
+error[E0308]: mismatched types
+  --> src/lib.rs:10:18
+   |
+warning: `foo` (bin "foo") generated 1 warning
+error: could not compile `foo` (bin "foo") due to 1 previous error"#;
+
+        assert_eq!(
+            builtin("$rustc").match_lines(output.lines()),
+            vec![
+                Problem {
+                    path: "src/main.rs".to_string(),
+                    start: position(1, 8),
+                    end: position(1, 8),
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `x`".to_string(),
+                    source: Some("rustc".to_string()),
+                },
+                Problem {
+                    path: "src/lib.rs".to_string(),
+                    start: position(9, 17),
+                    end: position(9, 17),
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0308".to_string()),
+                    message: "mismatched types".to_string(),
+                    source: Some("rustc".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matching_output_in_chunks() {
+        let matcher = builtin("$rustc");
+        let output = [
+            "error[E0308]: mismatched types",
+            "  --> src/lib.rs:10:18",
+            "warning: unused variable: `x`",
+            " --> src/main.rs:2:9",
+        ];
+        let mut state = ProblemMatchState::default();
+        matcher.match_more_lines(&mut state, output[..1].iter().copied());
+        assert!(state.problems().is_empty());
+        matcher.match_more_lines(&mut state, output[1..3].iter().copied());
+        matcher.match_more_lines(&mut state, output[3..].iter().copied());
+        assert_eq!(state.problems(), matcher.match_lines(output).as_slice());
+        assert_eq!(
+            state
+                .problems()
+                .iter()
+                .map(|problem| problem.path.as_str())
+                .collect::<Vec<_>>(),
+            ["src/lib.rs", "src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_single_line_matchers() {
+        let problems = builtin("gcc").match_lines([
+            "main.c:3:5: warning: implicit declaration of function 'foo'",
+            "/abs/util.c:12:1: fatal error: missing.h: No such file or directory",
+            "compilation terminated.",
+        ]);
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.as_str(),
+                    problem.start,
+                    problem.severity,
+                    problem.message.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "main.c",
+                    position(2, 4),
+                    ProblemSeverity::Warning,
+                    "implicit declaration of function 'foo'"
+                ),
+                (
+                    "/abs/util.c",
+                    position(11, 0),
+                    ProblemSeverity::Error,
+                    "missing.h: No such file or directory"
+                ),
+            ]
+        );
+
+        let problems = builtin("tsc").match_lines([
+            "src/index.ts(4,7): error TS2322: Type 'string' is not assignable to type 'number'.",
+            "src/app.ts:1:10 - warning TS6133: 'foo' is declared but its value is never read.",
+        ]);
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.as_str(),
+                    problem.start,
+                    problem.severity,
+                    problem.code.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "src/index.ts",
+                    position(3, 6),
+                    ProblemSeverity::Error,
+                    Some("2322")
+                ),
+                (
+                    "src/app.ts",
+                    position(0, 9),
+                    ProblemSeverity::Warning,
+                    Some("6133")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_looping_pattern() {
+        let matcher = CompiledProblemMatcher::new(ProblemMatcher {
+            source: Some("eslint".to_string()),
+            severity: ProblemSeverity::Warning,
+            patterns: vec![
+                ProblemPattern {
+                    regexp: r"^([^\s].*)$".to_string(),
+                    file: Some(1),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s+(\d+):(\d+)\s+(error|warning)\s+(.*?)\s\s+(\S+)$".to_string(),
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    r#loop: true,
+                    ..ProblemPattern::default()
+                },
+            ],
+            background: None,
+        })
+        .unwrap();
+
+        let problems = matcher.match_lines([
+            "src/a.js",
+            "  1:10  error  'foo' is not defined  no-undef",
+            "  2:1   warning  Unexpected console statement  no-console",
+            "",
+            "src/b.js",
+            "  7:3  error  Missing semicolon  semi",
+        ]);
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.as_str(),
+                    problem.start.row,
+                    problem.code.as_deref().unwrap()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("src/a.js", 0, "no-undef"),
+                ("src/a.js", 1, "no-console"),
+                ("src/b.js", 6, "semi"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_background_matcher_discards_previous_compilations() {
+        let problems = builtin("$tsc-watch").match_lines([
+            "[12:00:00 PM] Starting compilation in watch mode...",
+            "src/index.ts(4,7): error TS2322: Type 'string' is not assignable to type 'number'.",
+            "[12:00:02 PM] Found 1 error. Watching for file changes.",
+            "[12:01:00 PM] File change detected. Starting incremental compilation...",
+            "src/other.ts(1,1): error TS1005: ';' expected.",
+            "[12:01:01 PM] Found 1 error. Watching for file changes.",
+        ]);
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| problem.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["src/other.ts"]
+        );
+    }
+
+    #[test]
+    fn test_resolving_matcher_templates() {
+        assert_eq!(
+            ProblemMatcherTemplate::Named("$gcc".to_string()).resolve(),
+            ProblemMatcher::builtin("gcc")
+        );
+        assert_eq!(
+            ProblemMatcherTemplate::Named("$unknown".to_string()).resolve(),
+            None
+        );
+
+        let template: ProblemMatcherTemplate = serde_json::from_value(serde_json::json!({
+            "source": "custom",
+            "patterns": [{ "regexp": "^(.*):(\\d+): (.*)$", "file": 1, "line": 2, "message": 3 }]
+        }))
+        .unwrap();
+        let matcher = CompiledProblemMatcher::new(template.resolve().unwrap()).unwrap();
+        assert_eq!(
+            matcher.match_lines(["lib.py:3: bad indentation"]),
+            vec![Problem {
+                path: "lib.py".to_string(),
+                start: position(2, 0),
+                end: position(2, 0),
+                severity: ProblemSeverity::Error,
+                code: None,
+                message: "bad indentation".to_string(),
+                source: Some("custom".to_string()),
+            }]
+        );
+    }
+}
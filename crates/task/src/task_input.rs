@@ -0,0 +1,213 @@
+use collections::{HashMap, HashSet};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::ResolvedTask;
+
+const INPUT_REFERENCE_PREFIX: &str = "${input:";
+
+/// A value to ask the user for when the task is spawned.
+/// Referenced as `${input:<id>}` in the task's command, arguments, environment variables and working directory.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TaskInput {
+    /// The id to reference the input with.
+    pub id: String,
+    /// Text to show in the prompt.
+    #[serde(default)]
+    pub description: String,
+    /// How to get the value.
+    #[serde(flatten)]
+    pub kind: TaskInputKind,
+}
+
+/// How to get the value of a [`TaskInput`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskInputKind {
+    /// Let the user type the value in.
+    PromptString {
+        /// The value to use if nothing is typed.
+        #[serde(default)]
+        default: Option<String>,
+    },
+    /// Let the user pick one of the given values.
+    PickString {
+        /// The values to pick from.
+        options: Vec<String>,
+        /// The value to select initially.
+        #[serde(default)]
+        default: Option<String>,
+    },
+    /// Let the user pick one of the lines printed by a command, run in the task's working directory.
+    CommandOutput {
+        /// The command to run.
+        command: String,
+        /// Arguments to the command.
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+/// Inputs that are referenced in the given texts, in the order they are defined.
+pub(crate) fn referenced_inputs<'a>(
+    inputs: &[TaskInput],
+    texts: impl IntoIterator<Item = &'a str>,
+) -> Vec<TaskInput> {
+    let referenced_ids = texts
+        .into_iter()
+        .flat_map(input_references)
+        .collect::<HashSet<_>>();
+    inputs
+        .iter()
+        .filter(|input| referenced_ids.contains(input.id.as_str()))
+        .cloned()
+        .collect()
+}
+
+impl ResolvedTask {
+    /// Inputs that the user needs to provide the values for, before the task can be spawned.
+    pub fn unresolved_inputs(&self) -> Vec<TaskInput> {
+        let resolved = &self.resolved;
+        let cwd = resolved
+            .cwd
+            .as_ref()
+            .map(|cwd| cwd.to_string_lossy().into_owned());
+        referenced_inputs(
+            &self.original_task.inputs,
+            std::iter::once(resolved.command.as_str())
+                .chain(resolved.args.iter().map(String::as_str))
+                .chain(resolved.env.values().map(String::as_str))
+                .chain(cwd.as_deref()),
+        )
+    }
+
+    /// Replaces all `${input:<id>}` references to the inputs with the given values.
+    /// References to the inputs without a value are left as they are.
+    pub fn resolve_inputs(&mut self, values: &HashMap<String, String>) {
+        let resolved = &mut self.resolved;
+        resolved.command = substitute_inputs(&resolved.command, values);
+        resolved.command_label = substitute_inputs(&resolved.command_label, values);
+        for arg in &mut resolved.args {
+            *arg = substitute_inputs(arg, values);
+        }
+        for value in resolved.env.values_mut() {
+            *value = substitute_inputs(value, values);
+        }
+        if let Some(cwd) = resolved.cwd.as_mut() {
+            *cwd = substitute_inputs(&cwd.to_string_lossy(), values).into();
+        }
+    }
+}
+
+/// Returns the ids of all inputs referenced in the text.
+fn input_references(text: &str) -> impl Iterator<Item = &str> {
+    text.match_indices(INPUT_REFERENCE_PREFIX)
+        .filter_map(move |(start, _)| {
+            let id_start = start + INPUT_REFERENCE_PREFIX.len();
+            let id_len = text[id_start..].find('}')?;
+            Some(&text[id_start..id_start + id_len])
+        })
+}
+
+fn substitute_inputs(text: &str, values: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(INPUT_REFERENCE_PREFIX) {
+        let id_start = start + INPUT_REFERENCE_PREFIX.len();
+        let Some(id_len) = rest[id_start..].find('}') else {
+            break;
+        };
+        let id = &rest[id_start..id_start + id_len];
+        let end = id_start + id_len + 1;
+        result.push_str(&rest[..start]);
+        match values.get(id) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[start..end]),
+        }
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{TaskContext, TaskTemplate};
+
+    use super::*;
+
+    #[test]
+    fn test_resolving_inputs() {
+        let template: TaskTemplate = serde_json::from_value(serde_json::json!({
+            "label": "deploy",
+            "command": "deploy --env ${input:environment}",
+            "args": ["--tag", "${input:tag}", "${input:missing}"],
+            "env": { "DEPLOY_USER": "${input:user}" },
+            "inputs": [
+                { "id": "user", "type": "prompt_string", "default": "admin" },
+                { "id": "unused", "type": "prompt_string" },
+                {
+                    "id": "environment",
+                    "type": "pick_string",
+                    "description": "Environment",
+                    "options": ["staging", "production"]
+                },
+                { "id": "tag", "type": "command_output", "command": "git", "args": ["tag"] }
+            ]
+        }))
+        .unwrap();
+        let mut resolved_task = template
+            .resolve_task("test", &TaskContext::default())
+            .unwrap();
+
+        assert_eq!(
+            resolved_task
+                .unresolved_inputs()
+                .iter()
+                .map(|input| input.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["user", "environment", "tag"]
+        );
+        assert_eq!(
+            template.inputs[2].kind,
+            TaskInputKind::PickString {
+                options: vec!["staging".to_string(), "production".to_string()],
+                default: None,
+            }
+        );
+
+        resolved_task.resolve_inputs(&HashMap::from_iter([
+            ("user".to_string(), "ci".to_string()),
+            ("environment".to_string(), "staging".to_string()),
+            ("tag".to_string(), "v1.0".to_string()),
+        ]));
+        let resolved = &resolved_task.resolved;
+        assert_eq!(resolved.command, "deploy --env staging");
+        assert_eq!(resolved.args, vec!["--tag", "v1.0", "${input:missing}"]);
+        assert_eq!(
+            resolved.command_label,
+            "deploy --env staging --tag v1.0 ${input:missing}"
+        );
+        assert_eq!(
+            resolved.env.get("DEPLOY_USER").map(String::as_str),
+            Some("ci")
+        );
+        assert!(resolved_task.unresolved_inputs().is_empty());
+    }
+
+    #[test]
+    fn test_input_references() {
+        assert_eq!(
+            input_references("${input:a} and ${input:b-c}${input:unterminated").collect::<Vec<_>>(),
+            vec!["a", "b-c"]
+        );
+        assert_eq!(
+            substitute_inputs(
+                "${input:a}/${input:b}/${input:a",
+                &HashMap::from_iter([("a".to_string(), "x".to_string())])
+            ),
+            "x/${input:b}/${input:a"
+        );
+    }
+}
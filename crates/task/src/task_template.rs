@@ -8,8 +8,8 @@ use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX,
+    AttachRequest, ProblemMatcherTemplate, ResolvedTask, RevealTarget, Shell, SpawnInTerminal,
+    TaskContext, TaskId, TaskInput, VariableName, ZED_VARIABLE_NAME_PREFIX,
     serde_helpers::{non_empty_string_vec, non_empty_string_vec_json_schema},
 };
 
@@ -72,6 +72,24 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks to run before this one.
+    /// The task is only spawned after all of them finish successfully, and may omit `command`
+    /// to only group other tasks.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks listed in `depends_on`:
+    /// * `sequence` — one after another, in the order they are listed (default)
+    /// * `parallel` — all at the same time
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Problem matchers to find errors and warnings in the task output with, and show them as diagnostics.
+    /// Either names of the built-in matchers (`$rustc`, `$tsc`, `$tsc-watch`, `$gcc`) or custom matcher definitions.
+    #[serde(default)]
+    pub problem_matchers: Vec<ProblemMatcherTemplate>,
+    /// Values to ask the user for when the task is spawned, referenced as `${input:<id>}` in
+    /// `command`, `args`, `env` and `cwd`.
+    #[serde(default)]
+    pub inputs: Vec<TaskInput>,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -109,6 +127,17 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// How to run the tasks a task depends on.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Run the dependencies one after another, stopping at the first failure.
+    #[default]
+    Sequence,
+    /// Run all dependencies at the same time.
+    Parallel,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -133,7 +162,9 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty()
+            || (self.command.trim().is_empty() && self.depends_on.is_empty())
+        {
             return None;
         }
 
@@ -241,6 +272,7 @@ impl TaskTemplate {
             id: id.clone(),
             substituted_variables,
             original_task: self.clone(),
+            task_context: cx.clone(),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
                 id,
@@ -267,6 +299,11 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                problem_matchers: self
+                    .problem_matchers
+                    .iter()
+                    .filter_map(ProblemMatcherTemplate::resolve)
+                    .collect(),
             },
         })
     }
@@ -464,6 +501,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolving_templates_with_dependencies_only() {
+        let task = TaskTemplate {
+            label: "build and test".to_string(),
+            depends_on: vec!["build".to_string(), "test".to_string()],
+            ..TaskTemplate::default()
+        };
+        let resolved_task = task
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("tasks with dependencies should resolve without a command");
+        assert!(resolved_task.is_aggregator());
+        assert_eq!(resolved_task.original_task().depends_on, task.depends_on);
+        assert_eq!(
+            resolved_task.original_task().depends_order,
+            DependsOrder::Sequence
+        );
+
+        let parsed: TaskTemplate = serde_json_lenient::from_str(
+            r#"{"label": "all", "depends_on": ["a", "b"], "depends_order": "parallel"}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.depends_on, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(parsed.depends_order, DependsOrder::Parallel);
+    }
+
     #[test]
     fn test_template_cwd_resolution() {
         let task_without_cwd = TaskTemplate {
@@ -1,9 +1,13 @@
-use anyhow::bail;
+use anyhow::{Context as _, bail};
 use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    BackgroundPatterns, DependsOrder, EnvVariableReplacer, ProblemMatcher, ProblemMatcherTemplate,
+    ProblemPattern, ProblemSeverity, TaskInput, TaskInputKind, TaskTemplate, TaskTemplates,
+    VariableName, task_input::referenced_inputs,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +26,150 @@ struct VsCodeTaskDefinition {
     #[serde(flatten)]
     other_attributes: HashMap<String, serde_json_lenient::Value>,
     options: Option<TaskOptions>,
+    depends_on: Option<DependsOn>,
+    depends_order: Option<VsCodeDependsOrder>,
+    problem_matcher: Option<VsCodeProblemMatchers>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum DependsOn {
+    Label(String),
+    Labels(Vec<String>),
+    /// Dependencies that are task identifiers (e.g. `{ "type": "npm", "script": "build" }`) rather than labels.
+    Other(serde_json_lenient::Value),
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+enum VsCodeDependsOrder {
+    Parallel,
+    Sequence,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatchers {
+    One(VsCodeProblemMatcher),
+    Many(Vec<VsCodeProblemMatcher>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatcher {
+    Named(String),
+    Custom(VsCodeProblemMatcherDefinition),
+    /// Matchers that use the features Zed does not support, e.g. named patterns.
+    Other(serde_json_lenient::Value),
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemMatcherDefinition {
+    base: Option<String>,
+    owner: Option<String>,
+    source: Option<String>,
+    severity: Option<ProblemSeverity>,
+    pattern: Option<VsCodeProblemPatterns>,
+    background: Option<VsCodeBackgroundMatcher>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemPatterns {
+    One(VsCodeProblemPattern),
+    Many(Vec<VsCodeProblemPattern>),
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default)]
+    r#loop: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeBackgroundMatcher {
+    begins_pattern: VsCodeBackgroundPattern,
+    ends_pattern: VsCodeBackgroundPattern,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeBackgroundPattern {
+    Regexp(String),
+    Object { regexp: String },
+}
+
+impl VsCodeBackgroundPattern {
+    fn into_regexp(self) -> String {
+        match self {
+            Self::Regexp(regexp) | Self::Object { regexp } => regexp,
+        }
+    }
+}
+
+impl VsCodeProblemMatcher {
+    fn into_zed_format(self) -> anyhow::Result<ProblemMatcherTemplate> {
+        let definition = match self {
+            Self::Named(name) => return Ok(ProblemMatcherTemplate::Named(name)),
+            Self::Custom(definition) => definition,
+            Self::Other(value) => bail!("Encountered unsupported `problemMatcher` value {value}"),
+        };
+
+        let mut matcher = match &definition.base {
+            Some(base) => ProblemMatcher::builtin(base)
+                .with_context(|| format!("Unknown base problem matcher `{base}`"))?,
+            None => ProblemMatcher::default(),
+        };
+        if let Some(source) = definition.source.or(definition.owner) {
+            matcher.source = Some(source);
+        }
+        if let Some(severity) = definition.severity {
+            matcher.severity = severity;
+        }
+        if let Some(patterns) = definition.pattern {
+            let patterns = match patterns {
+                VsCodeProblemPatterns::One(pattern) => vec![pattern],
+                VsCodeProblemPatterns::Many(patterns) => patterns,
+            };
+            matcher.patterns = patterns
+                .into_iter()
+                .map(|pattern| ProblemPattern {
+                    regexp: pattern.regexp,
+                    file: pattern.file,
+                    line: pattern.line,
+                    column: pattern.column,
+                    end_line: pattern.end_line,
+                    end_column: pattern.end_column,
+                    severity: pattern.severity,
+                    code: pattern.code,
+                    message: pattern.message,
+                    r#loop: pattern.r#loop,
+                })
+                .collect();
+        }
+        if let Some(background) = definition.background {
+            matcher.background = Some(BackgroundPatterns {
+                begins_pattern: background.begins_pattern.into_regexp(),
+                ends_pattern: background.ends_pattern.into_regexp(),
+            });
+        }
+        if matcher.patterns.is_empty() {
+            bail!("Problem matcher has no patterns");
+        }
+        Ok(ProblemMatcherTemplate::Custom(matcher))
+    }
 }
 
 #[derive(Clone, Deserialize, PartialEq, Debug)]
@@ -43,15 +191,40 @@ enum Command {
 
 impl VsCodeTaskDefinition {
     fn into_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
-        if self.other_attributes.contains_key("dependsOn") {
-            bail!("Encountered unsupported `dependsOn` key during deserialization");
+        let depends_on = match self.depends_on {
+            None => Vec::new(),
+            Some(DependsOn::Label(label)) => vec![label],
+            Some(DependsOn::Labels(labels)) => labels,
+            Some(DependsOn::Other(_)) => {
+                bail!("Encountered unsupported `dependsOn` value, only task labels are supported")
+            }
+        };
+        // Unlike in Zed, VS Code runs dependencies in parallel by default.
+        let depends_order = match self.depends_order {
+            Some(VsCodeDependsOrder::Sequence) => DependsOrder::Sequence,
+            Some(VsCodeDependsOrder::Parallel) | None => DependsOrder::Parallel,
+        };
+        // Matchers that fail to import are skipped, as the task is still usable without them.
+        let problem_matchers = match self.problem_matcher {
+            None => Vec::new(),
+            Some(VsCodeProblemMatchers::One(matcher)) => vec![matcher],
+            Some(VsCodeProblemMatchers::Many(matchers)) => matchers,
         }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
+        .into_iter()
+        .filter_map(|matcher| matcher.into_zed_format().log_err())
+        .collect::<Vec<_>>();
+        // `type` might not be set in tasks that only group other tasks with `dependsOn`; we still want to deserialize the whole object though (hence command is an Option).
         let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+            if depends_on.is_empty() {
+                bail!("Missing `type` field in task");
+            }
+            return Ok(TaskTemplate {
+                label: self.label,
+                depends_on,
+                depends_order,
+                problem_matchers,
+                ..Default::default()
+            });
         };
 
         let (command, args) = match command {
@@ -66,6 +239,9 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
+            problem_matchers,
             ..Default::default()
         };
         if let Some(options) = self.options {
@@ -76,10 +252,77 @@ impl VsCodeTaskDefinition {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum VsCodeInput {
+    PromptString {
+        id: String,
+        #[serde(default)]
+        description: String,
+        default: Option<String>,
+    },
+    PickString {
+        id: String,
+        #[serde(default)]
+        description: String,
+        options: Vec<VsCodePickOption>,
+        default: Option<String>,
+    },
+    /// Inputs that run VS Code commands, which Zed does not have.
+    Command { id: String },
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodePickOption {
+    Value(String),
+    Labeled { value: String },
+}
+
+impl VsCodeInput {
+    fn into_zed_format(self) -> anyhow::Result<TaskInput> {
+        match self {
+            Self::PromptString {
+                id,
+                description,
+                default,
+            } => Ok(TaskInput {
+                id,
+                description,
+                kind: TaskInputKind::PromptString { default },
+            }),
+            Self::PickString {
+                id,
+                description,
+                options,
+                default,
+            } => Ok(TaskInput {
+                id,
+                description,
+                kind: TaskInputKind::PickString {
+                    options: options
+                        .into_iter()
+                        .map(|option| match option {
+                            VsCodePickOption::Value(value)
+                            | VsCodePickOption::Labeled { value } => value,
+                        })
+                        .collect(),
+                    default,
+                },
+            }),
+            Self::Command { id } => {
+                bail!("Encountered unsupported `command` input `{id}`")
+            }
+        }
+    }
+}
+
 /// [`VsCodeTaskFile`] is a superset of Code's task definition format.
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct VsCodeTaskFile {
     tasks: Vec<VsCodeTaskDefinition>,
+    #[serde(default)]
+    inputs: Vec<VsCodeInput>,
 }
 
 impl TryFrom<VsCodeTaskFile> for TaskTemplates {
@@ -98,10 +341,26 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
                 VariableName::SelectedText.to_string(),
             ),
         ]));
+        let inputs = value
+            .inputs
+            .into_iter()
+            .filter_map(|input| input.into_zed_format().log_err())
+            .collect::<Vec<_>>();
         let templates = value
             .tasks
             .into_iter()
             .filter_map(|vscode_definition| vscode_definition.into_zed_format(&replacer).log_err())
+            .map(|mut template| {
+                // Inputs are shared by all tasks in VS Code, but belong to a task in Zed.
+                template.inputs = referenced_inputs(
+                    &inputs,
+                    std::iter::once(template.command.as_str())
+                        .chain(template.args.iter().map(String::as_str))
+                        .chain(template.env.values().map(String::as_str))
+                        .chain(template.cwd.as_deref()),
+                );
+                template
+            })
             .collect();
         Ok(Self(templates))
     }
@@ -112,8 +371,12 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
+        DependsOrder, ProblemMatcher, ProblemMatcherTemplate, TaskInput, TaskInputKind,
         TaskTemplate, TaskTemplates, VsCodeTaskFile,
-        vscode_format::{Command, VsCodeTaskDefinition},
+        vscode_format::{
+            Command, DependsOn, VsCodeProblemMatcher, VsCodeProblemMatcherDefinition,
+            VsCodeProblemMatchers, VsCodeTaskDefinition,
+        },
     };
 
     use super::EnvVariableReplacer;
@@ -170,6 +433,11 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Many(vec![
+                    VsCodeProblemMatcher::Named("$tsc".to_string()),
+                ])),
             },
             VsCodeTaskDefinition {
                 label: "tsc: watch ./src".to_string(),
@@ -184,6 +452,11 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Many(vec![
+                    VsCodeProblemMatcher::Named("$tsc-watch".to_string()),
+                ])),
             },
             VsCodeTaskDefinition {
                 label: "npm: build:compiler".to_string(),
@@ -192,6 +465,11 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Many(vec![
+                    VsCodeProblemMatcher::Named("$tsc".to_string()),
+                ])),
             },
             VsCodeTaskDefinition {
                 label: "npm: build:tests".to_string(),
@@ -200,6 +478,11 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Many(vec![
+                    VsCodeProblemMatcher::Named("$tsc".to_string()),
+                ])),
             },
         ];
 
@@ -215,6 +498,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::Named("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
@@ -226,18 +510,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matchers: vec![ProblemMatcherTemplate::Named("$tsc-watch".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::Named("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::Named("$tsc".to_string())],
                 ..Default::default()
             },
         ];
@@ -259,6 +546,14 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::One(VsCodeProblemMatcher::Custom(
+                    VsCodeProblemMatcherDefinition {
+                        base: Some("$tsc-watch".to_string()),
+                        ..Default::default()
+                    },
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Build Extension".to_string(),
@@ -267,6 +562,14 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::One(VsCodeProblemMatcher::Custom(
+                    VsCodeProblemMatcherDefinition {
+                        base: Some("$tsc".to_string()),
+                        ..Default::default()
+                    },
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Build Server".to_string(),
@@ -276,6 +579,11 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::One(VsCodeProblemMatcher::Named(
+                    "$rustc".to_string(),
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release)".to_string(),
@@ -285,6 +593,11 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::One(VsCodeProblemMatcher::Named(
+                    "$rustc".to_string(),
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Pretest".to_string(),
@@ -293,18 +606,42 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::One(VsCodeProblemMatcher::Custom(
+                    VsCodeProblemMatcherDefinition {
+                        base: Some("$tsc".to_string()),
+                        ..Default::default()
+                    },
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Build Server and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Some(DependsOn::Labels(vec![
+                    "Build Server".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::One(VsCodeProblemMatcher::Named(
+                    "$rustc".to_string(),
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release) and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Some(DependsOn::Labels(vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::One(VsCodeProblemMatcher::Named(
+                    "$rustc".to_string(),
+                ))),
             },
         ];
         assert_eq!(vscode_definitions.tasks.len(), expected.len());
@@ -318,32 +655,124 @@ mod tests {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::Custom(
+                    ProblemMatcher::builtin("$tsc-watch").unwrap(),
+                )],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::Custom(
+                    ProblemMatcher::builtin("$tsc").unwrap(),
+                )],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcherTemplate::Named("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcherTemplate::Named("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::Custom(
+                    ProblemMatcher::builtin("$tsc").unwrap(),
+                )],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcherTemplate::Named("$rustc".to_string())],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcherTemplate::Named("$rustc".to_string())],
                 ..Default::default()
             },
         ];
         let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
         assert_eq!(tasks.0, expected);
     }
+
+    #[test]
+    fn can_import_inputs() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "version": "2.0.0",
+                "tasks": [
+                    {
+                        "label": "deploy",
+                        "type": "shell",
+                        "command": "./deploy.sh ${input:environment}",
+                        "options": { "env": { "TOKEN": "${input:token}" } }
+                    },
+                    { "label": "build", "type": "shell", "command": "make" }
+                ],
+                "inputs": [
+                    {
+                        "id": "environment",
+                        "type": "pickString",
+                        "description": "Where to deploy",
+                        "options": ["staging", { "label": "Production", "value": "prod" }],
+                        "default": "staging"
+                    },
+                    { "id": "token", "type": "promptString", "password": true },
+                    { "id": "branch", "type": "command", "command": "git.checkout" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "deploy".to_string(),
+                    command: "./deploy.sh ${input:environment}".to_string(),
+                    env: [("TOKEN".to_string(), "${input:token}".to_string())]
+                        .into_iter()
+                        .collect(),
+                    inputs: vec![
+                        TaskInput {
+                            id: "environment".to_string(),
+                            description: "Where to deploy".to_string(),
+                            kind: TaskInputKind::PickString {
+                                options: vec!["staging".to_string(), "prod".to_string()],
+                                default: Some("staging".to_string()),
+                            },
+                        },
+                        TaskInput {
+                            id: "token".to_string(),
+                            description: String::new(),
+                            kind: TaskInputKind::PromptString { default: None },
+                        },
+                    ],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "build".to_string(),
+                    command: "make".to_string(),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,119 @@
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription,
+};
+use ui::{Tooltip, prelude::*};
+use workspace::{
+    Workspace,
+    notifications::{Notification, NotificationId, SuppressEvent},
+    tasks::{RunningTaskChain, TaskChainStepStatus},
+};
+
+pub(crate) fn show_task_chain(
+    workspace: &mut Workspace,
+    running_chain: Entity<RunningTaskChain>,
+    cx: &mut Context<Workspace>,
+) {
+    let id = NotificationId::composite::<TaskChainNotification>((
+        "task_chain",
+        running_chain.entity_id(),
+    ));
+    workspace.show_notification(id, cx, |cx| {
+        cx.new(|cx| TaskChainNotification::new(running_chain, cx))
+    });
+}
+
+/// Shows the progress of a task with dependencies, and allows to cancel it.
+struct TaskChainNotification {
+    running_chain: Entity<RunningTaskChain>,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl TaskChainNotification {
+    fn new(running_chain: Entity<RunningTaskChain>, cx: &mut Context<Self>) -> Self {
+        let _subscription = cx.observe(&running_chain, |_, _, cx| cx.notify());
+        Self {
+            running_chain,
+            focus_handle: cx.focus_handle(),
+            _subscription,
+        }
+    }
+}
+
+impl EventEmitter<DismissEvent> for TaskChainNotification {}
+impl EventEmitter<SuppressEvent> for TaskChainNotification {}
+
+impl Focusable for TaskChainNotification {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Notification for TaskChainNotification {}
+
+impl Render for TaskChainNotification {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let running_chain = self.running_chain.read(cx);
+        let is_finished = running_chain.is_finished();
+        let title = if is_finished {
+            format!("Finished \"{}\"", running_chain.label())
+        } else {
+            format!("Running \"{}\"", running_chain.label())
+        };
+
+        v_flex()
+            .id("task_chain_notification")
+            .occlude()
+            .elevation_3(cx)
+            .p_2()
+            .gap_1()
+            .w_full()
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .gap_2()
+                    .child(Label::new(title).truncate())
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .when(!is_finished, |this| {
+                                this.child(
+                                    IconButton::new("cancel", IconName::Stop)
+                                        .icon_size(IconSize::Small)
+                                        .tooltip(Tooltip::text("Cancel Remaining Tasks"))
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.running_chain.update(cx, |running_chain, cx| {
+                                                running_chain.cancel(cx)
+                                            });
+                                        })),
+                                )
+                            })
+                            .child(
+                                IconButton::new("close", IconName::Close)
+                                    .icon_size(IconSize::Small)
+                                    .on_click(cx.listener(|_, _, _, cx| cx.emit(DismissEvent))),
+                            ),
+                    ),
+            )
+            .children(running_chain.steps().iter().map(|step| {
+                let (icon, color) = match step.status {
+                    TaskChainStepStatus::Pending => (IconName::Circle, Color::Muted),
+                    TaskChainStepStatus::Running => (IconName::ArrowCircle, Color::Accent),
+                    TaskChainStepStatus::Succeeded => (IconName::Check, Color::Success),
+                    TaskChainStepStatus::Failed => (IconName::XCircle, Color::Error),
+                    TaskChainStepStatus::Skipped | TaskChainStepStatus::Cancelled => {
+                        (IconName::Dash, Color::Muted)
+                    }
+                };
+                h_flex()
+                    .gap_1p5()
+                    .child(Icon::new(icon).size(IconSize::Small).color(color))
+                    .child(
+                        Label::new(step.label.clone())
+                            .size(LabelSize::Small)
+                            .truncate(),
+                    )
+            }))
+    }
+}
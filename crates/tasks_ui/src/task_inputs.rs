@@ -0,0 +1,395 @@
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use collections::HashMap;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, Task,
+    WeakEntity, Window, prelude::*,
+};
+use picker::{Picker, PickerDelegate};
+use task::{TaskInput, TaskInputKind};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, Workspace, tasks::TaskInputsRequest};
+
+/// Asks the user for the values of the task inputs one after another, and schedules the task once all of them are provided.
+pub(crate) fn request_task_inputs(
+    workspace: &mut Workspace,
+    request: TaskInputsRequest,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let inputs = request.resolved_task.unresolved_inputs();
+    let is_local = workspace.project().read(cx).is_local();
+    let workspace_handle = cx.entity().downgrade();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        let delegate = TaskInputsDelegate::new(
+            cx.entity().downgrade(),
+            workspace_handle,
+            request,
+            inputs,
+            is_local,
+        );
+        TaskInputsModal::new(delegate, window, cx)
+    });
+}
+
+pub(crate) struct TaskInputsModal {
+    picker: Entity<Picker<TaskInputsDelegate>>,
+    _subscription: Subscription,
+}
+
+impl TaskInputsModal {
+    fn new(delegate: TaskInputsDelegate, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let picker = cx.new(|cx| {
+            let mut picker = Picker::uniform_list(delegate, window, cx);
+            TaskInputsDelegate::start_input(&mut picker, 0, window, cx);
+            picker
+        });
+        let _subscription = cx.subscribe(&picker, |_, _, _: &DismissEvent, cx| {
+            cx.emit(DismissEvent);
+        });
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for TaskInputsModal {}
+
+impl EventEmitter<DismissEvent> for TaskInputsModal {}
+
+impl Focusable for TaskInputsModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for TaskInputsModal {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TaskInputsModal")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+/// Options to pick the value of the current input from.
+enum InputOptions {
+    /// The value is typed in.
+    None,
+    Loading,
+    Loaded(Vec<String>),
+    Failed(SharedString),
+}
+
+pub(crate) struct TaskInputsDelegate {
+    modal: WeakEntity<TaskInputsModal>,
+    workspace: WeakEntity<Workspace>,
+    request: TaskInputsRequest,
+    inputs: Vec<TaskInput>,
+    is_local: bool,
+    current_input: usize,
+    values: HashMap<String, String>,
+    options: InputOptions,
+    matches: Vec<StringMatch>,
+    query: String,
+    selected_index: usize,
+    _load_options: Task<()>,
+}
+
+impl TaskInputsDelegate {
+    fn new(
+        modal: WeakEntity<TaskInputsModal>,
+        workspace: WeakEntity<Workspace>,
+        request: TaskInputsRequest,
+        inputs: Vec<TaskInput>,
+        is_local: bool,
+    ) -> Self {
+        Self {
+            modal,
+            workspace,
+            request,
+            inputs,
+            is_local,
+            current_input: 0,
+            values: HashMap::default(),
+            options: InputOptions::None,
+            matches: Vec::new(),
+            query: String::new(),
+            selected_index: 0,
+            _load_options: Task::ready(()),
+        }
+    }
+
+    fn start_input(
+        picker: &mut Picker<Self>,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let delegate = &mut picker.delegate;
+        let Some(input) = delegate.inputs.get(ix).cloned() else {
+            return;
+        };
+        delegate.current_input = ix;
+        delegate.selected_index = 0;
+        delegate._load_options = Task::ready(());
+        delegate.options = match &input.kind {
+            TaskInputKind::PromptString { .. } => InputOptions::None,
+            TaskInputKind::PickString { options, .. } => InputOptions::Loaded(options.clone()),
+            TaskInputKind::CommandOutput { command, args } if delegate.is_local => {
+                let resolved = &delegate.request.resolved_task.resolved;
+                let mut process = util::command::new_smol_command(command);
+                process.args(args).envs(resolved.env.iter());
+                if let Some(cwd) = &resolved.cwd {
+                    process.current_dir(cwd);
+                }
+                let command = command.clone();
+                delegate._load_options = cx.spawn_in(window, async move |picker, cx| {
+                    let options = async {
+                        let output = process
+                            .output()
+                            .await
+                            .with_context(|| format!("running `{command}`"))?;
+                        anyhow::ensure!(
+                            output.status.success(),
+                            "`{command}` failed: {}",
+                            String::from_utf8_lossy(&output.stderr).trim()
+                        );
+                        anyhow::Ok(
+                            String::from_utf8_lossy(&output.stdout)
+                                .lines()
+                                .map(str::trim)
+                                .filter(|line| !line.is_empty())
+                                .map(ToOwned::to_owned)
+                                .collect::<Vec<_>>(),
+                        )
+                    }
+                    .await;
+                    picker
+                        .update_in(cx, |picker, window, cx| {
+                            picker.delegate.options = match options {
+                                Ok(options) => InputOptions::Loaded(options),
+                                Err(e) => InputOptions::Failed(format!("{e:#}").into()),
+                            };
+                            picker.refresh(window, cx);
+                        })
+                        .log_err();
+                });
+                InputOptions::Loading
+            }
+            TaskInputKind::CommandOutput { .. } => InputOptions::Failed(
+                "Command output inputs are not supported in remote projects".into(),
+            ),
+        };
+
+        picker.set_query("", window, cx);
+        picker.refresh_placeholder(window, cx);
+        picker.refresh(window, cx);
+    }
+
+    fn current_input(&self) -> Option<&TaskInput> {
+        self.inputs.get(self.current_input)
+    }
+
+    /// The value that confirming the current input would use.
+    fn selected_value(&self) -> Option<String> {
+        let input = self.current_input()?;
+        match (&input.kind, &self.options) {
+            (TaskInputKind::PromptString { default }, _) => {
+                if self.query.is_empty() {
+                    Some(default.clone().unwrap_or_default())
+                } else {
+                    Some(self.query.clone())
+                }
+            }
+            (_, InputOptions::Loaded(_)) => self
+                .matches
+                .get(self.selected_index)
+                .map(|string_match| string_match.string.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl PickerDelegate for TaskInputsDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        let Some(input) = self.current_input() else {
+            return Arc::default();
+        };
+        let description = if input.description.is_empty() {
+            format!("Value of `{}`", input.id)
+        } else {
+            input.description.clone()
+        };
+        match &input.kind {
+            TaskInputKind::PromptString {
+                default: Some(default),
+            } if !default.is_empty() => format!("{description} (default: {default})").into(),
+            _ => description.into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        match &self.options {
+            InputOptions::None => 1,
+            _ => self.matches.len(),
+        }
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        self.query = query.clone();
+        let InputOptions::Loaded(options) = &self.options else {
+            self.matches.clear();
+            self.selected_index = 0;
+            return Task::ready(());
+        };
+        let default = match self.current_input().map(|input| &input.kind) {
+            Some(TaskInputKind::PickString { default, .. }) => default.clone(),
+            _ => None,
+        };
+        let candidates = options
+            .iter()
+            .enumerate()
+            .map(|(ix, option)| StringMatchCandidate::new(ix, option))
+            .collect::<Vec<_>>();
+        let background = cx.background_executor().clone();
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    1000,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.selected_index = default
+                        .filter(|_| query.is_empty())
+                        .and_then(|default| {
+                            matches
+                                .iter()
+                                .position(|string_match| string_match.string == default)
+                        })
+                        .unwrap_or(0);
+                    delegate.matches = matches;
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let (Some(input), Some(value)) = (self.current_input(), self.selected_value()) else {
+            return;
+        };
+        self.values.insert(input.id.clone(), value);
+
+        let next_input = self.current_input + 1;
+        if next_input < self.inputs.len() {
+            let picker = cx.entity();
+            // The picker is being updated, so the next input is started after the update.
+            window.defer(cx, move |window, cx| {
+                picker.update(cx, |picker, cx| {
+                    Self::start_input(picker, next_input, window, cx)
+                });
+            });
+            return;
+        }
+
+        let mut request = self.request.clone();
+        request.resolved_task.resolve_inputs(&self.values);
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.schedule_resolved_task(
+                    request.task_source_kind,
+                    request.resolved_task,
+                    request.omit_history,
+                    window,
+                    cx,
+                );
+            })
+            .ok();
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.modal
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        match &self.options {
+            InputOptions::Loading => Some("Loading options…".into()),
+            InputOptions::Failed(error) => Some(error.clone()),
+            _ => Some("No matching options".into()),
+        }
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let label = match &self.options {
+            InputOptions::None => {
+                let value = self.selected_value()?;
+                HighlightedLabel::new(format!("Use \"{value}\""), Vec::new())
+            }
+            _ => {
+                let string_match = self.matches.get(ix)?;
+                HighlightedLabel::new(string_match.string.clone(), string_match.positions.clone())
+            }
+        };
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(label),
+        )
+    }
+}
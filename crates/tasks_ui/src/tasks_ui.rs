@@ -9,12 +9,37 @@ use task::{RevealTarget, TaskContext, TaskId, TaskTemplate, TaskVariables, Varia
 use workspace::Workspace;
 
 mod modal;
+mod task_chain;
+mod task_inputs;
 
 pub use modal::{Rerun, ShowAttachModal, Spawn, TasksModal};
 
 pub fn init(cx: &mut App) {
     cx.observe_new(
-        |workspace: &mut Workspace, _: Option<&mut Window>, _: &mut Context<Workspace>| {
+        |workspace: &mut Workspace, window: Option<&mut Window>, cx: &mut Context<Workspace>| {
+            cx.subscribe_self(|workspace, event: &workspace::Event, cx| {
+                if let workspace::Event::TaskChainStarted(running_chain) = event {
+                    task_chain::show_task_chain(workspace, running_chain.clone(), cx);
+                }
+            })
+            .detach();
+            if let Some(window) = window {
+                cx.subscribe_in(
+                    &cx.entity(),
+                    window,
+                    |workspace, _, event: &workspace::Event, window, cx| {
+                        if let workspace::Event::TaskInputsRequested(request) = event {
+                            task_inputs::request_task_inputs(
+                                workspace,
+                                request.clone(),
+                                window,
+                                cx,
+                            );
+                        }
+                    },
+                )
+                .detach();
+            }
             workspace
                 .register_action(spawn_task_or_modal)
                 .register_action(move |workspace, action: &modal::Rerun, window, cx| {
@@ -0,0 +1,227 @@
+//! Positions in the primary screen that stay attached to the output, while it scrolls through the scrollback.
+//!
+//! The positions use rows counted from the top of the scrollback: unlike the grid lines, these stay the same
+//! while the output grows, and only need to be adjusted when the rows get dropped from the top of the scrollback,
+//! or rewrapped after a resize.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use alacritty_terminal::{
+    Term,
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    term::{TermMode, cell::Flags},
+};
+
+/// Number of rows that are hashed together to recognize a place in the scrollback.
+const ANCHOR_ROWS: usize = 4;
+/// How many rows up from the end of the scrollback to look for a non-blank place to recognize.
+const ANCHOR_SEARCH_ROWS: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct GridPosition {
+    /// Counted from the top of the scrollback.
+    pub row: usize,
+    pub column: usize,
+}
+
+/// A place in the scrollback, recognized by the hash of the rows that end at it.
+#[derive(Clone, Copy, Debug)]
+struct Anchor {
+    row: usize,
+    hash: u64,
+}
+
+/// Follows the rows of the primary screen of a terminal.
+#[derive(Default)]
+pub(crate) struct GridRows {
+    history_size: usize,
+    anchor: Option<Anchor>,
+}
+
+impl GridRows {
+    /// The position of the cursor, as of the last [`GridRows::sync`].
+    pub(crate) fn cursor_position<T>(&self, term: &Term<T>) -> GridPosition {
+        let cursor = term.grid().cursor.point;
+        GridPosition {
+            row: (self.history_size as i32 + cursor.line.0).max(0) as usize,
+            column: cursor.column.0,
+        }
+    }
+
+    /// The grid point of the position, as of the last [`GridRows::sync`].
+    pub(crate) fn point(&self, position: GridPosition) -> AlacPoint {
+        AlacPoint::new(
+            Line(position.row as i32 - self.history_size as i32),
+            Column(position.column),
+        )
+    }
+
+    /// Returns how many rows got dropped from the top of the scrollback since the last call,
+    /// or `None` if the rows cannot be followed anymore.
+    ///
+    /// Finding the dropped rows takes looking through the scrollback, so it is skipped when no positions are tracked.
+    pub(crate) fn sync<T>(&mut self, term: &Term<T>, tracking: bool) -> Option<usize> {
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return Some(0);
+        }
+        let history_size = term.history_size();
+        let dropped_rows = if !tracking {
+            Some(0)
+        } else if history_size < self.history_size {
+            Some(self.history_size - history_size)
+        } else {
+            self.dropped_rows(term)
+        };
+        self.reset(term, tracking);
+        dropped_rows
+    }
+
+    fn reset<T>(&mut self, term: &Term<T>, tracking: bool) {
+        self.history_size = term.history_size();
+        self.anchor = if tracking { find_anchor(term) } else { None };
+    }
+
+    /// Once the scrollback is full, its oldest rows are dropped as the new ones get added,
+    /// which can only be noticed by looking for the rows that were at the end of the scrollback before.
+    fn dropped_rows<T>(&self, term: &Term<T>) -> Option<usize> {
+        let Some(anchor) = self.anchor else {
+            return Some(0);
+        };
+        (0..=anchor.row).find(|dropped_rows| {
+            anchor.row - dropped_rows < term.history_size()
+                && rows_hash(term, anchor.row - dropped_rows) == anchor.hash
+        })
+    }
+
+    /// Resizes the terminal, returning where the tracked positions end up in the rewrapped output.
+    /// Returns `None` if the positions cannot be followed.
+    pub(crate) fn resize<T>(
+        &mut self,
+        term: &mut Term<T>,
+        tracking: bool,
+        resize: impl FnOnce(&mut Term<T>),
+    ) -> Option<Rewrap> {
+        if !tracking || term.mode().contains(TermMode::ALT_SCREEN) {
+            // In the alternate screen, the primary screen is rewrapped out of sight.
+            resize(term);
+            self.reset(term, false);
+            return None;
+        }
+
+        let old_columns = term.columns();
+        let old_line_starts = logical_line_starts(term);
+        resize(term);
+        self.reset(term, true);
+        Some(Rewrap {
+            old_columns,
+            old_line_starts,
+            new_columns: term.columns(),
+            new_line_starts: logical_line_starts(term),
+        })
+    }
+
+    /// Forgets the scrollback, which is about to be cleared along with the lines above the cursor.
+    /// Returns the row of the cursor, which becomes the first row, or `None` in the alternate screen.
+    pub(crate) fn clear<T>(&mut self, term: &Term<T>) -> Option<usize> {
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return None;
+        }
+        let cursor_row = self.cursor_position(term).row;
+        self.history_size = 0;
+        self.anchor = None;
+        Some(cursor_row)
+    }
+}
+
+/// Adjusts the tracked positions to the rows dropped from the top of the scrollback,
+/// dropping the items positioned in these rows.
+pub(crate) fn drop_rows<I>(
+    items: &mut Vec<I>,
+    dropped_rows: Option<usize>,
+    mut position: impl FnMut(&mut I) -> Option<&mut GridPosition>,
+) {
+    match dropped_rows {
+        Some(0) => {}
+        Some(dropped_rows) => items.retain_mut(|item| match position(item) {
+            Some(position) if position.row < dropped_rows => false,
+            Some(position) => {
+                position.row -= dropped_rows;
+                true
+            }
+            None => true,
+        }),
+        None => items.retain_mut(|item| position(item).is_none()),
+    }
+}
+
+/// Maps the positions from before a resize to the rewrapped output.
+pub(crate) struct Rewrap {
+    old_columns: usize,
+    old_line_starts: Vec<usize>,
+    new_columns: usize,
+    new_line_starts: Vec<usize>,
+}
+
+impl Rewrap {
+    pub(crate) fn position(&self, position: GridPosition) -> Option<GridPosition> {
+        let line = self
+            .old_line_starts
+            .partition_point(|&start| start <= position.row)
+            .checked_sub(1)?;
+        let offset =
+            (position.row - self.old_line_starts[line]) * self.old_columns + position.column;
+        let start = self.new_line_starts.get(line)?;
+        Some(GridPosition {
+            row: start + offset / self.new_columns,
+            column: offset % self.new_columns,
+        })
+    }
+}
+
+pub(crate) fn row_line<T>(term: &Term<T>, row: usize) -> Line {
+    Line(row as i32 - term.history_size() as i32)
+}
+
+/// Rows where the lines start, that is, the rows that are not continuations of the wrapped lines above them.
+fn logical_line_starts<T>(term: &Term<T>) -> Vec<usize> {
+    let grid = term.grid();
+    let last_column = term.last_column();
+    let mut starts = vec![0];
+    for row in 0..grid.total_lines().saturating_sub(1) {
+        if !grid[row_line(term, row)][last_column]
+            .flags
+            .contains(Flags::WRAPLINE)
+        {
+            starts.push(row + 1);
+        }
+    }
+    starts
+}
+
+fn find_anchor<T>(term: &Term<T>) -> Option<Anchor> {
+    let history_size = term.history_size();
+    (history_size.saturating_sub(ANCHOR_SEARCH_ROWS)..history_size)
+        .rev()
+        .find(|&row| {
+            (row.saturating_sub(ANCHOR_ROWS - 1)..=row).any(|row| {
+                term.grid()[row_line(term, row)]
+                    .into_iter()
+                    .any(|cell| cell.c != ' ')
+            })
+        })
+        .map(|row| Anchor {
+            row,
+            hash: rows_hash(term, row),
+        })
+}
+
+fn rows_hash<T>(term: &Term<T>, last_row: usize) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for row in last_row.saturating_sub(ANCHOR_ROWS - 1)..=last_row {
+        for cell in term.grid()[row_line(term, row)].into_iter() {
+            cell.c.hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
@@ -0,0 +1,640 @@
+//! Inline images that programs print with the Kitty graphics protocol, Sixel or the iTerm2 image protocol,
+//! and their placements on the grid.
+
+use std::{io::Cursor, sync::Arc};
+
+use alacritty_terminal::{
+    Term,
+    event::EventListener,
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    term::TermMode,
+    vte::ansi::Handler,
+};
+use base64::{
+    Engine as _, alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+use collections::HashSet;
+use gpui::{Pixels, RenderImage, Size};
+use image::RgbaImage;
+
+use crate::{
+    grid_rows::{self, GridPosition, GridRows, Rewrap},
+    kitty_graphics::KittyReply,
+    sixel,
+};
+
+/// The most memory that the decoded images of a terminal take, the oldest images are dropped first.
+/// This is the same quota that Kitty uses.
+pub(crate) const MAX_IMAGE_MEMORY: usize = 320 << 20;
+/// The largest width and height of an image, in pixels.
+pub(crate) const MAX_IMAGE_SIDE: u32 = 10_000;
+/// The most images that are placed on the grid at once, the oldest placements are dropped first.
+const MAX_PLACEMENTS: usize = 1000;
+/// The largest width and height of a placement, in cells.
+const MAX_PLACEMENT_CELLS: usize = 1000;
+
+/// Base64 that is accepted with or without padding.
+pub(crate) const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// An image, decoded into the format that gpui paints.
+#[derive(Clone)]
+pub(crate) struct DecodedImage {
+    render: Arc<RenderImage>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl DecodedImage {
+    pub(crate) fn new(mut image: RgbaImage) -> Self {
+        let (width, height) = image.dimensions();
+        // Convert from RGBA to BGRA.
+        for pixel in image.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+        Self {
+            render: Arc::new(RenderImage::new(vec![image::Frame::new(image)])),
+            width,
+            height,
+        }
+    }
+
+    fn memory(&self) -> usize {
+        self.width as usize * self.height as usize * 4
+    }
+}
+
+/// Decodes an image file, in any of the formats that the `image` crate supports.
+pub(crate) fn decode_image_file(bytes: &[u8]) -> anyhow::Result<RgbaImage> {
+    let mut reader = image::ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+    let mut limits = image::Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_SIDE);
+    limits.max_image_height = Some(MAX_IMAGE_SIDE);
+    limits.max_alloc = Some(MAX_IMAGE_MEMORY as u64);
+    reader.limits(limits);
+    Ok(reader.decode()?.into_rgba8())
+}
+
+/// The width or the height of a placed image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ImageSize {
+    /// The size of the image itself, or the one that keeps its aspect ratio.
+    Auto,
+    Cells(usize),
+    Pixels(u32),
+    /// Percent of the terminal size.
+    Percent(u32),
+}
+
+/// Where the cursor goes after an image is placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CursorMovement {
+    None,
+    /// To the last row of the image, after its last column.
+    AfterImage,
+    /// To the row below the image, in the column where the image starts.
+    BelowImage,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct PlacementRequest {
+    pub width: ImageSize,
+    pub height: ImageSize,
+    /// Whether the image keeps its aspect ratio when both the width and the height are given.
+    pub preserve_aspect_ratio: bool,
+    pub cursor_movement: CursorMovement,
+    /// Whether the image is painted below the text.
+    pub below_text: bool,
+}
+
+impl PlacementRequest {
+    /// Places the image the way the program printed it, below the cursor.
+    fn printed() -> Self {
+        Self {
+            width: ImageSize::Auto,
+            height: ImageSize::Auto,
+            preserve_aspect_ratio: true,
+            cursor_movement: CursorMovement::BelowImage,
+            below_text: false,
+        }
+    }
+
+    /// The size of the placed image, in cells.
+    fn size(
+        &self,
+        image: &DecodedImage,
+        cell_size: Size<Pixels>,
+        screen_size: Size<usize>,
+    ) -> Size<f32> {
+        let cell_width = cell_size.width.0.max(1.);
+        let cell_height = cell_size.height.0.max(1.);
+        let pixels = |size, cell, screen_cells: usize| match size {
+            ImageSize::Auto => None,
+            ImageSize::Cells(cells) => Some(cells as f32 * cell),
+            ImageSize::Pixels(pixels) => Some(pixels as f32),
+            ImageSize::Percent(percent) => Some(screen_cells as f32 * cell * percent as f32 / 100.),
+        };
+        let image_width = image.width.max(1) as f32;
+        let image_height = image.height.max(1) as f32;
+        let (width, height) = match (
+            pixels(self.width, cell_width, screen_size.width),
+            pixels(self.height, cell_height, screen_size.height),
+        ) {
+            (None, None) => (image_width, image_height),
+            (Some(width), None) => (width, width * image_height / image_width),
+            (None, Some(height)) => (height * image_width / image_height, height),
+            (Some(width), Some(height)) if self.preserve_aspect_ratio => {
+                let scale = (width / image_width).min(height / image_height);
+                (image_width * scale, image_height * scale)
+            }
+            (Some(width), Some(height)) => (width, height),
+        };
+        let max_cells = MAX_PLACEMENT_CELLS as f32;
+        Size {
+            width: (width / cell_width).clamp(0., max_cells),
+            height: (height / cell_height).clamp(0., max_cells),
+        }
+    }
+}
+
+/// A command that an image sequence carries out.
+pub(crate) struct ImageCommand {
+    pub action: ImageAction,
+    /// How to respond to the program, for the Kitty graphics protocol commands.
+    pub reply: Option<KittyReply>,
+}
+
+pub(crate) enum ImageAction {
+    /// Only checks that the image could be stored.
+    Query,
+    Store {
+        /// The id to place the image with later, if any.
+        id: Option<u32>,
+        image: DecodedImage,
+        /// Where to place the image right away, if anywhere.
+        placement: Option<PlacementRequest>,
+    },
+    Place {
+        id: u32,
+        placement: PlacementRequest,
+    },
+    /// Removes the placements of the image with the given id, or all visible placements.
+    Delete {
+        id: Option<u32>,
+        /// Whether to forget the stored images too.
+        free: bool,
+    },
+    Failed(String),
+}
+
+/// Parses `OSC 1337 ; File=<arguments>:<base64 data>`, ignoring the files that are not displayed inline.
+pub(crate) fn parse_iterm_image(body: &[u8]) -> Option<ImageCommand> {
+    let separator = body.iter().position(|&byte| byte == b':')?;
+    let arguments = std::str::from_utf8(&body[..separator]).ok()?;
+    let mut placement = PlacementRequest::printed();
+    let mut inline = false;
+    for argument in arguments.split(';') {
+        let Some((key, value)) = argument.split_once('=') else {
+            continue;
+        };
+        match key {
+            "inline" => inline = value == "1",
+            "width" => placement.width = parse_iterm_size(value)?,
+            "height" => placement.height = parse_iterm_size(value)?,
+            "preserveAspectRatio" => placement.preserve_aspect_ratio = value != "0",
+            _ => {}
+        }
+    }
+    if !inline {
+        return None;
+    }
+
+    let data: Vec<u8> = body[separator + 1..]
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+    let action = BASE64
+        .decode(data)
+        .map_err(|error| error.to_string())
+        .and_then(|bytes| decode_image_file(&bytes).map_err(|error| error.to_string()))
+        .map_or_else(ImageAction::Failed, |image| ImageAction::Store {
+            id: None,
+            image: DecodedImage::new(image),
+            placement: Some(placement),
+        });
+    Some(ImageCommand {
+        action,
+        reply: None,
+    })
+}
+
+fn parse_iterm_size(value: &str) -> Option<ImageSize> {
+    if value == "auto" {
+        Some(ImageSize::Auto)
+    } else if let Some(pixels) = value.strip_suffix("px") {
+        pixels.parse().ok().map(ImageSize::Pixels)
+    } else if let Some(percent) = value.strip_suffix('%') {
+        percent.parse().ok().map(ImageSize::Percent)
+    } else {
+        value.parse().ok().map(ImageSize::Cells)
+    }
+}
+
+/// Parses `DCS <params> q <data>`.
+pub(crate) fn parse_sixel_image(body: &[u8]) -> Option<ImageCommand> {
+    let action = match sixel::decode(body) {
+        Some(image) => ImageAction::Store {
+            id: None,
+            image: DecodedImage::new(image),
+            placement: Some(PlacementRequest::printed()),
+        },
+        None => ImageAction::Failed("invalid sixel image".to_string()),
+    };
+    Some(ImageCommand {
+        action,
+        reply: None,
+    })
+}
+
+/// An image placed in the viewport.
+#[derive(Clone, Debug)]
+pub struct TerminalImage {
+    pub image: Arc<RenderImage>,
+    /// The cell where the top left corner of the image is.
+    pub point: AlacPoint,
+    /// The size of the image, in cells.
+    pub size: Size<f32>,
+    /// Whether the image is painted below the text.
+    pub below_text: bool,
+}
+
+struct Placement {
+    image: DecodedImage,
+    /// In the alternate screen, the row is the grid line, since there is no scrollback to follow.
+    position: GridPosition,
+    alt_screen: bool,
+    size: Size<f32>,
+    below_text: bool,
+    kitty_id: Option<u32>,
+}
+
+/// The images stored and placed in a terminal.
+#[derive(Default)]
+pub(crate) struct TerminalImages {
+    /// Images transmitted with the Kitty graphics protocol, oldest first.
+    stored: Vec<(u32, DecodedImage)>,
+    /// Oldest first.
+    placements: Vec<Placement>,
+    /// Images that are stored or placed.
+    known: Vec<Arc<RenderImage>>,
+    /// Images that are not used anymore, to be removed from the sprite atlas.
+    removed: Vec<Arc<RenderImage>>,
+}
+
+impl TerminalImages {
+    /// Whether any images are placed in the primary screen, where they need to follow the scrollback.
+    pub(crate) fn tracks_rows(&self) -> bool {
+        self.placements
+            .iter()
+            .any(|placement| !placement.alt_screen)
+    }
+
+    /// Carries out the command, returning the response to write to the PTY, if any.
+    pub(crate) fn apply<T: EventListener>(
+        &mut self,
+        command: ImageCommand,
+        term: &mut Term<T>,
+        rows: &GridRows,
+        cell_size: Size<Pixels>,
+    ) -> Option<String> {
+        let result = match command.action {
+            ImageAction::Query => Ok(()),
+            ImageAction::Failed(error) => Err(error),
+            ImageAction::Store {
+                id,
+                image,
+                placement,
+            } => {
+                if let Some(id) = id {
+                    self.forget_stored(|stored_id| stored_id == id);
+                    self.stored.push((id, image.clone()));
+                }
+                if let Some(placement) = placement {
+                    self.place(image, id, &placement, term, rows, cell_size);
+                }
+                Ok(())
+            }
+            ImageAction::Place { id, placement } => {
+                match self.stored.iter().find(|(stored_id, _)| *stored_id == id) {
+                    Some((_, image)) => {
+                        let image = image.clone();
+                        self.place(image, Some(id), &placement, term, rows, cell_size);
+                        Ok(())
+                    }
+                    None => Err(format!("ENOENT:image {id} not found")),
+                }
+            }
+            ImageAction::Delete { id, free } => {
+                let alt_screen = term.mode().contains(TermMode::ALT_SCREEN);
+                self.remove_placements(|placement| match id {
+                    Some(id) => placement.kitty_id == Some(id),
+                    None => placement.alt_screen == alt_screen,
+                });
+                if free {
+                    self.forget_stored(|stored_id| id.is_none_or(|id| id == stored_id));
+                }
+                Ok(())
+            }
+        };
+        for image in self.images() {
+            if !self
+                .known
+                .iter()
+                .any(|known| Arc::ptr_eq(known, &image.render))
+            {
+                self.known.push(image.render.clone());
+            }
+        }
+        self.enforce_limits();
+        command.reply.and_then(|reply| reply.response(result))
+    }
+
+    fn place<T: EventListener>(
+        &mut self,
+        image: DecodedImage,
+        kitty_id: Option<u32>,
+        request: &PlacementRequest,
+        term: &mut Term<T>,
+        rows: &GridRows,
+        cell_size: Size<Pixels>,
+    ) {
+        let screen_size = Size {
+            width: term.columns(),
+            height: term.screen_lines(),
+        };
+        let size = request.size(&image, cell_size, screen_size);
+        let alt_screen = term.mode().contains(TermMode::ALT_SCREEN);
+        let cursor = term.grid().cursor.point;
+        let position = if alt_screen {
+            GridPosition {
+                row: cursor.line.0.max(0) as usize,
+                column: cursor.column.0,
+            }
+        } else {
+            rows.cursor_position(term)
+        };
+        self.placements.push(Placement {
+            image,
+            position,
+            alt_screen,
+            size,
+            below_text: request.below_text,
+            kitty_id,
+        });
+
+        let columns = size.width.ceil() as usize;
+        let lines = (size.height.ceil() as usize).max(1);
+        match request.cursor_movement {
+            CursorMovement::None => {}
+            CursorMovement::AfterImage => {
+                for _ in 1..lines {
+                    term.linefeed();
+                }
+                let column = (cursor.column.0 + columns).min(term.last_column().0);
+                term.goto_col(column);
+            }
+            CursorMovement::BelowImage => {
+                for _ in 0..lines {
+                    term.linefeed();
+                }
+                term.goto_col(cursor.column.0);
+            }
+        }
+    }
+
+    pub(crate) fn drop_rows(&mut self, dropped_rows: Option<usize>) {
+        let placements = self.placements.len();
+        grid_rows::drop_rows(&mut self.placements, dropped_rows, |placement| {
+            (!placement.alt_screen).then_some(&mut placement.position)
+        });
+        if self.placements.len() < placements {
+            self.collect_removed();
+        }
+    }
+
+    pub(crate) fn rewrap(&mut self, rewrap: Option<&Rewrap>) {
+        self.placements.retain_mut(|placement| {
+            if placement.alt_screen {
+                return true;
+            }
+            match rewrap.and_then(|rewrap| rewrap.position(placement.position)) {
+                Some(position) => {
+                    placement.position = position;
+                    true
+                }
+                None => false,
+            }
+        });
+        self.collect_removed();
+    }
+
+    /// Removes the placements in the primary screen, which is about to be cleared.
+    pub(crate) fn clear(&mut self) {
+        self.remove_placements(|placement| !placement.alt_screen);
+    }
+
+    /// Removes the placements in the alternate screen once it is left.
+    pub(crate) fn sync_screen<T>(&mut self, term: &Term<T>) {
+        if !term.mode().contains(TermMode::ALT_SCREEN) {
+            self.remove_placements(|placement| placement.alt_screen);
+        }
+    }
+
+    /// The placements that are at least partly in the viewport.
+    pub(crate) fn visible<T>(&self, rows: &GridRows, term: &Term<T>) -> Vec<TerminalImage> {
+        let alt_screen = term.mode().contains(TermMode::ALT_SCREEN);
+        let display_offset = term.grid().display_offset() as i32;
+        let screen_lines = term.screen_lines() as i32;
+        self.placements
+            .iter()
+            .filter(|placement| placement.alt_screen == alt_screen)
+            .filter_map(|placement| {
+                let point = if alt_screen {
+                    AlacPoint::new(
+                        Line(placement.position.row as i32),
+                        Column(placement.position.column),
+                    )
+                } else {
+                    rows.point(placement.position)
+                };
+                let top = point.line.0 + display_offset;
+                let bottom = top + placement.size.height.ceil() as i32;
+                (top < screen_lines && bottom > 0).then(|| TerminalImage {
+                    image: placement.image.render.clone(),
+                    point,
+                    size: placement.size,
+                    below_text: placement.below_text,
+                })
+            })
+            .collect()
+    }
+
+    /// Takes the images that are not used anymore.
+    pub(crate) fn take_removed(&mut self) -> Vec<Arc<RenderImage>> {
+        std::mem::take(&mut self.removed)
+    }
+
+    fn remove_placements(&mut self, mut remove: impl FnMut(&Placement) -> bool) {
+        self.placements.retain(|placement| !remove(placement));
+        self.collect_removed();
+    }
+
+    fn forget_stored(&mut self, mut forget: impl FnMut(u32) -> bool) {
+        self.stored.retain(|(id, _)| !forget(*id));
+        self.collect_removed();
+    }
+
+    /// Drops the oldest images until the images fit into the limits.
+    fn enforce_limits(&mut self) {
+        if self.placements.len() > MAX_PLACEMENTS {
+            self.placements
+                .drain(..self.placements.len() - MAX_PLACEMENTS);
+            self.collect_removed();
+        }
+        while self.memory() > MAX_IMAGE_MEMORY {
+            if !self.placements.is_empty() {
+                self.placements.remove(0);
+            } else if !self.stored.is_empty() {
+                self.stored.remove(0);
+            } else {
+                break;
+            }
+            self.collect_removed();
+        }
+    }
+
+    fn images(&self) -> impl Iterator<Item = &DecodedImage> {
+        self.stored
+            .iter()
+            .map(|(_, image)| image)
+            .chain(self.placements.iter().map(|placement| &placement.image))
+    }
+
+    fn memory(&self) -> usize {
+        let mut counted = HashSet::default();
+        self.images()
+            .filter(|image| counted.insert(Arc::as_ptr(&image.render)))
+            .map(DecodedImage::memory)
+            .sum()
+    }
+
+    /// Remembers the images that were known before and are not used anymore.
+    fn collect_removed(&mut self) {
+        let used: HashSet<_> = self
+            .images()
+            .map(|image| Arc::as_ptr(&image.render))
+            .collect();
+        let known = std::mem::take(&mut self.known);
+        let (kept, removed): (Vec<_>, Vec<_>) = known
+            .into_iter()
+            .partition(|image| used.contains(&Arc::as_ptr(image)));
+        self.known = kept;
+        self.removed.extend(removed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::px;
+
+    use super::*;
+
+    #[test]
+    fn test_parsing_iterm_image() {
+        let mut png = Vec::new();
+        RgbaImage::new(3, 2)
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let body = format!(
+            "name=dGVzdA==;size={};width=50%;height=auto;inline=1:{}",
+            png.len(),
+            BASE64.encode(&png)
+        );
+        let Some(ImageCommand {
+            action:
+                ImageAction::Store {
+                    id: None,
+                    image,
+                    placement: Some(placement),
+                },
+            reply: None,
+        }) = parse_iterm_image(body.as_bytes())
+        else {
+            panic!("expected the image to be placed");
+        };
+        assert_eq!((image.width, image.height), (3, 2));
+        assert_eq!(placement.width, ImageSize::Percent(50));
+        assert_eq!(placement.height, ImageSize::Auto);
+        assert_eq!(placement.cursor_movement, CursorMovement::BelowImage);
+
+        // Files that are not displayed inline are ignored.
+        assert!(parse_iterm_image(b"name=dGVzdA==:AAAA").is_none());
+        assert!(matches!(
+            parse_iterm_image(b"inline=1:AAAA"),
+            Some(ImageCommand {
+                action: ImageAction::Failed(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_placement_size() {
+        let image = DecodedImage::new(RgbaImage::new(40, 20));
+        let cell_size = Size {
+            width: px(10.),
+            height: px(20.),
+        };
+        let screen_size = Size {
+            width: 80,
+            height: 24,
+        };
+        let size = |width, height, preserve_aspect_ratio| {
+            PlacementRequest {
+                width,
+                height,
+                preserve_aspect_ratio,
+                ..PlacementRequest::printed()
+            }
+            .size(&image, cell_size, screen_size)
+        };
+        let cells = |width, height| Size { width, height };
+
+        assert_eq!(size(ImageSize::Auto, ImageSize::Auto, true), cells(4., 1.));
+        assert_eq!(
+            size(ImageSize::Cells(8), ImageSize::Auto, true),
+            cells(8., 2.)
+        );
+        assert_eq!(
+            size(ImageSize::Cells(8), ImageSize::Cells(1), true),
+            cells(4., 1.)
+        );
+        assert_eq!(
+            size(ImageSize::Cells(8), ImageSize::Cells(1), false),
+            cells(8., 1.)
+        );
+        assert_eq!(
+            size(ImageSize::Percent(50), ImageSize::Auto, true),
+            cells(40., 10.)
+        );
+        assert_eq!(
+            size(ImageSize::Auto, ImageSize::Pixels(10), true),
+            cells(2., 0.5)
+        );
+    }
+}
@@ -0,0 +1,345 @@
+//! The Kitty graphics protocol: <https://sw.kovidgoyal.net/kitty/graphics-protocol/>
+//!
+//! Only the images transmitted directly in the escape sequences are supported.
+
+use std::io::Read as _;
+
+use base64::Engine as _;
+use image::{RgbImage, RgbaImage};
+
+use crate::images::{
+    BASE64, CursorMovement, DecodedImage, ImageAction, ImageCommand, ImageSize, MAX_IMAGE_MEMORY,
+    MAX_IMAGE_SIDE, PlacementRequest,
+};
+
+/// The most base64 data that a chunked transmission collects.
+const MAX_PAYLOAD_LEN: usize = 64 << 20;
+
+/// The control data of a command, `<key>=<value>` pairs separated by commas.
+#[derive(Clone, Debug, PartialEq)]
+struct Control {
+    /// `a`
+    action: u8,
+    /// `f`: 24 for RGB, 32 for RGBA, 100 for PNG.
+    format: u32,
+    /// `t`
+    medium: u8,
+    /// `o`
+    compression: Option<u8>,
+    /// `s`, in pixels.
+    width: u32,
+    /// `v`, in pixels.
+    height: u32,
+    /// `i`
+    id: Option<u32>,
+    /// `m`: whether more chunks follow.
+    more: bool,
+    /// `c`
+    columns: usize,
+    /// `r`
+    rows: usize,
+    /// `C`: whether the cursor stays where it is.
+    no_cursor_movement: bool,
+    /// `z`
+    z_index: i32,
+    /// `q`: 1 suppresses the OK responses, 2 suppresses the errors too.
+    quiet: u8,
+    /// `d`: what to delete.
+    delete: u8,
+}
+
+impl Default for Control {
+    fn default() -> Self {
+        Self {
+            action: b't',
+            format: 32,
+            medium: b'd',
+            compression: None,
+            width: 0,
+            height: 0,
+            id: None,
+            more: false,
+            columns: 0,
+            rows: 0,
+            no_cursor_movement: false,
+            z_index: 0,
+            quiet: 0,
+            delete: b'a',
+        }
+    }
+}
+
+impl Control {
+    fn parse(control: &[u8]) -> Self {
+        let mut parsed = Self::default();
+        for pair in control.split(|&byte| byte == b',') {
+            let Some((&[key], value)) = pair
+                .iter()
+                .position(|&byte| byte == b'=')
+                .map(|ix| (&pair[..ix], &pair[ix + 1..]))
+            else {
+                continue;
+            };
+            let number = || std::str::from_utf8(value).ok()?.parse::<i64>().ok();
+            let character = value.first().copied();
+            match key {
+                b'a' => parsed.action = character.unwrap_or(parsed.action),
+                b'f' => parsed.format = number().unwrap_or(0) as u32,
+                b't' => parsed.medium = character.unwrap_or(parsed.medium),
+                b'o' => parsed.compression = character,
+                b's' => parsed.width = number().unwrap_or(0) as u32,
+                b'v' => parsed.height = number().unwrap_or(0) as u32,
+                b'i' => parsed.id = number().map(|id| id as u32).filter(|&id| id > 0),
+                b'm' => parsed.more = number() == Some(1),
+                b'c' => parsed.columns = number().unwrap_or(0).max(0) as usize,
+                b'r' => parsed.rows = number().unwrap_or(0).max(0) as usize,
+                b'C' => parsed.no_cursor_movement = number() == Some(1),
+                b'z' => parsed.z_index = number().unwrap_or(0) as i32,
+                b'q' => parsed.quiet = number().unwrap_or(0) as u8,
+                b'd' => parsed.delete = character.unwrap_or(parsed.delete),
+                _ => {}
+            }
+        }
+        parsed
+    }
+
+    fn placement(&self) -> PlacementRequest {
+        let cells = |cells| {
+            if cells > 0 {
+                ImageSize::Cells(cells)
+            } else {
+                ImageSize::Auto
+            }
+        };
+        PlacementRequest {
+            width: cells(self.columns),
+            height: cells(self.rows),
+            preserve_aspect_ratio: false,
+            cursor_movement: if self.no_cursor_movement {
+                CursorMovement::None
+            } else {
+                CursorMovement::AfterImage
+            },
+            below_text: self.z_index < 0,
+        }
+    }
+
+    fn command(&self, payload: &[u8]) -> ImageCommand {
+        let action = match self.action {
+            b'd' => ImageAction::Delete {
+                id: match self.delete {
+                    b'i' | b'I' => self.id,
+                    _ => None,
+                },
+                free: self.delete.is_ascii_uppercase(),
+            },
+            b'p' => match self.id {
+                Some(id) => ImageAction::Place {
+                    id,
+                    placement: self.placement(),
+                },
+                None => ImageAction::Failed("EINVAL:no image id to place".to_string()),
+            },
+            b't' | b'T' | b'q' => match self.decode(payload) {
+                Ok(_) if self.action == b'q' => ImageAction::Query,
+                Ok(image) => ImageAction::Store {
+                    id: self.id,
+                    image,
+                    placement: (self.action == b'T').then(|| self.placement()),
+                },
+                Err(error) => ImageAction::Failed(error),
+            },
+            action => ImageAction::Failed(format!("EINVAL:unsupported action {}", action as char)),
+        };
+        ImageCommand {
+            action,
+            reply: self.id.map(|id| KittyReply {
+                id,
+                quiet: self.quiet,
+            }),
+        }
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<DecodedImage, String> {
+        if self.medium != b'd' {
+            return Err("EINVAL:only direct transmission is supported".to_string());
+        }
+        let data = BASE64
+            .decode(payload)
+            .map_err(|error| format!("EINVAL:{error}"))?;
+        let data = match self.compression {
+            None => data,
+            Some(b'z') => {
+                let mut decompressed = Vec::new();
+                flate2::read::ZlibDecoder::new(data.as_slice())
+                    .take(MAX_IMAGE_MEMORY as u64)
+                    .read_to_end(&mut decompressed)
+                    .map_err(|error| format!("EINVAL:{error}"))?;
+                decompressed
+            }
+            Some(compression) => {
+                return Err(format!(
+                    "EINVAL:unsupported compression {}",
+                    compression as char
+                ));
+            }
+        };
+        let image = match self.format {
+            24 | 32 => self.raw_image(data)?,
+            100 => crate::images::decode_image_file(&data)
+                .map_err(|error| format!("EBADPNG:{error}"))?,
+            format => return Err(format!("EINVAL:unsupported format {format}")),
+        };
+        Ok(DecodedImage::new(image))
+    }
+
+    fn raw_image(&self, data: Vec<u8>) -> Result<RgbaImage, String> {
+        if self.width == 0
+            || self.height == 0
+            || self.width > MAX_IMAGE_SIDE
+            || self.height > MAX_IMAGE_SIDE
+        {
+            return Err("EINVAL:invalid image size".to_string());
+        }
+        let image = if self.format == 24 {
+            RgbImage::from_raw(self.width, self.height, data)
+                .map(|image| image::DynamicImage::ImageRgb8(image).into_rgba8())
+        } else {
+            RgbaImage::from_raw(self.width, self.height, data)
+        };
+        image.ok_or_else(|| "ENODATA:insufficient image data".to_string())
+    }
+}
+
+/// How to respond to a command that has an image id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct KittyReply {
+    id: u32,
+    quiet: u8,
+}
+
+impl KittyReply {
+    pub(crate) fn response(&self, result: Result<(), String>) -> Option<String> {
+        let message = match result {
+            Ok(()) if self.quiet == 0 => "OK".to_string(),
+            Err(error) if self.quiet < 2 => error,
+            _ => return None,
+        };
+        Some(format!("\x1b_Gi={};{message}\x1b\\", self.id))
+    }
+}
+
+/// Collects the chunks of the images transmitted in several commands.
+#[derive(Default)]
+pub(crate) struct Transmissions {
+    chunked: Option<(Control, Vec<u8>)>,
+}
+
+impl Transmissions {
+    /// Receives the body of an `APC G` sequence, returning the command once all of its chunks are received.
+    pub(crate) fn receive(&mut self, body: &[u8]) -> Option<ImageCommand> {
+        let (control, payload) = match body.iter().position(|&byte| byte == b';') {
+            Some(ix) => (&body[..ix], &body[ix + 1..]),
+            None => (body, &[][..]),
+        };
+        let control = Control::parse(control);
+        let (control, payload) = match self.chunked.take() {
+            // The following chunks only carry the `m` and `q` keys.
+            Some((mut first, mut collected)) => {
+                first.more = control.more;
+                first.quiet = first.quiet.max(control.quiet);
+                collected.extend_from_slice(payload);
+                (first, collected)
+            }
+            None => (control, payload.to_vec()),
+        };
+
+        if payload.len() > MAX_PAYLOAD_LEN {
+            return Some(ImageCommand {
+                action: ImageAction::Failed("EFBIG:image is too large".to_string()),
+                reply: None,
+            });
+        }
+        if control.more {
+            self.chunked = Some((control, payload));
+            return None;
+        }
+        Some(control.command(&payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parsing_control_data() {
+        assert_eq!(
+            Control::parse(b"a=T,f=24,s=2,v=1,i=7,c=10,r=5,C=1,z=-1,q=2,unknown=x,m"),
+            Control {
+                action: b'T',
+                format: 24,
+                width: 2,
+                height: 1,
+                id: Some(7),
+                columns: 10,
+                rows: 5,
+                no_cursor_movement: true,
+                z_index: -1,
+                quiet: 2,
+                ..Control::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_chunked_transmission() {
+        let mut transmissions = Transmissions::default();
+        // Two RGB pixels, red and blue, split into two chunks.
+        assert!(
+            transmissions
+                .receive(b"a=t,f=24,s=2,v=1,i=3,m=1;/wAA")
+                .is_none()
+        );
+        let command = transmissions.receive(b"m=0;AAD/").unwrap();
+        assert_eq!(command.reply, Some(KittyReply { id: 3, quiet: 0 }));
+        let ImageAction::Store {
+            id,
+            image,
+            placement,
+        } = command.action
+        else {
+            panic!("expected the image to be stored");
+        };
+        assert_eq!(id, Some(3));
+        assert_eq!(placement, None);
+        assert_eq!((image.width, image.height), (2, 1));
+
+        let command = transmissions.receive(b"a=T,f=32,s=2,v=2,i=4;AAAA").unwrap();
+        assert!(
+            matches!(command.action, ImageAction::Failed(ref error) if error.starts_with("ENODATA"))
+        );
+        assert_eq!(
+            command
+                .reply
+                .unwrap()
+                .response(Err("ENODATA:insufficient image data".into())),
+            Some("\x1b_Gi=4;ENODATA:insufficient image data\x1b\\".to_string())
+        );
+    }
+
+    #[test]
+    fn test_replies() {
+        let reply = |quiet| KittyReply { id: 1, quiet };
+        assert_eq!(
+            reply(0).response(Ok(())),
+            Some("\x1b_Gi=1;OK\x1b\\".to_string())
+        );
+        assert_eq!(reply(1).response(Ok(())), None);
+        assert_eq!(
+            reply(1).response(Err("ENOENT:image 1 not found".into())),
+            Some("\x1b_Gi=1;ENOENT:image 1 not found\x1b\\".to_string())
+        );
+        assert_eq!(reply(2).response(Err("EINVAL".into())), None);
+    }
+}
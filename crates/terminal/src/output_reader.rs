@@ -0,0 +1,143 @@
+//! Reading the output of the primary screen line by line, as it gets printed.
+
+use alacritty_terminal::{
+    Term,
+    grid::Dimensions,
+    term::{TermMode, cell::Flags},
+};
+
+use crate::{
+    grid_rows::{GridPosition, GridRows, Rewrap, row_line},
+    row_to_string,
+};
+
+/// The lines printed to a terminal since they were read the last time.
+#[derive(Debug, Default)]
+pub struct NewLines {
+    /// The lines, with the wrapped lines joined and the trailing whitespace trimmed.
+    pub lines: Vec<String>,
+    /// Whether the lines read before could not be followed, e.g. after the scrollback got rewrapped,
+    /// so all lines of the scrollback are read again.
+    pub reread: bool,
+}
+
+/// Remembers which lines of the output were read already.
+#[derive(Default)]
+pub(crate) struct OutputReader {
+    reading: bool,
+    /// The start of the last row of the last line read.
+    last_read: Option<GridPosition>,
+    lost: bool,
+}
+
+impl OutputReader {
+    pub(crate) fn is_reading(&self) -> bool {
+        self.reading
+    }
+
+    pub(crate) fn start(&mut self) {
+        self.reading = true;
+    }
+
+    pub(crate) fn drop_rows(&mut self, dropped_rows: Option<usize>) {
+        let Some(last_read) = self.last_read else {
+            return;
+        };
+        match dropped_rows {
+            Some(dropped_rows) => {
+                self.last_read = last_read
+                    .row
+                    .checked_sub(dropped_rows)
+                    .map(|row| GridPosition { row, column: 0 });
+            }
+            None => self.forget(),
+        }
+    }
+
+    pub(crate) fn rewrap(&mut self, rewrap: Option<&Rewrap>) {
+        let Some(last_read) = self.last_read else {
+            return;
+        };
+        match rewrap.and_then(|rewrap| rewrap.position(last_read)) {
+            Some(position) => self.last_read = Some(position),
+            None => self.forget(),
+        }
+    }
+
+    /// Follows the row that becomes the first one after the terminal is cleared.
+    pub(crate) fn clear(&mut self, kept_row: usize) {
+        self.last_read = self
+            .last_read
+            .filter(|last_read| last_read.row >= kept_row)
+            .map(|_| GridPosition { row: 0, column: 0 });
+    }
+
+    fn forget(&mut self) {
+        self.last_read = None;
+        self.lost = true;
+    }
+
+    /// Reads the lines after the last line read, up to the line with the cursor, which may still be printed to.
+    /// With `include_cursor_line`, the line with the cursor is read too.
+    pub(crate) fn read<T>(
+        &mut self,
+        term: &Term<T>,
+        rows: &GridRows,
+        include_cursor_line: bool,
+    ) -> NewLines {
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return NewLines::default();
+        }
+
+        let grid = term.grid();
+        let last_column = term.last_column();
+        let total_rows = grid.total_lines();
+        let wraps = |row: usize| {
+            row + 1 < total_rows
+                && grid[row_line(term, row)][last_column]
+                    .flags
+                    .contains(Flags::WRAPLINE)
+        };
+        let line_end = |mut row: usize| {
+            while wraps(row) {
+                row += 1;
+            }
+            row + 1
+        };
+
+        let start = self
+            .last_read
+            .map_or(0, |last_read| line_end(last_read.row));
+        let cursor_row = rows.cursor_position(term).row;
+        let end = if include_cursor_line {
+            line_end(cursor_row)
+        } else {
+            let mut row = cursor_row;
+            while row > 0 && wraps(row - 1) {
+                row -= 1;
+            }
+            row
+        };
+
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        for row in start..end {
+            line.push_str(&row_to_string(&grid[row_line(term, row)]));
+            if !wraps(row) || row + 1 == end {
+                lines.push(line.trim_end().to_string());
+                line.clear();
+            }
+        }
+        if start < end {
+            self.last_read = Some(GridPosition {
+                row: end - 1,
+                column: 0,
+            });
+        }
+
+        NewLines {
+            lines,
+            reread: std::mem::take(&mut self.lost),
+        }
+    }
+}
@@ -0,0 +1,724 @@
+//! Handling of the escape sequences that Alacritty ignores: the shell integration marks and the inline images.
+//!
+//! These sequences are cut out of the PTY output before it gets parsed. To find out where a sequence is in the grid,
+//! the reader puts a cursor position request in its place, and stops reading until the terminal handles the response:
+//! at that point, the grid is exactly as it was when the sequence was printed.
+
+use std::{
+    io::{self, Read},
+    ops::Range,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use alacritty_terminal::{
+    event::{OnResize, WindowSize},
+    tty::{ChildEvent, EventedPty, EventedReadWrite, Pty},
+};
+use parking_lot::{Condvar, Mutex};
+use polling::{Event as PollEvent, PollMode, Poller};
+
+use crate::{
+    images::{self, ImageCommand},
+    kitty_graphics,
+    shell_integration::ShellMarkKind,
+};
+
+/// Put in place of the intercepted sequences, the terminal responds to it with the cursor position.
+const CURSOR_POSITION_REQUEST: &[u8] = b"\x1b[6n";
+const READ_CHUNK_SIZE: usize = 0x1_0000;
+/// How long a read waits for the paused sequence to be handled, before letting the event loop run.
+const PAUSED_READ_TIMEOUT: Duration = Duration::from_millis(100);
+/// Longer shell integration marks are not recognized, and are passed through to the terminal.
+const MAX_MARK_LEN: usize = 256;
+/// Longer image sequences are not recognized, and are passed through to the terminal.
+/// The output after an unterminated sequence is held back until this much of it is read.
+const MAX_IMAGE_SEQUENCE_LEN: usize = 4 << 20;
+/// Kitty graphics payloads are split into the chunks of at most 4096 bytes, so the sequences stay short.
+const MAX_KITTY_SEQUENCE_LEN: usize = 64 << 10;
+/// The most parameter bytes before the `q` that starts a Sixel sequence.
+const MAX_SIXEL_PARAMS_LEN: usize = 32;
+
+/// A sequence that was cut out of the PTY output, waiting for the terminal to handle it.
+pub(crate) enum InterceptedSequence {
+    ShellMark(ShellMarkKind),
+    Image(ImageCommand),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SequenceKind {
+    /// `OSC 133 ; <mark> ST`
+    ShellMark,
+    /// `OSC 1337 ; File=<arguments>:<base64 data> ST`
+    ItermImage,
+    /// `APC G <control data> ; <base64 payload> ST`
+    KittyGraphics,
+    /// `DCS <params> q <data> ST`
+    Sixel,
+}
+
+impl SequenceKind {
+    const ALL: [Self; 4] = [
+        Self::ShellMark,
+        Self::ItermImage,
+        Self::KittyGraphics,
+        Self::Sixel,
+    ];
+
+    fn prefix(self) -> &'static [u8] {
+        match self {
+            Self::ShellMark => b"\x1b]133;",
+            Self::ItermImage => b"\x1b]1337;File=",
+            Self::KittyGraphics => b"\x1b_G",
+            Self::Sixel => b"\x1bP",
+        }
+    }
+
+    fn max_len(self) -> usize {
+        match self {
+            Self::ShellMark => MAX_MARK_LEN,
+            Self::ItermImage | Self::Sixel => MAX_IMAGE_SEQUENCE_LEN,
+            Self::KittyGraphics => MAX_KITTY_SEQUENCE_LEN,
+        }
+    }
+
+    /// Whether BEL terminates the sequence, as well as ST (`ESC \`).
+    fn ends_with_bell(self) -> bool {
+        matches!(self, Self::ShellMark | Self::ItermImage)
+    }
+
+    /// Returns whether the sequence starts with the prefix of this kind, or `None` if it is too short to tell.
+    fn matches(self, sequence: &[u8]) -> Option<bool> {
+        let prefix = self.prefix();
+        if sequence.len() < prefix.len() {
+            return if prefix.starts_with(sequence) {
+                None
+            } else {
+                Some(false)
+            };
+        }
+        if !sequence.starts_with(prefix) {
+            return Some(false);
+        }
+        if self == Self::Sixel {
+            // Other device control strings are left to the terminal.
+            let params = &sequence[prefix.len()..];
+            return match params
+                .iter()
+                .take(MAX_SIXEL_PARAMS_LEN)
+                .position(|&byte| !(byte.is_ascii_digit() || byte == b';'))
+            {
+                Some(len) => Some(params[len] == b'q'),
+                None if params.len() < MAX_SIXEL_PARAMS_LEN => None,
+                None => Some(false),
+            };
+        }
+        Some(true)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum SequenceSearch {
+    NotFound,
+    /// The bytes end with what may be the start of a sequence.
+    Incomplete {
+        start: usize,
+        /// How far the sequence is known not to be terminated.
+        searched: usize,
+    },
+    Found {
+        start: usize,
+        end: usize,
+        kind: SequenceKind,
+        /// The sequence without its prefix and terminator.
+        body: Range<usize>,
+    },
+}
+
+/// Finds the first sequence to intercept in the bytes.
+/// The sequence at the start of the bytes is known not to be terminated before `searched`.
+fn find_sequence(bytes: &[u8], searched: usize) -> SequenceSearch {
+    let mut offset = 0;
+    while let Some(ix) = bytes[offset..].iter().position(|&byte| byte == 0x1b) {
+        let start = offset + ix;
+        let searched = if start == 0 { searched } else { 0 };
+        match match_sequence(&bytes[start..], searched) {
+            SequenceSearch::NotFound => {}
+            SequenceSearch::Incomplete { searched, .. } => {
+                return SequenceSearch::Incomplete { start, searched };
+            }
+            SequenceSearch::Found {
+                end, kind, body, ..
+            } => {
+                return SequenceSearch::Found {
+                    start,
+                    end: start + end,
+                    kind,
+                    body: start + body.start..start + body.end,
+                };
+            }
+        }
+        offset = start + 1;
+    }
+    SequenceSearch::NotFound
+}
+
+fn match_sequence(sequence: &[u8], searched: usize) -> SequenceSearch {
+    for kind in SequenceKind::ALL {
+        match kind.matches(sequence) {
+            None => return SequenceSearch::Incomplete { start: 0, searched },
+            Some(false) => continue,
+            Some(true) => {}
+        }
+
+        let body_start = kind.prefix().len();
+        let search_start = searched.max(body_start);
+        let search_end = sequence.len().min(kind.max_len());
+        let terminator = sequence[search_start..search_end]
+            .iter()
+            .position(|&byte| byte == 0x1b || (byte == 0x07 && kind.ends_with_bell()))
+            .map(|ix| search_start + ix);
+        return match terminator {
+            Some(ix) if sequence[ix] == 0x07 => SequenceSearch::Found {
+                start: 0,
+                end: ix + 1,
+                kind,
+                body: body_start..ix,
+            },
+            Some(ix) if ix + 1 == sequence.len() => SequenceSearch::Incomplete {
+                start: 0,
+                searched: ix,
+            },
+            Some(ix) if sequence[ix + 1] == b'\\' => SequenceSearch::Found {
+                start: 0,
+                end: ix + 2,
+                kind,
+                body: body_start..ix,
+            },
+            None if sequence.len() < kind.max_len() => SequenceSearch::Incomplete {
+                start: 0,
+                searched: sequence.len(),
+            },
+            // Not a valid sequence, leave it to the terminal.
+            _ => SequenceSearch::NotFound,
+        };
+    }
+    SequenceSearch::NotFound
+}
+
+fn intercept(
+    kind: SequenceKind,
+    body: &[u8],
+    kitty_transmissions: &mut kitty_graphics::Transmissions,
+) -> Option<InterceptedSequence> {
+    match kind {
+        SequenceKind::ShellMark => ShellMarkKind::parse(body).map(InterceptedSequence::ShellMark),
+        SequenceKind::ItermImage => images::parse_iterm_image(body).map(InterceptedSequence::Image),
+        SequenceKind::KittyGraphics => kitty_transmissions
+            .receive(body)
+            .map(InterceptedSequence::Image),
+        SequenceKind::Sixel => images::parse_sixel_image(body).map(InterceptedSequence::Image),
+    }
+}
+
+/// State shared between the PTY reader and the [`Terminal`](crate::Terminal).
+pub(crate) struct InterceptorSync {
+    /// The sequence that the reader waits to be handled before reading further.
+    paused: Mutex<Option<InterceptedSequence>>,
+    resumed: Condvar,
+    /// How many [`CURSOR_POSITION_REQUEST`]s the reader put in the output that the terminal has not responded to yet.
+    /// The program may request the cursor position too, and the responses to these have to reach it.
+    pending_reports: AtomicUsize,
+    waker: ReadWaker,
+}
+
+impl InterceptorSync {
+    pub(crate) fn new() -> io::Result<Self> {
+        Ok(Self {
+            paused: Mutex::new(None),
+            resumed: Condvar::new(),
+            pending_reports: AtomicUsize::new(0),
+            waker: ReadWaker::new()?,
+        })
+    }
+
+    fn pause(&self, sequence: InterceptedSequence) {
+        *self.paused.lock() = Some(sequence);
+        self.pending_reports.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Returns whether the PTY write is a response to a [`CURSOR_POSITION_REQUEST`] put in the output by the reader,
+    /// counting it as received. Any other writes are for the program.
+    pub(crate) fn take_cursor_position_report(&self, text: &str) -> bool {
+        is_cursor_position_report(text)
+            && self
+                .pending_reports
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |pending| {
+                    pending.checked_sub(1)
+                })
+                .is_ok()
+    }
+
+    /// Waits for the paused sequence to be handled, returns whether it was.
+    fn wait_until_resumed(&self, timeout: Duration) -> bool {
+        let mut paused = self.paused.lock();
+        if paused.is_some() {
+            self.resumed.wait_for(&mut paused, timeout);
+        }
+        paused.is_none()
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused.lock().is_some()
+    }
+
+    /// Lets the reader continue, returns the sequence it was paused on.
+    pub(crate) fn resume(&self) -> Option<InterceptedSequence> {
+        let sequence = self.paused.lock().take()?;
+        self.resumed.notify_all();
+        // The rest of the output may already be read from the PTY, make sure that the event loop reads it from us.
+        self.waker.wake();
+        Some(sequence)
+    }
+}
+
+/// Cuts the intercepted sequences out of the output, pausing after each of them.
+#[derive(Default)]
+struct SequenceReader {
+    pending: Vec<u8>,
+    /// How far the incomplete sequence at the start of `pending` is known not to be terminated.
+    searched: usize,
+    kitty_transmissions: kitty_graphics::Transmissions,
+    /// Whether the event loop may be holding the terminal lock while reading: it takes the lock
+    /// after the first successful read, and releases it after a read fails.
+    terminal_may_be_locked: bool,
+}
+
+impl SequenceReader {
+    fn read(
+        &mut self,
+        source: &mut impl Read,
+        buf: &mut [u8],
+        sync: &InterceptorSync,
+    ) -> io::Result<usize> {
+        let result = self.read_uninterrupted(source, buf, sync);
+        self.terminal_may_be_locked = matches!(result, Ok(len) if len > 0);
+        result
+    }
+
+    fn read_uninterrupted(
+        &mut self,
+        source: &mut impl Read,
+        buf: &mut [u8],
+        sync: &InterceptorSync,
+    ) -> io::Result<usize> {
+        // The terminal needs to be unlocked to handle the sequence, so only wait when it certainly is.
+        if self.terminal_may_be_locked && sync.is_paused()
+            || !sync.wait_until_resumed(PAUSED_READ_TIMEOUT)
+        {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+
+        let mut at_end = false;
+        loop {
+            match find_sequence(&self.pending, self.searched) {
+                SequenceSearch::Found {
+                    start: 0,
+                    end,
+                    kind,
+                    body,
+                } => {
+                    let sequence =
+                        intercept(kind, &self.pending[body], &mut self.kitty_transmissions);
+                    self.pending.drain(..end);
+                    self.searched = 0;
+                    let request_len = CURSOR_POSITION_REQUEST.len();
+                    // Sequences that need no handling are dropped.
+                    if let Some(sequence) = sequence.filter(|_| buf.len() >= request_len) {
+                        buf[..request_len].copy_from_slice(CURSOR_POSITION_REQUEST);
+                        sync.pause(sequence);
+                        return Ok(request_len);
+                    }
+                    continue;
+                }
+                SequenceSearch::Found { start, .. } | SequenceSearch::Incomplete { start, .. }
+                    if start > 0 =>
+                {
+                    return Ok(self.take_pending(start, buf));
+                }
+                // An incomplete sequence has no effect on the terminal, so it is held back until the rest of it is read.
+                SequenceSearch::Incomplete { searched, .. } if !at_end => self.searched = searched,
+                _ if !self.pending.is_empty() => {
+                    return Ok(self.take_pending(self.pending.len(), buf));
+                }
+                _ => {}
+            }
+
+            let len = self.pending.len();
+            self.pending.resize(len + READ_CHUNK_SIZE, 0);
+            let result = source.read(&mut self.pending[len..]);
+            self.pending
+                .truncate(len + result.as_ref().map_or(0, |read| *read));
+            match result {
+                Ok(0) if len == 0 => return Ok(0),
+                Ok(0) => at_end = true,
+                Ok(_) => {}
+                Err(error)
+                    if matches!(
+                        error.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted
+                    ) =>
+                {
+                    return Err(error);
+                }
+                // Pass the incomplete sequence through, the error is returned by the next read.
+                Err(_) if len > 0 => at_end = true,
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    fn take_pending(&mut self, len: usize, buf: &mut [u8]) -> usize {
+        let len = len.min(buf.len());
+        buf[..len].copy_from_slice(&self.pending[..len]);
+        self.pending.drain(..len);
+        self.searched = 0;
+        len
+    }
+}
+
+/// Makes the event loop read from the [`InterceptingPty`] when it has output pending.
+#[cfg(unix)]
+struct ReadWaker {
+    sender: std::os::unix::net::UnixStream,
+    receiver: std::os::unix::net::UnixStream,
+}
+
+#[cfg(unix)]
+impl ReadWaker {
+    fn new() -> io::Result<Self> {
+        let (sender, receiver) = std::os::unix::net::UnixStream::pair()?;
+        sender.set_nonblocking(true)?;
+        receiver.set_nonblocking(true)?;
+        Ok(Self { sender, receiver })
+    }
+
+    fn wake(&self) {
+        use std::io::Write as _;
+        // A full socket is readable already.
+        (&self.sender).write_all(&[0]).ok();
+    }
+
+    fn drain(&self) {
+        let mut buf = [0; 64];
+        while matches!((&self.receiver).read(&mut buf), Ok(read) if read > 0) {}
+    }
+
+    unsafe fn register(
+        &self,
+        poller: &Arc<Poller>,
+        interest: PollEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe { poller.add_with_mode(&self.receiver, PollEvent::readable(interest.key), mode) }
+    }
+
+    fn reregister(
+        &self,
+        poller: &Arc<Poller>,
+        interest: PollEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        poller.modify_with_mode(&self.receiver, PollEvent::readable(interest.key), mode)
+    }
+
+    fn deregister(&self, poller: &Arc<Poller>) -> io::Result<()> {
+        poller.delete(&self.receiver)
+    }
+}
+
+/// Makes the event loop read from the [`InterceptingPty`] when it has output pending.
+#[cfg(windows)]
+struct ReadWaker {
+    poller: Mutex<Option<(Arc<Poller>, PollEvent)>>,
+}
+
+#[cfg(windows)]
+impl ReadWaker {
+    fn new() -> io::Result<Self> {
+        Ok(Self {
+            poller: Mutex::new(None),
+        })
+    }
+
+    fn wake(&self) {
+        use polling::os::iocp::{CompletionPacket, PollerIocpExt as _};
+        if let Some((poller, interest)) = &*self.poller.lock() {
+            poller.post(CompletionPacket::new(*interest)).ok();
+        }
+    }
+
+    fn drain(&self) {}
+
+    unsafe fn register(
+        &self,
+        poller: &Arc<Poller>,
+        interest: PollEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.reregister(poller, interest, mode)
+    }
+
+    fn reregister(&self, poller: &Arc<Poller>, interest: PollEvent, _: PollMode) -> io::Result<()> {
+        *self.poller.lock() = Some((poller.clone(), PollEvent::readable(interest.key)));
+        Ok(())
+    }
+
+    fn deregister(&self, _: &Arc<Poller>) -> io::Result<()> {
+        self.poller.lock().take();
+        Ok(())
+    }
+}
+
+/// A [`Pty`] that cuts the intercepted sequences out of its output.
+pub(crate) struct InterceptingPty {
+    pty: Pty,
+    reader: SequenceReader,
+    sync: Arc<InterceptorSync>,
+}
+
+impl InterceptingPty {
+    pub(crate) fn new(pty: Pty, sync: Arc<InterceptorSync>) -> Self {
+        Self {
+            pty,
+            reader: SequenceReader::default(),
+            sync,
+        }
+    }
+}
+
+impl Read for InterceptingPty {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.sync.waker.drain();
+        self.reader.read(self.pty.reader(), buf, &self.sync)
+    }
+}
+
+impl EventedReadWrite for InterceptingPty {
+    type Reader = Self;
+    type Writer = <Pty as EventedReadWrite>::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poller: &Arc<Poller>,
+        interest: PollEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe {
+            self.pty.register(poller, interest, mode)?;
+            self.sync.waker.register(poller, interest, mode)
+        }
+    }
+
+    fn reregister(
+        &mut self,
+        poller: &Arc<Poller>,
+        interest: PollEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poller, interest, mode)?;
+        self.sync.waker.reregister(poller, interest, mode)
+    }
+
+    fn deregister(&mut self, poller: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poller)?;
+        self.sync.waker.deregister(poller)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl EventedPty for InterceptingPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl OnResize for InterceptingPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.pty.on_resize(window_size)
+    }
+}
+
+/// Whether the PTY write is a response to [`CURSOR_POSITION_REQUEST`].
+fn is_cursor_position_report(text: &str) -> bool {
+    text.strip_prefix("\x1b[")
+        .and_then(|report| report.strip_suffix('R'))
+        .and_then(|report| report.split_once(';'))
+        .is_some_and(|(line, column)| {
+            line.parse::<usize>().is_ok() && column.parse::<usize>().is_ok()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_find_sequence() {
+        assert_eq!(
+            find_sequence(b"plain output\x1b[0m", 0),
+            SequenceSearch::NotFound
+        );
+        assert_eq!(
+            find_sequence(b"$ \x1b]133;B\x07ls", 0),
+            SequenceSearch::Found {
+                start: 2,
+                end: 10,
+                kind: SequenceKind::ShellMark,
+                body: 8..9,
+            }
+        );
+        assert_eq!(
+            find_sequence(b"\x1b]133;D;127\x1b\\", 0),
+            SequenceSearch::Found {
+                start: 0,
+                end: 13,
+                kind: SequenceKind::ShellMark,
+                body: 6..11,
+            }
+        );
+        assert_eq!(
+            find_sequence(b"\x1b_Ga=T,f=100;iVBO\x1b\\", 0),
+            SequenceSearch::Found {
+                start: 0,
+                end: 19,
+                kind: SequenceKind::KittyGraphics,
+                body: 3..17,
+            }
+        );
+        assert_eq!(
+            find_sequence(b"\x1bP0;1q\"1;1;2;6#0!2~\x1b\\", 0),
+            SequenceSearch::Found {
+                start: 0,
+                end: 21,
+                kind: SequenceKind::Sixel,
+                body: 2..19,
+            }
+        );
+        assert_eq!(
+            find_sequence(b"\x1b]1337;File=inline=1:AAAA\x07", 0),
+            SequenceSearch::Found {
+                start: 0,
+                end: 26,
+                kind: SequenceKind::ItermImage,
+                body: 12..25,
+            }
+        );
+        assert_eq!(
+            find_sequence(b"output\x1b]13", 0),
+            SequenceSearch::Incomplete {
+                start: 6,
+                searched: 0
+            }
+        );
+        assert_eq!(
+            find_sequence(b"\x1b]133;D;1", 0),
+            SequenceSearch::Incomplete {
+                start: 0,
+                searched: 9
+            }
+        );
+        assert_eq!(
+            find_sequence(b"\x1b]133;D\x1b", 0),
+            SequenceSearch::Incomplete {
+                start: 0,
+                searched: 7
+            }
+        );
+        assert_eq!(
+            find_sequence(b"\x1b]0;title\x07", 0),
+            SequenceSearch::NotFound
+        );
+        // Unterminated sequences stop holding back the output once they get too long.
+        let mut unterminated = b"\x1b_Ga=T,f=100;".to_vec();
+        unterminated.resize(MAX_KITTY_SEQUENCE_LEN, b'A');
+        assert_eq!(find_sequence(&unterminated, 0), SequenceSearch::NotFound);
+        // Other device control strings are left to the terminal.
+        assert_eq!(
+            find_sequence(b"\x1bP$qm\x1b\\", 0),
+            SequenceSearch::NotFound
+        );
+        assert_eq!(
+            find_sequence(b"\x1bP1;2", 0),
+            SequenceSearch::Incomplete {
+                start: 0,
+                searched: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_cutting_sequences_out() {
+        let sync = InterceptorSync::new().unwrap();
+        let mut reader = SequenceReader::default();
+        let mut source = Cursor::new(
+            b"\x1b]133;D;0\x07\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C\x07file\r\n\x1b]133;P\x07\x1b]13"
+                .to_vec(),
+        );
+        let mut buf = [0; 1024];
+        let mut read = |reader: &mut SequenceReader| {
+            let len = reader.read(&mut source, &mut buf, &sync).unwrap();
+            String::from_utf8(buf[..len].to_vec()).unwrap()
+        };
+        let resume = || match sync.resume() {
+            Some(InterceptedSequence::ShellMark(kind)) => Some(kind),
+            _ => None,
+        };
+
+        assert_eq!(read(&mut reader), "\x1b[6n");
+        assert_eq!(
+            resume(),
+            Some(ShellMarkKind::CommandFinished { exit_code: Some(0) })
+        );
+        assert_eq!(read(&mut reader), "\x1b[6n");
+        assert_eq!(resume(), Some(ShellMarkKind::PromptStart));
+        assert_eq!(read(&mut reader), "$ ");
+        assert_eq!(read(&mut reader), "\x1b[6n");
+        assert!(sync.is_paused());
+        assert_eq!(resume(), Some(ShellMarkKind::InputStart));
+        assert_eq!(read(&mut reader), "ls\r\n");
+        assert_eq!(read(&mut reader), "\x1b[6n");
+        assert_eq!(resume(), Some(ShellMarkKind::OutputStart));
+        // Unknown marks are dropped, and with nothing else to read, the incomplete sequence is passed through.
+        assert_eq!(read(&mut reader), "file\r\n");
+        assert_eq!(read(&mut reader), "\x1b]13");
+        assert_eq!(read(&mut reader), "");
+    }
+
+    #[test]
+    fn test_cursor_position_report() {
+        assert!(is_cursor_position_report("\x1b[12;1R"));
+        assert!(!is_cursor_position_report("\x1b[?1;2c"));
+        assert!(!is_cursor_position_report("\x1b[;R"));
+
+        // Only the responses to the requests put in the output are taken, the rest are left for the program.
+        let sync = InterceptorSync::new().unwrap();
+        assert!(!sync.take_cursor_position_report("\x1b[3;1R"));
+        sync.pause(InterceptedSequence::ShellMark(ShellMarkKind::PromptStart));
+        assert!(!sync.take_cursor_position_report("\x1b[?1;2c"));
+        assert!(sync.take_cursor_position_report("\x1b[3;1R"));
+        assert!(!sync.take_cursor_position_report("\x1b[3;1R"));
+    }
+}
@@ -0,0 +1,176 @@
+//! Tracking of the prompts and commands that shells report with the "semantic prompt" (`OSC 133`) sequences.
+//!
+//! Alacritty ignores these sequences, so they are cut out of the PTY output by the [`crate::pty_interceptor`].
+
+use std::ops::RangeInclusive;
+
+use alacritty_terminal::{
+    Term,
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    term::TermMode,
+};
+
+use crate::grid_rows::{self, GridPosition, GridRows, Rewrap};
+
+/// The most marks that are kept per terminal, the oldest ones are dropped first.
+const MAX_MARKS: usize = 10_000;
+/// A mark that a shell prints to report where its prompt, the command input and the command output start.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShellMarkKind {
+    /// `OSC 133 ; A`, printed before the prompt.
+    PromptStart,
+    /// `OSC 133 ; B`, printed after the prompt, where the command input starts.
+    InputStart,
+    /// `OSC 133 ; C`, printed when the command is executed, before its output.
+    OutputStart,
+    /// `OSC 133 ; D [; <exit code>]`, printed when the command has finished.
+    CommandFinished { exit_code: Option<i32> },
+}
+
+impl ShellMarkKind {
+    pub(crate) fn parse(params: &[u8]) -> Option<Self> {
+        let mut params = params.split(|&byte| byte == b';');
+        match params.next()? {
+            b"A" => Some(Self::PromptStart),
+            b"B" => Some(Self::InputStart),
+            b"C" => Some(Self::OutputStart),
+            b"D" => Some(Self::CommandFinished {
+                exit_code: params
+                    .next()
+                    .and_then(|code| std::str::from_utf8(code).ok()?.parse().ok()),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// A command that the shell reported with the marks, in the grid coordinates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShellCommand {
+    /// Where the prompt starts.
+    pub start: AlacPoint,
+    /// Where the command input starts, after the prompt.
+    pub input_start: Option<AlacPoint>,
+    /// The command output, if the command has finished and printed anything.
+    pub output: Option<RangeInclusive<AlacPoint>>,
+    /// Where the command output ends, if the command has finished.
+    pub end: Option<AlacPoint>,
+    /// The exit code of the finished command, if the shell reported it.
+    pub exit_code: Option<i32>,
+}
+
+impl ShellCommand {
+    fn new(start: AlacPoint) -> Self {
+        Self {
+            start,
+            input_start: None,
+            output: None,
+            end: None,
+            exit_code: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.end.is_some()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ShellMark {
+    position: GridPosition,
+    kind: ShellMarkKind,
+}
+
+/// The marks recorded in the primary screen of a terminal.
+#[derive(Default)]
+pub(crate) struct ShellMarks {
+    marks: Vec<ShellMark>,
+}
+
+impl ShellMarks {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.marks.is_empty()
+    }
+
+    pub(crate) fn record(&mut self, kind: ShellMarkKind, position: GridPosition) {
+        self.marks.push(ShellMark { position, kind });
+        if self.marks.len() > MAX_MARKS {
+            self.marks.drain(..self.marks.len() - MAX_MARKS);
+        }
+    }
+
+    pub(crate) fn drop_rows(&mut self, dropped_rows: Option<usize>) {
+        grid_rows::drop_rows(&mut self.marks, dropped_rows, |mark| {
+            Some(&mut mark.position)
+        });
+    }
+
+    pub(crate) fn rewrap(&mut self, rewrap: Option<&Rewrap>) {
+        self.marks.retain_mut(|mark| {
+            match rewrap.and_then(|rewrap| rewrap.position(mark.position)) {
+                Some(position) => {
+                    mark.position = position;
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+
+    /// Keeps the marks in the row that becomes the first one after the terminal is cleared.
+    pub(crate) fn clear(&mut self, kept_row: usize) {
+        self.marks.retain(|mark| mark.position.row == kept_row);
+        for mark in &mut self.marks {
+            mark.position.row = 0;
+        }
+    }
+
+    pub(crate) fn commands<T>(&self, rows: &GridRows, term: &Term<T>) -> Vec<ShellCommand> {
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return Vec::new();
+        }
+
+        let last_column = term.last_column();
+        let mut commands = Vec::new();
+        let mut current: Option<ShellCommand> = None;
+        for mark in &self.marks {
+            let point = rows.point(mark.position);
+            let command = match mark.kind {
+                ShellMarkKind::PromptStart => {
+                    commands.extend(current.take());
+                    current.insert(ShellCommand::new(point))
+                }
+                _ => current.get_or_insert_with(|| ShellCommand::new(point)),
+            };
+            match mark.kind {
+                ShellMarkKind::PromptStart => {}
+                ShellMarkKind::InputStart => command.input_start = Some(point),
+                ShellMarkKind::OutputStart => command.output = Some(point..=point),
+                ShellMarkKind::CommandFinished { exit_code } => {
+                    // Shells that do not report the output start run the command after the input line.
+                    let output_start = command.output.as_ref().map_or_else(
+                        || {
+                            let input_line = command.input_start.unwrap_or(command.start).line;
+                            AlacPoint::new(Line(input_line.0 + 1), Column(0))
+                        },
+                        |output| *output.start(),
+                    );
+                    // The command finishes after its output is printed.
+                    let output_end = if point.column.0 > 0 {
+                        AlacPoint::new(point.line, point.column - 1)
+                    } else {
+                        AlacPoint::new(Line(point.line.0 - 1), last_column)
+                    };
+                    command.output =
+                        (output_start <= output_end).then_some(output_start..=output_end);
+                    command.end = Some(point);
+                    command.exit_code = exit_code;
+                    commands.extend(current.take());
+                }
+            }
+        }
+        commands.extend(current);
+        commands
+    }
+}
@@ -0,0 +1,294 @@
+//! Decoding of the Sixel images, printed as `DCS <params> q <data> ST`.
+
+use image::RgbaImage;
+
+use crate::images::{MAX_IMAGE_MEMORY, MAX_IMAGE_SIDE};
+
+/// The colors of the VT340, in percent of RGB, for the color registers that the image does not define.
+const DEFAULT_PALETTE: [[u32; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+const PALETTE_SIZE: usize = 256;
+
+/// The pixels drawn so far, growing as the image is drawn.
+#[derive(Default)]
+struct Canvas {
+    pixels: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+impl Canvas {
+    /// Makes sure that the canvas has at least the given size, returns whether it is within the limits.
+    fn reserve(&mut self, width: usize, height: usize) -> bool {
+        let max_side = MAX_IMAGE_SIDE as usize;
+        if width > max_side || height > max_side || width * height * 4 > MAX_IMAGE_MEMORY {
+            return false;
+        }
+        if width <= self.width && height <= self.height {
+            return true;
+        }
+
+        // Grow by doubling, so that drawing column by column does not copy the pixels every time.
+        let new_width = self.width.max(width.max(self.width * 2).min(max_side));
+        let new_height = self.height.max(height.max(self.height * 2).min(max_side));
+        let (new_width, new_height) = if new_width * new_height * 4 > MAX_IMAGE_MEMORY {
+            (self.width.max(width), self.height.max(height))
+        } else {
+            (new_width, new_height)
+        };
+        let mut pixels = vec![0; new_width * new_height * 4];
+        for row in 0..self.height {
+            let old_row = row * self.width * 4;
+            let new_row = row * new_width * 4;
+            pixels[new_row..new_row + self.width * 4]
+                .copy_from_slice(&self.pixels[old_row..old_row + self.width * 4]);
+        }
+        self.pixels = pixels;
+        self.width = new_width;
+        self.height = new_height;
+        true
+    }
+
+    fn set(&mut self, x: usize, y: usize, color: [u8; 4]) {
+        let ix = (y * self.width + x) * 4;
+        self.pixels[ix..ix + 4].copy_from_slice(&color);
+    }
+
+    fn into_image(self, width: usize, height: usize) -> Option<RgbaImage> {
+        let mut pixels = Vec::with_capacity(width * height * 4);
+        for row in 0..height {
+            let start = row * self.width * 4;
+            pixels.extend_from_slice(&self.pixels[start..start + width * 4]);
+        }
+        RgbaImage::from_raw(width as u32, height as u32, pixels)
+    }
+}
+
+/// Decodes the body of the sequence: the parameters and the `q`, followed by the sixel data.
+/// Returns `None` for the empty images and the ones that are too large.
+pub(crate) fn decode(body: &[u8]) -> Option<RgbaImage> {
+    let data_start = body.iter().position(|&byte| byte == b'q')? + 1;
+    let data = &body[data_start..];
+
+    let mut palette: Vec<[u8; 4]> = (0..PALETTE_SIZE)
+        .map(|ix| {
+            let [red, green, blue] = DEFAULT_PALETTE.get(ix).copied().unwrap_or_default();
+            [percent(red), percent(green), percent(blue), 255]
+        })
+        .collect();
+    let mut color = palette[0];
+    let mut canvas = Canvas::default();
+    let mut raster_size = None;
+    let (mut x, mut y) = (0, 0);
+    let (mut width, mut height) = (0, 0);
+
+    let mut ix = 0;
+    while ix < data.len() {
+        let byte = data[ix];
+        ix += 1;
+        match byte {
+            // Raster attributes: `" <pixel aspect numerator> ; <denominator> ; <width> ; <height>`
+            b'"' => {
+                let params = parse_params(data, &mut ix);
+                if let [_, _, raster_width, raster_height, ..] = params[..] {
+                    let size = (raster_width as usize, raster_height as usize);
+                    if !canvas.reserve(size.0, size.1) {
+                        return None;
+                    }
+                    raster_size = Some(size);
+                }
+            }
+            // Color introducer: `# <register>`, or `# <register> ; <space> ; <x> ; <y> ; <z>` to define the color.
+            b'#' => {
+                let params = parse_params(data, &mut ix);
+                let Some(&register) = params.first() else {
+                    continue;
+                };
+                let register = register as usize % PALETTE_SIZE;
+                match params[..] {
+                    [_, 1, hue, lightness, saturation] => {
+                        palette[register] = hls_to_rgb(hue, lightness, saturation)
+                    }
+                    [_, 2, red, green, blue] => {
+                        palette[register] = [percent(red), percent(green), percent(blue), 255]
+                    }
+                    _ => {}
+                }
+                color = palette[register];
+            }
+            // Graphics repeat introducer: `! <count> <sixel>`
+            b'!' => {
+                let count = parse_params(data, &mut ix).first().copied().unwrap_or(1);
+                if let Some(&sixel @ b'?'..=b'~') = data.get(ix) {
+                    ix += 1;
+                    let count = count.max(1) as usize;
+                    if !draw(&mut canvas, sixel - b'?', x, y, count, color) {
+                        return None;
+                    }
+                    x += count;
+                    width = width.max(x);
+                    height = height.max(sixel_bottom(sixel - b'?', y));
+                }
+            }
+            // Graphics carriage return.
+            b'$' => x = 0,
+            // Graphics new line.
+            b'-' => {
+                x = 0;
+                y += 6;
+            }
+            b'?'..=b'~' => {
+                if !draw(&mut canvas, byte - b'?', x, y, 1, color) {
+                    return None;
+                }
+                x += 1;
+                width = width.max(x);
+                height = height.max(sixel_bottom(byte - b'?', y));
+            }
+            _ => {}
+        }
+    }
+
+    let (width, height) = raster_size.map_or((width, height), |(raster_width, raster_height)| {
+        (width.max(raster_width), height.max(raster_height))
+    });
+    if width == 0 || height == 0 || !canvas.reserve(width, height) {
+        return None;
+    }
+    canvas.into_image(width, height)
+}
+
+/// Draws the sixel, a column of six pixels, `count` times.
+fn draw(canvas: &mut Canvas, sixel: u8, x: usize, y: usize, count: usize, color: [u8; 4]) -> bool {
+    if !canvas.reserve(x + count, y + 6) {
+        return false;
+    }
+    for bit in 0..6 {
+        if sixel & (1 << bit) != 0 {
+            for x in x..x + count {
+                canvas.set(x, y + bit, color);
+            }
+        }
+    }
+    true
+}
+
+/// The row below the lowest pixel of the sixel drawn at `y`, or 0 if it draws none.
+fn sixel_bottom(sixel: u8, y: usize) -> usize {
+    if sixel == 0 {
+        0
+    } else {
+        y + 8 - sixel.leading_zeros() as usize
+    }
+}
+
+/// Parses the numeric parameters separated by semicolons, that start at `ix`.
+fn parse_params(data: &[u8], ix: &mut usize) -> Vec<u32> {
+    let mut params = Vec::new();
+    let mut current = None::<u32>;
+    while let Some(&byte) = data.get(*ix) {
+        match byte {
+            b'0'..=b'9' => {
+                let digit = (byte - b'0') as u32;
+                current = Some(
+                    current
+                        .unwrap_or(0)
+                        .saturating_mul(10)
+                        .saturating_add(digit),
+                );
+            }
+            b';' => params.push(current.take().unwrap_or(0)),
+            _ => break,
+        }
+        *ix += 1;
+    }
+    params.extend(current);
+    params
+}
+
+fn percent(value: u32) -> u8 {
+    (value.min(100) as f32 * 2.55).round() as u8
+}
+
+/// Converts the color from the HLS that Sixel uses, where 0° is blue, to RGB.
+fn hls_to_rgb(hue: u32, lightness: u32, saturation: u32) -> [u8; 4] {
+    let hue = ((hue + 240) % 360) as f32 / 360.;
+    let lightness = lightness.min(100) as f32 / 100.;
+    let saturation = saturation.min(100) as f32 / 100.;
+    let q = if lightness < 0.5 {
+        lightness * (1. + saturation)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let p = 2. * lightness - q;
+    let channel = |t: f32| {
+        let t = t.rem_euclid(1.);
+        let value = if t < 1. / 6. {
+            p + (q - p) * 6. * t
+        } else if t < 1. / 2. {
+            q
+        } else if t < 2. / 3. {
+            p + (q - p) * (2. / 3. - t) * 6.
+        } else {
+            p
+        };
+        (value * 255.).round() as u8
+    };
+    [
+        channel(hue + 1. / 3.),
+        channel(hue),
+        channel(hue - 1. / 3.),
+        255,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decoding_sixel() {
+        // A 3x7 image: a red column, a green column of the top six pixels repeated twice, and a blue pixel below.
+        let image = decode(b"0;1q#1;2;100;0;0#2;2;0;100;0#3;2;0;0;100#1~#2!2~$-#3@").unwrap();
+        assert_eq!(image.dimensions(), (3, 7));
+        assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(0, 5).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(2, 3).0, [0, 255, 0, 255]);
+        assert_eq!(image.get_pixel(0, 6).0, [0, 0, 255, 255]);
+        // Pixels that are not drawn are transparent.
+        assert_eq!(image.get_pixel(1, 6).0, [0, 0, 0, 0]);
+
+        // Raster attributes set the size, even when fewer pixels are drawn.
+        let image = decode(b"q\"1;1;4;2#0A").unwrap();
+        assert_eq!(image.dimensions(), (4, 2));
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(0, 1).0, [0, 0, 0, 255]);
+
+        assert!(decode(b"q").is_none());
+        assert!(decode(b"q\"1;1;100000;1~").is_none());
+    }
+
+    #[test]
+    fn test_hls_colors() {
+        assert_eq!(hls_to_rgb(0, 50, 100), [0, 0, 255, 255]);
+        assert_eq!(hls_to_rgb(120, 50, 100), [255, 0, 0, 255]);
+        assert_eq!(hls_to_rgb(240, 50, 100), [0, 255, 0, 255]);
+        assert_eq!(hls_to_rgb(0, 100, 0), [255, 255, 255, 255]);
+    }
+}
@@ -2,7 +2,14 @@ pub mod mappings;
 
 pub use alacritty_terminal;
 
+mod grid_rows;
+mod images;
+mod kitty_graphics;
+mod output_reader;
 mod pty_info;
+mod pty_interceptor;
+mod shell_integration;
+mod sixel;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
@@ -38,10 +45,16 @@ use mappings::mouse::{
 
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
+use grid_rows::GridRows;
+use images::TerminalImages;
+pub use output_reader::NewLines;
+use output_reader::OutputReader;
 use pty_info::PtyProcessInfo;
+use pty_interceptor::{InterceptedSequence, InterceptingPty, InterceptorSync};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::ShellMarks;
 use smol::channel::{Receiver, Sender};
 use task::{HideStrategy, Shell, TaskId};
 use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
@@ -67,6 +80,9 @@ use gpui::{
 
 use crate::mappings::{colors::to_alac_rgb, keys::to_esc_str};
 
+pub use images::TerminalImage;
+pub use shell_integration::{ShellCommand, ShellMarkKind};
+
 actions!(
     terminal,
     [
@@ -81,6 +97,10 @@ actions!(
         ScrollPageDown,
         ScrollToTop,
         ScrollToBottom,
+        ScrollToPreviousPrompt,
+        ScrollToNextPrompt,
+        SelectCommandOutput,
+        CopyCommandOutput,
         ToggleViMode,
     ]
 );
@@ -464,6 +484,8 @@ impl TerminalBuilder {
         };
 
         let pty_info = PtyProcessInfo::new(&pty);
+        let interceptor_sync = Arc::new(InterceptorSync::new()?);
+        let pty = InterceptingPty::new(pty, interceptor_sync.clone());
 
         //And connect them together
         let event_loop = EventLoop::new(
@@ -502,6 +524,11 @@ impl TerminalBuilder {
             vi_mode_enabled: false,
             is_ssh_terminal,
             python_venv_directory,
+            grid_rows: GridRows::default(),
+            shell_marks: ShellMarks::default(),
+            images: TerminalImages::default(),
+            output_reader: OutputReader::default(),
+            interceptor_sync,
         };
 
         Ok(TerminalBuilder {
@@ -602,6 +629,10 @@ pub struct TerminalContent {
     pub last_hovered_word: Option<HoveredWord>,
     pub scrolled_to_top: bool,
     pub scrolled_to_bottom: bool,
+    /// Commands that the shell reported with the shell integration marks.
+    pub shell_commands: Vec<ShellCommand>,
+    /// Inline images that are visible in the viewport.
+    pub images: Vec<TerminalImage>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -628,6 +659,8 @@ impl Default for TerminalContent {
             last_hovered_word: None,
             scrolled_to_top: false,
             scrolled_to_bottom: false,
+            shell_commands: Vec::new(),
+            images: Vec::new(),
         }
     }
 }
@@ -662,6 +695,11 @@ pub struct Terminal {
     task: Option<TaskState>,
     vi_mode_enabled: bool,
     is_ssh_terminal: bool,
+    grid_rows: GridRows,
+    shell_marks: ShellMarks,
+    images: TerminalImages,
+    output_reader: OutputReader,
+    interceptor_sync: Arc<InterceptorSync>,
 }
 
 pub struct TaskState {
@@ -726,7 +764,14 @@ impl Terminal {
                     },
                 )
             }
-            AlacTermEvent::PtyWrite(out) => self.write_to_pty(out.clone()),
+            AlacTermEvent::PtyWrite(out) => {
+                // The cursor position reports requested in place of the intercepted sequences are not for the program.
+                if self.interceptor_sync.take_cursor_position_report(out) {
+                    self.handle_intercepted_sequence();
+                } else {
+                    self.write_to_pty(out.clone())
+                }
+            }
             AlacTermEvent::TextAreaSizeRequest(format) => {
                 self.write_to_pty(format(self.last_content.terminal_bounds.into()))
             }
@@ -743,6 +788,8 @@ impl Terminal {
                 //NOOP, Handled in render
             }
             AlacTermEvent::Wakeup => {
+                let term = self.term.clone();
+                self.sync_grid_rows(&term.lock_unfair());
                 cx.emit(Event::Wakeup);
 
                 if self.pty_info.has_changed() {
@@ -790,9 +837,23 @@ impl Terminal {
 
                 self.pty_tx.0.send(Msg::Resize(new_bounds.into())).ok();
 
-                term.resize(new_bounds);
+                self.sync_grid_rows(term);
+                let tracking = self.tracks_grid_rows();
+                let rewrap = self
+                    .grid_rows
+                    .resize(term, tracking, |term| term.resize(new_bounds));
+                self.shell_marks.rewrap(rewrap.as_ref());
+                self.images.rewrap(rewrap.as_ref());
+                self.output_reader.rewrap(rewrap.as_ref());
             }
             InternalEvent::Clear => {
+                self.sync_grid_rows(term);
+                if let Some(cursor_row) = self.grid_rows.clear(term) {
+                    self.shell_marks.clear(cursor_row);
+                    self.images.clear();
+                    self.output_reader.clear(cursor_row);
+                }
+
                 // Clear back buffer
                 term.clear_screen(ClearMode::Saved);
 
@@ -1219,6 +1280,140 @@ impl Terminal {
         self.last_content.scrolled_to_bottom
     }
 
+    /// Scrolls the closest prompt above the top of the viewport to the top.
+    pub fn scroll_to_previous_prompt(&mut self) {
+        let top = self.viewport_top();
+        if let Some(line) = self
+            .last_content
+            .shell_commands
+            .iter()
+            .rev()
+            .map(|command| command.start.line)
+            .find(|&line| line < top)
+        {
+            self.scroll_line_to_top(line);
+        }
+    }
+
+    /// Scrolls the closest prompt below the top of the viewport to the top.
+    pub fn scroll_to_next_prompt(&mut self) {
+        let top = self.viewport_top();
+        match self
+            .last_content
+            .shell_commands
+            .iter()
+            .map(|command| command.start.line)
+            .find(|&line| line > top)
+        {
+            Some(line) => self.scroll_line_to_top(line),
+            None => self.scroll_to_bottom(),
+        }
+    }
+
+    fn viewport_top(&self) -> Line {
+        Line(-(self.last_content.display_offset as i32))
+    }
+
+    fn scroll_line_to_top(&mut self, line: Line) {
+        let delta = self.viewport_top().0 - line.0;
+        self.events
+            .push_back(InternalEvent::Scroll(AlacScroll::Delta(delta)));
+    }
+
+    /// The command that the command output actions apply to:
+    /// the last finished command when scrolled to the bottom, otherwise the command at the top of the viewport.
+    fn focused_shell_command(&self) -> Option<&ShellCommand> {
+        let commands = &self.last_content.shell_commands;
+        if self.last_content.display_offset == 0 {
+            return commands.iter().rev().find(|command| command.is_finished());
+        }
+        let top = self.viewport_top();
+        commands
+            .iter()
+            .rev()
+            .find(|command| command.start.line <= top)
+            .or_else(|| commands.first())
+    }
+
+    pub fn select_command_output(&mut self) {
+        if let Some(output) = self
+            .focused_shell_command()
+            .and_then(|command| command.output.clone())
+        {
+            self.set_selection(Some((make_selection(&output), *output.end())));
+        }
+    }
+
+    pub fn copy_command_output(&self, cx: &App) {
+        if let Some(command) = self.focused_shell_command() {
+            let output = command.output.as_ref().map_or_else(String::new, |output| {
+                let term = self.term.lock_unfair();
+                term.bounds_to_string(*output.start(), *output.end())
+            });
+            cx.write_to_clipboard(ClipboardItem::new_string(output));
+        }
+    }
+
+    /// Returns the output of the last finished command, if the shell reports the commands with the shell integration marks.
+    pub fn last_command_output(&self) -> Option<String> {
+        let term = self.term.lock_unfair();
+        let command = self
+            .shell_marks
+            .commands(&self.grid_rows, &term)
+            .into_iter()
+            .rev()
+            .find(ShellCommand::is_finished)?;
+        Some(command.output.map_or_else(String::new, |output| {
+            term.bounds_to_string(*output.start(), *output.end())
+        }))
+    }
+
+    /// Handles the sequence that the PTY reader is paused on.
+    fn handle_intercepted_sequence(&mut self) {
+        // Keep the reader from changing the grid until the sequence is handled.
+        let term = self.term.clone();
+        let mut terminal = term.lock_unfair();
+        let Some(sequence) = self.interceptor_sync.resume() else {
+            return;
+        };
+        self.sync_grid_rows(&terminal);
+        match sequence {
+            InterceptedSequence::ShellMark(kind) => {
+                if !terminal.mode().contains(TermMode::ALT_SCREEN) {
+                    let position = self.grid_rows.cursor_position(&terminal);
+                    self.shell_marks.record(kind, position);
+                }
+            }
+            InterceptedSequence::Image(command) => {
+                let bounds = self.last_content.terminal_bounds;
+                let cell_size = Size {
+                    width: bounds.cell_width,
+                    height: bounds.line_height,
+                };
+                if let Some(response) =
+                    self.images
+                        .apply(command, &mut terminal, &self.grid_rows, cell_size)
+                {
+                    self.write_to_pty(response);
+                }
+            }
+        }
+    }
+
+    /// Whether anything is positioned in the rows of the primary screen.
+    fn tracks_grid_rows(&self) -> bool {
+        !self.shell_marks.is_empty() || self.images.tracks_rows() || self.output_reader.is_reading()
+    }
+
+    /// Follows the rows dropped from the scrollback, and the switches between the screens.
+    fn sync_grid_rows(&mut self, term: &Term<ZedListener>) {
+        let dropped_rows = self.grid_rows.sync(term, self.tracks_grid_rows());
+        self.shell_marks.drop_rows(dropped_rows);
+        self.images.drop_rows(dropped_rows);
+        self.output_reader.drop_rows(dropped_rows);
+        self.images.sync_screen(term);
+    }
+
     ///Resize the terminal and the PTY.
     pub fn set_size(&mut self, new_bounds: TerminalBounds) {
         if self.last_content.terminal_bounds != new_bounds {
@@ -1388,7 +1583,13 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, window, cx)
         }
 
+        self.sync_grid_rows(&terminal);
         self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content.shell_commands = self.shell_marks.commands(&self.grid_rows, &terminal);
+        self.last_content.images = self.images.visible(&self.grid_rows, &terminal);
+        for image in self.images.take_removed() {
+            cx.drop_image(image, Some(window));
+        }
     }
 
     fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
@@ -1418,6 +1619,8 @@ impl Terminal {
             last_hovered_word: last_content.last_hovered_word.clone(),
             scrolled_to_top: content.display_offset == term.history_size(),
             scrolled_to_bottom: content.display_offset == 0,
+            shell_commands: Vec::new(),
+            images: Vec::new(),
         }
     }
 
@@ -1453,6 +1656,17 @@ impl Terminal {
         lines
     }
 
+    /// Returns the lines printed since the last call, up to the line with the cursor, which may still be printed to.
+    /// With `include_cursor_line`, e.g. once the task has finished, the line with the cursor is returned too.
+    pub fn read_new_lines(&mut self, include_cursor_line: bool) -> NewLines {
+        let term = self.term.clone();
+        let terminal = term.lock_unfair();
+        self.output_reader.start();
+        self.sync_grid_rows(&terminal);
+        self.output_reader
+            .read(&terminal, &self.grid_rows, include_cursor_line)
+    }
+
     fn find_logical_line_start(&self, grid: &Grid<Cell>, current: i32, topmost: i32) -> i32 {
         let mut line_start = current;
         while line_start > topmost {
@@ -1,6 +1,6 @@
 use editor::{CursorLayout, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, DispatchPhase, Element,
+    AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, Corners, DispatchPhase, Element,
     ElementId, Entity, FocusHandle, Focusable, Font, FontStyle, FontWeight, GlobalElementId,
     HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity, IntoElement,
     LayoutId, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels, Point, ShapedLine,
@@ -12,7 +12,7 @@ use itertools::Itertools;
 use language::CursorShape;
 use settings::Settings;
 use terminal::{
-    IndexedCell, Terminal, TerminalBounds, TerminalContent,
+    IndexedCell, Terminal, TerminalBounds, TerminalContent, TerminalImage,
     alacritty_terminal::{
         grid::Dimensions,
         index::Point as AlacPoint,
@@ -40,6 +40,9 @@ pub struct LayoutState {
     cells: Vec<LayoutCell>,
     rects: Vec<LayoutRect>,
     relative_highlighted_ranges: Vec<(RangeInclusive<AlacPoint>, Hsla)>,
+    /// Displayed lines of the finished commands, colored by their exit status.
+    command_status_lines: Vec<(RangeInclusive<i32>, Hsla)>,
+    images: Vec<TerminalImage>,
     cursor: Option<CursorLayout>,
     background_color: Hsla,
     dimensions: TerminalBounds,
@@ -774,11 +777,29 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    shell_commands,
+                    images,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
 
+                let screen_lines = dimensions.num_lines() as i32;
+                let command_status_lines = shell_commands
+                    .iter()
+                    .filter_map(|command| {
+                        let color = match command.exit_code? {
+                            0 => theme.status().success,
+                            _ => theme.status().error,
+                        };
+                        let start = (command.start.line.0 + display_offset as i32).max(0);
+                        let end =
+                            (command.end?.line.0 + display_offset as i32).min(screen_lines - 1);
+                        (start <= end).then_some((start..=end, color))
+                    })
+                    .collect();
+                let images = images.clone();
+
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
                 for search_match in search_matches {
@@ -890,6 +911,8 @@ impl Element for TerminalElement {
                     dimensions,
                     rects,
                     relative_highlighted_ranges,
+                    command_status_lines,
+                    images,
                     mode,
                     display_offset,
                     hyperlink_tooltip,
@@ -971,6 +994,25 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, window);
                     }
 
+                    let line_height = layout.dimensions.line_height;
+                    for (lines, color) in &layout.command_status_lines {
+                        let status_bounds = Bounds::new(
+                            point(
+                                origin.x - layout.gutter * 0.75,
+                                origin.y + line_height * *lines.start() as f32,
+                            ),
+                            size(
+                                layout.gutter * 0.25,
+                                line_height * (lines.end() - lines.start() + 1) as f32,
+                            ),
+                        );
+                        window.paint_quad(fill(status_bounds, *color));
+                    }
+
+                    for image in layout.images.iter().filter(|image| image.below_text) {
+                        paint_image(image, origin, layout, window);
+                    }
+
                     for (relative_highlighted_range, color) in
                         layout.relative_highlighted_ranges.iter()
                     {
@@ -992,6 +1034,10 @@ impl Element for TerminalElement {
                         cell.paint(origin, &layout.dimensions, bounds, window, cx);
                     }
 
+                    for image in layout.images.iter().filter(|image| !image.below_text) {
+                        paint_image(image, origin, layout, window);
+                    }
+
                     if let Some(text_to_mark) = &marked_text_cloned {
                         if !text_to_mark.is_empty() {
                             if let Some(cursor_layout) = &original_cursor {
@@ -1192,6 +1238,35 @@ pub fn is_blank(cell: &IndexedCell) -> bool {
     true
 }
 
+fn paint_image(
+    image: &TerminalImage,
+    origin: Point<Pixels>,
+    layout: &LayoutState,
+    window: &mut Window,
+) {
+    let dimensions = &layout.dimensions;
+    let line = image.point.line.0 + layout.display_offset as i32;
+    let image_bounds = Bounds::new(
+        point(
+            origin.x + image.point.column.0 as f32 * dimensions.cell_width,
+            origin.y + line as f32 * dimensions.line_height,
+        ),
+        size(
+            image.size.width * dimensions.cell_width,
+            image.size.height * dimensions.line_height,
+        ),
+    );
+    window
+        .paint_image(
+            image_bounds,
+            Corners::default(),
+            image.image.clone(),
+            0,
+            false,
+        )
+        .log_err();
+}
+
 fn to_highlighted_range_lines(
     range: &RangeInclusive<AlacPoint>,
     layout: &LayoutState,
@@ -19,8 +19,9 @@ use project::{Entry, Metadata, Project, search::SearchQuery, terminals::Terminal
 use schemars::JsonSchema;
 use task::TaskId;
 use terminal::{
-    Clear, Copy, Event, HoveredWord, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp,
-    ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskState,
+    Clear, Copy, CopyCommandOutput, Event, HoveredWord, MaybeNavigationTarget, Paste,
+    ScrollLineDown, ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToNextPrompt,
+    ScrollToPreviousPrompt, ScrollToTop, SelectCommandOutput, ShowCharacterPalette, TaskState,
     TaskStatus, Terminal, TerminalBounds, ToggleViMode,
     alacritty_terminal::{
         index::Point,
@@ -301,6 +302,12 @@ impl TerminalView {
             .map_or(false, |terminal_panel| {
                 terminal_panel.read(cx).assistant_enabled()
             });
+        let has_commands = !self
+            .terminal
+            .read(cx)
+            .last_content
+            .shell_commands
+            .is_empty();
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal))
@@ -309,6 +316,11 @@ impl TerminalView {
                 .action("Paste", Box::new(Paste))
                 .action("Select All", Box::new(SelectAll))
                 .action("Clear", Box::new(Clear))
+                .when(has_commands, |menu| {
+                    menu.separator()
+                        .action("Select Command Output", Box::new(SelectCommandOutput))
+                        .action("Copy Command Output", Box::new(CopyCommandOutput))
+                })
                 .when(assistant_enabled, |menu| {
                     menu.separator()
                         .action("Inline Assist", Box::new(InlineAssist::default()))
@@ -530,6 +542,48 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(
+        &mut self,
+        _: &ScrollToNextPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
+    fn select_command_output(
+        &mut self,
+        _: &SelectCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.select_command_output());
+        cx.notify();
+    }
+
+    fn copy_command_output(
+        &mut self,
+        _: &CopyCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal.read(cx).copy_command_output(cx);
+    }
+
     fn toggle_vi_mode(&mut self, _: &ToggleViMode, _: &mut Window, cx: &mut Context<Self>) {
         self.terminal.update(cx, |term, _| term.toggle_vi_mode());
         cx.notify();
@@ -1424,6 +1478,10 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
+            .on_action(cx.listener(TerminalView::select_command_output))
+            .on_action(cx.listener(TerminalView::copy_command_output))
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
@@ -1769,6 +1827,7 @@ impl SearchableItem for TerminalView {
             replacement: false,
             selection: false,
             find_in_results: false,
+            syntax_filter: false,
         }
     }
 
@@ -2,7 +2,7 @@
 
 use std::sync::Arc;
 
-use gpui::{HighlightStyle, Hsla};
+use gpui::{HighlightStyle, Hsla, StrikethroughStyle, UnderlineStyle, px};
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub struct SyntaxTheme {
@@ -51,6 +51,57 @@ impl SyntaxTheme {
         Some(ix as u32)
     }
 
+    /// Returns the style for a semantic token reported by a language server.
+    ///
+    /// LSP token types are mapped onto the syntax capture names that themes already
+    /// style, so semantic tokens blend in with tree-sitter highlighting.
+    pub fn semantic_token_style<'a>(
+        &self,
+        token_type: &str,
+        modifiers: impl IntoIterator<Item = &'a str>,
+    ) -> Option<HighlightStyle> {
+        let modifiers = modifiers.into_iter().collect::<Vec<_>>();
+        let captures = semantic_token_captures(token_type);
+        let builtin_style = modifiers
+            .contains(&"defaultLibrary")
+            .then(|| {
+                captures
+                    .iter()
+                    .find_map(|capture| self.style_for_name(&format!("{capture}.builtin")))
+            })
+            .flatten();
+        let mut style = builtin_style.or_else(|| {
+            captures
+                .iter()
+                .find_map(|capture| self.style_for_name(capture))
+        });
+
+        for modifier in modifiers {
+            match modifier {
+                "deprecated" => {
+                    style.get_or_insert_default().strikethrough = Some(StrikethroughStyle {
+                        thickness: px(1.),
+                        ..Default::default()
+                    });
+                }
+                "mutable" => {
+                    style.get_or_insert_default().underline = Some(UnderlineStyle {
+                        thickness: px(1.),
+                        ..Default::default()
+                    });
+                }
+                _ => {}
+            }
+        }
+        style
+    }
+
+    fn style_for_name(&self, name: &str) -> Option<HighlightStyle> {
+        self.highlights
+            .iter()
+            .find_map(|(entry_name, style)| (entry_name == name).then_some(*style))
+    }
+
     /// Returns a new [`Arc<SyntaxTheme>`] with the given syntax styles merged in.
     pub fn merge(base: Arc<Self>, user_syntax_styles: Vec<(String, HighlightStyle)>) -> Arc<Self> {
         if user_syntax_styles.is_empty() {
@@ -87,6 +138,29 @@ impl SyntaxTheme {
     }
 }
 
+/// The syntax capture names to style a semantic token type with, in order of preference.
+fn semantic_token_captures(token_type: &str) -> &'static [&'static str] {
+    match token_type {
+        "namespace" => &["namespace", "module", "type"],
+        "type" | "class" | "enum" | "interface" | "struct" | "typeParameter" => &["type"],
+        "parameter" => &["variable.parameter", "variable"],
+        "variable" => &["variable"],
+        "property" => &["property"],
+        "enumMember" => &["variant", "constant"],
+        "function" | "method" => &["function"],
+        "macro" => &["function.special", "function"],
+        "keyword" | "modifier" => &["keyword"],
+        "comment" => &["comment"],
+        "string" => &["string"],
+        "number" => &["number"],
+        "regexp" => &["string.regex", "string"],
+        "operator" => &["operator"],
+        "decorator" => &["attribute"],
+        "lifetime" => &["lifetime"],
+        _ => &[],
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use gpui::FontStyle;
@@ -194,4 +268,41 @@ mod tests {
             ]))
         );
     }
+
+    #[test]
+    fn test_semantic_token_style() {
+        let syntax_theme = SyntaxTheme::new_test([
+            ("function", gpui::red()),
+            ("function.builtin", gpui::green()),
+            ("variable", gpui::blue()),
+        ]);
+
+        assert_eq!(
+            syntax_theme.semantic_token_style("method", []),
+            Some(HighlightStyle {
+                color: Some(gpui::red()),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            syntax_theme.semantic_token_style("function", ["defaultLibrary"]),
+            Some(HighlightStyle {
+                color: Some(gpui::green()),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            syntax_theme.semantic_token_style("parameter", ["mutable"]),
+            Some(HighlightStyle {
+                color: Some(gpui::blue()),
+                underline: Some(UnderlineStyle {
+                    thickness: px(1.),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        );
+        assert_eq!(syntax_theme.semantic_token_style("number", []), None);
+        assert_eq!(syntax_theme.semantic_token_style("unknown", []), None);
+    }
 }
@@ -13,7 +13,9 @@ use client::DevServerProjectId;
 use db::{define_connection, query, sqlez::connection::Connection, sqlez_macros::sql};
 use gpui::{Axis, Bounds, Task, WindowBounds, WindowId, point, size};
 use itertools::Itertools;
-use project::debugger::breakpoint_store::{BreakpointState, SourceBreakpoint};
+use project::debugger::breakpoint_store::{
+    BreakpointState, DataBreakpoint, FunctionBreakpoint, SerializedDebugState, SourceBreakpoint,
+};
 
 use language::{LanguageName, Toolchain};
 use project::WorktreeId;
@@ -542,6 +544,35 @@ define_connection! {
         ALTER TABLE breakpoints ADD COLUMN condition TEXT;
         ALTER TABLE breakpoints ADD COLUMN hit_condition TEXT;
     ),
+    sql!(
+        CREATE TABLE function_breakpoints (
+            workspace_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            condition TEXT,
+            hit_condition TEXT,
+            state INTEGER NOT NULL,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        );
+        CREATE TABLE data_breakpoints (
+            workspace_id INTEGER NOT NULL,
+            data_id TEXT NOT NULL,
+            description TEXT NOT NULL,
+            access_type TEXT,
+            enabled INTEGER NOT NULL,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        );
+        CREATE TABLE watch_expressions (
+            workspace_id INTEGER NOT NULL,
+            expression TEXT NOT NULL,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        );
+    ),
     ];
 }
 
@@ -630,6 +661,7 @@ impl WorkspaceDb {
             docks,
             session_id: None,
             breakpoints: self.breakpoints(workspace_id),
+            debug_state: self.debug_state(workspace_id),
             window_id,
         })
     }
@@ -684,6 +716,7 @@ impl WorkspaceDb {
             window_bounds,
             centered_layout: centered_layout.unwrap_or(false),
             breakpoints: self.breakpoints(workspace_id),
+            debug_state: self.debug_state(workspace_id),
             display,
             docks,
             session_id: None,
@@ -737,6 +770,85 @@ impl WorkspaceDb {
         }
     }
 
+    fn debug_state(&self, workspace_id: WorkspaceId) -> SerializedDebugState {
+        let function_breakpoints: Result<
+            Vec<(
+                String,
+                Option<String>,
+                Option<String>,
+                BreakpointStateWrapper,
+            )>,
+        > = self
+            .select_bound(sql! {
+                SELECT name, condition, hit_condition, state
+                FROM function_breakpoints
+                WHERE workspace_id = ?
+                ORDER BY rowid
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id));
+        let function_breakpoints = function_breakpoints
+            .context("Function breakpoints query failed")
+            .log_err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(
+                |(name, condition, hit_condition, state)| FunctionBreakpoint {
+                    name: name.into(),
+                    condition: condition.map(Into::into),
+                    hit_condition: hit_condition.map(Into::into),
+                    state: state.0.into_owned(),
+                },
+            )
+            .collect();
+
+        let data_breakpoints: Result<Vec<(String, String, Option<String>, bool)>> = self
+            .select_bound(sql! {
+                SELECT data_id, description, access_type, enabled
+                FROM data_breakpoints
+                WHERE workspace_id = ?
+                ORDER BY rowid
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id));
+        let data_breakpoints = data_breakpoints
+            .context("Data breakpoints query failed")
+            .log_err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(
+                |(data_id, description, access_type, is_enabled)| DataBreakpoint {
+                    data_id,
+                    description: description.into(),
+                    access_type: access_type.and_then(|access_type| {
+                        serde_json::from_value(serde_json::Value::String(access_type)).ok()
+                    }),
+                    is_enabled,
+                },
+            )
+            .collect();
+
+        let watch_expressions: Result<Vec<String>> = self
+            .select_bound(sql! {
+                SELECT expression
+                FROM watch_expressions
+                WHERE workspace_id = ?
+                ORDER BY rowid
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id));
+        let watch_expressions = watch_expressions
+            .context("Watch expressions query failed")
+            .log_err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        SerializedDebugState {
+            function_breakpoints,
+            data_breakpoints,
+            watch_expressions,
+        }
+    }
+
     /// Saves a workspace using the worktree roots. Will garbage collect any workspaces
     /// that used this workspace previously
     pub(crate) async fn save_workspace(&self, workspace: SerializedWorkspace) {
@@ -779,6 +891,52 @@ impl WorkspaceDb {
 
                 }
 
+                conn.exec_bound(sql!(
+                    DELETE FROM function_breakpoints WHERE workspace_id = ?1;
+                    DELETE FROM data_breakpoints WHERE workspace_id = ?1;
+                    DELETE FROM watch_expressions WHERE workspace_id = ?1;))?(workspace.id)
+                    .context("Clearing old debugger state")?;
+
+                for bp in workspace.debug_state.function_breakpoints {
+                    conn.exec_bound(sql!(
+                        INSERT INTO function_breakpoints (workspace_id, name, condition, hit_condition, state)
+                        VALUES (?1, ?2, ?3, ?4, ?5);))?
+                    ((
+                        workspace.id,
+                        bp.name.as_ref(),
+                        bp.condition.as_deref(),
+                        bp.hit_condition.as_deref(),
+                        BreakpointStateWrapper::from(bp.state),
+                    ))
+                    .context("Storing function breakpoint")?;
+                }
+
+                for bp in workspace.debug_state.data_breakpoints {
+                    conn.exec_bound(sql!(
+                        INSERT INTO data_breakpoints (workspace_id, data_id, description, access_type, enabled)
+                        VALUES (?1, ?2, ?3, ?4, ?5);))?
+                    ((
+                        workspace.id,
+                        bp.data_id.as_str(),
+                        bp.description.as_ref(),
+                        bp.access_type.and_then(|access_type| {
+                            serde_json::to_value(access_type)
+                                .ok()?
+                                .as_str()
+                                .map(ToOwned::to_owned)
+                        }),
+                        bp.is_enabled,
+                    ))
+                    .context("Storing data breakpoint")?;
+                }
+
+                for expression in workspace.debug_state.watch_expressions {
+                    conn.exec_bound(sql!(
+                        INSERT INTO watch_expressions (workspace_id, expression)
+                        VALUES (?1, ?2);))?
+                    ((workspace.id, expression.as_ref()))
+                    .context("Storing watch expression")?;
+                }
 
                 match workspace.location {
                     SerializedWorkspaceLocation::Local(local_paths, local_paths_order) => {
@@ -1571,6 +1729,7 @@ mod tests {
                 map
             },
             session_id: None,
+            debug_state: Default::default(),
             window_id: None,
         };
 
@@ -1649,6 +1808,69 @@ mod tests {
         assert_eq!(loaded_breakpoints[4].path, Arc::from(path));
     }
 
+    #[gpui::test]
+    async fn test_debug_state() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb::open_test_db("test_debug_state").await;
+        let id = db.next_id().await.unwrap();
+
+        let debug_state = SerializedDebugState {
+            function_breakpoints: vec![
+                FunctionBreakpoint {
+                    name: "main".into(),
+                    condition: None,
+                    hit_condition: None,
+                    state: BreakpointState::Enabled,
+                },
+                FunctionBreakpoint {
+                    name: "handle_request".into(),
+                    condition: Some("request.id == 2".into()),
+                    hit_condition: Some("3".into()),
+                    state: BreakpointState::Disabled,
+                },
+            ],
+            data_breakpoints: vec![
+                DataBreakpoint {
+                    data_id: "0x7ffd5c".into(),
+                    description: "counter".into(),
+                    access_type: Some(dap::DataBreakpointAccessType::Write),
+                    is_enabled: true,
+                },
+                DataBreakpoint {
+                    data_id: "0x7ffd60".into(),
+                    description: "total".into(),
+                    access_type: None,
+                    is_enabled: false,
+                },
+            ],
+            watch_expressions: vec!["counter * 2".into(), "total".into()],
+        };
+
+        let mut workspace = SerializedWorkspace {
+            id,
+            location: SerializedWorkspaceLocation::from_local_paths(["/tmp"]),
+            center_group: Default::default(),
+            window_bounds: Default::default(),
+            display: Default::default(),
+            docks: Default::default(),
+            centered_layout: false,
+            breakpoints: Default::default(),
+            debug_state: debug_state.clone(),
+            session_id: None,
+            window_id: None,
+        };
+
+        db.save_workspace(workspace.clone()).await;
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(loaded.debug_state, debug_state);
+
+        workspace.debug_state = SerializedDebugState::default();
+        db.save_workspace(workspace).await;
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(loaded.debug_state, SerializedDebugState::default());
+    }
+
     #[gpui::test]
     async fn test_remove_last_breakpoint() {
         env_logger::try_init().ok();
@@ -1690,6 +1912,7 @@ mod tests {
                 map
             },
             session_id: None,
+            debug_state: Default::default(),
             window_id: None,
         };
 
@@ -1722,6 +1945,7 @@ mod tests {
             centered_layout: false,
             breakpoints: collections::BTreeMap::default(),
             session_id: None,
+            debug_state: Default::default(),
             window_id: None,
         };
 
@@ -1816,6 +2040,7 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: None,
+            debug_state: Default::default(),
             window_id: None,
         };
 
@@ -1829,6 +2054,7 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: None,
+            debug_state: Default::default(),
             window_id: None,
         };
 
@@ -1937,6 +2163,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             session_id: None,
+            debug_state: Default::default(),
             window_id: Some(999),
         };
 
@@ -1972,6 +2199,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             session_id: None,
+            debug_state: Default::default(),
             window_id: Some(1),
         };
 
@@ -1985,6 +2213,7 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: None,
+            debug_state: Default::default(),
             window_id: Some(2),
         };
 
@@ -2028,6 +2257,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             session_id: None,
+            debug_state: Default::default(),
             window_id: Some(3),
         };
 
@@ -2065,6 +2295,7 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: Some("session-id-1".to_owned()),
+            debug_state: Default::default(),
             window_id: Some(10),
         };
 
@@ -2078,6 +2309,7 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: Some("session-id-1".to_owned()),
+            debug_state: Default::default(),
             window_id: Some(20),
         };
 
@@ -2091,6 +2323,7 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: Some("session-id-2".to_owned()),
+            debug_state: Default::default(),
             window_id: Some(30),
         };
 
@@ -2104,6 +2337,7 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: None,
+            debug_state: Default::default(),
             window_id: None,
         };
 
@@ -2122,6 +2356,7 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: Some("session-id-2".to_owned()),
+            debug_state: Default::default(),
             window_id: Some(50),
         };
 
@@ -2138,6 +2373,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             session_id: Some("session-id-3".to_owned()),
+            debug_state: Default::default(),
             window_id: Some(60),
         };
 
@@ -2194,6 +2430,7 @@ mod tests {
             breakpoints: Default::default(),
             centered_layout: false,
             session_id: None,
+            debug_state: Default::default(),
             window_id: None,
         }
     }
@@ -2240,6 +2477,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            debug_state: Default::default(),
             window_id: Some(window_id),
         })
         .collect::<Vec<_>>();
@@ -2333,6 +2571,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            debug_state: Default::default(),
             window_id: Some(window_id),
         })
         .collect::<Vec<_>>();
@@ -43,6 +43,8 @@ pub struct SearchOptions {
     pub replacement: bool,
     pub selection: bool,
     pub find_in_results: bool,
+    /// Specifies whether matches can be restricted to comments, strings or code.
+    pub syntax_filter: bool,
 }
 
 pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
@@ -56,6 +58,7 @@ pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
             replacement: true,
             selection: true,
             find_in_results: false,
+            syntax_filter: false,
         }
     }
 
@@ -1,14 +1,18 @@
 use std::process::ExitStatus;
 
 use anyhow::Result;
-use gpui::{AppContext, Context, Entity, Task};
+use futures::{
+    FutureExt as _,
+    future::{LocalBoxFuture, Shared},
+};
+use gpui::{AppContext, AsyncWindowContext, Context, Entity, Task, WeakEntity};
 use language::Buffer;
-use project::TaskSourceKind;
+use project::{TaskChain, TaskSourceKind};
 use remote::ConnectionState;
-use task::{DebugScenario, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
+use task::{DebugScenario, DependsOrder, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
 use ui::Window;
 
-use crate::Workspace;
+use crate::{Event, Workspace};
 
 impl Workspace {
     pub fn schedule_task(
@@ -54,6 +58,20 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Workspace>,
     ) {
+        if !resolved_task.unresolved_inputs().is_empty() {
+            cx.emit(Event::TaskInputsRequested(TaskInputsRequest {
+                task_source_kind,
+                resolved_task,
+                omit_history,
+            }));
+            return;
+        }
+
+        if !resolved_task.original_task().depends_on.is_empty() {
+            self.schedule_task_chain(task_source_kind, resolved_task, omit_history, window, cx);
+            return;
+        }
+
         let spawn_in_terminal = resolved_task.resolved.clone();
         if !omit_history {
             self.project().update(cx, |project, cx| {
@@ -86,6 +104,61 @@ impl Workspace {
         }
     }
 
+    fn schedule_task_chain(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        omit_history: bool,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(task_inventory) = self
+            .project()
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned()
+        else {
+            return;
+        };
+        let chain = match task_inventory
+            .read(cx)
+            .resolve_task_chain(task_source_kind.clone(), resolved_task.clone())
+        {
+            Ok(chain) => chain,
+            Err(e) => {
+                self.show_error(&e, cx);
+                return;
+            }
+        };
+        if !omit_history {
+            task_inventory.update(cx, |inventory, _| {
+                inventory.task_scheduled(task_source_kind, resolved_task);
+            });
+        }
+
+        let workspace = cx.weak_entity();
+        let running_chain = cx.new(|cx| RunningTaskChain::new(chain, workspace, window, cx));
+        // The workspace keeps the chain running until it finishes or is cancelled,
+        // whatever shows its progress only observes it.
+        let subscription = cx.observe(&running_chain, |workspace, running_chain, cx| {
+            if running_chain.read(cx).is_finished() {
+                workspace
+                    .running_task_chains
+                    .retain(|(chain, _)| chain != &running_chain);
+            }
+        });
+        self.running_task_chains
+            .push((running_chain.clone(), subscription));
+        cx.emit(Event::TaskChainStarted(running_chain));
+    }
+
+    /// Task chains started in this workspace that have not finished yet.
+    pub fn running_task_chains(&self) -> impl Iterator<Item = &Entity<RunningTaskChain>> {
+        self.running_task_chains.iter().map(|(chain, _)| chain)
+    }
+
     pub fn start_debug_session(
         &mut self,
         scenario: DebugScenario,
@@ -112,3 +185,222 @@ impl Workspace {
         }
     }
 }
+
+/// A task that needs the values of its `${input:<id>}` references to be provided before it is scheduled.
+#[derive(Debug, Clone)]
+pub struct TaskInputsRequest {
+    pub task_source_kind: TaskSourceKind,
+    pub resolved_task: ResolvedTask,
+    pub omit_history: bool,
+}
+
+/// Status of a task in a [`RunningTaskChain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskChainStepStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    /// The task did not run, because a task it depends on failed.
+    Skipped,
+    Cancelled,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskChainStep {
+    pub label: String,
+    pub status: TaskChainStepStatus,
+}
+
+/// A task with dependencies being run, each task in a terminal after the tasks it depends on
+/// finished successfully.
+pub struct RunningTaskChain {
+    label: String,
+    steps: Vec<TaskChainStep>,
+    finished: bool,
+    _run: Task<()>,
+}
+
+impl RunningTaskChain {
+    fn new(
+        chain: TaskChain,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let label = chain.task().task.display_label().to_string();
+        let steps = chain
+            .tasks
+            .iter()
+            .map(|chained_task| TaskChainStep {
+                label: chained_task.task.display_label().to_string(),
+                status: TaskChainStepStatus::Pending,
+            })
+            .collect();
+        let _run = cx.spawn_in(window, async move |this, cx| {
+            run_task_chain(chain, this.clone(), workspace, cx.clone()).await;
+            this.update(cx, |this, cx| this.finish(TaskChainStepStatus::Skipped, cx))
+                .ok();
+        });
+
+        Self {
+            label,
+            steps,
+            finished: false,
+            _run,
+        }
+    }
+
+    /// The label of the task the chain was started for.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// All tasks of the chain, each task after the tasks it depends on.
+    pub fn steps(&self) -> &[TaskChainStep] {
+        &self.steps
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Stops the chain from spawning any more tasks.
+    /// Tasks that are already running in their terminals are left running.
+    pub fn cancel(&mut self, cx: &mut Context<Self>) {
+        if self.finished {
+            return;
+        }
+        self._run = Task::ready(());
+        self.finish(TaskChainStepStatus::Cancelled, cx);
+    }
+
+    fn finish(&mut self, unfinished_status: TaskChainStepStatus, cx: &mut Context<Self>) {
+        self.finished = true;
+        for step in &mut self.steps {
+            if matches!(
+                step.status,
+                TaskChainStepStatus::Pending | TaskChainStepStatus::Running
+            ) {
+                step.status = unfinished_status;
+            }
+        }
+        cx.notify();
+    }
+
+    fn set_step_status(
+        &mut self,
+        step_ix: usize,
+        status: TaskChainStepStatus,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(step) = self.steps.get_mut(step_ix) {
+            step.status = status;
+            cx.notify();
+        }
+    }
+}
+
+/// Builds a future running the chain, with steps numbered in the same order as [`TaskChain::tasks`].
+/// Resolves to whether the chain's task ran successfully.
+fn run_task_chain(
+    chain: TaskChain,
+    this: WeakEntity<RunningTaskChain>,
+    workspace: WeakEntity<Workspace>,
+    cx: AsyncWindowContext,
+) -> LocalBoxFuture<'static, bool> {
+    // Tasks that several tasks depend on run once, and all of these tasks wait for the same run.
+    let mut runs = Vec::<Shared<LocalBoxFuture<'static, bool>>>::with_capacity(chain.tasks.len());
+    for (task_ix, chained_task) in chain.tasks.into_iter().enumerate() {
+        let dependencies = chained_task
+            .dependencies
+            .iter()
+            .map(|&dependency_ix| runs[dependency_ix].clone())
+            .collect();
+        let run = run_chained_task(
+            task_ix,
+            chained_task.task,
+            dependencies,
+            this.clone(),
+            workspace.clone(),
+            cx.clone(),
+        );
+        runs.push(run.shared());
+    }
+    match runs.pop() {
+        Some(run) => run.boxed_local(),
+        None => futures::future::ready(false).boxed_local(),
+    }
+}
+
+fn run_chained_task(
+    task_ix: usize,
+    task: ResolvedTask,
+    dependencies: Vec<Shared<LocalBoxFuture<'static, bool>>>,
+    this: WeakEntity<RunningTaskChain>,
+    workspace: WeakEntity<Workspace>,
+    mut cx: AsyncWindowContext,
+) -> LocalBoxFuture<'static, bool> {
+    let depends_order = task.original_task().depends_order;
+    let is_aggregator = task.is_aggregator();
+    let spawn_in_terminal = task.resolved;
+
+    async move {
+        let dependencies_succeeded = match depends_order {
+            DependsOrder::Sequence => {
+                let mut succeeded = true;
+                for dependency in dependencies {
+                    if !dependency.await {
+                        succeeded = false;
+                        break;
+                    }
+                }
+                succeeded
+            }
+            DependsOrder::Parallel => futures::future::join_all(dependencies)
+                .await
+                .into_iter()
+                .all(|succeeded| succeeded),
+        };
+        if !dependencies_succeeded {
+            return false;
+        }
+
+        if is_aggregator {
+            this.update(&mut cx, |this, cx| {
+                this.set_step_status(task_ix, TaskChainStepStatus::Succeeded, cx)
+            })
+            .ok();
+            return true;
+        }
+
+        this.update(&mut cx, |this, cx| {
+            this.set_step_status(task_ix, TaskChainStepStatus::Running, cx)
+        })
+        .ok();
+        let Ok(task_status) = workspace.update_in(&mut cx, |workspace, window, cx| {
+            workspace.spawn_in_terminal(spawn_in_terminal, window, cx)
+        }) else {
+            return false;
+        };
+        let succeeded = match task_status.await {
+            Some(Ok(status)) => status.success(),
+            Some(Err(e)) => {
+                log::error!("Task spawn failed: {e}");
+                false
+            }
+            None => false,
+        };
+        let status = if succeeded {
+            TaskChainStepStatus::Succeeded
+        } else {
+            TaskChainStepStatus::Failed
+        };
+        this.update(&mut cx, |this, cx| {
+            this.set_step_status(task_ix, status, cx)
+        })
+        .ok();
+        succeeded
+    }
+    .boxed_local()
+}
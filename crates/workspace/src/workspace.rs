@@ -871,6 +871,8 @@ pub enum Event {
     ZoomChanged,
     ModalOpened,
     ClearActivityIndicator,
+    TaskChainStarted(Entity<tasks::RunningTaskChain>),
+    TaskInputsRequested(tasks::TaskInputsRequest),
 }
 
 #[derive(Debug)]
@@ -948,6 +950,7 @@ pub struct Workspace {
     on_prompt_for_open_path: Option<PromptForOpenPath>,
     terminal_provider: Option<Box<dyn TerminalProvider>>,
     debugger_provider: Option<Box<dyn DebuggerProvider>>,
+    running_task_chains: Vec<(Entity<tasks::RunningTaskChain>, Subscription)>,
     serializable_items_tx: UnboundedSender<Box<dyn SerializableItemHandle>>,
     serialized_ssh_project: Option<SerializedSshProject>,
     _items_serializer: Task<Result<()>>,
@@ -1074,7 +1077,10 @@ impl Workspace {
             window,
             |workspace, _, event, window, cx| match event {
                 BreakpointStoreEvent::BreakpointsUpdated(_, _)
-                | BreakpointStoreEvent::BreakpointsCleared(_) => {
+                | BreakpointStoreEvent::BreakpointsCleared(_)
+                | BreakpointStoreEvent::FunctionBreakpointsUpdated
+                | BreakpointStoreEvent::DataBreakpointsUpdated
+                | BreakpointStoreEvent::WatchExpressionsUpdated => {
                     workspace.serialize_workspace(window, cx);
                 }
                 BreakpointStoreEvent::SetDebugLine | BreakpointStoreEvent::ClearDebugLines => {}
@@ -1280,6 +1286,7 @@ impl Workspace {
             on_prompt_for_open_path: None,
             terminal_provider: None,
             debugger_provider: None,
+            running_task_chains: Vec::new(),
             serializable_items_tx,
             _items_serializer,
             session_id: Some(session_id),
@@ -4992,11 +4999,12 @@ impl Workspace {
         }
 
         if let Some(location) = self.serialize_workspace_location(cx) {
-            let breakpoints = self.project.update(cx, |project, cx| {
-                project
-                    .breakpoint_store()
-                    .read(cx)
-                    .all_source_breakpoints(cx)
+            let (breakpoints, debug_state) = self.project.update(cx, |project, cx| {
+                let breakpoint_store = project.breakpoint_store().read(cx);
+                (
+                    breakpoint_store.all_source_breakpoints(cx),
+                    breakpoint_store.serialized_debug_state(),
+                )
             });
 
             let center_group = build_serialized_pane_group(&self.center.root, window, cx);
@@ -5012,6 +5020,7 @@ impl Workspace {
                 centered_layout: self.centered_layout,
                 session_id: self.session_id.clone(),
                 breakpoints,
+                debug_state,
                 window_id: Some(window.window_handle().window_id().as_u64()),
             };
 
@@ -5183,6 +5192,8 @@ impl Workspace {
                     project
                         .breakpoint_store()
                         .update(cx, |breakpoint_store, cx| {
+                            breakpoint_store
+                                .with_serialized_debug_state(serialized_workspace.debug_state, cx);
                             breakpoint_store
                                 .with_serialized_breakpoints(serialized_workspace.breakpoints, cx)
                         })
@@ -7,7 +7,7 @@ use ::ignore::gitignore::{Gitignore, GitignoreBuilder};
 use anyhow::{Context as _, Result, anyhow};
 use clock::ReplicaId;
 use collections::{HashMap, HashSet, VecDeque};
-use fs::{Fs, MTime, PathEvent, RemoveOptions, Watcher, copy_recursive, read_dir_items};
+use fs::{Encoding, Fs, MTime, PathEvent, RemoveOptions, Watcher, copy_recursive, read_dir_items};
 use futures::{
     FutureExt as _, Stream, StreamExt,
     channel::{
@@ -52,6 +52,7 @@ use std::{
     ffi::OsStr,
     fmt,
     future::Future,
+    io::{self, Read as _},
     mem::{self},
     ops::{Deref, DerefMut},
     path::{Component, Path, PathBuf},
@@ -68,9 +69,14 @@ use util::{
     ResultExt,
     paths::{PathMatcher, SanitizedPath, home_dir},
 };
-pub use worktree_settings::WorktreeSettings;
+pub use worktree_settings::{LargeFileSettings, WorktreeSettings};
 
 pub const FS_WATCH_LATENCY: Duration = Duration::from_millis(100);
+/// How much of a large file is read from disk at once.
+const LARGE_FILE_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+/// Files from this size are refused: even in large file mode, the whole text is kept in memory,
+/// and opening a file that does not fit in it freezes the system, see #27283.
+const FILE_SIZE_MAX: u64 = 6 * 1024 * 1024 * 1024;
 
 /// A set of local or remote files that are being opened as part of a project.
 /// Responsible for tracking related FS (for local)/collab (for remote) events and corresponding updates.
@@ -99,7 +105,19 @@ pub enum CreatedEntry {
 
 pub struct LoadedFile {
     pub file: Arc<File>,
-    pub text: String,
+    /// The contents, with the line endings normalized.
+    pub text: Rope,
+    pub line_ending: LineEnding,
+    pub encoding: Encoding,
+    /// Whether the file is larger than the `large_files` threshold, and was streamed from disk in chunks.
+    pub is_large: bool,
+}
+
+/// How much of a large file has been read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LoadProgress {
+    pub loaded_bytes: u64,
+    pub total_bytes: u64,
 }
 
 pub struct LoadedBinaryFile {
@@ -478,6 +496,11 @@ pub enum Event {
     UpdatedEntries(UpdatedEntriesSet),
     UpdatedGitRepositories(UpdatedGitRepositoriesSet),
     DeletedEntry(ProjectEntryId),
+    /// A large file is being loaded, the progress is `None` once it is loaded or failed to load.
+    LargeFileLoadProgress {
+        path: Arc<Path>,
+        progress: Option<LoadProgress>,
+    },
 }
 
 const EMPTY_PATH: &str = "";
@@ -836,10 +859,11 @@ impl Worktree {
         path: &Path,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &Context<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         match self {
-            Worktree::Local(this) => this.write_file(path, text, line_ending, cx),
+            Worktree::Local(this) => this.write_file(path, text, line_ending, encoding, cx),
             Worktree::Remote(_) => {
                 Task::ready(Err(anyhow!("remote worktree can't yet write files")))
             }
@@ -1517,24 +1541,55 @@ impl LocalWorktree {
         let fs = self.fs.clone();
         let entry = self.refresh_entry(path.clone(), None, cx);
         let is_private = self.is_path_private(path.as_ref());
+        let large_files = self.settings.large_files;
 
-        cx.spawn(async move |this, _cx| {
+        cx.spawn(async move |this, cx| {
             let abs_path = abs_path?;
-            // WARN: Temporary workaround for #27283.
-            //       We are not efficient with our memory usage per file, and use in excess of 64GB for a 10GB file
-            //       Therefore, as a temporary workaround to prevent system freezes, we just bail before opening a file
-            //       if it is too large
-            //       5GB seems to be more reasonable, peaking at ~16GB, while 6GB jumps up to >24GB which seems like a
-            //       reasonable limit
-            {
-                const FILE_SIZE_MAX: u64 = 6 * 1024 * 1024 * 1024; // 6GB
-                if let Ok(Some(metadata)) = fs.metadata(&abs_path).await {
-                    if metadata.len >= FILE_SIZE_MAX {
-                        anyhow::bail!("File is too large to load");
-                    }
+            let total_bytes = fs
+                .metadata(&abs_path)
+                .await
+                .ok()
+                .flatten()
+                .map_or(0, |metadata| metadata.len);
+            anyhow::ensure!(total_bytes < FILE_SIZE_MAX, "File is too large to load");
+            let is_large = large_files.is_large(total_bytes);
+            let (text, line_ending, encoding) = if is_large {
+                let (progress_tx, mut progress_rx) = mpsc::unbounded();
+                let load = cx.background_spawn({
+                    let fs = fs.clone();
+                    let abs_path = abs_path.clone();
+                    async move { load_large_file(fs.as_ref(), &abs_path, progress_tx).await }
+                });
+                let report_progress = |progress, cx: &mut AsyncApp| {
+                    this.update(cx, |_, cx| {
+                        cx.emit(Event::LargeFileLoadProgress {
+                            path: path.clone(),
+                            progress,
+                        })
+                    })
+                    .ok();
+                };
+                while let Some(loaded_bytes) = progress_rx.next().await {
+                    let progress = LoadProgress {
+                        loaded_bytes,
+                        total_bytes,
+                    };
+                    report_progress(Some(progress), cx);
                 }
-            }
-            let text = fs.load(&abs_path).await?;
+                let loaded = load.await;
+                report_progress(None, cx);
+                loaded?
+            } else {
+                let fs = fs.clone();
+                let abs_path = abs_path.clone();
+                cx.background_spawn(async move {
+                    let (mut text, encoding) = fs.load_with_encoding(&abs_path, None).await?;
+                    let line_ending = LineEnding::detect(&text);
+                    LineEnding::normalize(&mut text);
+                    anyhow::Ok((Rope::from(text), line_ending, encoding))
+                })
+                .await?
+            };
 
             let worktree = this.upgrade().context("worktree was dropped")?;
             let file = match entry.await? {
@@ -1562,7 +1617,13 @@ impl LocalWorktree {
                 }
             };
 
-            Ok(LoadedFile { file, text })
+            Ok(LoadedFile {
+                file,
+                text,
+                line_ending,
+                encoding,
+                is_large,
+            })
         })
     }
 
@@ -1649,6 +1710,7 @@ impl LocalWorktree {
         path: impl Into<Arc<Path>>,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &Context<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         let path = path.into();
@@ -1661,7 +1723,10 @@ impl LocalWorktree {
         let write = cx.background_spawn({
             let fs = fs.clone();
             let abs_path = abs_path.clone();
-            async move { fs.save(&abs_path, &text, line_ending).await }
+            async move {
+                fs.save_with_encoding(&abs_path, &text, line_ending, encoding)
+                    .await
+            }
         });
 
         cx.spawn(async move |this, cx| {
@@ -3346,6 +3411,59 @@ impl language::File for File {
     }
 }
 
+/// Streams a file into a rope in chunks, sending how many bytes have been read after each one.
+async fn load_large_file(
+    fs: &dyn Fs,
+    abs_path: &Path,
+    progress: UnboundedSender<u64>,
+) -> Result<(Rope, LineEnding, Encoding)> {
+    let mut reader = fs.open_sync(abs_path).await?;
+    let mut chunk = vec![0; LARGE_FILE_CHUNK_SIZE];
+    let mut decoder = None;
+    let mut encoding = Encoding::default();
+    let mut detected_line_ending = None;
+    let mut text = Rope::new();
+    let mut decoded = String::new();
+    let mut loaded_bytes = 0;
+    loop {
+        let mut len = 0;
+        while len < chunk.len() {
+            match reader.read(&mut chunk[len..]) {
+                Ok(0) => break,
+                Ok(read) => len += read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+        let last = len < chunk.len();
+        let bytes = &chunk[..len];
+
+        if decoder.is_none() {
+            encoding = Encoding::detect_from_prefix(bytes)?;
+        }
+        let decoder = decoder.get_or_insert_with(|| encoding.decoder());
+        decoder.decode(bytes, last, &mut decoded);
+        let line_ending = *detected_line_ending.get_or_insert_with(|| LineEnding::detect(&decoded));
+        // A carriage return at the end of the chunk can be followed by a line feed in the next one.
+        let split_line_ending = !last && decoded.ends_with('\r');
+        if split_line_ending {
+            decoded.pop();
+        }
+        LineEnding::normalize(&mut decoded);
+        text.push(&decoded);
+        decoded.clear();
+        if split_line_ending {
+            decoded.push('\r');
+        }
+
+        loaded_bytes += len as u64;
+        progress.unbounded_send(loaded_bytes).ok();
+        if last {
+            return Ok((text, line_ending, encoding));
+        }
+    }
+}
+
 impl language::LocalFile for File {
     fn abs_path(&self, cx: &App) -> PathBuf {
         let worktree_path = &self.worktree.read(cx).abs_path();
@@ -3356,11 +3474,11 @@ impl language::LocalFile for File {
         }
     }
 
-    fn load(&self, cx: &App) -> Task<Result<String>> {
+    fn load(&self, encoding: Option<Encoding>, cx: &App) -> Task<Result<(String, Encoding)>> {
         let worktree = self.worktree.read(cx).as_local().unwrap();
         let abs_path = worktree.absolutize(&self.path);
         let fs = worktree.fs.clone();
-        cx.background_spawn(async move { fs.load(&abs_path?).await })
+        cx.background_spawn(async move { fs.load_with_encoding(&abs_path?, encoding).await })
     }
 
     fn load_bytes(&self, cx: &App) -> Task<Result<Vec<u8>>> {
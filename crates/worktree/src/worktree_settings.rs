@@ -12,6 +12,22 @@ pub struct WorktreeSettings {
     pub file_scan_inclusions: PathMatcher,
     pub file_scan_exclusions: PathMatcher,
     pub private_files: PathMatcher,
+    pub large_files: LargeFileSettings,
+}
+
+/// How the files that are too large to be edited with all features are opened.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LargeFileSettings {
+    /// The size in bytes from which a file is opened in large file mode.
+    pub threshold: u64,
+    /// Whether the files opened in large file mode are read-only.
+    pub read_only: bool,
+}
+
+impl LargeFileSettings {
+    pub fn is_large(&self, size: u64) -> bool {
+        size >= self.threshold
+    }
 }
 
 impl WorktreeSettings {
@@ -62,6 +78,22 @@ pub struct WorktreeSettingsContent {
     /// Treat the files matching these globs as `.env` files.
     /// Default: [ "**/.env*" ]
     pub private_files: Option<Vec<String>>,
+
+    /// Open the files above a size in large file mode, without syntax highlighting,
+    /// language servers and undo history.
+    pub large_files: Option<LargeFileSettingsContent>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LargeFileSettingsContent {
+    /// The size in megabytes from which a file is opened in large file mode.
+    ///
+    /// Default: 64
+    pub threshold_mb: Option<u64>,
+    /// Whether the files opened in large file mode are read-only.
+    ///
+    /// Default: true
+    pub read_only: Option<bool>,
 }
 
 impl Settings for WorktreeSettings {
@@ -84,6 +116,7 @@ impl Settings for WorktreeSettings {
             })
             .filter(|p| p != "")
             .collect();
+        let large_files = result.large_files.unwrap_or_default();
         file_scan_exclusions.sort();
         private_files.sort();
         parsed_file_scan_inclusions.sort();
@@ -94,6 +127,13 @@ impl Settings for WorktreeSettings {
                 &parsed_file_scan_inclusions,
                 "file_scan_inclusions",
             )?,
+            large_files: LargeFileSettings {
+                threshold: large_files
+                    .threshold_mb
+                    .unwrap_or(64)
+                    .saturating_mul(1024 * 1024),
+                read_only: large_files.read_only.unwrap_or(true),
+            },
         })
     }
 
@@ -1,6 +1,7 @@
 use crate::{
-    Entry, EntryKind, Event, PathChange, WorkDirectory, Worktree, WorktreeModelHandle,
-    worktree_settings::WorktreeSettings,
+    Entry, EntryKind, Event, LoadProgress, PathChange, WorkDirectory, Worktree,
+    WorktreeModelHandle,
+    worktree_settings::{LargeFileSettingsContent, WorktreeSettings},
 };
 use anyhow::Result;
 use fs::{FakeFs, Fs, RealFs, RemoveOptions};
@@ -20,6 +21,7 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
+use text::LineEnding;
 use util::{ResultExt, path, test::TempTree};
 
 #[gpui::test]
@@ -714,6 +716,7 @@ async fn test_write_file(cx: &mut TestAppContext) {
                 Path::new("tracked-dir/file.txt"),
                 "hello".into(),
                 Default::default(),
+                Default::default(),
                 cx,
             )
         })
@@ -725,6 +728,7 @@ async fn test_write_file(cx: &mut TestAppContext) {
                 Path::new("ignored-dir/file.txt"),
                 "world".into(),
                 Default::default(),
+                Default::default(),
                 cx,
             )
         })
@@ -1746,8 +1750,13 @@ fn randomly_mutate_worktree(
                 })
             } else {
                 log::info!("overwriting file {:?} ({})", entry.path, entry.id.0);
-                let task =
-                    worktree.write_file(entry.path.clone(), "".into(), Default::default(), cx);
+                let task = worktree.write_file(
+                    entry.path.clone(),
+                    "".into(),
+                    Default::default(),
+                    Default::default(),
+                    cx,
+                );
                 cx.background_spawn(async move {
                     task.await?;
                     Ok(())
@@ -2090,6 +2099,83 @@ fn check_worktree_entries(
     }
 }
 
+#[gpui::test]
+async fn test_loading_large_files(cx: &mut TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<WorktreeSettings>(cx, |project_settings| {
+                project_settings.large_files = Some(LargeFileSettingsContent {
+                    threshold_mb: Some(1),
+                    read_only: None,
+                });
+            });
+        });
+    });
+
+    // Spans several chunks, so that some of the line endings are split between them.
+    let large_text = "a line of text\r\n".repeat(700_000);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "large.log": large_text,
+            "small.log": "a line of text\r\n",
+        }),
+    )
+    .await;
+
+    let tree = Worktree::local(
+        Path::new("/root"),
+        true,
+        fs.clone(),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let _subscription = cx.update(|cx| {
+        let progress = progress.clone();
+        cx.subscribe(&tree, move |_, event, _| {
+            if let Event::LargeFileLoadProgress {
+                progress: update, ..
+            } = event
+            {
+                progress.lock().push(*update);
+            }
+        })
+    });
+
+    let loaded = tree
+        .update(cx, |tree, cx| tree.load_file("large.log".as_ref(), cx))
+        .await
+        .unwrap();
+    assert!(loaded.is_large);
+    assert_eq!(loaded.line_ending, LineEnding::Windows);
+    assert_eq!(loaded.text.to_string(), "a line of text\n".repeat(700_000));
+    cx.run_until_parked();
+    let progress = progress.lock().clone();
+    assert_eq!(progress.last(), Some(&None));
+    assert_eq!(
+        progress[progress.len() - 2],
+        Some(LoadProgress {
+            loaded_bytes: large_text.len() as u64,
+            total_bytes: large_text.len() as u64,
+        })
+    );
+
+    let loaded = tree
+        .update(cx, |tree, cx| tree.load_file("small.log".as_ref(), cx))
+        .await
+        .unwrap();
+    assert!(!loaded.is_large);
+    assert_eq!(loaded.text.to_string(), "a line of text\n");
+}
+
 fn init_test(cx: &mut gpui::TestAppContext) {
     if std::env::var("RUST_LOG").is_ok() {
         env_logger::try_init().ok();
@@ -505,6 +505,7 @@ fn main() {
             app_state.fs.clone(),
             cx,
         );
+        language_model_extension::init(extension_host_proxy.clone());
         web_search::init(cx);
         web_search_providers::init(app_state.client.clone(), cx);
         snippet_provider::init(cx);
@@ -551,6 +552,7 @@ fn main() {
         project_symbols::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
+        hierarchy_panel::init(cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -559,6 +561,7 @@ fn main() {
         terminal_view::init(cx);
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
+        encoding_selector::init(cx);
         toolchain_selector::init(cx);
         theme_selector::init(cx);
         language_tools::init(cx);
@@ -31,6 +31,7 @@ use gpui::{
     Styled, Task, TitlebarOptions, UpdateGlobal, Window, WindowKind, WindowOptions, actions,
     image_cache, point, px, retain_all,
 };
+use hierarchy_panel::HierarchyPanel;
 use image_viewer::ImageInfo;
 use migrate::{MigrationBanner, MigrationEvent, MigrationNotification, MigrationType};
 use migrator::{migrate_keymap, migrate_settings};
@@ -232,6 +233,10 @@ pub fn initialize_workspace(
         );
         let active_buffer_language =
             cx.new(|_| language_selector::ActiveBufferLanguage::new(workspace));
+        let active_buffer_encoding =
+            cx.new(|_| encoding_selector::ActiveBufferEncoding::new(workspace));
+        let large_file_indicator =
+            cx.new(|_| editor::large_file_indicator::LargeFileIndicator::new());
         let active_toolchain_language =
             cx.new(|cx| toolchain_selector::ActiveToolchain::new(workspace, window, cx));
         let vim_mode_indicator = cx.new(|cx| vim::ModeIndicator::new(window, cx));
@@ -243,6 +248,8 @@ pub fn initialize_workspace(
             status_bar.add_left_item(diagnostic_summary, window, cx);
             status_bar.add_left_item(activity_indicator, window, cx);
             status_bar.add_right_item(inline_completion_button, window, cx);
+            status_bar.add_right_item(large_file_indicator, window, cx);
+            status_bar.add_right_item(active_buffer_encoding, window, cx);
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
             status_bar.add_right_item(vim_mode_indicator, window, cx);
@@ -379,6 +386,7 @@ fn initialize_panels(
     cx.spawn_in(window, async move |workspace_handle, cx| {
         let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
         let outline_panel = OutlinePanel::load(workspace_handle.clone(), cx.clone());
+        let hierarchy_panel = HierarchyPanel::load(workspace_handle.clone(), cx.clone());
         let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
         let channels_panel =
             collab_ui::collab_panel::CollabPanel::load(workspace_handle.clone(), cx.clone());
@@ -392,6 +400,7 @@ fn initialize_panels(
         let (
             project_panel,
             outline_panel,
+            hierarchy_panel,
             terminal_panel,
             channels_panel,
             chat_panel,
@@ -399,6 +408,7 @@ fn initialize_panels(
         ) = futures::try_join!(
             project_panel,
             outline_panel,
+            hierarchy_panel,
             terminal_panel,
             channels_panel,
             chat_panel,
@@ -408,6 +418,7 @@ fn initialize_panels(
         workspace_handle.update_in(cx, |workspace, window, cx| {
             workspace.add_panel(project_panel, window, cx);
             workspace.add_panel(outline_panel, window, cx);
+            workspace.add_panel(hierarchy_panel, window, cx);
             workspace.add_panel(terminal_panel, window, cx);
             workspace.add_panel(channels_panel, window, cx);
             workspace.add_panel(chat_panel, window, cx);
@@ -4277,6 +4288,7 @@ mod tests {
             git_ui::init(cx);
             project_panel::init(cx);
             outline_panel::init(cx);
+            hierarchy_panel::init(cx);
             terminal_view::init(cx);
             copilot::copilot_chat::init(app_state.fs.clone(), app_state.client.http_client(), cx);
             image_viewer::init(cx);
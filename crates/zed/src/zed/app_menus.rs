@@ -170,6 +170,7 @@ pub fn app_menus() -> Vec<Menu> {
                 MenuItem::separator(),
                 MenuItem::action("Project Panel", project_panel::ToggleFocus),
                 MenuItem::action("Outline Panel", outline_panel::ToggleFocus),
+                MenuItem::action("Hierarchy Panel", hierarchy_panel::ToggleFocus),
                 MenuItem::action("Collab Panel", collab_panel::ToggleFocus),
                 MenuItem::action("Terminal Panel", terminal_panel::ToggleFocus),
                 MenuItem::separator(),
@@ -145,7 +145,17 @@ pub mod git {
 pub mod jj {
     use gpui::actions;
 
-    actions!(jj, [BookmarkList]);
+    actions!(
+        jj,
+        [
+            BookmarkList,
+            ChangeStatus,
+            Describe,
+            NewChange,
+            OperationLog,
+            Squash,
+        ]
+    );
 }
 
 pub mod command_palette {
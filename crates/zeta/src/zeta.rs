@@ -1172,7 +1172,7 @@ impl LicenseDetectionWatcher {
                     };
 
                     let path = &loaded_file.file.path;
-                    if is_license_eligible_for_data_collection(&loaded_file.text) {
+                    if is_license_eligible_for_data_collection(&loaded_file.text.to_string()) {
                         log::info!("detected '{path:?}' as open source license");
                         *is_open_source_tx.borrow_mut() = true;
                     } else {